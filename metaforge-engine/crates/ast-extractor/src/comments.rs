@@ -0,0 +1,220 @@
+//! Comment attachment
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::SourceRange;
+
+/// Key under a block's attributes or abstract syntax holding its `AttachedComment`s
+pub const ATTACHED_COMMENTS_KEY: &str = "attached_comments";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentAttachment {
+    /// On the lines directly above the block, e.g. a doc or banner comment
+    Leading,
+    /// After the block's last token, on its last line
+    Trailing,
+    /// Inside the block's body
+    Inline,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttachedComment {
+    /// The comment including its delimiters, e.g. `// checked above`
+    pub text: String,
+    pub attachment: CommentAttachment,
+    /// Zero-based line the comment starts on
+    pub line: usize,
+}
+
+impl AttachedComment {
+    /// Read the comments stored under `ATTACHED_COMMENTS_KEY`; none when absent
+    pub fn from_value(value: Option<&serde_json::Value>) -> Vec<Self> {
+        value.and_then(|value| serde_json::from_value(value.clone()).ok()).unwrap_or_default()
+    }
+}
+
+/// A comment as it appears in the source
+#[derive(Debug, Clone)]
+pub struct SourceComment {
+    pub text: String,
+    pub range: SourceRange,
+    /// Nothing but whitespace precedes the comment on its first line
+    pub own_line: bool,
+}
+
+/// Every comment node under `root`, in source order
+pub fn collect_comments(root: Node, source: &str) -> Vec<SourceComment> {
+    let mut comments = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind().contains("comment") {
+            let Ok(text) = node.utf8_text(source.as_bytes()) else {
+                continue;
+            };
+            let line_start = source[..node.start_byte()].rfind('\n').map(|at| at + 1).unwrap_or(0);
+            comments.push(SourceComment {
+                text: text.trim_end().to_string(),
+                range: SourceRange {
+                    start_line: node.start_position().row,
+                    start_column: node.start_position().column,
+                    end_line: node.end_position().row,
+                    end_column: node.end_position().column,
+                    byte_start: node.start_byte(),
+                    byte_end: node.end_byte(),
+                },
+                own_line: source[line_start..node.start_byte()].trim().is_empty(),
+            });
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    comments
+}
+
+/// The comments attached to each of `blocks`, indexed like `blocks` and in
+/// source order. Comments that annotate no block are dropped.
+pub fn attach_comments(comments: &[SourceComment], blocks: &[SourceRange]) -> Vec<Vec<AttachedComment>> {
+    let mut attached = vec![Vec::new(); blocks.len()];
+    // Block each comment leads, filled from the bottom so a run of comment
+    // lines follows the comment below it to the block
+    let mut leading: Vec<Option<usize>> = vec![None; comments.len()];
+    for index in (0..comments.len()).rev() {
+        let comment = &comments[index];
+        if !comment.own_line {
+            continue;
+        }
+        let next_line = comment.range.end_line + 1;
+        leading[index] = widest(blocks, |block| block.start_line == next_line).or_else(|| {
+            comments.get(index + 1)
+                .filter(|next| next.own_line && next.range.start_line == next_line)
+                .and_then(|_| leading[index + 1])
+        });
+    }
+
+    for (index, comment) in comments.iter().enumerate() {
+        let line = comment.range.start_line;
+        // Some grammars (Python's) stretch the block over a comment that ends it
+        let trailing = (!comment.own_line).then(|| narrowest(blocks, |block| {
+            block.end_line == line
+                && (block.end_column <= comment.range.start_column || block.end_column == comment.range.end_column)
+        })).flatten();
+        let target = trailing.map(|block| (block, CommentAttachment::Trailing))
+            .or_else(|| leading[index].map(|block| (block, CommentAttachment::Leading)))
+            .or_else(|| narrowest(blocks, |block| contains(block, &comment.range)).map(|block| (block, CommentAttachment::Inline)));
+        if let Some((block, attachment)) = target {
+            attached[block].push(AttachedComment { text: comment.text.clone(), attachment, line });
+        }
+    }
+    attached
+}
+
+fn contains(block: &SourceRange, range: &SourceRange) -> bool {
+    (block.start_line, block.start_column) <= (range.start_line, range.start_column)
+        && (range.end_line, range.end_column) <= (block.end_line, block.end_column)
+}
+
+fn span(block: &SourceRange) -> (usize, usize) {
+    (block.end_line - block.start_line, block.end_column.saturating_sub(block.start_column))
+}
+
+fn narrowest(blocks: &[SourceRange], matches: impl Fn(&SourceRange) -> bool) -> Option<usize> {
+    blocks.iter().enumerate()
+        .filter(|(_, block)| matches(block))
+        .min_by_key(|(_, block)| span(block))
+        .map(|(index, _)| index)
+}
+
+fn widest(blocks: &[SourceRange], matches: impl Fn(&SourceRange) -> bool) -> Option<usize> {
+    blocks.iter().enumerate()
+        .filter(|(_, block)| matches(block))
+        .max_by_key(|(_, block)| span(block))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start_line: usize, start_column: usize, end_line: usize, end_column: usize) -> SourceRange {
+        SourceRange { start_line, start_column, end_line, end_column, byte_start: 0, byte_end: 0 }
+    }
+
+    fn comment(text: &str, line: usize, column: usize, own_line: bool) -> SourceComment {
+        SourceComment { text: text.to_string(), range: range(line, column, line, column + text.len()), own_line }
+    }
+
+    #[test]
+    fn test_comments_are_classified_by_position() {
+        // 0  // Adds two numbers
+        // 1  // without overflow checks
+        // 2  function add(a, b) {
+        // 3    return a + b; // wraps
+        // 4  } // end add
+        // 5
+        // 6  // stray
+        let blocks = vec![range(2, 0, 4, 1)];
+        let comments = vec![
+            comment("// Adds two numbers", 0, 0, true),
+            comment("// without overflow checks", 1, 0, true),
+            comment("// wraps", 3, 16, false),
+            comment("// end add", 4, 2, false),
+            comment("// stray", 6, 0, true),
+        ];
+        let attached = attach_comments(&comments, &blocks);
+        let kinds: Vec<(&str, CommentAttachment)> = attached[0].iter()
+            .map(|comment| (comment.text.as_str(), comment.attachment))
+            .collect();
+        assert_eq!(kinds, vec![
+            ("// Adds two numbers", CommentAttachment::Leading),
+            ("// without overflow checks", CommentAttachment::Leading),
+            ("// wraps", CommentAttachment::Inline),
+            ("// end add", CommentAttachment::Trailing),
+        ]);
+    }
+
+    #[test]
+    fn test_nested_blocks_take_their_own_comments() {
+        // 0  class A:
+        // 1      # builds A
+        // 2      def __init__(self):
+        // 3          pass  # nothing yet
+        let blocks = vec![range(0, 0, 3, 14), range(2, 4, 3, 14)];
+        let comments = vec![
+            comment("# builds A", 1, 4, true),
+            comment("# nothing yet", 3, 16, false),
+        ];
+        let attached = attach_comments(&comments, &blocks);
+        assert!(attached[0].is_empty());
+        assert_eq!(attached[1].iter().map(|comment| comment.attachment).collect::<Vec<_>>(), vec![
+            CommentAttachment::Leading,
+            CommentAttachment::Trailing,
+        ]);
+    }
+
+    #[test]
+    fn test_python_extractor_attaches_comments_when_asked() {
+        use crate::{ASTExtractor, ExtractionContext, PythonASTExtractor};
+        use uuid::Uuid;
+
+        let source = "# Sum the items\ndef total(items):\n    return sum(items)  # builtin\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let context = ExtractionContext::new("total.py".to_string(), "python".to_string(), Uuid::new_v4(), Uuid::new_v4());
+
+        let extractor = PythonASTExtractor::new();
+        let result = extractor.extract(tree.root_node(), source, &context.clone().with_comments(true)).unwrap();
+        let comments = AttachedComment::from_value(result.semantic_blocks[0].ast_node.attributes.get(ATTACHED_COMMENTS_KEY));
+        assert_eq!(comments, vec![
+            AttachedComment { text: "# Sum the items".to_string(), attachment: CommentAttachment::Leading, line: 0 },
+            AttachedComment { text: "# builtin".to_string(), attachment: CommentAttachment::Trailing, line: 2 },
+        ]);
+
+        let result = extractor.extract(tree.root_node(), source, &context).unwrap();
+        assert!(!result.semantic_blocks[0].ast_node.attributes.contains_key(ATTACHED_COMMENTS_KEY));
+    }
+}
@@ -8,6 +8,10 @@ use tree_sitter::{Node, TreeCursor};
 pub struct ExpressionAST {
     pub expression_type: String,
     pub operator: Option<String>,
+    /// Every operator of a comparison chain such as `a < b <= c`, in order;
+    /// empty for single-operator expressions
+    #[serde(default)]
+    pub chained_operators: Vec<String>,
     pub operands: Vec<Value>,
     pub literal_value: Option<Value>,
     pub function_calls: Vec<FunctionCall>,
@@ -15,16 +19,93 @@ pub struct ExpressionAST {
     pub variables: Vec<String>,
     pub complexity_score: u32,
     pub source_text: String,
+    #[serde(default)]
+    pub formatting: FormattingHints,
+}
+
+/// Layout choices the author made that don't change meaning, kept so
+/// generation can reproduce them instead of normalizing them away
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormattingHints {
+    /// The collection or argument list ends with a comma
+    pub trailing_comma: bool,
+    /// The expression was wrapped in parentheses, whether or not they were needed
+    pub parenthesized: bool,
+    /// Collection elements or call arguments were written one per line
+    pub multiline: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionCall {
     pub name: String,
+    /// Expression of each argument as written: a keyword argument is a
+    /// `keyword_argument` and a splat a `list_splat` or
+    /// `dictionary_splat` (`spread_element` in JavaScript)
     pub arguments: Vec<Value>,
+    /// How each of `arguments` binds; empty for calls extracted before
+    /// argument kinds were recorded, whose arguments count as positional
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub argument_kinds: Vec<ArgumentKind>,
     pub module_path: Option<String>,
     pub is_method: bool,
 }
 
+impl FunctionCall {
+    pub fn argument_kind(&self, index: usize) -> &ArgumentKind {
+        self.argument_kinds.get(index).unwrap_or(&ArgumentKind::Positional)
+    }
+
+    /// Number of arguments bound by position, not counting `*args`
+    pub fn positional_count(&self) -> usize {
+        (0..self.arguments.len())
+            .filter(|&index| *self.argument_kind(index) == ArgumentKind::Positional)
+            .count()
+    }
+
+    /// Parameter names passed by keyword, in call order
+    pub fn keyword_names(&self) -> impl Iterator<Item = &str> {
+        self.argument_kinds.iter().filter_map(|kind| match kind {
+            ArgumentKind::Keyword { name } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether a `*` or `**` splat (or JavaScript spread) makes the
+    /// bindings unknowable from the call alone
+    pub fn has_splat(&self) -> bool {
+        self.argument_kinds.iter()
+            .any(|kind| matches!(kind, ArgumentKind::Spread | ArgumentKind::KeywordSpread))
+    }
+}
+
+/// How a call argument binds to the callee's parameters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArgumentKind {
+    /// `f(a)`
+    Positional,
+    /// `f(name=a)`
+    Keyword { name: String },
+    /// `f(*args)`, or `f(...args)` in JavaScript
+    Spread,
+    /// `f(**kwargs)`
+    KeywordSpread,
+}
+
+impl ArgumentKind {
+    fn of(node: Node, source: &str) -> Result<Self> {
+        Ok(match node.kind() {
+            "keyword_argument" => match node.child_by_field_name("name") {
+                Some(name) => ArgumentKind::Keyword { name: name.utf8_text(source.as_bytes())?.to_string() },
+                None => ArgumentKind::Positional,
+            },
+            "list_splat" | "spread_element" => ArgumentKind::Spread,
+            "dictionary_splat" => ArgumentKind::KeywordSpread,
+            _ => ArgumentKind::Positional,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttributeAccess {
     pub object: String,
@@ -47,6 +128,7 @@ impl ExpressionExtractor {
         let mut ast = ExpressionAST {
             expression_type: node.kind().to_string(),
             operator: None,
+            chained_operators: Vec::new(),
             operands: Vec::new(),
             literal_value: None,
             function_calls: Vec::new(),
@@ -54,13 +136,32 @@ impl ExpressionExtractor {
             variables: Vec::new(),
             complexity_score: 1,
             source_text,
+            formatting: FormattingHints::default(),
         };
 
         match node.kind() {
-            "binary_operator" | "comparison_operator" => {
+            // A bare expression on its own line is the expression itself
+            "expression_statement" if node.named_child_count() == 1 => {
+                if let Some(inner) = node.named_child(0) {
+                    return self.extract_expression(inner, source);
+                }
+            }
+            "parenthesized_expression" => {
+                if let Some(inner) = node.named_child(0) {
+                    let mut inner_ast = self.extract_expression(inner, source)?;
+                    inner_ast.formatting.parenthesized = true;
+                    inner_ast.source_text = ast.source_text;
+                    return Ok(inner_ast);
+                }
+                self.extract_generic(node, source, &mut ast)?;
+            }
+            "binary_operator" | "comparison_operator" | "boolean_operator" => {
                 self.extract_binary_operation(node, source, &mut ast)?;
             }
-            "call" => {
+            "unary_operator" | "not_operator" | "list_splat" | "dictionary_splat" | "spread_element" => {
+                self.extract_unary_operation(node, source, &mut ast)?;
+            }
+            "call" | "call_expression" => {
                 self.extract_function_call(node, source, &mut ast)?;
             }
             "attribute" => {
@@ -75,6 +176,17 @@ impl ExpressionExtractor {
             "assignment" => {
                 self.extract_assignment(node, source, &mut ast)?;
             }
+            "list" | "tuple" | "set" | "dictionary" => {
+                self.extract_collection(node, source, &mut ast)?;
+            }
+            // Parts in source order: `value[subscript, ...]` and
+            // `body if condition else alternative`
+            "subscript" | "conditional_expression" => {
+                self.extract_collection(node, source, &mut ast)?;
+            }
+            "pair" | "keyword_argument" => {
+                self.extract_pair(node, source, &mut ast)?;
+            }
             _ => {
                 // Generic extraction for unknown node types
                 self.extract_generic(node, source, &mut ast)?;
@@ -88,13 +200,29 @@ impl ExpressionExtractor {
     fn extract_binary_operation(&self, node: Node, source: &str, ast: &mut ExpressionAST) -> Result<()> {
         let mut cursor = node.walk();
         
-        // Find operator and operands
+        // Operands are already nested by the grammar (`a + b * c` is
+        // `a + (b * c)`), so each operand keeps its own operator tree.
+        // Operators are the unnamed tokens; `not in` / `is not` arrive as two
+        // consecutive tokens and are joined back into one operator
+        let mut operators: Vec<String> = Vec::new();
+        let mut after_operator = false;
         for child in node.children(&mut cursor) {
             match child.kind() {
-                "==" | "!=" | "<" | ">" | "<=" | ">=" | "and" | "or" | "+" | "-" | "*" | "/" | "%" | "**" => {
-                    ast.operator = Some(child.utf8_text(source.as_bytes())?.to_string());
+                _ if !child.is_named() => {
+                    let text = child.utf8_text(source.as_bytes())?;
+                    match operators.last_mut() {
+                        Some(operator) if after_operator => {
+                            operator.push(' ');
+                            operator.push_str(text);
+                        }
+                        _ => operators.push(text.to_string()),
+                    }
+                    after_operator = true;
                 }
+                "comment" => {}
                 _ => {
+                    after_operator = false;
+                    
                     // Extract operands recursively
                     let operand_ast = self.extract_expression(child, source)?;
                     ast.operands.push(json!(operand_ast));
@@ -106,6 +234,30 @@ impl ExpressionExtractor {
                 }
             }
         }
+        
+        ast.operator = operators.first().cloned();
+        if operators.len() > 1 {
+            ast.chained_operators = operators;
+        }
+        Ok(())
+    }
+
+    /// `-x`, `+x`, `~x` and `not x`, and the `*args`, `**kwargs` and
+    /// `...args` splats of call arguments
+    fn extract_unary_operation(&self, node: Node, source: &str, ast: &mut ExpressionAST) -> Result<()> {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if !child.is_named() {
+                ast.operator = Some(child.utf8_text(source.as_bytes())?.to_string());
+            } else if child.kind() != "comment" {
+                let operand_ast = self.extract_expression(child, source)?;
+                ast.operands.push(json!(operand_ast));
+                
+                ast.variables.extend(operand_ast.variables);
+                ast.function_calls.extend(operand_ast.function_calls);
+                ast.attribute_access.extend(operand_ast.attribute_access);
+            }
+        }
         Ok(())
     }
 
@@ -113,6 +265,7 @@ impl ExpressionExtractor {
         let mut cursor = node.walk();
         let mut function_name = String::new();
         let mut arguments = Vec::new();
+        let mut argument_kinds = Vec::new();
         let mut module_path = None;
         let mut is_method = false;
 
@@ -131,13 +284,25 @@ impl ExpressionExtractor {
                     }
                     ast.attribute_access.extend(attr_ast.attribute_access);
                 }
-                "argument_list" => {
+                "member_expression" => {
+                    // JavaScript `object.method()`
+                    if let (Some(object), Some(property)) = (child.child_by_field_name("object"), child.child_by_field_name("property")) {
+                        module_path = Some(object.utf8_text(source.as_bytes())?.to_string());
+                        function_name = property.utf8_text(source.as_bytes())?.to_string();
+                        is_method = true;
+                    }
+                }
+                "argument_list" | "arguments" => {
+                    ast.formatting.trailing_comma = has_trailing_comma(child);
+                    ast.formatting.multiline = child.start_position().row != child.end_position().row;
+                    
                     // Extract all arguments
                     let mut arg_cursor = child.walk();
                     for arg_child in child.children(&mut arg_cursor) {
-                        if !matches!(arg_child.kind(), "," | "(" | ")") {
+                        if !matches!(arg_child.kind(), "," | "(" | ")" | "comment") {
                             let arg_ast = self.extract_expression(arg_child, source)?;
                             arguments.push(json!(arg_ast));
+                            argument_kinds.push(ArgumentKind::of(arg_child, source)?);
                             
                             // Collect nested data
                             ast.variables.extend(arg_ast.variables);
@@ -153,6 +318,7 @@ impl ExpressionExtractor {
         ast.function_calls.push(FunctionCall {
             name: function_name,
             arguments,
+            argument_kinds,
             module_path,
             is_method,
         });
@@ -241,6 +407,43 @@ impl ExpressionExtractor {
         Ok(())
     }
 
+    /// Lists, tuples, sets and dicts; each element becomes an operand
+    fn extract_collection(&self, node: Node, source: &str, ast: &mut ExpressionAST) -> Result<()> {
+        ast.formatting.trailing_comma = has_trailing_comma(node);
+        ast.formatting.multiline = node.start_position().row != node.end_position().row;
+        
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "comment" {
+                continue;
+            }
+            let element_ast = self.extract_expression(child, source)?;
+            ast.operands.push(json!(element_ast));
+            
+            ast.variables.extend(element_ast.variables);
+            ast.function_calls.extend(element_ast.function_calls);
+            ast.attribute_access.extend(element_ast.attribute_access);
+        }
+        Ok(())
+    }
+
+    /// `key: value` dict entries and `name=value` keyword arguments
+    fn extract_pair(&self, node: Node, source: &str, ast: &mut ExpressionAST) -> Result<()> {
+        ast.operator = Some(if node.kind() == "pair" { ":" } else { "=" }.to_string());
+        
+        for field in ["key", "name", "value"] {
+            if let Some(child) = node.child_by_field_name(field) {
+                let child_ast = self.extract_expression(child, source)?;
+                ast.operands.push(json!(child_ast));
+                
+                ast.variables.extend(child_ast.variables);
+                ast.function_calls.extend(child_ast.function_calls);
+                ast.attribute_access.extend(child_ast.attribute_access);
+            }
+        }
+        Ok(())
+    }
+
     fn extract_identifier(&self, node: Node, source: &str, ast: &mut ExpressionAST) -> Result<()> {
         let identifier = node.utf8_text(source.as_bytes())?.to_string();
         ast.variables.push(identifier);
@@ -280,6 +483,9 @@ impl ExpressionExtractor {
         for child in node.children(&mut cursor) {
             let child_ast = self.extract_expression(child, source)?;
             
+            // Merge operands
+            ast.operands.push(json!(child_ast));
+            
             // Merge child data
             ast.variables.extend(child_ast.variables);
             ast.function_calls.extend(child_ast.function_calls);
@@ -288,9 +494,6 @@ impl ExpressionExtractor {
             if child_ast.literal_value.is_some() {
                 ast.literal_value = child_ast.literal_value;
             }
-            
-            // Merge operands
-            ast.operands.push(json!(child_ast));
         }
 
         // If no children provided useful data, store as raw text
@@ -330,6 +533,12 @@ impl ExpressionExtractor {
     }
 }
 
+/// Whether the last token before a collection's closing bracket is a comma
+fn has_trailing_comma(node: Node) -> bool {
+    let count = node.child_count();
+    count >= 2 && node.child(count - 2).map_or(false, |child| child.kind() == ",")
+}
+
 impl Default for ExpressionExtractor {
     fn default() -> Self {
         Self::new()
@@ -398,6 +607,25 @@ mod tests {
         assert!(ast.function_calls[0].is_method);
     }
 
+    #[test]
+    fn test_javascript_spread_arguments() {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        
+        let code = "logger.warn(message, ...details);";
+        let tree = parser.parse(code, None).unwrap();
+        let call = tree.root_node().child(0).unwrap().named_child(0).unwrap();
+        
+        let extractor = ExpressionExtractor::new();
+        let ast = extractor.extract_expression(call, code).unwrap();
+        
+        let call = &ast.function_calls[0];
+        assert_eq!(call.name, "warn");
+        assert_eq!(call.module_path, Some("logger".to_string()));
+        assert_eq!(call.argument_kinds, vec![ArgumentKind::Positional, ArgumentKind::Spread]);
+        assert_eq!(call.positional_count(), 1);
+    }
+
     #[test]
     fn test_complex_expression() {
         let mut parser = Parser::new();
@@ -23,6 +23,7 @@ impl PythonASTExtractor {
         let mut cursor = node.walk();
         let mut function_name = String::new();
         let mut parameters = Vec::new();
+        let mut parameter_details = Vec::new();
         let mut decorators = Vec::new();
         let mut return_type = None;
         let mut docstring = None;
@@ -37,7 +38,10 @@ impl PythonASTExtractor {
                     }
                 }
                 "parameters" => {
-                    parameters = self.extract_parameters(child, source)?;
+                    parameter_details = self.extract_parameters(child, source)?;
+                    parameters = parameter_details.iter()
+                        .filter_map(|detail| detail["name"].as_str().map(str::to_string))
+                        .collect();
                 }
                 "type" => {
                     return_type = Some(child.utf8_text(source.as_bytes())?.to_string());
@@ -66,6 +70,7 @@ impl PythonASTExtractor {
 
         // Add function-specific attributes
         ast_node.attributes.insert("parameters".to_string(), serde_json::json!(parameters));
+        ast_node.attributes.insert("parameter_details".to_string(), serde_json::json!(parameter_details));
         ast_node.attributes.insert("decorators".to_string(), serde_json::json!(decorators));
         if let Some(ret_type) = return_type {
             ast_node.attributes.insert("return_type".to_string(), serde_json::json!(ret_type));
@@ -228,13 +233,53 @@ impl PythonASTExtractor {
     }
 
     // Helper methods
-    fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<String>> {
+    /// One entry per parameter with the fields of a mapper `Parameter`:
+    /// name, type hint, default as an `ExpressionAST`, and whether it is
+    /// variadic (`*args`; `**kwargs` is also keyword-only) or keyword-only
+    fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<serde_json::Value>> {
         let mut parameters = Vec::new();
+        let mut keyword_only = false;
         let mut cursor = node.walk();
 
-        for child in node.children(&mut cursor) {
-            if child.kind() == "identifier" {
-                parameters.push(child.utf8_text(source.as_bytes())?.to_string());
+        for child in node.named_children(&mut cursor) {
+            let (name_node, default_node) = match child.kind() {
+                "default_parameter" | "typed_default_parameter" => {
+                    (child.child_by_field_name("name"), child.child_by_field_name("value"))
+                }
+                "typed_parameter" => (child.named_child(0), None),
+                "identifier" | "list_splat_pattern" | "dictionary_splat_pattern" => (Some(child), None),
+                // A bare `*`: everything after it is keyword-only
+                "keyword_separator" => {
+                    keyword_only = true;
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some(name_node) = name_node else { continue };
+
+            let (name_node, is_variadic, is_keyword_splat) = match name_node.kind() {
+                "list_splat_pattern" => (name_node.named_child(0).unwrap_or(name_node), true, false),
+                "dictionary_splat_pattern" => (name_node.named_child(0).unwrap_or(name_node), true, true),
+                _ => (name_node, false, false),
+            };
+            let type_hint = match child.child_by_field_name("type") {
+                Some(type_node) => Some(type_node.utf8_text(source.as_bytes())?.to_string()),
+                None => None,
+            };
+            let default_value = match default_node {
+                Some(default_node) => Some(self.expression_extractor.extract_expression(default_node, source)?),
+                None => None,
+            };
+
+            parameters.push(serde_json::json!({
+                "name": name_node.utf8_text(source.as_bytes())?,
+                "type_hint": type_hint,
+                "default_value": default_value,
+                "is_variadic": is_variadic,
+                "is_keyword_only": keyword_only || is_keyword_splat,
+            }));
+            if is_variadic {
+                keyword_only = true;
             }
         }
 
@@ -358,7 +403,7 @@ impl ASTExtractor for PythonASTExtractor {
                 }
                 _ => {
                     // Handle other node types generically
-                    if context.max_depth.map_or(true, |max| cursor.depth() < max) {
+                    if context.max_depth.map_or(true, |max| node_depth(child) < max) {
                         // Recursively process unknown nodes
                         let nested_result = self.extract(child, source, context)?;
                         result.semantic_blocks.extend(nested_result.semantic_blocks);
@@ -369,6 +414,11 @@ impl ASTExtractor for PythonASTExtractor {
             }
         }
 
+        // Comments are attached once, from the module, after every block is known
+        if context.include_comments && node.parent().is_none() {
+            result.attach_comments(node, source);
+        }
+
         // Update metadata
         result.metadata.total_nodes = self.count_nodes(node);
         result.metadata.extraction_time_ms = start_time.elapsed().as_millis() as u64;
@@ -424,3 +474,8 @@ impl Default for PythonASTExtractor {
         Self::new()
     }
 }
+
+/// How many ancestors `node` has; tree-sitter 0.20 cursors don't track depth
+fn node_depth(node: Node) -> usize {
+    std::iter::successors(node.parent(), |parent| parent.parent()).count()
+}
@@ -0,0 +1,180 @@
+//! Source languages
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Python,
+    JavaScript,
+    TypeScript,
+    /// TypeScript with JSX, parsed with its own grammar
+    Tsx,
+    Rust,
+    Go,
+    Java,
+    Kotlin,
+    CSharp,
+    Cpp,
+    C,
+    Ruby,
+    Php,
+}
+
+impl Language {
+    pub const ALL: [Language; 13] = [
+        Language::Python,
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::Tsx,
+        Language::Rust,
+        Language::Go,
+        Language::Java,
+        Language::Kotlin,
+        Language::CSharp,
+        Language::Cpp,
+        Language::C,
+        Language::Ruby,
+        Language::Php,
+    ];
+
+    /// Canonical lowercase name, the key used by mappers, builders and templates
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Tsx => "tsx",
+            Language::Rust => "rust",
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::Kotlin => "kotlin",
+            Language::CSharp => "csharp",
+            Language::Cpp => "cpp",
+            Language::C => "c",
+            Language::Ruby => "ruby",
+            Language::Php => "php",
+        }
+    }
+
+    /// Extension of generated files, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Language::Python => "py",
+            Language::JavaScript => "js",
+            Language::TypeScript => "ts",
+            Language::Tsx => "tsx",
+            Language::Rust => "rs",
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::Kotlin => "kt",
+            Language::CSharp => "cs",
+            Language::Cpp => "cpp",
+            Language::C => "c",
+            Language::Ruby => "rb",
+            Language::Php => "php",
+        }
+    }
+
+    /// External formatter generated code is passed through, if there is one
+    pub fn default_formatter(self) -> Option<&'static str> {
+        match self {
+            Language::Python => Some("black"),
+            Language::JavaScript | Language::TypeScript | Language::Tsx => Some("prettier"),
+            Language::Rust => Some("rustfmt"),
+            Language::Go => Some("gofmt"),
+            Language::Cpp | Language::C => Some("clang-format"),
+            _ => None,
+        }
+    }
+
+    /// Canonical name of `name` when it is a known language or alias,
+    /// otherwise `name` unchanged, for lookups that also accept other languages
+    pub fn canonical_name(name: &str) -> &str {
+        name.parse::<Language>().map(Language::name).unwrap_or(name)
+    }
+
+    /// The language whose semantic mapper handles this one. JavaScript
+    /// blocks are TypeScript shapes without type annotations, so they share
+    /// its mapper.
+    pub fn mapper_language(self) -> Language {
+        match self {
+            Language::JavaScript => Language::TypeScript,
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A language name that is neither a canonical name nor a known alias
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLanguage(pub String);
+
+impl fmt::Display for UnknownLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unsupported language: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownLanguage {}
+
+impl FromStr for Language {
+    type Err = UnknownLanguage;
+
+    /// Canonical names, extensions and common spellings, case-insensitively
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let language = match name.trim().to_lowercase().as_str() {
+            "python" | "py" | "python3" => Language::Python,
+            "javascript" | "js" | "jsx" | "mjs" | "node" => Language::JavaScript,
+            "typescript" | "ts" | "mts" => Language::TypeScript,
+            "tsx" => Language::Tsx,
+            "rust" | "rs" => Language::Rust,
+            "go" | "golang" => Language::Go,
+            "java" => Language::Java,
+            "kotlin" | "kt" => Language::Kotlin,
+            "csharp" | "c#" | "cs" => Language::CSharp,
+            "cpp" | "c++" | "cxx" | "cc" => Language::Cpp,
+            "c" => Language::C,
+            "ruby" | "rb" => Language::Ruby,
+            "php" => Language::Php,
+            _ => return Err(UnknownLanguage(name.to_string())),
+        };
+        Ok(language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_resolve_to_canonical_names() {
+        assert_eq!("js".parse::<Language>(), Ok(Language::JavaScript));
+        assert_eq!("TypeScript".parse::<Language>(), Ok(Language::TypeScript));
+        assert_eq!("c#".parse::<Language>(), Ok(Language::CSharp));
+        assert_eq!("cobol".parse::<Language>(), Err(UnknownLanguage("cobol".to_string())));
+        assert_eq!(Language::canonical_name("ts"), "typescript");
+        assert_eq!(Language::canonical_name("cobol"), "cobol");
+
+        for language in Language::ALL {
+            assert_eq!(language.name().parse::<Language>(), Ok(language));
+            assert_eq!(language.to_string(), language.name());
+            assert_eq!(serde_json::to_value(language).unwrap(), language.name());
+        }
+    }
+
+    #[test]
+    fn test_javascript_uses_the_typescript_mapper() {
+        assert_eq!(Language::JavaScript.mapper_language(), Language::TypeScript);
+        assert_eq!(Language::Tsx.mapper_language(), Language::Tsx);
+        assert_eq!(Language::Rust.default_formatter(), Some("rustfmt"));
+        assert_eq!(Language::Ruby.default_formatter(), None);
+    }
+}
@@ -4,13 +4,17 @@
 //! This crate provides comprehensive AST extraction capabilities that go beyond
 //! simple parsing to capture semantic meaning and relationships.
 
+pub mod comments;
 pub mod expression;
 pub mod traits;
 pub mod extractors;
+pub mod language;
 
-pub use expression::{ExpressionAST, ExpressionExtractor, FunctionCall, AttributeAccess};
+pub use comments::{AttachedComment, CommentAttachment, ATTACHED_COMMENTS_KEY};
+pub use expression::{ExpressionAST, ExpressionExtractor, FormattingHints, FunctionCall, ArgumentKind, AttributeAccess};
 pub use traits::{ASTExtractor, ExtractionContext, ExtractionResult};
 pub use extractors::{PythonASTExtractor, RustASTExtractor, JavaScriptASTExtractor};
+pub use language::{Language, UnknownLanguage};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
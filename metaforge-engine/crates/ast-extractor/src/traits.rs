@@ -4,7 +4,8 @@ use std::collections::HashMap;
 use tree_sitter::Node;
 use uuid::Uuid;
 
-use crate::{ASTNode, ExpressionAST};
+use crate::comments::{attach_comments, collect_comments, ATTACHED_COMMENTS_KEY};
+use crate::{ASTNode, ExpressionAST, SourceRange};
 
 /// Core trait for AST extraction from different languages
 pub trait ASTExtractor {
@@ -30,6 +31,7 @@ pub struct ExtractionContext {
     pub migration_id: Uuid,
     pub extract_expressions: bool,
     pub max_depth: Option<usize>,
+    /// Attach comments to the blocks they annotate (see `comments`)
     pub include_comments: bool,
 }
 
@@ -197,6 +199,23 @@ impl ExtractionResult {
         self.metadata.semantic_blocks_count = self.semantic_blocks.len();
     }
 
+    /// Attach the comments under `root` to the extracted blocks, stored in
+    /// each block's AST node attributes under `ATTACHED_COMMENTS_KEY`
+    pub fn attach_comments(&mut self, root: Node, source: &str) {
+        let ranges: Vec<SourceRange> = self.semantic_blocks.iter()
+            .map(|block| block.ast_node.source_range.clone())
+            .collect();
+        let attached = attach_comments(&collect_comments(root, source), &ranges);
+        for (block, comments) in self.semantic_blocks.iter_mut().zip(attached) {
+            if !comments.is_empty() {
+                block.ast_node.attributes.insert(
+                    ATTACHED_COMMENTS_KEY.to_string(),
+                    serde_json::to_value(&comments).unwrap_or_default(),
+                );
+            }
+        }
+    }
+
     pub fn add_dependency(&mut self, dependency: Dependency) {
         self.dependencies.push(dependency);
         self.metadata.dependencies_count = self.dependencies.len();
@@ -0,0 +1,264 @@
+//! Batched formatting
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use ast_extractor::Language;
+use crate::{wait_with_deadline, BuildConfig, BuilderError, BuilderResult, IndentStyle};
+
+/// How long a batched invocation may run when the caller sets no deadline
+pub const DEFAULT_BATCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// An external formatter that accepts many files in one invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormatter {
+    Prettier,
+    Rustfmt,
+}
+
+/// What running the tool with `--version` told us, probed once per process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ToolProbe {
+    major_version: Option<u32>,
+    startup_time_ms: u64,
+}
+
+static PRETTIER_PROBE: OnceLock<Option<ToolProbe>> = OnceLock::new();
+static RUSTFMT_PROBE: OnceLock<Option<ToolProbe>> = OnceLock::new();
+
+/// Formatted files, in input order, plus timing for the batched invocation
+#[derive(Debug, Clone)]
+pub struct BatchFormatOutcome {
+    pub formatted: Vec<String>,
+    pub format_time_ms: u64,
+    /// Estimated time saved over one invocation per file
+    pub time_saved_ms: u64,
+}
+
+impl BatchFormatter {
+    /// The batch formatter for `language`'s default formatter, if it batches
+    pub fn for_language(language: &str) -> Option<Self> {
+        match language.parse::<Language>().ok()?.default_formatter()? {
+            "prettier" => Some(Self::Prettier),
+            "rustfmt" => Some(Self::Rustfmt),
+            _ => None,
+        }
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            Self::Prettier => "prettier",
+            Self::Rustfmt => "rustfmt",
+        }
+    }
+
+    /// The tool's version and startup time, `None` when it isn't installed
+    /// or doesn't answer within `timeout`. Only the first call runs the tool.
+    fn probe(&self, timeout: Duration) -> Option<ToolProbe> {
+        let cell = match self {
+            Self::Prettier => &PRETTIER_PROBE,
+            Self::Rustfmt => &RUSTFMT_PROBE,
+        };
+        *cell.get_or_init(|| {
+            let start = Instant::now();
+            let mut child = Command::new(self.program())
+                .arg("--version")
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let status = wait_with_deadline(&mut child, timeout).ok()??;
+            let startup_time_ms = start.elapsed().as_millis() as u64;
+            let mut version = String::new();
+            child.stdout.take()?.read_to_string(&mut version).ok()?;
+            status.success().then(|| ToolProbe {
+                major_version: major_version(&version),
+                startup_time_ms,
+            })
+        })
+    }
+
+    /// The invocation for `paths`, honoring `config`'s line length and
+    /// indentation. rustfmt's edition comes from the `rust_edition`
+    /// generation hint, defaulting to 2021. Prettier 3 renamed `--loglevel`
+    /// to `--log-level`, so the flag follows `major_version`.
+    fn command(&self, paths: &[PathBuf], config: &BuildConfig, major_version: Option<u32>) -> Command {
+        let mut command = Command::new(self.program());
+        match self {
+            Self::Prettier => {
+                let log_level = match major_version {
+                    Some(major) if major < 3 => "--loglevel",
+                    _ => "--log-level",
+                };
+                command.arg("--write").arg(log_level).arg("warn");
+                command.arg("--print-width").arg(config.max_line_length.to_string());
+                match config.indent_style {
+                    IndentStyle::Spaces(width) => command.arg("--tab-width").arg(width.to_string()),
+                    IndentStyle::Tabs => command.arg("--use-tabs"),
+                };
+            }
+            Self::Rustfmt => {
+                let edition = config.generation_hints.get("rust_edition")
+                    .and_then(|edition| edition.as_str())
+                    .unwrap_or("2021");
+                let indent = match config.indent_style {
+                    IndentStyle::Spaces(width) => format!("tab_spaces={}", width),
+                    IndentStyle::Tabs => "hard_tabs=true".to_string(),
+                };
+                command.arg("--edition").arg(edition);
+                command.arg("--config").arg(format!("max_width={},{}", config.max_line_length, indent));
+            }
+        }
+        command.args(paths);
+        command
+    }
+
+    /// Format `(path, code)` pairs with a single invocation of the tool.
+    ///
+    /// Paths are only used for their file names, which tools like prettier
+    /// use to pick a parser. The tool is killed if it is still running after
+    /// `timeout`.
+    pub fn format_files(&self, files: &[(String, String)], config: &BuildConfig, timeout: Duration) -> BuilderResult<BatchFormatOutcome> {
+        let probe = self.probe(timeout).ok_or_else(|| BuilderError::Formatter {
+            tool: self.program(),
+            message: "not installed".to_string(),
+        })?;
+        let scratch = std::env::temp_dir().join(format!("metaforge-format-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&scratch)?;
+        let outcome = self.format_in(&scratch, files, config, probe, timeout);
+        let _ = std::fs::remove_dir_all(&scratch);
+        outcome
+    }
+
+    fn format_in(
+        &self,
+        scratch: &Path,
+        files: &[(String, String)],
+        config: &BuildConfig,
+        probe: ToolProbe,
+        timeout: Duration,
+    ) -> BuilderResult<BatchFormatOutcome> {
+        // Prefix with the index so files with the same name don't collide
+        let paths = files.iter()
+            .enumerate()
+            .map(|(index, (path, code))| {
+                let file_name = Path::new(path).file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "generated".to_string());
+                let scratch_path = scratch.join(format!("{}_{}", index, file_name));
+                std::fs::write(&scratch_path, code)?;
+                Ok(scratch_path)
+            })
+            .collect::<BuilderResult<Vec<_>>>()?;
+
+        let start = Instant::now();
+        let mut child = self.command(&paths, config, probe.major_version)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| BuilderError::Formatter { tool: self.program(), message: e.to_string() })?;
+
+        // Drain stderr on its own thread so a chatty tool can't fill the pipe
+        // and stall before the deadline
+        let mut stderr = child.stderr.take();
+        let reader = thread::spawn(move || {
+            let mut output = String::new();
+            if let Some(stderr) = stderr.as_mut() {
+                let _ = stderr.read_to_string(&mut output);
+            }
+            output
+        });
+
+        let status = wait_with_deadline(&mut child, timeout)?.ok_or_else(|| BuilderError::Formatter {
+            tool: self.program(),
+            message: format!("did not finish within {:?}", timeout),
+        })?;
+        let format_time_ms = start.elapsed().as_millis() as u64;
+
+        if !status.success() {
+            return Err(BuilderError::Formatter {
+                tool: self.program(),
+                message: reader.join().unwrap_or_default().trim().to_string(),
+            });
+        }
+
+        let formatted = paths.iter()
+            .map(|path| Ok(std::fs::read_to_string(path)?))
+            .collect::<BuilderResult<Vec<_>>>()?;
+
+        // Each file formatted separately would pay the tool's startup cost
+        // again; estimate that cost with the time `--version` took
+        let time_saved_ms = probe.startup_time_ms * (files.len().max(1) as u64 - 1);
+
+        Ok(BatchFormatOutcome {
+            formatted,
+            format_time_ms,
+            time_saved_ms,
+        })
+    }
+}
+
+/// Major version in `--version` output such as `3.1.0` or
+/// `rustfmt 1.7.0-stable (...)`
+fn major_version(output: &str) -> Option<u32> {
+    output.split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(formatter: BatchFormatter, config: &BuildConfig) -> Vec<String> {
+        formatter.command(&[], config, Some(3)).get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_command_honors_build_config() {
+        let mut config = BuildConfig {
+            indent_style: IndentStyle::Spaces(4),
+            max_line_length: 100,
+            ..BuildConfig::default()
+        };
+        config.generation_hints.insert("rust_edition".to_string(), serde_json::json!("2018"));
+
+        assert_eq!(
+            args(BatchFormatter::Rustfmt, &config),
+            vec!["--edition", "2018", "--config", "max_width=100,tab_spaces=4"]
+        );
+        assert_eq!(
+            args(BatchFormatter::Prettier, &config),
+            vec!["--write", "--log-level", "warn", "--print-width", "100", "--tab-width", "4"]
+        );
+
+        config.indent_style = IndentStyle::Tabs;
+        assert!(args(BatchFormatter::Prettier, &config).contains(&"--use-tabs".to_string()));
+        assert!(args(BatchFormatter::Rustfmt, &config).contains(&"max_width=100,hard_tabs=true".to_string()));
+    }
+
+    #[test]
+    fn test_prettier_log_level_flag_follows_version() {
+        let flags = |major_version| {
+            BatchFormatter::Prettier.command(&[], &BuildConfig::default(), major_version).get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .filter(|arg| arg.contains("log"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(flags(Some(2)), vec!["--loglevel"]);
+        assert_eq!(flags(Some(3)), vec!["--log-level"]);
+        assert_eq!(major_version("2.8.8\n"), Some(2));
+        assert_eq!(major_version("rustfmt 1.7.0-stable (129f3b99 2024-06-10)"), Some(1));
+        assert_eq!(major_version("unknown"), None);
+    }
+}
@@ -1,7 +0,0 @@
-pub mod python;
-pub mod rust;
-pub mod javascript;
-
-pub use python::PythonBuilder;
-pub use rust::RustBuilder;
-pub use javascript::JavaScriptBuilder;
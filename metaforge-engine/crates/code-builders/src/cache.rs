@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use crate::{BuildConfig, BuilderResult};
+
+/// Rendered code for a block, keyed by `(block hash, language, config hash)`.
+///
+/// Blocks whose semantic hash is unchanged since the last build skip
+/// rendering entirely. The build configuration's hash is part of the key, so
+/// changing any `BuildConfig` setting invalidates every entry built with the
+/// old settings. The cache can be persisted between runs with `save`/`load`.
+///
+/// A block whose source changes gets a new hash, so its old entry is never
+/// looked up again; `evict_unused` drops such entries after a build.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GenerationCache {
+    entries: HashMap<String, String>,
+    #[serde(skip)]
+    stats: CacheStats,
+    /// Keys looked up or inserted since this cache was created or loaded
+    #[serde(skip)]
+    used: HashSet<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl GenerationCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a cache written by `save`; a missing file gives an empty cache
+    pub fn load(path: &Path) -> BuilderResult<Self> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> BuilderResult<()> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Cached code for the block, or the result of `render`, which is then cached
+    pub fn get_or_render(
+        &mut self,
+        block_hash: &str,
+        config: &BuildConfig,
+        render: impl FnOnce() -> BuilderResult<String>,
+    ) -> BuilderResult<String> {
+        let config_hash = config.config_hash();
+        if let Some(code) = self.get(block_hash, &config.language, &config_hash) {
+            return Ok(code);
+        }
+        let code = render()?;
+        self.insert(block_hash, &config.language, &config_hash, code.clone());
+        Ok(code)
+    }
+
+    /// Cached code for the block, counting the lookup as a hit or a miss.
+    ///
+    /// For callers that render outside of `get_or_render`, such as a batch
+    /// of files formatted together; `config_hash` identifies whatever
+    /// settings the code was produced with.
+    pub fn get(&mut self, block_hash: &str, language: &str, config_hash: &str) -> Option<String> {
+        let key = cache_key(block_hash, language, config_hash);
+        let code = self.entries.get(&key).cloned();
+        match code {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        self.used.insert(key);
+        code
+    }
+
+    pub fn insert(&mut self, block_hash: &str, language: &str, config_hash: &str, code: String) {
+        let key = cache_key(block_hash, language, config_hash);
+        self.used.insert(key.clone());
+        self.entries.insert(key, code);
+    }
+
+    /// Drop entries that were neither looked up nor inserted since this
+    /// cache was created or loaded: their blocks changed, were removed or
+    /// were built with other settings. Returns how many were dropped.
+    pub fn evict_unused(&mut self) -> usize {
+        let before = self.entries.len();
+        let used = &self.used;
+        self.entries.retain(|key, _| used.contains(key));
+        before - self.entries.len()
+    }
+
+    /// Hits and misses since this cache was created or loaded
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used.clear();
+    }
+}
+
+impl BuildConfig {
+    /// Stable hash of every setting that can affect generated code
+    pub fn config_hash(&self) -> String {
+        // serde_json writes struct fields in declaration order; sort the
+        // hint map so equal configs always serialize identically
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(hints) = value.get_mut("generation_hints").and_then(|h| h.as_object_mut()) {
+            let sorted: std::collections::BTreeMap<_, _> = std::mem::take(hints).into_iter().collect();
+            *hints = sorted.into_iter().collect();
+        }
+        blake3::hash(value.to_string().as_bytes()).to_hex().to_string()
+    }
+}
+
+fn cache_key(block_hash: &str, language: &str, config_hash: &str) -> String {
+    format!("{}:{}:{}", language, config_hash, block_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evict_unused_drops_entries_of_changed_blocks() {
+        let mut cache = GenerationCache::new();
+        cache.insert("old", "rust", "config", "fn old() {}".to_string());
+        cache.insert("kept", "rust", "config", "fn kept() {}".to_string());
+
+        // The next build sees "kept" unchanged and "old" replaced by "new"
+        let mut cache: GenerationCache = serde_json::from_str(&serde_json::to_string(&cache).unwrap()).unwrap();
+        assert_eq!(cache.get("kept", "rust", "config").as_deref(), Some("fn kept() {}"));
+        assert_eq!(cache.get("new", "rust", "config"), None);
+        cache.insert("new", "rust", "config", "fn new() {}".to_string());
+
+        assert_eq!(cache.evict_unused(), 1);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+        assert_eq!(cache.get("old", "rust", "config"), None);
+    }
+}
@@ -0,0 +1,82 @@
+//! Comments in generated code
+
+use ast_extractor::Language;
+
+/// Opening and closing of a one-line comment that is valid in `language`,
+/// which may be an alias such as `py`. The closing is empty for languages
+/// with line comments; css and html only have block comments.
+pub fn comment_delimiters(language: &str) -> (&'static str, &'static str) {
+    match Language::canonical_name(language) {
+        "python" | "ruby" | "shell" | "bash" | "yaml" | "toml" => ("#", ""),
+        "sql" | "lua" | "haskell" => ("--", ""),
+        "css" | "scss" | "less" => ("/*", "*/"),
+        "html" | "xml" => ("<!--", "-->"),
+        _ => ("//", ""),
+    }
+}
+
+/// `text` as comments in `language`, one per line of `text`
+pub fn comment(language: &str, text: &str) -> String {
+    let (open, close) = comment_delimiters(language);
+    let wrap = |line: &str| match (line.is_empty(), close.is_empty()) {
+        (true, true) => open.to_string(),
+        (true, false) => format!("{} {}", open, close),
+        (false, true) => format!("{} {}", open, line),
+        (false, false) => format!("{} {} {}", open, line, close),
+    };
+    let lines: Vec<String> = text.lines().map(wrap).collect();
+    if lines.is_empty() {
+        wrap("")
+    } else {
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    #[test]
+    fn test_comments_use_the_language_syntax() {
+        assert_eq!(comment("python", "TODO: Define struct fields"), "# TODO: Define struct fields");
+        assert_eq!(comment("rb", "Unknown block type: Macro"), "# Unknown block type: Macro");
+        assert_eq!(comment("ts", "note"), "// note");
+        assert_eq!(comment("lua", "first\n\nsecond"), "-- first\n--\n-- second");
+        assert_eq!(comment("python", ""), "#");
+        assert_eq!(comment("css", "Rule: .button"), "/* Rule: .button */");
+        assert_eq!(comment("html", "first\n\nsecond"), "<!-- first -->\n<!-- -->\n<!-- second -->");
+    }
+
+    #[test]
+    fn test_python_comments_parse() {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let source = format!("def load():\n    {}\n    pass\n", comment("py", "Implementation generated from semantic blocks"));
+        let tree = parser.parse(&source, None).unwrap();
+        assert!(!tree.root_node().has_error(), "{}", source);
+    }
+
+    /// Ruby shares Python's `#` comments, so the Python grammar checks both
+    #[test]
+    fn test_ruby_comments_parse() {
+        assert_eq!(comment("rb", "first\n\nsecond"), comment("python", "first\n\nsecond"));
+
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let source = format!(
+            "class Loader:\n    {}\n    def load(self):\n        {}\n        pass\n",
+            comment("rb", "Unknown block type: Macro"),
+            comment("ruby", "first\n\nsecond").replace('\n', "\n        "),
+        );
+        let tree = parser.parse(&source, None).unwrap();
+        assert!(!tree.root_node().has_error(), "{}", source);
+        assert_eq!(count_kind(tree.root_node(), "comment"), 4, "{}", source);
+    }
+
+    fn count_kind(node: tree_sitter::Node, kind: &str) -> usize {
+        let mut cursor = node.walk();
+        let nested: usize = node.children(&mut cursor).map(|child| count_kind(child, kind)).sum();
+        nested + usize::from(node.kind() == kind)
+    }
+}
@@ -0,0 +1,113 @@
+//! Semantic coverage
+
+use uuid::Uuid;
+use crate::{BuildConfig, BuildResult};
+
+/// Comment text marking a placeholder emission, unless `BuildConfig` overrides it
+pub const DEFAULT_PLACEHOLDER_MARKERS: [&str; 2] = [
+    "Implementation generated from semantic blocks",
+    "TODO:",
+];
+
+pub fn default_placeholder_markers() -> Vec<String> {
+    DEFAULT_PLACEHOLDER_MARKERS.iter().map(|marker| marker.to_string()).collect()
+}
+
+/// Whether `code` has a comment line containing one of `markers`. Only
+/// comments count, so a string literal mentioning a marker is real code.
+pub fn is_placeholder(code: &str, markers: &[String]) -> bool {
+    code.lines().any(|line| {
+        let line = line.trim_start();
+        let comment = line.strip_prefix("//")
+            .or_else(|| line.strip_prefix("/*"))
+            .or_else(|| line.strip_prefix('#'))
+            .or_else(|| line.strip_prefix("--"));
+        comment.is_some_and(|comment| markers.iter().any(|marker| comment.contains(marker.as_str())))
+    })
+}
+
+impl BuildResult {
+    /// Score the rendered code of each block against `config.placeholder_markers`
+    /// and record the coverage in the metadata. In strict mode any placeholder
+    /// block is a build error; otherwise it is a warning.
+    pub fn record_coverage(&mut self, rendered_blocks: &[(Uuid, String)], config: &BuildConfig) {
+        let placeholders: Vec<Uuid> = rendered_blocks.iter()
+            .filter(|(_, code)| is_placeholder(code, &config.placeholder_markers))
+            .map(|(block_id, _)| *block_id)
+            .collect();
+
+        self.metadata.semantic_coverage = if rendered_blocks.is_empty() {
+            1.0
+        } else {
+            1.0 - placeholders.len() as f64 / rendered_blocks.len() as f64
+        };
+
+        if !placeholders.is_empty() {
+            let ids: Vec<String> = placeholders.iter().map(Uuid::to_string).collect();
+            let message = format!(
+                "{} of {} blocks generated placeholder code: {}",
+                placeholders.len(),
+                rendered_blocks.len(),
+                ids.join(", ")
+            );
+            if config.strict_mode {
+                self.add_error(message);
+            } else {
+                self.add_warning(message);
+            }
+        }
+        self.metadata.placeholder_blocks = placeholders;
+    }
+
+    /// Blocks whose generated code is a placeholder rather than real code
+    pub fn placeholder_blocks(&self) -> Vec<Uuid> {
+        self.metadata.placeholder_blocks.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_marker_comments_are_placeholders() {
+        let markers = default_placeholder_markers();
+        assert!(is_placeholder("fn load() {\n    // Implementation generated from semantic blocks\n}", &markers));
+        assert!(is_placeholder("class Store:\n    # TODO: Define struct fields\n    pass", &markers));
+        assert!(!is_placeholder("def note():\n    return \"TODO: write docs\"", &markers));
+        assert!(!is_placeholder("def add(a, b):\n    return a + b", &markers));
+        assert!(is_placeholder(&crate::comment("lua", "TODO: Define module content"), &markers));
+
+        // Projects whose real code keeps TODO comments narrow the markers
+        let narrow = vec!["Implementation generated from semantic blocks".to_string()];
+        assert!(!is_placeholder("    # TODO: Define struct fields", &narrow));
+    }
+
+    #[test]
+    fn test_coverage_counts_placeholder_blocks_and_fails_strict_mode() {
+        let real = Uuid::new_v4();
+        let stub = Uuid::new_v4();
+        let blocks = vec![
+            (real, "def add(a, b):\n    return a + b".to_string()),
+            (stub, "def load():\n    # Implementation generated from semantic blocks\n    pass".to_string()),
+        ];
+
+        let mut strict = BuildResult::new(String::new());
+        strict.record_coverage(&blocks, &BuildConfig::default());
+        assert_eq!(strict.placeholder_blocks(), vec![stub]);
+        assert_eq!(strict.metadata.semantic_coverage, 0.5);
+        assert!(strict.has_errors());
+        assert!(strict.errors[0].contains(&stub.to_string()));
+
+        let lenient_config = BuildConfig { strict_mode: false, ..BuildConfig::default() };
+        let mut lenient = BuildResult::new(String::new());
+        lenient.record_coverage(&blocks, &lenient_config);
+        assert!(!lenient.has_errors());
+        assert_eq!(lenient.warnings.len(), 1);
+
+        let mut empty = BuildResult::new(String::new());
+        empty.record_coverage(&[], &BuildConfig::default());
+        assert_eq!(empty.metadata.semantic_coverage, 1.0);
+        assert!(empty.placeholder_blocks().is_empty());
+    }
+}
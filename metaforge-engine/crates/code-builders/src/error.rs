@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+/// Errors returned by code builders, formatters and the builder registry
+#[derive(Debug, Error)]
+pub enum BuilderError {
+    /// No builder is registered for the language
+    #[error("No code builder registered for language: {0}")]
+    UnsupportedLanguage(String),
+
+    /// Strict mode found a block without the AST data generation needs
+    #[error("Block {block_id} is missing AST field: {field}")]
+    IncompleteAst { block_id: String, field: String },
+
+    /// A build finished but reported errors
+    #[error("Failed to build block {block_id}: {}", errors.join("; "))]
+    BuildFailed { block_id: String, errors: Vec<String> },
+
+    /// Rendered output grew past `BuildConfig::max_output_bytes`
+    #[error("Generated output reached {bytes} bytes, over the {limit}-byte limit (max_output_bytes)")]
+    OutputTooLarge { bytes: usize, limit: usize },
+
+    /// An external formatter could not be run or rejected its input
+    #[error("{tool} failed: {message}")]
+    Formatter { tool: &'static str, message: String },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Serialization(#[from] serde_json::Error),
+
+    /// Any other failure inside a builder
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+pub type BuilderResult<T> = std::result::Result<T, BuilderError>;
@@ -0,0 +1,524 @@
+//! Python expression rendering
+
+use anyhow::{anyhow, Result};
+use ast_extractor::{ExpressionAST, FunctionCall};
+use semantic_mapper::Parameter;
+use serde_json::Value;
+
+/// Renders `ExpressionAST`s as Python source
+pub struct ExpressionRenderer {
+    indent_unit: String,
+}
+
+impl ExpressionRenderer {
+    pub fn new(indent_unit: impl Into<String>) -> Self {
+        Self {
+            indent_unit: indent_unit.into(),
+        }
+    }
+
+    /// Render `ast`; multi-line collections are indented relative to column zero
+    pub fn render(&self, ast: &ExpressionAST) -> Result<String> {
+        self.render_at(ast, 0)
+    }
+
+    /// Render a parameter list without its parentheses. Defaults are
+    /// rebuilt from their `ExpressionAST`, so mutable (`[]`) and call
+    /// (`compute()`) defaults come back as written; expression kinds the
+    /// renderer doesn't know fall back to their source text. A bare `*` is
+    /// restored before the first keyword-only parameter unless `*args`
+    /// already marks the boundary.
+    pub fn render_parameters(&self, parameters: &[Parameter]) -> String {
+        self.render_parameter_list(parameters, |default| {
+            self.render(default).unwrap_or_else(|_| default.source_text.clone())
+        })
+    }
+
+    /// Render a parameter list the way `.pyi` stubs write it, every
+    /// default replaced by `...`
+    pub fn render_stub_parameters(&self, parameters: &[Parameter]) -> String {
+        self.render_parameter_list(parameters, |_| "...".to_string())
+    }
+
+    fn render_parameter_list(&self, parameters: &[Parameter], render_default: impl Fn(&ExpressionAST) -> String) -> String {
+        let mut rendered = Vec::new();
+        let mut keyword_only = false;
+        for parameter in parameters {
+            let is_keyword_splat = parameter.is_variadic && parameter.is_keyword_only;
+            if parameter.is_keyword_only && !keyword_only && !is_keyword_splat {
+                rendered.push("*".to_string());
+            }
+            keyword_only |= parameter.is_variadic || parameter.is_keyword_only;
+
+            let prefix = match (parameter.is_variadic, is_keyword_splat) {
+                (true, true) => "**",
+                (true, false) => "*",
+                _ => "",
+            };
+            let default = parameter.default_value.as_ref().map(&render_default);
+            rendered.push(match (&parameter.type_hint, default) {
+                (Some(type_hint), Some(default)) => format!("{}{}: {} = {}", prefix, parameter.name, type_hint, default),
+                (Some(type_hint), None) => format!("{}{}: {}", prefix, parameter.name, type_hint),
+                (None, Some(default)) => format!("{}{}={}", prefix, parameter.name, default),
+                (None, None) => format!("{}{}", prefix, parameter.name),
+            });
+        }
+        rendered.join(", ")
+    }
+
+    fn render_at(&self, ast: &ExpressionAST, depth: usize) -> Result<String> {
+        let rendered = match ast.expression_type.as_str() {
+            "identifier" => ast.variables.first()
+                .cloned()
+                .ok_or_else(|| anyhow!("Identifier expression has no name"))?,
+            // Literal tokens are atoms: their text is their structure. Slices
+            // are kept as written
+            "string" | "integer" | "float" | "true" | "false" | "none" | "slice" => {
+                unparenthesized_text(ast).to_string()
+            }
+            "binary_operator" | "comparison_operator" | "boolean_operator" => {
+                self.render_binary(ast, depth)?
+            }
+            "unary_operator" | "not_operator" => {
+                let operator = ast.operator.as_deref()
+                    .ok_or_else(|| anyhow!("{} has no operator", ast.expression_type))?;
+                let operand = ast.operands.first()
+                    .ok_or_else(|| anyhow!("{} has no operand", ast.expression_type))?;
+                let operand: ExpressionAST = serde_json::from_value(operand.clone())?;
+                let rendered = self.render_operand(&operand, ast, Side::Only, depth)?;
+                if operator == "not" {
+                    format!("not {}", rendered)
+                } else {
+                    format!("{}{}", operator, rendered)
+                }
+            }
+            // `*args` and `**kwargs` call arguments
+            "list_splat" | "dictionary_splat" => {
+                let operator = ast.operator.as_deref()
+                    .ok_or_else(|| anyhow!("{} has no operator", ast.expression_type))?;
+                let operand = ast.operands.first()
+                    .ok_or_else(|| anyhow!("{} has no operand", ast.expression_type))?;
+                let operand: ExpressionAST = serde_json::from_value(operand.clone())?;
+                format!("{}{}", operator, self.render_at(&operand, depth)?)
+            }
+            "pair" => {
+                let operands = self.render_operands(&ast.operands, depth)?;
+                operands.join(": ")
+            }
+            "keyword_argument" => {
+                let operands = self.render_operands(&ast.operands, depth)?;
+                operands.join("=")
+            }
+            "attribute" => {
+                let access = ast.attribute_access.first()
+                    .ok_or_else(|| anyhow!("Attribute expression has no access"))?;
+                format!("{}.{}", access.object, access.attribute)
+            }
+            "call" => {
+                let call = ast.function_calls.last()
+                    .ok_or_else(|| anyhow!("Call expression has no function call"))?;
+                self.render_call(call, ast, depth)?
+            }
+            "subscript" => {
+                let (value, subscripts) = ast.operands.split_first()
+                    .ok_or_else(|| anyhow!("Subscript has no value"))?;
+                let value: ExpressionAST = serde_json::from_value(value.clone())?;
+                let value = self.render_operand(&value, ast, Side::Left, depth)?;
+                let subscripts = self.render_operands(subscripts, depth + 1)?;
+                format!("{}{}", value, self.render_elements(&subscripts, ast, "[", "]", depth))
+            }
+            "conditional_expression" => {
+                if ast.operands.len() != 3 {
+                    return Err(anyhow!("Conditional expression has {} operands", ast.operands.len()));
+                }
+                let mut parts = Vec::new();
+                for (operand, side) in ast.operands.iter().zip([Side::Left, Side::Middle, Side::Right]) {
+                    let operand: ExpressionAST = serde_json::from_value(operand.clone())?;
+                    parts.push(self.render_operand(&operand, ast, side, depth)?);
+                }
+                format!("{} if {} else {}", parts[0], parts[1], parts[2])
+            }
+            "list" => self.render_collection(ast, "[", "]", depth)?,
+            "set" | "dictionary" => self.render_collection(ast, "{", "}", depth)?,
+            "tuple" => self.render_collection(ast, "(", ")", depth)?,
+            other => return Err(anyhow!("Cannot render expression type: {}", other)),
+        };
+
+        // Tuples bring their own parentheses
+        if ast.formatting.parenthesized && ast.expression_type != "tuple" {
+            Ok(format!("({})", rendered))
+        } else {
+            Ok(rendered)
+        }
+    }
+
+    fn render_binary(&self, ast: &ExpressionAST, depth: usize) -> Result<String> {
+        let operators: Vec<&str> = if ast.chained_operators.is_empty() {
+            let operator = ast.operator.as_deref()
+                .ok_or_else(|| anyhow!("{} has no operator", ast.expression_type))?;
+            vec![operator]
+        } else {
+            ast.chained_operators.iter().map(String::as_str).collect()
+        };
+        if ast.operands.len() != operators.len() + 1 {
+            return Err(anyhow!(
+                "{} has {} operands for {} operators",
+                ast.expression_type, ast.operands.len(), operators.len()
+            ));
+        }
+
+        let last = ast.operands.len() - 1;
+        let mut rendered = String::new();
+        for (index, operand) in ast.operands.iter().enumerate() {
+            let operand: ExpressionAST = serde_json::from_value(operand.clone())?;
+            let side = if index == 0 { Side::Left } else if index == last { Side::Right } else { Side::Middle };
+            if index > 0 {
+                rendered.push_str(&format!(" {} ", operators[index - 1]));
+            }
+            rendered.push_str(&self.render_operand(&operand, ast, side, depth)?);
+        }
+        Ok(rendered)
+    }
+
+    /// Render an operand of `parent`, adding parentheses if precedence requires them
+    fn render_operand(&self, operand: &ExpressionAST, parent: &ExpressionAST, side: Side, depth: usize) -> Result<String> {
+        let rendered = self.render_at(operand, depth)?;
+        if !operand.formatting.parenthesized && needs_parentheses(operand, parent, side) {
+            Ok(format!("({})", rendered))
+        } else {
+            Ok(rendered)
+        }
+    }
+
+    fn render_operands(&self, operands: &[Value], depth: usize) -> Result<Vec<String>> {
+        operands.iter()
+            .map(|operand| {
+                let operand: ExpressionAST = serde_json::from_value(operand.clone())?;
+                self.render_at(&operand, depth)
+            })
+            .collect()
+    }
+
+    fn render_call(&self, call: &FunctionCall, ast: &ExpressionAST, depth: usize) -> Result<String> {
+        let callee = match &call.module_path {
+            Some(module) => format!("{}.{}", module, call.name),
+            None => call.name.clone(),
+        };
+        let arguments = self.render_operands(&call.arguments, depth + 1)?;
+        Ok(format!("{}{}", callee, self.render_elements(&arguments, ast, "(", ")", depth)))
+    }
+
+    fn render_collection(&self, ast: &ExpressionAST, open: &str, close: &str, depth: usize) -> Result<String> {
+        let elements = self.render_operands(&ast.operands, depth + 1)?;
+        Ok(self.render_elements(&elements, ast, open, close, depth))
+    }
+
+    fn render_elements(&self, elements: &[String], ast: &ExpressionAST, open: &str, close: &str, depth: usize) -> String {
+        let hints = &ast.formatting;
+
+        if hints.multiline && !elements.is_empty() {
+            let inner = self.indent_unit.repeat(depth + 1);
+            let mut lines: Vec<String> = elements.iter()
+                .map(|element| format!("{}{},", inner, element))
+                .collect();
+            if !hints.trailing_comma {
+                if let Some(last) = lines.last_mut() {
+                    last.pop();
+                }
+            }
+            return format!("{}\n{}\n{}{}", open, lines.join("\n"), self.indent_unit.repeat(depth), close);
+        }
+
+        let trailing = if hints.trailing_comma { "," } else { "" };
+        format!("{}{}{}{}", open, elements.join(", "), trailing, close)
+    }
+}
+
+/// `ast`'s source text without the parentheses its `parenthesized` hint
+/// records, so rendering adds them back exactly once
+fn unparenthesized_text(ast: &ExpressionAST) -> &str {
+    let text = ast.source_text.trim();
+    if ast.formatting.parenthesized {
+        if let Some(inner) = text.strip_prefix('(').and_then(|text| text.strip_suffix(')')) {
+            return inner.trim();
+        }
+    }
+    text
+}
+
+/// Position of an operand within its parent operator expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    /// Inner operand of a comparison chain, or a conditional's condition
+    Middle,
+    Right,
+    /// Operand of a unary operator
+    Only,
+}
+
+/// Python operator precedence, lowest to highest; atoms bind tightest
+fn precedence(ast: &ExpressionAST) -> u8 {
+    let operator = ast.operator.as_deref().unwrap_or("");
+    match ast.expression_type.as_str() {
+        "conditional_expression" => 0,
+        "boolean_operator" if operator == "or" => 1,
+        "boolean_operator" => 2,
+        "not_operator" => 3,
+        "comparison_operator" => 4,
+        "binary_operator" => match operator {
+            "|" => 5,
+            "^" => 6,
+            "&" => 7,
+            "<<" | ">>" => 8,
+            "+" | "-" => 9,
+            "*" | "@" | "/" | "//" | "%" => 10,
+            "**" => 12,
+            _ => 10,
+        },
+        "unary_operator" => 11,
+        _ => 13,
+    }
+}
+
+fn needs_parentheses(operand: &ExpressionAST, parent: &ExpressionAST, side: Side) -> bool {
+    let operand_precedence = precedence(operand);
+    let parent_precedence = precedence(parent);
+    let parent_is_power = parent.expression_type == "binary_operator"
+        && parent.operator.as_deref() == Some("**");
+
+    // `2 ** -1` needs no parentheses: the exponent may be a unary expression
+    if parent_is_power && side == Side::Right && operand.expression_type == "unary_operator" {
+        return false;
+    }
+    if operand_precedence != parent_precedence {
+        return operand_precedence < parent_precedence;
+    }
+
+    match parent.expression_type.as_str() {
+        // A parenthesized comparison operand is not part of the chain
+        "comparison_operator" => true,
+        // `a if b else c if d else e` nests to the right
+        "conditional_expression" => side != Side::Right,
+        // `**` groups right to left, everything else left to right
+        "binary_operator" if parent_is_power => side == Side::Left,
+        "binary_operator" | "boolean_operator" => side == Side::Right,
+        _ => false,
+    }
+}
+
+impl Default for ExpressionRenderer {
+    fn default() -> Self {
+        Self::new("    ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast_extractor::ExpressionExtractor;
+    use tree_sitter::Parser;
+
+    /// Extract the right-hand side of `value = <expression>` and render it back
+    fn round_trip(expression: &str) -> String {
+        let code = format!("value = {}\n", expression);
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(&code, None).unwrap();
+
+        let assignment = tree.root_node().child(0).unwrap().child(0).unwrap();
+        let right = assignment.child_by_field_name("right").unwrap();
+        let ast = ExpressionExtractor::new().extract_expression(right, &code).unwrap();
+        ExpressionRenderer::default().render(&ast).unwrap()
+    }
+
+    #[test]
+    fn test_multiline_list_keeps_trailing_comma() {
+        let source = "[\n    1,\n    2,\n    3,\n]";
+        assert_eq!(round_trip(source), source);
+    }
+
+    #[test]
+    fn test_single_line_collections() {
+        assert_eq!(round_trip("[1, 2, 3]"), "[1, 2, 3]");
+        assert_eq!(round_trip("(1,)"), "(1,)");
+        assert_eq!(round_trip("{'a': 1, 'b': 2}"), "{'a': 1, 'b': 2}");
+    }
+
+    #[test]
+    fn test_explicit_parentheses_are_kept() {
+        assert_eq!(round_trip("(a + b) * c"), "(a + b) * c");
+        assert_eq!(round_trip("a + (b * c)"), "a + (b * c)");
+    }
+
+    /// Render `expression` after forgetting which parentheses the author wrote,
+    /// so every parenthesis in the output comes from precedence alone
+    fn minimal_parentheses(expression: &str) -> String {
+        let code = format!("value = {}\n", expression);
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(&code, None).unwrap();
+
+        let assignment = tree.root_node().child(0).unwrap().child(0).unwrap();
+        let right = assignment.child_by_field_name("right").unwrap();
+        let ast = ExpressionExtractor::new().extract_expression(right, &code).unwrap();
+
+        let mut value = serde_json::to_value(&ast).unwrap();
+        clear_parenthesized(&mut value);
+        let ast: ExpressionAST = serde_json::from_value(value).unwrap();
+        ExpressionRenderer::default().render(&ast).unwrap()
+    }
+
+    fn clear_parenthesized(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::Object(formatting)) = map.get_mut("formatting") {
+                    formatting.insert("parenthesized".to_string(), Value::Bool(false));
+                }
+                map.values_mut().for_each(clear_parenthesized);
+            }
+            Value::Array(items) => items.iter_mut().for_each(clear_parenthesized),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_parenthesized_atoms_are_wrapped_once() {
+        assert_eq!(round_trip("(1)"), "(1)");
+        assert_eq!(round_trip("((1))"), "((1))");
+        assert_eq!(round_trip("('a')"), "('a')");
+        assert_eq!(round_trip("(x)"), "(x)");
+        assert_eq!(round_trip("(1) + 2"), "(1) + 2");
+    }
+
+    #[test]
+    fn test_subscripts_round_trip() {
+        for source in ["items[0]", "grid[row][col]", "table[1, 2]", "items[1:2]", "items[::2]", "(a + b)[0]", "mapping['key']"] {
+            assert_eq!(round_trip(source), source);
+        }
+        assert_eq!(minimal_parentheses("(a + b)[0]"), "(a + b)[0]");
+    }
+
+    #[test]
+    fn test_conditional_expressions_round_trip() {
+        for source in ["a if flag else b", "a if x or y else b", "a if b else c if d else e", "(a if b else c) if d else e"] {
+            assert_eq!(round_trip(source), source);
+        }
+        assert_eq!(minimal_parentheses("(a if b else c) + 1"), "(a if b else c) + 1");
+        assert_eq!(minimal_parentheses("a if (b if c else d) else e"), "a if (b if c else d) else e");
+        assert_eq!(minimal_parentheses("a if b else (c if d else e)"), "a if b else c if d else e");
+    }
+
+    #[test]
+    fn test_mixed_precedence_round_trip() {
+        for source in ["a + b * c", "a * b + c", "(a + b) * c", "a - b - c", "a ** b ** c", "x or y and z"] {
+            assert_eq!(round_trip(source), source);
+        }
+    }
+
+    #[test]
+    fn test_parentheses_added_only_where_required() {
+        assert_eq!(minimal_parentheses("(a + b) * c"), "(a + b) * c");
+        assert_eq!(minimal_parentheses("a + (b * c)"), "a + b * c");
+        assert_eq!(minimal_parentheses("(a - b) - c"), "a - b - c");
+        assert_eq!(minimal_parentheses("a - (b - c)"), "a - (b - c)");
+        assert_eq!(minimal_parentheses("(a ** b) ** c"), "(a ** b) ** c");
+        assert_eq!(minimal_parentheses("a ** (b ** c)"), "a ** b ** c");
+        assert_eq!(minimal_parentheses("(x or y) and z"), "(x or y) and z");
+    }
+
+    #[test]
+    fn test_unary_minus_precedence() {
+        assert_eq!(minimal_parentheses("-(x ** 2)"), "-x ** 2");
+        assert_eq!(minimal_parentheses("(-x) ** 2"), "(-x) ** 2");
+        assert_eq!(minimal_parentheses("-(a + b)"), "-(a + b)");
+        assert_eq!(minimal_parentheses("2 ** -1"), "2 ** -1");
+        assert_eq!(minimal_parentheses("not (a and b)"), "not (a and b)");
+    }
+
+    #[test]
+    fn test_comparison_chaining() {
+        assert_eq!(round_trip("a < b <= c"), "a < b <= c");
+        assert_eq!(round_trip("x not in items"), "x not in items");
+        assert_eq!(minimal_parentheses("(a < b) < c"), "(a < b) < c");
+        assert_eq!(minimal_parentheses("a + 1 < b * 2"), "a + 1 < b * 2");
+    }
+
+    /// Extract `def f(<parameters>): pass`, map it, and render the parameters back
+    fn parameters_round_trip(parameters: &str) -> String {
+        use ast_extractor::{ASTExtractor, ExtractionContext, PythonASTExtractor};
+        use semantic_mapper::{CodeComponent, SemanticMapper};
+
+        let code = format!("def f({}):\n    pass\n", parameters);
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(&code, None).unwrap();
+
+        let context = ExtractionContext::new("f.py".to_string(), "python".to_string(), uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let extraction = PythonASTExtractor::new().extract(tree.root_node(), &code, &context).unwrap();
+        let function = extraction.semantic_blocks.iter().find(|block| block.block_type == "Function").unwrap();
+        let components = SemanticMapper::new().map_block_to_components(function, "python").unwrap();
+        let signature = components.iter()
+            .find_map(|component| match component {
+                CodeComponent::FunctionSignature(signature) => Some(signature),
+                _ => None,
+            })
+            .unwrap();
+        ExpressionRenderer::default().render_parameters(&signature.parameters)
+    }
+
+    #[test]
+    fn test_parameter_defaults_round_trip() {
+        assert_eq!(parameters_round_trip("x=10"), "x=10");
+        assert_eq!(parameters_round_trip("name: str = 'guest'"), "name: str = 'guest'");
+        assert_eq!(parameters_round_trip("items=[]"), "items=[]");
+        assert_eq!(parameters_round_trip("options={'retries': 3}"), "options={'retries': 3}");
+        assert_eq!(parameters_round_trip("created=compute(1, fast=True)"), "created=compute(1, fast=True)");
+        assert_eq!(parameters_round_trip("a, *args, b=None, **kwargs"), "a, *args, b=None, **kwargs");
+        assert_eq!(parameters_round_trip("a, *, b=2"), "a, *, b=2");
+    }
+
+    #[test]
+    fn test_parenthesized_parameter_defaults_round_trip() {
+        assert_eq!(parameters_round_trip("x=(1)"), "x=(1)");
+        assert_eq!(parameters_round_trip("name: str = ('guest')"), "name: str = ('guest')");
+        assert_eq!(parameters_round_trip("scale=(a + b) * 2"), "scale=(a + b) * 2");
+        assert_eq!(parameters_round_trip("key=items[0]"), "key=items[0]");
+    }
+
+    #[test]
+    fn test_call_argument_kinds_round_trip() {
+        use ast_extractor::ArgumentKind;
+
+        for source in ["f(a, b=2, **kwargs)", "f(a, *args, b=2, **kwargs)", "log.info(msg, *args, exc_info=True)"] {
+            assert_eq!(round_trip(source), source);
+        }
+
+        let code = "value = f(a, b=2, *rest, **kwargs)\n";
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let right = tree.root_node().child(0).unwrap().child(0).unwrap().child_by_field_name("right").unwrap();
+        let ast = ExpressionExtractor::new().extract_expression(right, code).unwrap();
+        let call = ast.function_calls.last().unwrap();
+        assert_eq!(call.argument_kinds, vec![
+            ArgumentKind::Positional,
+            ArgumentKind::Keyword { name: "b".to_string() },
+            ArgumentKind::Spread,
+            ArgumentKind::KeywordSpread,
+        ]);
+        assert_eq!(call.positional_count(), 1);
+        assert_eq!(call.keyword_names().collect::<Vec<_>>(), ["b"]);
+        assert!(call.has_splat());
+        // The kinds survive storage
+        let stored: ExpressionAST = serde_json::from_value(serde_json::to_value(&ast).unwrap()).unwrap();
+        assert_eq!(stored.function_calls.last().unwrap().argument_kinds, call.argument_kinds);
+        assert_eq!(ExpressionRenderer::default().render(&stored).unwrap(), "f(a, b=2, *rest, **kwargs)");
+    }
+
+    #[test]
+    fn test_call_arguments_keep_trailing_comma() {
+        let source = "build(\n    name,\n    debug=True,\n)";
+        assert_eq!(round_trip(source), source);
+    }
+}
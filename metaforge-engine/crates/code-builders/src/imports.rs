@@ -0,0 +1,589 @@
+//! Import deduplication
+
+use std::collections::{HashMap, HashSet};
+use crate::BlockTransform;
+
+/// Deduplicate and merge import statements, keeping first-occurrence order
+pub fn dedupe_import_statements(statements: &[String], language: &str) -> Vec<String> {
+    merge_slots(statements, language).into_iter().flatten().collect()
+}
+
+/// Deduplicate the import statements at the top of rendered code. Only the
+/// leading run of imports is touched, so code between them is never reordered.
+pub fn dedupe_imports(code: &str, language: &str) -> String {
+    let lines: Vec<&str> = code.lines().collect();
+    let mut items: Vec<RegionItem> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+        if trimmed.is_empty() || is_comment(trimmed, language) {
+            items.push(RegionItem::Other(line.to_string()));
+            index += 1;
+            continue;
+        }
+        match statement_end(&lines, index, language) {
+            Some(end) => {
+                items.push(RegionItem::Statement(lines[index..=end].join("\n")));
+                index = end + 1;
+            }
+            None => break,
+        }
+    }
+
+    let statements: Vec<String> = items.iter()
+        .filter_map(|item| match item {
+            RegionItem::Statement(text) => Some(text.clone()),
+            RegionItem::Other(_) => None,
+        })
+        .collect();
+    let mut merged = merge_slots(&statements, language).into_iter();
+
+    let mut output: Vec<String> = Vec::new();
+    for item in items {
+        match item {
+            RegionItem::Other(line) => output.push(line),
+            RegionItem::Statement(_) => output.extend(merged.next().flatten()),
+        }
+    }
+    output.extend(lines[index..].iter().map(|line| line.to_string()));
+
+    let mut result = output.join("\n");
+    if code.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Post-render step that runs `dedupe_imports` for one language
+pub struct DedupeImports {
+    language: String,
+}
+
+impl DedupeImports {
+    pub fn new(language: &str) -> Self {
+        Self { language: language.to_string() }
+    }
+}
+
+impl BlockTransform for DedupeImports {
+    fn transform_code(&self, code: &mut String) {
+        *code = dedupe_imports(code, &self.language);
+    }
+}
+
+enum RegionItem {
+    Statement(String),
+    Other(String),
+}
+
+fn is_comment(line: &str, language: &str) -> bool {
+    match language {
+        "python" => line.starts_with('#'),
+        // Inner attributes such as `#![allow(unused)]` open Rust files
+        "rust" => line.starts_with("//") || line.starts_with("#!["),
+        _ => line.starts_with("//"),
+    }
+}
+
+/// Index of the last line of the import statement starting at `start`
+fn statement_end(lines: &[&str], start: usize, language: &str) -> Option<usize> {
+    let first = lines[start];
+    if first.starts_with(char::is_whitespace) {
+        return None;
+    }
+    match language {
+        "python" => {
+            if !(first.starts_with("import ") || first.starts_with("from ")) {
+                return None;
+            }
+            let mut depth = 0i32;
+            for (offset, line) in lines[start..].iter().enumerate() {
+                depth += line.matches('(').count() as i32 - line.matches(')').count() as i32;
+                if depth <= 0 && !line.trim_end().ends_with('\\') {
+                    return Some(start + offset);
+                }
+            }
+            None
+        }
+        "rust" => {
+            // Attributes belong to the `use` that follows them
+            let mut index = start;
+            while lines.get(index)?.trim_start().starts_with("#[") {
+                index += 1;
+            }
+            let statement = lines.get(index)?.trim_start();
+            let body = strip_visibility(statement).unwrap_or(statement);
+            if !body.starts_with("use ") {
+                return None;
+            }
+            lines[index..].iter().position(|line| line.trim_end().ends_with(';')).map(|offset| index + offset)
+        }
+        _ => {
+            let is_import = first.starts_with("import ")
+                || (language == "go" && first.starts_with("import"));
+            (is_import && !first.trim_end().ends_with('(') && !first.trim_end().ends_with('{')).then_some(start)
+        }
+    }
+}
+
+/// What to emit in place of each input statement, aligned with the input
+fn merge_slots(statements: &[String], language: &str) -> Vec<Option<String>> {
+    match language {
+        "python" => merge_python(statements),
+        "rust" => merge_rust(statements),
+        _ => {
+            let mut seen = HashSet::new();
+            statements.iter()
+                .map(|statement| seen.insert(statement.trim().to_string()).then(|| statement.clone()))
+                .collect()
+        }
+    }
+}
+
+/// Names bound to more than one distinct target
+fn conflicting_names(bindings: impl Iterator<Item = (String, String)>) -> HashSet<String> {
+    let mut targets: HashMap<String, HashSet<String>> = HashMap::new();
+    for (name, target) in bindings {
+        targets.entry(name).or_default().insert(target);
+    }
+    targets.into_iter()
+        .filter(|(_, targets)| targets.len() > 1)
+        .map(|(name, _)| name)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ImportedName {
+    name: String,
+    alias: Option<String>,
+}
+
+impl ImportedName {
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split_whitespace();
+        let name = parts.next()?.to_string();
+        match (parts.next(), parts.next(), parts.next()) {
+            (None, _, _) => Some(Self { name, alias: None }),
+            (Some("as"), Some(alias), None) => Some(Self { name, alias: Some(alias.to_string()) }),
+            _ => None,
+        }
+    }
+
+    fn render(&self) -> String {
+        match &self.alias {
+            Some(alias) => format!("{} as {}", self.name, alias),
+            None => self.name.clone(),
+        }
+    }
+}
+
+enum PythonImport {
+    /// `import a.b, c as d`
+    Plain(Vec<ImportedName>),
+    /// `from module import a, b as c`
+    From { module: String, names: Vec<ImportedName> },
+    /// Anything merging could mangle: comments, star imports, odd syntax
+    Verbatim,
+}
+
+impl PythonImport {
+    fn parse(statement: &str) -> Self {
+        if statement.contains('#') || statement.contains(';') {
+            return Self::Verbatim;
+        }
+        let flat = statement.replace("\\\n", " ").replace('\n', " ");
+        let flat = flat.trim();
+
+        if let Some(rest) = flat.strip_prefix("import ") {
+            return match rest.split(',').map(|name| ImportedName::parse(name.trim())).collect::<Option<Vec<_>>>() {
+                Some(names) if !names.is_empty() => Self::Plain(names),
+                _ => Self::Verbatim,
+            };
+        }
+
+        let Some((module, names)) = flat.strip_prefix("from ").and_then(|rest| rest.split_once(" import ")) else {
+            return Self::Verbatim;
+        };
+        let names = names.trim();
+        let names = names.strip_prefix('(').and_then(|n| n.strip_suffix(')')).unwrap_or(names);
+        let parsed = names.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(ImportedName::parse)
+            .collect::<Option<Vec<_>>>();
+        match parsed {
+            Some(names) if !names.is_empty() && names.iter().all(|n| n.name != "*") => Self::From {
+                module: module.trim().to_string(),
+                names,
+            },
+            _ => Self::Verbatim,
+        }
+    }
+
+    /// (bound name, what it refers to) for every name this statement binds
+    fn bindings(&self) -> Vec<(String, String)> {
+        match self {
+            Self::Plain(names) => names.iter().map(|n| match &n.alias {
+                Some(alias) => (alias.clone(), n.name.clone()),
+                // `import a.b` binds the top-level package `a`
+                None => {
+                    let top = n.name.split('.').next().unwrap_or(&n.name).to_string();
+                    (top.clone(), top)
+                }
+            }).collect(),
+            Self::From { module, names } => names.iter()
+                .map(|n| (n.alias.clone().unwrap_or_else(|| n.name.clone()), format!("{}:{}", module, n.name)))
+                .collect(),
+            Self::Verbatim => Vec::new(),
+        }
+    }
+}
+
+fn merge_python(statements: &[String]) -> Vec<Option<String>> {
+    let parsed: Vec<PythonImport> = statements.iter().map(|s| PythonImport::parse(s)).collect();
+    let conflicts = conflicting_names(parsed.iter().flat_map(PythonImport::bindings));
+
+    // Modules whose `from` imports cannot be merged safely
+    let mut unmergeable: HashSet<&str> = HashSet::new();
+    for import in &parsed {
+        if let PythonImport::From { module, .. } = import {
+            if import.bindings().iter().any(|(name, _)| conflicts.contains(name)) {
+                unmergeable.insert(module);
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<String>> = vec![None; statements.len()];
+    let mut seen_text = HashSet::new();
+    let mut seen_plain = HashSet::new();
+    let mut groups: HashMap<&str, (usize, Vec<ImportedName>)> = HashMap::new();
+
+    for (index, (statement, import)) in statements.iter().zip(&parsed).enumerate() {
+        match import {
+            PythonImport::Plain(names) => {
+                let fresh: Vec<String> = names.iter()
+                    .filter(|name| seen_plain.insert((*name).clone()))
+                    .map(ImportedName::render)
+                    .collect();
+                if !fresh.is_empty() {
+                    slots[index] = Some(format!("import {}", fresh.join(", ")));
+                }
+            }
+            PythonImport::From { module, names } if !unmergeable.contains(module.as_str()) => {
+                let (_, merged) = groups.entry(module.as_str()).or_insert_with(|| (index, Vec::new()));
+                for name in names {
+                    if !merged.contains(name) {
+                        merged.push(name.clone());
+                    }
+                }
+            }
+            _ => {
+                if seen_text.insert(statement.trim().to_string()) {
+                    slots[index] = Some(statement.clone());
+                }
+            }
+        }
+    }
+
+    for (module, (index, names)) in groups {
+        let names: Vec<String> = names.iter().map(ImportedName::render).collect();
+        slots[index] = Some(format!("from {} import {}", module, names.join(", ")));
+    }
+    slots
+}
+
+/// One flattened path of a `use` tree, e.g. `std::io::Read as _`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct UseEntry {
+    visibility: String,
+    parent: Vec<String>,
+    leaf: String,
+    alias: Option<String>,
+}
+
+impl UseEntry {
+    /// The path this entry names, with `self` resolved to its parent
+    fn target(&self) -> String {
+        if self.leaf == "self" {
+            self.parent.join("::")
+        } else {
+            self.parent.iter().chain(std::iter::once(&self.leaf)).cloned().collect::<Vec<_>>().join("::")
+        }
+    }
+
+    fn binding(&self) -> Option<(String, String)> {
+        let name = match &self.alias {
+            Some(alias) => alias.clone(),
+            None if self.leaf == "self" => self.parent.last()?.clone(),
+            None => self.leaf.clone(),
+        };
+        (name != "_" && self.leaf != "*").then(|| (name, self.target()))
+    }
+
+    fn render_leaf(&self) -> String {
+        match &self.alias {
+            Some(alias) => format!("{} as {}", self.leaf, alias),
+            None => self.leaf.clone(),
+        }
+    }
+}
+
+/// `pub use`, `pub(crate) use` ... -> the text after the visibility
+fn strip_visibility(statement: &str) -> Option<&str> {
+    if let Some(rest) = statement.strip_prefix("pub(") {
+        let close = rest.find(')')?;
+        return Some(rest[close + 1..].trim_start());
+    }
+    statement.strip_prefix("pub ").map(str::trim_start)
+}
+
+/// Flatten a `use` statement, or `None` when it should be kept verbatim
+fn parse_use(statement: &str) -> Option<Vec<UseEntry>> {
+    if statement.contains("#[") || statement.contains("//") || statement.contains("/*") {
+        return None;
+    }
+    let flat = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+    let body = strip_visibility(&flat).unwrap_or(&flat);
+    let visibility = flat[..flat.len() - body.len()].trim().to_string();
+    let tree = body.strip_prefix("use ")?.trim().strip_suffix(';')?.trim();
+
+    let mut entries = Vec::new();
+    flatten_use_tree(tree, &[], &visibility, &mut entries)?;
+    Some(entries)
+}
+
+fn flatten_use_tree(tree: &str, prefix: &[String], visibility: &str, out: &mut Vec<UseEntry>) -> Option<()> {
+    let tree = tree.trim();
+    if let Some(open) = tree.find('{') {
+        let inner = tree[open + 1..].strip_suffix('}')?;
+        let mut path = prefix.to_vec();
+        let head = tree[..open].trim();
+        if !head.is_empty() {
+            path.extend(split_path(head.strip_suffix("::")?)?);
+        }
+        for branch in split_top_level(inner) {
+            if !branch.trim().is_empty() {
+                flatten_use_tree(branch, &path, visibility, out)?;
+            }
+        }
+        return Some(());
+    }
+
+    let (path, alias) = match tree.split_once(" as ") {
+        Some((path, alias)) => (path.trim(), Some(alias.trim().to_string())),
+        None => (tree, None),
+    };
+    let mut segments = prefix.to_vec();
+    segments.extend(split_path(path)?);
+    let leaf = segments.pop()?;
+    out.push(UseEntry { visibility: visibility.to_string(), parent: segments, leaf, alias });
+    Some(())
+}
+
+fn split_path(path: &str) -> Option<Vec<String>> {
+    path.split("::")
+        .map(|segment| {
+            let segment = segment.trim();
+            let valid = segment == "*" || (!segment.is_empty() && segment.chars().all(|c| c.is_alphanumeric() || c == '_'));
+            valid.then(|| segment.to_string())
+        })
+        .collect()
+}
+
+/// Split on commas that are not nested inside braces
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn merge_rust(statements: &[String]) -> Vec<Option<String>> {
+    let parsed: Vec<Option<Vec<UseEntry>>> = statements.iter().map(|s| parse_use(s)).collect();
+    let conflicts = conflicting_names(parsed.iter().flatten().flatten().filter_map(UseEntry::binding));
+
+    let mut slots: Vec<Vec<String>> = vec![Vec::new(); statements.len()];
+    let mut seen_text = HashSet::new();
+    let mut seen_targets = HashSet::new();
+    let mut group_order: Vec<(usize, (String, Vec<String>))> = Vec::new();
+    let mut groups: HashMap<(String, Vec<String>), Vec<UseEntry>> = HashMap::new();
+
+    for (index, (statement, entries)) in statements.iter().zip(&parsed).enumerate() {
+        let mergeable = entries.as_ref().filter(|entries| {
+            entries.iter().all(|entry| !entry.binding().is_some_and(|(name, _)| conflicts.contains(&name)))
+        });
+        let Some(entries) = mergeable else {
+            if seen_text.insert(statement.trim().to_string()) {
+                slots[index].push(statement.clone());
+            }
+            continue;
+        };
+
+        for entry in entries {
+            if !seen_targets.insert((entry.visibility.clone(), entry.target(), entry.leaf == "*", entry.alias.clone())) {
+                continue;
+            }
+            let key = (entry.visibility.clone(), entry.parent.clone());
+            let group = groups.entry(key.clone()).or_insert_with(|| {
+                group_order.push((index, key));
+                Vec::new()
+            });
+            group.push(entry.clone());
+        }
+    }
+
+    for (index, key) in group_order {
+        let entries = &groups[&key];
+        let (visibility, parent) = key;
+        let prefix = if visibility.is_empty() { String::new() } else { format!("{} ", visibility) };
+
+        // Crate-level names (`use serde;`) have no parent to share
+        if parent.is_empty() {
+            slots[index].extend(entries.iter().map(|entry| format!("{}use {};", prefix, entry.render_leaf())));
+            continue;
+        }
+
+        let parent = parent.join("::");
+        if let [entry] = entries.as_slice() {
+            if entry.leaf != "self" {
+                slots[index].push(format!("{}use {}::{};", prefix, parent, entry.render_leaf()));
+                continue;
+            }
+        }
+        // `self` leads, matching how rustfmt orders use trees
+        let mut leaves: Vec<&UseEntry> = entries.iter().filter(|entry| entry.leaf == "self").collect();
+        leaves.extend(entries.iter().filter(|entry| entry.leaf != "self"));
+        let leaves: Vec<String> = leaves.into_iter().map(UseEntry::render_leaf).collect();
+        slots[index].push(format!("{}use {}::{{{}}};", prefix, parent, leaves.join(", ")));
+    }
+
+    slots.into_iter()
+        .map(|lines| (!lines.is_empty()).then(|| lines.join("\n")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statements(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_python_merges_from_imports_and_drops_repeats() {
+        let merged = dedupe_import_statements(&statements(&[
+            "import os",
+            "from typing import List",
+            "import os",
+            "from typing import Dict, List",
+            "from typing import (\n    Optional,\n)",
+            "import numpy as np",
+            "import numpy as np, sys",
+        ]), "python");
+        assert_eq!(merged, vec![
+            "import os",
+            "from typing import List, Dict, Optional",
+            "import numpy as np",
+            "import sys",
+        ]);
+    }
+
+    #[test]
+    fn test_python_keeps_conflicting_aliases_and_star_imports_apart() {
+        let merged = dedupe_import_statements(&statements(&[
+            "from json import loads as load",
+            "from pickle import load",
+            "from json import dumps",
+            "from os.path import *",
+            "from os.path import *",
+            "from os.path import join",
+            "import sys  # noqa",
+        ]), "python");
+        // `load` names two different functions, so `json` is left as written
+        assert_eq!(merged, vec![
+            "from json import loads as load",
+            "from pickle import load",
+            "from json import dumps",
+            "from os.path import *",
+            "from os.path import join",
+            "import sys  # noqa",
+        ]);
+    }
+
+    #[test]
+    fn test_rust_collapses_use_trees_by_parent() {
+        let merged = dedupe_import_statements(&statements(&[
+            "use std::collections::HashMap;",
+            "use std::io::{self, Read};",
+            "use std::collections::{HashMap, HashSet};",
+            "use std::io::Write as _;",
+            "pub use crate::model::Block;",
+            "use crate::model::Block as StoredBlock;",
+            "use serde;",
+            "use serde;",
+        ]), "rust");
+        assert_eq!(merged, vec![
+            "use std::collections::{HashMap, HashSet};",
+            "use std::io::{self, Read, Write as _};",
+            "pub use crate::model::Block;",
+            "use crate::model::Block as StoredBlock;",
+            "use serde;",
+        ]);
+    }
+
+    #[test]
+    fn test_rust_leaves_conflicts_and_attributed_uses_verbatim() {
+        let merged = dedupe_import_statements(&statements(&[
+            "use std::fmt::Result;",
+            "use std::fmt::Display;",
+            "use std::io::Result;",
+            "#[cfg(test)]\nuse std::fmt::Debug;",
+            "#[cfg(test)]\nuse std::fmt::Debug;",
+        ]), "rust");
+        assert_eq!(merged, vec![
+            "use std::fmt::Result;",
+            "use std::fmt::Display;",
+            "use std::io::Result;",
+            "#[cfg(test)]\nuse std::fmt::Debug;",
+        ]);
+    }
+
+    #[test]
+    fn test_other_languages_only_drop_identical_imports() {
+        let merged = dedupe_import_statements(&statements(&[
+            "import { a } from './a';",
+            "import { b } from './a';",
+            "import { a } from './a';",
+        ]), "typescript");
+        assert_eq!(merged, vec!["import { a } from './a';", "import { b } from './a';"]);
+    }
+
+    #[test]
+    fn test_dedupe_imports_only_rewrites_the_leading_import_block() {
+        let code = "# stdlib\nimport os\nfrom typing import List\n\nimport os\nfrom typing import Dict\n\ndef main():\n    import os\n";
+        assert_eq!(
+            dedupe_imports(code, "python"),
+            "# stdlib\nimport os\nfrom typing import List, Dict\n\n\ndef main():\n    import os\n",
+        );
+
+        let rust = "#![allow(unused)]\nuse std::fmt::Debug;\n#[cfg(test)]\nuse std::fmt::Display;\nuse std::fmt::Write;\n\nfn main() {}\n";
+        assert_eq!(
+            dedupe_imports(rust, "rust"),
+            "#![allow(unused)]\nuse std::fmt::{Debug, Write};\n#[cfg(test)]\nuse std::fmt::Display;\n\nfn main() {}\n",
+        );
+    }
+}
@@ -4,19 +4,44 @@
 //! This crate implements the core principle: **NO SOURCE CODE FALLBACK**.
 //! All generation must come from semantic understanding.
 
-pub mod builders;
-pub mod formatters;
+pub mod batch;
+pub mod cache;
+pub mod comments;
+pub mod coverage;
+pub mod error;
+pub mod expression;
+pub mod imports;
+pub mod process;
+pub mod profile;
+pub mod registry;
+pub mod source_map;
+pub mod strict;
+pub mod stubs;
 pub mod traits;
-
-pub use builders::{PythonBuilder, RustBuilder, JavaScriptBuilder};
-pub use formatters::{PythonFormatter, RustFormatter, JavaScriptFormatter};
+pub mod transform;
+
+pub use batch::{BatchFormatter, BatchFormatOutcome, DEFAULT_BATCH_TIMEOUT};
+pub use cache::{GenerationCache, CacheStats};
+pub use comments::{comment, comment_delimiters};
+pub use coverage::{is_placeholder, DEFAULT_PLACEHOLDER_MARKERS};
+pub use error::{BuilderError, BuilderResult};
+pub use expression::ExpressionRenderer;
+pub use imports::{dedupe_import_statements, dedupe_imports, DedupeImports};
+pub use process::wait_with_deadline;
+pub use profile::{StrictnessProfile, UnknownProfile};
+pub use registry::BuilderRegistry;
+pub use source_map::{LineMapping, OriginalRange, SourceMap};
+pub use strict::IncompleteBlock;
+pub use stubs::{render_stubs, stub_extension};
 pub use traits::{CodeBuilder, LanguageFormatter};
+pub use transform::{BlockTransform, TransformChain, TransformedBuilder};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Configuration for code building
+/// Configuration for code building. `StrictnessProfile::apply` sets the
+/// strictness settings together.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildConfig {
     pub language: String,
@@ -25,9 +50,28 @@ pub struct BuildConfig {
     pub max_line_length: usize,
     pub format_on_build: bool,
     pub strict_mode: bool, // If true, fail on incomplete AST data
+    /// In strict mode, check every block before failing instead of
+    /// stopping at the first incomplete one
+    #[serde(default = "collect_all_errors")]
+    pub collect_all_errors: bool,
     pub generation_hints: HashMap<String, serde_json::Value>,
+    /// Comment text that marks a block's code as a placeholder stub
+    #[serde(default = "coverage::default_placeholder_markers")]
+    pub placeholder_markers: Vec<String>,
+    /// Record a `SourceMap` of the output in `BuildResult::source_map`
+    #[serde(default)]
+    pub source_map: bool,
+    /// Largest output a build may produce; rendering stops with
+    /// `BuilderError::OutputTooLarge` as soon as it passes this many bytes
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
 }
 
+/// Output cap of `BuildConfig::default()`, far above any real source file
+/// but low enough that a runaway synthesized block fails instead of
+/// exhausting memory
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024 * 1024;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum IndentStyle {
     Spaces(usize),
@@ -48,6 +92,9 @@ pub struct BuildResult {
     pub metadata: BuildMetadata,
     pub warnings: Vec<String>,
     pub errors: Vec<String>,
+    /// Origin of each output line, when `BuildConfig::source_map` is set
+    #[serde(default)]
+    pub source_map: Option<SourceMap>,
 }
 
 /// Metadata about the build process
@@ -59,6 +106,32 @@ pub struct BuildMetadata {
     pub ast_utilization: f64, // Percentage of AST data actually used
     pub generation_quality: f64, // Quality score 0.0-1.0
     pub language_specific: HashMap<String, serde_json::Value>,
+    /// Blocks served from a `GenerationCache` instead of being rendered
+    #[serde(default)]
+    pub cache_hits: usize,
+    #[serde(default)]
+    pub cache_misses: usize,
+    /// Fraction of blocks whose code is real rather than a placeholder
+    #[serde(default = "full_coverage")]
+    pub semantic_coverage: f64,
+    /// Blocks that rendered as placeholders, see `BuildResult::placeholder_blocks`
+    #[serde(default)]
+    pub placeholder_blocks: Vec<uuid::Uuid>,
+    /// Blocks strict mode refused, when it aborted the build
+    #[serde(default)]
+    pub incomplete_blocks: Vec<IncompleteBlock>,
+}
+
+fn full_coverage() -> f64 {
+    1.0
+}
+
+fn collect_all_errors() -> bool {
+    true
+}
+
+fn default_max_output_bytes() -> usize {
+    DEFAULT_MAX_OUTPUT_BYTES
 }
 
 impl Default for BuildConfig {
@@ -70,8 +143,22 @@ impl Default for BuildConfig {
             max_line_length: 88, // Black's default
             format_on_build: true,
             strict_mode: true, // Default to strict mode - fail on incomplete data
+            collect_all_errors: true,
             generation_hints: HashMap::new(),
+            placeholder_markers: coverage::default_placeholder_markers(),
+            source_map: false,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+        }
+    }
+}
+
+impl BuildConfig {
+    /// Fail once output has grown to `bytes`, past `max_output_bytes`
+    pub fn check_output_size(&self, bytes: usize) -> BuilderResult<()> {
+        if bytes > self.max_output_bytes {
+            return Err(BuilderError::OutputTooLarge { bytes, limit: self.max_output_bytes });
         }
+        Ok(())
     }
 }
 
@@ -88,9 +175,15 @@ impl BuildResult {
                 ast_utilization: 0.0,
                 generation_quality: 0.0,
                 language_specific: HashMap::new(),
+                cache_hits: 0,
+                cache_misses: 0,
+                semantic_coverage: 1.0,
+                placeholder_blocks: Vec::new(),
+                incomplete_blocks: Vec::new(),
             },
             warnings: Vec::new(),
             errors: Vec::new(),
+            source_map: None,
         }
     }
 
@@ -0,0 +1,39 @@
+//! Running external tools with a deadline
+
+use std::process::{Child, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Wait for `child` to exit. Once `timeout` has passed it is killed and
+/// `Ok(None)` is returned.
+pub fn wait_with_deadline(child: &mut Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_hung_child_is_killed_at_the_deadline() {
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let start = Instant::now();
+        assert!(wait_with_deadline(&mut child, Duration::from_millis(50)).unwrap().is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let mut child = Command::new("true").spawn().unwrap();
+        assert!(wait_with_deadline(&mut child, Duration::from_secs(5)).unwrap().unwrap().success());
+    }
+}
@@ -0,0 +1,175 @@
+//! Strictness profiles
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use crate::BuildConfig;
+
+/// Comment text the paranoid profile also treats as a placeholder
+pub const PARANOID_PLACEHOLDER_MARKERS: [&str; 2] = ["FIXME", "XXX"];
+
+/// One setting in place of the strictness knobs spread across `BuildConfig`
+/// and the `generate` command. Each profile sets:
+///
+/// | Profile    | `strict_mode` | `format_on_build` | Placeholders                   | Quality threshold | Re-parse validation |
+/// |------------|---------------|-------------------|--------------------------------|-------------------|---------------------|
+/// | `lenient`  | off           | off               | warn                           | 0.0               | optional            |
+/// | `balanced` | off           | on                | warn                           | 0.7               | optional            |
+/// | `strict`   | on            | on                | fail                           | 0.85              | optional            |
+/// | `paranoid` | on            | on                | fail, `FIXME`/`XXX` count too  | 0.95              | required            |
+///
+/// Strict mode collects every incomplete block before failing under all
+/// four. Fields set after applying a profile override it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrictnessProfile {
+    /// Best-effort output: incomplete blocks and placeholders are warnings,
+    /// nothing is formatted and no quality is required
+    Lenient,
+    /// Formatted output that warns about incomplete blocks and placeholders
+    /// and fails below 0.7 quality, the `generate` defaults
+    #[default]
+    Balanced,
+    /// ADR-001: incomplete blocks and placeholders fail the build, as does
+    /// quality below 0.85
+    Strict,
+    /// `Strict` with FIXME/XXX comments counted as placeholders, quality of
+    /// at least 0.95 and generated files required to re-parse
+    Paranoid,
+}
+
+impl StrictnessProfile {
+    pub const ALL: [StrictnessProfile; 4] = [Self::Lenient, Self::Balanced, Self::Strict, Self::Paranoid];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lenient => "lenient",
+            Self::Balanced => "balanced",
+            Self::Strict => "strict",
+            Self::Paranoid => "paranoid",
+        }
+    }
+
+    /// Whether incomplete blocks and placeholder code are errors rather than warnings
+    pub fn strict_mode(self) -> bool {
+        matches!(self, Self::Strict | Self::Paranoid)
+    }
+
+    pub fn format_on_build(self) -> bool {
+        self != Self::Lenient
+    }
+
+    /// Minimum generation quality (0.0-1.0) of every file
+    pub fn quality_threshold(self) -> f64 {
+        match self {
+            Self::Lenient => 0.0,
+            Self::Balanced => 0.7,
+            Self::Strict => 0.85,
+            Self::Paranoid => 0.95,
+        }
+    }
+
+    /// Whether a run that didn't re-parse its output fails
+    pub fn requires_validation(self) -> bool {
+        self == Self::Paranoid
+    }
+
+    /// Comment text marking placeholder code
+    pub fn placeholder_markers(self) -> Vec<String> {
+        let mut markers = crate::coverage::default_placeholder_markers();
+        if self == Self::Paranoid {
+            markers.extend(PARANOID_PLACEHOLDER_MARKERS.iter().map(|marker| marker.to_string()));
+        }
+        markers
+    }
+
+    /// Set the build settings this profile covers on `config`, leaving the
+    /// language, layout and hints alone
+    pub fn apply(self, config: &mut BuildConfig) {
+        config.strict_mode = self.strict_mode();
+        config.collect_all_errors = true;
+        config.format_on_build = self.format_on_build();
+        config.placeholder_markers = self.placeholder_markers();
+    }
+
+    /// The default `BuildConfig` under this profile
+    pub fn build_config(self) -> BuildConfig {
+        let mut config = BuildConfig::default();
+        self.apply(&mut config);
+        config
+    }
+}
+
+impl fmt::Display for StrictnessProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A profile name other than `lenient`, `balanced`, `strict` or `paranoid`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownProfile(pub String);
+
+impl fmt::Display for UnknownProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown strictness profile: {} (expected lenient, balanced, strict or paranoid)", self.0)
+    }
+}
+
+impl std::error::Error for UnknownProfile {}
+
+impl FromStr for StrictnessProfile {
+    type Err = UnknownProfile;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter()
+            .find(|profile| profile.name() == name)
+            .ok_or(UnknownProfile(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_placeholder, BuildResult};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_profiles_set_a_coherent_bundle_of_build_settings() {
+        let lenient = StrictnessProfile::Lenient.build_config();
+        assert!(!lenient.strict_mode && !lenient.format_on_build);
+        let balanced = StrictnessProfile::Balanced.build_config();
+        assert!(!balanced.strict_mode && balanced.format_on_build);
+        let strict = StrictnessProfile::Strict.build_config();
+        assert!(strict.strict_mode && strict.collect_all_errors);
+        assert_eq!(strict.placeholder_markers, BuildConfig::default().placeholder_markers);
+
+        // Thresholds only rise with strictness; only paranoid requires re-parsing
+        let thresholds: Vec<f64> = StrictnessProfile::ALL.iter().map(|profile| profile.quality_threshold()).collect();
+        assert!(thresholds.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", thresholds);
+        assert!(StrictnessProfile::ALL.iter().all(|profile| profile.requires_validation() == (*profile == StrictnessProfile::Paranoid)));
+
+        // Paranoid fails on FIXME comments that strict lets through
+        let code = vec![(Uuid::new_v4(), "def load():\n    # FIXME: handle timeouts\n    return fetch()".to_string())];
+        assert!(!is_placeholder(&code[0].1, &strict.placeholder_markers));
+        let paranoid = StrictnessProfile::Paranoid.build_config();
+        let mut result = BuildResult::new(String::new());
+        result.record_coverage(&code, &paranoid);
+        assert!(result.has_errors());
+        let mut result = BuildResult::new(String::new());
+        result.record_coverage(&code, &StrictnessProfile::Lenient.build_config());
+        assert!(!result.has_errors());
+
+        // Fields set afterwards override the profile
+        let mut config = BuildConfig { language: "rust".to_string(), ..BuildConfig::default() };
+        StrictnessProfile::Lenient.apply(&mut config);
+        config.format_on_build = true;
+        assert_eq!((config.language.as_str(), config.strict_mode, config.format_on_build), ("rust", false, true));
+
+        assert_eq!("Paranoid".parse::<StrictnessProfile>(), Ok(StrictnessProfile::Paranoid));
+        assert_eq!(StrictnessProfile::Strict.to_string(), "strict");
+        assert!("pedantic".parse::<StrictnessProfile>().is_err());
+        assert_eq!(serde_json::to_value(StrictnessProfile::Balanced).unwrap(), serde_json::json!("balanced"));
+    }
+}
@@ -0,0 +1,204 @@
+use ast_extractor::Language;
+use semantic_mapper::CodeComponent;
+use std::collections::HashMap;
+use crate::{BuildConfig, BuildResult, BuilderError, BuilderResult, CodeBuilder, GenerationCache};
+
+/// Code builders keyed by the language they generate.
+///
+/// Downstream crates register their own `CodeBuilder` implementations here
+/// to add languages (or replace a bundled builder) without modifying this crate.
+/// Names `Language` knows are stored under their canonical name, so a builder
+/// registered as `typescript` is found for `ts`; other names are kept as given.
+#[derive(Default)]
+pub struct BuilderRegistry {
+    builders: HashMap<String, Box<dyn CodeBuilder>>,
+}
+
+impl BuilderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `builder` under `builder.language()`, replacing any previous
+    /// builder for that language
+    pub fn register(&mut self, builder: Box<dyn CodeBuilder>) {
+        self.builders.insert(Language::canonical_name(builder.language()).to_string(), builder);
+    }
+
+    pub fn get(&self, language: &str) -> Option<&dyn CodeBuilder> {
+        self.builders.get(Language::canonical_name(language)).map(|builder| builder.as_ref())
+    }
+
+    /// Registered languages, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.builders.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+
+    /// Build with the builder registered for `config.language`
+    pub fn build(&self, components: Vec<CodeComponent>, config: &BuildConfig) -> BuilderResult<BuildResult> {
+        let builder = self.get(&config.language)
+            .ok_or_else(|| BuilderError::UnsupportedLanguage(config.language.clone()))?;
+
+        builder.validate_components(&components)?;
+        let result = builder.build_from_components(components, config)?;
+        config.check_output_size(result.generated_code.len())?;
+        Ok(result)
+    }
+
+    /// Build block by block, reusing cached code for blocks whose hash is unchanged.
+    ///
+    /// `blocks` pairs each block's semantic hash with its components. Rendered
+    /// blocks are separated by a blank line; warnings and errors are only
+    /// reported for blocks that were actually rendered.
+    pub fn build_cached(
+        &self,
+        blocks: Vec<(String, Vec<CodeComponent>)>,
+        config: &BuildConfig,
+        cache: &mut GenerationCache,
+    ) -> BuilderResult<BuildResult> {
+        let start = std::time::Instant::now();
+        let stats_before = cache.stats();
+        let block_count = blocks.len();
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+
+        let mut rendered = Vec::with_capacity(block_count);
+        let mut rendered_bytes = 0;
+        for (block_hash, components) in blocks {
+            let code = cache.get_or_render(&block_hash, config, || {
+                let result = self.build(components, config)?;
+                if result.has_errors() {
+                    return Err(BuilderError::BuildFailed { block_id: block_hash.clone(), errors: result.errors });
+                }
+                warnings.extend(result.warnings);
+                Ok(result.generated_code)
+            });
+
+            match code {
+                Ok(code) => {
+                    rendered_bytes += code.len();
+                    config.check_output_size(rendered_bytes)?;
+                    rendered.push(code);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        let line_ending = config.line_ending.as_str();
+        let mut result = BuildResult::new(rendered.join(&format!("{}{}", line_ending, line_ending)));
+        let stats = cache.stats();
+        result.metadata.blocks_processed = block_count;
+        result.metadata.build_time_ms = start.elapsed().as_millis() as u64;
+        result.metadata.cache_hits = stats.hits - stats_before.hits;
+        result.metadata.cache_misses = stats.misses - stats_before.misses;
+        result.warnings = warnings;
+        result.errors = errors;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reports an error for every build
+    struct FailingBuilder;
+
+    impl CodeBuilder for FailingBuilder {
+        fn build_from_components(&self, _components: Vec<CodeComponent>, _config: &BuildConfig) -> BuilderResult<BuildResult> {
+            let mut result = BuildResult::new(String::new());
+            result.add_error("no body".to_string());
+            Ok(result)
+        }
+
+        fn language(&self) -> &'static str {
+            "python"
+        }
+
+        fn supports_component(&self, _component: &CodeComponent) -> bool {
+            true
+        }
+
+        fn validate_components(&self, _components: &[CodeComponent]) -> BuilderResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Renders a kilobyte per build, whatever the input
+    struct KilobyteBuilder;
+
+    impl CodeBuilder for KilobyteBuilder {
+        fn build_from_components(&self, _components: Vec<CodeComponent>, _config: &BuildConfig) -> BuilderResult<BuildResult> {
+            Ok(BuildResult::new("#".repeat(1024)))
+        }
+
+        fn language(&self) -> &'static str {
+            "python"
+        }
+
+        fn supports_component(&self, _component: &CodeComponent) -> bool {
+            true
+        }
+
+        fn validate_components(&self, _components: &[CodeComponent]) -> BuilderResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_output_past_max_output_bytes_fails_the_build() {
+        let mut registry = BuilderRegistry::new();
+        registry.register(Box::new(KilobyteBuilder));
+        assert!(registry.build(vec![], &BuildConfig::default()).is_ok());
+
+        let config = BuildConfig { max_output_bytes: 1000, ..BuildConfig::default() };
+        let error = registry.build(vec![], &config).unwrap_err();
+        assert!(matches!(error, BuilderError::OutputTooLarge { bytes: 1024, limit: 1000 }), "{:?}", error);
+        assert!(error.to_string().contains("max_output_bytes"));
+
+        // Cached builds stop at the block that crosses the limit
+        let blocks: Vec<(String, Vec<CodeComponent>)> = (0..5).map(|i| (i.to_string(), vec![])).collect();
+        let config = BuildConfig { max_output_bytes: 3000, ..BuildConfig::default() };
+        let error = registry.build_cached(blocks, &config, &mut GenerationCache::new()).unwrap_err();
+        assert!(matches!(error, BuilderError::OutputTooLarge { bytes: 3072, limit: 3000 }), "{:?}", error);
+
+        // A config serialized before the limit existed gets the default
+        let mut value = serde_json::to_value(BuildConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("max_output_bytes");
+        let config: BuildConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.max_output_bytes, crate::DEFAULT_MAX_OUTPUT_BYTES);
+    }
+
+    #[test]
+    fn test_unregistered_language_is_typed() {
+        let registry = BuilderRegistry::new();
+        let config = BuildConfig { language: "cobol".to_string(), ..BuildConfig::default() };
+
+        let error = registry.build(vec![], &config).unwrap_err();
+        assert!(matches!(error, BuilderError::UnsupportedLanguage(language) if language == "cobol"));
+    }
+
+    #[test]
+    fn test_builders_are_found_by_language_alias() {
+        let mut registry = BuilderRegistry::new();
+        registry.register(Box::new(FailingBuilder));
+
+        assert!(registry.get("py").is_some());
+        assert!(registry.get("Python").is_some());
+        assert!(registry.get("rust").is_none());
+        assert_eq!(registry.languages(), vec!["python"]);
+    }
+
+    #[test]
+    fn test_failed_cached_block_reports_build_failure() {
+        let mut registry = BuilderRegistry::new();
+        registry.register(Box::new(FailingBuilder));
+
+        let result = registry
+            .build_cached(vec![("abc".to_string(), vec![])], &BuildConfig::default(), &mut GenerationCache::new())
+            .unwrap();
+        assert_eq!(result.errors, vec!["Failed to build block abc: no body".to_string()]);
+    }
+}
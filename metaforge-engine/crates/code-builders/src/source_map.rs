@@ -0,0 +1,259 @@
+//! Source maps
+
+use ast_extractor::SourceRange;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Bumped whenever the map layout changes incompatibly
+pub const SOURCE_MAP_VERSION: u32 = 1;
+
+/// Where each line of a build's output came from, recorded when
+/// `BuildConfig::source_map` is set: the block that produced the line and
+/// that block's range in the original source. The map serializes to JSON as
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "file": "billing/pricing.py",         // generated file, when known
+///   "pipeline_id": "<uuid>",              // run that generated it, when known
+///   "config_hash": "<blake3 hex>",        // BuildConfig::config_hash, when built
+///   "mappings": [
+///     {
+///       "generated_line": 12,
+///       "block_id": "<uuid>",
+///       "original": { "start_line": 40, "start_column": 0, "end_line": 52, "end_column": 17 }
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Lines and columns are 0-based. `mappings` holds one entry per attributed
+/// line, in line order; blank lines and lines no block accounts for, such as
+/// those a formatter or an import transform adds, are left out. `original`
+/// is omitted for blocks stored without their source position. Unlike the
+/// generation manifest, which records one range per block, the map answers
+/// "where did this line come from" directly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceMap {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    pub mappings: Vec<LineMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineMapping {
+    pub generated_line: usize,
+    pub block_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<OriginalRange>,
+}
+
+/// Where a block sat in the file it was extracted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OriginalRange {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl From<&SourceRange> for OriginalRange {
+    fn from(range: &SourceRange) -> Self {
+        Self {
+            start_line: range.start_line,
+            start_column: range.start_column,
+            end_line: range.end_line,
+            end_column: range.end_column,
+        }
+    }
+}
+
+impl Default for SourceMap {
+    fn default() -> Self {
+        Self {
+            version: SOURCE_MAP_VERSION,
+            file: None,
+            pipeline_id: None,
+            config_hash: None,
+            mappings: Vec::new(),
+        }
+    }
+}
+
+impl SourceMap {
+    /// Attribute the lines of `generated_code` to the blocks whose
+    /// separately rendered code, `rendered_blocks`, contains them.
+    ///
+    /// Each block's lines are looked for in order after those of the blocks
+    /// before it, comparing trimmed text, so reindented or reflowed output
+    /// still maps where its lines survive unchanged.
+    pub fn locate(
+        generated_code: &str,
+        rendered_blocks: &[(Uuid, String)],
+        originals: &HashMap<Uuid, OriginalRange>,
+    ) -> Self {
+        let generated: Vec<&str> = generated_code.lines().map(str::trim).collect();
+        let mut mappings = Vec::new();
+        let mut cursor = 0;
+        for (block_id, code) in rendered_blocks {
+            for line in code.lines().map(str::trim).filter(|line| !line.is_empty()) {
+                if let Some(offset) = generated[cursor..].iter().position(|candidate| *candidate == line) {
+                    mappings.push(LineMapping {
+                        generated_line: cursor + offset,
+                        block_id: *block_id,
+                        original: originals.get(block_id).copied(),
+                    });
+                    cursor += offset + 1;
+                }
+            }
+        }
+        Self { mappings, ..Self::default() }
+    }
+
+    /// Follow a rewrite of the mapped code from `before` to `after`,
+    /// dropping the mappings of lines the rewrite removed
+    pub fn realign(&mut self, before: &str, after: &str) {
+        if before == after {
+            return;
+        }
+        let after: Vec<&str> = after.lines().map(str::trim).collect();
+        let mut moved = HashMap::new();
+        let mut cursor = 0;
+        for (line_index, line) in before.lines().map(str::trim).enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(offset) = after[cursor..].iter().position(|candidate| *candidate == line) {
+                moved.insert(line_index, cursor + offset);
+                cursor += offset + 1;
+            }
+        }
+        self.mappings.retain_mut(|mapping| match moved.get(&mapping.generated_line) {
+            Some(line) => {
+                mapping.generated_line = *line;
+                true
+            }
+            None => false,
+        });
+    }
+
+    /// The mapping of a 0-based generated line, if any block accounts for it
+    pub fn lookup(&self, generated_line: usize) -> Option<&LineMapping> {
+        self.mappings
+            .binary_search_by_key(&generated_line, |mapping| mapping.generated_line)
+            .ok()
+            .map(|index| &self.mappings[index])
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn with_pipeline_id(mut self, pipeline_id: Uuid) -> Self {
+        self.pipeline_id = Some(pipeline_id);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildConfig, BuildResult, BuilderResult, CodeBuilder};
+    use ast_extractor::traits::SemanticBlock;
+    use ast_extractor::ASTNode;
+    use semantic_mapper::CodeComponent;
+
+    /// Renders each function as a one-line stub after a header line
+    struct StubBuilder;
+
+    impl CodeBuilder for StubBuilder {
+        fn build_from_components(&self, components: Vec<CodeComponent>, _config: &BuildConfig) -> BuilderResult<BuildResult> {
+            let mut code = String::from("# generated\n");
+            for component in components {
+                if let CodeComponent::FunctionSignature(signature) = component {
+                    code.push_str(&format!("def {}(): ...\n", signature.name));
+                }
+            }
+            Ok(BuildResult::new(code))
+        }
+
+        fn language(&self) -> &'static str {
+            "python"
+        }
+
+        fn supports_component(&self, _component: &CodeComponent) -> bool {
+            true
+        }
+
+        fn validate_components(&self, _components: &[CodeComponent]) -> BuilderResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_build_maps_output_lines_to_original_ranges() {
+        let block = |name: &str, start_line: usize| {
+            let range = SourceRange { start_line, start_column: 0, end_line: start_line + 2, end_column: 4, byte_start: 0, byte_end: 0 };
+            SemanticBlock::new("Function".to_string(), name.to_string(), ASTNode::new("function_definition".to_string(), range))
+        };
+        let (load, save) = (block("load", 3), block("save", 9));
+        let config = BuildConfig { strict_mode: false, source_map: true, ..BuildConfig::default() };
+
+        let result = StubBuilder.build_from_blocks(vec![load.clone(), save.clone()], &config).unwrap();
+        let map = result.source_map.unwrap();
+        assert_eq!(map.config_hash, Some(config.config_hash()));
+        // The header comes from every rendering, so it goes to the first block
+        let lines: Vec<(usize, Uuid, usize)> = map.mappings.iter()
+            .map(|mapping| (mapping.generated_line, mapping.block_id, mapping.original.unwrap().start_line))
+            .collect();
+        assert_eq!(lines, vec![(0, load.id, 3), (1, load.id, 3), (2, save.id, 9)]);
+
+        let result = StubBuilder.build_from_blocks(vec![load], &BuildConfig { source_map: false, ..config }).unwrap();
+        assert!(result.source_map.is_none());
+    }
+
+    #[test]
+    fn test_lines_map_to_their_blocks_and_follow_rewrites() {
+        let (load, save) = (Uuid::new_v4(), Uuid::new_v4());
+        let load_range = OriginalRange { start_line: 10, start_column: 0, end_line: 11, end_column: 21 };
+        let originals = HashMap::from([(load, load_range)]);
+        let rendered = vec![
+            (load, "def load():\n    return fetch()\n".to_string()),
+            (save, "def save(value):\n    return value\n".to_string()),
+        ];
+        let generated = "import os\n\ndef load():\n    return fetch()\n\n\ndef save(value):\n    return value\n";
+
+        let map = SourceMap::locate(generated, &rendered, &originals);
+        let lines: Vec<(usize, Uuid)> = map.mappings.iter().map(|mapping| (mapping.generated_line, mapping.block_id)).collect();
+        assert_eq!(lines, vec![(2, load), (3, load), (6, save), (7, save)]);
+        assert_eq!(map.lookup(3).unwrap().original, Some(load_range));
+        assert!(map.lookup(6).unwrap().original.is_none());
+        assert!(map.lookup(0).is_none());
+
+        // `save`'s second line matches the first `return value` after
+        // `def save`, not an earlier one
+        let generated = "def load():\n    return fetch()\n    return value\ndef save(value):\n    return value\n";
+        let map = SourceMap::locate(generated, &rendered, &originals);
+        assert_eq!(map.lookup(4).map(|mapping| mapping.block_id), Some(save));
+        assert!(map.lookup(2).is_none());
+
+        // Dropping the import moves every mapped line up one
+        let mut map = SourceMap::locate("import os\ndef load():\n    return fetch()\n", &rendered[..1], &originals);
+        map.realign("import os\ndef load():\n    return fetch()\n", "def load():\n    return fetch()\n");
+        assert_eq!(map.mappings.iter().map(|mapping| mapping.generated_line).collect::<Vec<_>>(), vec![0, 1]);
+
+        let map = map.with_file("store.py").with_pipeline_id(Uuid::nil());
+        let json = serde_json::to_value(&map).unwrap();
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["mappings"][0]["original"]["start_line"], 10);
+        assert!(json.get("config_hash").is_none());
+        assert_eq!(serde_json::from_value::<SourceMap>(json).unwrap(), map);
+    }
+}
@@ -0,0 +1,163 @@
+//! Strict-mode violations
+
+use ast_extractor::traits::SemanticBlock;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::{BuildResult, BuilderError, BuilderResult};
+
+/// A block strict mode refused, with every field it lacks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncompleteBlock {
+    pub id: Uuid,
+    pub block_type: String,
+    /// The missing fields, e.g. `semantic_name` or a field the builder's
+    /// validation named
+    pub missing_fields: Vec<String>,
+}
+
+impl IncompleteBlock {
+    /// The fields `block` lacks, given the builder's `validation` of its
+    /// components, or `None` when it is complete. Validation failures other
+    /// than `BuilderError::IncompleteAst` are returned as they are.
+    pub fn check(block: &SemanticBlock, validation: BuilderResult<()>) -> BuilderResult<Option<Self>> {
+        let mut missing_fields = Vec::new();
+        if block.semantic_name.trim().is_empty() {
+            missing_fields.push("semantic_name".to_string());
+        }
+        if block.ast_node.node_type.is_empty() {
+            missing_fields.push("ast_node.node_type".to_string());
+        }
+        match validation {
+            Ok(()) => {}
+            Err(BuilderError::IncompleteAst { field, .. }) => {
+                if !missing_fields.contains(&field) {
+                    missing_fields.push(field);
+                }
+            }
+            Err(other) => return Err(other),
+        }
+        Ok((!missing_fields.is_empty()).then(|| Self {
+            id: block.id,
+            block_type: block.block_type.clone(),
+            missing_fields,
+        }))
+    }
+
+    /// The fail-fast error for this block
+    pub fn into_error(self) -> BuilderError {
+        BuilderError::IncompleteAst {
+            block_id: self.id.to_string(),
+            field: self.missing_fields.join(", "),
+        }
+    }
+}
+
+impl BuildResult {
+    /// A build strict mode aborted, with an error per block of `incomplete`
+    /// and the blocks themselves in the metadata
+    pub fn incomplete(incomplete: Vec<IncompleteBlock>, blocks_processed: usize) -> Self {
+        let mut result = BuildResult::new(String::new());
+        result.metadata.blocks_processed = blocks_processed;
+        for block in &incomplete {
+            result.add_error(format!(
+                "Block {} ({}) is missing AST fields: {}",
+                block.id,
+                block.block_type,
+                block.missing_fields.join(", ")
+            ));
+        }
+        result.metadata.incomplete_blocks = incomplete;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildConfig, CodeBuilder};
+    use ast_extractor::{ASTNode, SourceRange};
+    use semantic_mapper::CodeComponent;
+
+    /// Requires a return type on every function, like a typed target language
+    struct TypedBuilder;
+
+    impl CodeBuilder for TypedBuilder {
+        fn build_from_components(&self, components: Vec<CodeComponent>, _config: &BuildConfig) -> BuilderResult<BuildResult> {
+            Ok(BuildResult::new(format!("{} components", components.len())))
+        }
+
+        fn language(&self) -> &'static str {
+            "python"
+        }
+
+        fn supports_component(&self, _component: &CodeComponent) -> bool {
+            true
+        }
+
+        fn validate_components(&self, components: &[CodeComponent]) -> BuilderResult<()> {
+            for component in components {
+                if let CodeComponent::FunctionSignature(signature) = component {
+                    if signature.return_type.is_none() {
+                        return Err(BuilderError::IncompleteAst {
+                            block_id: signature.name.clone(),
+                            field: "return_type".to_string(),
+                        });
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn function_block(name: &str, return_type: Option<&str>) -> SemanticBlock {
+        let range = SourceRange { start_line: 0, start_column: 0, end_line: 1, end_column: 0, byte_start: 0, byte_end: 0 };
+        let mut ast_node = ASTNode::new("function_definition".to_string(), range);
+        if let Some(return_type) = return_type {
+            ast_node.attributes.insert("return_type".to_string(), serde_json::json!(return_type));
+        }
+        SemanticBlock::new("Function".to_string(), name.to_string(), ast_node)
+    }
+
+    #[test]
+    fn test_strict_mode_reports_every_incomplete_block() {
+        let typed = function_block("load", Some("str"));
+        let untyped = function_block("save", None);
+        let unnamed = function_block("", None);
+        let blocks = vec![typed.clone(), untyped.clone(), unnamed.clone()];
+
+        let result = TypedBuilder.build_from_blocks(blocks.clone(), &BuildConfig::default()).unwrap();
+        let incomplete: Vec<(Uuid, &str, Vec<&str>)> = result.metadata.incomplete_blocks.iter()
+            .map(|block| (block.id, block.block_type.as_str(), block.missing_fields.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(incomplete, vec![
+            (untyped.id, "Function", vec!["return_type"]),
+            (unnamed.id, "Function", vec!["semantic_name", "return_type"]),
+        ]);
+        assert_eq!(result.generated_code, "");
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0], format!("Block {} (Function) is missing AST fields: return_type", untyped.id));
+
+        // The list is part of the JSON report
+        let report = serde_json::to_value(&result).unwrap();
+        assert_eq!(report["metadata"]["incomplete_blocks"][1]["missing_fields"], serde_json::json!(["semantic_name", "return_type"]));
+        let round_trip: BuildResult = serde_json::from_value(report).unwrap();
+        assert_eq!(round_trip.metadata.incomplete_blocks, result.metadata.incomplete_blocks);
+
+        // Fail-fast stops at the first incomplete block
+        let fail_fast = BuildConfig { collect_all_errors: false, ..BuildConfig::default() };
+        match TypedBuilder.build_from_blocks(blocks.clone(), &fail_fast) {
+            Err(BuilderError::IncompleteAst { block_id, field }) => {
+                assert_eq!((block_id, field.as_str()), (untyped.id.to_string(), "return_type"));
+            }
+            other => panic!("expected IncompleteAst, got {:?}", other.map(|result| result.errors)),
+        }
+
+        // Lenient builds don't check, and complete blocks build normally
+        let lenient = BuildConfig { strict_mode: false, ..BuildConfig::default() };
+        let lenient = TypedBuilder.build_from_blocks(blocks, &lenient).unwrap();
+        assert!(lenient.metadata.incomplete_blocks.is_empty());
+        let complete = TypedBuilder.build_from_blocks(vec![typed], &BuildConfig::default()).unwrap();
+        assert!(complete.metadata.incomplete_blocks.is_empty());
+        assert!(!complete.has_errors());
+    }
+}
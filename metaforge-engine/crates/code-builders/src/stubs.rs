@@ -0,0 +1,406 @@
+//! Declaration-only output
+
+use ast_extractor::Language;
+use semantic_mapper::{
+    ClassBody, ClassDeclaration, CodeComponent, FunctionSignature, ImportStatement, Parameter,
+    TypeAnnotation, VariableDeclaration,
+};
+
+use crate::{BuilderError, BuilderResult, ExpressionRenderer};
+
+/// Extension of the stub file for `language`: `pyi` for Python, `d.ts` for
+/// JavaScript and TypeScript
+pub fn stub_extension(language: &str) -> Option<&'static str> {
+    match language.parse::<Language>().ok()? {
+        Language::Python => Some("pyi"),
+        Language::JavaScript | Language::TypeScript | Language::Tsx => Some("d.ts"),
+        _ => None,
+    }
+}
+
+/// Render the declarations in `components`, members indented by `indent`
+pub fn render_stubs(components: &[CodeComponent], language: &str, indent: &str) -> BuilderResult<String> {
+    let style = match language.parse::<Language>() {
+        Ok(Language::Python) => StubStyle::Python,
+        Ok(Language::JavaScript | Language::TypeScript | Language::Tsx) => StubStyle::Declaration,
+        _ => return Err(BuilderError::UnsupportedLanguage(language.to_string())),
+    };
+    let renderer = StubRenderer { style, indent };
+
+    let mut items: Vec<(bool, String)> = Vec::new();
+    let mut components = components.iter().peekable();
+    while let Some(component) = components.next() {
+        let item = match component {
+            CodeComponent::Import(import) => (false, renderer.import(import)),
+            CodeComponent::FunctionSignature(signature) => (false, renderer.function(signature, "")),
+            CodeComponent::Variable(variable) => (false, renderer.variable(variable)),
+            CodeComponent::ClassDeclaration(declaration) => {
+                let body = match components.peek() {
+                    Some(CodeComponent::ClassBody(body)) => {
+                        components.next();
+                        Some(body)
+                    }
+                    _ => None,
+                };
+                (true, renderer.class(declaration, body))
+            }
+            _ => continue,
+        };
+        items.push(item);
+    }
+
+    // Classes get a blank line on either side, other declarations sit together
+    let mut rendered = String::new();
+    for (index, (is_class, item)) in items.iter().enumerate() {
+        if index > 0 {
+            rendered.push('\n');
+            if *is_class || items[index - 1].0 {
+                rendered.push('\n');
+            }
+        }
+        rendered.push_str(item);
+    }
+    if !rendered.is_empty() {
+        rendered.push('\n');
+    }
+    Ok(rendered)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum StubStyle {
+    /// `.pyi`: `def f(x: int = ...) -> str: ...`
+    Python,
+    /// `.d.ts`: `export declare function f(x?: number): string;`
+    Declaration,
+}
+
+struct StubRenderer<'a> {
+    style: StubStyle,
+    indent: &'a str,
+}
+
+impl StubRenderer<'_> {
+    fn import(&self, import: &ImportStatement) -> String {
+        let names: Vec<String> = import.imported_names.iter()
+            .map(|name| {
+                let prefix = if name.is_type && self.style == StubStyle::Declaration { "type " } else { "" };
+                match &name.alias {
+                    Some(alias) => format!("{}{} as {}", prefix, name.original, alias),
+                    None => format!("{}{}", prefix, name.original),
+                }
+            })
+            .collect();
+        match (self.style, names.is_empty(), &import.alias) {
+            (StubStyle::Python, true, Some(alias)) => format!("import {} as {}", import.module_path, alias),
+            (StubStyle::Python, true, None) => format!("import {}", import.module_path),
+            (StubStyle::Python, false, _) => format!("from {} import {}", import.module_path, names.join(", ")),
+            (StubStyle::Declaration, true, Some(alias)) => format!("import * as {} from \"{}\";", alias, import.module_path),
+            (StubStyle::Declaration, true, None) => format!("import \"{}\";", import.module_path),
+            (StubStyle::Declaration, false, _) => {
+                format!("import {{ {} }} from \"{}\";", names.join(", "), import.module_path)
+            }
+        }
+    }
+
+    /// A function, or a Python method when `indent` is set
+    fn function(&self, signature: &FunctionSignature, indent: &str) -> String {
+        match self.style {
+            StubStyle::Python => {
+                let decorators: String = signature.decorators.iter()
+                    .map(|decorator| format!("{}@{}\n", indent, decorator.trim_start_matches('@')))
+                    .collect();
+                let return_type = signature.return_type.as_ref()
+                    .map(|return_type| format!(" -> {}", return_type))
+                    .unwrap_or_default();
+                format!(
+                    "{}{}{}def {}({}){}: ...",
+                    decorators,
+                    indent,
+                    if signature.is_async { "async " } else { "" },
+                    signature.name,
+                    ExpressionRenderer::default().render_stub_parameters(&signature.parameters),
+                    return_type,
+                )
+            }
+            StubStyle::Declaration => format!("{}export declare function {}", indent, declaration_signature(signature)),
+        }
+    }
+
+    fn variable(&self, variable: &VariableDeclaration) -> String {
+        let annotation = variable.type_annotation.as_ref().map(|annotation| self.annotation(annotation));
+        match (self.style, annotation) {
+            (StubStyle::Python, Some(annotation)) => format!("{}: {}", variable.name, annotation),
+            (StubStyle::Python, None) => format!("{} = ...", variable.name),
+            (StubStyle::Declaration, annotation) => format!(
+                "export declare {} {}: {};",
+                if variable.is_constant { "const" } else { "let" },
+                variable.name,
+                annotation.unwrap_or_else(|| "any".to_string()),
+            ),
+        }
+    }
+
+    /// The class line and its members' signatures
+    fn class(&self, declaration: &ClassDeclaration, body: Option<&ClassBody>) -> String {
+        let indent = self.indent;
+        let mut members: Vec<String> = Vec::new();
+        if let Some(body) = body {
+            for attribute in &body.attributes {
+                let annotation = attribute.type_annotation.as_ref().map(|annotation| self.annotation(annotation));
+                members.push(match (self.style, annotation) {
+                    (StubStyle::Python, Some(annotation)) => format!("{}{}: {}", indent, attribute.name, annotation),
+                    (StubStyle::Python, None) => format!("{}{} = ...", indent, attribute.name),
+                    (StubStyle::Declaration, annotation) => format!(
+                        "{}{}{}: {};",
+                        indent,
+                        if attribute.is_static { "static " } else { "" },
+                        attribute.name,
+                        annotation.unwrap_or_else(|| "any".to_string()),
+                    ),
+                });
+            }
+            for method in &body.methods {
+                members.push(self.method(method, body, indent));
+            }
+        }
+
+        match self.style {
+            StubStyle::Python => {
+                let decorators: String = declaration.decorators.iter()
+                    .map(|decorator| format!("@{}\n", decorator.trim_start_matches('@')))
+                    .collect();
+                let bases = if declaration.base_classes.is_empty() {
+                    String::new()
+                } else {
+                    format!("({})", declaration.base_classes.join(", "))
+                };
+                if members.is_empty() {
+                    members.push(format!("{}...", indent));
+                }
+                format!("{}class {}{}:\n{}", decorators, declaration.name, bases, members.join("\n"))
+            }
+            StubStyle::Declaration => {
+                let mut heading = format!("export declare {}class {}", if declaration.is_abstract { "abstract " } else { "" }, declaration.name);
+                if !declaration.type_parameters.is_empty() {
+                    heading.push_str(&format!("<{}>", declaration.type_parameters.join(", ")));
+                }
+                if let Some((base, interfaces)) = declaration.base_classes.split_first() {
+                    heading.push_str(&format!(" extends {}", base));
+                    if !interfaces.is_empty() {
+                        heading.push_str(&format!(" implements {}", interfaces.join(", ")));
+                    }
+                }
+                if members.is_empty() {
+                    format!("{} {{}}", heading)
+                } else {
+                    format!("{} {{\n{}\n}}", heading, members.join("\n"))
+                }
+            }
+        }
+    }
+
+    /// A method signature. Class bodies often list methods by name only, so
+    /// a Python method without parameters gets its `self` or `cls` back.
+    fn method(&self, method: &FunctionSignature, body: &ClassBody, indent: &str) -> String {
+        let is_static = body.static_methods.contains(&method.name);
+        let is_class_method = body.class_methods.contains(&method.name);
+        match self.style {
+            StubStyle::Python => {
+                let mut method = method.clone();
+                let (decorator, receiver) = match (is_static, is_class_method) {
+                    (true, _) => (Some("staticmethod"), None),
+                    (false, true) => (Some("classmethod"), Some("cls")),
+                    _ => (None, Some("self")),
+                };
+                if let Some(decorator) = decorator {
+                    if !method.decorators.iter().any(|existing| existing.trim_start_matches('@') == decorator) {
+                        method.decorators.insert(0, decorator.to_string());
+                    }
+                }
+                if let (Some(receiver), true) = (receiver, method.parameters.is_empty()) {
+                    method.parameters.push(Parameter {
+                        name: receiver.to_string(),
+                        type_hint: None,
+                        default_value: None,
+                        is_variadic: false,
+                        is_keyword_only: false,
+                    });
+                }
+                self.function(&method, indent)
+            }
+            StubStyle::Declaration => {
+                format!("{}{}{}", indent, if is_static { "static " } else { "" }, declaration_signature(method))
+            }
+        }
+    }
+
+    fn annotation(&self, annotation: &TypeAnnotation) -> String {
+        let mut rendered = if annotation.is_union && !annotation.union_types.is_empty() {
+            annotation.union_types.join(" | ")
+        } else if annotation.type_parameters.is_empty() {
+            annotation.base_type.clone()
+        } else {
+            let (open, close) = match self.style {
+                StubStyle::Python => ('[', ']'),
+                StubStyle::Declaration => ('<', '>'),
+            };
+            format!("{}{}{}{}", annotation.base_type, open, annotation.type_parameters.join(", "), close)
+        };
+        if annotation.is_optional {
+            rendered.push_str(match self.style {
+                StubStyle::Python => " | None",
+                StubStyle::Declaration => " | undefined",
+            });
+        }
+        rendered
+    }
+}
+
+/// `name(id: number): Row;`, an async function returning a `Promise`
+fn declaration_signature(signature: &FunctionSignature) -> String {
+    let return_type = signature.return_type.clone().unwrap_or_else(|| "any".to_string());
+    let return_type = if signature.is_async && !return_type.starts_with("Promise<") {
+        format!("Promise<{}>", return_type)
+    } else {
+        return_type
+    };
+    format!("{}({}): {};", signature.name, declaration_parameters(&signature.parameters), return_type)
+}
+
+/// `name?: type`, `...rest: any[]`; a parameter with a default is optional
+fn declaration_parameters(parameters: &[Parameter]) -> String {
+    parameters.iter()
+        .map(|parameter| {
+            let type_hint = parameter.type_hint.clone().unwrap_or_else(|| "any".to_string());
+            if parameter.is_variadic {
+                let type_hint = if type_hint.ends_with("[]") { type_hint } else { format!("{}[]", type_hint) };
+                format!("...{}: {}", parameter.name, type_hint)
+            } else if parameter.default_value.is_some() {
+                format!("{}?: {}", parameter.name, type_hint)
+            } else {
+                format!("{}: {}", parameter.name, type_hint)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildConfig, BuildResult, CodeBuilder};
+    use ast_extractor::{ASTExtractor, ExtractionContext, PythonASTExtractor};
+    use tree_sitter::Parser;
+
+    /// Builds nothing itself; stubs come from the trait's default method
+    struct StubOnlyBuilder(&'static str);
+
+    impl CodeBuilder for StubOnlyBuilder {
+        fn build_from_components(&self, _components: Vec<CodeComponent>, _config: &BuildConfig) -> BuilderResult<BuildResult> {
+            Ok(BuildResult::new(String::new()))
+        }
+
+        fn language(&self) -> &'static str {
+            self.0
+        }
+
+        fn supports_component(&self, _component: &CodeComponent) -> bool {
+            true
+        }
+
+        fn validate_components(&self, _components: &[CodeComponent]) -> BuilderResult<()> {
+            Ok(())
+        }
+    }
+
+    fn signature(name: &str, parameters: Vec<Parameter>, return_type: Option<&str>) -> FunctionSignature {
+        FunctionSignature {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            parameters,
+            return_type: return_type.map(str::to_string),
+            is_async: false,
+            decorators: vec![],
+            type_parameters: vec![],
+        }
+    }
+
+    fn parameter(name: &str, type_hint: Option<&str>) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            type_hint: type_hint.map(str::to_string),
+            default_value: None,
+            is_variadic: false,
+            is_keyword_only: false,
+        }
+    }
+
+    #[test]
+    fn test_python_module_of_functions_builds_a_pyi_stub() {
+        let code = "def greet(name: str, punctuation: str = '!') -> str:\n    return 'Hello ' + name + punctuation\n\ndef fetch(url, *, retries=3):\n    return get(url, retries)\n";
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let context = ExtractionContext::new("greet.py".to_string(), "python".to_string(), uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let blocks = PythonASTExtractor::new().extract(tree.root_node(), code, &context).unwrap().semantic_blocks;
+
+        let result = StubOnlyBuilder("python").build_stubs(blocks, &BuildConfig::default()).unwrap();
+        assert_eq!(
+            result.generated_code,
+            "def greet(name: str, punctuation: str = ...) -> str: ...\ndef fetch(url, *, retries=...): ...\n"
+        );
+        assert_eq!(result.metadata.language_specific["stub_extension"], "pyi");
+    }
+
+    #[test]
+    fn test_class_shells_keep_member_signatures() {
+        let components = vec![
+            CodeComponent::ClassDeclaration(ClassDeclaration {
+                name: "Repository".to_string(),
+                qualified_name: "Repository".to_string(),
+                base_classes: vec!["Base".to_string()],
+                decorators: vec![],
+                type_parameters: vec![],
+                is_abstract: false,
+            }),
+            CodeComponent::ClassBody(ClassBody {
+                methods: vec![
+                    signature("find", vec![parameter("self", None), parameter("id", Some("int"))], Some("Row")),
+                    signature("connect", vec![], None),
+                ],
+                attributes: vec![VariableDeclaration {
+                    name: "table".to_string(),
+                    qualified_name: "Repository.table".to_string(),
+                    type_annotation: Some(TypeAnnotation {
+                        base_type: "str".to_string(),
+                        type_parameters: vec![],
+                        is_optional: false,
+                        is_union: false,
+                        union_types: vec![],
+                    }),
+                    initial_value: None,
+                    is_constant: false,
+                    is_static: false,
+                }],
+                properties: vec![],
+                static_methods: vec![],
+                class_methods: vec!["connect".to_string()],
+            }),
+            CodeComponent::FunctionSignature(FunctionSignature {
+                is_async: true,
+                ..signature("main", vec![], Some("None"))
+            }),
+        ];
+
+        assert_eq!(
+            render_stubs(&components, "python", "    ").unwrap(),
+            "class Repository(Base):\n    table: str\n    def find(self, id: int) -> Row: ...\n    @classmethod\n    def connect(cls): ...\n\nasync def main() -> None: ...\n"
+        );
+        assert_eq!(
+            render_stubs(&components, "ts", "  ").unwrap(),
+            "export declare class Repository extends Base {\n  table: str;\n  find(self: any, id: int): Row;\n  connect(): any;\n}\n\nexport declare function main(): Promise<None>;\n"
+        );
+        assert_eq!(stub_extension("typescript"), Some("d.ts"));
+        assert!(matches!(render_stubs(&components, "go", "\t"), Err(BuilderError::UnsupportedLanguage(language)) if language == "go"));
+    }
+}
@@ -1,6 +1,10 @@
-use anyhow::Result;
-use semantic_mapper::CodeComponent;
-use crate::{BuildConfig, BuildResult};
+use ast_extractor::traits::SemanticBlock;
+use semantic_mapper::{CodeComponent, MapperError, SemanticMapper};
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::{BatchFormatter, BlockTransform, DedupeImports, BuildConfig, IncompleteBlock, BuildResult, BuilderError, BuilderResult, OriginalRange, SourceMap, TransformedBuilder};
+use crate::batch::DEFAULT_BATCH_TIMEOUT;
+use crate::stubs::{render_stubs, stub_extension};
 
 /// Core trait for language-specific code builders
 pub trait CodeBuilder: Send + Sync {
@@ -9,7 +13,7 @@ pub trait CodeBuilder: Send + Sync {
         &self,
         components: Vec<CodeComponent>,
         config: &BuildConfig,
-    ) -> Result<BuildResult>;
+    ) -> BuilderResult<BuildResult>;
     
     /// Get the language this builder supports
     fn language(&self) -> &'static str;
@@ -18,13 +22,180 @@ pub trait CodeBuilder: Send + Sync {
     fn supports_component(&self, component: &CodeComponent) -> bool;
     
     /// Validate that all required data is present for generation
-    fn validate_components(&self, components: &[CodeComponent]) -> Result<()>;
+    fn validate_components(&self, components: &[CodeComponent]) -> BuilderResult<()>;
+    
+    /// Map blocks to components for this builder's language and build them.
+    ///
+    /// In strict mode any incomplete block aborts the build before anything
+    /// is rendered; see `IncompleteBlock`. Each block is also rendered on
+    /// its own, unformatted, to find the blocks that came out as
+    /// placeholders; see `BuildResult::record_coverage`. Those renderings
+    /// also locate each block's lines for the `SourceMap`, when one is asked for.
+    fn build_from_blocks(&self, blocks: Vec<SemanticBlock>, config: &BuildConfig) -> BuilderResult<BuildResult> {
+        let mapper = SemanticMapper::new();
+        let mut per_block = Vec::with_capacity(blocks.len());
+        let mut incomplete = Vec::new();
+        for block in &blocks {
+            let components = map_block(&mapper, block, self.language())?;
+            if config.strict_mode {
+                if let Some(block) = IncompleteBlock::check(block, self.validate_components(&components))? {
+                    if !config.collect_all_errors {
+                        return Err(block.into_error());
+                    }
+                    incomplete.push(block);
+                }
+            }
+            per_block.push((block.id, components));
+        }
+        if !incomplete.is_empty() {
+            return Ok(BuildResult::incomplete(incomplete, blocks.len()));
+        }
+
+        let render_config = BuildConfig {
+            format_on_build: false,
+            ..config.clone()
+        };
+        // Stop at the block that takes the output past the cap rather than
+        // rendering the rest and then the whole file
+        let mut rendered_blocks: Vec<(Uuid, String)> = Vec::with_capacity(per_block.len());
+        let mut rendered_bytes = 0;
+        for (block_id, components) in &per_block {
+            let code = self.build_from_components(components.clone(), &render_config)
+                .map(|result| result.generated_code)
+                .unwrap_or_default();
+            rendered_bytes += code.len();
+            config.check_output_size(rendered_bytes)?;
+            rendered_blocks.push((*block_id, code));
+        }
+
+        let components = per_block.into_iter().flat_map(|(_, components)| components).collect();
+        let mut result = self.build_from_components(components, config)?;
+        config.check_output_size(result.generated_code.len())?;
+        result.record_coverage(&rendered_blocks, config);
+        if config.source_map {
+            let originals: HashMap<Uuid, OriginalRange> = blocks.iter()
+                .map(|block| (block.id, OriginalRange::from(&block.ast_node.source_range)))
+                .collect();
+            let mut source_map = SourceMap::locate(&result.generated_code, &rendered_blocks, &originals);
+            source_map.config_hash = Some(config.config_hash());
+            result.source_map = Some(source_map);
+        }
+        Ok(result)
+    }
+    
+    /// Map blocks to components and render declarations only: signatures
+    /// with `...` or `;` in place of bodies and class shells with their
+    /// member signatures, as a `.pyi` stub for Python or a `.d.ts` file for
+    /// JavaScript and TypeScript. The file extension is recorded under
+    /// `language_specific["stub_extension"]`.
+    fn build_stubs(&self, blocks: Vec<SemanticBlock>, config: &BuildConfig) -> BuilderResult<BuildResult> {
+        let mapper = SemanticMapper::new();
+        let mut components = Vec::new();
+        for block in &blocks {
+            components.extend(map_block(&mapper, block, self.language())?);
+        }
+
+        let code = render_stubs(&components, self.language(), &config.indent_style.to_string(1))?;
+        config.check_output_size(code.len())?;
+        let mut result = BuildResult::new(code);
+        result.metadata.blocks_processed = blocks.len();
+        if let Some(extension) = stub_extension(self.language()) {
+            result.metadata.language_specific.insert("stub_extension".to_string(), serde_json::json!(extension));
+        }
+        Ok(result)
+    }
+
+    /// Wrap this builder so `transform` runs on every block before rendering
+    /// and on the rendered code after. Further `with_transform` calls on the
+    /// result run in registration order.
+    fn with_transform(self, transform: Box<dyn BlockTransform>) -> TransformedBuilder<Self>
+    where
+        Self: Sized,
+    {
+        TransformedBuilder::new(self).with_transform(transform)
+    }
+
+    /// Wrap this builder so repeated imports in its output are merged
+    fn with_dedupe_imports(self) -> TransformedBuilder<Self>
+    where
+        Self: Sized,
+    {
+        let language = self.language();
+        self.with_transform(Box::new(DedupeImports::new(language)))
+    }
+
+    /// Build many files, formatting them with one external tool invocation
+    /// when the language's formatter supports it.
+    ///
+    /// Falls back to building each file with `config` as-is (formatting per
+    /// file) when there is no batch formatter or the batched call fails. The
+    /// batch timing is recorded under `language_specific["batch_format"]`.
+    fn build_batch(&self, files: &[(String, Vec<CodeComponent>)], config: &BuildConfig) -> Vec<BuildResult> {
+        let build = |components: &[CodeComponent], config: &BuildConfig| {
+            self.build_from_components(components.to_vec(), config)
+                .unwrap_or_else(|e| {
+                    let mut result = BuildResult::new(String::new());
+                    result.add_error(e.to_string());
+                    result
+                })
+        };
+        let build_each = || files.iter()
+            .map(|(_, components)| build(components, config))
+            .collect::<Vec<_>>();
+        
+        let batch_formatter = match BatchFormatter::for_language(self.language()) {
+            Some(formatter) if config.format_on_build => formatter,
+            _ => return build_each(),
+        };
+        
+        let render_config = BuildConfig {
+            format_on_build: false,
+            ..config.clone()
+        };
+        let mut results: Vec<BuildResult> = files.iter()
+            .map(|(_, components)| build(components, &render_config))
+            .collect();
+        
+        let to_format: Vec<(String, String)> = files.iter()
+            .zip(&results)
+            .filter(|(_, result)| !result.has_errors())
+            .map(|((path, _), result)| (path.clone(), result.generated_code.clone()))
+            .collect();
+        
+        let outcome = match batch_formatter.format_files(&to_format, config, DEFAULT_BATCH_TIMEOUT) {
+            Ok(outcome) => outcome,
+            Err(_) => return build_each(),
+        };
+        
+        let report = serde_json::json!({
+            "files": to_format.len(),
+            "format_time_ms": outcome.format_time_ms,
+            "time_saved_ms": outcome.time_saved_ms,
+        });
+        let mut formatted = outcome.formatted.into_iter();
+        for result in results.iter_mut().filter(|result| !result.has_errors()) {
+            if let Some(code) = formatted.next() {
+                result.metadata.lines_generated = code.lines().count();
+                result.generated_code = code;
+            }
+            result.metadata.language_specific.insert("batch_format".to_string(), report.clone());
+        }
+        results
+    }
+}
+
+fn map_block(mapper: &SemanticMapper, block: &SemanticBlock, language: &str) -> BuilderResult<Vec<CodeComponent>> {
+    mapper.map_block_to_components(block, language)
+        .map_err(|e| match e {
+            MapperError::UnsupportedLanguage(language) => BuilderError::UnsupportedLanguage(language),
+            other => BuilderError::Other(other.into()),
+        })
 }
 
 /// Trait for language-specific code formatting
 pub trait LanguageFormatter: Send + Sync {
     /// Format generated code according to language conventions
-    fn format(&self, code: &str, config: &BuildConfig) -> Result<String>;
+    fn format(&self, code: &str, config: &BuildConfig) -> BuilderResult<String>;
     
     /// Get the language this formatter supports
     fn language(&self) -> &'static str;
@@ -0,0 +1,200 @@
+//! Block transforms
+
+use ast_extractor::traits::SemanticBlock;
+use semantic_mapper::CodeComponent;
+use crate::{BuildConfig, BuildResult, BuilderResult, CodeBuilder};
+
+/// A pre-render and/or post-render hook. Both default to doing nothing.
+pub trait BlockTransform: Send + Sync {
+    /// Runs on each block before it is mapped to components
+    fn transform(&self, _block: &mut SemanticBlock) {}
+
+    /// Runs on the rendered code
+    fn transform_code(&self, _code: &mut String) {}
+}
+
+/// Transforms applied in registration order
+#[derive(Default)]
+pub struct TransformChain {
+    transforms: Vec<Box<dyn BlockTransform>>,
+}
+
+impl TransformChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, transform: Box<dyn BlockTransform>) {
+        self.transforms.push(transform);
+    }
+
+    pub fn len(&self) -> usize {
+        self.transforms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    pub fn apply_block(&self, block: &mut SemanticBlock) {
+        for transform in &self.transforms {
+            transform.transform(block);
+        }
+    }
+
+    pub fn apply_code(&self, code: &mut String) {
+        for transform in &self.transforms {
+            transform.transform_code(code);
+        }
+    }
+}
+
+/// A builder whose blocks and output pass through a `TransformChain`
+pub struct TransformedBuilder<B> {
+    inner: B,
+    transforms: TransformChain,
+}
+
+impl<B: CodeBuilder> TransformedBuilder<B> {
+    pub fn new(inner: B) -> Self {
+        Self { inner, transforms: TransformChain::new() }
+    }
+
+    /// Add a transform after those already registered
+    pub fn with_transform(mut self, transform: Box<dyn BlockTransform>) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
+    pub fn transforms(&self) -> &TransformChain {
+        &self.transforms
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn finish(&self, mut result: BuildResult) -> BuildResult {
+        let before = result.source_map.is_some().then(|| result.generated_code.clone());
+        self.transforms.apply_code(&mut result.generated_code);
+        if let (Some(source_map), Some(before)) = (result.source_map.as_mut(), before) {
+            source_map.realign(&before, &result.generated_code);
+        }
+        result.metadata.lines_generated = result.generated_code.lines().count();
+        result
+    }
+}
+
+impl<B: CodeBuilder> CodeBuilder for TransformedBuilder<B> {
+    fn build_from_components(&self, components: Vec<CodeComponent>, config: &BuildConfig) -> BuilderResult<BuildResult> {
+        let result = self.inner.build_from_components(components, config)?;
+        Ok(self.finish(result))
+    }
+
+    fn build_from_blocks(&self, mut blocks: Vec<SemanticBlock>, config: &BuildConfig) -> BuilderResult<BuildResult> {
+        for block in &mut blocks {
+            self.transforms.apply_block(block);
+        }
+        let result = self.inner.build_from_blocks(blocks, config)?;
+        Ok(self.finish(result))
+    }
+
+    fn language(&self) -> &'static str {
+        self.inner.language()
+    }
+
+    fn supports_component(&self, component: &CodeComponent) -> bool {
+        self.inner.supports_component(component)
+    }
+
+    fn validate_components(&self, components: &[CodeComponent]) -> BuilderResult<()> {
+        self.inner.validate_components(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast_extractor::{ASTNode, SourceRange};
+
+    /// Renders one `def name():` line per function signature
+    struct SignatureBuilder;
+
+    impl CodeBuilder for SignatureBuilder {
+        fn build_from_components(&self, components: Vec<CodeComponent>, _config: &BuildConfig) -> BuilderResult<BuildResult> {
+            let lines: Vec<String> = components.iter()
+                .filter_map(|component| match component {
+                    CodeComponent::FunctionSignature(signature) => Some(format!("def {}():", signature.name)),
+                    _ => None,
+                })
+                .collect();
+            Ok(BuildResult::new(lines.join("\n")))
+        }
+
+        fn language(&self) -> &'static str {
+            "python"
+        }
+
+        fn supports_component(&self, _component: &CodeComponent) -> bool {
+            true
+        }
+
+        fn validate_components(&self, _components: &[CodeComponent]) -> BuilderResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Replaces a secret-looking name before rendering
+    struct Redact;
+
+    impl BlockTransform for Redact {
+        fn transform(&self, block: &mut SemanticBlock) {
+            if block.semantic_name.contains("secret") {
+                block.semantic_name = "redacted".to_string();
+            }
+        }
+    }
+
+    /// Appends a marker comment to the rendered code
+    struct Tag(&'static str);
+
+    impl BlockTransform for Tag {
+        fn transform_code(&self, code: &mut String) {
+            code.push_str(&format!("\n# {}", self.0));
+        }
+    }
+
+    fn function_block(name: &str) -> SemanticBlock {
+        let range = SourceRange { start_line: 0, start_column: 0, end_line: 1, end_column: 0, byte_start: 0, byte_end: 0 };
+        SemanticBlock {
+            id: uuid::Uuid::new_v4(),
+            block_type: "Function".to_string(),
+            semantic_name: name.to_string(),
+            ast_node: ASTNode::new("function_definition".to_string(), range),
+            expression_ast: None,
+            dependencies: vec![],
+            exports: vec![],
+            complexity_score: 1,
+            generation_ready: true,
+        }
+    }
+
+    #[test]
+    fn test_transforms_run_before_and_after_rendering_in_order() {
+        let builder = SignatureBuilder
+            .with_transform(Box::new(Redact))
+            .with_transform(Box::new(Tag("first")))
+            .with_transform(Box::new(Tag("second")));
+        assert_eq!(builder.transforms().len(), 3);
+
+        let result = builder
+            .build_from_blocks(vec![function_block("load_secret"), function_block("save")], &BuildConfig::default())
+            .unwrap();
+        assert_eq!(result.generated_code, "def redacted():\ndef save():\n# first\n# second");
+        assert_eq!(result.metadata.lines_generated, 4);
+
+        // Builders without transforms render blocks untouched
+        let plain = SignatureBuilder.build_from_blocks(vec![function_block("load_secret")], &BuildConfig::default()).unwrap();
+        assert_eq!(plain.generated_code, "def load_secret():");
+    }
+}
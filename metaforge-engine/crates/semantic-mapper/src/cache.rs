@@ -0,0 +1,49 @@
+//! Versioned JSON form of mapped components
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::CodeComponent;
+use crate::error::{MapperError, MapperResult};
+
+/// Version of the components JSON written by this crate. Bump it whenever a
+/// component type changes shape.
+pub const COMPONENTS_FORMAT_VERSION: u32 = 1;
+
+/// The mapped components of one block, as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentsDocument {
+    pub format_version: u32,
+    /// Language the block was mapped as
+    pub language: String,
+    /// `semantic_name` of the mapped block
+    pub block_name: String,
+    pub components: Vec<CodeComponent>,
+}
+
+impl ComponentsDocument {
+    pub fn new(language: &str, block_name: &str, components: Vec<CodeComponent>) -> Self {
+        Self {
+            format_version: COMPONENTS_FORMAT_VERSION,
+            language: language.to_string(),
+            block_name: block_name.to_string(),
+            components,
+        }
+    }
+
+    pub fn to_json(&self) -> MapperResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a document, failing with `MapperError::UnsupportedFormatVersion`
+    /// when it was written with another format version
+    pub fn from_json(json: &str) -> MapperResult<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value.get("format_version")
+            .and_then(|v| v.as_u64())
+            .ok_or(MapperError::MissingField("format_version"))?;
+        if version != u64::from(COMPONENTS_FORMAT_VERSION) {
+            return Err(MapperError::UnsupportedFormatVersion(version));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
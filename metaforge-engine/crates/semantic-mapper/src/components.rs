@@ -1,5 +1,23 @@
 use serde::{Deserialize, Serialize};
-use ast_extractor::ExpressionAST;
+use ast_extractor::{AttachedComment, CommentAttachment, ExpressionAST};
+
+/// Block attribute naming the scope a block is declared in, e.g. the
+/// enclosing class of a method (`Repository`) or a dotted path of nested
+/// scopes (`models.Repository`)
+pub const SCOPE_KEY: &str = "scope";
+
+/// `name` qualified by `scope`, joined with `.`
+pub fn qualify(scope: Option<&str>, name: &str) -> String {
+    match scope {
+        Some(scope) if !scope.is_empty() => format!("{}.{}", scope, name),
+        _ => name.to_string(),
+    }
+}
+
+/// The scope part of a qualified name, `None` at module level
+pub fn scope_of(qualified_name: &str) -> Option<&str> {
+    qualified_name.rsplit_once('.').map(|(scope, _)| scope)
+}
 
 /// Semantic code components that can be generated
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +36,11 @@ pub enum CodeComponent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionSignature {
     pub name: String,
+    /// `name` qualified by its scope, e.g. `Repository.save`. Builders emit
+    /// `name`; relationship analysis keys on this so same-named members of
+    /// different scopes stay apart. Empty means the same as `name`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub qualified_name: String,
     pub parameters: Vec<Parameter>,
     pub return_type: Option<String>,
     pub is_async: bool,
@@ -36,6 +59,9 @@ pub struct FunctionBody {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassDeclaration {
     pub name: String,
+    /// Scope-qualified `name` (see `FunctionSignature::qualified_name`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub qualified_name: String,
     pub base_classes: Vec<String>,
     pub decorators: Vec<String>,
     pub type_parameters: Vec<String>,
@@ -54,6 +80,9 @@ pub struct ClassBody {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VariableDeclaration {
     pub name: String,
+    /// Scope-qualified `name` (see `FunctionSignature::qualified_name`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub qualified_name: String,
     pub type_annotation: Option<TypeAnnotation>,
     pub initial_value: Option<ExpressionAST>,
     pub is_constant: bool,
@@ -131,6 +160,9 @@ pub struct Comment {
     pub content: String,
     pub comment_type: CommentType,
     pub associated_element: Option<String>,
+    /// Where the comment sits relative to `associated_element`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<CommentAttachment>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +173,23 @@ pub enum CommentType {
     TypeComment,
 }
 
+impl Comment {
+    /// A comment the extractor attached to the block named `element`
+    pub fn attached(comment: AttachedComment, element: &str) -> Self {
+        let comment_type = if comment.text.contains('\n') || comment.text.starts_with("/*") {
+            CommentType::MultiLine
+        } else {
+            CommentType::SingleLine
+        };
+        Self {
+            content: comment.text,
+            comment_type,
+            associated_element: Some(element.to_string()),
+            attachment: Some(comment.attachment),
+        }
+    }
+}
+
 impl CodeComponent {
     /// Get the semantic name of this component
     pub fn semantic_name(&self) -> Option<&str> {
@@ -152,6 +201,18 @@ impl CodeComponent {
         }
     }
 
+    /// The scope-qualified name of this component, falling back to its
+    /// semantic name when no scope was recorded
+    pub fn qualified_name(&self) -> Option<&str> {
+        let (name, qualified) = match self {
+            CodeComponent::FunctionSignature(f) => (&f.name, &f.qualified_name),
+            CodeComponent::ClassDeclaration(c) => (&c.name, &c.qualified_name),
+            CodeComponent::Variable(v) => (&v.name, &v.qualified_name),
+            _ => return None,
+        };
+        Some(if qualified.is_empty() { name } else { qualified })
+    }
+
     /// Check if this component is ready for generation
     pub fn is_generation_ready(&self) -> bool {
         match self {
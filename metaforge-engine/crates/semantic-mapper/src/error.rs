@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+/// Errors returned by `SemanticMapper`
+#[derive(Debug, Error)]
+pub enum MapperError {
+    /// No mapper is registered for the language
+    #[error("Unsupported language: {0}")]
+    UnsupportedLanguage(String),
+
+    /// A JSON AST lacks a field mapping requires
+    #[error("AST is missing required field: {0}")]
+    MissingField(&'static str),
+
+    /// A components document was written with another format version
+    #[error("Unsupported components format version: {0}")]
+    UnsupportedFormatVersion(u64),
+
+    /// A components document is not valid JSON or has the wrong shape
+    #[error("Invalid components JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A language mapper or relationship detector failed
+    #[error(transparent)]
+    Mapping(#[from] anyhow::Error),
+}
+
+pub type MapperResult<T> = std::result::Result<T, MapperError>;
@@ -9,63 +9,145 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-use ast_extractor::{ASTNode, ExpressionAST, traits::{SemanticBlock, Dependency, Export}};
+use ast_extractor::{ASTNode, AttachedComment, CommentAttachment, ExpressionAST, Language, ATTACHED_COMMENTS_KEY, traits::{SemanticBlock, Dependency, Export}};
 
+pub mod cache;
 pub mod components;
+pub mod error;
 pub mod mappers;
 pub mod relationships;
 
+pub use cache::{ComponentsDocument, COMPONENTS_FORMAT_VERSION};
 pub use components::{
     CodeComponent, FunctionSignature, FunctionBody, ClassDeclaration, ClassBody,
-    VariableDeclaration, ImportStatement, Statement, Parameter, TypeAnnotation
+    VariableDeclaration, ImportStatement, Statement, Parameter, TypeAnnotation, Comment, CommentType,
+    SCOPE_KEY, qualify,
 };
+pub use error::{MapperError, MapperResult};
 pub use mappers::{ComponentMapper, PythonMapper, RustMapper, TypeScriptMapper};
-pub use relationships::{RelationshipAnalyzer, ComponentRelationship, RelationshipType};
+pub use relationships::{RelationshipAnalyzer, ComponentRelationship, RelationshipType, CollisionResolution};
 
 /// Main semantic mapper that orchestrates component extraction
 pub struct SemanticMapper {
-    mappers: HashMap<String, Box<dyn ComponentMapper>>,
+    mappers: HashMap<Language, Box<dyn ComponentMapper>>,
+    /// Extra language names mapped with another language's mapper, e.g.
+    /// `tsx` => `typescript`. Checked before `Language`'s own aliases.
+    aliases: HashMap<String, String>,
     relationship_analyzer: RelationshipAnalyzer,
 }
 
 impl SemanticMapper {
+    /// Mappers for Python, Rust and TypeScript. JavaScript blocks use the
+    /// TypeScript mapper (see `Language::mapper_language`).
     pub fn new() -> Self {
-        let mut mappers: HashMap<String, Box<dyn ComponentMapper>> = HashMap::new();
-        mappers.insert("python".to_string(), Box::new(PythonMapper::new()));
-        mappers.insert("rust".to_string(), Box::new(RustMapper::new()));
-        mappers.insert("typescript".to_string(), Box::new(TypeScriptMapper::new()));
-        mappers.insert("javascript".to_string(), Box::new(TypeScriptMapper::new())); // JS uses TS mapper
+        let mappers: Vec<Box<dyn ComponentMapper>> = vec![
+            Box::new(PythonMapper::new()),
+            Box::new(RustMapper::new()),
+            Box::new(TypeScriptMapper::new()),
+        ];
+        let mappers = mappers.into_iter().map(|mapper| (mapper.language(), mapper)).collect();
 
         Self {
             mappers,
+            aliases: HashMap::new(),
             relationship_analyzer: RelationshipAnalyzer::new(),
         }
     }
 
-    /// Map a semantic block to code components
-    pub fn map_block_to_components(&self, block: &SemanticBlock, language: &str) -> Result<Vec<CodeComponent>> {
-        let mapper = self.mappers.get(language)
-            .ok_or_else(|| anyhow::anyhow!("Unsupported language: {}", language))?;
+    /// Map `alias` blocks with the mapper registered for `language`, e.g.
+    /// `("tsx", "typescript")`. Replaces an existing alias of the same name.
+    pub fn with_language_alias(mut self, alias: impl Into<String>, language: impl Into<String>) -> Self {
+        self.aliases.insert(alias.into(), language.into());
+        self
+    }
+
+    /// Resolve names declared in several scopes, like two classes' `__init__`,
+    /// with `resolution` when analyzing relationships
+    pub fn with_collision_resolution(mut self, resolution: CollisionResolution) -> Self {
+        self.relationship_analyzer = RelationshipAnalyzer::with_collision_resolution(resolution);
+        self
+    }
+
+    /// The language whose mapper handles `language`, or `None` when the name
+    /// isn't a known language or alias
+    pub fn mapper_language(&self, language: &str) -> Option<Language> {
+        let language = self.aliases.get(language).map(String::as_str).unwrap_or(language);
+        language.parse::<Language>().ok().map(Language::mapper_language)
+    }
+
+    /// Name of the language whose mapper handles `language`; unknown names
+    /// are returned unchanged
+    pub fn resolve_language<'a>(&'a self, language: &'a str) -> &'a str {
+        self.mapper_language(language).map(Language::name).unwrap_or(language)
+    }
+
+    /// Whether blocks in `language` can be mapped, directly or through an alias
+    pub fn supports_language(&self, language: &str) -> bool {
+        self.mapper_language(language).is_some_and(|language| self.mappers.contains_key(&language))
+    }
 
-        mapper.map_semantic_block(block)
+    fn mapper_for(&self, language: &str) -> MapperResult<&dyn ComponentMapper> {
+        self.mapper_language(language)
+            .and_then(|language| self.mappers.get(&language))
+            .map(|mapper| mapper.as_ref())
+            .ok_or_else(|| MapperError::UnsupportedLanguage(language.to_string()))
+    }
+
+    /// Map a semantic block to code components. Languages without a mapper
+    /// fail with `MapperError::UnsupportedLanguage` so callers can skip the
+    /// file instead of aborting.
+    pub fn map_block_to_components(&self, block: &SemanticBlock, language: &str) -> MapperResult<Vec<CodeComponent>> {
+        let mapper = self.mapper_for(language)?;
+
+        Ok(mapper.map_semantic_block(block)?)
+    }
+
+    /// Map a block in `context.language`. With `context.extract_comments`
+    /// the comments the extractor attached to the block come along as
+    /// `Comment` components: leading ones before the block's components,
+    /// inline and trailing ones after.
+    pub fn map_block_with_context(&self, block: &SemanticBlock, context: &MappingContext) -> MapperResult<Vec<CodeComponent>> {
+        let components = self.map_block_to_components(block, &context.language)?;
+        if !context.extract_comments {
+            return Ok(components);
+        }
+        let (leading, rest): (Vec<AttachedComment>, Vec<AttachedComment>) =
+            AttachedComment::from_value(block.ast_node.attributes.get(ATTACHED_COMMENTS_KEY)).into_iter()
+                .partition(|comment| comment.attachment == CommentAttachment::Leading);
+        let to_component = |comment| CodeComponent::Comment(Comment::attached(comment, &block.semantic_name));
+        Ok(leading.into_iter().map(to_component)
+            .chain(components)
+            .chain(rest.into_iter().map(to_component))
+            .collect())
+    }
+
+    /// Map `block` and serialize its components as a `ComponentsDocument`,
+    /// for `load_components` to read back without mapping again
+    pub fn dump_block_components(&self, block: &SemanticBlock, language: &str) -> MapperResult<String> {
+        let components = self.map_block_to_components(block, language)?;
+        ComponentsDocument::new(language, &block.semantic_name, components).to_json()
+    }
+
+    /// The components in a document written by `dump_block_components`
+    pub fn load_components(json: &str) -> MapperResult<Vec<CodeComponent>> {
+        Ok(ComponentsDocument::from_json(json)?.components)
     }
 
     /// Map AST directly to components (backward compatibility)
-    pub fn map_ast_to_components(&self, ast: &serde_json::Value) -> Result<Vec<CodeComponent>> {
+    pub fn map_ast_to_components(&self, ast: &serde_json::Value) -> MapperResult<Vec<CodeComponent>> {
         let mut components = Vec::new();
         
         // Extract block type
         let block_type = ast.get("type")
             .and_then(|t| t.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing block type"))?;
+            .ok_or(MapperError::MissingField("type"))?;
         
         // Determine language from context or default to Python
         let language = ast.get("language")
             .and_then(|l| l.as_str())
             .unwrap_or("python");
         
-        let mapper = self.mappers.get(language)
-            .ok_or_else(|| anyhow::anyhow!("Unsupported language: {}", language))?;
+        let mapper = self.mapper_for(language)?;
 
         // Create a temporary semantic block from JSON
         let temp_block = self.json_to_semantic_block(ast)?;
@@ -76,8 +158,8 @@ impl SemanticMapper {
     }
 
     /// Analyze relationships between components
-    pub fn analyze_relationships(&self, components: &[CodeComponent]) -> Result<Vec<ComponentRelationship>> {
-        self.relationship_analyzer.analyze(components)
+    pub fn analyze_relationships(&self, components: &[CodeComponent]) -> MapperResult<Vec<ComponentRelationship>> {
+        Ok(self.relationship_analyzer.analyze(components)?)
     }
 
     /// Convert JSON AST to semantic block (for backward compatibility)
@@ -140,6 +222,7 @@ pub struct MappingContext {
     pub file_path: Option<String>,
     pub container_id: Uuid,
     pub preserve_formatting: bool,
+    /// Emit the comments attached to each block (see `map_block_with_context`)
     pub extract_comments: bool,
 }
 
@@ -153,6 +236,11 @@ impl MappingContext {
             extract_comments: false,
         }
     }
+
+    pub fn with_comments(mut self, extract: bool) -> Self {
+        self.extract_comments = extract;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +279,63 @@ mod tests {
         assert!(components.iter().any(|c| matches!(c, CodeComponent::FunctionBody(_))));
     }
 
+    #[test]
+    fn test_dumped_components_reload_identically() {
+        let mapper = SemanticMapper::new();
+        let mut block = mapper.json_to_semantic_block(&serde_json::json!({"type": "Function", "name": "calculate_sum"})).unwrap();
+        block.ast_node.attributes.insert("parameters".to_string(), serde_json::json!(["a", "b"]));
+
+        let mapped = mapper.map_block_to_components(&block, "python").unwrap();
+        assert!(!mapped.is_empty());
+        let json = mapper.dump_block_components(&block, "python").unwrap();
+        let document = ComponentsDocument::from_json(&json).unwrap();
+        assert_eq!(document.format_version, COMPONENTS_FORMAT_VERSION);
+        assert_eq!(document.block_name, "calculate_sum");
+
+        let reloaded = SemanticMapper::load_components(&json).unwrap();
+        assert_eq!(serde_json::to_value(&reloaded).unwrap(), serde_json::to_value(&mapped).unwrap());
+        // Dumping the reloaded components writes the same document again
+        assert_eq!(ComponentsDocument::new("python", "calculate_sum", reloaded).to_json().unwrap(), json);
+
+        let newer = json.replacen(&format!("\"format_version\": {}", COMPONENTS_FORMAT_VERSION), "\"format_version\": 99", 1);
+        assert!(matches!(SemanticMapper::load_components(&newer), Err(MapperError::UnsupportedFormatVersion(99))));
+        assert!(matches!(SemanticMapper::load_components("{}"), Err(MapperError::MissingField("format_version"))));
+    }
+
+    #[test]
+    fn test_errors_are_typed() {
+        let mapper = SemanticMapper::new();
+
+        let unsupported = mapper.map_ast_to_components(&serde_json::json!({"type": "function", "language": "cobol"}));
+        assert!(matches!(unsupported, Err(MapperError::UnsupportedLanguage(language)) if language == "cobol"));
+
+        let untyped = mapper.map_ast_to_components(&serde_json::json!({"name": "orphan"}));
+        assert!(matches!(untyped, Err(MapperError::MissingField("type"))));
+    }
+
+    #[test]
+    fn test_language_aliases_resolve_to_registered_mappers() {
+        let mapper = SemanticMapper::new();
+        assert_eq!(mapper.resolve_language("javascript"), "typescript");
+        assert!(mapper.supports_language("javascript"));
+        // Short names resolve through `Language`
+        assert_eq!(mapper.mapper_language("js"), Some(Language::TypeScript));
+        assert!(mapper.supports_language("py"));
+        assert_eq!(mapper.mapper_language("cobol"), None);
+        assert!(!mapper.supports_language("tsx"));
+
+        let block = mapper.json_to_semantic_block(&serde_json::json!({"type": "function", "name": "render"})).unwrap();
+        let unsupported = mapper.map_block_to_components(&block, "tsx");
+        assert!(matches!(unsupported, Err(MapperError::UnsupportedLanguage(language)) if language == "tsx"));
+
+        let mapper = mapper.with_language_alias("tsx", "typescript").with_language_alias("cobol", "fortran");
+        assert!(mapper.supports_language("tsx"));
+        assert!(mapper.map_block_to_components(&block, "tsx").is_ok());
+        // An alias to a language without a mapper is still unsupported, under the name asked for
+        let dangling = mapper.map_block_to_components(&block, "cobol");
+        assert!(matches!(dangling, Err(MapperError::UnsupportedLanguage(language)) if language == "cobol"));
+    }
+
     #[test]
     fn test_analyze_relationships() {
         let mapper = SemanticMapper::new();
@@ -198,6 +343,7 @@ mod tests {
         let components = vec![
             CodeComponent::FunctionSignature(FunctionSignature {
                 name: "process_data".to_string(),
+                qualified_name: "process_data".to_string(),
                 parameters: vec![],
                 return_type: None,
                 is_async: false,
@@ -217,4 +363,155 @@ mod tests {
         // Should detect function call relationship
         assert!(!relationships.is_empty());
     }
-}
\ No newline at end of file
+
+    fn calling(source: &str, calls: &[(&str, usize)]) -> ExpressionAST {
+        serde_json::from_value(serde_json::json!({
+            "expression_type": "call",
+            "operator": null,
+            "operands": [],
+            "literal_value": null,
+            "function_calls": calls.iter().map(|(name, arguments)| serde_json::json!({
+                "name": name,
+                "arguments": vec![serde_json::Value::Null; *arguments],
+                "module_path": null,
+                "is_method": false,
+            })).collect::<Vec<_>>(),
+            "attribute_access": [],
+            "variables": [],
+            "complexity_score": 1,
+            "source_text": source,
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_call_relationships_carry_call_site_metadata() {
+        let statement = |statement_type, expression, nested_statements| Statement {
+            statement_type,
+            expression: Some(expression),
+            nested_statements,
+        };
+        let signature = |name: &str| CodeComponent::FunctionSignature(FunctionSignature {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            parameters: vec![],
+            return_type: None,
+            is_async: false,
+            decorators: vec![],
+            type_parameters: vec![],
+        });
+
+        // def process(rows):
+        //     save(rows)
+        //     if rows.dirty():
+        //         save(rows, True)
+        let components = vec![
+            signature("save"),
+            signature("process"),
+            CodeComponent::FunctionBody(FunctionBody {
+                statements: vec![
+                    statement(components::StatementType::Expression, calling("save(rows)", &[("save", 1)]), vec![]),
+                    statement(
+                        components::StatementType::If,
+                        calling("rows.dirty()", &[("dirty", 0)]),
+                        vec![statement(components::StatementType::Expression, calling("save(rows, True)", &[("save", 2)]), vec![])],
+                    ),
+                ],
+                expressions: vec![],
+                local_variables: vec![],
+                called_functions: vec!["save".to_string(), "dirty".to_string(), "save".to_string()],
+            }),
+        ];
+
+        let relationships = SemanticMapper::new().analyze_relationships(&components).unwrap();
+        let calls: Vec<&ComponentRelationship> = relationships.iter()
+            .filter(|relationship| relationship.relationship_type == RelationshipType::FunctionCall)
+            .collect();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].from_component, "process");
+        assert_eq!(calls[0].to_component, "save");
+        assert_eq!(calls[0].metadata["call_count"], 2);
+        assert_eq!(calls[0].metadata["conditional_call_count"], 1);
+        assert_eq!(calls[0].metadata["is_conditional"], false);
+        assert_eq!(calls[0].metadata["argument_counts"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_attached_comments_follow_extract_comments() {
+        let mapper = SemanticMapper::new();
+        let mut block = mapper.json_to_semantic_block(&serde_json::json!({"type": "function", "name": "add"})).unwrap();
+        block.ast_node.attributes.insert(ATTACHED_COMMENTS_KEY.to_string(), serde_json::json!([
+            {"text": "/* Adds two numbers */", "attachment": "leading", "line": 0},
+            {"text": "// no overflow check", "attachment": "trailing", "line": 3},
+        ]));
+        let context = MappingContext::new("python".to_string(), Uuid::new_v4());
+
+        let plain = mapper.map_block_with_context(&block, &context).unwrap();
+        assert!(!plain.iter().any(|component| matches!(component, CodeComponent::Comment(_))));
+
+        let with_comments = mapper.map_block_with_context(&block, &context.with_comments(true)).unwrap();
+        assert_eq!(with_comments.len(), plain.len() + 2);
+        let CodeComponent::Comment(leading) = &with_comments[0] else { panic!("leading comment first") };
+        assert_eq!(leading.content, "/* Adds two numbers */");
+        assert!(matches!(leading.comment_type, CommentType::MultiLine));
+        assert_eq!(leading.attachment, Some(CommentAttachment::Leading));
+        assert_eq!(leading.associated_element.as_deref(), Some("add"));
+        let CodeComponent::Comment(trailing) = with_comments.last().unwrap() else { panic!("trailing comment last") };
+        assert_eq!(trailing.attachment, Some(CommentAttachment::Trailing));
+    }
+
+    #[test]
+    fn test_same_named_methods_in_different_classes_stay_apart() {
+        let mapper = SemanticMapper::new();
+        let method = |scope: Option<&str>, name: &str, calls: &[(&str, usize)]| {
+            let mut block = mapper.json_to_semantic_block(&serde_json::json!({"type": "function_definition", "name": name})).unwrap();
+            if let Some(scope) = scope {
+                block.ast_node.attributes.insert(SCOPE_KEY.to_string(), serde_json::json!(scope));
+            }
+            block.expression_ast = Some(calling(name, calls));
+            mapper.map_block_to_components(&block, "python").unwrap()
+        };
+
+        // class Repository: def save(self) / def commit(self): self.save()
+        // class Cache: def save(self) / def flush(self): self.save()
+        // def main(): save()
+        let components: Vec<CodeComponent> = [
+            method(Some("Repository"), "save", &[]),
+            method(Some("Repository"), "commit", &[("save", 0)]),
+            method(Some("Cache"), "save", &[]),
+            method(Some("Cache"), "flush", &[("save", 0)]),
+            method(None, "main", &[("save", 0)]),
+        ].concat();
+
+        // Builders keep the short name
+        let CodeComponent::FunctionSignature(save) = &components[0] else { panic!("signature first") };
+        assert_eq!(save.name, "save");
+        assert_eq!(save.qualified_name, "Repository.save");
+        assert_eq!(components[0].qualified_name(), Some("Repository.save"));
+
+        let calls = |mapper: &SemanticMapper| {
+            let mut calls: Vec<(String, String)> = mapper.analyze_relationships(&components).unwrap().into_iter()
+                .filter(|relationship| relationship.relationship_type == RelationshipType::FunctionCall)
+                .map(|relationship| (relationship.from_component, relationship.to_component))
+                .collect();
+            calls.sort();
+            calls
+        };
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+        // Methods call their own class's `save`; `main` has no `save` in scope
+        assert_eq!(calls(&mapper), vec![pair("Cache.flush", "Cache.save"), pair("Repository.commit", "Repository.save")]);
+
+        let all = SemanticMapper::new().with_collision_resolution(CollisionResolution::All);
+        assert_eq!(calls(&all), vec![
+            pair("Cache.flush", "Cache.save"),
+            pair("Cache.flush", "Repository.save"),
+            pair("Repository.commit", "Cache.save"),
+            pair("Repository.commit", "Repository.save"),
+            pair("main", "Cache.save"),
+            pair("main", "Repository.save"),
+        ]);
+
+        let skip = SemanticMapper::new().with_collision_resolution(CollisionResolution::Skip);
+        assert!(calls(&skip).is_empty());
+    }
+}
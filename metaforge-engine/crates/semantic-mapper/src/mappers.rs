@@ -1,5 +1,5 @@
 use anyhow::Result;
-use ast_extractor::traits::SemanticBlock;
+use ast_extractor::{traits::SemanticBlock, Language};
 
 use crate::components::*;
 
@@ -9,7 +9,13 @@ pub trait ComponentMapper: Send + Sync {
     fn map_semantic_block(&self, block: &SemanticBlock) -> Result<Vec<CodeComponent>>;
     
     /// Get the language this mapper supports
-    fn language(&self) -> &'static str;
+    fn language(&self) -> Language;
+}
+
+/// The block's name qualified by the scope recorded under `SCOPE_KEY`
+fn qualified_name(block: &SemanticBlock) -> String {
+    let scope = block.ast_node.attributes.get(SCOPE_KEY).and_then(|scope| scope.as_str());
+    qualify(scope, &block.semantic_name)
 }
 
 /// Python-specific component mapper
@@ -23,14 +29,19 @@ impl PythonMapper {
     fn map_function(&self, block: &SemanticBlock) -> Result<Vec<CodeComponent>> {
         let mut components = Vec::new();
 
-        // Extract function signature
-        let params = block.ast_node.attributes.get("parameters")
-            .and_then(|p| p.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|p| p.as_str())
-                    .map(|name| Parameter::new(name.to_string()))
-                    .collect()
+        // Extract function signature; `parameter_details` carries types and
+        // defaults, older blocks only have the `parameters` names
+        let params = block.ast_node.attributes.get("parameter_details")
+            .and_then(|details| serde_json::from_value::<Vec<Parameter>>(details.clone()).ok())
+            .or_else(|| {
+                block.ast_node.attributes.get("parameters")
+                    .and_then(|p| p.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|p| p.as_str())
+                            .map(|name| Parameter::new(name.to_string()))
+                            .collect()
+                    })
             })
             .unwrap_or_default();
 
@@ -54,6 +65,7 @@ impl PythonMapper {
 
         let signature = FunctionSignature {
             name: block.semantic_name.clone(),
+            qualified_name: qualified_name(block),
             parameters: params,
             return_type,
             is_async,
@@ -103,8 +115,10 @@ impl PythonMapper {
             })
             .unwrap_or_default();
 
+        let class_name = qualified_name(block);
         let declaration = ClassDeclaration {
             name: block.semantic_name.clone(),
+            qualified_name: class_name.clone(),
             base_classes,
             decorators,
             type_parameters: vec![],
@@ -121,6 +135,7 @@ impl PythonMapper {
                     .filter_map(|m| m.as_str())
                     .map(|name| FunctionSignature {
                         name: name.to_string(),
+                        qualified_name: qualify(Some(&class_name), name),
                         parameters: vec![],
                         return_type: None,
                         is_async: false,
@@ -138,6 +153,7 @@ impl PythonMapper {
                     .filter_map(|a| a.as_str())
                     .map(|name| VariableDeclaration {
                         name: name.to_string(),
+                        qualified_name: qualify(Some(&class_name), name),
                         type_annotation: None,
                         initial_value: None,
                         is_constant: false,
@@ -173,6 +189,7 @@ impl PythonMapper {
 
         let variable = VariableDeclaration {
             name: block.semantic_name.clone(),
+            qualified_name: qualified_name(block),
             type_annotation,
             initial_value: block.expression_ast.clone(),
             is_constant: false,
@@ -200,8 +217,8 @@ impl ComponentMapper for PythonMapper {
         }
     }
 
-    fn language(&self) -> &'static str {
-        "python"
+    fn language(&self) -> Language {
+        Language::Python
     }
 }
 
@@ -222,6 +239,7 @@ impl ComponentMapper for RustMapper {
                 // Map Rust function
                 let signature = FunctionSignature {
                     name: block.semantic_name.clone(),
+                    qualified_name: qualified_name(block),
                     parameters: vec![],
                     return_type: None,
                     is_async: false,
@@ -234,6 +252,7 @@ impl ComponentMapper for RustMapper {
                 // Map Rust struct
                 let declaration = ClassDeclaration {
                     name: block.semantic_name.clone(),
+                    qualified_name: qualified_name(block),
                     base_classes: vec![],
                     decorators: vec![],
                     type_parameters: vec![],
@@ -245,8 +264,8 @@ impl ComponentMapper for RustMapper {
         }
     }
 
-    fn language(&self) -> &'static str {
-        "rust"
+    fn language(&self) -> Language {
+        Language::Rust
     }
 }
 
@@ -267,6 +286,7 @@ impl ComponentMapper for TypeScriptMapper {
                 // Map TypeScript function
                 let signature = FunctionSignature {
                     name: block.semantic_name.clone(),
+                    qualified_name: qualified_name(block),
                     parameters: vec![],
                     return_type: None,
                     is_async: false,
@@ -279,6 +299,7 @@ impl ComponentMapper for TypeScriptMapper {
                 // Map TypeScript class
                 let declaration = ClassDeclaration {
                     name: block.semantic_name.clone(),
+                    qualified_name: qualified_name(block),
                     base_classes: vec![],
                     decorators: vec![],
                     type_parameters: vec![],
@@ -290,7 +311,7 @@ impl ComponentMapper for TypeScriptMapper {
         }
     }
 
-    fn language(&self) -> &'static str {
-        "typescript"
+    fn language(&self) -> Language {
+        Language::TypeScript
     }
 }
@@ -2,7 +2,9 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-use crate::components::CodeComponent;
+use ast_extractor::ExpressionAST;
+
+use crate::components::{scope_of, CodeComponent, FunctionBody, Statement, StatementType};
 
 /// Analyzes relationships between code components
 pub struct RelationshipAnalyzer {
@@ -11,11 +13,16 @@ pub struct RelationshipAnalyzer {
 
 impl RelationshipAnalyzer {
     pub fn new() -> Self {
+        Self::with_collision_resolution(CollisionResolution::default())
+    }
+
+    /// Resolve names declared in several scopes with `resolution`
+    pub fn with_collision_resolution(resolution: CollisionResolution) -> Self {
         Self {
             analyzers: vec![
-                Box::new(FunctionCallDetector),
-                Box::new(InheritanceDetector),
-                Box::new(CompositionDetector),
+                Box::new(FunctionCallDetector { resolution }),
+                Box::new(InheritanceDetector { resolution }),
+                Box::new(CompositionDetector { resolution }),
                 Box::new(DependencyDetector),
             ],
         }
@@ -71,39 +78,123 @@ pub enum RelationshipType {
     Override,
 }
 
+/// How a referenced name declared in several scopes, like the `save`
+/// methods of two classes, is resolved to the components it links to.
+/// Relationships name components by their qualified names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionResolution {
+    /// The declaration in the referencing component's scope, else in the
+    /// nearest enclosing scope up to module level; with none there the
+    /// reference is left unlinked
+    #[default]
+    NearestScope,
+    /// Link every declaration, marking the relationships `ambiguous`
+    All,
+    /// Leave references to names declared more than once unlinked
+    Skip,
+}
+
+/// Qualified names of the components declared under each short name, so a
+/// reference resolves the same way whatever order components come in
+struct Declarations<'a> {
+    by_name: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> Declarations<'a> {
+    fn collect(components: &'a [CodeComponent], declares: impl Fn(&CodeComponent) -> bool) -> Self {
+        let mut by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+        for component in components.iter().filter(|component| declares(component)) {
+            if let (Some(name), Some(qualified)) = (component.semantic_name(), component.qualified_name()) {
+                by_name.entry(name).or_default().push(qualified);
+            }
+        }
+        for qualified_names in by_name.values_mut() {
+            qualified_names.sort_unstable();
+            qualified_names.dedup();
+        }
+        Self { by_name }
+    }
+
+    /// The qualified names `name`, referenced from within `scope`, links to
+    fn resolve(&self, name: &str, scope: Option<&str>, resolution: CollisionResolution) -> Vec<&'a str> {
+        let Some(candidates) = self.by_name.get(name) else {
+            return Vec::new();
+        };
+        if candidates.len() == 1 {
+            return candidates.clone();
+        }
+        match resolution {
+            CollisionResolution::All => candidates.clone(),
+            CollisionResolution::Skip => Vec::new(),
+            CollisionResolution::NearestScope => {
+                let mut scope = scope;
+                loop {
+                    if let Some(found) = candidates.iter().find(|candidate| scope_of(candidate) == scope) {
+                        return vec![*found];
+                    }
+                    match scope {
+                        Some(current) => scope = scope_of(current),
+                        None => return Vec::new(),
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_ambiguous(&self, name: &str) -> bool {
+        self.by_name.get(name).is_some_and(|candidates| candidates.len() > 1)
+    }
+}
+
 /// Trait for detecting specific types of relationships
 trait RelationshipDetector: Send + Sync {
     fn detect(&self, components: &[CodeComponent]) -> Result<Vec<ComponentRelationship>>;
 }
 
 /// Detects function call relationships
-struct FunctionCallDetector;
+struct FunctionCallDetector {
+    resolution: CollisionResolution,
+}
 
 impl RelationshipDetector for FunctionCallDetector {
     fn detect(&self, components: &[CodeComponent]) -> Result<Vec<ComponentRelationship>> {
         let mut relationships = Vec::new();
-        
-        // Build a map of all function names
-        let function_names: HashSet<String> = components.iter()
-            .filter_map(|c| match c {
-                CodeComponent::FunctionSignature(sig) => Some(sig.name.clone()),
-                _ => None,
-            })
-            .collect();
-
-        // Look for function calls in function bodies
+
+        let functions = Declarations::collect(components, |c| matches!(c, CodeComponent::FunctionSignature(_)));
+
+        // Look for function calls in function bodies, attributed to the
+        // signature the mappers emit just before each body
+        let mut current_function = None;
         for component in components {
-            if let CodeComponent::FunctionBody(body) = component {
-                for called_func in &body.called_functions {
-                    if function_names.contains(called_func) {
-                        relationships.push(ComponentRelationship {
-                            from_component: "current_function".to_string(), // TODO: Track current function
-                            to_component: called_func.clone(),
-                            relationship_type: RelationshipType::FunctionCall,
-                            metadata: HashMap::new(),
-                        });
+            match component {
+                CodeComponent::FunctionSignature(_) => current_function = component.qualified_name(),
+                CodeComponent::FunctionBody(body) => {
+                    let sites = call_sites(body);
+                    // Calls from a method resolve in its class first
+                    let scope = current_function.and_then(scope_of);
+                    let mut seen = HashSet::new();
+                    for called_func in &body.called_functions {
+                        if !seen.insert(called_func) {
+                            continue;
+                        }
+                        let ambiguous = functions.is_ambiguous(called_func);
+                        for callee in functions.resolve(called_func, scope, self.resolution) {
+                            let relationship = ComponentRelationship::new(
+                                current_function.unwrap_or("current_function").to_string(),
+                                callee.to_string(),
+                                RelationshipType::FunctionCall,
+                            );
+                            let relationship = with_call_metadata(relationship, called_func, body, &sites);
+                            relationships.push(if ambiguous && self.resolution == CollisionResolution::All {
+                                relationship.with_metadata("ambiguous".to_string(), serde_json::json!(true))
+                            } else {
+                                relationship
+                            });
+                        }
                     }
                 }
+                _ => {}
             }
         }
 
@@ -111,29 +202,93 @@ impl RelationshipDetector for FunctionCallDetector {
     }
 }
 
+/// One call in a function body
+struct CallSite<'a> {
+    name: &'a str,
+    argument_count: usize,
+    /// Parameters passed by keyword
+    keywords: Vec<&'a str>,
+    /// Inside an `if` branch or a loop body, so it may not run
+    conditional: bool,
+}
+
+/// Every call in `body`. Statements are walked when the mapper filled them
+/// in; otherwise the body's expressions count as unconditional.
+fn call_sites(body: &FunctionBody) -> Vec<CallSite<'_>> {
+    let mut sites = Vec::new();
+    if body.statements.is_empty() {
+        for expression in &body.expressions {
+            collect_calls(expression, false, &mut sites);
+        }
+    } else {
+        collect_statement_calls(&body.statements, false, &mut sites);
+    }
+    sites
+}
+
+fn collect_statement_calls<'a>(statements: &'a [Statement], conditional: bool, sites: &mut Vec<CallSite<'a>>) {
+    for statement in statements {
+        // A condition is evaluated every time; only the branch may be skipped
+        if let Some(expression) = &statement.expression {
+            collect_calls(expression, conditional, sites);
+        }
+        let branches = matches!(statement.statement_type, StatementType::If | StatementType::While | StatementType::For);
+        collect_statement_calls(&statement.nested_statements, conditional || branches, sites);
+    }
+}
+
+fn collect_calls<'a>(expression: &'a ExpressionAST, conditional: bool, sites: &mut Vec<CallSite<'a>>) {
+    sites.extend(expression.function_calls.iter().map(|call| CallSite {
+        name: &call.name,
+        argument_count: call.arguments.len(),
+        keywords: call.keyword_names().collect(),
+        conditional,
+    }));
+}
+
+/// `call_count`, `conditional_call_count`, `is_conditional` (every call
+/// may be skipped), `argument_counts` and `keyword_arguments` per call of
+/// `name`, in body order
+fn with_call_metadata(relationship: ComponentRelationship, name: &str, body: &FunctionBody, sites: &[CallSite]) -> ComponentRelationship {
+    let calls: Vec<&CallSite> = sites.iter().filter(|site| site.name == name).collect();
+    if calls.is_empty() {
+        // Only the name list is known
+        let count = body.called_functions.iter().filter(|called| called.as_str() == name).count();
+        return relationship
+            .with_metadata("call_count".to_string(), serde_json::json!(count))
+            .with_metadata("is_conditional".to_string(), serde_json::json!(false));
+    }
+    let conditional = calls.iter().filter(|site| site.conditional).count();
+    let argument_counts: Vec<usize> = calls.iter().map(|site| site.argument_count).collect();
+    let keyword_arguments: Vec<&[&str]> = calls.iter().map(|site| site.keywords.as_slice()).collect();
+    relationship
+        .with_metadata("call_count".to_string(), serde_json::json!(calls.len()))
+        .with_metadata("conditional_call_count".to_string(), serde_json::json!(conditional))
+        .with_metadata("is_conditional".to_string(), serde_json::json!(conditional == calls.len()))
+        .with_metadata("argument_counts".to_string(), serde_json::json!(argument_counts))
+        .with_metadata("keyword_arguments".to_string(), serde_json::json!(keyword_arguments))
+}
+
 /// Detects inheritance relationships
-struct InheritanceDetector;
+struct InheritanceDetector {
+    resolution: CollisionResolution,
+}
 
 impl RelationshipDetector for InheritanceDetector {
     fn detect(&self, components: &[CodeComponent]) -> Result<Vec<ComponentRelationship>> {
         let mut relationships = Vec::new();
 
-        // Build a map of all class names
-        let class_names: HashSet<String> = components.iter()
-            .filter_map(|c| match c {
-                CodeComponent::ClassDeclaration(decl) => Some(decl.name.clone()),
-                _ => None,
-            })
-            .collect();
+        let classes = Declarations::collect(components, |c| matches!(c, CodeComponent::ClassDeclaration(_)));
 
         // Look for inheritance relationships
         for component in components {
             if let CodeComponent::ClassDeclaration(decl) = component {
+                let class_name = component.qualified_name().unwrap_or(&decl.name);
                 for base_class in &decl.base_classes {
-                    if class_names.contains(base_class) {
+                    for base in classes.resolve(base_class, scope_of(class_name), self.resolution) {
                         relationships.push(ComponentRelationship {
-                            from_component: decl.name.clone(),
-                            to_component: base_class.clone(),
+                            from_component: class_name.to_string(),
+                            to_component: base.to_string(),
                             relationship_type: RelationshipType::Inheritance,
                             metadata: HashMap::new(),
                         });
@@ -147,35 +302,38 @@ impl RelationshipDetector for InheritanceDetector {
 }
 
 /// Detects composition relationships
-struct CompositionDetector;
+struct CompositionDetector {
+    resolution: CollisionResolution,
+}
 
 impl RelationshipDetector for CompositionDetector {
     fn detect(&self, components: &[CodeComponent]) -> Result<Vec<ComponentRelationship>> {
         let mut relationships = Vec::new();
 
-        // Build a map of all class names
-        let class_names: HashSet<String> = components.iter()
-            .filter_map(|c| match c {
-                CodeComponent::ClassDeclaration(decl) => Some(decl.name.clone()),
-                _ => None,
-            })
-            .collect();
+        let classes = Declarations::collect(components, |c| matches!(c, CodeComponent::ClassDeclaration(_)));
 
-        // Look for class attributes that reference other classes
+        // Look for class attributes that reference other classes, attributed
+        // to the declaration the mappers emit just before each body
+        let mut current_class = None;
         for component in components {
-            if let CodeComponent::ClassBody(body) = component {
-                for attribute in &body.attributes {
-                    if let Some(type_ann) = &attribute.type_annotation {
-                        if class_names.contains(&type_ann.base_type) {
+            match component {
+                CodeComponent::ClassDeclaration(_) => current_class = component.qualified_name(),
+                CodeComponent::ClassBody(body) => {
+                    for attribute in &body.attributes {
+                        let Some(type_ann) = &attribute.type_annotation else {
+                            continue;
+                        };
+                        for class in classes.resolve(&type_ann.base_type, current_class.and_then(scope_of), self.resolution) {
                             relationships.push(ComponentRelationship {
-                                from_component: "current_class".to_string(), // TODO: Track current class
-                                to_component: type_ann.base_type.clone(),
+                                from_component: current_class.unwrap_or("current_class").to_string(),
+                                to_component: class.to_string(),
                                 relationship_type: RelationshipType::Composition,
                                 metadata: HashMap::new(),
                             });
                         }
                     }
                 }
+                _ => {}
             }
         }
 
@@ -228,12 +386,11 @@ impl ComponentRelationship {
         self
     }
 
-    /// Check if this is a local relationship (within the same module)
+    /// Check if this is a local relationship (within the same module).
+    /// Only imports leave it: the detectors link other relationships to
+    /// components they were given, whose qualified names may contain `.`.
     pub fn is_local(&self) -> bool {
-        match self.relationship_type {
-            RelationshipType::Import => false,
-            _ => !self.to_component.contains('.') && !self.to_component.contains('/')
-        }
+        self.relationship_type != RelationshipType::Import
     }
 
     /// Get the strength of this relationship (for dependency analysis)
@@ -0,0 +1,57 @@
+// Package cache is a fixed benchmark fixture.
+package cache
+
+import (
+	"sync"
+	"time"
+)
+
+// Entry is a cached value with its expiry.
+type Entry struct {
+	Value     string
+	ExpiresAt time.Time
+}
+
+// Cache is a TTL cache safe for concurrent use.
+type Cache struct {
+	mu      sync.Mutex
+	entries map[string]Entry
+	ttl     time.Duration
+}
+
+// New creates a cache whose entries live for ttl.
+func New(ttl time.Duration) *Cache {
+	return &Cache{entries: make(map[string]Entry), ttl: ttl}
+}
+
+// Get returns the value for key if it has not expired.
+func (c *Cache) Get(key string) (string, bool) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	entry, ok := c.entries[key]
+	if !ok || time.Now().After(entry.ExpiresAt) {
+		return "", false
+	}
+	return entry.Value, true
+}
+
+// Set stores value under key.
+func (c *Cache) Set(key, value string) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.entries[key] = Entry{Value: value, ExpiresAt: time.Now().Add(c.ttl)}
+}
+
+// Purge drops expired entries and reports how many were removed.
+func (c *Cache) Purge() int {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	removed := 0
+	for key, entry := range c.entries {
+		if time.Now().After(entry.ExpiresAt) {
+			delete(c.entries, key)
+			removed++
+		}
+	}
+	return removed
+}
@@ -0,0 +1,54 @@
+"""Inventory tracking used as a fixed benchmark fixture."""
+import json
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional
+
+MAX_ITEMS = 1000
+
+
+@dataclass
+class Item:
+    sku: str
+    name: str
+    quantity: int = 0
+    tags: List[str] = field(default_factory=list)
+
+    @property
+    def in_stock(self) -> bool:
+        return self.quantity > 0
+
+
+class Inventory:
+    def __init__(self):
+        self.items: Dict[str, Item] = {}
+
+    def add(self, item: Item) -> None:
+        if len(self.items) >= MAX_ITEMS:
+            raise ValueError("inventory is full")
+        self.items[item.sku] = item
+
+    def remove(self, sku: str) -> Optional[Item]:
+        return self.items.pop(sku, None)
+
+    def restock(self, sku: str, amount: int) -> int:
+        item = self.items[sku]
+        item.quantity += amount
+        return item.quantity
+
+    def tagged(self, tag: str) -> List[Item]:
+        return [item for item in self.items.values() if tag in item.tags]
+
+    def to_json(self) -> str:
+        return json.dumps({sku: item.quantity for sku, item in self.items.items()})
+
+
+def low_stock(inventory: Inventory, threshold: int = 5) -> List[str]:
+    result = []
+    for sku, item in inventory.items.items():
+        if item.quantity < threshold:
+            result.append(sku)
+    return sorted(result)
+
+
+def total_quantity(inventory: Inventory) -> int:
+    return sum(item.quantity for item in inventory.items.values())
@@ -0,0 +1,62 @@
+//! Double-entry ledger used as a fixed benchmark fixture.
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub account: String,
+    pub amount: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct Ledger {
+    balances: HashMap<String, i64>,
+    history: Vec<Vec<Entry>>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn post(&mut self, entries: Vec<Entry>) -> Result<(), String> {
+        let total: i64 = entries.iter().map(|entry| entry.amount).sum();
+        if total != 0 {
+            return Err(format!("unbalanced transaction: {}", total));
+        }
+        for entry in &entries {
+            *self.balances.entry(entry.account.clone()).or_insert(0) += entry.amount;
+        }
+        self.history.push(entries);
+        Ok(())
+    }
+
+    pub fn balance(&self, account: &str) -> i64 {
+        self.balances.get(account).copied().unwrap_or(0)
+    }
+
+    pub fn accounts<'a>(&'a self) -> impl Iterator<Item = &'a String> {
+        self.balances.keys()
+    }
+}
+
+impl fmt::Display for Ledger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<_> = self.balances.keys().collect();
+        names.sort();
+        for name in names {
+            writeln!(f, "{}: {}", name, self.balances[name])?;
+        }
+        Ok(())
+    }
+}
+
+pub fn largest<T: PartialOrd + Copy>(values: &[T]) -> Option<T> {
+    let mut best = *values.first()?;
+    for &value in values {
+        if value > best {
+            best = value;
+        }
+    }
+    Some(best)
+}
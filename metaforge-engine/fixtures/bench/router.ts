@@ -0,0 +1,40 @@
+// Minimal request router used as a fixed benchmark fixture.
+import { EventEmitter } from "events";
+
+export interface Request {
+  method: string;
+  path: string;
+  body?: unknown;
+}
+
+export type Handler = (request: Request) => Promise<string>;
+
+export class Router extends EventEmitter {
+  private routes: Map<string, Handler> = new Map();
+
+  register(method: string, path: string, handler: Handler): void {
+    this.routes.set(`${method} ${path}`, handler);
+  }
+
+  async dispatch(request: Request): Promise<string> {
+    const handler = this.routes.get(`${request.method} ${request.path}`);
+    if (!handler) {
+      this.emit("miss", request);
+      return "404";
+    }
+    const response = await handler(request);
+    this.emit("hit", request);
+    return response;
+  }
+
+  size(): number {
+    return this.routes.size;
+  }
+}
+
+export function normalizePath(path: string): string {
+  const trimmed = path.replace(/\/+$/, "");
+  return trimmed.length === 0 ? "/" : trimmed.toLowerCase();
+}
+
+export const DEFAULT_TIMEOUT_MS = 5000;
@@ -0,0 +1,16 @@
+-- Incremental migrations (`migrate --since <commit>`) store only the files
+-- that changed since an earlier migration and reference the rest
+ALTER TABLE migrations
+ADD COLUMN IF NOT EXISTS base_migration_id UUID REFERENCES migrations(id) ON DELETE SET NULL;
+
+CREATE TABLE IF NOT EXISTS migration_containers (
+    migration_id UUID NOT NULL REFERENCES migrations(id) ON DELETE CASCADE,
+    container_id UUID NOT NULL REFERENCES containers(id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK (status IN ('unchanged', 'deleted')),
+    PRIMARY KEY (migration_id, container_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_migration_containers_container_id ON migration_containers(container_id);
+
+COMMENT ON COLUMN migrations.base_migration_id IS 'Migration an incremental migration was diffed against';
+COMMENT ON TABLE migration_containers IS 'Containers of an earlier migration that an incremental migration carries over unchanged or records as deleted';
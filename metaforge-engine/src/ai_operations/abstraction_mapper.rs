@@ -1,6 +1,7 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use crate::ai_operations::{AbstractBlockSpec, PatternLibrary, LanguageGenerator, code_generators::CodeGenerator};
+use crate::generator::output_naming::known_file_extension;
 
 pub struct AbstractionMapper {
     pattern_library: PatternLibrary,
@@ -30,8 +31,8 @@ impl AbstractionMapper {
             .get(target_language)
             .ok_or_else(|| anyhow::anyhow!("Unsupported language: {}", target_language))?;
         
-        // 3. Generate concrete implementation
-        let code = generator.generate_from_pattern(&pattern, abstraction)?;
+        // 3. Generate concrete implementation, with names in the target language's casing
+        let code = generator.generate_from_pattern(&pattern, &abstraction.with_native_names(target_language))?;
         
         // 4. Apply constraints and optimizations
         let optimized = self.apply_constraints(code, &abstraction.invariants)?;
@@ -167,15 +168,8 @@ impl AbstractionMapper {
     }
 
     fn get_file_extension(&self, language: &str) -> Result<&str> {
-        match language {
-            "python" => Ok("py"),
-            "typescript" => Ok("ts"),
-            "javascript" => Ok("js"),
-            "rust" => Ok("rs"),
-            "java" => Ok("java"),
-            "go" => Ok("go"),
-            _ => Err(anyhow::anyhow!("Unknown language: {}", language)),
-        }
+        known_file_extension(language)
+            .ok_or_else(|| anyhow::anyhow!("Unknown language: {}", language))
     }
 
     fn create_module_index(
@@ -4,6 +4,8 @@ use anyhow::Result;
 use std::collections::HashMap;
 use crate::database::Database;
 use crate::ai_operations::{code_generators::CodeGenerator, PatternLibrary};
+use crate::ai_operations::spec_validation::{field_path, is_identifier, SpecValidation};
+use crate::generator::identifier_casing::{Casing, IdentifierKind, NamingConvention};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockSynthesisRequest {
@@ -21,6 +23,180 @@ pub struct AbstractBlockSpec {
     pub properties: BlockProperties,
     pub behaviors: Vec<BehaviorSpec>,
     pub invariants: Vec<Invariant>,
+    /// Free-form switches for code generation, e.g. `"emit_docs": true`
+    #[serde(default)]
+    pub generation_hints: HashMap<String, serde_json::Value>,
+}
+
+impl AbstractBlockSpec {
+    /// Whether generated code should carry a doc comment built from the spec
+    pub fn emit_docs(&self) -> bool {
+        self.generation_hints.get("emit_docs")
+            .and_then(|hint| hint.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Casing to rewrite names to when generating `language`, from
+    /// `generation_hints["naming_convention"]`: absent, `true` or `"native"`
+    /// follow the target language, a language name borrows that language's
+    /// convention, an object such as `{"constants": "pascal"}` overrides
+    /// single kinds, and `false` or `"preserve"` keep names as written
+    pub fn naming_convention(&self, language: &str) -> Option<NamingConvention> {
+        match self.generation_hints.get("naming_convention") {
+            None | Some(serde_json::Value::Bool(true)) => NamingConvention::for_language(language),
+            Some(serde_json::Value::String(name)) => match name.as_str() {
+                "native" => NamingConvention::for_language(language),
+                "preserve" => None,
+                other => NamingConvention::for_language(other),
+            },
+            Some(serde_json::Value::Object(overrides)) => {
+                let mut convention = NamingConvention::for_language(language)?;
+                for (key, casing) in overrides {
+                    if let Some(casing) = casing.as_str().and_then(Casing::from_name) {
+                        convention.set(key, casing);
+                    }
+                }
+                Some(convention)
+            }
+            _ => None,
+        }
+    }
+
+    /// A copy with the block, parameter and behavior names recased to the
+    /// naming convention for `language`
+    pub fn with_native_names(&self, language: &str) -> AbstractBlockSpec {
+        let mut spec = self.clone();
+        let Some(convention) = self.naming_convention(language) else {
+            return spec;
+        };
+
+        let kind = match self.block_type {
+            BlockType::Function => Some(IdentifierKind::Function),
+            BlockType::Class | BlockType::Interface | BlockType::Struct | BlockType::Enum => Some(IdentifierKind::Type),
+            BlockType::Constant => Some(IdentifierKind::Constant),
+            BlockType::Variable => Some(IdentifierKind::Variable),
+            // Module names are file names, see `OutputNaming`
+            BlockType::Module => None,
+        };
+        if let Some(kind) = kind {
+            spec.semantic_name = convention.apply(kind, &spec.semantic_name);
+        }
+        for parameter in &mut spec.properties.parameters {
+            parameter.name = convention.apply(IdentifierKind::Variable, &parameter.name);
+        }
+        for behavior in &mut spec.behaviors {
+            behavior.name = convention.apply(IdentifierKind::Function, &behavior.name);
+        }
+        spec
+    }
+
+    /// Field-level problems with the spec, without touching the database.
+    /// Errors cover what synthesis would reject or generate broken code
+    /// from; warnings cover what is likely a mistake.
+    pub fn validate(&self) -> SpecValidation {
+        let mut validation = SpecValidation::default();
+
+        if self.semantic_name.trim().is_empty() {
+            validation.error("semantic_name", "must not be empty");
+        } else if !is_identifier(&self.semantic_name) {
+            validation.error("semantic_name", format!("`{}` is not a valid identifier", self.semantic_name));
+        }
+        if self.description.trim().is_empty() {
+            validation.warning("description", "is empty; generated docs will have nothing to say");
+        }
+        if matches!(self.block_type, BlockType::Class) && self.behaviors.is_empty() && self.properties.parameters.is_empty() {
+            validation.error("behaviors", "a Class needs at least one behavior or parameter");
+        }
+
+        let properties = &self.properties;
+        let mut seen_optional = false;
+        for (index, parameter) in properties.parameters.iter().enumerate() {
+            let field = format!("properties.parameters[{}]", index);
+            if parameter.name.trim().is_empty() {
+                validation.error(field_path(&field, "name"), "must not be empty");
+            } else if !is_identifier(&parameter.name) {
+                validation.error(field_path(&field, "name"), format!("`{}` is not a valid identifier", parameter.name));
+            } else if properties.parameters[..index].iter().any(|earlier| earlier.name == parameter.name) {
+                validation.error(field_path(&field, "name"), format!("duplicate parameter `{}`", parameter.name));
+            }
+            parameter.param_type.validate(&field_path(&field, "param_type"), &mut validation);
+
+            let optional = parameter.is_optional || parameter.default_value.is_some();
+            if seen_optional && !optional {
+                validation.warning(field, "required parameter follows an optional one");
+            }
+            seen_optional |= optional;
+        }
+        if let Some(return_type) = &properties.return_type {
+            return_type.validate("properties.return_type", &mut validation);
+        }
+        if properties.complexity_target == Some(0) {
+            validation.warning("properties.complexity_target", "0 can never be met; use at least 1");
+        }
+        if let Some(visibility) = &properties.visibility {
+            if !["public", "private", "protected", "internal"].contains(&visibility.to_lowercase().as_str()) {
+                validation.warning("properties.visibility", format!("unknown visibility `{}`", visibility));
+            }
+        }
+        if properties.is_async && !matches!(self.block_type, BlockType::Function | BlockType::Class | BlockType::Interface) {
+            validation.warning("properties.is_async", format!("has no effect on a {:?}", self.block_type));
+        }
+
+        for (index, behavior) in self.behaviors.iter().enumerate() {
+            let field = format!("behaviors[{}]", index);
+            if behavior.name.trim().is_empty() {
+                validation.error(field_path(&field, "name"), "must not be empty");
+            } else if self.behaviors[..index].iter().any(|earlier| earlier.name == behavior.name) {
+                validation.error(field_path(&field, "name"), format!("duplicate behavior `{}`", behavior.name));
+            }
+            if behavior.postconditions.is_empty() {
+                validation.warning(field_path(&field, "postconditions"), "none given; the behavior is unchecked");
+            }
+        }
+        for (index, invariant) in self.invariants.iter().enumerate() {
+            let field = format!("invariants[{}]", index);
+            if invariant.condition.trim().is_empty() {
+                validation.error(field_path(&field, "condition"), "must not be empty");
+            }
+            if invariant.name.trim().is_empty() {
+                validation.warning(field_path(&field, "name"), "is empty");
+            }
+        }
+        if self.generation_hints.get("emit_docs").is_some_and(|hint| !hint.is_boolean()) {
+            validation.warning("generation_hints.emit_docs", "should be true or false; treated as false");
+        }
+        match self.generation_hints.get("naming_convention") {
+            None | Some(serde_json::Value::Bool(_)) => {}
+            Some(serde_json::Value::String(name)) => {
+                if !matches!(name.as_str(), "native" | "preserve") && NamingConvention::for_language(name).is_none() {
+                    validation.warning("generation_hints.naming_convention", format!("unknown convention `{}`; names are kept as written", name));
+                }
+            }
+            Some(serde_json::Value::Object(overrides)) => {
+                for (key, casing) in overrides {
+                    let field = field_path("generation_hints.naming_convention", key);
+                    if !["functions", "variables", "constants", "types"].contains(&key.as_str()) {
+                        validation.warning(field, "unknown identifier kind, ignored");
+                    } else if casing.as_str().and_then(Casing::from_name).is_none() {
+                        validation.warning(field, "should be snake, camel, pascal or screaming_snake; ignored");
+                    }
+                }
+            }
+            Some(_) => validation.warning("generation_hints.naming_convention", "should be a string, a boolean or an object; names are kept as written"),
+        }
+        validation
+    }
+}
+
+impl TypeSpec {
+    fn validate(&self, field: &str, validation: &mut SpecValidation) {
+        if self.name.trim().is_empty() {
+            validation.error(field_path(field, "name"), "must not be empty");
+        }
+        for (index, generic) in self.generics.iter().enumerate() {
+            generic.validate(&format!("{}.generics[{}]", field, index), validation);
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -331,16 +507,24 @@ pub struct ModuleSynthesisResult {
     pub warnings: Vec<String>,
 }
 
-// Placeholder struct for semantic validation
 pub struct SemanticValidator;
 
 impl SemanticValidator {
+    /// Every field-level issue with `spec`; see `AbstractBlockSpec::validate`
+    pub fn validate_spec(&self, spec: &AbstractBlockSpec) -> SpecValidation {
+        spec.validate()
+    }
+
+    /// Fails on the first spec error so synthesis never generates from an
+    /// invalid spec; warnings don't block it
     pub fn validate_constraints(
         &self,
-        _spec: &AbstractBlockSpec,
+        spec: &AbstractBlockSpec,
         _constraints: &[Constraint],
     ) -> Result<()> {
-        // Placeholder implementation
+        if let Some(issue) = self.validate_spec(spec).errors().next() {
+            anyhow::bail!("Invalid spec: {} {}", issue.field, issue.message);
+        }
         Ok(())
     }
 }
@@ -52,8 +52,8 @@ impl CodeGenerator {
             .get(language)
             .ok_or_else(|| anyhow::anyhow!("Unsupported language: {}", language))?;
         
-        // Generate code
-        generator.generate_from_pattern(&pattern, spec)
+        // Generate code, with names in the target language's casing
+        generator.generate_from_pattern(&pattern, &spec.with_native_names(language))
     }
 
     pub fn add_generator(&mut self, language: String, generator: Box<dyn LanguageGenerator>) {
@@ -71,6 +71,54 @@ impl Default for CodeGenerator {
     }
 }
 
+/// The human-readable parts of a spec, rendered as a doc comment when
+/// `generation_hints["emit_docs"]` is set
+struct DocContent<'a> {
+    summary: &'a str,
+    parameters: Vec<(&'a str, &'a str)>,
+    preconditions: Vec<&'a str>,
+    postconditions: Vec<&'a str>,
+}
+
+impl<'a> DocContent<'a> {
+    fn from_spec(spec: &'a AbstractBlockSpec) -> Self {
+        Self {
+            summary: spec.description.trim(),
+            parameters: spec.properties.parameters
+                .iter()
+                .map(|param| (param.name.as_str(), param.description.as_deref().unwrap_or("").trim()))
+                .collect(),
+            preconditions: spec.behaviors
+                .iter()
+                .flat_map(|behavior| behavior.preconditions.iter().map(String::as_str))
+                .collect(),
+            postconditions: spec.behaviors
+                .iter()
+                .flat_map(|behavior| behavior.postconditions.iter().map(String::as_str))
+                .collect(),
+        }
+    }
+
+    /// Pre- and postcondition sections that have entries
+    fn conditions(&self) -> Vec<(&'static str, &[&'a str])> {
+        [("Preconditions", &self.preconditions), ("Postconditions", &self.postconditions)]
+            .into_iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .map(|(heading, entries)| (heading, entries.as_slice()))
+            .collect()
+    }
+}
+
+/// Drop `// {{description}}` style comment lines, for languages whose doc
+/// comment goes above the item instead
+fn strip_description_comment(template: &str) -> String {
+    template
+        .lines()
+        .filter(|line| !matches!(line.trim(), "// {{description}}" | "/// {{description}}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 // Python Code Generator
 pub struct PythonGenerator {
     indent: String,
@@ -132,6 +180,31 @@ impl PythonGenerator {
         result
     }
 
+    /// Body of a `"""` docstring, ending with the indent for the closing quotes
+    fn format_docstring(&self, doc: &DocContent) -> String {
+        let mut lines = vec![doc.summary.to_string()];
+
+        if !doc.parameters.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("{}Args:", self.indent));
+            for (name, description) in &doc.parameters {
+                let line = format!("{}{}{}: {}", self.indent, self.indent, name, description);
+                lines.push(line.trim_end().to_string());
+            }
+        }
+
+        for (heading, entries) in doc.conditions() {
+            lines.push(String::new());
+            lines.push(format!("{}{}:", self.indent, heading));
+            for entry in entries {
+                lines.push(format!("{}{}- {}", self.indent, self.indent, entry));
+            }
+        }
+
+        lines.push(self.indent.clone());
+        lines.join("\n")
+    }
+
     fn generate_function_body(&self, spec: &AbstractBlockSpec) -> String {
         if spec.behaviors.is_empty() {
             return format!("{}pass", self.indent);
@@ -191,6 +264,13 @@ impl LanguageGenerator for PythonGenerator {
         let mut template = variant.template.clone();
         let mut imports = variant.imports.clone();
 
+        // Only a placeholder that fills the whole docstring can become a
+        // multi-line one
+        if spec.emit_docs() {
+            let docstring = self.format_docstring(&DocContent::from_spec(spec));
+            template = template.replace("{{description}}\"\"\"", &format!("{}\"\"\"", docstring));
+        }
+
         // Replace placeholders based on block type
         match spec.block_type {
             BlockType::Function => {
@@ -315,6 +395,28 @@ impl TypeScriptGenerator {
         
         result
     }
+
+    fn format_jsdoc(&self, doc: &DocContent) -> String {
+        let mut lines = vec!["/**".to_string(), format!(" * {}", doc.summary)];
+
+        if !doc.parameters.is_empty() {
+            lines.push(" *".to_string());
+            for (name, description) in &doc.parameters {
+                lines.push(format!(" * @param {} {}", name, description).trim_end().to_string());
+            }
+        }
+
+        for (heading, entries) in doc.conditions() {
+            lines.push(" *".to_string());
+            lines.push(format!(" * {}:", heading));
+            for entry in entries {
+                lines.push(format!(" * - {}", entry));
+            }
+        }
+
+        lines.push(" */".to_string());
+        lines.join("\n")
+    }
 }
 
 impl LanguageGenerator for TypeScriptGenerator {
@@ -327,7 +429,11 @@ impl LanguageGenerator for TypeScriptGenerator {
             .get("typescript")
             .ok_or_else(|| anyhow::anyhow!("TypeScript variant not found for pattern: {}", pattern.name))?;
 
-        let mut template = variant.template.clone();
+        let mut template = if spec.emit_docs() {
+            strip_description_comment(&variant.template)
+        } else {
+            variant.template.clone()
+        };
 
         // Replace placeholders
         match spec.block_type {
@@ -356,6 +462,10 @@ impl LanguageGenerator for TypeScriptGenerator {
             }
         }
 
+        if spec.emit_docs() {
+            template = format!("{}\n{}", self.format_jsdoc(&DocContent::from_spec(spec)), template);
+        }
+
         Ok(template)
     }
 
@@ -421,6 +531,30 @@ impl RustGenerator {
         
         result
     }
+
+    fn format_doc_comment(&self, doc: &DocContent) -> String {
+        let mut lines = vec![format!("/// {}", doc.summary)];
+
+        if !doc.parameters.is_empty() {
+            lines.push("///".to_string());
+            lines.push("/// # Arguments".to_string());
+            lines.push("///".to_string());
+            for (name, description) in &doc.parameters {
+                lines.push(format!("/// * `{}` - {}", name, description).trim_end().to_string());
+            }
+        }
+
+        for (heading, entries) in doc.conditions() {
+            lines.push("///".to_string());
+            lines.push(format!("/// # {}", heading));
+            lines.push("///".to_string());
+            for entry in entries {
+                lines.push(format!("/// - {}", entry));
+            }
+        }
+
+        lines.join("\n")
+    }
 }
 
 impl LanguageGenerator for RustGenerator {
@@ -433,7 +567,11 @@ impl LanguageGenerator for RustGenerator {
             .get("rust")
             .ok_or_else(|| anyhow::anyhow!("Rust variant not found for pattern: {}", pattern.name))?;
 
-        let mut template = variant.template.clone();
+        let mut template = if spec.emit_docs() {
+            strip_description_comment(&variant.template)
+        } else {
+            variant.template.clone()
+        };
 
         // Replace placeholders
         match spec.block_type {
@@ -463,6 +601,10 @@ impl LanguageGenerator for RustGenerator {
             }
         }
 
+        if spec.emit_docs() {
+            template = format!("{}\n{}", self.format_doc_comment(&DocContent::from_spec(spec)), template);
+        }
+
         Ok(template)
     }
 
@@ -474,3 +616,120 @@ impl LanguageGenerator for RustGenerator {
         "rs"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_operations::{BehaviorSpec, BlockProperties, Constraint};
+    use crate::generator::identifier_casing::{Casing, IdentifierKind, NamingConvention};
+
+    fn documented_spec(emit_docs: bool) -> AbstractBlockSpec {
+        AbstractBlockSpec {
+            block_type: crate::ai_operations::BlockType::Function,
+            semantic_name: "transfer".to_string(),
+            description: "Move funds between two accounts".to_string(),
+            properties: BlockProperties {
+                parameters: vec![ParameterSpec {
+                    name: "amount".to_string(),
+                    param_type: TypeSpec { name: "int".to_string(), generics: vec![], nullable: false, constraints: vec![] },
+                    description: Some("Amount in cents".to_string()),
+                    default_value: None,
+                    is_optional: false,
+                }],
+                return_type: None,
+                modifiers: vec![],
+                annotations: vec![],
+                complexity_target: None,
+                is_async: false,
+                visibility: None,
+            },
+            behaviors: vec![BehaviorSpec {
+                name: "transfer".to_string(),
+                description: "Debit one account and credit the other".to_string(),
+                preconditions: vec!["amount is positive".to_string()],
+                postconditions: vec!["balances sum is unchanged".to_string()],
+                side_effects: vec![],
+            }],
+            invariants: vec![],
+            generation_hints: HashMap::from([("emit_docs".to_string(), serde_json::json!(emit_docs))]),
+        }
+    }
+
+    fn synthesize_in(language: &str, spec: AbstractBlockSpec) -> Result<String> {
+        let request = BlockSynthesisRequest {
+            block_spec: spec.clone(),
+            relationships: vec![],
+            constraints: vec![Constraint {
+                constraint_type: "target_language".to_string(),
+                value: serde_json::json!(language),
+                description: String::new(),
+            }],
+            target_container: None,
+        };
+        CodeGenerator::new().generate_from_spec(&spec, &request)
+    }
+
+    #[test]
+    fn test_synthesized_blocks_emit_docs_from_spec() -> Result<()> {
+        let python = synthesize_in("python", documented_spec(true))?;
+        assert!(python.contains("    \"\"\"Move funds between two accounts\n\n    Args:\n        amount: Amount in cents\n"));
+        assert!(python.contains("    Preconditions:\n        - amount is positive\n"));
+        assert!(python.contains("    Postconditions:\n        - balances sum is unchanged\n    \"\"\""));
+
+        let rust = synthesize_in("rust", documented_spec(true))?;
+        assert!(rust.starts_with("/// Move funds between two accounts\n///\n/// # Arguments\n///\n/// * `amount` - Amount in cents\n"));
+        assert!(rust.contains("/// # Preconditions\n///\n/// - amount is positive\n"));
+        assert!(rust.contains("/// - balances sum is unchanged\nfn transfer("));
+        assert!(!rust.contains("    // Move funds"), "inline description comment should be replaced");
+
+        let typescript = synthesize_in("typescript", documented_spec(true))?;
+        assert!(typescript.starts_with("/**\n * Move funds between two accounts\n *\n * @param amount Amount in cents\n"));
+        assert!(typescript.contains(" * - balances sum is unchanged\n */\nfunction transfer("));
+
+        // Without the hint the templates keep their one-line description
+        let plain = synthesize_in("python", documented_spec(false))?;
+        assert!(plain.contains("    \"\"\"Move funds between two accounts\"\"\""));
+        assert!(!plain.contains("Args:"));
+
+        Ok(())
+    }
+
+    /// Test that synthesis recases identifiers to the target language's convention
+    #[test]
+    fn test_synthesis_recases_identifiers_for_target_language() -> Result<()> {
+        let mut spec = documented_spec(false);
+        spec.semantic_name = "get_user_name".to_string();
+        spec.properties.parameters[0].name = "user_id".to_string();
+        spec.behaviors[0].name = "get_user_name".to_string();
+    
+        let java = spec.with_native_names("java");
+        assert_eq!(java.semantic_name, "getUserName");
+        assert_eq!(java.properties.parameters[0].name, "userId");
+        assert_eq!(java.behaviors[0].name, "getUserName");
+    
+        let convention = NamingConvention::for_language("java").expect("java has a convention");
+        assert_eq!(convention.apply(IdentifierKind::Constant, "maxRetries"), "MAX_RETRIES");
+        assert_eq!(convention.apply(IdentifierKind::Type, "user_record"), "UserRecord");
+        assert_eq!(Casing::Camel.apply("_cached_name"), "_cachedName");
+        assert_eq!(Casing::Camel.apply("__init__"), "__init__");
+        assert_eq!(Casing::Snake.apply("HTTPServerError"), "http_server_error");
+    
+        // Generators receive the recased names
+        let typescript = synthesize_in("typescript", spec.clone())?;
+        assert!(typescript.contains("function getUserName(userId"), "{}", typescript);
+    
+        // Hints opt out, borrow another language's convention or override one kind
+        spec.generation_hints.insert("naming_convention".to_string(), serde_json::json!("preserve"));
+        assert_eq!(spec.with_native_names("java").semantic_name, "get_user_name");
+        spec.generation_hints.insert("naming_convention".to_string(), serde_json::json!("csharp"));
+        assert_eq!(spec.with_native_names("java").semantic_name, "GetUserName");
+        spec.generation_hints.insert("naming_convention".to_string(), serde_json::json!({ "variables": "snake" }));
+        let overridden = spec.with_native_names("java");
+        assert_eq!((overridden.semantic_name.as_str(), overridden.properties.parameters[0].name.as_str()), ("getUserName", "user_id"));
+    
+        spec.generation_hints.insert("naming_convention".to_string(), serde_json::json!({ "methods": "camel" }));
+        let fields: Vec<String> = spec.validate().warnings().map(|issue| issue.field.clone()).collect();
+        assert_eq!(fields, vec!["generation_hints.naming_convention.methods"]);
+        Ok(())
+    }
+}
@@ -449,6 +449,7 @@ impl IntentProcessor {
                 }
             ],
             invariants: vec![],
+            generation_hints: HashMap::new(),
         };
 
         Ok(SemanticOperation {
@@ -3,8 +3,12 @@ pub mod abstraction_mapper;
 pub mod pattern_library;
 pub mod code_generators;
 pub mod intent_processor;
+pub mod spec_validation;
+pub mod rename_symbol;
 
 pub use block_synthesis::*;
 pub use abstraction_mapper::*;
 pub use pattern_library::*;
 pub use code_generators::*;
+pub use spec_validation::*;
+pub use rename_symbol::SymbolRenamer;
@@ -0,0 +1,451 @@
+//! Cross-file symbol rename
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+use uuid::Uuid;
+
+use crate::database::schema::BlockRelationship;
+use crate::database::{Block, Database};
+use crate::generator::HierarchicalGenerator;
+use crate::parser::universal::grammar_for;
+use crate::scanner::FileScanner;
+
+/// A block a rename changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TouchedBlock {
+    pub block_id: Uuid,
+    pub container_id: Uuid,
+    /// Path of the block's file, when known
+    pub path: Option<String>,
+    /// The block's name after the rename
+    pub name: Option<String>,
+    /// How the block refers to the renamed symbol, e.g. `calls`; `None` for
+    /// the symbol's own definition
+    pub relationship: Option<String>,
+}
+
+/// The blocks a rename changes, worked out from a snapshot of the store
+#[derive(Debug, Clone)]
+pub struct RenamePlan {
+    /// The block defining the symbol
+    pub definition: Uuid,
+    /// Renamed copies of every touched block, definition first
+    pub updated: Vec<Block>,
+    pub touched: Vec<TouchedBlock>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RenameReport {
+    pub definition: Uuid,
+    pub touched: Vec<TouchedBlock>,
+    /// Regenerated source of each affected file, by path
+    pub files: BTreeMap<String, String>,
+}
+
+impl RenamePlan {
+    /// Plan renaming the block `old_qualified_name` resolves to among
+    /// `blocks` to `new_name`. `container_paths` maps container ids to
+    /// their file paths, whose directories and stem qualify top-level names.
+    pub fn new(
+        blocks: &[Block],
+        container_paths: &HashMap<Uuid, String>,
+        relationships: &[BlockRelationship],
+        old_qualified_name: &str,
+        new_name: &str,
+    ) -> Result<Self> {
+        if !is_identifier(new_name) {
+            bail!("'{}' is not a valid identifier", new_name);
+        }
+        let by_id: HashMap<Uuid, &Block> = blocks.iter().map(|block| (block.id, block)).collect();
+        let definition = resolve(blocks, &by_id, container_paths, old_qualified_name)?;
+        let old_name = definition.semantic_name.clone().unwrap_or_default();
+        if old_name == new_name {
+            bail!("'{}' is already named {}", old_qualified_name, new_name);
+        }
+
+        // Every block referring to the definition, once, in store order
+        let mut references: Vec<(&Block, &str)> = Vec::new();
+        let mut seen = HashSet::from([definition.id]);
+        for relationship in relationships {
+            if relationship.target_block_id != definition.id || relationship.relationship_type == "contains" {
+                continue;
+            }
+            if let Some(block) = by_id.get(&relationship.source_block_id) {
+                if seen.insert(block.id) {
+                    references.push((block, relationship.relationship_type.as_str()));
+                }
+            }
+        }
+
+        let qualifiers = qualifiers(definition, &by_id, container_paths);
+        let path_of = |block: &Block| container_paths.get(&block.container_id).cloned();
+        let describe = |block: &Block| format!(
+            "{} in {}",
+            block.semantic_name.as_deref().unwrap_or("unnamed block"),
+            path_of(block).unwrap_or_else(|| block.container_id.to_string()),
+        );
+
+        // A name already next to the symbol or at the top of a referencing
+        // file would be shadowed
+        let mut scopes = vec![(definition.container_id, definition.parent_block_id)];
+        scopes.extend(references.iter().map(|(block, _)| (block.container_id, None)));
+        if let Some(clash) = blocks.iter().find(|block| {
+            !seen.contains(&block.id)
+                && block.semantic_name.as_deref() == Some(new_name)
+                && scopes.contains(&(block.container_id, block.parent_block_id))
+        }) {
+            bail!("Cannot rename {} to {}: {} already defines it", old_qualified_name, new_name, describe(clash));
+        }
+
+        let mut updated = Vec::new();
+        let mut touched = Vec::new();
+        for (block, relationship) in std::iter::once((definition, None)).chain(references.iter().map(|&(block, kind)| (block, Some(kind)))) {
+            let grammar = path_of(block)
+                .and_then(|path| FileScanner::language_for(Path::new(&path)))
+                .and_then(grammar_for)
+                .ok_or_else(|| anyhow!("Cannot rename {}: no grammar to parse {}", old_qualified_name, describe(block)))?;
+            let mut parser = Parser::new();
+            parser.set_language(grammar)?;
+            let mut renaming = Renaming { parser, qualifiers: &qualifiers };
+
+            if renaming.mentions(block, new_name) {
+                bail!("Cannot rename {} to {}: {} already uses that name", old_qualified_name, new_name, describe(block));
+            }
+            let mut renamed = block.clone();
+            let changed = renaming.rename(&mut renamed, &old_name, new_name);
+            if relationship.is_none() {
+                renamed.semantic_name = Some(new_name.to_string());
+            } else if !changed {
+                continue;
+            }
+            touched.push(TouchedBlock {
+                block_id: block.id,
+                container_id: block.container_id,
+                path: path_of(block),
+                name: renamed.semantic_name.clone(),
+                relationship: relationship.map(str::to_string),
+            });
+            updated.push(renamed);
+        }
+
+        Ok(Self { definition: definition.id, updated, touched })
+    }
+
+    /// Containers holding a touched block, in the order first touched
+    pub fn containers(&self) -> Vec<Uuid> {
+        let mut seen = HashSet::new();
+        self.touched.iter()
+            .map(|touched| touched.container_id)
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+}
+
+pub struct SymbolRenamer {
+    db: Database,
+}
+
+impl SymbolRenamer {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Rename `old_qualified_name` in `migration_id` to `new_name`, store
+    /// the touched blocks in one transaction and regenerate their files
+    pub async fn rename_symbol(&self, migration_id: Uuid, old_qualified_name: &str, new_name: &str) -> Result<RenameReport> {
+        let containers = self.db.get_containers_by_migration(migration_id).await?;
+        let blocks = self.db.get_blocks_by_migration(migration_id).await?;
+        let relationships = self.db.get_relationships_by_migration(migration_id).await?;
+        let container_paths: HashMap<Uuid, String> = containers.iter()
+            .filter_map(|container| Some((container.id, container.original_path.clone()?)))
+            .collect();
+
+        let plan = RenamePlan::new(&blocks, &container_paths, &relationships, old_qualified_name, new_name)?;
+        self.db.update_blocks(&plan.updated).await?;
+
+        let mut files = BTreeMap::new();
+        for container_id in plan.containers() {
+            let Some(path) = container_paths.get(&container_id) else {
+                continue;
+            };
+            let generated = HierarchicalGenerator::from_container(&self.db, container_id).await?.generate()?;
+            files.insert(path.clone(), generated);
+        }
+
+        Ok(RenameReport { definition: plan.definition, touched: plan.touched, files })
+    }
+}
+
+/// The one non-import block whose name, qualified by its enclosing blocks
+/// and its file's path, ends with the segments of `qualified_name`
+fn resolve<'a>(
+    blocks: &'a [Block],
+    by_id: &HashMap<Uuid, &'a Block>,
+    container_paths: &HashMap<Uuid, String>,
+    qualified_name: &str,
+) -> Result<&'a Block> {
+    let segments: Vec<&str> = qualified_name.split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() {
+        bail!("Empty symbol name");
+    }
+
+    let qualified_path = |block: &Block| -> Vec<String> {
+        let mut path = vec![block.semantic_name.clone().unwrap_or_default()];
+        let mut parent = block.parent_block_id.and_then(|id| by_id.get(&id));
+        while let Some(enclosing) = parent {
+            path.push(enclosing.semantic_name.clone().unwrap_or_default());
+            parent = enclosing.parent_block_id.and_then(|id| by_id.get(&id));
+        }
+        if let Some(file) = container_paths.get(&block.container_id) {
+            let module = file.rsplit_once('.').map_or(file.as_str(), |(stem, _)| stem);
+            path.extend(module.rsplit(['/', '\\']).filter(|segment| !segment.is_empty()).map(str::to_string));
+        }
+        path.reverse();
+        path
+    };
+
+    let matches: Vec<(&Block, Vec<String>)> = blocks.iter()
+        .filter(|block| !matches!(block.block_type.as_str(), "Import" | "Comment"))
+        .filter(|block| block.semantic_name.as_deref() == segments.last().copied())
+        .map(|block| (block, qualified_path(block)))
+        .filter(|(_, path)| path.len() >= segments.len() && path[path.len() - segments.len()..].iter().zip(&segments).all(|(a, b)| a == b))
+        .collect();
+    match matches.as_slice() {
+        [] => Err(anyhow!("No block named {}", qualified_name)),
+        [(block, _)] => Ok(block),
+        _ => {
+            let candidates: Vec<String> = matches.iter().map(|(_, path)| path.join(".")).collect();
+            bail!("{} is ambiguous, qualify it further: {}", qualified_name, candidates.join(", "))
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Names a reference to `definition` may be qualified by: its module and,
+/// for a member, its enclosing block or the receiver of a method
+fn qualifiers(definition: &Block, by_id: &HashMap<Uuid, &Block>, container_paths: &HashMap<Uuid, String>) -> Vec<String> {
+    let mut qualifiers: Vec<String> = container_paths.get(&definition.container_id)
+        .and_then(|path| Path::new(path).file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .into_iter()
+        .collect();
+    if let Some(parent) = definition.parent_block_id.and_then(|id| by_id.get(&id)) {
+        qualifiers.extend(parent.semantic_name.clone());
+        qualifiers.extend(["self", "this", "Self", "cls"].map(str::to_string));
+    }
+    qualifiers
+}
+
+/// Finds the identifiers naming the renamed symbol in one block's fields
+struct Renaming<'a> {
+    /// Parser for the block's language
+    parser: Parser,
+    qualifiers: &'a [String],
+}
+
+impl Renaming<'_> {
+    /// Whether any of the block's fields already refers to `name`
+    fn mentions(&mut self, block: &Block, name: &str) -> bool {
+        let mut mentioned = false;
+        rewrite_strings(&mut block.clone(), &mut |text| {
+            mentioned |= !self.references(text, name).is_empty();
+            false
+        });
+        mentioned
+    }
+
+    /// Replace references to `old_name` with `new_name` in the block's
+    /// stored name and fields; whether anything changed
+    fn rename(&mut self, block: &mut Block, old_name: &str, new_name: &str) -> bool {
+        rewrite_strings(block, &mut |text| {
+            let references = self.references(text, old_name);
+            for range in references.iter().rev() {
+                text.replace_range(range.clone(), new_name);
+            }
+            !references.is_empty()
+        })
+    }
+
+    /// Byte ranges of the identifier nodes in `text` that refer to `name`
+    fn references(&mut self, text: &str, name: &str) -> Vec<Range<usize>> {
+        if !text.contains(name) {
+            return Vec::new();
+        }
+        let Some(tree) = self.parser.parse(text, None) else {
+            return Vec::new();
+        };
+        let mut references = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if node.child_count() == 0 {
+                if node.kind().ends_with("identifier") && &text[node.byte_range()] == name && self.resolves(node, text) {
+                    references.push(node.byte_range());
+                }
+                continue;
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+        references.sort_by_key(|range| range.start);
+        references
+    }
+
+    /// Whether an identifier names the symbol rather than a keyword
+    /// argument, an object key or a member of something else
+    fn resolves(&self, node: Node, text: &str) -> bool {
+        let Some(parent) = node.parent() else {
+            return true;
+        };
+        let is_field = |field: &str| parent.child_by_field_name(field).is_some_and(|child| child.id() == node.id());
+        if matches!(parent.kind(), "keyword_argument" | "pair") && (is_field("name") || is_field("key")) {
+            return false;
+        }
+        match node.prev_sibling() {
+            Some(separator) if matches!(separator.kind(), "." | "::" | "?.") => {
+                let Some(qualifier) = separator.prev_sibling() else {
+                    return true;
+                };
+                let qualifier = &text[qualifier.byte_range()];
+                let last = qualifier.rsplit(['.', ':']).next().unwrap_or(qualifier);
+                self.qualifiers.iter().any(|name| name == last)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Pass the block's stored name and every string in its fields through
+/// `rewrite`; whether it changed any
+fn rewrite_strings(block: &mut Block, rewrite: &mut dyn FnMut(&mut String) -> bool) -> bool {
+    let mut changed = false;
+    for name in block.semantic_name.iter_mut().chain(block.return_type.iter_mut()) {
+        changed |= rewrite(name);
+    }
+    let values = std::iter::once(&mut block.abstract_syntax).chain(
+        [
+            &mut block.metadata,
+            &mut block.parameters,
+            &mut block.decorators,
+            &mut block.body_ast,
+            &mut block.language_ast,
+            &mut block.language_features,
+            &mut block.scope_info,
+        ]
+        .into_iter()
+        .flatten(),
+    );
+    for value in values {
+        changed |= rewrite_value(value, rewrite);
+    }
+    changed
+}
+
+fn rewrite_value(value: &mut serde_json::Value, rewrite: &mut dyn FnMut(&mut String) -> bool) -> bool {
+    match value {
+        serde_json::Value::String(text) => rewrite(text),
+        serde_json::Value::Array(items) => items.iter_mut().fold(false, |changed, item| rewrite_value(item, rewrite) | changed),
+        serde_json::Value::Object(fields) => fields.values_mut().fold(false, |changed, field| rewrite_value(field, rewrite) | changed),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test renaming a function referenced from two other files
+    #[test]
+    fn test_rename_symbol_updates_references_across_files() -> Result<()> {
+        let (pricing, cart, invoice) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let container_paths = HashMap::from([
+            (pricing, "shop/pricing.py".to_string()),
+            (cart, "shop/cart.py".to_string()),
+            (invoice, "billing/invoice.py".to_string()),
+        ]);
+        let block = |container: Uuid, block_type: &str, name: &str, text: &str| Block::new(container, block_type, name, serde_json::json!({"text": text}));
+        let blocks = vec![
+            block(pricing, "Function", "format_price", "def format_price(amount):\n    return f\"${amount:.2f}\""),
+            block(pricing, "Function", "format_price_range", "def format_price_range(low, high):\n    return f\"{low}-{high}\""),
+            block(cart, "Import", "shop.pricing", "from shop.pricing import format_price"),
+            block(cart, "Function", "cart_total", "def cart_total(items):\n    return format_price(sum(items))"),
+            block(invoice, "Import", "shop.pricing", "from shop.pricing import format_price, format_price_range"),
+            block(invoice, "Function", "render_invoice", "def render_invoice(amount):\n    return \"Total: \" + format_price(amount)"),
+        ];
+        let ids: Vec<Uuid> = blocks.iter().map(|block| block.id).collect();
+        let relationship = |source: usize, relationship_type: &str| crate::database::schema::BlockRelationship {
+            source_block_id: ids[source],
+            target_block_id: ids[0],
+            relationship_type: relationship_type.to_string(),
+            metadata: None,
+        };
+        let relationships = vec![relationship(2, "imports"), relationship(3, "calls"), relationship(4, "imports"), relationship(5, "calls")];
+    
+        let plan = RenamePlan::new(&blocks, &container_paths, &relationships, "pricing.format_price", "format_amount")?;
+        assert_eq!(plan.definition, ids[0]);
+        let touched: Vec<(Uuid, Option<&str>)> = plan.touched.iter()
+            .map(|touched| (touched.block_id, touched.relationship.as_deref()))
+            .collect();
+        assert_eq!(touched, vec![(ids[0], None), (ids[2], Some("imports")), (ids[3], Some("calls")), (ids[4], Some("imports")), (ids[5], Some("calls"))]);
+        assert_eq!(plan.containers(), vec![pricing, cart, invoice]);
+    
+        let text = |index: usize| plan.updated[index].abstract_syntax["text"].as_str().unwrap_or_default().to_string();
+        assert_eq!(plan.updated[0].semantic_name.as_deref(), Some("format_amount"));
+        assert_eq!(text(0), "def format_amount(amount):\n    return f\"${amount:.2f}\"");
+        assert_eq!(text(1), "from shop.pricing import format_amount");
+        assert_eq!(text(2), "def cart_total(items):\n    return format_amount(sum(items))");
+        // A longer name sharing the prefix is left alone
+        assert_eq!(text(3), "from shop.pricing import format_amount, format_price_range");
+        assert_eq!(text(4), "def render_invoice(amount):\n    return \"Total: \" + format_amount(amount)");
+    
+        // Rust-style separators and the full path resolve to the same block
+        let plan = RenamePlan::new(&blocks, &container_paths, &relationships, "shop::pricing::format_price", "format_amount")?;
+        assert_eq!(plan.definition, ids[0]);
+        assert!(RenamePlan::new(&blocks, &container_paths, &relationships, "cart.format_price", "format_amount").is_err());
+        assert!(RenamePlan::new(&blocks, &container_paths, &relationships, "pricing.format_price", "format amount").is_err());
+    
+        // Names already defined beside the symbol, or used by a referencing block, are refused
+        let collision = RenamePlan::new(&blocks, &container_paths, &relationships, "pricing.format_price", "format_price_range");
+        assert!(collision.unwrap_err().to_string().contains("already defines it"));
+        let shadowed = RenamePlan::new(&blocks, &container_paths, &relationships, "pricing.format_price", "items");
+        assert!(shadowed.unwrap_err().to_string().contains("cart_total in shop/cart.py already uses that name"));
+        let mut with_helper = blocks.clone();
+        with_helper.push(block(invoice, "Function", "format_amount", "def format_amount(value):\n    return str(value)"));
+        let collision = RenamePlan::new(&with_helper, &container_paths, &relationships, "pricing.format_price", "format_amount");
+        assert!(collision.unwrap_err().to_string().contains("format_amount in billing/invoice.py already defines it"));
+    
+        // Only identifiers that resolve to the symbol change: not strings,
+        // comments, keyword arguments or another object's member
+        let mut with_mentions = blocks.clone();
+        with_mentions.push(block(
+            invoice,
+            "Function",
+            "render_receipt",
+            "def render_receipt(order):\n    # format_price rounds to cents\n    label = \"format_price\" + order.format_price\n    return pricing.format_price(order.total, format_price=True) + label",
+        ));
+        let mut with_mentions_relationships = relationships.clone();
+        with_mentions_relationships.push(crate::database::schema::BlockRelationship {
+            source_block_id: with_mentions[6].id,
+            target_block_id: ids[0],
+            relationship_type: "calls".to_string(),
+            metadata: None,
+        });
+        let plan = RenamePlan::new(&with_mentions, &container_paths, &with_mentions_relationships, "pricing.format_price", "format_amount")?;
+        assert_eq!(
+            plan.updated[5].abstract_syntax["text"],
+            "def render_receipt(order):\n    # format_price rounds to cents\n    label = \"format_price\" + order.format_price\n    return pricing.format_amount(order.total, format_price=True) + label",
+        );
+        Ok(())
+    }
+}
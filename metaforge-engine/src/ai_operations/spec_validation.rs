@@ -0,0 +1,384 @@
+//! Specification linting
+
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::ai_operations::AbstractBlockSpec;
+use crate::synthesis::behavior_compiler::BehaviorSpecification;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecSeverity {
+    /// The spec can't be synthesized as written
+    Error,
+    /// The spec works but is probably not what was meant
+    Warning,
+}
+
+impl fmt::Display for SpecSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpecSeverity::Error => "error",
+            SpecSeverity::Warning => "warning",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecIssue {
+    pub severity: SpecSeverity,
+    /// Path to the field, e.g. `properties.parameters[1].name`; empty when
+    /// the issue is with the document as a whole
+    pub field: String,
+    pub message: String,
+}
+
+/// Every issue found in one spec, in the order the checks found them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpecValidation {
+    pub issues: Vec<SpecIssue>,
+}
+
+impl SpecValidation {
+    pub fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(SpecSeverity::Error, field.into(), message.into());
+    }
+
+    pub fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(SpecSeverity::Warning, field.into(), message.into());
+    }
+
+    fn push(&mut self, severity: SpecSeverity, field: String, message: String) {
+        self.issues.push(SpecIssue { severity, field, message });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &SpecIssue> {
+        self.issues.iter().filter(|issue| issue.severity == SpecSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &SpecIssue> {
+        self.issues.iter().filter(|issue| issue.severity == SpecSeverity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+}
+
+/// `parent.child`, or just `child` at the top level
+pub fn field_path(parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+        child.to_string()
+    } else {
+        format!("{}.{}", parent, child)
+    }
+}
+
+/// Whether `name` can be used as an identifier in every target language
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecKind {
+    /// An `AbstractBlockSpec`, as read by Synthesize
+    Abstract,
+    /// A `BehaviorSpecification`, as read by Behavior
+    Behavior,
+}
+
+impl SpecKind {
+    /// Guess the kind from the top-level keys: `block_type` marks an
+    /// abstract block spec, `postconditions` or `intent` a behavior spec
+    pub fn detect(value: &serde_json::Value) -> Option<Self> {
+        let object = value.as_object()?;
+        if object.contains_key("block_type") {
+            Some(SpecKind::Abstract)
+        } else if object.contains_key("postconditions") || object.contains_key("intent") {
+            Some(SpecKind::Behavior)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a spec file is YAML rather than JSON, going by its extension
+pub fn is_yaml_spec(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml"))
+}
+
+/// Parse a spec and validate it as `kind`, or as the kind its keys suggest
+/// when `None`. Parse failures are reported as issues, not errors.
+pub fn validate_spec(content: &str, yaml: bool, kind: Option<SpecKind>) -> SpecValidation {
+    let mut validation = SpecValidation::default();
+    let parsed: Result<serde_json::Value, String> = if yaml {
+        serde_yaml::from_str(content).map_err(|e| format!("invalid YAML: {}", e))
+    } else {
+        serde_json::from_str(content).map_err(|e| format!("invalid JSON: {}", e))
+    };
+    let value = match parsed {
+        Ok(value) => value,
+        Err(message) => {
+            validation.error("", message);
+            return validation;
+        }
+    };
+
+    match kind.or_else(|| SpecKind::detect(&value)) {
+        Some(SpecKind::Abstract) => check(&value, &mut validation, AbstractBlockSpec::validate),
+        Some(SpecKind::Behavior) => check(&value, &mut validation, BehaviorSpecification::validate),
+        None => validation.error("", "not a recognised spec: expected `block_type` (abstract block) or `postconditions` (behavior)"),
+    }
+    validation
+}
+
+fn check<T: DeserializeOwned + Serialize>(
+    value: &serde_json::Value,
+    validation: &mut SpecValidation,
+    validate: impl Fn(&T) -> SpecValidation,
+) {
+    let spec: T = match serde_json::from_value(value.clone()) {
+        Ok(spec) => spec,
+        Err(e) => {
+            validation.error("", e.to_string());
+            return;
+        }
+    };
+    // Whatever doesn't survive a round trip was ignored while parsing
+    if let Ok(known) = serde_json::to_value(&spec) {
+        unknown_fields(value, &known, "", validation);
+    }
+    validation.issues.extend(validate(&spec).issues);
+}
+
+fn unknown_fields(input: &serde_json::Value, known: &serde_json::Value, path: &str, validation: &mut SpecValidation) {
+    match (input, known) {
+        (serde_json::Value::Object(input), serde_json::Value::Object(known)) => {
+            for (key, value) in input {
+                let field = field_path(path, key);
+                match known.get(key) {
+                    Some(known) => unknown_fields(value, known, &field, validation),
+                    None => validation.warning(field, "unknown field, ignored"),
+                }
+            }
+        }
+        (serde_json::Value::Array(input), serde_json::Value::Array(known)) => {
+            for (index, (value, known)) in input.iter().zip(known).enumerate() {
+                unknown_fields(value, known, &format!("{}[{}]", path, index), validation);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Expand `*`, `?` and `**` in each pattern to the files they match, in
+/// path order. Patterns without wildcards are kept as given so a missing
+/// file is reported when it is read; a wildcard matching nothing is an error.
+pub fn expand_spec_patterns(patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        if !pattern.contains(['*', '?']) {
+            paths.push(PathBuf::from(pattern));
+            continue;
+        }
+        let components: Vec<&str> = pattern.split('/').collect();
+        let literal = components.iter().take_while(|component| !component.contains(['*', '?'])).count();
+        let base = if literal == 0 { PathBuf::from(".") } else { PathBuf::from(components[..literal].join("/")) };
+        let base = if base.as_os_str().is_empty() { PathBuf::from("/") } else { base };
+
+        let mut matched: Vec<PathBuf> = walkdir::WalkDir::new(&base)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| {
+                let Ok(relative) = entry.path().strip_prefix(&base) else {
+                    return false;
+                };
+                let relative: Vec<String> = relative.components()
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                let relative: Vec<&str> = relative.iter().map(String::as_str).collect();
+                path_matches(&components[literal..], &relative)
+            })
+            .map(|entry| entry.into_path())
+            .collect();
+        if matched.is_empty() {
+            anyhow::bail!("No spec files match `{}`", pattern);
+        }
+        matched.sort();
+        paths.extend(matched);
+    }
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Match path components, with `**` standing for any number of directories
+fn path_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| path_matches(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first()
+            .is_some_and(|(component, remaining)| {
+                let pattern: Vec<char> = first.chars().collect();
+                let name: Vec<char> = component.chars().collect();
+                name_matches(&pattern, &name) && path_matches(rest, remaining)
+            }),
+    }
+}
+
+/// Match one file name against a pattern of `*` (any run) and `?` (any one character)
+fn name_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| name_matches(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && name_matches(rest, &name[1..]),
+        Some((first, rest)) => name.first() == Some(first) && name_matches(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+    use crate::ai_operations::{BehaviorSpec, BlockProperties, ParameterSpec, SemanticValidator, TypeSpec};
+
+    fn transfer_spec() -> AbstractBlockSpec {
+        AbstractBlockSpec {
+            block_type: crate::ai_operations::BlockType::Function,
+            semantic_name: "transfer".to_string(),
+            description: "Move funds between two accounts".to_string(),
+            properties: BlockProperties {
+                parameters: vec![ParameterSpec {
+                    name: "amount".to_string(),
+                    param_type: TypeSpec { name: "int".to_string(), generics: vec![], nullable: false, constraints: vec![] },
+                    description: Some("Amount in cents".to_string()),
+                    default_value: None,
+                    is_optional: false,
+                }],
+                return_type: None,
+                modifiers: vec![],
+                annotations: vec![],
+                complexity_target: None,
+                is_async: false,
+                visibility: None,
+            },
+            behaviors: vec![BehaviorSpec {
+                name: "transfer".to_string(),
+                description: "Debit one account and credit the other".to_string(),
+                preconditions: vec!["amount is positive".to_string()],
+                postconditions: vec!["balances sum is unchanged".to_string()],
+                side_effects: vec![],
+            }],
+            invariants: vec![],
+            generation_hints: Default::default(),
+        }
+    }
+
+    /// Spec files are linted field by field without synthesizing anything
+    #[test]
+    fn test_validate_spec_reports_field_level_issues() -> Result<()> {
+        let issues = |content: &str, yaml: bool, kind: Option<SpecKind>| -> Vec<(SpecSeverity, String)> {
+            validate_spec(content, yaml, kind).issues.into_iter()
+                .map(|issue| (issue.severity, issue.field))
+                .collect()
+        };
+
+        let valid = serde_json::to_string(&transfer_spec())?;
+        assert!(issues(&valid, false, None).is_empty());
+
+        let yaml = "\
+block_type: Function
+semantic_name: transfer funds
+description: Move funds
+properties:
+  parameters:
+    - name: amount
+      param_type: { name: int, generics: [], nullable: false, constraints: [] }
+      description: null
+      default_value: \"0\"
+      is_optional: false
+    - name: amount
+      param_type: { name: \"\", generics: [], nullable: false, constraints: [] }
+      description: null
+      default_value: null
+      is_optional: false
+  return_type: null
+  modifiers: []
+  annotations: []
+  complexity_target: null
+  is_async: false
+  visibility: null
+behaviors: []
+invariants: []
+generaton_hints: { emit_docs: true }
+";
+        assert_eq!(issues(yaml, true, None), vec![
+            (SpecSeverity::Warning, "generaton_hints".to_string()),
+            (SpecSeverity::Error, "semantic_name".to_string()),
+            (SpecSeverity::Error, "properties.parameters[1].name".to_string()),
+            (SpecSeverity::Error, "properties.parameters[1].param_type.name".to_string()),
+            (SpecSeverity::Warning, "properties.parameters[1]".to_string()),
+        ]);
+
+        let behavior = serde_json::json!({
+            "id": Uuid::new_v4(),
+            "name": "debit",
+            "description": "Debit an account",
+            "intent": "Take money out",
+            "preconditions": [],
+            "postconditions": [],
+            "invariants": [],
+            "performance_requirements": null,
+            "security_requirements": null,
+            "error_handling": {
+                "strategy": "FailFast",
+                "recovery_actions": [],
+                "logging_level": "Error",
+                "user_facing_messages": false
+            },
+            "examples": []
+        }).to_string();
+        assert_eq!(issues(&behavior, false, None), vec![
+            (SpecSeverity::Warning, "preconditions".to_string()),
+            (SpecSeverity::Error, "postconditions".to_string()),
+            (SpecSeverity::Warning, "examples".to_string()),
+        ]);
+
+        // Synthesis rejects the same specs the linter reports errors for
+        let mut unnamed = transfer_spec();
+        assert!(SemanticValidator.validate_constraints(&unnamed, &[]).is_ok());
+        unnamed.semantic_name = " ".to_string();
+        assert_eq!(SemanticValidator.validate_spec(&unnamed).errors().next().map(|issue| issue.field.as_str()), Some("semantic_name"));
+        assert!(SemanticValidator.validate_constraints(&unnamed, &[]).is_err());
+
+        // Parse failures and specs of the wrong kind are issues, not panics
+        assert!(validate_spec("{ \"block_type\": ", false, None).has_errors());
+        assert!(validate_spec(&valid, false, Some(SpecKind::Behavior)).has_errors());
+
+        let dir = std::env::temp_dir().join(format!("metaforge-specs-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("nested"))?;
+        for name in ["a.yaml", "b.json", "nested/c.yaml", "notes.txt"] {
+            std::fs::write(dir.join(name), "")?;
+        }
+        let root = dir.display().to_string();
+        assert_eq!(expand_spec_patterns(&[format!("{}/*.yaml", root), format!("{}/*.json", root)])?, vec![
+            dir.join("a.yaml"),
+            dir.join("b.json"),
+        ]);
+        assert_eq!(expand_spec_patterns(&[format!("{}/**/*.yaml", root)])?, vec![
+            dir.join("a.yaml"),
+            dir.join("nested/c.yaml"),
+        ]);
+        assert!(expand_spec_patterns(&[format!("{}/*.toml", root)]).is_err());
+        std::fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Blocking I/O in async functions
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::{IoType, SideEffectAnalysis};
+use crate::database::Block;
+
+/// A blocking call made from an async function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockingInAsyncFinding {
+    pub block_id: Uuid,
+    /// Name of the async function
+    pub name: String,
+    /// The offending call, e.g. `std::fs::read_to_string`, or the resource
+    /// it touches when the extractor didn't record the call
+    pub call: String,
+    pub resource: String,
+    pub operation_type: IoType,
+}
+
+impl BlockingInAsyncFinding {
+    /// Blocking calls in a stored block, if it is an async function
+    pub fn from_block(block: &Block) -> Vec<Self> {
+        let modifier_columns = block.modifiers.iter().flatten().cloned();
+        let stored_modifiers = block.semantic_metadata.as_ref()
+            .and_then(|metadata| metadata.get("modifiers"))
+            .and_then(|modifiers| serde_json::from_value::<Vec<String>>(modifiers.clone()).ok())
+            .unwrap_or_default();
+        if !modifier_columns.chain(stored_modifiers).any(|modifier| modifier == "Async") {
+            return Vec::new();
+        }
+        let analysis = block.semantic_metadata.as_ref()
+            .and_then(|metadata| metadata.get("side_effect_analysis"))
+            .and_then(|analysis| serde_json::from_value::<SideEffectAnalysis>(analysis.clone()).ok());
+        match analysis {
+            Some(analysis) => Self::find(block.id, block.semantic_name.as_deref().unwrap_or("unnamed"), &analysis),
+            None => Vec::new(),
+        }
+    }
+
+    fn find(block_id: Uuid, name: &str, analysis: &SideEffectAnalysis) -> Vec<Self> {
+        analysis.resource_usage.io_operations.iter()
+            .filter(|operation| operation.is_blocking)
+            .map(|operation| Self {
+                block_id,
+                name: name.to_string(),
+                call: operation.call.clone().unwrap_or_else(|| format!("{} I/O", operation.resource)),
+                resource: operation.resource.clone(),
+                operation_type: operation.operation_type.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Every blocking call made from an async function among `blocks`
+pub fn find_blocking_in_async(blocks: &[Block]) -> Vec<BlockingInAsyncFinding> {
+    blocks.iter().flat_map(BlockingInAsyncFinding::from_block).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::parser::universal::UniversalParser;
+
+    #[test]
+    fn test_blocking_io_in_async_functions_is_flagged() -> Result<()> {
+        let source = "async fn load_config(path: &str) -> String {\n    std::fs::read_to_string(path).unwrap()\n}\n\nasync fn load_awaited(path: &str) -> String {\n    tokio::fs::read_to_string(path).await.unwrap()\n}\n\nfn load_sync(path: &str) -> String {\n    std::fs::read_to_string(path).unwrap()\n}\n";
+    
+        let result = UniversalParser::new()?.parse_file(source, "rust", "config.rs")?;
+        // Stored blocks keep the modifiers and analysis as JSON
+        let stored = result.blocks.iter().map(|block| Block::from_semantic_block(block, Uuid::new_v4())).collect::<Result<Vec<_>>>()?;
+        let findings: Vec<BlockingInAsyncFinding> = find_blocking_in_async(&stored);
+        assert_eq!(findings.len(), 1, "{:?}", findings);
+        assert_eq!((findings[0].name.as_str(), findings[0].call.as_str()), ("load_config", "std::fs::read_to_string"));
+        assert!(matches!(findings[0].operation_type, crate::core::IoType::Read));
+        let load_config = result.blocks.iter()
+            .find(|block| block.semantic_identity.canonical_name == "load_config")
+            .expect("load_config block");
+        assert_eq!(findings[0].block_id, load_config.id);
+        Ok(())
+    }
+}
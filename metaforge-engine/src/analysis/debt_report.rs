@@ -0,0 +1,87 @@
+//! Technical debt inventory
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::core::{DebtKind, DebtMarker};
+use crate::database::{Block, Container};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtEntry {
+    /// Original path of the file, or the container name when it has none
+    pub file: String,
+    /// Semantic name of the block holding the marker
+    pub block: String,
+    pub kind: DebtKind,
+    pub text: String,
+    /// One-based source line
+    pub line: usize,
+}
+
+/// Every debt marker of one migration, ordered by file and line
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebtReport {
+    pub entries: Vec<DebtEntry>,
+}
+
+impl DebtReport {
+    pub fn from_blocks(containers: &[Container], blocks: &[Block]) -> Self {
+        let files: HashMap<Uuid, String> = containers.iter()
+            .map(|container| (container.id, container.original_path.clone().unwrap_or_else(|| container.name.clone())))
+            .collect();
+
+        let mut entries: Vec<DebtEntry> = blocks.iter()
+            .flat_map(|block| {
+                let file = files.get(&block.container_id).cloned().unwrap_or_default();
+                let name = block.semantic_name.clone().unwrap_or_else(|| block.block_type.clone());
+                DebtMarker::from_abstract_syntax(&block.abstract_syntax).into_iter()
+                    .map(move |marker| DebtEntry {
+                        file: file.clone(),
+                        block: name.clone(),
+                        kind: marker.kind,
+                        text: marker.text,
+                        line: marker.line + 1,
+                    })
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.file, a.line, a.kind).cmp(&(&b.file, b.line, b.kind)));
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries per kind
+    pub fn counts(&self) -> BTreeMap<DebtKind, usize> {
+        let mut counts = BTreeMap::new();
+        for entry in &self.entries {
+            *counts.entry(entry.kind).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// One `file:line  kind  block  text` line per entry, columns aligned
+    pub fn render_table(&self) -> String {
+        let rows: Vec<[String; 4]> = self.entries.iter()
+            .map(|entry| [
+                format!("{}:{}", entry.file, entry.line),
+                entry.kind.to_string(),
+                entry.block.clone(),
+                entry.text.clone(),
+            ])
+            .collect();
+        let widths: Vec<usize> = (0..3)
+            .map(|column| rows.iter().map(|cells| cells[column].len()).max().unwrap_or(0))
+            .collect();
+        rows.iter()
+            .map(|cells| format!(
+                "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+                cells[0], cells[1], cells[2], cells[3],
+                w0 = widths[0], w1 = widths[1], w2 = widths[2],
+            ))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,215 @@
+//! Extraction coverage statistics
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::core::{BlockType, SemanticBlock};
+
+/// Categories always shown in the table, even at zero
+pub const CORE_CATEGORIES: [&str; 4] = ["Function", "Method", "Class", "Import"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub blocks: usize,
+    /// Block counts keyed by category: the `BlockType` name, with functions
+    /// nested directly in a class counted as `Method`
+    pub by_type: BTreeMap<String, usize>,
+    /// Source lines across all files, blank lines included
+    pub total_lines: usize,
+    /// Mean cyclomatic complexity of the blocks that carry complexity
+    /// metrics; `None` when no block does
+    pub average_complexity: Option<f64>,
+    /// Blocks behind `average_complexity`
+    pub measured_blocks: usize,
+}
+
+impl LanguageStats {
+    pub fn count(&self, category: &str) -> usize {
+        self.by_type.get(category).copied().unwrap_or(0)
+    }
+
+    fn merge(&mut self, other: &LanguageStats) {
+        let complexity_total = self.complexity_total() + other.complexity_total();
+        self.files += other.files;
+        self.blocks += other.blocks;
+        self.total_lines += other.total_lines;
+        for (category, count) in &other.by_type {
+            *self.by_type.entry(category.clone()).or_insert(0) += count;
+        }
+        self.measured_blocks += other.measured_blocks;
+        self.average_complexity = (self.measured_blocks > 0)
+            .then(|| complexity_total / self.measured_blocks as f64);
+    }
+
+    fn complexity_total(&self) -> f64 {
+        self.average_complexity.unwrap_or(0.0) * self.measured_blocks as f64
+    }
+}
+
+/// Per-language extraction statistics for one migration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractionStats {
+    pub languages: BTreeMap<String, LanguageStats>,
+}
+
+impl ExtractionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one file's blocks
+    pub fn record_file(&mut self, language: &str, source: &str, blocks: &[SemanticBlock]) {
+        let block_types: HashMap<Uuid, &BlockType> = blocks.iter()
+            .map(|block| (block.id, &block.block_type))
+            .collect();
+
+        let mut file = LanguageStats {
+            files: 1,
+            blocks: blocks.len(),
+            total_lines: source.lines().count(),
+            ..LanguageStats::default()
+        };
+        let mut complexity_total = 0.0;
+        for block in blocks {
+            let parent_type = block.structural_context.parent_block
+                .and_then(|parent| block_types.get(&parent).copied());
+            *file.by_type.entry(category(&block.block_type, parent_type)).or_insert(0) += 1;
+
+            if let Some(metrics) = &block.semantic_metadata.complexity_metrics {
+                complexity_total += metrics.cyclomatic_complexity as f64;
+                file.measured_blocks += 1;
+            }
+        }
+        file.average_complexity = (file.measured_blocks > 0)
+            .then(|| complexity_total / file.measured_blocks as f64);
+
+        self.languages.entry(language.to_string()).or_default().merge(&file);
+    }
+
+    /// All languages combined
+    pub fn total(&self) -> LanguageStats {
+        let mut total = LanguageStats::default();
+        for stats in self.languages.values() {
+            total.merge(stats);
+        }
+        total
+    }
+
+    /// Table columns: the core categories, then any other category seen
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = CORE_CATEGORIES.iter().map(|c| c.to_string()).collect();
+        let mut others: Vec<&String> = self.languages.values()
+            .flat_map(|stats| stats.by_type.keys())
+            .filter(|category| !CORE_CATEGORIES.contains(&category.as_str()))
+            .collect();
+        others.sort();
+        others.dedup();
+        categories.extend(others.into_iter().cloned());
+        categories
+    }
+
+    /// One row per language plus a total row, columns aligned
+    pub fn render_table(&self) -> String {
+        let categories = self.categories();
+        let mut header = vec!["Language".to_string(), "Files".to_string()];
+        header.extend(categories.iter().cloned());
+        header.extend(["Blocks", "Avg complexity", "Lines"].map(String::from));
+
+        let row = |name: &str, stats: &LanguageStats| {
+            let mut cells = vec![name.to_string(), stats.files.to_string()];
+            cells.extend(categories.iter().map(|category| stats.count(category).to_string()));
+            cells.push(stats.blocks.to_string());
+            cells.push(stats.average_complexity.map(|avg| format!("{:.1}", avg)).unwrap_or_else(|| "-".to_string()));
+            cells.push(stats.total_lines.to_string());
+            cells
+        };
+        let mut rows = vec![header];
+        rows.extend(self.languages.iter().map(|(language, stats)| row(language, stats)));
+        if self.languages.len() > 1 {
+            rows.push(row("total", &self.total()));
+        }
+
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|column| rows.iter().map(|cells| cells[column].len()).max().unwrap_or(0))
+            .collect();
+        rows.iter()
+            .map(|cells| {
+                cells.iter().zip(&widths).enumerate()
+                    .map(|(column, (cell, width))| if column == 0 {
+                        format!("{:<width$}", cell, width = width)
+                    } else {
+                        format!("{:>width$}", cell, width = width)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn category(block_type: &BlockType, parent_type: Option<&BlockType>) -> String {
+    match (block_type, parent_type) {
+        (BlockType::Function, Some(BlockType::Class | BlockType::Interface)) => "Method".to_string(),
+        _ => block_type.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::parser::universal::UniversalParser;
+
+    #[test]
+    fn test_extraction_stats_break_blocks_down_by_type() -> Result<()> {
+        let mut parser = UniversalParser::new()?;
+        let python = "import os\n\nclass Store:\n    def load(self):\n        pass\n\n    def save(self):\n        pass\n\ndef main():\n    pass\n";
+        let rust = "struct Counter {\n    n: u32,\n}\n\nimpl Counter {\n    fn bump(&mut self) {\n        if self.n < 10 {\n            self.n += 1;\n        }\n    }\n}\n";
+
+        let mut stats = ExtractionStats::new();
+        for (language, path, source) in [("python", "store.py", python), ("rust", "counter.rs", rust)] {
+            let result = parser.parse_file(source, language, path)?;
+            stats.record_file(language, source, &result.blocks);
+        }
+
+        let python_stats = &stats.languages["python"];
+        assert_eq!(python_stats.files, 1);
+        assert_eq!(python_stats.count("Class"), 1);
+        assert_eq!(python_stats.count("Method"), 2);
+        assert_eq!(python_stats.count("Function"), 1);
+        assert_eq!(python_stats.count("Import"), 1);
+        assert_eq!(python_stats.total_lines, python.lines().count());
+        assert_eq!(python_stats.average_complexity, None);
+
+        // Functions inside an impl are methods; Rust blocks carry complexity metrics
+        let rust_stats = &stats.languages["rust"];
+        assert_eq!(rust_stats.count("Method"), 1);
+        assert_eq!(rust_stats.count("Function"), 0);
+        assert!(rust_stats.average_complexity.is_some());
+
+        let total = stats.total();
+        assert_eq!(total.files, 2);
+        assert_eq!(total.blocks, python_stats.blocks + rust_stats.blocks);
+        assert_eq!(total.count("Method"), 3);
+
+        // Core categories are columns even when a language has none of them
+        let table = stats.render_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("Language  Files  Function  Method  Class  Import"), "{}", table);
+        assert_eq!(lines.len(), 4, "{}", table);
+        assert!(lines[1].starts_with("python"), "{}", table);
+        assert!(lines[3].starts_with("total"), "{}", table);
+
+        let json = serde_json::to_value(&stats)?;
+        assert_eq!(json["languages"]["python"]["by_type"]["Method"], 2);
+        let restored: ExtractionStats = serde_json::from_value(json)?;
+        assert_eq!(restored.languages["rust"].count("Method"), 1);
+
+        Ok(())
+    }
+}
@@ -2,5 +2,8 @@ pub mod property_graph;
 pub mod semantic_queries;
 pub mod dependency_analyzer;
 pub mod call_graph;
+pub mod extraction_stats;
+pub mod debt_report;
+pub mod blocking_async;
 
 pub use dependency_analyzer::*;
@@ -3,6 +3,8 @@ use uuid::Uuid;
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use crate::database::{Database, Block};
+use crate::core::{SideEffectAnalysis, SideEffectType, EffectSeverity, Parameter};
+use super::blocking_async::{find_blocking_in_async, BlockingInAsyncFinding};
 
 /// Comprehensive property graph for semantic code analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -248,6 +250,7 @@ pub struct GraphPath {
 pub struct PropertyGraphEngine {
     db: Database,
     graph_cache: Option<CodePropertyGraph>,
+    blocking_in_async: Vec<BlockingInAsyncFinding>,
 }
 
 impl PropertyGraphEngine {
@@ -255,6 +258,7 @@ impl PropertyGraphEngine {
         Self {
             db,
             graph_cache: None,
+            blocking_in_async: Vec::new(),
         }
     }
 
@@ -302,6 +306,8 @@ impl PropertyGraphEngine {
             },
         };
 
+        self.blocking_in_async = find_blocking_in_async(&blocks);
+        
         // Convert blocks to nodes
         for block in blocks {
             let node = self.block_to_node(&block)?;
@@ -468,9 +474,180 @@ impl PropertyGraphEngine {
             }
         }
 
+        for finding in &self.blocking_in_async {
+            issues.push(PerformanceIssue {
+                issue_type: PerformanceIssueType::SynchronousBlocking,
+                node_id: finding.block_id,
+                severity: Severity::High,
+                description: format!("async fn {} calls blocking {}", finding.name, finding.call),
+                recommendation: "Use the async runtime's equivalent (e.g. tokio::fs) or move the call into spawn_blocking".to_string(),
+                estimated_impact: ImpactLevel::High,
+            });
+        }
+
         Ok(issues)
     }
 
+    /// Blocking I/O calls made from async functions, see `blocking_async`
+    pub fn analyze_blocking_in_async(&self) -> Result<Vec<BlockingInAsyncFinding>> {
+        if self.graph_cache.is_none() {
+            anyhow::bail!("Graph not built");
+        }
+        Ok(self.blocking_in_async.clone())
+    }
+
+    /// Find functions that mutate shared state, ranked by risk.
+    ///
+    /// Risk grows with what is mutated (globals outweigh externals, parameters
+    /// and `self`), the worst recorded side effect, and how many functions
+    /// reach the mutation through the call graph.
+    pub fn analyze_mutations(&self) -> Result<Vec<MutationFinding>> {
+        let graph = self.graph_cache.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Graph not built"))?;
+
+        let mut findings = Vec::new();
+
+        for node in graph.nodes.values() {
+            let mutations = string_array(node.properties.get("mutations"));
+            let side_effects = node.properties.get("side_effects")
+                .map(|value| match value {
+                    PropertyValue::Array(items) => items.clone(),
+                    _ => Vec::new(),
+                })
+                .unwrap_or_default();
+
+            let shared_state_effects: Vec<&HashMap<String, PropertyValue>> = side_effects.iter()
+                .filter_map(|effect| match effect {
+                    PropertyValue::Object(effect) => Some(effect),
+                    _ => None,
+                })
+                .filter(|effect| matches!(effect.get("type"), Some(PropertyValue::String(t)) if t == "GlobalStateMutation"))
+                .collect();
+
+            let shares_state = mutations.iter().any(|m| m == "globals" || m == "externals");
+            if !shares_state && shared_state_effects.is_empty() {
+                continue;
+            }
+
+            let callers = self.direct_callers(graph, node.id);
+            let transitive_callers = self.transitive_callers(graph, node.id);
+
+            let mutation_weight: f64 = mutations.iter()
+                .map(|m| match m.as_str() {
+                    "globals" => 4.0,
+                    "externals" => 3.0,
+                    "parameters" => 2.0,
+                    _ => 1.0,
+                })
+                .sum();
+            let effect_weight = side_effects.iter()
+                .filter_map(|effect| match effect {
+                    PropertyValue::Object(effect) => effect.get("severity"),
+                    _ => None,
+                })
+                .map(|severity| match severity {
+                    PropertyValue::String(s) if s == "Critical" => 4.0,
+                    PropertyValue::String(s) if s == "High" => 3.0,
+                    PropertyValue::String(s) if s == "Medium" => 2.0,
+                    _ => 1.0,
+                })
+                .fold(0.0, f64::max);
+            // Each extra caller widens the blast radius, with diminishing returns
+            let reach = (1.0 + transitive_callers as f64).ln() + 1.0;
+            let risk_score = (mutation_weight + effect_weight) * reach;
+
+            let severity = if risk_score >= 16.0 {
+                Severity::Critical
+            } else if risk_score >= 9.0 {
+                Severity::High
+            } else if risk_score >= 4.0 {
+                Severity::Medium
+            } else {
+                Severity::Low
+            };
+
+            findings.push(MutationFinding {
+                node_id: node.id,
+                name: match node.properties.get("name") {
+                    Some(PropertyValue::String(name)) => name.clone(),
+                    _ => "unnamed".to_string(),
+                },
+                mutations,
+                side_effects: shared_state_effects.iter()
+                    .filter_map(|effect| match effect.get("description") {
+                        Some(PropertyValue::String(description)) => Some(description.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+                callers,
+                transitive_callers,
+                risk_score,
+                severity,
+            });
+        }
+
+        findings.sort_by(|a, b| b.risk_score.partial_cmp(&a.risk_score).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(findings)
+    }
+
+    /// Suggest memoizing pure functions that are called from many sites.
+    ///
+    /// Candidates are ranked by call count, discounted for parameters whose
+    /// values are unlikely to repeat (floats, collections, closures) or that
+    /// can't be cached at all (`&mut`).
+    pub fn suggest_optimizations(&self) -> Result<Vec<OptimizationSuggestion>> {
+        let graph = self.graph_cache.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Graph not built"))?;
+
+        let mut suggestions = Vec::new();
+
+        for node in graph.nodes.values() {
+            if node.node_type != NodeType::Function {
+                continue;
+            }
+            if !matches!(node.properties.get("purity"), Some(PropertyValue::String(p)) if p == "Pure") {
+                continue;
+            }
+
+            let call_count = graph.edges.values()
+                .filter(|edge| edge.target_id == node.id)
+                .filter(|edge| matches!(edge.edge_type, EdgeType::Calls | EdgeType::CallsAsync | EdgeType::Invokes))
+                .count();
+            if call_count < 2 {
+                continue;
+            }
+
+            let parameter_types = string_array(node.properties.get("parameter_types"));
+            let repetition: f64 = parameter_types.iter()
+                .map(|param_type| argument_repetition_likelihood(param_type))
+                .product();
+            let score = call_count as f64 * repetition;
+            if score < 2.0 {
+                continue;
+            }
+
+            let name = match node.properties.get("name") {
+                Some(PropertyValue::String(name)) => name.clone(),
+                _ => "unnamed".to_string(),
+            };
+            let arguments = if parameter_types.is_empty() {
+                "takes no arguments, so every call returns the same value".to_string()
+            } else {
+                format!("takes {} argument(s) likely to repeat", parameter_types.len())
+            };
+
+            suggestions.push(OptimizationSuggestion {
+                block_id: node.id,
+                kind: OptimizationKind::Memoize,
+                rationale: format!("{} is pure, called from {} sites and {}", name, call_count, arguments),
+                score,
+            });
+        }
+
+        suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(suggestions)
+    }
+
     // Helper methods
     fn block_to_node(&self, block: &Block) -> Result<GraphNode> {
         let node_type = match block.block_type.as_str() {
@@ -492,6 +669,56 @@ impl PropertyGraphEngine {
                 .unwrap_or(1)
         ));
 
+        let side_effect_analysis = block.semantic_metadata.as_ref()
+            .and_then(|metadata| metadata.get("side_effect_analysis"))
+            .and_then(|analysis| serde_json::from_value::<SideEffectAnalysis>(analysis.clone()).ok());
+        if let Some(parameters) = block.parameters.as_ref()
+            .and_then(|parameters| serde_json::from_value::<Vec<Parameter>>(parameters.clone()).ok())
+        {
+            let parameter_types = parameters.iter()
+                .map(|parameter| PropertyValue::String(parameter.type_hint.clone().unwrap_or_default()))
+                .collect();
+            properties.insert("parameter_types".to_string(), PropertyValue::Array(parameter_types));
+        }
+
+        if let Some(analysis) = side_effect_analysis {
+            properties.insert("purity".to_string(), PropertyValue::String(format!("{:?}", analysis.purity_level)));
+
+            let mutability = &analysis.mutability;
+            let mutations: Vec<PropertyValue> = [
+                (mutability.mutates_globals, "globals"),
+                (mutability.mutates_externals, "externals"),
+                (mutability.mutates_parameters, "parameters"),
+                (mutability.mutates_self, "self"),
+            ]
+            .into_iter()
+            .filter(|(mutates, _)| *mutates)
+            .map(|(_, target)| PropertyValue::String(target.to_string()))
+            .collect();
+            properties.insert("mutations".to_string(), PropertyValue::Array(mutations));
+
+            let side_effects = analysis.side_effects.iter()
+                .map(|effect| {
+                    let effect_type = match &effect.effect_type {
+                        SideEffectType::Other(other) => other.clone(),
+                        effect_type => format!("{:?}", effect_type),
+                    };
+                    let severity = match effect.severity {
+                        EffectSeverity::Low => "Low",
+                        EffectSeverity::Medium => "Medium",
+                        EffectSeverity::High => "High",
+                        EffectSeverity::Critical => "Critical",
+                    };
+                    PropertyValue::Object(HashMap::from([
+                        ("type".to_string(), PropertyValue::String(effect_type)),
+                        ("description".to_string(), PropertyValue::String(effect.description.clone())),
+                        ("severity".to_string(), PropertyValue::String(severity.to_string())),
+                    ]))
+                })
+                .collect();
+            properties.insert("side_effects".to_string(), PropertyValue::Array(side_effects));
+        }
+
         let mut labels = HashSet::new();
         labels.insert(block.block_type.clone());
         if let Some(metadata) = &block.metadata {
@@ -540,6 +767,33 @@ impl PropertyGraphEngine {
         })
     }
 
+    fn direct_callers(&self, graph: &CodePropertyGraph, node_id: Uuid) -> Vec<Uuid> {
+        let mut callers: Vec<Uuid> = graph.edges.values()
+            .filter(|edge| edge.target_id == node_id)
+            .filter(|edge| matches!(edge.edge_type, EdgeType::Calls | EdgeType::CallsAsync | EdgeType::Invokes))
+            .map(|edge| edge.source_id)
+            .collect();
+        callers.sort();
+        callers.dedup();
+        callers
+    }
+
+    /// Number of distinct functions that can reach `node_id` through calls
+    fn transitive_callers(&self, graph: &CodePropertyGraph, node_id: Uuid) -> usize {
+        let mut visited = HashSet::new();
+        let mut stack = vec![node_id];
+
+        while let Some(current) = stack.pop() {
+            for caller in self.direct_callers(graph, current) {
+                if caller != node_id && visited.insert(caller) {
+                    stack.push(caller);
+                }
+            }
+        }
+
+        visited.len()
+    }
+
     fn add_node_to_graph(&self, graph: &mut CodePropertyGraph, node: GraphNode) {
         // Add to main collection
         graph.nodes.insert(node.id, node.clone());
@@ -718,6 +972,35 @@ pub enum PerformanceIssueType {
     UnindexedQuery,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationSuggestion {
+    pub block_id: Uuid,
+    pub kind: OptimizationKind,
+    pub rationale: String,
+    /// Ranking score; higher means a more promising candidate
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OptimizationKind {
+    Memoize,
+}
+
+/// A function that mutates shared state, with the callers it can affect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MutationFinding {
+    pub node_id: Uuid,
+    pub name: String,
+    /// What the function mutates: `globals`, `externals`, `parameters`, `self`
+    pub mutations: Vec<String>,
+    /// Descriptions of the recorded shared-state side effects
+    pub side_effects: Vec<String>,
+    pub callers: Vec<Uuid>,
+    pub transitive_callers: usize,
+    pub risk_score: f64,
+    pub severity: Severity,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Severity {
     Low,
@@ -733,3 +1016,35 @@ pub enum ImpactLevel {
     High,
     Critical,
 }
+
+fn string_array(value: Option<&PropertyValue>) -> Vec<String> {
+    match value {
+        Some(PropertyValue::Array(items)) => items.iter()
+            .filter_map(|item| match item {
+                PropertyValue::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// How likely calls are to repeat an argument of this type, from 0 to 1
+fn argument_repetition_likelihood(param_type: &str) -> f64 {
+    let param_type = param_type.trim();
+    if param_type.starts_with("&mut") || param_type.contains("Fn") || param_type.contains("Callable") {
+        // Mutated or callable arguments can't be used as a cache key
+        return 0.0;
+    }
+
+    let base = param_type.trim_start_matches('&').trim();
+    match base {
+        "bool" | "char" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64"
+        | "usize" | "isize" | "int" | "str" | "String" | "string" | "number" => 1.0,
+        "f32" | "f64" | "float" => 0.3,
+        "" => 0.6,
+        _ if base.starts_with("Vec") || base.starts_with("HashMap") || base.starts_with('[')
+            || base.starts_with("list") || base.starts_with("dict") => 0.3,
+        _ => 0.6,
+    }
+}
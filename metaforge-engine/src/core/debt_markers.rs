@@ -0,0 +1,52 @@
+//! Technical debt markers
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Key under `normalized_ast` / `abstract_syntax` holding a block's markers
+pub const DEBT_MARKERS_KEY: &str = "debt_markers";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebtKind {
+    /// A comment containing `TODO`
+    Todo,
+    /// A comment containing `FIXME`
+    Fixme,
+    /// A body that only stubs the function out: `pass`, `...`, `todo!()`,
+    /// `unimplemented!()`, `raise NotImplementedError`, `throw new Error("not implemented")`
+    Unimplemented,
+}
+
+impl fmt::Display for DebtKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DebtKind::Todo => "TODO",
+            DebtKind::Fixme => "FIXME",
+            DebtKind::Unimplemented => "unimplemented",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtMarker {
+    pub kind: DebtKind,
+    /// The comment from the marker word on, e.g. `TODO: retry on timeout`,
+    /// or the stub statement, e.g. `raise NotImplementedError`
+    pub text: String,
+    /// Zero-based source line of the marker
+    pub line: usize,
+}
+
+impl DebtMarker {
+    pub fn new(kind: DebtKind, text: impl Into<String>, line: usize) -> Self {
+        Self { kind, text: text.into(), line }
+    }
+
+    /// Read the markers recorded on a block's abstract syntax; none when absent
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Vec<Self> {
+        abstract_syntax.get(DEBT_MARKERS_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+}
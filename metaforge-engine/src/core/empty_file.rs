@@ -0,0 +1,98 @@
+//! Empty and whitespace-only files
+
+use serde::{Deserialize, Serialize};
+
+/// Key under a container's `parsing_metadata` marking an empty file
+pub const EMPTY_FILE_KEY: &str = "empty_file";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmptyFile {
+    /// The file's whole content, empty or whitespace only
+    pub content: String,
+}
+
+/// What migration does with empty and whitespace-only files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyFilePolicy {
+    /// Leave them out of the migration, counting them as skipped
+    Skip,
+    /// Store a container flagged as empty so they regenerate unchanged
+    #[default]
+    Preserve,
+}
+
+impl EmptyFile {
+    /// `Some` when `source` has nothing but whitespace
+    pub fn detect(source: &str) -> Option<Self> {
+        source.trim().is_empty().then(|| Self { content: source.to_string() })
+    }
+
+    /// The empty-file record in a container's parsing metadata
+    pub fn from_parsing_metadata(metadata: Option<&serde_json::Value>) -> Option<Self> {
+        let empty_file = metadata?.get(EMPTY_FILE_KEY)?;
+        serde_json::from_value(empty_file.clone()).ok()
+    }
+
+    /// `metadata` with this record added, keeping its other entries
+    pub fn store_in(&self, metadata: Option<serde_json::Value>) -> serde_json::Value {
+        let mut metadata = match metadata {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert(EMPTY_FILE_KEY.to_string(), serde_json::to_value(self).unwrap_or_default());
+        serde_json::Value::Object(metadata)
+    }
+}
+
+impl EmptyFilePolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "skip" => Some(Self::Skip),
+            "preserve" => Some(Self::Preserve),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::database::Container;
+    use crate::generator::HierarchicalGenerator;
+    use crate::generator::templates::TemplateEngine;
+    use crate::scanner::FileScanner;
+
+    /// Test that empty and whitespace-only files regenerate byte for byte,
+    /// not as the template's header and footer
+    #[test]
+    fn test_empty_files_round_trip_unchanged() -> Result<()> {
+        let repo = std::env::temp_dir().join(format!("metaforge-empty-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(repo.join("pkg"))?;
+        std::fs::write(repo.join("pkg/__init__.py"), "")?;
+        std::fs::write(repo.join("pkg/blank.py"), "\n  \n")?;
+        let scanned = FileScanner::new().scan_directory(&repo)?;
+        std::fs::remove_dir_all(&repo)?;
+        assert_eq!(scanned.len(), 2);
+    
+        for file in scanned {
+            let empty_file = EmptyFile::detect(&file.content).expect("empty file detected");
+            let mut container = Container {
+                language: Some("python".to_string()),
+                original_path: Some(file.path.to_string_lossy().to_string()),
+                ..Container::new("module", "file")
+            };
+            container.parsing_metadata = Some(empty_file.store_in(None));
+            assert_eq!(EmptyFile::from_parsing_metadata(container.parsing_metadata.as_ref()), Some(empty_file));
+        
+            let generator = HierarchicalGenerator::from_blocks(&container, Vec::new());
+            assert!(generator.is_empty_file());
+            assert_eq!(generator.generate()?, file.content);
+            assert_eq!(TemplateEngine::new().render_file(&container, &[], "python")?, file.content);
+        }
+    
+        assert_eq!(EmptyFile::detect("import os\n"), None);
+        Ok(())
+    }
+}
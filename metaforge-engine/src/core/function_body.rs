@@ -0,0 +1,267 @@
+//! Function bodies
+
+use ast_extractor::ExpressionAST;
+use serde::{Deserialize, Serialize};
+use super::jsx::JsxElement;
+
+/// Key under `normalized_ast` / `abstract_syntax` where extractors attach the body
+pub const FUNCTION_BODY_KEY: &str = "function_body";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementKind {
+    /// `x = …`, `x += …`, `let x = …`, `const x = …`
+    Assignment,
+    /// `return …`, or a Rust tail expression when `implicit`
+    Return,
+    Yield,
+    /// A call or other expression evaluated for its effect
+    Expression,
+    /// `if`, loops, `match`/`switch`, `try`, `with`, `break`, `continue`, `raise`/`throw`
+    ControlFlow,
+    /// Nested definitions, `pass` and anything else without an expression
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyStatement {
+    pub kind: StatementKind,
+    /// A `Return` written without the keyword: a Rust tail expression or a
+    /// JavaScript arrow function's expression body
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub implicit: bool,
+    /// Left-hand side of an assignment, e.g. `self.total` or `(a, b)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// The value returned, yielded or assigned, the expression statement
+    /// itself, or the condition of a control-flow statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<ExpressionAST>,
+    /// Element tree of `expression` when it is JSX, e.g. a component's `return (<ul>…</ul>)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsx: Option<JsxElement>,
+    /// Context managers of a Python `with` statement, whose block is `body`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_clause: Option<WithClause>,
+    /// Statements of the branches and loop bodies nested in a control-flow statement
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub body: Vec<BodyStatement>,
+    /// Source text, continuation lines dedented to the statement's first line
+    pub code: String,
+    /// Zero-based line of the statement's first character
+    pub line: usize,
+}
+
+impl BodyStatement {
+    pub fn new(kind: StatementKind, code: impl Into<String>, line: usize) -> Self {
+        Self {
+            kind,
+            implicit: false,
+            target: None,
+            expression: None,
+            jsx: None,
+            with_clause: None,
+            body: Vec::new(),
+            code: code.into(),
+            line,
+        }
+    }
+
+    pub fn with_expression(mut self, expression: ExpressionAST) -> Self {
+        self.expression = Some(expression);
+        self
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn implicit(mut self) -> Self {
+        self.implicit = true;
+        self
+    }
+
+    /// This statement followed by every statement nested in it, depth first
+    pub fn walk(&self) -> Box<dyn Iterator<Item = &BodyStatement> + '_> {
+        Box::new(std::iter::once(self).chain(self.body.iter().flat_map(BodyStatement::walk)))
+    }
+}
+
+/// The managers of `with a() as x, b() as y:`, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithClause {
+    pub managers: Vec<ContextManager>,
+    /// `async with`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+    /// Managers grouped as `with (a() as x, b() as y):`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub parenthesized: bool,
+}
+
+/// One manager of a `with` statement, e.g. `open(path) as fh`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextManager {
+    pub expression: ExpressionAST,
+    /// The `as` binding, e.g. `fh` or `(first, second)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionBody {
+    pub statements: Vec<BodyStatement>,
+}
+
+impl FunctionBody {
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Parse a stored `body_ast` value; malformed values yield `None`
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        if value.is_null() {
+            return None;
+        }
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// Read the body an extractor attached to a block's abstract syntax
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        abstract_syntax.get(FUNCTION_BODY_KEY).and_then(Self::from_value)
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::database::Block;
+    use crate::generator::statements::render_statement;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::UniversalParser;
+
+    /// Every `Return` at any depth of `body`, explicit or implicit
+    fn return_statements(body: &FunctionBody) -> Vec<&BodyStatement> {
+        body.statements.iter()
+            .flat_map(BodyStatement::walk)
+            .filter(|statement| statement.kind == StatementKind::Return)
+            .collect()
+    }
+
+    #[test]
+    fn test_function_bodies_classify_statements_and_returns() -> Result<()> {
+        let mut parser = UniversalParser::new()?;
+        let rust = "fn clamp_total(items: &[u32]) -> u32 {\n    let mut sum = 0;\n    for item in items {\n        sum += item;\n    }\n    if sum > 100 {\n        return 100;\n    }\n    sum\n}\n";
+        let parse_result = parser.parse_file(rust, "rust", "lib.rs")?;
+        let body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast)
+            .expect("body attached");
+
+        let kinds: Vec<StatementKind> = body.statements.iter().map(|statement| statement.kind).collect();
+        assert_eq!(kinds, vec![StatementKind::Assignment, StatementKind::ControlFlow, StatementKind::ControlFlow, StatementKind::Return]);
+        assert_eq!(body.statements[0].target.as_deref(), Some("sum"));
+        assert_eq!(body.statements[1].body[0].kind, StatementKind::Assignment);
+
+        // The early `return` is explicit, the tail expression implicit
+        let returns: Vec<(bool, &str)> = return_statements(&body).iter()
+            .map(|statement| (statement.implicit, statement.expression.as_ref().unwrap().source_text.as_str()))
+            .collect();
+        assert_eq!(returns, vec![(false, "100"), (true, "sum")]);
+
+        // A tail `if` makes each branch's tail a return; a `;` makes it a statement
+        let branches = "fn pick(flag: bool) -> u32 {\n    log(flag);\n    if flag { 1 } else { 2 }\n}\n";
+        let parse_result = parser.parse_file(branches, "rust", "lib.rs")?;
+        let body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast).unwrap();
+        assert_eq!(body.statements[0].kind, StatementKind::Expression);
+        assert!(return_statements(&body).iter().all(|statement| statement.implicit));
+        assert_eq!(return_statements(&body).len(), 2);
+
+        let python = "def running(items):\n    total = 0\n    for item in items:\n        total += item\n        yield total\n    print(total)\n    return total * 2\n";
+        let parse_result = parser.parse_file(python, "python", "app.py")?;
+        let body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast).unwrap();
+        let kinds: Vec<StatementKind> = body.statements.iter().flat_map(|statement| statement.walk()).map(|statement| statement.kind).collect();
+        assert_eq!(kinds, vec![
+            StatementKind::Assignment, StatementKind::ControlFlow, StatementKind::Assignment,
+            StatementKind::Yield, StatementKind::Expression, StatementKind::Return,
+        ]);
+        let returned = return_statements(&body)[0].expression.as_ref().unwrap();
+        assert_eq!(returned.expression_type, "binary_operator");
+        assert!(!return_statements(&body)[0].implicit);
+
+        // The stored body regenerates through the template engine
+        let block = Block {
+            body_ast: Some(body.to_value()),
+            source_language: Some("python".to_string()),
+            ..Block::new(Uuid::new_v4(), "Function", "running", serde_json::json!({}))
+        };
+        let rendered = TemplateEngine::new().render_block(&block, "python")?;
+        assert!(rendered.contains("    for item in items:\n        total += item\n        yield total\n"), "{}", rendered);
+        assert!(rendered.contains("    return total * 2"), "{}", rendered);
+
+        // Only Rust keeps a bare tail expression; an arrow body gains `return`
+        let arrow = parser.parse_file("const double = (x) => x * 2;\n", "javascript", "app.js")?;
+        let body = FunctionBody::from_abstract_syntax(&arrow.blocks[0].syntax_preservation.normalized_ast).unwrap();
+        assert!(body.statements[0].implicit);
+        assert_eq!(render_statement(&body.statements[0], "javascript"), "return x * 2;");
+        let tail = BodyStatement::new(StatementKind::Return, "sum", 8)
+            .with_expression(returned.clone())
+            .implicit();
+        assert_eq!(render_statement(&tail, "rust"), "total * 2");
+
+        Ok(())
+    }
+
+    /// Test that `with` statements keep their managers and `as` bindings through the body AST
+    #[test]
+    fn test_python_with_statements_round_trip_through_the_body_ast() -> Result<()> {
+        let mut parser = UniversalParser::new()?;
+        let source = "def copy(source, destination):\n    with lock:\n        with open(source) as reader:\n            data = reader.read()\n    with open(source) as x, open(destination, \"w\") as y:\n        y.write(x.read())\n    with (connect() as db, db.cursor() as cursor):\n        cursor.execute(data)\n";
+        let parse_result = parser.parse_file(source, "python", "copy.py")?;
+        let body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast)
+            .expect("body attached");
+
+        let managers = |statement: &BodyStatement| -> Vec<(String, Option<String>)> {
+            statement.with_clause.as_ref().expect("with clause captured").managers.iter()
+                .map(|manager| (manager.expression.source_text.clone(), manager.target.clone()))
+                .collect()
+        };
+        assert_eq!(managers(&body.statements[0]), vec![("lock".to_string(), None)]);
+        assert_eq!(managers(&body.statements[0].body[0]), vec![("open(source)".to_string(), Some("reader".to_string()))]);
+        assert_eq!(managers(&body.statements[1]), vec![
+            ("open(source)".to_string(), Some("x".to_string())),
+            ("open(destination, \"w\")".to_string(), Some("y".to_string())),
+        ]);
+        assert!(body.statements[2].with_clause.as_ref().unwrap().parenthesized);
+        assert_eq!(managers(&body.statements[2]), vec![
+            ("connect()".to_string(), Some("db".to_string())),
+            ("db.cursor()".to_string(), Some("cursor".to_string())),
+        ]);
+
+        // The function regenerates from the stored managers and parses back the same
+        let block = Block {
+            body_ast: Some(body.to_value()),
+            source_language: Some("python".to_string()),
+            ..Block::new(Uuid::new_v4(), "Function", "copy", serde_json::json!({}))
+        };
+        let rendered = TemplateEngine::new().render_block(&block, "python")?;
+        assert!(rendered.contains("    with lock:\n        with open(source) as reader:\n            data = reader.read()\n"), "{}", rendered);
+        let reparsed = parser.parse_file(&rendered, "python", "copy.py")?;
+        let regenerated = FunctionBody::from_abstract_syntax(&reparsed.blocks[0].syntax_preservation.normalized_ast)
+            .expect("body attached");
+        let all = |body: &FunctionBody| -> Vec<Vec<(String, Option<String>)>> {
+            body.statements.iter().flat_map(BodyStatement::walk)
+                .filter(|statement| statement.with_clause.is_some())
+                .map(&managers)
+                .collect()
+        };
+        assert_eq!(all(&regenerated), all(&body));
+        Ok(())
+    }
+}
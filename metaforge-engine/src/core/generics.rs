@@ -0,0 +1,128 @@
+//! Generic parameter lists
+
+use super::semantic_block::{GenericInfo, GenericParameter};
+
+impl GenericParameter {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            bounds: Vec::new(),
+            constraints: Vec::new(),
+            default_type: None,
+            variance: None,
+        }
+    }
+
+    /// Set the bounds, keeping the `constraints` alias in step
+    pub fn with_bounds(mut self, bounds: Vec<String>) -> Self {
+        self.constraints = bounds.clone();
+        self.bounds = bounds;
+        self
+    }
+
+    pub fn with_default(mut self, default_type: Option<String>) -> Self {
+        self.default_type = default_type;
+        self
+    }
+
+    fn render(&self, language: &str) -> String {
+        let mut rendered = self.name.clone();
+        if !self.bounds.is_empty() {
+            match language {
+                "rust" => rendered.push_str(&format!(": {}", self.bounds.join(" + "))),
+                "go" => rendered.push_str(&format!(" {}", self.bounds.join(" | "))),
+                _ => rendered.push_str(&format!(" extends {}", self.bounds.join(" & "))),
+            }
+        }
+        if let Some(default_type) = self.default_type.as_ref().filter(|_| !matches!(language, "java" | "go")) {
+            rendered.push_str(&format!(" = {}", default_type));
+        }
+        rendered
+    }
+}
+
+impl GenericInfo {
+    /// Parameters in declaration order, mirrored into the `parameters` alias
+    pub fn new(parameters: Vec<GenericParameter>) -> Self {
+        Self {
+            generic_parameters: parameters.clone(),
+            parameters,
+            constraints: Vec::new(),
+            variance: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generic_parameters.is_empty()
+    }
+
+    /// Parse a stored `generics` value; malformed values yield `None`
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// The parameter list in `language`'s syntax, brackets included, or an
+    /// empty string when there are no parameters
+    pub fn render(&self, language: &str) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let params = self.generic_parameters.iter()
+            .map(|param| param.render(language))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if language == "go" {
+            format!("[{}]", params)
+        } else {
+            format!("<{}>", params)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use crate::core::BlockType;
+    use crate::database::{Block, Container};
+    use crate::generator::HierarchicalGenerator;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that a TypeScript generic class keeps its bounded and defaulted type
+    /// parameters, and its generic method and function keep theirs
+    #[test]
+    fn test_typescript_generic_class_round_trip() -> Result<()> {
+        let source = "class Box<T extends Comparable<T>, U = string> {\n  compare<K extends keyof T>(other: Box<T>, key: K): number {\n    return 0;\n  }\n}\n\nfunction first<T extends { id: number }>(items: T[]): T {\n  return items[0];\n}\n";
+        let generics = |source: &str| -> Result<Vec<(String, String)>> {
+            Ok(UniversalParser::new()?.parse_file(source, "typescript", "box.ts")?.blocks.iter()
+                .filter_map(|block| block.semantic_metadata.generics.as_ref()
+                    .map(|generics| (block.semantic_identity.canonical_name.clone(), generics.render("typescript"))))
+                .collect())
+        };
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "typescript", "box.ts")?;
+        let class = parse_result.blocks.iter()
+            .find(|block| block.block_type == BlockType::Class)
+            .and_then(|block| block.semantic_metadata.generics.as_ref())
+            .expect("class generics extracted");
+        assert_eq!(class.generic_parameters[0].bounds, vec!["Comparable<T>"]);
+        assert_eq!(class.generic_parameters[1].default_type.as_deref(), Some("string"));
+    
+        let container = Container {
+            language: Some("typescript".to_string()),
+            original_path: Some("box.ts".to_string()),
+            ..Container::new("box", "file")
+        };
+        let stored = parse_result.blocks.iter().map(|block| Block::from_semantic_block(block, container.id)).collect::<Result<Vec<_>>>()?;
+    
+        let generated = HierarchicalGenerator::from_blocks(&container, stored).generate()?;
+        for declaration in [
+            "class Box<T extends Comparable<T>, U = string> {",
+            "  compare<K extends keyof T>(other: Box<T>, key: K): number {",
+            "function first<T extends { id: number }>(items: T[]): T {",
+        ] {
+            assert!(generated.contains(declaration), "{} missing from\n{}", declaration, generated);
+        }
+        assert_eq!(generics(&generated)?, generics(source)?);
+        Ok(())
+    }
+}
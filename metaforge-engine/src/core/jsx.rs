@@ -0,0 +1,197 @@
+//! JSX elements
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsxElement {
+    /// Element name, e.g. `div`, `Todo.Item` or `svg:rect`; `None` for a fragment `<>…</>`
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<JsxAttribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<JsxChild>,
+    /// Written as `<Tag />`
+    #[serde(default)]
+    pub self_closing: bool,
+    /// The closing tag starts its own line
+    #[serde(default)]
+    pub multiline: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JsxAttribute {
+    /// `disabled`, `className="todos"`, `count={count}` or `icon=<Star />`
+    Named {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<JsxNode>,
+    },
+    /// `{...props}`, stored without the braces
+    Expression { expression: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum JsxNode {
+    /// Literal text; for an attribute value, the quoted string
+    Text(String),
+    /// An embedded `{expression}`, stored without the braces
+    Expression(String),
+    Element(Box<JsxElement>),
+}
+
+/// What separates a child from whatever precedes it in the source. JSX
+/// drops whitespace that contains a line break but keeps a plain space, so
+/// `Hello {name}` and `Hello{name}` render differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsxSpacing {
+    #[default]
+    None,
+    Space,
+    Newline,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsxChild {
+    #[serde(default)]
+    pub spacing: JsxSpacing,
+    pub node: JsxNode,
+}
+
+impl JsxElement {
+    /// Render the element as it would start at column `indent`. The first
+    /// line carries no indentation; children on their own lines are indented
+    /// two spaces deeper.
+    pub fn render(&self, indent: &str) -> String {
+        let mut rendered = String::from("<");
+        rendered.push_str(self.tag.as_deref().unwrap_or_default());
+        for attribute in &self.attributes {
+            rendered.push(' ');
+            rendered.push_str(&match attribute {
+                JsxAttribute::Named { name, value: None } => name.clone(),
+                JsxAttribute::Named { name, value: Some(value) } => format!("{}={}", name, value.render(indent)),
+                JsxAttribute::Expression { expression } => format!("{{{}}}", reindent(expression, indent)),
+            });
+        }
+        if self.self_closing {
+            rendered.push_str(" />");
+            return rendered;
+        }
+        rendered.push('>');
+
+        let child_indent = format!("{}  ", indent);
+        // Indentation of the line being written, which a child that doesn't
+        // start its own line continues from
+        let mut line_indent = indent;
+        for child in &self.children {
+            match child.spacing {
+                JsxSpacing::Newline => {
+                    rendered.push('\n');
+                    rendered.push_str(&child_indent);
+                    line_indent = &child_indent;
+                }
+                JsxSpacing::Space => rendered.push(' '),
+                JsxSpacing::None => {}
+            }
+            rendered.push_str(&child.node.render(line_indent));
+        }
+        if self.multiline {
+            rendered.push('\n');
+            rendered.push_str(indent);
+        }
+        rendered.push_str("</");
+        rendered.push_str(self.tag.as_deref().unwrap_or_default());
+        rendered.push('>');
+        rendered
+    }
+}
+
+impl JsxNode {
+    fn render(&self, indent: &str) -> String {
+        match self {
+            JsxNode::Text(text) => text.clone(),
+            JsxNode::Expression(expression) => format!("{{{}}}", reindent(expression, indent)),
+            JsxNode::Element(element) => element.render(indent),
+        }
+    }
+}
+
+/// Prefix every continuation line of dedented text with `indent`
+fn reindent(text: &str, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| if index == 0 || line.is_empty() { line.to_string() } else { format!("{}{}", indent, line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::core::{BodyStatement, FunctionBody, StatementKind};
+    use crate::database::Block;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::UniversalParser;
+
+    /// `element` followed by every element nested in its children and attribute
+    /// values, depth first
+    fn jsx_elements(element: &JsxElement) -> Vec<&JsxElement> {
+        let nested = element.attributes.iter()
+            .filter_map(|attribute| match attribute {
+                JsxAttribute::Named { value: Some(value), .. } => Some(value),
+                _ => None,
+            })
+            .chain(element.children.iter().map(|child| &child.node))
+            .filter_map(|node| match node {
+                JsxNode::Element(element) => Some(jsx_elements(element)),
+                _ => None,
+            })
+            .flatten();
+        std::iter::once(element).chain(nested).collect()
+    }
+
+    #[test]
+    fn test_jsx_component_round_trips_through_the_body_ast() -> Result<()> {
+        let mut parser = UniversalParser::new()?;
+        let returned_jsx = |body: &FunctionBody| body.statements.iter()
+            .flat_map(BodyStatement::walk)
+            .find(|statement| statement.kind == StatementKind::Return)
+            .and_then(|statement| statement.jsx.clone());
+        let component = "function TodoList({ items, title }) {\n  return (\n    <section className=\"todos\">\n      <h2>{title} ({items.length})</h2>\n      <ul>\n        {items.map(item => <li key={item.id}>{item.text}</li>)}\n      </ul>\n      <Footer total={items.length} {...rest} />\n    </section>\n  );\n}\n";
+
+        for (language, path) in [("javascript", "TodoList.jsx"), ("tsx", "TodoList.tsx")] {
+            let parse_result = parser.parse_file(component, language, path)?;
+            let body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast)
+                .expect("body attached");
+            let jsx = returned_jsx(&body).expect("element tree captured");
+
+            assert_eq!(jsx.tag.as_deref(), Some("section"));
+            assert_eq!(jsx.attributes, vec![JsxAttribute::Named {
+                name: "className".to_string(),
+                value: Some(JsxNode::Text("\"todos\"".to_string())),
+            }]);
+            let tags: Vec<Option<&str>> = jsx_elements(&jsx).iter().map(|element| element.tag.as_deref()).collect();
+            assert_eq!(tags, vec![Some("section"), Some("h2"), Some("ul"), Some("Footer")]);
+            let heading = jsx_elements(&jsx)[1].clone();
+            assert_eq!(heading.children[0].node, JsxNode::Expression("title".to_string()));
+            assert_eq!(heading.children[1].node, JsxNode::Text("(".to_string()));
+
+            // The regenerated component parses back to the same element tree
+            let block = Block {
+                body_ast: Some(body.to_value()),
+                source_language: Some("javascript".to_string()),
+                ..Block::new(Uuid::new_v4(), "Function", "TodoList", serde_json::json!({}))
+            };
+            let rendered = TemplateEngine::new().render_block(&block, "javascript")?;
+            let reparsed = parser.parse_file(&rendered, language, path)?;
+            let regenerated = FunctionBody::from_abstract_syntax(&reparsed.blocks[0].syntax_preservation.normalized_ast)
+                .expect("body attached");
+            assert_eq!(returned_jsx(&regenerated), Some(jsx));
+        }
+        Ok(())
+    }
+}
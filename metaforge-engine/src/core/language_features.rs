@@ -0,0 +1,105 @@
+//! Language features
+
+use serde::{Deserialize, Serialize};
+
+/// Key under `normalized_ast` / `abstract_syntax` where extractors attach features
+pub const LANGUAGE_FEATURES_KEY: &str = "language_features";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageFeatures {
+    /// Type parameter names in declaration order, e.g. `["K", "V"]`
+    pub generics: Vec<String>,
+    /// Lifetime parameters including the tick, e.g. `["'a"]`
+    pub lifetimes: Vec<String>,
+    /// Bounds written inline on a parameter, e.g. `["T: Clone + Send"]`
+    pub trait_bounds: Vec<String>,
+    /// Predicates of a `where` clause without the keyword, e.g. `T: Display, U: Debug`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub where_clause: Option<String>,
+    /// Declared type of a variable or field, e.g. `number`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_annotation: Option<String>,
+}
+
+impl LanguageFeatures {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Parse a stored `language_features` value; malformed values yield `None`
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        if value.is_null() {
+            return None;
+        }
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// Read the features an extractor attached to a block's abstract syntax
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        abstract_syntax.get(LANGUAGE_FEATURES_KEY).and_then(Self::from_value)
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+
+    /// Inline bound for a type parameter, e.g. `Clone + Send` for `T`
+    pub fn bound_for(&self, param: &str) -> Option<&str> {
+        self.trait_bounds.iter().find_map(|bound| {
+            let (name, rest) = bound.split_once(':')?;
+            (name.trim() == param).then(|| rest.trim())
+        })
+    }
+
+    /// Generic parameter list as written between `<` and `>`: lifetimes first,
+    /// then type parameters with their inline bounds
+    pub fn generic_params(&self) -> Vec<String> {
+        let types = self.generics.iter().map(|param| match self.bound_for(param) {
+            Some(bound) => format!("{}: {}", param, bound),
+            None => param.clone(),
+        });
+        self.lifetimes.iter().cloned().chain(types).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::database::Block;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::UniversalParser;
+
+    #[test]
+    fn test_language_features_flow_from_extractor_to_templates() -> Result<()> {
+        let source = "pub fn largest<'a, T: PartialOrd + Copy>(items: &'a [T]) -> T where T: std::fmt::Debug {\n    items[0]\n}\n";
+        let parse_result = UniversalParser::new()?.parse_file(source, "rust", "lib.rs")?;
+        let function = parse_result.blocks.iter()
+            .find(|block| block.semantic_identity.canonical_name == "largest")
+            .expect("function extracted");
+
+        let features = LanguageFeatures::from_abstract_syntax(&function.syntax_preservation.normalized_ast)
+            .expect("features attached");
+        assert_eq!(features.generics, vec!["T"]);
+        assert_eq!(features.lifetimes, vec!["'a"]);
+        assert_eq!(features.bound_for("T"), Some("PartialOrd + Copy"));
+        assert_eq!(features.where_clause.as_deref(), Some("T: std::fmt::Debug"));
+
+        // Rows stored before the column was filled fall back to the abstract syntax
+        let mut block = Block {
+            return_type: Some("i32".to_string()),
+            modifiers: Some(vec!["pub".to_string()]),
+            ..Block::new(Uuid::new_v4(), "Function", "add", function.syntax_preservation.normalized_ast.clone())
+        };
+        assert_eq!(block.language_features_typed(), Some(features.clone()));
+
+        block.language_features = Some(features.to_value());
+        let rendered = TemplateEngine::new().render_block(&block, "rust")?;
+        assert!(rendered.contains("fn add<'a, T: PartialOrd + Copy>("), "{}", rendered);
+        assert!(rendered.contains(" where T: std::fmt::Debug {"), "{}", rendered);
+
+        Ok(())
+    }
+}
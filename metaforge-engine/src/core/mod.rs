@@ -1,3 +1,18 @@
+pub mod debt_markers;
+pub mod empty_file;
+pub mod function_body;
+pub mod generics;
+pub mod jsx;
+pub mod language_features;
+pub mod normalize;
+pub mod preamble;
 pub mod semantic_block;
 
+pub use debt_markers::{DebtKind, DebtMarker, DEBT_MARKERS_KEY};
+pub use empty_file::{EmptyFile, EmptyFilePolicy};
+pub use function_body::{BodyStatement, ContextManager, FunctionBody, StatementKind, WithClause, FUNCTION_BODY_KEY};
+pub use jsx::{JsxAttribute, JsxChild, JsxElement, JsxNode, JsxSpacing};
+pub use language_features::{LanguageFeatures, LANGUAGE_FEATURES_KEY};
+pub use normalize::{normalize_block, NormalizedBlock};
+pub use preamble::FilePreamble;
 pub use semantic_block::*;
@@ -0,0 +1,219 @@
+//! Canonical block forms for hashing
+
+use serde_json::Value;
+use crate::database::Block;
+
+/// Keys that record where a block was, not what it is
+pub const POSITION_KEYS: &[&str] = &[
+    "start_line", "end_line", "start_column", "end_column",
+    "start_byte", "end_byte", "line", "column", "position", "index",
+];
+
+/// A block reduced to its formatting-independent semantic content
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedBlock {
+    pub block_type: String,
+    pub semantic_name: Option<String>,
+    pub abstract_syntax: Value,
+    pub parameters: Value,
+    pub return_type: Option<String>,
+    /// Sorted, since modifier order carries no meaning
+    pub modifiers: Vec<String>,
+}
+
+impl NormalizedBlock {
+    /// Hash of structure and interface; stable across reformatting
+    pub fn semantic_hash(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        let parts: [&str; 6] = [
+            self.block_type.as_str(),
+            self.semantic_name.as_deref().unwrap_or(""),
+            &self.abstract_syntax.to_string(),
+            &self.parameters.to_string(),
+            self.return_type.as_deref().unwrap_or(""),
+            &self.modifiers.join(" "),
+        ];
+        for part in parts {
+            // Separators keep ("ab", "c") and ("a", "bc") apart
+            hasher.update(part.as_bytes());
+            hasher.update(&[0]);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+}
+
+pub fn normalize_block(block: &Block) -> NormalizedBlock {
+    let mut modifiers = block.modifiers.clone().unwrap_or_default();
+    modifiers.sort();
+
+    NormalizedBlock {
+        block_type: block.block_type.clone(),
+        semantic_name: block.semantic_name.clone(),
+        abstract_syntax: normalize_value(&block.abstract_syntax),
+        parameters: block.parameters.as_ref().map(normalize_value).unwrap_or(Value::Null),
+        return_type: block.return_type.as_deref().map(normalize_source),
+        modifiers,
+    }
+}
+
+/// Drop position keys, sort object keys and normalize every string as source
+pub fn normalize_value(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter()
+                .filter(|(key, _)| !POSITION_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), normalize_value(value)))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.iter().map(normalize_value).collect()),
+        Value::String(text) => Value::String(normalize_source(text)),
+        other => other.clone(),
+    }
+}
+
+/// Languages where a line's leading indentation is syntax
+const INDENTATION_SENSITIVE: &[&str] = &["python", "yaml", "haskell", "fsharp", "nim", "coffeescript"];
+
+/// Remove whitespace that doesn't separate two word characters.
+///
+/// `fn add(a: i32) -> i32 { a + 1 }` and `fn add(a:i32)->i32{a+1}` normalize
+/// to the same text, while `pub fn` keeps its space. Whitespace inside
+/// double or single quoted strings is kept as written.
+pub fn normalize_source(text: &str) -> String {
+    normalize_source_in(text, "")
+}
+
+/// `normalize_source` for code in `language`. Indentation-sensitive
+/// languages keep each non-blank line and its leading indentation; Rust,
+/// where `'` also starts lifetimes and labels, only protects double quoted
+/// strings.
+pub fn normalize_source_in(text: &str, language: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let language = ast_extractor::Language::canonical_name(language);
+    let keep_indentation = INDENTATION_SENSITIVE.contains(&language);
+    let quotes: &[char] = if language == "rust" { &['"'] } else { &['"', '\''] };
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut pending_space = false;
+    let mut at_line_start = true;
+    let mut indentation = String::new();
+
+    for c in text.chars() {
+        if let Some(open) = quote {
+            normalized.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == open => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+
+        if keep_indentation {
+            if c == '\n' {
+                at_line_start = true;
+                indentation.clear();
+                continue;
+            }
+            if at_line_start {
+                if c.is_whitespace() {
+                    indentation.push(c);
+                    continue;
+                }
+                if !normalized.is_empty() {
+                    normalized.push('\n');
+                }
+                normalized.push_str(&indentation);
+                at_line_start = false;
+                pending_space = false;
+            }
+        }
+
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+
+        if pending_space && normalized.chars().last().is_some_and(is_word) && is_word(c) {
+            normalized.push(' ');
+        }
+        pending_space = false;
+        normalized.push(c);
+        if quotes.contains(&c) {
+            quote = Some(c);
+        }
+    }
+
+    normalized
+}
+
+/// Hash of source text in `language` with its formatting normalized away
+pub fn source_hash(text: &str, language: &str) -> String {
+    blake3::hash(normalize_source_in(text, language).as_bytes()).to_hex().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::parser::universal::UniversalParser;
+
+    #[test]
+    fn test_semantic_hash_ignores_formatting() -> Result<()> {
+        let async_function = |abstract_syntax: serde_json::Value, modifiers: [&str; 2]| Block {
+            return_type: Some("i32".to_string()),
+            modifiers: Some(modifiers.map(str::to_string).to_vec()),
+            ..Block::new(Uuid::new_v4(), "Function", "add", abstract_syntax)
+        };
+        let compact = async_function(serde_json::json!({"source": "fn add(a: i32, b: i32) -> i32 { a + b }", "start_line": 3}), ["pub", "async"]);
+        let spread = async_function(serde_json::json!({"start_line": 40, "source": "fn add(\n    a: i32,\n    b: i32,\n) -> i32 {\n    a+b\n}"}), ["async", "pub"]);
+        // The trailing comma is a real token, so this layout differs in more than whitespace
+        let spread_without_comma = async_function(serde_json::json!({"start_line": 40, "source": "fn add(\n    a: i32,\n    b: i32\n) -> i32 {\n    a+b\n}"}), ["async", "pub"]);
+        let subtracts = async_function(serde_json::json!({"source": "fn add(a: i32, b: i32) -> i32 { a - b }", "start_line": 3}), ["pub", "async"]);
+
+        let hash = |block| normalize_block(block).semantic_hash();
+        assert_ne!(hash(&compact), hash(&spread));
+        assert_eq!(hash(&compact), hash(&spread_without_comma));
+        assert_ne!(hash(&compact), hash(&subtracts));
+
+        // Extractor signature hashes follow the same normalization
+        let signature = |source: &str| -> Result<String> {
+            let result = UniversalParser::new()?.parse_file(source, "rust", "add.rs")?;
+            let block = result.blocks.iter()
+                .find(|b| b.semantic_identity.canonical_name == "add")
+                .expect("add extracted");
+            Ok(block.semantic_identity.signature_hash.clone())
+        };
+        assert_eq!(
+            signature("fn add(a: i32, b: i32) -> i32 { a + b }")?,
+            signature("fn add(a:i32,b:i32)->i32{\n    a+b\n}")?
+        );
+        assert_ne!(
+            signature("fn add(a: i32, b: i32) -> i32 { a + b }")?,
+            signature("fn add(a: i32, b: i32) -> i32 { a - b }")?
+        );
+        assert_eq!(
+            signature("fn add<'a>(a: &'a str, b: &'a str) -> &'a str { a }")?,
+            signature("fn add<'a>(a:&'a str,b:&'a str)->&'a str{\n    a\n}")?
+        );
+    
+        // Single quoted strings keep their whitespace like double quoted ones
+        assert_ne!(normalize_source("x = 'a  b'"), normalize_source("x = 'a b'"));
+        assert_eq!(normalize_source("x = 'a  b'"), normalize_source("x='a  b'"));
+    
+        // Indentation is syntax in Python, so moving a line out of a block changes the hash
+        let nested = "def run(items):\n    for item in items:\n        process(item)\n        log(item)\n";
+        let dedented = "def run(items):\n    for item in items:\n        process(item)\n    log(item)\n";
+        let respaced = "def run( items ):\n\n    for item in items :\n        process( item )\n        log(item)  \n";
+        assert_ne!(source_hash(nested, "python"), source_hash(dedented, "python"));
+        assert_eq!(source_hash(nested, "python"), source_hash(respaced, "python"));
+        assert_eq!(source_hash(nested, "rust"), source_hash(dedented, "rust"));
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+//! File-level preamble: shebang lines and file pragmas
+
+use ast_extractor::Language;
+use serde::{Deserialize, Serialize};
+
+/// Key under a container's `formatting_preferences` holding its preamble
+pub const FILE_PREAMBLE_KEY: &str = "preamble";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilePreamble {
+    /// The preamble lines in source order, without blank lines between them
+    pub lines: Vec<String>,
+}
+
+impl FilePreamble {
+    /// The shebang and pragma lines `source` opens with, `None` when there are none
+    pub fn detect(source: &str, language: &str) -> Option<Self> {
+        let lines: Vec<String> = leading_preamble(source, language)
+            .into_iter()
+            .map(|(_, line)| line.to_string())
+            .collect();
+        (!lines.is_empty()).then_some(Self { lines })
+    }
+
+    /// The preamble stored in a container's formatting preferences
+    pub fn from_formatting_preferences(preferences: Option<&serde_json::Value>) -> Option<Self> {
+        let preamble = preferences?.get(FILE_PREAMBLE_KEY)?;
+        serde_json::from_value(preamble.clone()).ok()
+    }
+
+    /// `preferences` with this preamble recorded, keeping its other settings
+    pub fn store_in(&self, preferences: Option<serde_json::Value>) -> serde_json::Value {
+        let mut preferences = match preferences {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        preferences.insert(FILE_PREAMBLE_KEY.to_string(), serde_json::to_value(self).unwrap_or_default());
+        serde_json::Value::Object(preferences)
+    }
+
+    /// A template file header with its own shebang and pragma lines
+    /// replaced by this preamble
+    pub fn replace_header(&self, header: &str, language: &str) -> String {
+        let mut rest = header;
+        if let Some(&(end, _)) = leading_preamble(header, language).last() {
+            rest = header[end..].trim_start_matches(['\r', '\n']);
+        }
+        let mut content = self.lines.join("\n");
+        content.push('\n');
+        content.push_str(rest);
+        content
+    }
+}
+
+/// The leading preamble lines of `source`, each with the byte offset just
+/// past it. Blank lines between preamble lines are skipped.
+fn leading_preamble<'a>(source: &'a str, language: &str) -> Vec<(usize, &'a str)> {
+    let language = language.parse::<Language>().ok();
+    let mut found = Vec::new();
+    let mut offset = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        offset += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+        if is_preamble_line(line, index, language) {
+            found.push((offset, line));
+        } else if found.is_empty() || !line.trim().is_empty() {
+            break;
+        }
+    }
+    found
+}
+
+fn is_preamble_line(line: &str, index: usize, language: Option<Language>) -> bool {
+    // A shebang is only one on the first line; `#![` opens a Rust inner attribute
+    if index == 0 && line.starts_with("#!") && !line.starts_with("#![") {
+        return true;
+    }
+    match language {
+        // PEP 263 encoding declarations, including Emacs and Vim modelines
+        Some(Language::Python) => line.starts_with('#') && (line.contains("coding:") || line.contains("coding=")),
+        Some(Language::Ruby) => line.starts_with('#') && ["coding:", "coding=", "frozen_string_literal:", "warn_indent:"]
+            .iter()
+            .any(|pragma| line.contains(pragma)),
+        Some(Language::Rust) => line.starts_with("#![") && line.trim_end().ends_with(']'),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::database::Container;
+    use crate::generator::templates::TemplateEngine;
+
+    /// Test that a script's shebang and coding declaration survive regeneration
+    #[test]
+    fn test_file_preamble_round_trip() -> Result<()> {
+        let source = "#!/usr/bin/env python3\n# -*- coding: latin-1 -*-\n\nimport sys\n\n\ndef main():\n    print(sys.argv)\n";
+        let preamble = FilePreamble::detect(source, "python").expect("preamble detected");
+        assert_eq!(preamble.lines, vec!["#!/usr/bin/env python3", "# -*- coding: latin-1 -*-"]);
+    
+        let preferences = preamble.store_in(Some(serde_json::json!({"indent": 4})));
+        assert_eq!(preferences["indent"], 4);
+        let mut container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("bin/script.py".to_string()),
+            ..Container::new("script", "file")
+        };
+        container.formatting_preferences = Some(preferences);
+        assert_eq!(FilePreamble::from_formatting_preferences(container.formatting_preferences.as_ref()), Some(preamble));
+    
+        // The original lines come first, unchanged, in place of the template's shebang and coding line
+        let generated = TemplateEngine::new().render_file(&container, &[], "python")?;
+        assert!(generated.starts_with("#!/usr/bin/env python3\n# -*- coding: latin-1 -*-\n"), "{}", generated);
+        assert_eq!(generated.matches("#!").count(), 1, "{}", generated);
+        assert!(!generated.contains("utf-8"), "{}", generated);
+    
+        // Rust inner attributes are file pragmas too; a file without any has no preamble
+        let rust = FilePreamble::detect("#![no_std]\n#![allow(dead_code)]\n\nfn main() {}\n", "rust").expect("pragmas detected");
+        assert_eq!(rust.lines, vec!["#![no_std]", "#![allow(dead_code)]"]);
+        assert_eq!(rust.replace_header("#![allow(unused)]\n// Generated from semantic blocks\n\n", "rust"),
+            "#![no_std]\n#![allow(dead_code)]\n// Generated from semantic blocks\n\n");
+        assert_eq!(FilePreamble::detect("import sys\n#!/not/a/shebang\n", "python"), None);
+        Ok(())
+    }
+}
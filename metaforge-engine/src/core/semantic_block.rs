@@ -1,3 +1,4 @@
+use ast_extractor::ExpressionAST;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -104,6 +105,10 @@ pub struct Parameter {
     pub default_value: Option<String>,
     pub is_optional: bool,
     pub position: usize,
+    /// Structured form of `default_value`, so generators can rebuild the
+    /// expression instead of pasting text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_expression: Option<ExpressionAST>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +152,8 @@ pub enum Modifier {
     Override,
 }
 
+/// Lines are 0-based; columns are byte offsets within the line, as
+/// tree-sitter reports them, not character counts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockPosition {
     pub start_line: usize,
@@ -160,6 +167,8 @@ pub struct BlockPosition {
 pub enum BlockType {
     Function,
     Class,
+    /// Rust `enum`, its variants stored under `rust_enum`
+    Enum,
     Interface,
     Variable,
     Import,
@@ -173,6 +182,10 @@ pub enum BlockType {
     Query,
     Config,
     Module,
+    /// Module-level code that runs on import, such as `app = Flask(__name__)`
+    /// followed by a call or an `if __name__ == "__main__":` guard, kept whole
+    /// and in place between the declarations
+    Statement,
 }
 
 impl std::fmt::Display for BlockType {
@@ -180,6 +193,7 @@ impl std::fmt::Display for BlockType {
         match self {
             BlockType::Function => write!(f, "Function"),
             BlockType::Class => write!(f, "Class"),
+            BlockType::Enum => write!(f, "Enum"),
             BlockType::Interface => write!(f, "Interface"),
             BlockType::Variable => write!(f, "Variable"),
             BlockType::Import => write!(f, "Import"),
@@ -193,6 +207,7 @@ impl std::fmt::Display for BlockType {
             BlockType::Query => write!(f, "Query"),
             BlockType::Config => write!(f, "Config"),
             BlockType::Module => write!(f, "Module"),
+            BlockType::Statement => write!(f, "Statement"),
         }
     }
 }
@@ -467,6 +482,9 @@ pub struct IoOperation {
     pub resource: String,
     pub is_blocking: bool,
     pub estimated_latency: Option<f64>,
+    /// The call performing the operation, e.g. `std::fs::read_to_string`
+    #[serde(default)]
+    pub call: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -548,6 +566,31 @@ pub struct ComplexityMetrics {
 
 // Helper implementations
 impl Parameter {
+    pub fn new(name: String, position: usize) -> Self {
+        Self {
+            name,
+            type_hint: None,
+            default_value: None,
+            is_optional: false,
+            position,
+            default_expression: None,
+        }
+    }
+
+    pub fn with_type(mut self, type_hint: String) -> Self {
+        self.type_hint = Some(type_hint);
+        self
+    }
+
+    /// Attach a default; the parameter becomes optional and `default_value`
+    /// keeps the source text for consumers that only need a string
+    pub fn with_default(mut self, default: ExpressionAST) -> Self {
+        self.default_value = Some(default.source_text.clone());
+        self.is_optional = true;
+        self.default_expression = Some(default);
+        self
+    }
+
     pub fn to_info(&self) -> ParameterInfo {
         ParameterInfo {
             name: self.name.clone(),
@@ -572,7 +615,7 @@ impl SemanticBlock {
                 canonical_name: canonical_name.clone(),
                 aliases: vec![],
                 fully_qualified_name: Some(canonical_name),
-                signature_hash: String::new(), // Will be filled during extraction
+                signature_hash: super::normalize::source_hash(&original_text, &source_language),
             },
             syntax_preservation: SyntaxPreservation {
                 original_text: original_text.clone(),
@@ -0,0 +1,307 @@
+//! Querying stored blocks by predicate
+
+use anyhow::{Context, Result};
+use ast_extractor::Language;
+use regex::Regex;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::schema::{Block, BlockRelationship, Database};
+
+/// Which way a relationship points from the block being filtered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationshipDirection {
+    /// The block is the relationship's source, e.g. the caller
+    Outgoing,
+    /// The block is the relationship's target, e.g. the callee
+    Incoming,
+    Either,
+}
+
+/// A predicate over stored blocks
+#[derive(Debug, Clone)]
+pub enum BlockFilter {
+    /// `block_type` equals this, ignoring case
+    BlockType(String),
+    /// Written in this language or one of its aliases
+    Language(String),
+    /// `semantic_name` matches this regex
+    Name(Regex),
+    /// Cyclomatic complexity within these inclusive bounds; blocks without
+    /// complexity metrics never match
+    Complexity { min: Option<usize>, max: Option<usize> },
+    /// Has a decorator (or attribute) with this name, with or without `@`
+    HasDecorator(String),
+    /// Has a relationship of this type (any type when `None`) in this
+    /// direction, with a block matching `other` (any block when `None`)
+    Related {
+        direction: RelationshipDirection,
+        relationship_type: Option<String>,
+        other: Option<Box<BlockFilter>>,
+    },
+    And(Vec<BlockFilter>),
+    Or(Vec<BlockFilter>),
+    Not(Box<BlockFilter>),
+}
+
+impl BlockFilter {
+    pub fn block_type(block_type: impl Into<String>) -> Self {
+        Self::BlockType(block_type.into())
+    }
+
+    pub fn language(language: impl Into<String>) -> Self {
+        Self::Language(language.into())
+    }
+
+    /// Blocks whose name matches `pattern`
+    pub fn name(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).with_context(|| format!("Invalid name pattern '{}'", pattern))?;
+        Ok(Self::Name(regex))
+    }
+
+    pub fn complexity(min: Option<usize>, max: Option<usize>) -> Self {
+        Self::Complexity { min, max }
+    }
+
+    pub fn has_decorator(decorator: impl Into<String>) -> Self {
+        Self::HasDecorator(decorator.into())
+    }
+
+    /// Blocks with a `relationship_type` relationship to a block matching `other`
+    pub fn relates_to(relationship_type: impl Into<String>, other: BlockFilter) -> Self {
+        Self::Related {
+            direction: RelationshipDirection::Outgoing,
+            relationship_type: Some(relationship_type.into()),
+            other: Some(Box::new(other)),
+        }
+    }
+
+    /// Blocks a block matching `other` has a `relationship_type` relationship to
+    pub fn related_from(relationship_type: impl Into<String>, other: BlockFilter) -> Self {
+        Self::Related {
+            direction: RelationshipDirection::Incoming,
+            relationship_type: Some(relationship_type.into()),
+            other: Some(Box::new(other)),
+        }
+    }
+
+    pub fn and(self, other: BlockFilter) -> Self {
+        match self {
+            Self::And(mut filters) => {
+                filters.push(other);
+                Self::And(filters)
+            }
+            filter => Self::And(vec![filter, other]),
+        }
+    }
+
+    pub fn or(self, other: BlockFilter) -> Self {
+        match self {
+            Self::Or(mut filters) => {
+                filters.push(other);
+                Self::Or(filters)
+            }
+            filter => Self::Or(vec![filter, other]),
+        }
+    }
+
+    pub fn negate(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Whether `block`, one of `blocks`, matches
+    pub fn matches(&self, block: &Block, blocks: &BlockSnapshot) -> bool {
+        match self {
+            Self::BlockType(block_type) => block.block_type.eq_ignore_ascii_case(block_type),
+            Self::Language(language) => blocks.language_of(block)
+                .is_some_and(|block_language| Language::canonical_name(block_language) == Language::canonical_name(language)),
+            Self::Name(regex) => block.semantic_name.as_deref().is_some_and(|name| regex.is_match(name)),
+            Self::Complexity { min, max } => match cyclomatic_complexity(block) {
+                Some(complexity) => !min.is_some_and(|min| complexity < min) && !max.is_some_and(|max| complexity > max),
+                None => false,
+            },
+            Self::HasDecorator(decorator) => decorator_names(block).any(|name| name == decorator.trim_start_matches('@')),
+            Self::Related { direction, relationship_type, other } => blocks.related(block.id, *direction)
+                .filter(|(relationship, _)| match relationship_type {
+                    Some(relationship_type) => relationship.relationship_type.eq_ignore_ascii_case(relationship_type),
+                    None => true,
+                })
+                .any(|(_, related)| match (other, blocks.block(related)) {
+                    (None, _) => true,
+                    (Some(other), Some(related)) => other.matches(related, blocks),
+                    (Some(_), None) => false,
+                }),
+            Self::And(filters) => filters.iter().all(|filter| filter.matches(block, blocks)),
+            Self::Or(filters) => filters.iter().any(|filter| filter.matches(block, blocks)),
+            Self::Not(filter) => !filter.matches(block, blocks),
+        }
+    }
+}
+
+/// Blocks with the languages of their containers and the relationships
+/// between them, which filters are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct BlockSnapshot {
+    blocks: Vec<Block>,
+    /// Index into `blocks` by block id
+    by_id: HashMap<Uuid, usize>,
+    /// Language of each container, by container id
+    container_languages: HashMap<Uuid, String>,
+    relationships: Vec<BlockRelationship>,
+    /// Indexes into `relationships` by source and by target block id
+    outgoing: HashMap<Uuid, Vec<usize>>,
+    incoming: HashMap<Uuid, Vec<usize>>,
+}
+
+impl BlockSnapshot {
+    pub fn new(blocks: Vec<Block>, container_languages: HashMap<Uuid, String>, relationships: Vec<BlockRelationship>) -> Self {
+        let by_id = blocks.iter().enumerate().map(|(index, block)| (block.id, index)).collect();
+        let mut outgoing: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        let mut incoming: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        for (index, relationship) in relationships.iter().enumerate() {
+            outgoing.entry(relationship.source_block_id).or_default().push(index);
+            incoming.entry(relationship.target_block_id).or_default().push(index);
+        }
+        Self { blocks, by_id, container_languages, relationships, outgoing, incoming }
+    }
+
+    /// The blocks matching `filter`, in snapshot order
+    pub fn query(&self, filter: &BlockFilter) -> Vec<&Block> {
+        self.blocks.iter().filter(|block| filter.matches(block, self)).collect()
+    }
+
+    fn block(&self, id: Uuid) -> Option<&Block> {
+        self.by_id.get(&id).map(|&index| &self.blocks[index])
+    }
+
+    fn language_of<'a>(&'a self, block: &'a Block) -> Option<&'a str> {
+        block.source_language.as_deref()
+            .or_else(|| self.container_languages.get(&block.container_id).map(String::as_str))
+    }
+
+    /// Each relationship of `id` in `direction`, with the block at its other end
+    fn related(&self, id: Uuid, direction: RelationshipDirection) -> impl Iterator<Item = (&BlockRelationship, Uuid)> {
+        let outgoing = match direction {
+            RelationshipDirection::Incoming => None,
+            _ => self.outgoing.get(&id),
+        };
+        let incoming = match direction {
+            RelationshipDirection::Outgoing => None,
+            _ => self.incoming.get(&id),
+        };
+        let outgoing = outgoing.into_iter().flatten().map(|&index| {
+            let relationship = &self.relationships[index];
+            (relationship, relationship.target_block_id)
+        });
+        let incoming = incoming.into_iter().flatten().map(|&index| {
+            let relationship = &self.relationships[index];
+            (relationship, relationship.source_block_id)
+        });
+        outgoing.chain(incoming)
+    }
+}
+
+fn cyclomatic_complexity(block: &Block) -> Option<usize> {
+    let complexity = block.complexity_metrics.as_ref()
+        .and_then(|metrics| metrics.get("cyclomatic_complexity"))
+        .or_else(|| block.get_metadata("cyclomatic_complexity"))?;
+    complexity.as_u64().map(|complexity| complexity as usize)
+}
+
+/// Decorator names without `@` or arguments. Stored decorators are objects
+/// with a `name`; older rows hold the source text.
+fn decorator_names(block: &Block) -> impl Iterator<Item = &str> {
+    block.decorators.as_ref()
+        .and_then(|decorators| decorators.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|decorator| decorator.get("name").unwrap_or(decorator).as_str())
+        .map(|name| {
+            let name = name.trim_start_matches('@');
+            name.split('(').next().unwrap_or(name).trim()
+        })
+}
+
+impl Database {
+    /// Every stored block matching `filter`, ordered by container and
+    /// position. Filters are evaluated in memory against all blocks, the
+    /// languages of their containers and their relationships.
+    pub async fn query_blocks(&self, filter: &BlockFilter) -> Result<Vec<Block>> {
+        let blocks = sqlx::query_as::<_, Block>(
+            "SELECT * FROM blocks ORDER BY container_id, position"
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        let container_languages: Vec<(Uuid, Option<String>)> = sqlx::query_as(
+            "SELECT id, language FROM containers"
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        let relationships = sqlx::query_as::<_, BlockRelationship>(
+            "SELECT source_block_id, target_block_id, relationship_type, metadata FROM block_relationships"
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        let container_languages = container_languages.into_iter()
+            .filter_map(|(id, language)| Some((id, language?)))
+            .collect();
+        let snapshot = BlockSnapshot::new(blocks, container_languages, relationships);
+        Ok(snapshot.query(filter).into_iter().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_filters_compose() -> Result<()> {
+        let (python, rust) = (Uuid::new_v4(), Uuid::new_v4());
+        let block = |container: Uuid, block_type: &str, name: &str, complexity: u64, decorators: serde_json::Value| {
+            let mut block = Block::new(container, block_type, name, serde_json::json!({}));
+            block.decorators = Some(decorators);
+            block.complexity_metrics = Some(serde_json::json!({"cyclomatic_complexity": complexity}));
+            block
+        };
+        let blocks = vec![
+            block(python, "Function", "test_load", 2, serde_json::json!([{"name": "pytest.mark.slow", "arguments": [], "line_number": 1}])),
+            block(python, "Function", "load", 9, serde_json::json!([])),
+            block(python, "Class", "Loader", 1, serde_json::json!(["@dataclass"])),
+            block(rust, "Function", "load", 4, serde_json::json!([])),
+        ];
+        let ids: Vec<Uuid> = blocks.iter().map(|block| block.id).collect();
+        let calls = |source: usize, target: usize| crate::database::schema::BlockRelationship {
+            source_block_id: ids[source],
+            target_block_id: ids[target],
+            relationship_type: "calls".to_string(),
+            metadata: None,
+        };
+        let snapshot = BlockSnapshot::new(
+            blocks,
+            HashMap::from([(python, "py".to_string()), (rust, "rust".to_string())]),
+            vec![calls(0, 1), calls(3, 1)],
+        );
+        let query = |filter: BlockFilter| -> Vec<Uuid> {
+            snapshot.query(&filter).into_iter().map(|block| block.id).collect()
+        };
+    
+        // Language aliases resolve; names are regexes
+        let python_functions = BlockFilter::block_type("function").and(BlockFilter::language("python"));
+        assert_eq!(query(python_functions.clone()), vec![ids[0], ids[1]]);
+        assert_eq!(query(python_functions.clone().and(BlockFilter::name("^test_")?)), vec![ids[0]]);
+        assert!(BlockFilter::name("(").is_err());
+    
+        assert_eq!(query(BlockFilter::complexity(Some(3), None)), vec![ids[1], ids[3]]);
+        assert_eq!(query(BlockFilter::complexity(Some(2), Some(4)).negate()), vec![ids[1], ids[2]]);
+        assert_eq!(query(BlockFilter::has_decorator("dataclass").or(BlockFilter::has_decorator("@pytest.mark.slow"))), vec![ids[0], ids[2]]);
+    
+        // Relationship predicates look at the block on the other end
+        assert_eq!(query(BlockFilter::relates_to("calls", BlockFilter::name("^load$")?)), vec![ids[0], ids[3]]);
+        assert_eq!(query(BlockFilter::related_from("calls", BlockFilter::language("rust"))), vec![ids[1]]);
+        assert_eq!(query(python_functions.and(BlockFilter::related_from("calls", BlockFilter::name("^test_")?).negate())), vec![ids[0]]);
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Blocks as they stand on a semantic branch
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::schema::{Block, BlockVersion, Database, SemanticBranch};
+
+/// Fields a branch version may not change
+const FIXED_FIELDS: [&str; 3] = ["id", "container_id", "created_at"];
+
+#[derive(Debug, Clone)]
+pub struct BranchView {
+    pub branch: SemanticBranch,
+    /// The branch's versions of each block, oldest first
+    versions: HashMap<Uuid, Vec<BlockVersion>>,
+}
+
+impl BranchView {
+    /// View `branch` through `versions`, ignoring versions of other branches
+    pub fn new(branch: SemanticBranch, versions: Vec<BlockVersion>) -> Self {
+        let mut by_block: HashMap<Uuid, Vec<BlockVersion>> = HashMap::new();
+        for version in versions {
+            if version.branch_name.as_deref() == Some(branch.name.as_str()) {
+                by_block.entry(version.block_id).or_default().push(version);
+            }
+        }
+        for block_versions in by_block.values_mut() {
+            block_versions.sort_by_key(|version| version.version_number);
+        }
+        Self { branch, versions: by_block }
+    }
+
+    /// Number of blocks the branch changes
+    pub fn changed_blocks(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// `block` with the branch's changes applied
+    pub fn apply(&self, block: &Block) -> Result<Block> {
+        let Some(versions) = self.versions.get(&block.id) else {
+            return Ok(block.clone());
+        };
+        let mut value = serde_json::to_value(block)?;
+        let fields = value.as_object_mut()
+            .ok_or_else(|| anyhow!("Block {} did not serialize to an object", block.id))?;
+        for changes in versions.iter().filter_map(|version| version.semantic_changes.as_ref()?.as_object()) {
+            for (field, change) in changes {
+                // Other keys describe the change rather than set a field
+                if fields.contains_key(field) && !FIXED_FIELDS.contains(&field.as_str()) {
+                    fields.insert(field.clone(), change.clone());
+                }
+            }
+        }
+        serde_json::from_value(value)
+            .map_err(|e| anyhow!("Branch {} leaves block {} invalid: {}", self.branch.name, block.id, e))
+    }
+
+    /// Every block of `blocks` as it stands on the branch
+    pub fn apply_all(&self, blocks: &[Block]) -> Result<Vec<Block>> {
+        blocks.iter().map(|block| self.apply(block)).collect()
+    }
+}
+
+impl Database {
+    /// The most recently created branch called `name`
+    pub async fn get_semantic_branch(&self, name: &str) -> Result<SemanticBranch> {
+        sqlx::query_as::<_, SemanticBranch>(
+            "SELECT * FROM semantic_branches WHERE name = $1 ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(name)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| anyhow!("No semantic branch named {}", name))
+    }
+
+    /// The branch called `name` with the block versions made on it
+    pub async fn get_branch_view(&self, name: &str) -> Result<BranchView> {
+        let branch = self.get_semantic_branch(name).await?;
+        let versions = sqlx::query_as::<_, BlockVersion>(
+            "SELECT * FROM block_versions WHERE branch_name = $1 ORDER BY block_id, version_number"
+        )
+        .bind(name)
+        .fetch_all(self.pool())
+        .await?;
+        Ok(BranchView::new(branch, versions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Container;
+    use crate::generator::HierarchicalGenerator;
+
+    /// Test generating a file as it stands on a semantic branch
+    #[test]
+    fn test_branch_view_generates_branch_versions_of_blocks() -> Result<()> {
+        let container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("app/settings.py".to_string()),
+            ..Container::new("settings.py", "file")
+        };
+        let block = |name: &str, text: &str, position: i32| {
+            let mut block = Block::new(container.id, "Variable", name, serde_json::json!({"raw_text": text}));
+            block.position = position;
+            block.position_in_parent = position;
+            block
+        };
+        let blocks = vec![block("TAX_RATE", "TAX_RATE = 0.2", 0), block("CURRENCY", "CURRENCY = \"USD\"", 1)];
+        let branch: crate::database::schema::SemanticBranch = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "name": "vat-update",
+            "intent": "Raise the tax rate",
+            "default_llm_provider": "anthropic",
+            "created_at": chrono::Utc::now(),
+        }))?;
+        let version = |block_id: Uuid, branch_name: &str, version_number: i32, changes: serde_json::Value| -> Result<crate::database::schema::BlockVersion> {
+            Ok(serde_json::from_value(serde_json::json!({
+                "id": Uuid::new_v4(),
+                "block_id": block_id,
+                "version_number": version_number,
+                "semantic_hash": "",
+                "syntax_hash": "",
+                "created_at": chrono::Utc::now(),
+                "semantic_changes": changes,
+                "breaking_change": false,
+                "branch_name": branch_name,
+            }))?)
+        };
+        let versions = vec![
+            // Out of order: the later version wins
+            version(blocks[0].id, "vat-update", 2, serde_json::json!({"abstract_syntax": {"raw_text": "TAX_RATE = 0.25"}, "reason": "new rate"}))?,
+            version(blocks[0].id, "vat-update", 1, serde_json::json!({"abstract_syntax": {"raw_text": "TAX_RATE = 0.21"}, "id": Uuid::new_v4()}))?,
+            version(blocks[1].id, "euro", 1, serde_json::json!({"abstract_syntax": {"raw_text": "CURRENCY = \"EUR\""}}))?,
+        ];
+    
+        let view = BranchView::new(branch, versions);
+        assert_eq!(view.changed_blocks(), 1);
+        let branch_blocks = view.apply_all(&blocks)?;
+        // Fields a version can't set, and keys that aren't fields, are left alone
+        assert_eq!(branch_blocks[0].id, blocks[0].id);
+        assert_eq!(branch_blocks[0].abstract_syntax["raw_text"], "TAX_RATE = 0.25");
+        assert_eq!(branch_blocks[1].abstract_syntax, blocks[1].abstract_syntax);
+    
+        let generated = HierarchicalGenerator::from_blocks(&container, branch_blocks).generate()?;
+        assert_eq!(generated, "TAX_RATE = 0.25\n\nCURRENCY = \"USD\"");
+        let base = HierarchicalGenerator::from_blocks(&container, blocks).generate()?;
+        assert_eq!(base, "TAX_RATE = 0.2\n\nCURRENCY = \"USD\"");
+    
+        Ok(())
+    }
+}
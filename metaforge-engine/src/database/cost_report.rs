@@ -0,0 +1,228 @@
+//! LLM spend reporting
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use super::schema::{Database, IN_MIGRATION};
+
+/// Which interactions a cost report covers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum CostScope {
+    /// Interactions on blocks the migration covers, including blocks it
+    /// carries over unchanged from its base
+    Migration(Uuid),
+    /// Interactions on versions committed to the branch
+    Branch(String),
+}
+
+/// One logged interaction's usage, as read for reporting
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionUsage {
+    pub provider: String,
+    pub model: String,
+    pub tokens_used: Option<i32>,
+    pub latency_ms: Option<i32>,
+    pub cost_cents: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50_ms: Option<i32>,
+    pub p90_ms: Option<i32>,
+    pub p99_ms: Option<i32>,
+}
+
+/// Usage totals for a set of interactions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub interactions: usize,
+    pub total_tokens: i64,
+    pub total_cost_cents: f64,
+    /// Averages are over interactions that recorded the value
+    pub avg_tokens: Option<f64>,
+    pub avg_cost_cents: Option<f64>,
+    pub latency: LatencyPercentiles,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelCost {
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub usage: UsageSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostReport {
+    pub scope: CostScope,
+    pub total: UsageSummary,
+    /// Sorted by cost, most expensive first
+    pub by_model: Vec<ModelCost>,
+}
+
+impl CostReport {
+    pub fn from_interactions(scope: CostScope, interactions: &[InteractionUsage]) -> Self {
+        let mut grouped: BTreeMap<(&str, &str), Vec<&InteractionUsage>> = BTreeMap::new();
+        for interaction in interactions {
+            grouped.entry((interaction.provider.as_str(), interaction.model.as_str()))
+                .or_default()
+                .push(interaction);
+        }
+
+        let mut by_model: Vec<ModelCost> = grouped.into_iter()
+            .map(|((provider, model), group)| ModelCost {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                usage: UsageSummary::from_interactions(group),
+            })
+            .collect();
+        by_model.sort_by(|a, b| b.usage.total_cost_cents.total_cmp(&a.usage.total_cost_cents));
+
+        Self {
+            scope,
+            total: UsageSummary::from_interactions(interactions.iter()),
+            by_model,
+        }
+    }
+}
+
+impl UsageSummary {
+    fn from_interactions<'a>(interactions: impl IntoIterator<Item = &'a InteractionUsage>) -> Self {
+        let interactions: Vec<&InteractionUsage> = interactions.into_iter().collect();
+        let tokens: Vec<i64> = interactions.iter().filter_map(|i| i.tokens_used).map(i64::from).collect();
+        let costs: Vec<f64> = interactions.iter().filter_map(|i| i.cost_cents).map(f64::from).collect();
+        let mut latencies: Vec<i32> = interactions.iter().filter_map(|i| i.latency_ms).collect();
+        latencies.sort_unstable();
+
+        let average = |sum: f64, count: usize| (count > 0).then(|| sum / count as f64);
+        let total_tokens: i64 = tokens.iter().sum();
+        let total_cost_cents: f64 = costs.iter().sum();
+
+        Self {
+            interactions: interactions.len(),
+            total_tokens,
+            total_cost_cents,
+            avg_tokens: average(total_tokens as f64, tokens.len()),
+            avg_cost_cents: average(total_cost_cents, costs.len()),
+            latency: LatencyPercentiles {
+                p50_ms: percentile(&latencies, 50),
+                p90_ms: percentile(&latencies, 90),
+                p99_ms: percentile(&latencies, 99),
+            },
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[i32], percent: usize) -> Option<i32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+/// `(provider, model, tokens_used, latency_ms, cost_cents)` as stored
+type InteractionRow = (String, String, Option<i32>, Option<i32>, Option<f64>);
+
+impl Database {
+    /// Sum LLM usage for a migration or branch. Interactions are attributed
+    /// to a block through their block version, or else through the
+    /// `block_id` the provider manager records in the request payload.
+    pub async fn llm_cost_report(&self, scope: &CostScope) -> Result<CostReport> {
+        let base = r#"
+            SELECT li.provider, li.model, li.tokens_used, li.latency_ms, li.cost_cents
+            FROM llm_interactions li
+            LEFT JOIN block_versions bv ON li.block_version_id = bv.id
+        "#;
+
+        let rows: Vec<InteractionRow> = match scope {
+            CostScope::Migration(migration_id) => {
+                sqlx::query_as(&format!(
+                    "{} JOIN blocks b ON b.id = COALESCE(bv.block_id, (li.request_payload->>'block_id')::uuid)
+                     JOIN containers c ON b.container_id = c.id
+                     WHERE {}",
+                    base, IN_MIGRATION
+                ))
+                .bind(migration_id)
+                .fetch_all(self.pool())
+                .await?
+            }
+            CostScope::Branch(branch_name) => {
+                sqlx::query_as(&format!("{} WHERE bv.branch_name = $1", base))
+                    .bind(branch_name)
+                    .fetch_all(self.pool())
+                    .await?
+            }
+        };
+
+        let interactions: Vec<InteractionUsage> = rows.into_iter()
+            .map(|(provider, model, tokens_used, latency_ms, cost_cents)| InteractionUsage {
+                provider,
+                model,
+                tokens_used,
+                latency_ms,
+                cost_cents: cost_cents.map(|cost| cost as f32),
+            })
+            .collect();
+
+        Ok(CostReport::from_interactions(scope.clone(), &interactions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(provider: &str, model: &str, tokens: i32, latency_ms: i32, cost_cents: f32) -> InteractionUsage {
+        InteractionUsage {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            tokens_used: Some(tokens),
+            latency_ms: Some(latency_ms),
+            cost_cents: Some(cost_cents),
+        }
+    }
+
+    #[test]
+    fn test_llm_cost_report_aggregates_by_model() -> Result<()> {
+        let mut interactions: Vec<InteractionUsage> = (1..=10)
+            .map(|i| usage("openai", "gpt-4o", 100 * i, 10 * i, 2.0))
+            .collect();
+        interactions.push(usage("local", "llama", 50, 5, 0.0));
+        interactions.push(InteractionUsage { tokens_used: None, latency_ms: None, cost_cents: None, ..usage("local", "llama", 0, 0, 0.0) });
+
+        let report = CostReport::from_interactions(CostScope::Branch("main".to_string()), &interactions);
+
+        assert_eq!(report.total.interactions, 12);
+        assert_eq!(report.total.total_tokens, 5550);
+        assert!((report.total.total_cost_cents - 20.0).abs() < 1e-6);
+
+        // Most expensive model first
+        let gpt = &report.by_model[0];
+        assert_eq!((gpt.provider.as_str(), gpt.model.as_str()), ("openai", "gpt-4o"));
+        assert_eq!(gpt.usage.interactions, 10);
+        assert_eq!(gpt.usage.avg_tokens, Some(550.0));
+        assert_eq!(gpt.usage.latency.p50_ms, Some(50));
+        assert_eq!(gpt.usage.latency.p90_ms, Some(90));
+        assert_eq!(gpt.usage.latency.p99_ms, Some(100));
+
+        // Missing values are left out of averages rather than counted as zero
+        let llama = &report.by_model[1];
+        assert_eq!(llama.usage.interactions, 2);
+        assert_eq!(llama.usage.avg_tokens, Some(50.0));
+        assert_eq!(llama.usage.latency.p99_ms, Some(5));
+
+        let json = serde_json::to_value(&report)?;
+        assert_eq!(json["scope"]["kind"], "branch");
+        assert_eq!(json["by_model"][0]["total_tokens"], 5500);
+
+        let empty = CostReport::from_interactions(CostScope::Migration(Uuid::new_v4()), &[]);
+        assert_eq!(empty.total.avg_cost_cents, None);
+        assert_eq!(empty.total.latency.p50_ms, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+//! Incremental migrations
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use super::resume::StoredFile;
+use super::schema::{Database, IN_MIGRATION};
+use crate::github::FileChanges;
+use crate::scanner::SourceFile;
+
+/// A file stored by the base migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseContainer {
+    pub container_id: Uuid,
+    pub file: StoredFile,
+}
+
+/// `(id, original_path, original_hash, language, block count)` as stored
+type BaseContainerRow = (Uuid, Option<String>, Option<String>, Option<String>, i64);
+
+/// What an incremental migration parses and what it takes from its base
+#[derive(Debug, Default)]
+pub struct IncrementalPlan {
+    /// Files added since the base commit, and files the base migration
+    /// never stored (e.g. because of a language filter)
+    pub added: Vec<SourceFile>,
+    pub modified: Vec<SourceFile>,
+    /// Base containers whose file is unchanged, carried over as stored
+    pub unchanged: Vec<BaseContainer>,
+    /// Base containers whose file was deleted
+    pub deleted: Vec<BaseContainer>,
+}
+
+impl IncrementalPlan {
+    /// Sort the scanned `files` and the `base` containers by `changes`.
+    /// Paths are compared relative to `repo_root`, where both the base
+    /// migration and this one cloned the repository.
+    pub fn new(files: Vec<SourceFile>, base: Vec<BaseContainer>, changes: &FileChanges, repo_root: &Path) -> Self {
+        let added: HashSet<&Path> = changes.added.iter().map(PathBuf::as_path).collect();
+        let modified: HashSet<&Path> = changes.modified.iter().map(PathBuf::as_path).collect();
+        let deleted: HashSet<&Path> = changes.deleted.iter().map(PathBuf::as_path).collect();
+
+        let mut plan = Self::default();
+        let mut stored = HashSet::new();
+        for container in base {
+            let path = relative_to(repo_root, Path::new(&container.file.path)).to_path_buf();
+            if deleted.contains(path.as_path()) && !added.contains(path.as_path()) {
+                plan.deleted.push(container);
+            } else if !modified.contains(path.as_path()) && !added.contains(path.as_path()) {
+                plan.unchanged.push(container);
+            }
+            // Changed files get a new container; the base's is left behind
+            stored.insert(path);
+        }
+
+        for file in files {
+            let path = relative_to(repo_root, &file.path);
+            if modified.contains(path) {
+                plan.modified.push(file);
+            } else if added.contains(path) || !stored.contains(path) {
+                plan.added.push(file);
+            }
+        }
+        plan
+    }
+
+    /// The files to parse, added first
+    pub fn pending(&mut self) -> Vec<SourceFile> {
+        let mut pending = std::mem::take(&mut self.added);
+        pending.append(&mut self.modified);
+        pending
+    }
+
+    /// Blocks per language in the carried-over files, so the migration's
+    /// statistics cover the whole repository
+    pub fn unchanged_blocks_by_language(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for container in self.unchanged.iter().filter(|container| container.file.blocks > 0) {
+            let language = container.file.language.clone().unwrap_or_else(|| "unknown".to_string());
+            *counts.entry(language).or_insert(0) += container.file.blocks;
+        }
+        counts
+    }
+}
+
+fn relative_to<'a>(root: &Path, path: &'a Path) -> &'a Path {
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+impl Database {
+    /// The most recent migration of `repo_url` taken at `commit_hash`
+    pub async fn find_migration_at_commit(&self, repo_url: &str, commit_hash: &str) -> Result<Option<Uuid>> {
+        let migration = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM migrations WHERE repo_url = $1 AND commit_hash = $2
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(repo_url)
+        .bind(commit_hash)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(migration)
+    }
+
+    /// Every file of a migration, including those it carries over from its
+    /// own base, with its container id
+    pub async fn get_base_containers(&self, migration_id: Uuid) -> Result<Vec<BaseContainer>> {
+        let rows: Vec<BaseContainerRow> = sqlx::query_as(&format!(
+            "SELECT c.id, c.original_path, c.original_hash, c.language, COUNT(b.id)
+             FROM containers c
+             LEFT JOIN blocks b ON b.container_id = c.id
+             WHERE {}
+             GROUP BY c.id",
+            IN_MIGRATION
+        ))
+        .bind(migration_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.into_iter()
+            .filter_map(|(container_id, path, hash, language, blocks)| Some(BaseContainer {
+                container_id,
+                file: StoredFile { path: path?, hash: hash?, language, blocks: blocks as usize },
+            }))
+            .collect())
+    }
+
+    /// Record `base_migration_id` as the base of `migration_id` and link the
+    /// plan's unchanged and deleted containers, in one transaction
+    pub async fn link_base_migration(&self, migration_id: Uuid, base_migration_id: Uuid, plan: &IncrementalPlan) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("UPDATE migrations SET base_migration_id = $1 WHERE id = $2")
+            .bind(base_migration_id)
+            .bind(migration_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (status, containers) in [("unchanged", &plan.unchanged), ("deleted", &plan.deleted)] {
+            let ids: Vec<Uuid> = containers.iter().map(|container| container.container_id).collect();
+            sqlx::query(
+                "INSERT INTO migration_containers (migration_id, container_id, status)
+                 SELECT $1, container_id, $2 FROM UNNEST($3::uuid[]) AS container_id
+                 ON CONFLICT (migration_id, container_id) DO NOTHING"
+            )
+            .bind(migration_id)
+            .bind(status)
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_plan_parses_only_changed_files() {
+        let file = |path: &str| SourceFile {
+            path: std::path::PathBuf::from(path),
+            content: String::new(),
+            language: "python".to_string(),
+            hash: String::new(),
+        };
+        let base = |path: &str, blocks: usize| BaseContainer {
+            container_id: Uuid::new_v4(),
+            file: StoredFile { path: path.to_string(), hash: String::new(), language: Some("python".to_string()), blocks },
+        };
+        let changes = FileChanges {
+            base_commit: "0123456789abcdef".to_string(),
+            added: vec!["pkg/new.py".into()],
+            modified: vec!["pkg/edited.py".into()],
+            deleted: vec!["pkg/gone.py".into(), "README.md".into()],
+        };
+        let stored = vec![base("repos/app/pkg/same.py", 3), base("repos/app/pkg/edited.py", 5), base("repos/app/pkg/gone.py", 7)];
+
+        let mut plan = IncrementalPlan::new(
+            vec![
+                file("repos/app/pkg/same.py"),
+                file("repos/app/pkg/edited.py"),
+                file("repos/app/pkg/new.py"),
+                // Never stored by the base migration, e.g. filtered out then
+                file("repos/app/pkg/skipped.py"),
+            ],
+            stored.clone(),
+            &changes,
+            std::path::Path::new("repos/app"),
+        );
+
+        assert_eq!(plan.unchanged, vec![stored[0].clone()]);
+        assert_eq!(plan.deleted, vec![stored[2].clone()]);
+        assert_eq!((plan.added.len(), plan.modified.len()), (2, 1));
+        assert_eq!(plan.unchanged_blocks_by_language().get("python"), Some(&3));
+        let pending: Vec<String> = plan.pending().iter().map(|file| file.path.display().to_string()).collect();
+        assert_eq!(pending, vec!["repos/app/pkg/new.py", "repos/app/pkg/skipped.py", "repos/app/pkg/edited.py"]);
+    }
+}
@@ -1,5 +1,16 @@
 pub mod schema;
+pub mod cost_report;
 pub mod source_code_migrator;
+pub mod resume;
+pub mod prune;
+pub mod block_query;
+pub mod incremental;
+pub mod branches;
 
-pub use schema::{Database, Container, Block};
+pub use schema::{Database, DatabaseConfig, Container, Block};
 pub use source_code_migrator::*;
+pub use cost_report::CostScope;
+pub use resume::ResumePlan;
+pub use prune::PrunePolicy;
+pub use block_query::{BlockFilter, RelationshipDirection};
+pub use incremental::IncrementalPlan;
@@ -0,0 +1,301 @@
+//! Pruning old migrations
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+use sqlx::{Postgres, Transaction};
+use super::schema::Database;
+
+/// Which migrations to delete. With both limits set, only migrations past
+/// both are deleted: the latest `keep_latest` of a repository are kept
+/// however old they are.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrunePolicy {
+    /// Delete migrations created longer ago than this
+    pub older_than: Option<Duration>,
+    /// Keep only this many of each repository's most recent migrations
+    pub keep_latest: Option<usize>,
+}
+
+impl PrunePolicy {
+    pub fn is_empty(&self) -> bool {
+        self.older_than.is_none() && self.keep_latest.is_none()
+    }
+
+    /// The migrations this policy deletes, oldest first
+    pub fn select<'a>(&self, migrations: &'a [StoredMigration], now: DateTime<Utc>) -> Vec<&'a StoredMigration> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut by_repo: HashMap<&str, Vec<&StoredMigration>> = HashMap::new();
+        for migration in migrations {
+            by_repo.entry(migration.repo_url.as_str()).or_default().push(migration);
+        }
+
+        let mut selected = Vec::new();
+        for mut repo_migrations in by_repo.into_values() {
+            repo_migrations.sort_by_key(|migration| std::cmp::Reverse(migration.created_at));
+            for (rank, migration) in repo_migrations.into_iter().enumerate() {
+                let too_old = match self.older_than {
+                    Some(age) => migration.created_at < now - age,
+                    None => true,
+                };
+                let beyond_latest = match self.keep_latest {
+                    Some(keep) => rank >= keep,
+                    None => true,
+                };
+                if too_old && beyond_latest {
+                    selected.push(migration);
+                }
+            }
+        }
+        selected.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        selected
+    }
+}
+
+/// Parse an age such as `90m`, `12h`, `30d` or `2w`
+pub fn parse_age(age: &str) -> Result<Duration> {
+    let age = age.trim();
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (number, unit) = age.split_at(split);
+    let Ok(number) = number.parse::<i64>() else {
+        bail!("Invalid age '{}': expected a number followed by m, h, d or w", age);
+    };
+    match unit {
+        "m" => Ok(Duration::minutes(number)),
+        "h" => Ok(Duration::hours(number)),
+        "d" | "" => Ok(Duration::days(number)),
+        "w" => Ok(Duration::weeks(number)),
+        _ => bail!("Invalid age '{}': unit must be m, h, d or w", age),
+    }
+}
+
+/// A stored migration, as needed to decide whether to prune it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredMigration {
+    pub id: Uuid,
+    pub repo_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Rows deleted (or, for a dry run, that would be)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub dry_run: bool,
+    pub migrations: u64,
+    pub containers: u64,
+    pub blocks: u64,
+    pub relationships: u64,
+    /// Blocks whose container no longer exists
+    pub orphaned_blocks: u64,
+    /// Relationships whose source or target block no longer exists
+    pub orphaned_relationships: u64,
+    /// LLM interactions kept, without the block version they were logged on
+    pub detached_interactions: u64,
+    /// Semantic branches kept, without the migration they were based on
+    pub detached_branches: u64,
+}
+
+impl PruneReport {
+    pub fn total_rows(&self) -> u64 {
+        self.migrations + self.containers + self.blocks + self.relationships
+            + self.orphaned_blocks + self.orphaned_relationships
+    }
+}
+
+impl Database {
+    /// Every migration's id, repository and creation time
+    pub async fn get_stored_migrations(&self) -> Result<Vec<StoredMigration>> {
+        let rows: Vec<(Uuid, String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT id, repo_url, created_at FROM migrations ORDER BY created_at"
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.into_iter()
+            .map(|(id, repo_url, created_at)| StoredMigration { id, repo_url, created_at })
+            .collect())
+    }
+
+    /// Delete `migration_ids` with their containers, blocks and
+    /// relationships, then orphaned blocks and relationships, in one
+    /// transaction that a dry run rolls back
+    pub async fn prune_migrations(&self, migration_ids: &[Uuid], dry_run: bool) -> Result<PruneReport> {
+        let mut tx = self.pool().begin().await?;
+        let mut report = PruneReport { dry_run, ..PruneReport::default() };
+
+        // A container an incremental migration that is kept carries over
+        // moves to the oldest such migration instead of going with its owner
+        sqlx::query(
+            "UPDATE containers c SET migration_id = heir.migration_id
+             FROM (
+                 SELECT DISTINCT ON (mc.container_id) mc.container_id, mc.migration_id
+                 FROM migration_containers mc JOIN migrations m ON m.id = mc.migration_id
+                 WHERE mc.status = 'unchanged' AND NOT (mc.migration_id = ANY($1))
+                 ORDER BY mc.container_id, m.created_at
+             ) heir
+             WHERE c.id = heir.container_id AND c.migration_id = ANY($1)"
+        )
+        .bind(migration_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM migration_containers mc USING containers c
+             WHERE mc.container_id = c.id AND mc.migration_id = c.migration_id"
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // The versioning tables predate some databases' migrations, so their
+        // references are only cleared where they exist
+        if has_column(&mut tx, "llm_interactions", "block_version_id").await? {
+            report.detached_interactions = sqlx::query(
+                "UPDATE llm_interactions SET block_version_id = NULL
+                 WHERE block_version_id IN (
+                     SELECT v.id FROM block_versions v
+                     JOIN blocks b ON b.id = v.block_id
+                     LEFT JOIN containers c ON c.id = b.container_id
+                     WHERE c.id IS NULL OR c.migration_id = ANY($1)
+                 )"
+            )
+            .bind(migration_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        if has_column(&mut tx, "semantic_branches", "base_migration_id").await? {
+            report.detached_branches = sqlx::query(
+                "UPDATE semantic_branches SET base_migration_id = NULL WHERE base_migration_id = ANY($1)"
+            )
+            .bind(migration_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        // Children first, so each count is the rows of that table alone
+        report.relationships = sqlx::query(
+            "DELETE FROM block_relationships r
+             USING blocks b, containers c
+             WHERE (r.source_block_id = b.id OR r.target_block_id = b.id)
+               AND b.container_id = c.id AND c.migration_id = ANY($1)"
+        )
+        .bind(migration_ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        report.blocks = sqlx::query(
+            "DELETE FROM blocks b USING containers c
+             WHERE b.container_id = c.id AND c.migration_id = ANY($1)"
+        )
+        .bind(migration_ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        report.containers = sqlx::query("DELETE FROM containers WHERE migration_id = ANY($1)")
+            .bind(migration_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        report.migrations = sqlx::query("DELETE FROM migrations WHERE id = ANY($1)")
+            .bind(migration_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // Databases created before the foreign keys can hold rows pointing at nothing
+        report.orphaned_blocks = sqlx::query(
+            "DELETE FROM blocks b WHERE NOT EXISTS (SELECT 1 FROM containers c WHERE c.id = b.container_id)"
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        report.orphaned_relationships = sqlx::query(
+            "DELETE FROM block_relationships r
+             WHERE NOT EXISTS (SELECT 1 FROM blocks b WHERE b.id = r.source_block_id)
+                OR NOT EXISTS (SELECT 1 FROM blocks b WHERE b.id = r.target_block_id)"
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+        Ok(report)
+    }
+}
+
+/// Whether `table` exists and has `column`
+async fn has_column(tx: &mut Transaction<'_, Postgres>, table: &str, column: &str) -> Result<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+             SELECT 1 FROM information_schema.columns
+             WHERE table_schema = current_schema() AND table_name = $1 AND column_name = $2
+         )"
+    )
+    .bind(table)
+    .bind(column)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_policy_selects_old_migrations_per_repository() -> Result<()> {
+        let now = chrono::Utc::now();
+        let migration = |repo: &str, days_ago: i64| StoredMigration {
+            id: Uuid::new_v4(),
+            repo_url: repo.to_string(),
+            created_at: now - chrono::Duration::days(days_ago),
+        };
+        let migrations = vec![
+            migration("github.com/a/app", 90),
+            migration("github.com/a/app", 40),
+            migration("github.com/a/app", 1),
+            migration("github.com/b/lib", 60),
+        ];
+        let selected = |policy: PrunePolicy| -> Vec<Uuid> {
+            policy.select(&migrations, now).into_iter().map(|migration| migration.id).collect()
+        };
+    
+        let older_than = |age: &str| -> Result<PrunePolicy> {
+            Ok(PrunePolicy { older_than: Some(parse_age(age)?), keep_latest: None })
+        };
+        assert_eq!(selected(older_than("30d")?), vec![migrations[0].id, migrations[3].id, migrations[1].id]);
+        assert_eq!(selected(older_than("10w")?), vec![migrations[0].id]);
+    
+        // Keeping the latest N is per repository
+        let keep_one = PrunePolicy { older_than: None, keep_latest: Some(1) };
+        assert_eq!(selected(keep_one), vec![migrations[0].id, migrations[1].id]);
+    
+        // With both, a repository's latest migrations survive however old they are
+        let both = PrunePolicy { older_than: Some(parse_age("30d")?), keep_latest: Some(2) };
+        assert_eq!(selected(both), vec![migrations[0].id]);
+    
+        // No limits prunes no migrations, only orphans
+        assert!(selected(PrunePolicy::default()).is_empty());
+    
+        assert_eq!(parse_age("12h")?, chrono::Duration::hours(12));
+        assert_eq!(parse_age("90m")?, chrono::Duration::minutes(90));
+        assert!(parse_age("3 months").is_err());
+        assert!(parse_age("d").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+//! Resumable migrations
+
+use anyhow::Result;
+use std::collections::HashMap;
+use uuid::Uuid;
+use super::schema::Database;
+use crate::scanner::SourceFile;
+
+/// A file an earlier run of the migration already stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredFile {
+    pub path: String,
+    pub hash: String,
+    pub language: Option<String>,
+    pub blocks: usize,
+}
+
+/// `(original_path, original_hash, language, block count)` as stored
+type StoredFileRow = (Option<String>, Option<String>, Option<String>, i64);
+
+/// What a resumed migration still has to do
+#[derive(Debug, Default)]
+pub struct ResumePlan {
+    /// New files and files whose hash no longer matches the stored container
+    pub pending: Vec<SourceFile>,
+    /// Unchanged files, left as stored
+    pub done: Vec<StoredFile>,
+}
+
+impl ResumePlan {
+    /// Split `files` into those already stored with the same hash and the rest
+    pub fn new(files: Vec<SourceFile>, stored: Vec<StoredFile>) -> Self {
+        let mut stored: HashMap<String, StoredFile> = stored.into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+        let mut plan = Self::default();
+        for file in files {
+            let path = file.path.to_string_lossy();
+            match stored.remove(path.as_ref()) {
+                Some(previous) if previous.hash == file.hash => plan.done.push(previous),
+                _ => plan.pending.push(file),
+            }
+        }
+        plan
+    }
+
+    /// Blocks per language in the files left as stored, so the migration's
+    /// statistics cover the whole repository rather than the last run
+    pub fn stored_blocks_by_language(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for file in self.done.iter().filter(|file| file.blocks > 0) {
+            let language = file.language.clone().unwrap_or_else(|| "unknown".to_string());
+            *counts.entry(language).or_insert(0) += file.blocks;
+        }
+        counts
+    }
+}
+
+impl Database {
+    /// The most recent unfinished migration of `repo_url` taken at `commit_hash`
+    pub async fn find_resumable_migration(&self, repo_url: &str, commit_hash: &str) -> Result<Option<Uuid>> {
+        let migration = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM migrations
+             WHERE repo_url = $1 AND commit_hash = $2 AND status <> 'completed'
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(repo_url)
+        .bind(commit_hash)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(migration)
+    }
+
+    /// Path, hash and block count of every container stored for a migration,
+    /// without loading the source code
+    pub async fn get_stored_files(&self, migration_id: Uuid) -> Result<Vec<StoredFile>> {
+        let rows: Vec<StoredFileRow> = sqlx::query_as(
+            "SELECT c.original_path, c.original_hash, c.language, COUNT(b.id)
+             FROM containers c
+             LEFT JOIN blocks b ON b.container_id = c.id
+             WHERE c.migration_id = $1
+             GROUP BY c.id"
+        )
+        .bind(migration_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.into_iter()
+            .filter_map(|(path, hash, language, blocks)| Some(StoredFile {
+                path: path?,
+                hash: hash?,
+                language,
+                blocks: blocks as usize,
+            }))
+            .collect())
+    }
+
+    /// Mark a migration as running again before resuming it
+    pub async fn reopen_migration(&self, migration_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE migrations SET status = 'in_progress' WHERE id = $1")
+            .bind(migration_id)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_plan_skips_files_stored_with_the_same_hash() {
+        let file = |path: &str, hash: &str| SourceFile {
+            path: std::path::PathBuf::from(path),
+            content: String::new(),
+            language: "python".to_string(),
+            hash: hash.to_string(),
+        };
+        let stored = |path: &str, hash: &str, blocks: usize| StoredFile {
+            path: path.to_string(),
+            hash: hash.to_string(),
+            language: Some("python".to_string()),
+            blocks,
+        };
+
+        let plan = ResumePlan::new(
+            vec![file("repo/done.py", "aaa"), file("repo/edited.py", "new"), file("repo/added.py", "ccc")],
+            vec![stored("repo/done.py", "aaa", 3), stored("repo/edited.py", "old", 5), stored("repo/deleted.py", "ddd", 7)],
+        );
+
+        let pending: Vec<String> = plan.pending.iter().map(|file| file.path.display().to_string()).collect();
+        assert_eq!(pending, vec!["repo/edited.py", "repo/added.py"]);
+        assert_eq!(plan.done, vec![stored("repo/done.py", "aaa", 3)]);
+
+        // Only the files left as stored count towards the carried-over statistics
+        assert_eq!(plan.stored_blocks_by_language().get("python"), Some(&3));
+    }
+}
@@ -1,13 +1,21 @@
-use sqlx::{PgPool, postgres::PgPoolOptions, migrate::Migrator};
+use sqlx::{PgPool, PgExecutor, postgres::{PgPoolOptions, PgConnectOptions}, migrate::Migrator};
 use anyhow::Result;
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
 
 // Embed migrations at compile time
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
+/// Condition on `containers c` selecting the containers of migration `$1`:
+/// its own, and those an incremental migration carries over from its base
+pub(crate) const IN_MIGRATION: &str = "(c.migration_id = $1 OR c.id IN (
+    SELECT mc.container_id FROM migration_containers mc
+    WHERE mc.migration_id = $1 AND mc.status = 'unchanged'))";
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Container {
     pub id: Uuid,
@@ -28,6 +36,29 @@ pub struct Container {
     pub reconstruction_hints: Option<serde_json::Value>,
 }
 
+impl Container {
+    /// A container with only the required columns set
+    pub fn new(name: impl Into<String>, container_type: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            container_type: container_type.into(),
+            language: None,
+            original_path: None,
+            original_hash: None,
+            source_code: None,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+            semantic_summary: None,
+            parsing_metadata: None,
+            formatting_preferences: None,
+            reconstruction_hints: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Block {
     pub id: Uuid,
@@ -75,6 +106,74 @@ pub struct Block {
 }
 
 impl Block {
+    /// A top-level block with only the required columns set
+    pub fn new(container_id: Uuid, block_type: impl Into<String>, semantic_name: impl Into<String>, abstract_syntax: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            container_id,
+            block_type: block_type.into(),
+            semantic_name: Some(semantic_name.into()),
+            abstract_syntax,
+            position: 0,
+            indent_level: 0,
+            metadata: None,
+            created_at: Utc::now(),
+            parent_block_id: None,
+            position_in_parent: 0,
+            parameters: None,
+            return_type: None,
+            modifiers: None,
+            decorators: None,
+            body_ast: None,
+            language_ast: None,
+            language_features: None,
+            complexity_metrics: None,
+            scope_info: None,
+            syntax_preservation: None,
+            structural_context: None,
+            semantic_metadata: None,
+            source_language: None,
+            template_metadata: None,
+            generation_hints: None,
+            semantic_signature: None,
+            behavioral_contract: None,
+            formatting_metadata: None,
+            attached_comments: None,
+            dependency_info: None,
+            position_metadata: None,
+            hierarchical_index: None,
+            depth_level: None,
+        }
+    }
+
+    /// The row `Database::insert_semantic_block` stores for an extracted block
+    pub fn from_semantic_block(block: &crate::core::SemanticBlock, container_id: Uuid) -> Result<Self> {
+        let row = SemanticBlockRow::from_block(block)?;
+        let column = |value: serde_json::Value| Some(value).filter(|value| !value.is_null());
+        Ok(Self {
+            id: block.id,
+            position: block.position.index as i32,
+            parent_block_id: block.structural_context.parent_block,
+            parameters: column(row.parameters),
+            return_type: block.semantic_metadata.return_type.as_ref().map(|rt| rt.representation.clone()),
+            modifiers: Some(row.modifiers),
+            decorators: column(row.decorators),
+            body_ast: column(row.body_ast),
+            language_features: column(row.language_features),
+            complexity_metrics: column(row.complexity_metrics),
+            scope_info: column(row.scope_info),
+            semantic_metadata: column(row.semantic_metadata),
+            attached_comments: column(row.attached_comments),
+            position_metadata: column(row.position_metadata),
+            ..Self::new(
+                container_id,
+                format!("{:?}", block.block_type),
+                block.semantic_identity.canonical_name.clone(),
+                block.syntax_preservation.normalized_ast.clone(),
+            )
+        })
+    }
+
     /// Helper method to set metadata fields safely
     pub fn set_metadata(&mut self, key: &str, value: serde_json::Value) {
         match &mut self.metadata {
@@ -94,6 +193,22 @@ impl Block {
         self.metadata.as_ref()?.as_object()?.get(key)
     }
     
+    /// Typed view of `language_features`, falling back to the features an
+    /// extractor attached to `abstract_syntax` for rows stored without them
+    pub fn language_features_typed(&self) -> Option<crate::core::LanguageFeatures> {
+        self.language_features.as_ref()
+            .and_then(crate::core::LanguageFeatures::from_value)
+            .or_else(|| crate::core::LanguageFeatures::from_abstract_syntax(&self.abstract_syntax))
+    }
+
+    /// Type parameters the extractor recorded in `semantic_metadata`, if any
+    pub fn generics_typed(&self) -> Option<crate::core::GenericInfo> {
+        self.semantic_metadata.as_ref()?
+            .get("generics")
+            .and_then(crate::core::GenericInfo::from_value)
+            .filter(|generics| !generics.is_empty())
+    }
+    
     /// Helper method to get mutable metadata reference
     pub fn get_metadata_mut(&mut self, key: &str) -> Option<&mut serde_json::Value> {
         if let Some(serde_json::Value::Object(ref mut map)) = self.metadata {
@@ -285,20 +400,182 @@ pub struct SourceCodeBackup {
     pub restored: Option<bool>,
 }
 
+/// Connection pool settings used when opening a [`Database`]
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Upper bound on pooled connections
+    pub max_connections: u32,
+    /// How long to wait for a free connection before failing
+    pub acquire_timeout: Duration,
+    /// Server-side `statement_timeout` applied to every connection (`None` = server default)
+    pub statement_timeout: Option<Duration>,
+    /// Retries attempted by [`Database::with_retry`] on transient failures
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for every further attempt
+    pub retry_backoff: Duration,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            acquire_timeout: Duration::from_secs(30),
+            statement_timeout: None,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Whether a database error is worth retrying (connection loss, deadlock,
+/// serialization failure, server overload). Constraint violations and other
+/// permanent errors are not.
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Io(_)) | Some(sqlx::Error::PoolTimedOut) => true,
+        Some(sqlx::Error::Database(db_error)) => match db_error.code() {
+            Some(code) => code.starts_with("08")    // connection exception
+                || code == "40001"                 // serialization_failure
+                || code == "40P01"                 // deadlock_detected
+                || code == "53300"                 // too_many_connections
+                || code == "57P01",                // admin_shutdown
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// JSON columns of a semantic block, serialized up front so batch inserts can bind them
+struct SemanticBlockRow<'a> {
+    block: &'a crate::core::SemanticBlock,
+    parameters: serde_json::Value,
+    modifiers: Vec<String>,
+    decorators: serde_json::Value,
+    complexity_metrics: serde_json::Value,
+    scope_info: serde_json::Value,
+    semantic_metadata: serde_json::Value,
+    language_features: serde_json::Value,
+    body_ast: serde_json::Value,
+    attached_comments: serde_json::Value,
+    position_metadata: serde_json::Value,
+}
+
+impl<'a> SemanticBlockRow<'a> {
+    fn from_block(block: &'a crate::core::SemanticBlock) -> Result<Self> {
+        Ok(Self {
+            block,
+            parameters: serde_json::to_value(&block.semantic_metadata.parameters)?,
+            modifiers: block.semantic_metadata.modifiers.iter().map(|m| format!("{:?}", m)).collect(),
+            decorators: serde_json::to_value(&block.structural_context.decorators)?,
+            complexity_metrics: serde_json::to_value(&block.semantic_metadata.complexity_metrics)?,
+            scope_info: serde_json::to_value(&block.structural_context.scope)?,
+            semantic_metadata: serde_json::to_value(&block.semantic_metadata)?,
+            language_features: language_features_column(block),
+            body_ast: body_ast_column(block),
+            attached_comments: attached_comments_column(block),
+            position_metadata: position_metadata_column(block),
+        })
+    }
+}
+
+/// Features the extractor attached to the block, or NULL when it found none
+fn language_features_column(block: &crate::core::SemanticBlock) -> serde_json::Value {
+    crate::core::LanguageFeatures::from_abstract_syntax(&block.syntax_preservation.normalized_ast)
+        .filter(|features| !features.is_empty())
+        .map(|features| features.to_value())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Statements the extractor classified in a function's body, or NULL
+fn body_ast_column(block: &crate::core::SemanticBlock) -> serde_json::Value {
+    crate::core::FunctionBody::from_abstract_syntax(&block.syntax_preservation.normalized_ast)
+        .filter(|body| !body.is_empty())
+        .map(|body| body.to_value())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Comments the parser attached to the block, or NULL when it has none
+fn attached_comments_column(block: &crate::core::SemanticBlock) -> serde_json::Value {
+    block.syntax_preservation.normalized_ast.get(ast_extractor::ATTACHED_COMMENTS_KEY)
+        .cloned()
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Where the block sat in its original file, for source maps
+fn position_metadata_column(block: &crate::core::SemanticBlock) -> serde_json::Value {
+    serde_json::json!({
+        "start_line": block.position.start_line,
+        "start_column": block.position.start_column,
+        "end_line": block.position.end_line,
+        "end_column": block.position.end_column,
+    })
+}
+
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct Database {
     pool: PgPool,
+    max_retries: u32,
+    retry_backoff: Duration,
 }
 
 impl Database {
     pub async fn new(database_url: &str) -> Result<Self> {
+        Self::with_config(database_url, &DatabaseConfig::default()).await
+    }
+
+    /// Open a database pool with explicit pool settings
+    pub async fn with_config(database_url: &str, config: &DatabaseConfig) -> Result<Self> {
+        let mut connect_options = PgConnectOptions::from_str(database_url)?;
+        if let Some(timeout) = config.statement_timeout {
+            connect_options = connect_options
+                .options([("statement_timeout", timeout.as_millis().to_string())]);
+        }
+
         let pool = PgPoolOptions::new()
-            .max_connections(10)
-            .connect(database_url)
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.acquire_timeout)
+            .connect_with(connect_options)
             .await?;
         
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            max_retries: config.max_retries,
+            retry_backoff: config.retry_backoff,
+        })
+    }
+
+    /// Wrap an already-open pool, e.g. the one shared by the GraphQL server
+    pub fn from_pool(pool: PgPool) -> Self {
+        let defaults = DatabaseConfig::default();
+        Self {
+            pool,
+            max_retries: defaults.max_retries,
+            retry_backoff: defaults.retry_backoff,
+        }
+    }
+
+    /// Run `operation`, retrying with exponential backoff while it fails with a
+    /// transient error. Permanent errors are returned immediately.
+    pub async fn with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_retries && is_transient_error(&e) => {
+                    let delay = self.retry_backoff * 2u32.saturating_pow(attempt);
+                    attempt += 1;
+                    tracing::warn!("Transient database error ({}), retry {}/{} in {:?}",
+                        e, attempt, self.max_retries, delay);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Run embedded SQLx migrations
@@ -451,10 +728,15 @@ impl Database {
     }
     
     pub async fn insert_container(&self, container: &Container, migration_id: Uuid) -> Result<()> {
+        Self::insert_container_with(&self.pool, container, migration_id).await
+    }
+    
+    async fn insert_container_with(executor: impl PgExecutor<'_>, container: &Container, migration_id: Uuid) -> Result<()> {
         sqlx::query(
             "INSERT INTO containers (id, migration_id, name, container_type, language, 
-                                    original_path, original_hash, source_code, version)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+                                    original_path, original_hash, source_code, version,
+                                    formatting_preferences)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, COALESCE($10, '{}'::jsonb))"
         )
         .bind(container.id)
         .bind(migration_id)
@@ -465,7 +747,8 @@ impl Database {
         .bind(&container.original_hash)
         .bind(&container.source_code)
         .bind(container.version)
-        .execute(&self.pool)
+        .bind(&container.formatting_preferences)
+        .execute(executor)
         .await?;
         
         Ok(())
@@ -511,35 +794,101 @@ impl Database {
     }
     
     pub async fn insert_semantic_block(&self, block: &crate::core::SemanticBlock, container_id: Uuid) -> Result<()> {
-        sqlx::query(
-            r#"INSERT INTO blocks (
+        Self::insert_semantic_block_rows(&self.pool, container_id, &[SemanticBlockRow::from_block(block)?]).await
+    }
+    
+    /// Insert `rows` into `container_id` with one statement. The single
+    /// place the semantic block columns are bound, for both the one-block
+    /// and the batched file insert.
+    async fn insert_semantic_block_rows(executor: impl PgExecutor<'_>, container_id: Uuid, rows: &[SemanticBlockRow<'_>]) -> Result<()> {
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO blocks (
                 id, container_id, block_type, semantic_name, abstract_syntax, 
                 position, indent_level, parent_block_id, position_in_parent,
                 parameters, return_type, modifiers, decorators, body_ast,
-                language_ast, language_features, complexity_metrics, scope_info
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)"#
-        )
-        .bind(block.id)
-        .bind(container_id)
-        .bind(format!("{:?}", block.block_type))
-        .bind(&block.semantic_identity.canonical_name)
-        .bind(&block.syntax_preservation.normalized_ast)
-        .bind(block.position.index as i32)
-        .bind(0) // indent_level - will be calculated
-        .bind(block.structural_context.parent_block)
-        .bind(0) // position_in_parent - will be set during extraction
-        .bind(serde_json::to_value(&block.semantic_metadata.parameters)?)
-        .bind(block.semantic_metadata.return_type.as_ref().map(|rt| rt.representation.clone()))
-        .bind(block.semantic_metadata.modifiers.iter().map(|m| format!("{:?}", m)).collect::<Vec<_>>())
-        .bind(serde_json::to_value(&block.structural_context.decorators)?)
-        .bind(serde_json::Value::Null) // body_ast - to be filled
-        .bind(serde_json::Value::Null) // language_ast - to be filled
-        .bind(serde_json::Value::Null) // language_features - to be filled
-        .bind(serde_json::to_value(&block.semantic_metadata.complexity_metrics)?)
-        .bind(serde_json::to_value(&block.structural_context.scope)?)
-        .execute(&self.pool)
-        .await?;
+                language_ast, language_features, complexity_metrics, scope_info,
+                semantic_metadata, attached_comments, position_metadata
+            ) "
+        );
+        
+        query_builder.push_values(rows, |mut b, row| {
+            b.push_bind(row.block.id)
+             .push_bind(container_id)
+             .push_bind(format!("{:?}", row.block.block_type))
+             .push_bind(&row.block.semantic_identity.canonical_name)
+             .push_bind(&row.block.syntax_preservation.normalized_ast)
+             .push_bind(row.block.position.index as i32)
+             .push_bind(0) // indent_level - will be calculated
+             .push_bind(row.block.structural_context.parent_block)
+             .push_bind(0) // position_in_parent - will be set during extraction
+             .push_bind(&row.parameters)
+             .push_bind(row.block.semantic_metadata.return_type.as_ref().map(|rt| rt.representation.clone()))
+             .push_bind(&row.modifiers)
+             .push_bind(&row.decorators)
+             .push_bind(&row.body_ast)
+             .push_bind(serde_json::Value::Null) // language_ast - to be filled
+             .push_bind(&row.language_features)
+             .push_bind(&row.complexity_metrics)
+             .push_bind(&row.scope_info)
+             .push_bind(&row.semantic_metadata)
+             .push_bind(&row.attached_comments)
+             .push_bind(&row.position_metadata);
+        });
+        
+        query_builder.build().execute(executor).await?;
+        Ok(())
+    }
+    
+    /// Insert a parsed file (container, blocks and relationships) in a single
+    /// transaction. Either everything is stored or, on any error, nothing is.
+    /// A container already stored for the same path in this migration is
+    /// replaced, so re-running a file (e.g. when resuming) never duplicates it.
+    pub async fn insert_file(
+        &self,
+        container: &Container,
+        migration_id: Uuid,
+        blocks: &[crate::core::SemanticBlock],
+        relationships: &[BlockRelationship],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        if let Some(path) = &container.original_path {
+            // Blocks and relationships go with it (ON DELETE CASCADE)
+            sqlx::query("DELETE FROM containers WHERE migration_id = $1 AND original_path = $2")
+                .bind(migration_id)
+                .bind(path)
+                .execute(&mut *tx)
+                .await?;
+        }
+        
+        Self::insert_container_with(&mut *tx, container, migration_id).await?;
+        
+        let rows = blocks.iter()
+            .map(SemanticBlockRow::from_block)
+            .collect::<Result<Vec<_>>>()?;
+        
+        // 21 binds per row keeps each statement well under the 65535 parameter limit
+        for chunk in rows.chunks(1000) {
+            Self::insert_semantic_block_rows(&mut *tx, container.id, chunk).await?;
+        }
+        
+        for chunk in relationships.chunks(5000) {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "INSERT INTO block_relationships (source_block_id, target_block_id, relationship_type, metadata) "
+            );
+            
+            query_builder.push_values(chunk, |mut b, relationship| {
+                b.push_bind(relationship.source_block_id)
+                 .push_bind(relationship.target_block_id)
+                 .push_bind(&relationship.relationship_type)
+                 .push_bind(&relationship.metadata);
+            });
+            query_builder.push(" ON CONFLICT (source_block_id, target_block_id, relationship_type) DO NOTHING");
+            
+            query_builder.build().execute(&mut *tx).await?;
+        }
         
+        tx.commit().await?;
         Ok(())
     }
     
@@ -573,9 +922,10 @@ impl Database {
     }
     
     pub async fn get_containers_by_migration(&self, migration_id: Uuid) -> Result<Vec<Container>> {
-        let containers = sqlx::query_as::<_, Container>(
-            "SELECT * FROM containers WHERE migration_id = $1 ORDER BY created_at"
-        )
+        let containers = sqlx::query_as::<_, Container>(&format!(
+            "SELECT c.* FROM containers c WHERE {} ORDER BY c.created_at",
+            IN_MIGRATION
+        ))
         .bind(migration_id)
         .fetch_all(&self.pool)
         .await?;
@@ -713,13 +1063,12 @@ impl Database {
     }
     
     pub async fn get_blocks_by_migration(&self, migration_id: Uuid) -> Result<Vec<Block>> {
-        let blocks = sqlx::query_as::<_, Block>(
-            r#"
-            SELECT b.* FROM blocks b
-            JOIN containers c ON b.container_id = c.id
-            WHERE c.migration_id = $1
-            "#
-        )
+        let blocks = sqlx::query_as::<_, Block>(&format!(
+            "SELECT b.* FROM blocks b
+             JOIN containers c ON b.container_id = c.id
+             WHERE {}",
+            IN_MIGRATION
+        ))
         .bind(migration_id)
         .fetch_all(&self.pool)
         .await?;
@@ -728,14 +1077,13 @@ impl Database {
     }
     
     pub async fn get_relationships_by_migration(&self, migration_id: Uuid) -> Result<Vec<BlockRelationship>> {
-        let relationships = sqlx::query_as::<_, BlockRelationship>(
-            r#"
-            SELECT br.* FROM block_relationships br
-            JOIN blocks b ON br.source_block_id = b.id
-            JOIN containers c ON b.container_id = c.id
-            WHERE c.migration_id = $1
-            "#
-        )
+        let relationships = sqlx::query_as::<_, BlockRelationship>(&format!(
+            "SELECT br.* FROM block_relationships br
+             JOIN blocks b ON br.source_block_id = b.id
+             JOIN containers c ON b.container_id = c.id
+             WHERE {}",
+            IN_MIGRATION
+        ))
         .bind(migration_id)
         .fetch_all(&self.pool)
         .await?;
@@ -932,6 +1280,20 @@ impl Database {
     
     /// Update an existing block in the database
     pub async fn update_block(&self, block: &Block) -> Result<()> {
+        Self::update_block_with(&self.pool, block).await
+    }
+    
+    /// Update `blocks` in one transaction, so either all change or none do
+    pub async fn update_blocks(&self, blocks: &[Block]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for block in blocks {
+            Self::update_block_with(&mut *tx, block).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+    
+    async fn update_block_with(executor: impl PgExecutor<'_>, block: &Block) -> Result<()> {
         sqlx::query(
             r#"
             UPDATE blocks SET
@@ -973,9 +1335,60 @@ impl Database {
         .bind(&block.language_features)
         .bind(&block.complexity_metrics)
         .bind(&block.scope_info)
-        .execute(&self.pool)
+        .execute(executor)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Source backups taken by EliminateSourceCode, for one container or a whole migration
+    pub async fn get_source_code_backups(
+        &self,
+        container_id: Option<Uuid>,
+        migration_id: Option<Uuid>,
+    ) -> Result<Vec<SourceCodeBackup>> {
+        let backups = sqlx::query_as::<_, SourceCodeBackup>(
+            "SELECT b.container_id, b.original_source_code, b.original_path, b.original_hash,
+                    b.backup_created_at, b.restored
+             FROM source_code_backup b
+             JOIN containers c ON c.id = b.container_id
+             WHERE ($1::uuid IS NULL OR b.container_id = $1)
+               AND ($2::uuid IS NULL OR c.migration_id = $2)
+             ORDER BY c.original_path"
+        )
+        .bind(container_id)
+        .bind(migration_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(backups)
+    }
+    
+    /// Write a backup's source back into its container and mark the backup restored
+    pub async fn restore_source_code(&self, backup: &SourceCodeBackup) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query(
+            "UPDATE containers SET
+                source_code = $2,
+                semantic_summary = COALESCE(semantic_summary, '{}'::jsonb) || jsonb_build_object(
+                    'source_code_free', false,
+                    'source_restored_at', NOW()
+                ),
+                updated_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(backup.container_id)
+        .bind(&backup.original_source_code)
+        .execute(&mut *tx)
         .await?;
         
+        sqlx::query("UPDATE source_code_backup SET restored = TRUE WHERE container_id = $1")
+            .bind(backup.container_id)
+            .execute(&mut *tx)
+            .await?;
+        
+        tx.commit().await?;
         Ok(())
     }
     
@@ -11,6 +11,7 @@ pub struct SourceCodeMigrator {
     db: Database,
     template_engine: TemplateEngine,
     dry_run: bool,
+    per_container_threshold: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -20,9 +21,27 @@ pub struct MigrationReport {
     pub failed_migrations: Vec<(Uuid, String)>,
     pub semantic_quality_scores: HashMap<Uuid, f64>,
     pub reconstruction_accuracy: HashMap<Uuid, f64>,
+    /// Containers below the per-container threshold whose source was kept
+    pub retained_for_review: Vec<(Uuid, String)>,
     pub migration_duration: std::time::Duration,
 }
 
+/// Running status reported after each container is processed
+#[derive(Debug, Clone)]
+pub struct MigrationProgress {
+    pub container_id: Uuid,
+    pub container_name: String,
+    /// Whether this container was migrated
+    pub container_succeeded: bool,
+    pub processed: usize,
+    pub total_containers: usize,
+    pub successful_migrations: usize,
+    pub failed_migrations: usize,
+    /// Running averages over successfully migrated containers
+    pub average_quality: f64,
+    pub average_accuracy: f64,
+}
+
 #[derive(Debug, Clone)]
 pub struct ContainerMigrationResult {
     pub container_id: Uuid,
@@ -31,6 +50,8 @@ pub struct ContainerMigrationResult {
     pub reconstruction_quality: f64,
     pub semantic_completeness: f64,
     pub migration_successful: bool,
+    /// Source was kept because the container missed the per-container threshold
+    pub source_retained: bool,
     pub error_messages: Vec<String>,
 }
 
@@ -40,11 +61,28 @@ impl SourceCodeMigrator {
             db,
             template_engine: TemplateEngine::new(),
             dry_run,
+            per_container_threshold: None,
         }
     }
 
+    /// Gate every container individually: containers whose reconstruction
+    /// quality or semantic completeness is below `min_quality` keep their
+    /// source_code and are reported as retained for manual review.
+    pub fn with_per_container_threshold(mut self, min_quality: f64) -> Self {
+        self.per_container_threshold = Some(min_quality);
+        self
+    }
+
     /// Migrate all containers from source_code dependencies to pure semantic storage
     pub async fn migrate_all_containers(&self) -> Result<MigrationReport> {
+        self.migrate_all_containers_with_progress(|_| {}).await
+    }
+
+    /// Same as `migrate_all_containers`, invoking `on_progress` after every container
+    pub async fn migrate_all_containers_with_progress(
+        &self,
+        mut on_progress: impl FnMut(MigrationProgress),
+    ) -> Result<MigrationReport> {
         let start_time = std::time::Instant::now();
         
         // Get all containers that still have source_code dependencies
@@ -59,13 +97,24 @@ impl SourceCodeMigrator {
             failed_migrations: Vec::new(),
             semantic_quality_scores: HashMap::new(),
             reconstruction_accuracy: HashMap::new(),
+            retained_for_review: Vec::new(),
             migration_duration: std::time::Duration::default(),
         };
         
-        for container in containers_with_source {
+        for (index, container) in containers_with_source.into_iter().enumerate() {
+            let successes_before = report.successful_migrations;
+            
             match self.migrate_container(&container).await {
                 Ok(result) => {
-                    if result.migration_successful {
+                    if result.source_retained {
+                        let reason = format!(
+                            "below threshold: reconstruction={:.1}%, semantic={:.1}%",
+                            result.reconstruction_quality * 100.0,
+                            result.semantic_completeness * 100.0
+                        );
+                        println!("⏸️  Retained source of container '{}' for manual review ({})", container.name, reason);
+                        report.retained_for_review.push((container.id, reason));
+                    } else if result.migration_successful {
                         report.successful_migrations += 1;
                         report.semantic_quality_scores.insert(container.id, result.semantic_completeness);
                         report.reconstruction_accuracy.insert(container.id, result.reconstruction_quality);
@@ -83,6 +132,18 @@ impl SourceCodeMigrator {
                     println!("❌ Error migrating container '{}': {}", container.name, e);
                 }
             }
+            
+            on_progress(MigrationProgress {
+                container_id: container.id,
+                container_name: container.name.clone(),
+                container_succeeded: report.successful_migrations > successes_before,
+                processed: index + 1,
+                total_containers,
+                successful_migrations: report.successful_migrations,
+                failed_migrations: report.failed_migrations.len(),
+                average_quality: average(&report.semantic_quality_scores),
+                average_accuracy: average(&report.reconstruction_accuracy),
+            });
         }
         
         report.migration_duration = start_time.elapsed();
@@ -102,6 +163,7 @@ impl SourceCodeMigrator {
             reconstruction_quality: 0.0,
             semantic_completeness: 0.0,
             migration_successful: false,
+            source_retained: false,
             error_messages: Vec::new(),
         };
 
@@ -159,8 +221,11 @@ impl SourceCodeMigrator {
         result.semantic_completeness = self.calculate_semantic_completeness(container.id).await?;
 
         // Step 5: Remove source_code field if quality is acceptable
-        if result.reconstruction_quality >= 0.7 && result.semantic_completeness >= 0.8 {
+        if self.meets_quality_gate(result) {
             self.remove_source_code_field(container.id).await?;
+        } else if self.per_container_threshold.is_some() {
+            // Keep the source; backup and enhanced metadata are still committed
+            result.source_retained = true;
         } else {
             return Err(anyhow!(
                 "Migration quality insufficient: reconstruction={:.1}%, semantic={:.1}%",
@@ -194,11 +259,21 @@ impl SourceCodeMigrator {
         let reconstructed = self.reconstruct_from_semantics(container.id).await?;
         result.reconstruction_quality = self.calculate_reconstruction_quality(source_code, &reconstructed);
 
-        result.migration_successful = result.reconstruction_quality >= 0.7 && result.semantic_completeness >= 0.8;
+        result.migration_successful = self.meets_quality_gate(result);
+        result.source_retained = !result.migration_successful && self.per_container_threshold.is_some();
 
         Ok(())
     }
 
+    fn meets_quality_gate(&self, result: &ContainerMigrationResult) -> bool {
+        match self.per_container_threshold {
+            Some(min_quality) => {
+                result.reconstruction_quality >= min_quality && result.semantic_completeness >= min_quality
+            }
+            None => result.reconstruction_quality >= 0.7 && result.semantic_completeness >= 0.8,
+        }
+    }
+
     async fn backup_source_code(&self, container: &Container, source_code: &str) -> Result<()> {
         sqlx::query(
             r#"
@@ -452,6 +527,13 @@ impl SourceCodeMigrator {
             println!("Average reconstruction accuracy: {:.1}%", avg_accuracy * 100.0);
         }
 
+        if !report.retained_for_review.is_empty() {
+            println!("\n⏸️  Retained for manual review:");
+            for (container_id, reason) in &report.retained_for_review {
+                println!("  {} - {}", container_id, reason);
+            }
+        }
+
         if !report.failed_migrations.is_empty() {
             println!("\n❌ Failed migrations:");
             for (container_id, error) in &report.failed_migrations {
@@ -517,6 +599,14 @@ impl SourceCodeMigrator {
     }
 }
 
+fn average(scores: &HashMap<Uuid, f64>) -> f64 {
+    if scores.is_empty() {
+        0.0
+    } else {
+        scores.values().sum::<f64>() / scores.len() as f64
+    }
+}
+
 #[derive(Debug)]
 struct EnhancedMetadata {
     semantic_signature: Value,
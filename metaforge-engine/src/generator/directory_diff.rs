@@ -0,0 +1,508 @@
+//! Comparing an original tree with its regenerated counterpart
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::scanner::FileScanner;
+
+/// Bumped whenever the JSON layout changes incompatibly
+pub const DIFF_FORMAT_VERSION: u32 = 1;
+
+/// Unchanged lines kept around each change
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// Width of each column of the side-by-side format
+pub const SIDE_BY_SIDE_WIDTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// A patch `git apply` and `patch -p1` understand
+    #[default]
+    Unified,
+    /// `DirectoryDiff` serialized, for tooling
+    Json,
+    /// Original and generated lines next to each other, for reading
+    SideBySide,
+}
+
+impl DiffFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "unified" => Some(Self::Unified),
+            "json" => Some(Self::Json),
+            "side-by-side" => Some(Self::SideBySide),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Modified,
+    /// Nothing was generated for the original file
+    OnlyInOriginal,
+    /// Generated without an original, e.g. a package `__init__.py`
+    OnlyInGenerated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Context,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineChange {
+    pub kind: ChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_line: Option<usize>,
+    pub text: String,
+    /// Set on a last line that doesn't end in a newline
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_newline_at_end: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hunk {
+    pub original_start: usize,
+    pub original_lines: usize,
+    pub generated_start: usize,
+    pub generated_lines: usize,
+    pub changes: Vec<LineChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub identical: usize,
+    pub modified: usize,
+    pub only_in_original: usize,
+    pub only_in_generated: usize,
+}
+
+/// Every difference between the original and generated trees, as rendered
+/// by `DiffFormat::Json`.
+///
+/// The JSON layout is versioned by `DIFF_FORMAT_VERSION`:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "original": "repos/app",
+///   "generated": "generated/repos/app",
+///   "summary": { "identical": 40, "modified": 2, "only_in_original": 1, "only_in_generated": 0 },
+///   "files": [                              // only files that differ
+///     {
+///       "path": "src/app.py",               // relative to both roots
+///       "status": "modified",               // or only_in_original, only_in_generated
+///       "hunks": [
+///         {
+///           "original_start": 3, "original_lines": 2,
+///           "generated_start": 3, "generated_lines": 2,
+///           "changes": [
+///             { "kind": "context", "original_line": 3, "generated_line": 3, "text": "def load():" },
+///             { "kind": "removed", "original_line": 4, "text": "    return 1" },
+///             { "kind": "added", "generated_line": 4, "text": "    return 2" }
+///           ]
+///         }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+///
+/// A last line without a trailing newline has `"no_newline_at_end": true`.
+/// Line numbers are 1-based. As in a unified diff, an empty range starts at
+/// the line it follows, 0 at the top of the file, and hunks carry
+/// `DEFAULT_CONTEXT` lines of context. A file present on one side only is a
+/// single hunk adding or removing all of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryDiff {
+    pub version: u32,
+    pub original: PathBuf,
+    pub generated: PathBuf,
+    pub summary: DiffSummary,
+    pub files: Vec<FileDiff>,
+}
+
+impl FileDiff {
+    /// Diff two versions of the file at `path`, `None` when they are equal
+    pub fn between(path: impl Into<PathBuf>, original: &str, generated: &str) -> Option<Self> {
+        if original == generated {
+            return None;
+        }
+        Some(Self {
+            path: path.into(),
+            status: FileStatus::Modified,
+            hunks: hunks(&line_changes(original, generated), DEFAULT_CONTEXT),
+        })
+    }
+
+    /// A file that exists in one tree only, as one hunk adding or removing it
+    pub fn one_sided(path: impl Into<PathBuf>, status: FileStatus, content: &str) -> Self {
+        let changes = match status {
+            FileStatus::OnlyInGenerated => line_changes("", content),
+            _ => line_changes(content, ""),
+        };
+        Self { path: path.into(), status, hunks: hunks(&changes, 0) }
+    }
+}
+
+impl DirectoryDiff {
+    /// Compare the source files under `original` with those under
+    /// `generated`, matching them by their path relative to each root
+    pub fn compare(original: &Path, generated: &Path) -> Result<Self> {
+        let scanner = FileScanner::new();
+        let originals = source_files(&scanner, original)?;
+        let mut generated_files = source_files(&scanner, generated)?;
+
+        let mut summary = DiffSummary::default();
+        let mut files = Vec::new();
+        for (path, content) in originals {
+            match generated_files.remove(&path) {
+                Some(regenerated) => match FileDiff::between(path, &content, &regenerated) {
+                    Some(diff) => {
+                        summary.modified += 1;
+                        files.push(diff);
+                    }
+                    None => summary.identical += 1,
+                },
+                None => {
+                    summary.only_in_original += 1;
+                    files.push(FileDiff::one_sided(path, FileStatus::OnlyInOriginal, &content));
+                }
+            }
+        }
+        for (path, content) in generated_files {
+            summary.only_in_generated += 1;
+            files.push(FileDiff::one_sided(path, FileStatus::OnlyInGenerated, &content));
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            version: DIFF_FORMAT_VERSION,
+            original: original.to_path_buf(),
+            generated: generated.to_path_buf(),
+            summary,
+            files,
+        })
+    }
+
+    pub fn is_identical(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn render(&self, format: DiffFormat) -> Result<String> {
+        Ok(match format {
+            DiffFormat::Unified => self.render_unified(),
+            DiffFormat::Json => serde_json::to_string_pretty(self)?,
+            DiffFormat::SideBySide => self.render_side_by_side(SIDE_BY_SIDE_WIDTH),
+        })
+    }
+
+    /// `a/` is the original tree and `b/` the generated one
+    pub fn render_unified(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            let path = file.path.to_string_lossy();
+            let (from, to) = match file.status {
+                FileStatus::Modified => (format!("a/{}", path), format!("b/{}", path)),
+                FileStatus::OnlyInOriginal => (format!("a/{}", path), "/dev/null".to_string()),
+                FileStatus::OnlyInGenerated => ("/dev/null".to_string(), format!("b/{}", path)),
+            };
+            out.push_str(&format!("--- {}\n+++ {}\n", from, to));
+            for hunk in &file.hunks {
+                out.push_str(&format!(
+                    "@@ -{} +{} @@\n",
+                    hunk_range(hunk.original_start, hunk.original_lines),
+                    hunk_range(hunk.generated_start, hunk.generated_lines),
+                ));
+                for change in &hunk.changes {
+                    let prefix = match change.kind {
+                        ChangeKind::Context => ' ',
+                        ChangeKind::Removed => '-',
+                        ChangeKind::Added => '+',
+                    };
+                    out.push(prefix);
+                    out.push_str(&change.text);
+                    out.push('\n');
+                    if change.no_newline_at_end {
+                        out.push_str("\\ No newline at end of file\n");
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Two `width`-character columns per file, original on the left; `|`
+    /// marks a changed line, `<` one only the original has and `>` one only
+    /// the generated file has
+    pub fn render_side_by_side(&self, width: usize) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            out.push_str(&format!("=== {} ({})\n", file.path.display(), status_label(file.status)));
+            for (index, hunk) in file.hunks.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(&format!("{:width$} ...\n", "", width = width));
+                }
+                for (left, marker, right) in side_by_side_rows(&hunk.changes) {
+                    let left = column(left, width);
+                    let row = match right {
+                        Some(right) => format!("{} {} {}", left, marker, column(Some(right), width)),
+                        None => format!("{} {}", left, marker),
+                    };
+                    out.push_str(row.trim_end());
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// One line of counts, e.g. for the end of a round trip
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{} identical, {} modified, {} only in original, {} only in generated",
+            self.summary.identical, self.summary.modified, self.summary.only_in_original, self.summary.only_in_generated,
+        )
+    }
+}
+
+/// Source files under `root` by their path relative to it; unreadable and
+/// non-UTF-8 files are left out, as migration leaves them out
+fn source_files(scanner: &FileScanner, root: &Path) -> Result<BTreeMap<PathBuf, String>> {
+    let mut files = BTreeMap::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    for entry in WalkDir::new(root).follow_links(false).into_iter().filter_entry(|e| !scanner.should_ignore(e.path())) {
+        let entry = entry.with_context(|| format!("Failed to walk {}", root.display()))?;
+        if !entry.file_type().is_file() || FileScanner::language_for(entry.path()).is_none() {
+            continue;
+        }
+        if let Ok(content) = std::fs::read_to_string(entry.path()) {
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            files.insert(relative.to_path_buf(), content);
+        }
+    }
+    Ok(files)
+}
+
+/// Every line of both versions in order, as context, removals and additions
+/// along a longest common subsequence. Lines keep their newline while being
+/// compared, so a file that only lost its final newline still differs.
+fn line_changes(original: &str, generated: &str) -> Vec<LineChange> {
+    let before: Vec<&str> = original.split_inclusive('\n').collect();
+    let after: Vec<&str> = generated.split_inclusive('\n').collect();
+    // Only the middle that differs needs the quadratic table
+    let prefix = before.iter().zip(&after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..].iter().rev().zip(after[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&before[prefix..before.len() - suffix], &after[prefix..after.len() - suffix]);
+
+    let (n, m) = (old.len(), new.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let change = |kind, original_line: Option<usize>, generated_line: Option<usize>, raw: &str| LineChange {
+        kind,
+        original_line: original_line.map(|i| i + 1),
+        generated_line: generated_line.map(|j| j + 1),
+        text: raw.strip_suffix('\n').unwrap_or(raw).to_string(),
+        no_newline_at_end: !raw.ends_with('\n'),
+    };
+    let context = |i: usize, j: usize| change(ChangeKind::Context, Some(i), Some(j), before[i]);
+    let removed = |i: usize| change(ChangeKind::Removed, Some(i), None, before[i]);
+    let added = |j: usize| change(ChangeKind::Added, None, Some(j), after[j]);
+
+    let mut changes: Vec<LineChange> = (0..prefix).map(|i| context(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            changes.push(context(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if j >= m || (i < n && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            changes.push(removed(prefix + i));
+            i += 1;
+        } else {
+            changes.push(added(prefix + j));
+            j += 1;
+        }
+    }
+    changes.extend((0..suffix).map(|k| context(prefix + n + k, prefix + m + k)));
+    changes
+}
+
+/// Group `changes` into hunks keeping `context` unchanged lines around each
+/// change and merging hunks whose context would overlap
+fn hunks(changes: &[LineChange], context: usize) -> Vec<Hunk> {
+    let changed: Vec<usize> = changes.iter()
+        .enumerate()
+        .filter(|(_, change)| change.kind != ChangeKind::Context)
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for index in changed {
+        let (start, end) = (index.saturating_sub(context), (index + context + 1).min(changes.len()));
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    // Each side's lines before the hunk; an empty range starts at the last of them
+    let lines_before = |end: usize, line: fn(&LineChange) -> Option<usize>| {
+        changes[..end].iter().filter(|change| line(change).is_some()).count()
+    };
+    let start_of = |before: usize, lines: usize| if lines == 0 { before } else { before + 1 };
+    ranges.into_iter()
+        .map(|(start, end)| {
+            let changes = changes[start..end].to_vec();
+            let original_lines = changes.iter().filter(|change| change.original_line.is_some()).count();
+            let generated_lines = changes.iter().filter(|change| change.generated_line.is_some()).count();
+            Hunk {
+                original_start: start_of(lines_before(start, |change| change.original_line), original_lines),
+                original_lines,
+                generated_start: start_of(lines_before(start, |change| change.generated_line), generated_lines),
+                generated_lines,
+                changes,
+            }
+        })
+        .collect()
+}
+
+/// `start,lines` of a unified hunk header, `start` alone for one line
+fn hunk_range(start: usize, lines: usize) -> String {
+    if lines == 1 {
+        start.to_string()
+    } else {
+        format!("{},{}", start, lines)
+    }
+}
+
+fn status_label(status: FileStatus) -> &'static str {
+    match status {
+        FileStatus::Modified => "modified",
+        FileStatus::OnlyInOriginal => "only in original",
+        FileStatus::OnlyInGenerated => "only in generated",
+    }
+}
+
+/// Rows of (original text, marker, generated text): context lines face
+/// themselves and each run of removals is paired with the additions after it
+fn side_by_side_rows(changes: &[LineChange]) -> Vec<(Option<&str>, char, Option<&str>)> {
+    let mut rows = Vec::new();
+    let mut index = 0;
+    while index < changes.len() {
+        if changes[index].kind == ChangeKind::Context {
+            rows.push((Some(changes[index].text.as_str()), ' ', Some(changes[index].text.as_str())));
+            index += 1;
+            continue;
+        }
+        let run_end = changes[index..].iter()
+            .position(|change| change.kind == ChangeKind::Context)
+            .map_or(changes.len(), |offset| index + offset);
+        let run = &changes[index..run_end];
+        let removed: Vec<&str> = run.iter().filter(|c| c.kind == ChangeKind::Removed).map(|c| c.text.as_str()).collect();
+        let added: Vec<&str> = run.iter().filter(|c| c.kind == ChangeKind::Added).map(|c| c.text.as_str()).collect();
+        for row in 0..removed.len().max(added.len()) {
+            let marker = match (removed.get(row), added.get(row)) {
+                (Some(_), Some(_)) => '|',
+                (Some(_), None) => '<',
+                _ => '>',
+            };
+            rows.push((removed.get(row).copied(), marker, added.get(row).copied()));
+        }
+        index = run_end;
+    }
+    rows
+}
+
+/// `text` cut or padded to exactly `width` characters
+fn column(text: Option<&str>, width: usize) -> String {
+    let text: String = text.unwrap_or("").replace('\t', "    ").chars().take(width).collect();
+    format!("{:width$}", text, width = width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// Test the round-trip comparison in each diff format
+    #[test]
+    fn test_directory_diff_formats() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("metaforge-diff-{}", Uuid::new_v4()));
+        let (original, generated) = (root.join("original"), root.join("generated"));
+        for (dir, files) in [
+            (&original, vec![("same.py", "x = 1\n"), ("app.py", "import os\n\ndef load():\n    return 1\n"), ("gone.py", "a = 1\nb = 2\n"), ("README.md", "docs\n"), (".git/hook.py", "ignored\n")]),
+            (&generated, vec![("same.py", "x = 1\n"), ("app.py", "import os\n\ndef load():\n    return 2\n"), ("pkg/__init__.py", "")]),
+        ] {
+            for (path, content) in files {
+                let path = dir.join(path);
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::write(path, content)?;
+            }
+        }
+        let diff = DirectoryDiff::compare(&original, &generated)?;
+        std::fs::remove_dir_all(&root)?;
+    
+        assert_eq!(diff.summary_line(), "1 identical, 1 modified, 1 only in original, 1 only in generated");
+        let statuses: Vec<(String, FileStatus)> = diff.files.iter().map(|file| (file.path.to_string_lossy().to_string(), file.status)).collect();
+        assert_eq!(statuses, vec![
+            ("app.py".to_string(), FileStatus::Modified),
+            ("gone.py".to_string(), FileStatus::OnlyInOriginal),
+            ("pkg/__init__.py".to_string(), FileStatus::OnlyInGenerated),
+        ]);
+    
+        let unified = diff.render(DiffFormat::Unified)?;
+        assert!(unified.starts_with("--- a/app.py\n+++ b/app.py\n@@ -1,4 +1,4 @@\n import os\n \n def load():\n-    return 1\n+    return 2\n"), "{}", unified);
+        assert!(unified.contains("--- a/gone.py\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-a = 1\n-b = 2\n"), "{}", unified);
+        assert!(unified.contains("--- /dev/null\n+++ b/pkg/__init__.py\n"), "{}", unified);
+    
+        let json: serde_json::Value = serde_json::from_str(&diff.render(DiffFormat::Json)?)?;
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["summary"]["modified"], 1);
+        assert_eq!(json["files"][0]["status"], "modified");
+        assert_eq!(json["files"][0]["hunks"][0]["changes"][3], serde_json::json!({"kind": "removed", "original_line": 4, "text": "    return 1"}));
+        assert_eq!(serde_json::from_value::<DirectoryDiff>(json)?, diff);
+    
+        let side_by_side = diff.render(DiffFormat::SideBySide)?;
+        assert!(side_by_side.contains("=== app.py (modified)"), "{}", side_by_side);
+        assert!(side_by_side.lines().any(|line| line.starts_with("    return 1") && line.contains(" | ") && line.ends_with("    return 2")), "{}", side_by_side);
+        assert!(side_by_side.lines().any(|line| line.starts_with("a = 1") && line.trim_end().ends_with('<')), "{}", side_by_side);
+    
+        // A lost final newline is a change, and hunks keep three lines of context
+        let file = FileDiff::between("tail.py", "a\nb\nc\nd\ne\nf\n", "a\nb\nc\nd\ne\nf").unwrap();
+        let hunk = &file.hunks[0];
+        assert_eq!((hunk.original_start, hunk.original_lines, hunk.generated_start, hunk.generated_lines), (3, 4, 3, 4));
+        assert_eq!(hunk.changes.iter().map(|change| change.kind).collect::<Vec<_>>(),
+            vec![ChangeKind::Context, ChangeKind::Context, ChangeKind::Context, ChangeKind::Removed, ChangeKind::Added]);
+        assert!(FileDiff::between("same.py", "x\n", "x\n").is_none());
+        Ok(())
+    }
+}
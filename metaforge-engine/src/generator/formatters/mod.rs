@@ -1,8 +1,27 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use ast_extractor::Language;
+use code_builders::{wait_with_deadline, BatchFormatOutcome, BatchFormatter, BuildConfig, IndentStyle};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use std::process::{Command, Stdio};
-use std::io::Write;
-
-/// Trait for code formatters - maintains compatibility with existing code
+use std::io::{Read, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::parser::string_literals::map_lines_outside_strings;
+
+/// Seconds an external formatter gets before it is killed and the builtin
+/// formatter is used instead
+pub const DEFAULT_FORMATTER_TIMEOUT_SECS: u64 = 10;
+
+/// A formatter for one language's code.
+///
+/// This is the extension point for formatting: implement it and pass it to
+/// `LanguageFormatters::register` to replace the builtin formatter of a
+/// language, or to add one for a language that has none. `format` returning
+/// an error makes `LanguageFormatters` fall back to the builtin formatter.
 pub trait CodeFormatter {
     fn format(&self, code: &str) -> Result<String>;
     fn is_available(&self) -> bool;
@@ -10,7 +29,174 @@ pub trait CodeFormatter {
 
 /// Get a formatter for a specific language - compatibility function
 pub fn get_formatter(language: &str) -> Box<dyn CodeFormatter> {
-    Box::new(CompatibilityFormatter::new(language))
+    get_formatter_with_config(language, &FormatConfig::default())
+}
+
+/// Get a formatter that passes `config`'s settings to the external tool
+pub fn get_formatter_with_config(language: &str, config: &FormatConfig) -> Box<dyn CodeFormatter> {
+    Box::new(CompatibilityFormatter::new(language, config.clone()))
+}
+
+/// Settings passed to an external formatter. Unset fields keep the defaults
+/// we have always used: line length 88 for black, 80 for prettier, indent 2
+/// for prettier and edition 2021 for rustfmt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormatSettings {
+    pub max_line_length: Option<usize>,
+    pub indent_width: Option<usize>,
+    pub use_tabs: Option<bool>,
+    /// Rust edition passed to rustfmt
+    pub edition: Option<String>,
+}
+
+impl FormatSettings {
+    /// Fields set here, falling back to `fallback`'s
+    pub fn or(&self, fallback: &FormatSettings) -> FormatSettings {
+        FormatSettings {
+            max_line_length: self.max_line_length.or(fallback.max_line_length),
+            indent_width: self.indent_width.or(fallback.indent_width),
+            use_tabs: self.use_tabs.or(fallback.use_tabs),
+            edition: self.edition.clone().or_else(|| fallback.edition.clone()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self == &FormatSettings::default()
+    }
+}
+
+/// Formatter settings for every language, with per-language overrides.
+///
+/// As JSON: `{"max_line_length": 100, "languages": {"rust": {"edition": "2018"}}}`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormatConfig {
+    #[serde(flatten)]
+    pub defaults: FormatSettings,
+    #[serde(default)]
+    pub languages: HashMap<String, FormatSettings>,
+    /// Seconds before a hung formatter is killed, `DEFAULT_FORMATTER_TIMEOUT_SECS` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// External commands used instead of the builtin formatter, by language:
+    /// `{"commands": {"python": {"command": "ruff", "args": ["format", "-"]}}}`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub commands: HashMap<String, FormatterCommand>,
+}
+
+/// A custom formatter command, given the code on stdin and expected to
+/// print the formatted code on stdout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormatterCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl FormatConfig {
+    /// Read a JSON format config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read format config {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid format config {}", path.display()))
+    }
+
+    /// Settings from the formatter config files in `dir`: `rustfmt.toml`,
+    /// `.prettierrc` and black's section of `pyproject.toml`
+    pub fn discover(dir: &Path) -> Self {
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+        let mut config = Self::default();
+
+        if let Some(content) = read("rustfmt.toml").or_else(|| read(".rustfmt.toml")) {
+            config = config.with_language("rust", parse_rustfmt_toml(&content));
+        }
+        if let Some(content) = read(".prettierrc").or_else(|| read(".prettierrc.json")) {
+            let settings = parse_prettierrc(&content);
+            config = config
+                .with_language("javascript", settings.clone())
+                .with_language("typescript", settings);
+        }
+        if let Some(settings) = read("pyproject.toml").and_then(|content| parse_black_config(&content)) {
+            config = config.with_language("python", settings);
+        }
+        config
+    }
+
+    /// Override the default settings for `language`
+    pub fn with_language(mut self, language: &str, settings: FormatSettings) -> Self {
+        self.languages.insert(language.to_string(), settings);
+        self
+    }
+
+    /// Format `language` with an external command instead of the builtin formatter
+    pub fn with_command(mut self, language: &str, command: &str, args: &[&str]) -> Self {
+        self.commands.insert(language.to_string(), FormatterCommand {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Effective settings for `language`
+    pub fn settings_for(&self, language: &str) -> FormatSettings {
+        match self.languages.get(&language.to_lowercase()) {
+            Some(settings) => settings.or(&self.defaults),
+            None => self.defaults.clone(),
+        }
+    }
+}
+
+/// `key = value` pairs of one TOML table (`None` for the top level), unquoted
+fn toml_pairs<'a>(content: &'a str, table: Option<&str>) -> Vec<(&'a str, &'a str)> {
+    let mut current: Option<&str> = None;
+    let mut pairs = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            current = Some(line.trim_matches(|c| c == '[' || c == ']').trim());
+        } else if let Some((key, value)) = line.split_once('=') {
+            if current == table {
+                let value = value.split('#').next().unwrap_or("").trim().trim_matches('"');
+                pairs.push((key.trim(), value));
+            }
+        }
+    }
+    pairs
+}
+
+fn parse_rustfmt_toml(content: &str) -> FormatSettings {
+    let mut settings = FormatSettings::default();
+    for (key, value) in toml_pairs(content, None) {
+        match key {
+            "max_width" => settings.max_line_length = value.parse().ok(),
+            "tab_spaces" => settings.indent_width = value.parse().ok(),
+            "hard_tabs" => settings.use_tabs = value.parse().ok(),
+            "edition" => settings.edition = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    settings
+}
+
+fn parse_prettierrc(content: &str) -> FormatSettings {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+        return FormatSettings::default();
+    };
+    let number = |key: &str| json.get(key).and_then(|v| v.as_u64()).map(|n| n as usize);
+    FormatSettings {
+        max_line_length: number("printWidth"),
+        indent_width: number("tabWidth"),
+        use_tabs: json.get("useTabs").and_then(|v| v.as_bool()),
+        edition: None,
+    }
+}
+
+fn parse_black_config(content: &str) -> Option<FormatSettings> {
+    let line_length = toml_pairs(content, Some("tool.black")).into_iter()
+        .find(|(key, _)| *key == "line-length" || *key == "line_length")?
+        .1
+        .parse()
+        .ok()?;
+    Some(FormatSettings { max_line_length: Some(line_length), ..FormatSettings::default() })
 }
 
 /// Compatibility wrapper that implements the old CodeFormatter trait
@@ -20,10 +206,10 @@ struct CompatibilityFormatter {
 }
 
 impl CompatibilityFormatter {
-    fn new(language: &str) -> Self {
+    fn new(language: &str, config: FormatConfig) -> Self {
         Self {
             language: language.to_string(),
-            formatters: LanguageFormatters::new(),
+            formatters: LanguageFormatters::new().with_config(config),
         }
     }
 }
@@ -34,334 +220,496 @@ impl CodeFormatter for CompatibilityFormatter {
     }
     
     fn is_available(&self) -> bool {
-        // Check if external formatter is available
-        match self.language.to_lowercase().as_str() {
-            "rust" => Command::new("rustfmt").arg("--version").output().is_ok(),
-            "python" => Command::new("black").arg("--version").output().is_ok(),
-            "javascript" | "typescript" => Command::new("prettier").arg("--version").output().is_ok(),
-            "go" => Command::new("gofmt").arg("-h").output().is_ok(),
-            "cpp" | "c++" => Command::new("clang-format").arg("--version").output().is_ok(),
-            _ => true, // Basic formatting always available
+        if let Some(formatter) = self.formatters.custom_formatter(&self.language) {
+            return formatter.is_available();
+        }
+        // Check if external formatter is available, within the formatter timeout
+        let timeout = self.formatters.timeout();
+        match self.language.parse::<Language>().ok().and_then(Language::default_formatter) {
+            Some("gofmt") => run_external("gofmt", &[], "", timeout).is_some(),
+            Some(tool) => run_external(tool, &["--version".to_string()], "", timeout).is_some(),
+            None => true, // Basic formatting always available
         }
     }
 }
 
-/// Language formatters for Phase 1B template completion
-#[derive(Debug, Clone)]
-pub struct LanguageFormatters;
+/// Runs a configured `FormatterCommand`
+pub struct CommandFormatter {
+    command: FormatterCommand,
+    timeout: Duration,
+}
 
-impl LanguageFormatters {
-    pub fn new() -> Self {
-        Self
+impl CommandFormatter {
+    pub fn new(command: FormatterCommand) -> Self {
+        Self { command, timeout: Duration::from_secs(DEFAULT_FORMATTER_TIMEOUT_SECS) }
     }
 
-    /// Format code using appropriate language formatter
-    pub fn format_code(&self, code: &str, language: &str) -> Result<String> {
-        match language.to_lowercase().as_str() {
-            "rust" => self.format_rust(code),
-            "python" => self.format_python(code),
-            "javascript" | "js" => self.format_javascript(code),
-            "typescript" | "ts" => self.format_typescript(code),
-            "go" => self.format_go(code),
-            "java" => self.format_java(code),
-            "csharp" | "cs" => self.format_csharp(code),
-            "cpp" | "c++" => self.format_cpp(code),
-            "ruby" => self.format_ruby(code),
-            "php" => self.format_php(code),
-            _ => Ok(code.to_string()), // Return unformatted for unsupported languages
-        }
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
     }
+}
 
-    fn format_rust(&self, code: &str) -> Result<String> {
-        // Use rustfmt if available
-        let mut child = match Command::new("rustfmt")
-            .arg("--edition")
-            .arg("2021")
-            .arg("--emit")
-            .arg("stdout")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(_) => return Ok(self.basic_rust_format(code)),
-        };
-
-        // Write code to stdin
-        if let Some(ref mut stdin) = child.stdin {
-            let _ = stdin.write_all(code.as_bytes());
-        }
+impl CodeFormatter for CommandFormatter {
+    fn format(&self, code: &str) -> Result<String> {
+        run_external(&self.command.command, &self.command.args, code, self.timeout)
+            .with_context(|| format!("Formatter command {} failed", self.command.command))
+    }
 
-        // Get output
-        match child.wait_with_output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    Ok(self.basic_rust_format(code))
-                }
-            }
-            Err(_) => Ok(self.basic_rust_format(code)),
+    fn is_available(&self) -> bool {
+        let command = Path::new(&self.command.command);
+        if command.components().count() > 1 {
+            return command.is_file();
         }
+        std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).any(|dir| dir.join(command).is_file()))
+            .unwrap_or(false)
     }
+}
 
-    fn format_python(&self, code: &str) -> Result<String> {
-        // Try black formatter first
-        let mut child = match Command::new("black")
-            .arg("--line-length")
-            .arg("88")
-            .arg("--quiet")
-            .arg("-")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(_) => return Ok(self.basic_python_format(code)),
-        };
+/// Language formatters for Phase 1B template completion
+#[derive(Clone, Default)]
+pub struct LanguageFormatters {
+    config: FormatConfig,
+    /// Formatters registered at runtime, by lowercase canonical language name
+    registered: HashMap<String, Arc<dyn CodeFormatter>>,
+}
 
-        // Write code to stdin
-        if let Some(ref mut stdin) = child.stdin {
-            let _ = stdin.write_all(code.as_bytes());
-        }
+impl std::fmt::Debug for LanguageFormatters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut registered: Vec<&String> = self.registered.keys().collect();
+        registered.sort();
+        f.debug_struct("LanguageFormatters")
+            .field("config", &self.config)
+            .field("registered", &registered)
+            .finish()
+    }
+}
 
-        // Get output
-        match child.wait_with_output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    Ok(self.basic_python_format(code))
-                }
+impl LanguageFormatters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `config`'s settings, and its commands for languages without a
+    /// registered formatter
+    pub fn with_config(mut self, config: FormatConfig) -> Self {
+        self.config = config;
+        let timeout = self.timeout();
+        for (language, command) in self.config.commands.clone() {
+            if !self.registered.contains_key(&Language::canonical_name(&language).to_lowercase()) {
+                self.register(&language, Box::new(CommandFormatter::new(command).with_timeout(timeout)));
             }
-            Err(_) => Ok(self.basic_python_format(code)),
         }
+        self
     }
 
-    fn format_javascript(&self, code: &str) -> Result<String> {
-        // Use prettier if available
-        let mut child = match Command::new("prettier")
-            .arg("--parser")
-            .arg("babel")
-            .arg("--print-width")
-            .arg("80")
-            .arg("--tab-width")
-            .arg("2")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(_) => return Ok(self.basic_js_format(code)),
-        };
+    /// Use `formatter` for `language`, replacing its builtin formatter and
+    /// any configured command
+    pub fn register(&mut self, language: &str, formatter: Box<dyn CodeFormatter>) {
+        self.registered.insert(Language::canonical_name(language).to_lowercase(), Arc::from(formatter));
+    }
 
-        // Write code to stdin
-        if let Some(ref mut stdin) = child.stdin {
-            let _ = stdin.write_all(code.as_bytes());
-        }
+    /// The formatter registered or configured for `language`; `None` when
+    /// the builtin formatter applies
+    pub fn custom_formatter(&self, language: &str) -> Option<Arc<dyn CodeFormatter>> {
+        self.registered.get(&Language::canonical_name(language).to_lowercase()).cloned()
+    }
 
-        // Get output
-        match child.wait_with_output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    /// Command-line arguments for `language`'s external formatter
+    pub fn tool_args(&self, language: &str) -> Vec<String> {
+        let language = Language::canonical_name(language).to_lowercase();
+        let settings = self.config.settings_for(&language);
+        let tabs = settings.use_tabs.unwrap_or(false);
+        let mut args: Vec<String> = Vec::new();
+
+        match language.as_str() {
+            "rust" => {
+                args.extend(["--edition".to_string(), settings.edition.clone().unwrap_or_else(|| "2021".to_string())]);
+                args.extend(["--emit".to_string(), "stdout".to_string()]);
+                let mut options = Vec::new();
+                if let Some(width) = settings.max_line_length {
+                    options.push(format!("max_width={}", width));
+                }
+                if let Some(indent) = settings.indent_width {
+                    options.push(format!("tab_spaces={}", indent));
+                }
+                if let Some(use_tabs) = settings.use_tabs {
+                    options.push(format!("hard_tabs={}", use_tabs));
+                }
+                if !options.is_empty() {
+                    args.extend(["--config".to_string(), options.join(",")]);
+                }
+            }
+            "python" => {
+                args.extend(["--line-length".to_string(), settings.max_line_length.unwrap_or(88).to_string()]);
+                args.extend(["--quiet".to_string(), "-".to_string()]);
+            }
+            "javascript" | "typescript" => {
+                let parser = if language == "javascript" { "babel" } else { "typescript" };
+                args.extend(["--parser".to_string(), parser.to_string()]);
+                args.extend(["--print-width".to_string(), settings.max_line_length.unwrap_or(80).to_string()]);
+                args.extend(["--tab-width".to_string(), settings.indent_width.unwrap_or(2).to_string()]);
+                if tabs {
+                    args.push("--use-tabs".to_string());
+                }
+            }
+            "cpp" => {
+                if settings.is_empty() {
+                    args.push("--style=LLVM".to_string());
                 } else {
-                    Ok(self.basic_js_format(code))
+                    let mut style = vec!["BasedOnStyle: LLVM".to_string()];
+                    if let Some(width) = settings.max_line_length {
+                        style.push(format!("ColumnLimit: {}", width));
+                    }
+                    if let Some(indent) = settings.indent_width {
+                        style.push(format!("IndentWidth: {}", indent));
+                    }
+                    if tabs {
+                        style.push("UseTab: Always".to_string());
+                    }
+                    args.push(format!("--style={{{}}}", style.join(", ")));
                 }
             }
-            Err(_) => Ok(self.basic_js_format(code)),
+            _ => {}
         }
+        args
     }
 
-    fn format_typescript(&self, code: &str) -> Result<String> {
-        // Use prettier with TypeScript parser
-        let mut child = match Command::new("prettier")
-            .arg("--parser")
-            .arg("typescript")
-            .arg("--print-width")
-            .arg("80")
-            .arg("--tab-width")
-            .arg("2")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(_) => return Ok(self.basic_js_format(code)),
-        };
-
-        // Write code to stdin
-        if let Some(ref mut stdin) = child.stdin {
-            let _ = stdin.write_all(code.as_bytes());
-        }
-
-        // Get output
-        match child.wait_with_output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    Ok(self.basic_js_format(code))
-                }
-            }
-            Err(_) => Ok(self.basic_js_format(code)),
+    /// The formatter that takes all of `language`'s files in one
+    /// invocation; `None` when a custom formatter replaces the builtin one or
+    /// the builtin one formats a file at a time
+    pub fn batch_formatter(&self, language: &str) -> Option<BatchFormatter> {
+        if self.custom_formatter(language).is_some() {
+            return None;
         }
+        BatchFormatter::for_language(language)
     }
 
-    fn format_go(&self, code: &str) -> Result<String> {
-        // Use gofmt
-        let mut child = match Command::new("gofmt")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(_) => return Ok(self.basic_go_format(code)),
+    /// Format `(path, code)` pairs of `language` with one invocation of its
+    /// batch formatter, using the same settings as `tool_args`
+    pub fn format_batch(&self, files: &[(String, String)], language: &str) -> Result<BatchFormatOutcome> {
+        let formatter = self.batch_formatter(language)
+            .with_context(|| format!("No batch formatter for {}", language))?;
+        let settings = self.config.settings_for(&Language::canonical_name(language).to_lowercase());
+        // Unset fields keep each tool's own defaults
+        let (default_width, default_indent) = match formatter {
+            BatchFormatter::Prettier => (80, 2),
+            BatchFormatter::Rustfmt => (100, 4),
         };
-
-        // Write code to stdin
-        if let Some(ref mut stdin) = child.stdin {
-            let _ = stdin.write_all(code.as_bytes());
+        let mut config = BuildConfig {
+            language: language.to_string(),
+            max_line_length: settings.max_line_length.unwrap_or(default_width),
+            indent_style: if settings.use_tabs.unwrap_or(false) {
+                IndentStyle::Tabs
+            } else {
+                IndentStyle::Spaces(settings.indent_width.unwrap_or(default_indent))
+            },
+            ..BuildConfig::default()
+        };
+        if let Some(edition) = settings.edition {
+            config.generation_hints.insert("rust_edition".to_string(), serde_json::json!(edition));
         }
+        Ok(formatter.format_files(files, &config, self.timeout())?)
+    }
 
-        // Get output
-        match child.wait_with_output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    Ok(self.basic_go_format(code))
-                }
+    /// Stable hash of the settings and commands formatting depends on
+    pub fn config_hash(&self) -> String {
+        // Sort the maps so equal configs always serialize identically
+        let mut value = serde_json::to_value(&self.config).unwrap_or_default();
+        for map in ["languages", "commands"] {
+            if let Some(entries) = value.get_mut(map).and_then(|entries| entries.as_object_mut()) {
+                let sorted: std::collections::BTreeMap<_, _> = std::mem::take(entries).into_iter().collect();
+                *entries = sorted.into_iter().collect();
             }
-            Err(_) => Ok(self.basic_go_format(code)),
         }
+        blake3::hash(value.to_string().as_bytes()).to_hex().to_string()
     }
 
-    fn format_java(&self, code: &str) -> Result<String> {
-        // Google Java Format or basic formatting
-        Ok(self.basic_java_format(code))
+    /// How long an external formatter may run
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs.unwrap_or(DEFAULT_FORMATTER_TIMEOUT_SECS))
     }
 
-    fn format_csharp(&self, code: &str) -> Result<String> {
-        // dotnet format or basic formatting
-        Ok(self.basic_csharp_format(code))
+    /// Pipe `code` through `tool`. `None` when the tool is missing, fails,
+    /// or is still running after `timeout`, in which case it is killed.
+    pub fn run_formatter(&self, tool: &str, args: &[String], code: &str) -> Option<String> {
+        run_external(tool, args, code, self.timeout())
     }
 
-    fn format_cpp(&self, code: &str) -> Result<String> {
-        // clang-format if available
-        let mut child = match Command::new("clang-format")
-            .arg("--style=LLVM")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(_) => return Ok(self.basic_cpp_format(code)),
-        };
-
-        // Write code to stdin
-        if let Some(ref mut stdin) = child.stdin {
-            let _ = stdin.write_all(code.as_bytes());
-        }
+    /// Format code using appropriate language formatter: a registered
+    /// formatter, then a configured command, then the builtin one
+    pub fn format_code(&self, code: &str, language: &str) -> Result<String> {
+        Ok(self.format_with_tool(code, language)
+            .unwrap_or_else(|| self.basic_format(code, language)))
+    }
 
-        // Get output
-        match child.wait_with_output() {
-            Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    Ok(self.basic_cpp_format(code))
-                }
+    /// `code` formatted by a registered formatter or the language's external
+    /// tool. `None` when neither produced output, so only the builtin
+    /// formatter would run; callers caching formatted code skip those.
+    pub fn format_with_tool(&self, code: &str, language: &str) -> Option<String> {
+        if let Some(formatter) = self.custom_formatter(language) {
+            match formatter.format(code) {
+                Ok(formatted) => return Some(formatted),
+                Err(e) => tracing::warn!("Custom {} formatter failed, using the builtin formatter: {:#}", language, e),
             }
-            Err(_) => Ok(self.basic_cpp_format(code)),
         }
+        // Java, C#, Ruby and PHP have no external formatter wired up yet
+        let (tool, args) = match language.parse::<Language>().ok()? {
+            Language::Rust => ("rustfmt", self.tool_args("rust")),
+            Language::Python => ("black", self.tool_args("python")),
+            Language::JavaScript => ("prettier", self.tool_args("javascript")),
+            Language::TypeScript | Language::Tsx => ("prettier", self.tool_args("typescript")),
+            Language::Go => ("gofmt", Vec::new()),
+            Language::Cpp | Language::C => ("clang-format", self.tool_args("cpp")),
+            _ => return None,
+        };
+        self.run_formatter(tool, &args, code)
     }
 
-    fn format_ruby(&self, code: &str) -> Result<String> {
-        // RuboCop or basic formatting
-        Ok(self.basic_ruby_format(code))
-    }
-
-    fn format_php(&self, code: &str) -> Result<String> {
-        // PHP-CS-Fixer or basic formatting
-        Ok(self.basic_php_format(code))
+    /// The builtin formatting `format_code` falls back to
+    pub fn basic_format(&self, code: &str, language: &str) -> String {
+        match language.parse::<Language>() {
+            Ok(Language::Rust) => self.basic_rust_format(code),
+            Ok(Language::Python) => self.basic_python_format(code),
+            Ok(Language::JavaScript | Language::TypeScript | Language::Tsx) => self.basic_js_format(code),
+            Ok(Language::Go) => self.basic_go_format(code),
+            Ok(Language::Java) => self.basic_java_format(code),
+            Ok(Language::CSharp) => self.basic_csharp_format(code),
+            Ok(Language::Cpp | Language::C) => self.basic_cpp_format(code),
+            Ok(Language::Ruby) => self.basic_ruby_format(code),
+            Ok(Language::Php) => self.basic_php_format(code),
+            _ => code.to_string(), // Return unformatted for unsupported languages
+        }
     }
 
-    // Basic formatting fallbacks for when external formatters aren't available
+    // Basic formatting fallbacks for when external formatters aren't available.
+    // Lines inside multi-line string literals are the string's content, so
+    // they are never touched.
 
     fn basic_rust_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "rust", |line| line.trim_start().to_string())
     }
 
     fn basic_python_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| {
-                let trimmed = line.trim_start();
-                if trimmed.is_empty() {
-                    String::new()
-                } else {
-                    format!("{}", trimmed)
-                }
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
+        // Indentation is syntax in Python, so only trailing whitespace goes
+        map_lines_outside_strings(code, "python", |line| line.trim_end().to_string())
     }
 
     fn basic_js_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "javascript", |line| line.trim_start().to_string())
     }
 
     fn basic_go_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "go", |line| line.trim_start().to_string())
     }
 
     fn basic_java_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "java", |line| line.trim_start().to_string())
     }
 
     fn basic_csharp_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "csharp", |line| line.trim_start().to_string())
     }
 
     fn basic_cpp_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "cpp", |line| line.trim_start().to_string())
     }
 
     fn basic_ruby_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "ruby", |line| line.trim_start().to_string())
     }
 
     fn basic_php_format(&self, code: &str) -> String {
-        code.lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n")
+        map_lines_outside_strings(code, "php", |line| line.trim_start().to_string())
+    }
+}
+
+/// Pipe `code` through `tool`. `None` when the tool is missing, fails,
+/// or is still running after `timeout`, in which case it is killed.
+fn run_external(tool: &str, args: &[String], code: &str, timeout: Duration) -> Option<String> {
+    let mut child = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Feed and drain the pipes on their own threads so a tool that stops
+    // reading or writing can't block us past the deadline
+    let mut stdin = child.stdin.take()?;
+    let input = code.to_string();
+    let writer = thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let mut stdout = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let status = match wait_with_deadline(&mut child, timeout) {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            tracing::warn!("{} did not finish within {:?}, using the builtin formatter", tool, timeout);
+            return None;
+        }
+        Err(_) => return None,
+    };
+
+    let _ = writer.join();
+    let output = reader.join().ok()?;
+    status.success().then(|| String::from_utf8_lossy(&output).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    struct Uppercase;
+
+    impl CodeFormatter for Uppercase {
+        fn format(&self, code: &str) -> Result<String> {
+            Ok(code.to_uppercase())
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_only_tool_output_counts_as_formatted_with_a_tool() {
+        let mut formatters = LanguageFormatters::new();
+        let code = "class A {\n    int x;\n}";
+
+        // Java has no external formatter, so only the builtin one runs
+        assert_eq!(formatters.format_with_tool(code, "java"), None);
+        assert_eq!(formatters.format_code(code, "java").unwrap(), formatters.basic_format(code, "java"));
+
+        formatters.register("java", Box::new(Uppercase));
+        assert_eq!(formatters.format_with_tool(code, "java").as_deref(), Some("CLASS A {\n    INT X;\n}"));
+    }
+
+    #[test]
+    fn test_format_config_reaches_formatter_arguments() -> Result<()> {
+        let project = std::env::temp_dir().join(format!("metaforge-format-config-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&project)?;
+        std::fs::write(project.join("rustfmt.toml"), "edition = \"2018\"\nmax_width = 100 # wide screens\n")?;
+        std::fs::write(project.join(".prettierrc"), r#"{"printWidth": 100, "tabWidth": 4}"#)?;
+        std::fs::write(project.join("pyproject.toml"), "[tool.isort]\nline_length = 70\n\n[tool.black]\nline-length = 100\n")?;
+        let discovered = FormatConfig::discover(&project);
+        std::fs::remove_dir_all(&project)?;
+
+        let formatters = LanguageFormatters::new().with_config(discovered);
+        assert_eq!(formatters.tool_args("rust"), vec!["--edition", "2018", "--emit", "stdout", "--config", "max_width=100"]);
+        assert_eq!(formatters.tool_args("ts"), vec!["--parser", "typescript", "--print-width", "100", "--tab-width", "4"]);
+        assert_eq!(formatters.tool_args("python"), vec!["--line-length", "100", "--quiet", "-"]);
+
+        // Explicit config: shared defaults with per-language overrides
+        let config: FormatConfig = serde_json::from_value(serde_json::json!({
+            "max_line_length": 120,
+            "indent_width": 4,
+            "languages": {"rust": {"edition": "2018", "use_tabs": true}},
+        }))?;
+        let formatters = LanguageFormatters::new().with_config(config);
+        assert_eq!(
+            formatters.tool_args("rust"),
+            vec!["--edition", "2018", "--emit", "stdout", "--config", "max_width=120,tab_spaces=4,hard_tabs=true"]
+        );
+        assert_eq!(formatters.tool_args("cpp"), vec!["--style={BasedOnStyle: LLVM, ColumnLimit: 120, IndentWidth: 4}"]);
+
+        // Without settings the historical defaults apply
+        let defaults = LanguageFormatters::new();
+        assert_eq!(defaults.tool_args("python"), vec!["--line-length", "88", "--quiet", "-"]);
+        assert_eq!(defaults.tool_args("javascript"), vec!["--parser", "babel", "--print-width", "80", "--tab-width", "2"]);
+        assert_eq!(defaults.tool_args("cpp"), vec!["--style=LLVM"]);
+        Ok(())
+    }
+
+    /// Test which languages are formatted in one batched invocation
+    #[test]
+    fn test_batch_formatter_selection() {
+        let defaults = LanguageFormatters::new();
+        assert_eq!(defaults.batch_formatter("rust"), Some(BatchFormatter::Rustfmt));
+        assert_eq!(defaults.batch_formatter("typescript"), Some(BatchFormatter::Prettier));
+        assert_eq!(defaults.batch_formatter("python"), None);
+
+        // A configured command formats file by file instead
+        let custom = LanguageFormatters::new()
+            .with_config(FormatConfig::default().with_command("rust", "cat", &[]));
+        assert_eq!(custom.batch_formatter("rust"), None);
+        assert!(custom.format_batch(&[("lib.rs".to_string(), "fn main() {}".to_string())], "rust").is_err());
+    }
+
+    /// Test that cached formatting is keyed on every formatter setting
+    #[test]
+    fn test_formatter_config_hash_tracks_settings() {
+        let defaults = LanguageFormatters::new().config_hash();
+        assert_eq!(defaults, LanguageFormatters::new().config_hash());
+
+        let with_command = LanguageFormatters::new()
+            .with_config(FormatConfig::default().with_command("rust", "cat", &[]));
+        assert_ne!(with_command.config_hash(), defaults);
+
+        let settings = FormatSettings { max_line_length: Some(120), ..FormatSettings::default() };
+        let with_settings = LanguageFormatters::new()
+            .with_config(FormatConfig::default().with_language("python", settings));
+        assert_ne!(with_settings.config_hash(), defaults);
+    }
+
+    /// Test that a formatter that never finishes is killed at the timeout
+    #[cfg(unix)]
+    #[test]
+    fn test_hung_formatter_is_killed_after_the_timeout() -> Result<()> {
+        let config: FormatConfig = serde_json::from_value(serde_json::json!({"timeout_secs": 1}))?;
+        let formatters = LanguageFormatters::new().with_config(config);
+        assert_eq!(formatters.timeout(), std::time::Duration::from_secs(1));
+        assert_eq!(LanguageFormatters::new().timeout(), std::time::Duration::from_secs(10));
+
+        let started = std::time::Instant::now();
+        assert_eq!(formatters.run_formatter("sleep", &["30".to_string()], "fn main() {}"), None);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        // A tool that finishes in time is used as before
+        assert_eq!(formatters.run_formatter("cat", &[], "fn main() {}"), Some("fn main() {}".to_string()));
+        assert_eq!(formatters.run_formatter("metaforge-missing-formatter", &[], "x"), None);
+        Ok(())
+    }
+
+    /// Test that registered formatters and configured commands replace the builtin ones
+    #[cfg(unix)]
+    #[test]
+    fn test_custom_formatters_override_builtin_ones() -> Result<()> {
+        let mut formatters = LanguageFormatters::new();
+        formatters.register("py", Box::new(Uppercase));
+        assert_eq!(formatters.format_code("def run():\n    pass", "python")?, "DEF RUN():\n    PASS");
+        // Other languages keep the builtin formatter
+        assert_eq!(formatters.format_code("    int x;", "java")?, "int x;");
+
+        let config: FormatConfig = serde_json::from_value(serde_json::json!({
+            "commands": {
+                "ruby": {"command": "tr", "args": ["a-z", "A-Z"]},
+                "php": {"command": "metaforge-missing-formatter"},
+            },
+        }))?;
+        let formatters = LanguageFormatters::new().with_config(config.clone());
+        assert_eq!(formatters.format_code("puts 'hi'", "ruby")?, "PUTS 'HI'");
+        // A command that can't run falls back to the builtin formatter
+        assert_eq!(formatters.format_code("    echo 1;", "php")?, "echo 1;");
+
+        // A registered formatter wins over a configured command
+        let mut formatters = formatters;
+        formatters.register("ruby", Box::new(Uppercase));
+        assert_eq!(formatters.format_code("puts 'hi'", "rb")?, "PUTS 'HI'");
+
+        assert!(get_formatter_with_config("ruby", &config).is_available());
+        assert!(!get_formatter_with_config("php", &config).is_available());
+        let config = FormatConfig::default().with_command("ruby", "tr", &["a-z", "A-Z"]);
+        assert_eq!(get_formatter_with_config("ruby", &config).format("end")?, "END");
+        Ok(())
     }
 }
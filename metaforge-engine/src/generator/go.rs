@@ -0,0 +1,406 @@
+//! Go generation
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use super::templates::{LanguageTemplate, TemplateEngine};
+
+/// Key under `abstract_syntax` holding a serialized `GoDeclaration`
+pub const GO_DECLARATION_KEY: &str = "go_declaration";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GoDeclaration {
+    Package {
+        name: String,
+    },
+    Import {
+        specs: Vec<GoImport>,
+        /// Written as a parenthesized `import ( ... )` group
+        grouped: bool,
+    },
+    Function {
+        name: String,
+        type_parameters: Vec<GoParameter>,
+        parameters: Vec<GoParameter>,
+        results: Vec<GoParameter>,
+        body: Option<String>,
+    },
+    Method {
+        receiver: GoParameter,
+        name: String,
+        parameters: Vec<GoParameter>,
+        results: Vec<GoParameter>,
+        body: Option<String>,
+    },
+    Struct {
+        name: String,
+        type_parameters: Vec<GoParameter>,
+        fields: Vec<GoField>,
+    },
+    Interface {
+        name: String,
+        type_parameters: Vec<GoParameter>,
+        /// Method specs and embedded types, one per line
+        elements: Vec<String>,
+    },
+    /// `type Celsius float64`, or `type Name = string` when `alias` is set
+    Type {
+        name: String,
+        type_parameters: Vec<GoParameter>,
+        underlying: String,
+        alias: bool,
+    },
+    /// A top-level `var` or `const` declaration
+    Value {
+        keyword: GoValueKeyword,
+        specs: Vec<GoValueSpec>,
+        /// Written as a parenthesized `const ( ... )` group
+        grouped: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoValueKeyword {
+    Var,
+    Const,
+}
+
+impl GoValueKeyword {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoValueKeyword::Var => "var",
+            GoValueKeyword::Const => "const",
+        }
+    }
+}
+
+/// One `a, b int = 1, 2` line; in a const group both type and values may be
+/// left out to repeat the previous spec
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoValueSpec {
+    pub names: Vec<String>,
+    pub value_type: Option<String>,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoImport {
+    pub alias: Option<String>,
+    pub path: String,
+}
+
+/// A parameter, result or type parameter declaration; `a, b int` has two names
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoParameter {
+    pub names: Vec<String>,
+    pub param_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoField {
+    /// Empty for embedded fields
+    pub names: Vec<String>,
+    pub field_type: String,
+    pub tag: Option<String>,
+}
+
+impl GoDeclaration {
+    /// Read the declaration stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(GO_DECLARATION_KEY)?.clone()).ok()
+    }
+}
+
+impl GoValueSpec {
+    fn render(&self) -> String {
+        let mut line = self.names.join(", ");
+        if let Some(value_type) = &self.value_type {
+            line.push(' ');
+            line.push_str(value_type);
+        }
+        if !self.values.is_empty() {
+            line.push_str(" = ");
+            line.push_str(&self.values.join(", "));
+        }
+        line
+    }
+}
+
+impl GoParameter {
+    fn render(&self) -> String {
+        if self.names.is_empty() {
+            self.param_type.clone()
+        } else {
+            format!("{} {}", self.names.join(", "), self.param_type)
+        }
+    }
+}
+
+/// Renders `GoDeclaration`s using the Go templates
+pub struct GoGenerator {
+    templates: TemplateEngine,
+}
+
+impl GoGenerator {
+    pub fn new() -> Self {
+        Self {
+            templates: TemplateEngine::new(),
+        }
+    }
+
+    pub fn render(&self, declaration: &GoDeclaration) -> Result<String> {
+        let template = self.templates.get_template("go")?;
+
+        let rendered = match declaration {
+            GoDeclaration::Package { name } => {
+                template.module_template
+                    .replace("{{name}}", name)
+                    .replace("{{content}}", "")
+                    .trim_end()
+                    .to_string()
+            }
+            GoDeclaration::Import { specs, grouped } => render_imports(template, specs, *grouped),
+            GoDeclaration::Function { name, type_parameters, parameters, results, body } => {
+                template.function_template
+                    .replace("{{name}}", name)
+                    .replace("{{generics}}", &render_type_parameters(type_parameters))
+                    .replace("{{params}}", &render_parameters(parameters))
+                    .replace("{{return_type}}", &render_results(results))
+                    .replace("{{body}}", body.as_deref().unwrap_or(""))
+            }
+            GoDeclaration::Method { receiver, name, parameters, results, body } => {
+                template.method_template
+                    .replace("{{receiver}}", &receiver.render())
+                    .replace("{{name}}", name)
+                    .replace("{{generics}}", "")
+                    .replace("{{params}}", &render_parameters(parameters))
+                    .replace("{{return_type}}", &render_results(results))
+                    .replace("{{body}}", body.as_deref().unwrap_or(""))
+            }
+            GoDeclaration::Struct { name, type_parameters, fields } => {
+                let fields = fields.iter()
+                    .map(|field| {
+                        let mut line = format!("\t{}", GoParameter {
+                            names: field.names.clone(),
+                            param_type: field.field_type.clone(),
+                        }.render());
+                        if let Some(tag) = &field.tag {
+                            line.push(' ');
+                            line.push_str(tag);
+                        }
+                        line
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                // The struct template has no generics slot; type parameters follow the name
+                template.struct_template
+                    .replace("{{name}}", &format!("{}{}", name, render_type_parameters(type_parameters)))
+                    .replace("{{fields}}", &fields)
+            }
+            GoDeclaration::Interface { name, type_parameters, elements } => {
+                let methods = elements.iter()
+                    .map(|element| format!("\t{}", element))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                template.interface_template
+                    .replace("{{name}}", &format!("{}{}", name, render_type_parameters(type_parameters)))
+                    .replace("{{methods}}", &methods)
+            }
+            // The Go templates have no slot for named types or var/const
+            // declarations, so these are written directly
+            GoDeclaration::Type { name, type_parameters, underlying, alias } => {
+                format!(
+                    "type {}{} {}{}",
+                    name,
+                    render_type_parameters(type_parameters),
+                    if *alias { "= " } else { "" },
+                    underlying,
+                )
+            }
+            GoDeclaration::Value { keyword, specs, grouped } => render_values(*keyword, specs, *grouped),
+        };
+
+        // Empty bodies render as `{\n\n}`; gofmt writes them as `{\n}`, and
+        // empty struct and interface types as `struct{}` / `interface{}`
+        Ok(rendered
+            .replace(" struct {\n\n}", " struct{}")
+            .replace(" interface {\n\n}", " interface{}")
+            .replace("{\n\n}", "{\n}"))
+    }
+}
+
+impl Default for GoGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_imports(template: &LanguageTemplate, specs: &[GoImport], grouped: bool) -> String {
+    let lines: Vec<String> = specs.iter()
+        .map(|spec| {
+            let alias = spec.alias.as_deref().unwrap_or("");
+            let line = template.import_template
+                .replace("{{alias}}", alias)
+                .replace("{{path}}", &spec.path);
+            // Without an alias the template leaves a double space
+            line.replace("import  ", "import ")
+        })
+        .collect();
+
+    if grouped {
+        let specs: Vec<String> = lines.iter()
+            .map(|line| format!("\t{}", line.trim_start_matches("import ")))
+            .collect();
+        format!("import (\n{}\n)", specs.join("\n"))
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn render_values(keyword: GoValueKeyword, specs: &[GoValueSpec], grouped: bool) -> String {
+    if grouped {
+        let specs: String = specs.iter()
+            .map(|spec| format!("\t{}\n", spec.render()))
+            .collect();
+        format!("{} (\n{})", keyword.as_str(), specs)
+    } else {
+        specs.iter()
+            .map(|spec| format!("{} {}", keyword.as_str(), spec.render()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn render_parameters(parameters: &[GoParameter]) -> String {
+    parameters.iter().map(GoParameter::render).collect::<Vec<_>>().join(", ")
+}
+
+fn render_type_parameters(type_parameters: &[GoParameter]) -> String {
+    if type_parameters.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", render_parameters(type_parameters))
+    }
+}
+
+/// ` T` for a single unnamed result, ` (a T, b U)` / ` (T, U)` otherwise
+fn render_results(results: &[GoParameter]) -> String {
+    match results {
+        [] => String::new(),
+        [single] if single.names.is_empty() => format!(" {}", single.param_type),
+        _ => format!(" ({})", render_parameters(results)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::universal::UniversalParser;
+
+    fn regenerate_go(source: &str) -> Result<String> {
+        let parse_result = UniversalParser::new()?.parse_file(source, "go", "main.go")?;
+        let generator = GoGenerator::new();
+    
+        let rendered = parse_result.blocks.iter()
+            .filter_map(|block| GoDeclaration::from_abstract_syntax(&block.syntax_preservation.normalized_ast))
+            .map(|declaration| generator.render(&declaration))
+            .collect::<Result<Vec<_>>>()?;
+    
+        Ok(format!("{}\n", rendered.join("\n\n")))
+    }
+
+    /// Test round-trip of Go functions with multiple and named return values
+    #[test]
+    fn test_go_multiple_and_named_returns_round_trip() -> Result<()> {
+        let source = r#"package mathx
+
+import (
+	"errors"
+	str "strings"
+)
+
+func Divide(a, b int) (q int, err error) {
+	if b == 0 {
+		return 0, errors.New("division by zero")
+	}
+	q = a / b
+	return
+}
+
+func Pair(name string) (int, string) {
+	return len(name), str.ToUpper(name)
+}
+"#;
+    
+        assert_eq!(regenerate_go(source)?, source);
+        Ok(())
+    }
+
+    /// Test round-trip of Go structs, interfaces and methods with receivers
+    #[test]
+    fn test_go_types_and_methods_round_trip() -> Result<()> {
+        let source = r#"package shapes
+
+import "fmt"
+
+type Point[T any] struct {
+	X, Y T `json:"x"`
+	*Base
+}
+
+type Shape interface {
+	Area() float64
+	fmt.Stringer
+}
+
+func (p *Point[T]) String() string {
+	return fmt.Sprintf("%v,%v", p.X, p.Y)
+}
+"#;
+    
+        assert_eq!(regenerate_go(source)?, source);
+        Ok(())
+    }
+
+    /// Test round-trip of top-level Go var/const declarations and named types
+    #[test]
+    fn test_go_values_and_named_types_round_trip() -> Result<()> {
+        let source = r#"package config
+
+type Celsius float64
+
+type Handler = func(string) error
+
+type Set[T comparable] map[T]struct{}
+
+const Boiling Celsius = 100
+
+const (
+	Low = iota
+	High
+)
+
+var (
+	retries, timeout int = 3, 30
+	names []string
+)
+
+var onError = func(err error) {
+	log(err)
+}
+"#;
+    
+        assert_eq!(regenerate_go(source)?, source);
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "go", "config.go")?;
+        let names: Vec<_> = parse_result.blocks.iter()
+            .map(|block| (block.block_type.to_string(), block.semantic_identity.canonical_name.as_str()))
+            .collect();
+        assert!(names.contains(&("TypeDef".to_string(), "Celsius")));
+        assert!(names.contains(&("Variable".to_string(), "retries, timeout, names")));
+        Ok(())
+    }
+}
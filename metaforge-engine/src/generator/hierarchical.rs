@@ -1,13 +1,32 @@
 use std::collections::HashMap;
 use uuid::Uuid;
 use anyhow::Result;
-use crate::database::{Database, Block};
+use crate::core::{EmptyFile, FilePreamble};
+use crate::database::{Block, Container, Database};
+use super::{markers, ordering};
+use super::type_declarations::TypeDeclaration;
+use super::go::{GoDeclaration, GoGenerator};
+use super::java::{JavaDeclaration, JavaGenerator};
+use super::parameters::render_parameters;
+use super::python_members::PythonMember;
+use super::python_types::PythonTypeDeclaration;
+use super::rust_attributes::{render_attributes, RustAttribute};
+use super::rust_enums::RustEnum;
+use super::rust_impls::RustImpl;
+use code_builders::{comment, dedupe_import_statements, BuildConfig, BuilderError, GenerationCache};
+use serde::{Deserialize, Serialize};
 
 pub struct HierarchicalGenerator {
     blocks: Vec<Block>,
     root_blocks: Vec<Uuid>,
     children_map: HashMap<Uuid, Vec<Uuid>>,
     language: String,
+    /// Shebang and pragma lines the original file opened with
+    preamble: Option<FilePreamble>,
+    /// Content of a file migrated empty or whitespace only
+    empty_file: Option<EmptyFile>,
+    add_markers: bool,
+    dedupe_imports: bool,
 }
 
 #[allow(dead_code)]
@@ -15,15 +34,24 @@ pub struct GenerationContext {
     language: String,
     indent_size: usize,
     current_imports: Vec<String>,
+    /// Emitted blocks, and whether each rendered from semantic data rather
+    /// than a placeholder
+    rendered: HashMap<Uuid, bool>,
 }
 
 impl HierarchicalGenerator {
     pub async fn from_container(db: &Database, container_id: Uuid) -> Result<Self> {
         let blocks = db.get_blocks_by_container(container_id).await?;
-        
-        // Get container to extract language
         let container = db.get_container_by_id(container_id).await?;
-        let language = container.language.unwrap_or_else(|| "unknown".to_string());
+        Ok(Self::from_blocks(&container, blocks))
+    }
+    
+    /// Generate `container` from `blocks` rather than the blocks stored for
+    /// it, e.g. as they stand on a semantic branch
+    pub fn from_blocks(container: &Container, blocks: Vec<Block>) -> Self {
+        let preamble = FilePreamble::from_formatting_preferences(container.formatting_preferences.as_ref());
+        let empty_file = EmptyFile::from_parsing_metadata(container.parsing_metadata.as_ref());
+        let language = container.language.clone().unwrap_or_else(|| "unknown".to_string());
         
         let mut root_blocks = Vec::new();
         let mut children_map: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
@@ -39,28 +67,85 @@ impl HierarchicalGenerator {
             }
         }
         
-        // Sort children by position
+        // Sort roots and children into the deterministic generation order
+        let by_id: HashMap<Uuid, &Block> = blocks.iter().map(|b| (b.id, b)).collect();
+        root_blocks.sort_by(|a, b| ordering::compare_blocks(by_id[a], by_id[b]));
         for children in children_map.values_mut() {
-            children.sort_by_key(|&id| {
-                blocks.iter()
-                    .find(|b| b.id == id)
-                    .map(|b| b.position_in_parent)
-                    .unwrap_or(0)
-            });
+            children.sort_by(|a, b| ordering::compare_children(by_id[a], by_id[b]));
         }
         
-        Ok(Self {
+        Self {
             blocks,
             root_blocks,
             children_map,
             language,
-        })
+            preamble,
+            empty_file,
+            add_markers: false,
+            dedupe_imports: false,
+        }
+    }
+    
+    /// Wrap every generated block (imports excepted) in sync markers
+    pub fn with_markers(mut self, enabled: bool) -> Self {
+        self.add_markers = enabled;
+        self
+    }
+    
+    /// Drop repeated imports and merge those from the same module
+    pub fn with_dedupe_imports(mut self, enabled: bool) -> Self {
+        self.dedupe_imports = enabled;
+        self
+    }
+    
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+    
+    /// Whether this generates a file migrated empty, unchanged
+    pub fn is_empty_file(&self) -> bool {
+        self.empty_file.is_some() && self.blocks.is_empty()
     }
     
     pub fn generate(&self) -> Result<String> {
+        Ok(self.generate_with_coverage()?.0)
+    }
+    
+    /// Generate the file along with how many blocks rendered their own
+    /// source from preserved semantic data; the rest of [`Self::blocks`]
+    /// fell back to placeholders or were not emitted
+    pub fn generate_with_coverage(&self) -> Result<(String, usize)> {
+        self.generate_with_cache(None)
+    }
+    
+    /// `generate_with_coverage`, taking the code of top-level blocks whose
+    /// blocks are unchanged from `cache` instead of rendering them again
+    pub fn generate_cached(&self, cache: &mut GenerationCache) -> Result<(String, usize)> {
+        self.generate_with_cache(Some(cache))
+    }
+    
+    fn generate_with_cache(&self, mut cache: Option<&mut GenerationCache>) -> Result<(String, usize)> {
+        // Until blocks are added to it, an empty file stays exactly as it was
+        if let Some(empty_file) = self.empty_file.as_ref().filter(|_| self.blocks.is_empty()) {
+            return Ok((empty_file.content.clone(), 0));
+        }
+        
         let mut output = Vec::new();
         let mut context = GenerationContext::new(&self.language);
         
+        if let Some(preamble) = &self.preamble {
+            output.extend(preamble.lines.iter().cloned());
+            output.push(String::new());
+        }
+        
+        // Go and Java files must open with their package clause
+        if self.opens_with_package() {
+            for package in self.collect_by_type("Module") {
+                output.push(self.generate_block_opening(package, "", &mut context)?);
+                output.push(String::new());
+            }
+        }
+        
         // Phase 1: Collect and group imports
         let imports = self.collect_by_type("Import");
         if !imports.is_empty() {
@@ -71,10 +156,14 @@ impl HierarchicalGenerator {
         // Phase 2: Generate top-level code
         for &root_id in &self.root_blocks {
             if let Some(block) = self.find_block(root_id) {
-                if block.block_type != "Import" {
-                    self.generate_recursive(block, 0, &mut output, &mut context)?;
-                    output.push(String::new()); // Empty line between top-level blocks
+                if block.block_type == "Import" || (self.opens_with_package() && block.block_type == "Module") {
+                    continue;
+                }
+                match cache.as_deref_mut() {
+                    Some(cache) => self.generate_root_cached(block, &mut output, &mut context, cache)?,
+                    None => self.generate_recursive(block, 0, &mut output, &mut context)?,
                 }
+                output.push(String::new()); // Empty line between top-level blocks
             }
         }
         
@@ -83,13 +172,18 @@ impl HierarchicalGenerator {
             output.pop();
         }
         
-        Ok(output.join("\n"))
+        let covered = context.rendered.values().filter(|&&semantic| semantic).count();
+        Ok((output.join("\n"), covered))
     }
     
-    fn generate_recursive(&self, block: &Block, depth: usize, output: &mut Vec<String>, _ctx: &mut GenerationContext) -> Result<()> {
+    fn generate_recursive(&self, block: &Block, depth: usize, output: &mut Vec<String>, ctx: &mut GenerationContext) -> Result<()> {
         // Generate block opening
         let indent = self.get_indent(depth);
-        let opening = self.generate_block_opening(block, &indent, _ctx)?;
+        if self.add_markers {
+            output.push(format!("{}{}", indent, markers::start_marker(block.id, &self.language)));
+        }
+        
+        let opening = self.generate_block_opening(block, &indent, ctx)?;
         if !opening.is_empty() {
             output.push(opening);
         }
@@ -98,7 +192,7 @@ impl HierarchicalGenerator {
         if let Some(children_ids) = self.children_map.get(&block.id) {
             for &child_id in children_ids {
                 if let Some(child) = self.find_block(child_id) {
-                    self.generate_recursive(child, depth + 1, output, _ctx)?;
+                    self.generate_recursive(child, depth + 1, output, ctx)?;
                 }
             }
         }
@@ -108,16 +202,84 @@ impl HierarchicalGenerator {
             output.push(closing);
         }
         
+        if self.add_markers {
+            output.push(format!("{}{}", indent, markers::end_marker(block.id, &self.language)));
+        }
+        
         Ok(())
     }
     
-    fn generate_block_opening(&self, block: &Block, indent: &str, _ctx: &mut GenerationContext) -> Result<String> {
-        match self.language.as_str() {
-            "python" => self.generate_python_opening(block, indent, _ctx),
-            "javascript" | "typescript" | "tsx" => self.generate_js_opening(block, indent, _ctx),
-            "rust" => self.generate_rust_opening(block, indent, _ctx),
-            _ => Ok(format!("{}// {}: {}", indent, block.block_type, block.semantic_name.as_ref().unwrap_or(&"unknown".to_string())))
+    /// Render a top-level block and its descendants through `cache`, keyed by
+    /// a hash of every block in the subtree
+    fn generate_root_cached(
+        &self,
+        block: &Block,
+        output: &mut Vec<String>,
+        ctx: &mut GenerationContext,
+        cache: &mut GenerationCache,
+    ) -> Result<()> {
+        let subtree = self.subtree(block);
+        let mut hasher = blake3::Hasher::new();
+        for block in &subtree {
+            hasher.update(&serde_json::to_vec(block)?);
         }
+        let block_hash = hasher.finalize().to_hex().to_string();
+        
+        let code = cache.get_or_render(&block_hash, &self.cache_config(), || {
+            let mut lines = Vec::new();
+            self.generate_recursive(block, 0, &mut lines, ctx).map_err(BuilderError::Other)?;
+            let rendered = subtree.iter()
+                .filter_map(|block| ctx.rendered.get(&block.id).map(|&semantic| (block.id, semantic)))
+                .collect();
+            Ok(serde_json::to_string(&CachedSubtree { lines, rendered })?)
+        })?;
+        
+        let cached: CachedSubtree = serde_json::from_str(&code)?;
+        ctx.rendered.extend(cached.rendered);
+        output.extend(cached.lines);
+        Ok(())
+    }
+    
+    /// `block` followed by its descendants, depth first
+    fn subtree<'a>(&'a self, block: &'a Block) -> Vec<&'a Block> {
+        let mut blocks = vec![block];
+        let mut index = 0;
+        while index < blocks.len() {
+            let id = blocks[index].id;
+            if let Some(children) = self.children_map.get(&id) {
+                blocks.extend(children.iter().filter_map(|&child| self.find_block(child)));
+            }
+            index += 1;
+        }
+        blocks
+    }
+    
+    /// The settings rendered code depends on, for `GenerationCache` keys
+    fn cache_config(&self) -> BuildConfig {
+        let mut config = BuildConfig {
+            language: self.language.clone(),
+            ..BuildConfig::default()
+        };
+        config.generation_hints.insert("markers".to_string(), serde_json::json!(self.add_markers));
+        config.generation_hints.insert("dedupe_imports".to_string(), serde_json::json!(self.dedupe_imports));
+        config
+    }
+    
+    fn generate_block_opening(&self, block: &Block, indent: &str, ctx: &mut GenerationContext) -> Result<String> {
+        let opening = match self.language.as_str() {
+            "python" => self.generate_python_opening(block, indent, ctx)?,
+            "javascript" | "typescript" | "tsx" => self.generate_js_opening(block, indent, ctx)?,
+            "rust" => self.generate_rust_opening(block, indent, ctx)?,
+            "go" => self.generate_go_opening(block, indent, ctx)?,
+            "java" => self.generate_java_opening(block, indent, ctx)?,
+            _ => {
+                ctx.rendered.insert(block.id, false);
+                format!("{}{}", indent, comment(&self.language, &format!("{}: {}", block.block_type, block.semantic_name.as_deref().unwrap_or("unknown"))))
+            }
+        };
+        // Openings that didn't fall back to a placeholder rendered from semantic data
+        ctx.rendered.entry(block.id).or_insert(true);
+        Ok(opening)
     }
     
     fn generate_block_closing(&self, block: &Block, indent: &str) -> Result<Option<String>> {
@@ -134,11 +296,26 @@ impl HierarchicalGenerator {
                     _ => Ok(None)
                 }
             },
+            "java" => {
+                // Only class and interface openings leave their body open
+                match JavaDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+                    Some(JavaDeclaration::Class { .. } | JavaDeclaration::Interface { .. }) => Ok(Some(format!("{}}}", indent))),
+                    _ => Ok(None)
+                }
+            },
             _ => Ok(None)
         }
     }
     
-    fn generate_python_opening(&self, block: &Block, indent: &str, _ctx: &mut GenerationContext) -> Result<String> {
+    fn generate_python_opening(&self, block: &Block, indent: &str, ctx: &mut GenerationContext) -> Result<String> {
+        // Properties and class attributes render whole, decorators and all
+        if let Some(member) = PythonMember::from_abstract_syntax(&block.abstract_syntax) {
+            return Ok(member.render(indent));
+        }
+        if let Some(declaration) = PythonTypeDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+            return Ok(declaration.render(indent));
+        }
+        
         match block.block_type.as_str() {
             "Function" => {
                 let params = self.extract_parameters(block)?;
@@ -158,21 +335,21 @@ impl HierarchicalGenerator {
             },
             "Import" => {
                 // Use original text for imports to preserve exact syntax
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             },
             "Variable" => {
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             },
             _ => {
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             }
         }
     }
     
-    fn generate_js_opening(&self, block: &Block, indent: &str, _ctx: &mut GenerationContext) -> Result<String> {
+    fn generate_js_opening(&self, block: &Block, indent: &str, ctx: &mut GenerationContext) -> Result<String> {
         match block.block_type.as_str() {
             "Function" => {
                 let params = self.extract_parameters(block)?;
@@ -180,78 +357,160 @@ impl HierarchicalGenerator {
                 let name = block.semantic_name.as_ref().unwrap_or(&default_name);
                 let modifiers = self.extract_modifiers(block)?;
                 let modifier_str = if modifiers.is_empty() { String::new() } else { format!("{} ", modifiers.join(" ")) };
+                let return_type = self.extract_return_type(block)?;
+                let return_str = if return_type.is_empty() { String::new() } else { format!(": {}", return_type) };
+                let generics = self.render_generics(block);
                 
                 if name == "anonymous" {
-                    Ok(format!("{}{}({}) {{", indent, modifier_str, params))
+                    Ok(format!("{}{}{}({}){} {{", indent, modifier_str, generics, params, return_str))
+                } else if self.is_method(block) {
+                    Ok(format!("{}{}{}{}({}){} {{", indent, modifier_str, name, generics, params, return_str))
                 } else {
-                    Ok(format!("{}{}function {}({}) {{", indent, modifier_str, name, params))
+                    Ok(format!("{}{}function {}{}({}){} {{", indent, modifier_str, name, generics, params, return_str))
                 }
             },
             "Class" => {
                 let default_name = "UnnamedClass".to_string();
                 let name = block.semantic_name.as_ref().unwrap_or(&default_name);
+                let generics = self.render_generics(block);
                 let extends = self.extract_extends_clause(block)?;
                 if extends.is_empty() {
-                    Ok(format!("{}class {} {{", indent, name))
+                    Ok(format!("{}class {}{} {{", indent, name, generics))
                 } else {
-                    Ok(format!("{}class {} extends {} {{", indent, name, extends))
+                    Ok(format!("{}class {}{} extends {} {{", indent, name, generics, extends))
                 }
             },
             "Import" => {
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
+                Ok(format!("{}{}", indent, original.trim()))
+            },
+            "Interface" | "TypeDef" => {
+                if let Some(declaration) = TypeDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+                    return Ok(declaration.render_typescript(indent));
+                }
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             },
             "Export" => {
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             },
             "Variable" => {
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             },
             _ => {
-                let original = self.extract_original_text(block)?;
+                let original = self.extract_original_text(block, ctx)?;
                 Ok(format!("{}{}", indent, original.trim()))
             }
         }
     }
     
-    fn generate_rust_opening(&self, block: &Block, indent: &str, _ctx: &mut GenerationContext) -> Result<String> {
-        match block.block_type.as_str() {
+    fn generate_rust_opening(&self, block: &Block, indent: &str, ctx: &mut GenerationContext) -> Result<String> {
+        // Attributes such as `cfg` go back above the item they gate
+        let attributes = render_attributes(&RustAttribute::from_abstract_syntax(&block.abstract_syntax), indent);
+        let opening = match block.block_type.as_str() {
             "Function" => {
                 let params = self.extract_parameters(block)?;
                 let default_name = "unnamed".to_string();
                 let name = block.semantic_name.as_ref().unwrap_or(&default_name);
                 let return_type = self.extract_return_type(block)?;
                 let return_str = if return_type.is_empty() { String::new() } else { format!(" -> {}", return_type) };
-                Ok(format!("{}fn {}({}){} {{", indent, name, params, return_str))
+                format!("{}fn {}({}){} {{", indent, name, params, return_str)
             },
-            "Class" => {
-                let default_name = "UnnamedStruct".to_string();
-                let name = block.semantic_name.as_ref().unwrap_or(&default_name);
-                if name.starts_with("impl ") {
-                    Ok(format!("{}{} {{", indent, name))
-                } else {
-                    Ok(format!("{}struct {} {{", indent, name))
+            // Methods of an impl are its child blocks
+            "Class" => match RustImpl::from_abstract_syntax(&block.abstract_syntax) {
+                Some(rust_impl) => format!("{}{} {{", indent, rust_impl.header()),
+                None => {
+                    let default_name = "UnnamedStruct".to_string();
+                    let name = block.semantic_name.as_ref().unwrap_or(&default_name);
+                    if name.starts_with("impl ") {
+                        format!("{}{} {{", indent, name)
+                    } else {
+                        format!("{}struct {} {{", indent, name)
+                    }
+                }
+            },
+            // Enums render whole from their variants, closing brace included
+            "Enum" => match RustEnum::from_abstract_syntax(&block.abstract_syntax) {
+                Some(rust_enum) => {
+                    let default_name = "UnnamedEnum".to_string();
+                    let name = block.semantic_name.as_ref().unwrap_or(&default_name);
+                    let variants = rust_enum.render_variants(&format!("{}    ", indent));
+                    format!("{}enum {} {{\n{}\n{}}}", indent, name, variants, indent)
                 }
+                None => format!("{}{}", indent, self.extract_original_text(block, ctx)?.trim()),
             },
             "Import" => {
-                let original = self.extract_original_text(block)?;
-                Ok(format!("{}{}", indent, original.trim()))
+                let original = self.extract_original_text(block, ctx)?;
+                format!("{}{}", indent, original.trim())
             },
             _ => {
-                let original = self.extract_original_text(block)?;
-                Ok(format!("{}{}", indent, original.trim()))
+                let original = self.extract_original_text(block, ctx)?;
+                format!("{}{}", indent, original.trim())
             }
-        }
+        };
+        
+        Ok(format!("{}{}", attributes, opening))
     }
     
-    fn generate_imports(&self, imports: &[&Block], _ctx: &mut GenerationContext) -> Result<String> {
+    /// Go declarations are rendered whole from their stored `GoDeclaration`
+    fn generate_go_opening(&self, block: &Block, indent: &str, ctx: &mut GenerationContext) -> Result<String> {
+        let rendered = match GoDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+            Some(declaration) => GoGenerator::new().render(&declaration)?,
+            None => self.extract_original_text(block, ctx)?.trim().to_string(),
+        };
+        
+        Ok(rendered.lines()
+            .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", indent, line) })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+    
+    /// Java declarations are rendered from their stored `JavaDeclaration`;
+    /// members of a class are its child blocks
+    fn generate_java_opening(&self, block: &Block, indent: &str, ctx: &mut GenerationContext) -> Result<String> {
+        let rendered = match JavaDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+            Some(declaration) => JavaGenerator::new().render(&declaration)?,
+            None => self.extract_original_text(block, ctx)?.trim().to_string(),
+        };
+        
+        Ok(rendered.lines()
+            .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", indent, line) })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+    
+    fn opens_with_package(&self) -> bool {
+        matches!(self.language.as_str(), "go" | "java")
+    }
+    
+    fn generate_imports(&self, imports: &[&Block], ctx: &mut GenerationContext) -> Result<String> {
+        if self.language == "go" {
+            // Keep declaration order; gofmt sorts within each group itself
+            let mut sorted = imports.to_vec();
+            sorted.sort_by(|a, b| ordering::compare_blocks(a, b));
+            return sorted.into_iter()
+                .map(|import| self.generate_block_opening(import, "", ctx))
+                .collect::<Result<Vec<_>>>()
+                .map(|lines| lines.join("\n"));
+        }
+        
         let mut import_lines = Vec::new();
         
         for import in imports {
-            let original = self.extract_original_text(import)?;
-            import_lines.push(original.trim().to_string());
+            let original = self.extract_original_text(import, ctx)?;
+            ctx.rendered.entry(import.id).or_insert(true);
+            let attributes = if self.language == "rust" {
+                render_attributes(&RustAttribute::from_abstract_syntax(&import.abstract_syntax), "")
+            } else {
+                String::new()
+            };
+            import_lines.push(format!("{}{}", attributes, original.trim()));
+        }
+        
+        if self.dedupe_imports {
+            import_lines = dedupe_import_statements(&import_lines, &self.language);
         }
         
         // Sort imports for consistency
@@ -270,11 +529,26 @@ impl HierarchicalGenerator {
         self.blocks.iter().find(|b| b.id == id)
     }
     
+    /// Whether `block` is a function declared in a class body
+    fn is_method(&self, block: &Block) -> bool {
+        block.parent_block_id
+            .and_then(|parent| self.find_block(parent))
+            .is_some_and(|parent| parent.block_type == "Class")
+    }
+    
+    /// Type parameter list with bounds and defaults, in this file's language
+    fn render_generics(&self, block: &Block) -> String {
+        block.generics_typed()
+            .map(|generics| generics.render(&self.language))
+            .unwrap_or_default()
+    }
+    
     fn get_indent(&self, depth: usize) -> String {
         match self.language.as_str() {
             "python" => "    ".repeat(depth), // 4 spaces
             "javascript" | "typescript" | "tsx" => "  ".repeat(depth), // 2 spaces
             "rust" => "    ".repeat(depth), // 4 spaces
+            "go" => "\t".repeat(depth), // gofmt indents with tabs
             _ => "    ".repeat(depth)
         }
     }
@@ -282,17 +556,9 @@ impl HierarchicalGenerator {
     // Helper methods to extract information from blocks
     
     fn extract_parameters(&self, block: &Block) -> Result<String> {
-        if let Some(params) = &block.parameters {
-            if let Some(param_array) = params.as_array() {
-                let param_strings: Vec<String> = param_array.iter()
-                    .filter_map(|p| {
-                        p.get("name").and_then(|n| n.as_str()).map(|s| s.to_string())
-                    })
-                    .collect();
-                return Ok(param_strings.join(", "));
-            }
-        }
-        Ok(String::new())
+        Ok(block.parameters.as_ref()
+            .map(|params| render_parameters(params, &self.language))
+            .unwrap_or_default())
     }
     
     fn extract_return_type(&self, block: &Block) -> Result<String> {
@@ -319,7 +585,36 @@ impl HierarchicalGenerator {
         Ok(String::new())
     }
     
-    fn extract_original_text(&self, block: &Block) -> Result<String> {
+    fn extract_original_text(&self, block: &Block, ctx: &mut GenerationContext) -> Result<String> {
+        if let Some(text) = self.preserved_text(block) {
+            return Ok(text);
+        }
+        ctx.rendered.insert(block.id, false);
+        
+        // Fallback to reconstructing from semantic name and parameters
+        let default_name = "unnamed".to_string();
+        let name = block.semantic_name.as_ref().unwrap_or(&default_name);
+        
+        match block.block_type.as_str() {
+            "Function" => {
+                let params = self.extract_parameters(block).unwrap_or_default();
+                Ok(format!("def {}({}):\n    pass", name, params))
+            },
+            "Class" => {
+                Ok(format!("class {}:\n    pass", name))
+            },
+            "Variable" => {
+                Ok(format!("{} = None", name))
+            },
+            "Import" => {
+                Ok(format!("import {}", name))
+            },
+            _ => Ok(format!("pass  # {}: {}", block.block_type, name))
+        }
+    }
+    
+    /// Source text preserved for a block during migration, if any
+    fn preserved_text(&self, block: &Block) -> Option<String> {
         // ✅ ENHANCED: First priority - use preserved implementation data
         if let Some(implementation) = block.abstract_syntax.get("implementation") {
             // For variables, use the preserved variable assignments
@@ -339,11 +634,11 @@ impl HierarchicalGenerator {
                                     .unwrap_or("None")
                                     .to_string(),
                             };
-                            return Ok(format!("{} = {}", semantic_name, value_str));
+                            return Some(format!("{} = {}", semantic_name, value_str));
                         } else if let Some(expression) = assignment_info.get("expression") {
                             // Use the preserved expression
                             let expr_str = expression.as_str().unwrap_or("None");
-                            return Ok(format!("{} = {}", semantic_name, expr_str));
+                            return Some(format!("{} = {}", semantic_name, expr_str));
                         }
                     }
                 }
@@ -353,7 +648,7 @@ impl HierarchicalGenerator {
                 if let Some(original_body) = implementation.get("original_body") {
                     if let Some(body_str) = original_body.as_str() {
                         if !body_str.trim().is_empty() {
-                            return Ok(body_str.to_string());
+                            return Some(body_str.to_string());
                         }
                     }
                 }
@@ -362,7 +657,7 @@ impl HierarchicalGenerator {
             if let Some(original_text) = implementation.get("original_text") {
                 if let Some(text_str) = original_text.as_str() {
                     if !text_str.trim().is_empty() {
-                        return Ok(text_str.to_string());
+                        return Some(text_str.to_string());
                     }
                 }
             }
@@ -371,7 +666,7 @@ impl HierarchicalGenerator {
         // Try to get original text from abstract_syntax (legacy)
         if let Some(raw_text) = block.abstract_syntax.get("raw_text") {
             if let Some(text) = raw_text.as_str() {
-                return Ok(text.to_string());
+                return Some(text.to_string());
             }
         }
         
@@ -379,34 +674,23 @@ impl HierarchicalGenerator {
         if let Some(metadata) = &block.metadata {
             if let Some(source) = metadata.get("source_code") {
                 if let Some(text) = source.as_str() {
-                    return Ok(text.to_string());
+                    return Some(text.to_string());
                 }
             }
         }
         
-        // Fallback to reconstructing from semantic name and parameters
-        let default_name = "unnamed".to_string();
-        let name = block.semantic_name.as_ref().unwrap_or(&default_name);
-        
-        match block.block_type.as_str() {
-            "Function" => {
-                let params = self.extract_parameters(block).unwrap_or_default();
-                Ok(format!("def {}({}):\n    pass", name, params))
-            },
-            "Class" => {
-                Ok(format!("class {}:\n    pass", name))
-            },
-            "Variable" => {
-                Ok(format!("{} = None", name))
-            },
-            "Import" => {
-                Ok(format!("import {}", name))
-            },
-            _ => Ok(format!("pass  # {}: {}", block.block_type, name))
-        }
+        None
     }
 }
 
+/// What `generate_root_cached` stores for a top-level block
+#[derive(Serialize, Deserialize)]
+struct CachedSubtree {
+    lines: Vec<String>,
+    /// `GenerationContext::rendered` for the blocks in the subtree
+    rendered: HashMap<Uuid, bool>,
+}
+
 impl GenerationContext {
     pub fn new(language: &str) -> Self {
         Self {
@@ -418,6 +702,117 @@ impl GenerationContext {
                 _ => 4,
             },
             current_imports: Vec::new(),
+            rendered: HashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BlockType, SemanticBlock};
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that module-level code round-trips in place between the declarations
+    #[test]
+    fn test_module_level_statements_round_trip_in_place() -> Result<()> {
+        let source = "import os\n\nDEBUG = os.environ.get(\"DEBUG\") == \"1\"\napp = Flask(__name__)\napp.config[\"DEBUG\"] = DEBUG\n\n\ndef create_app():\n    return app\n\n\nprint(\"configured\")\n\n\ndef main():\n    app.run()\n\n\nif __name__ == \"__main__\":\n    main()\n";
+        let top_level = |source: &str| -> Result<Vec<SemanticBlock>> {
+            let mut blocks: Vec<SemanticBlock> = UniversalParser::new()?.parse_file(source, "python", "app.py")?.blocks.into_iter()
+                .filter(|block| block.structural_context.parent_block.is_none())
+                .collect();
+            blocks.sort_by_key(|block| block.position.index);
+            Ok(blocks)
+        };
+        let outline = |blocks: &[SemanticBlock]| -> Vec<(BlockType, String)> {
+            blocks.iter().map(|block| (block.block_type.clone(), block.semantic_identity.canonical_name.clone())).collect()
+        };
+    
+        let blocks = top_level(source)?;
+        assert_eq!(outline(&blocks), vec![
+            (BlockType::Import, "os".to_string()),
+            (BlockType::Variable, "DEBUG".to_string()),
+            (BlockType::Variable, "app".to_string()),
+            (BlockType::Statement, "app.config[\"DEBUG\"] = DEBUG".to_string()),
+            (BlockType::Function, "create_app".to_string()),
+            (BlockType::Statement, "print(\"configured\")".to_string()),
+            (BlockType::Function, "main".to_string()),
+            (BlockType::Statement, "if __name__ == \"__main__\":".to_string()),
+        ]);
+    
+        let container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("app.py".to_string()),
+            ..Container::new("app", "file")
+        };
+        let stored = blocks.iter().map(|block| Block::from_semantic_block(block, container.id)).collect::<Result<Vec<_>>>()?;
+    
+        let generated = HierarchicalGenerator::from_blocks(&container, stored).generate()?;
+        let at = |needle: &str| generated.find(needle).unwrap_or_else(|| panic!("{} missing from\n{}", needle, generated));
+        assert!(at("def create_app") < at("print(\"configured\")") && at("print(\"configured\")") < at("def main"), "{}", generated);
+        assert!(generated.contains("if __name__ == \"__main__\":\n    main()"), "{}", generated);
+        assert_eq!(outline(&top_level(&generated)?), outline(&blocks));
+        Ok(())
+    }
+
+    /// Test that unchanged top-level blocks are taken from the render cache
+    #[test]
+    fn test_unchanged_blocks_skip_rendering() -> Result<()> {
+        let source = "import os\n\n\nclass Loader:\n    def load(self):\n        return os.getcwd()\n\n\ndef main():\n    Loader().load()\n";
+        let container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("app.py".to_string()),
+            ..Container::new("app", "file")
+        };
+        let mut stored = UniversalParser::new()?.parse_file(source, "python", "app.py")?.blocks.iter()
+            .map(|block| Block::from_semantic_block(block, container.id))
+            .collect::<Result<Vec<_>>>()?;
+        let roots = stored.iter().filter(|block| block.parent_block_id.is_none() && block.block_type != "Import").count();
+    
+        let mut cache = GenerationCache::new();
+        let (first, covered) = HierarchicalGenerator::from_blocks(&container, stored.clone()).generate_cached(&mut cache)?;
+        assert_eq!((cache.stats().hits, cache.stats().misses), (0, roots));
+        assert_eq!((first.clone(), covered), HierarchicalGenerator::from_blocks(&container, stored.clone()).generate_with_coverage()?);
+    
+        let (second, cached_covered) = HierarchicalGenerator::from_blocks(&container, stored.clone()).generate_cached(&mut cache)?;
+        assert_eq!((second, cached_covered), (first, covered));
+        assert_eq!((cache.stats().hits, cache.stats().misses), (roots, roots));
+    
+        // Editing a method invalidates its class, not the other top-level blocks
+        let method = stored.iter_mut().find(|block| block.semantic_name.as_deref() == Some("load")).expect("method stored");
+        method.semantic_name = Some("load_all".to_string());
+        HierarchicalGenerator::from_blocks(&container, stored).generate_cached(&mut cache)?;
+        assert_eq!((cache.stats().hits, cache.stats().misses), (2 * roots - 1, roots + 1));
+        Ok(())
+    }
+
+    /// Test that coverage counts blocks rendered from their own preserved data,
+    /// not blocks that merely have children
+    #[test]
+    fn test_generation_coverage_excludes_placeholders() -> Result<()> {
+        let python = Container {
+            language: Some("python".to_string()),
+            original_path: Some("app/settings.py".to_string()),
+            ..Container::new("settings.py", "file")
+        };
+        let preserved = Block::new(python.id, "Variable", "TAX_RATE", serde_json::json!({"raw_text": "TAX_RATE = 0.2"}));
+        let mut placeholder = Block::new(python.id, "Variable", "CURRENCY", serde_json::json!({}));
+        placeholder.position = 1;
+        let (generated, covered) = HierarchicalGenerator::from_blocks(&python, vec![preserved, placeholder]).generate_with_coverage()?;
+        assert_eq!(generated, "TAX_RATE = 0.2\n\nCURRENCY = None");
+        assert_eq!(covered, 1);
+    
+        // Without a generator for the language every block is a placeholder comment
+        let ruby = Container {
+            language: Some("ruby".to_string()),
+            original_path: Some("greeter.rb".to_string()),
+            ..Container::new("greeter.rb", "file")
+        };
+        let class = Block::new(ruby.id, "Class", "Greeter", serde_json::json!({}));
+        let mut method = Block::new(ruby.id, "Function", "greet", serde_json::json!({}));
+        method.parent_block_id = Some(class.id);
+        let (_, covered) = HierarchicalGenerator::from_blocks(&ruby, vec![class, method]).generate_with_coverage()?;
+        assert_eq!(covered, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Idempotency checks
+
+use std::collections::{BTreeMap, HashMap};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::core::{normalize::normalize_value, SemanticBlock};
+use crate::database::Block;
+
+/// The semantic fields of one block, keyed by its place in the file
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFingerprint {
+    /// `Parent::Child` path plus block type, with `#n` for repeated names
+    pub key: String,
+    pub fields: BTreeMap<&'static str, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockOutcome {
+    Unchanged,
+    /// Present on both sides; lists the fields whose values differ
+    Changed(Vec<&'static str>),
+    /// Not extracted again from the regenerated code
+    Missing,
+    /// Only extracted from the regenerated code
+    Added,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockComparison {
+    pub key: String,
+    pub outcome: BlockOutcome,
+}
+
+impl BlockFingerprint {
+    pub fn semantic_hash(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        for (field, value) in &self.fields {
+            hasher.update(field.as_bytes());
+            hasher.update(&[0]);
+            hasher.update(value.as_bytes());
+            hasher.update(&[0]);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+}
+
+/// Fingerprints of stored blocks
+pub fn fingerprints_from_blocks(blocks: &[Block]) -> Vec<BlockFingerprint> {
+    let by_id: HashMap<Uuid, &Block> = blocks.iter().map(|b| (b.id, b)).collect();
+    let entries = blocks.iter()
+        .map(|block| {
+            let mut path = vec![block.semantic_name.clone().unwrap_or_default()];
+            let mut parent = block.parent_block_id;
+            while let Some(parent_block) = parent.and_then(|id| by_id.get(&id)) {
+                path.push(parent_block.semantic_name.clone().unwrap_or_default());
+                parent = parent_block.parent_block_id;
+            }
+            path.reverse();
+
+            let fields = fields(
+                &block.block_type,
+                block.parameters.clone().unwrap_or(Value::Null),
+                block.return_type.clone(),
+                block.modifiers.clone().unwrap_or_default(),
+                &block.abstract_syntax,
+            );
+            (path.join("::"), block.block_type.clone(), fields)
+        })
+        .collect();
+    with_unique_keys(entries)
+}
+
+/// Fingerprints of freshly extracted blocks
+pub fn fingerprints_from_parse(blocks: &[SemanticBlock]) -> Vec<BlockFingerprint> {
+    let by_id: HashMap<Uuid, &SemanticBlock> = blocks.iter().map(|b| (b.id, b)).collect();
+    let entries = blocks.iter()
+        .map(|block| {
+            let mut path = vec![block.semantic_identity.canonical_name.clone()];
+            let mut parent = block.structural_context.parent_block;
+            while let Some(parent_block) = parent.and_then(|id| by_id.get(&id)) {
+                path.push(parent_block.semantic_identity.canonical_name.clone());
+                parent = parent_block.structural_context.parent_block;
+            }
+            path.reverse();
+
+            // Same representations the database insert uses
+            let block_type = format!("{:?}", block.block_type);
+            let fields = fields(
+                &block_type,
+                serde_json::to_value(&block.semantic_metadata.parameters).unwrap_or(Value::Null),
+                block.semantic_metadata.return_type.as_ref().map(|rt| rt.representation.clone()),
+                block.semantic_metadata.modifiers.iter().map(|m| format!("{:?}", m)).collect(),
+                &block.syntax_preservation.normalized_ast,
+            );
+            (path.join("::"), block_type, fields)
+        })
+        .collect();
+    with_unique_keys(entries)
+}
+
+/// Pair up blocks by key and report, per block, whether it survived the round trip
+pub fn compare(original: &[BlockFingerprint], regenerated: &[BlockFingerprint]) -> Vec<BlockComparison> {
+    let regenerated_by_key: HashMap<&str, &BlockFingerprint> = regenerated.iter()
+        .map(|fingerprint| (fingerprint.key.as_str(), fingerprint))
+        .collect();
+
+    let mut comparisons: Vec<BlockComparison> = original.iter()
+        .map(|before| {
+            let outcome = match regenerated_by_key.get(before.key.as_str()) {
+                None => BlockOutcome::Missing,
+                Some(after) if after.semantic_hash() == before.semantic_hash() => BlockOutcome::Unchanged,
+                Some(after) => BlockOutcome::Changed(
+                    before.fields.iter()
+                        .filter(|(field, value)| after.fields.get(*field) != Some(value))
+                        .map(|(field, _)| *field)
+                        .collect()
+                ),
+            };
+            BlockComparison { key: before.key.clone(), outcome }
+        })
+        .collect();
+
+    let original_keys: std::collections::HashSet<&str> = original.iter().map(|f| f.key.as_str()).collect();
+    comparisons.extend(regenerated.iter()
+        .filter(|after| !original_keys.contains(after.key.as_str()))
+        .map(|after| BlockComparison { key: after.key.clone(), outcome: BlockOutcome::Added }));
+
+    comparisons.sort_by(|a, b| a.key.cmp(&b.key));
+    comparisons
+}
+
+fn fields(
+    block_type: &str,
+    parameters: Value,
+    return_type: Option<String>,
+    modifiers: Vec<String>,
+    abstract_syntax: &Value,
+) -> BTreeMap<&'static str, String> {
+    let mut modifiers = modifiers;
+    modifiers.sort();
+
+    BTreeMap::from([
+        ("block_type", block_type.to_string()),
+        ("parameters", parameters.to_string()),
+        ("return_type", return_type.unwrap_or_default()),
+        ("modifiers", modifiers.join(" ")),
+        ("abstract_syntax", normalize_value(abstract_syntax).to_string()),
+    ])
+}
+
+fn with_unique_keys(entries: Vec<(String, String, BTreeMap<&'static str, String>)>) -> Vec<BlockFingerprint> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    entries.into_iter()
+        .map(|(path, block_type, fields)| {
+            let key = format!("{} {}", block_type, path);
+            let occurrence = seen.entry(key.clone()).or_insert(0);
+            *occurrence += 1;
+            let key = if *occurrence == 1 { key } else { format!("{}#{}", key, occurrence) };
+            BlockFingerprint { key, fields }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::generator::idempotency;
+    use crate::parser::universal::UniversalParser;
+
+    /// Reformatting alone must not change block fingerprints; semantic edits must
+    #[test]
+    fn test_idempotency_ignores_layout_but_reports_semantic_changes() -> Result<()> {
+        let original = "class Greeter:\n    def greet(self, name: str) -> str:\n        return 'hi ' + name\n";
+        let reformatted = "class Greeter:\n\n    def greet(self, name: str) -> str:\n        return   'hi ' + name\n";
+        let edited = "class Greeter:\n    def greet(self, name: str) -> bytes:\n        return 'hi ' + name\n";
+    
+        let fingerprints = |source: &str| -> Result<Vec<idempotency::BlockFingerprint>> {
+            let result = UniversalParser::new()?.parse_file(source, "python", "greeter.py")?;
+            Ok(idempotency::fingerprints_from_parse(&result.blocks))
+        };
+    
+        let before = fingerprints(original)?;
+        let comparisons = idempotency::compare(&before, &fingerprints(reformatted)?);
+        assert!(!comparisons.is_empty());
+        assert!(comparisons.iter().all(|c| c.outcome == BlockOutcome::Unchanged), "{:?}", comparisons);
+    
+        let comparisons = idempotency::compare(&before, &fingerprints(edited)?);
+        let greet = comparisons.iter()
+            .find(|c| c.key.ends_with("Greeter::greet"))
+            .expect("greet compared");
+        match &greet.outcome {
+            BlockOutcome::Changed(fields) => assert_eq!(fields, &vec!["return_type"]),
+            other => panic!("expected greet to change, got {:?}", other),
+        }
+        Ok(())
+    }
+}
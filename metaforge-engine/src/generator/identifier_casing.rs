@@ -0,0 +1,125 @@
+//! Identifier casing for cross-language synthesis
+
+use ast_extractor::Language;
+use serde::{Deserialize, Serialize};
+
+use super::output_naming::{capitalize, words};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Casing {
+    /// `user_name`
+    Snake,
+    /// `userName`
+    Camel,
+    /// `UserName`
+    Pascal,
+    /// `USER_NAME`
+    ScreamingSnake,
+}
+
+impl Casing {
+    /// Look up a casing by name (`snake`, `camel`, `pascal` or `screaming_snake`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake" => Some(Self::Snake),
+            "camel" => Some(Self::Camel),
+            "pascal" => Some(Self::Pascal),
+            "screaming_snake" => Some(Self::ScreamingSnake),
+            _ => None,
+        }
+    }
+
+    /// Recase `name`, keeping leading and trailing underscores (Python's
+    /// private `_name`) and leaving dunder names such as `__init__` alone
+    pub fn apply(self, name: &str) -> String {
+        if name.len() > 4 && name.starts_with("__") && name.ends_with("__") {
+            return name.to_string();
+        }
+        let core = name.trim_matches('_');
+        if core.is_empty() {
+            return name.to_string();
+        }
+        let prefix = &name[..name.len() - name.trim_start_matches('_').len()];
+        let suffix = &name[name.trim_end_matches('_').len()..];
+
+        let words = words(core);
+        let recased = match self {
+            Self::Snake => words.join("_").to_lowercase(),
+            Self::ScreamingSnake => words.join("_").to_uppercase(),
+            Self::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+            Self::Camel => words.iter()
+                .enumerate()
+                .map(|(index, word)| if index == 0 { word.to_lowercase() } else { capitalize(word) })
+                .collect(),
+        };
+        format!("{}{}{}", prefix, recased, suffix)
+    }
+}
+
+/// What an identifier names, which decides the casing it gets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierKind {
+    /// Functions and methods
+    Function,
+    /// Variables, fields and parameters
+    Variable,
+    Constant,
+    /// Classes, interfaces, structs and enums
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamingConvention {
+    pub functions: Casing,
+    pub variables: Casing,
+    pub constants: Casing,
+    pub types: Casing,
+}
+
+impl NamingConvention {
+    /// The usual convention of `language`, or `None` for languages without
+    /// one (C++) or that aren't known
+    pub fn for_language(language: &str) -> Option<Self> {
+        use Casing::*;
+        let (functions, variables, constants, types) = match language.parse::<Language>().ok()? {
+            Language::Python | Language::Rust | Language::Ruby => (Snake, Snake, ScreamingSnake, Pascal),
+            Language::C => (Snake, Snake, ScreamingSnake, Snake),
+            Language::Java | Language::Kotlin | Language::JavaScript | Language::TypeScript | Language::Tsx | Language::Php => {
+                (Camel, Camel, ScreamingSnake, Pascal)
+            }
+            Language::CSharp => (Pascal, Camel, Pascal, Pascal),
+            // Synthesized blocks are meant to be used from other packages, so exported
+            Language::Go => (Pascal, Camel, Pascal, Pascal),
+            Language::Cpp => return None,
+        };
+        Some(Self { functions, variables, constants, types })
+    }
+
+    pub fn casing(&self, kind: IdentifierKind) -> Casing {
+        match kind {
+            IdentifierKind::Function => self.functions,
+            IdentifierKind::Variable => self.variables,
+            IdentifierKind::Constant => self.constants,
+            IdentifierKind::Type => self.types,
+        }
+    }
+
+    /// `name` in the casing this convention uses for `kind`
+    pub fn apply(&self, kind: IdentifierKind, name: &str) -> String {
+        self.casing(kind).apply(name)
+    }
+
+    /// Replace the casing for one kind of identifier, by its plural key
+    /// (`functions`, `variables`, `constants` or `types`)
+    pub fn set(&mut self, key: &str, casing: Casing) -> bool {
+        match key {
+            "functions" => self.functions = casing,
+            "variables" => self.variables = casing,
+            "constants" => self.constants = casing,
+            "types" => self.types = casing,
+            _ => return false,
+        }
+        true
+    }
+}
@@ -0,0 +1,375 @@
+//! Java generation
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use super::templates::TemplateEngine;
+
+/// Key under `abstract_syntax` holding a serialized `JavaDeclaration`
+pub const JAVA_DECLARATION_KEY: &str = "java_declaration";
+
+const JAVA_VISIBILITY: [&str; 3] = ["public", "protected", "private"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JavaDeclaration {
+    Package {
+        name: String,
+    },
+    Import {
+        /// Dotted path, ending in `.*` for on-demand imports
+        path: String,
+        is_static: bool,
+    },
+    Class {
+        annotations: Vec<String>,
+        modifiers: Vec<String>,
+        name: String,
+        /// Including the angle brackets, e.g. `<T extends Comparable<T>>`
+        type_parameters: Option<String>,
+        superclass: Option<String>,
+        interfaces: Vec<String>,
+    },
+    Interface {
+        annotations: Vec<String>,
+        modifiers: Vec<String>,
+        name: String,
+        type_parameters: Option<String>,
+        extends: Vec<String>,
+    },
+    Method {
+        annotations: Vec<String>,
+        modifiers: Vec<String>,
+        type_parameters: Option<String>,
+        return_type: String,
+        name: String,
+        parameters: Vec<JavaParameter>,
+        /// Exception types of the `throws` clause, in declaration order
+        throws: Vec<String>,
+        /// `None` for abstract and interface methods
+        body: Option<String>,
+    },
+    Constructor {
+        annotations: Vec<String>,
+        modifiers: Vec<String>,
+        name: String,
+        parameters: Vec<JavaParameter>,
+        throws: Vec<String>,
+        body: String,
+    },
+    Field {
+        annotations: Vec<String>,
+        modifiers: Vec<String>,
+        field_type: String,
+        /// Each declared variable with its initializer, e.g. `count = 0`
+        declarators: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JavaParameter {
+    /// `final` and parameter annotations
+    pub modifiers: Vec<String>,
+    /// Ends in `...` for varargs
+    pub param_type: String,
+    pub name: String,
+}
+
+impl JavaDeclaration {
+    /// Read the declaration stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(JAVA_DECLARATION_KEY)?.clone()).ok()
+    }
+}
+
+impl JavaParameter {
+    fn render(&self) -> String {
+        let mut parts = self.modifiers.clone();
+        parts.push(self.param_type.clone());
+        parts.push(self.name.clone());
+        parts.join(" ")
+    }
+}
+
+/// Renders `JavaDeclaration`s using the Java templates
+pub struct JavaGenerator {
+    templates: TemplateEngine,
+}
+
+impl JavaGenerator {
+    pub fn new() -> Self {
+        Self {
+            templates: TemplateEngine::new(),
+        }
+    }
+
+    pub fn render(&self, declaration: &JavaDeclaration) -> Result<String> {
+        let template = self.templates.get_template("java")?;
+
+        let rendered = match declaration {
+            JavaDeclaration::Package { name } => {
+                template.module_template
+                    .replace("{{name}}", name)
+                    .replace("{{content}}", "")
+                    .trim_end()
+                    .to_string()
+            }
+            JavaDeclaration::Import { path, is_static } => {
+                template.import_template
+                    .replace("{{static_keyword}}", if *is_static { "static " } else { "" })
+                    .replace("{{path}}", path)
+            }
+            JavaDeclaration::Class { annotations, modifiers, name, type_parameters, superclass, interfaces } => {
+                let (visibility, modifiers) = split_visibility(modifiers);
+                let header = template.class_template
+                    .replace("{{visibility}}", &visibility)
+                    .replace("{{modifiers}}", &modifiers.join(" "))
+                    .replace("{{name}}", name)
+                    .replace("{{generics}}", type_parameters.as_deref().unwrap_or(""))
+                    .replace("{{extends}}", &superclass.as_ref().map(|base| format!(" extends {}", base)).unwrap_or_default())
+                    .replace("{{implements}}", &clause(" implements ", interfaces));
+                with_annotations(annotations, &opening_line(&header))
+            }
+            JavaDeclaration::Interface { annotations, modifiers, name, type_parameters, extends } => {
+                // The interface template has no modifiers slot
+                let header = template.interface_template
+                    .replace("{{visibility}}", &modifiers.join(" "))
+                    .replace("{{name}}", name)
+                    .replace("{{generics}}", type_parameters.as_deref().unwrap_or(""))
+                    .replace("{{extends}}", &clause(" extends ", extends));
+                with_annotations(annotations, &opening_line(&header))
+            }
+            JavaDeclaration::Method { annotations, modifiers, type_parameters, return_type, name, parameters, throws, body } => {
+                let (visibility, mut modifiers) = split_visibility(modifiers);
+                // Method type parameters precede the return type, not follow the name
+                modifiers.extend(type_parameters.iter().cloned());
+                let member = member_template(&template.method_template);
+                let member = match body {
+                    Some(_) => member,
+                    None => member.split(" {\n").next().unwrap_or_default().to_string() + ";",
+                };
+                let rendered = member
+                    .replace("{{visibility}}", &visibility)
+                    .replace("{{modifiers}}", &modifiers.join(" "))
+                    .replace("{{return_type}}", return_type)
+                    .replace("{{name}}", name)
+                    .replace("{{generics}}", "")
+                    .replace("{{params}}", &render_parameters(parameters))
+                    .replace("{{throws}}", &clause(" throws ", throws))
+                    .replace("{{body}}", body.as_deref().unwrap_or(""));
+                with_annotations(annotations, &tidy_signature(&rendered))
+            }
+            JavaDeclaration::Constructor { annotations, modifiers, name, parameters, throws, body } => {
+                // The constructor template has no modifiers slot
+                let rendered = member_template(&template.constructor_template)
+                    .replace("{{visibility}}", &modifiers.join(" "))
+                    .replace("{{name}}", name)
+                    .replace("{{params}}", &render_parameters(parameters))
+                    .replace("{{throws}}", &clause(" throws ", throws))
+                    .replace("{{body}}", body);
+                with_annotations(annotations, &tidy_signature(&rendered))
+            }
+            JavaDeclaration::Field { annotations, modifiers, field_type, declarators } => {
+                let (visibility, modifiers) = split_visibility(modifiers);
+                let rendered = template.variable_template
+                    .replace("{{name}} = {{value}}", &declarators.join(", "))
+                    .replace("{{visibility}}", &visibility)
+                    .replace("{{modifiers}}", &modifiers.join(" "))
+                    .replace("{{type}}", field_type);
+                with_annotations(annotations, &tidy_signature(&rendered))
+            }
+        };
+
+        // Empty bodies render as `{\n\n}`
+        Ok(rendered.replace("{\n\n}", "{\n}"))
+    }
+}
+
+impl Default for JavaGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The visibility keyword, if any, and the remaining modifiers in order
+fn split_visibility(modifiers: &[String]) -> (String, Vec<String>) {
+    let visibility = modifiers.iter()
+        .find(|modifier| JAVA_VISIBILITY.contains(&modifier.as_str()))
+        .cloned()
+        .unwrap_or_default();
+    let rest = modifiers.iter()
+        .filter(|modifier| !JAVA_VISIBILITY.contains(&modifier.as_str()))
+        .cloned()
+        .collect();
+    (visibility, rest)
+}
+
+fn clause(keyword: &str, names: &[String]) -> String {
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", keyword, names.join(", "))
+    }
+}
+
+fn render_parameters(parameters: &[JavaParameter]) -> String {
+    parameters.iter().map(JavaParameter::render).collect::<Vec<_>>().join(", ")
+}
+
+/// Member templates are indented for a class body; bodies are stored
+/// relative to their declaration, so drop that indent
+fn member_template(template: &str) -> String {
+    template.lines()
+        .map(|line| line.strip_prefix("    ").unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The first line of a type template, up to and including its opening brace
+fn opening_line(rendered: &str) -> String {
+    tidy_signature(rendered.lines().next().unwrap_or_default())
+}
+
+/// Collapse the gaps that empty placeholders leave in the declaration line,
+/// up to the parameter list or initializer so literals are left alone
+fn tidy_signature(rendered: &str) -> String {
+    let (first, rest) = rendered.split_once('\n').map_or((rendered, None), |(first, rest)| (first, Some(rest)));
+    let split = first.find(['(', '=']).unwrap_or(first.len());
+    let (head, tail) = first.split_at(split);
+    let mut head = head.split_whitespace().collect::<Vec<_>>().join(" ");
+    if tail.starts_with('=') {
+        head.push(' ');
+    }
+    let line = format!("{}{}", head, tail);
+    match rest {
+        Some(rest) => format!("{}\n{}", line, rest),
+        None => line,
+    }
+}
+
+fn with_annotations(annotations: &[String], declaration: &str) -> String {
+    annotations.iter()
+        .map(String::as_str)
+        .chain(std::iter::once(declaration))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+    use crate::core::{BlockType, SemanticBlock};
+    use crate::database::Block;
+    use crate::parser::universal::UniversalParser;
+
+    fn regenerate_java(source: &str) -> Result<String> {
+        let parse_result = UniversalParser::new()?.parse_file(source, "java", "Loader.java")?;
+        let generator = JavaGenerator::new();
+        let render = |block: &SemanticBlock| -> Result<String> {
+            let declaration = JavaDeclaration::from_abstract_syntax(&block.syntax_preservation.normalized_ast)
+                .expect("declaration stored");
+            generator.render(&declaration)
+        };
+    
+        // Consecutive imports share a section; other declarations are separated by a blank line
+        let mut sections: Vec<String> = Vec::new();
+        let mut after_import = false;
+        for block in parse_result.blocks.iter().filter(|block| block.structural_context.parent_block.is_none()) {
+            match block.block_type {
+                BlockType::Import if after_import => {
+                    let imports = sections.last_mut().expect("import section");
+                    imports.push('\n');
+                    imports.push_str(&render(block)?);
+                }
+                BlockType::Class | BlockType::Interface => {
+                    let members = parse_result.blocks.iter()
+                        .filter(|member| member.structural_context.parent_block == Some(block.id))
+                        .map(|member| render(member).map(|rendered| rendered.lines()
+                            .map(|line| if line.is_empty() { String::new() } else { format!("    {}", line) })
+                            .collect::<Vec<_>>()
+                            .join("\n")))
+                        .collect::<Result<Vec<_>>>()?;
+                    sections.push(format!("{}\n{}\n}}", render(block)?, members.join("\n\n")));
+                }
+                _ => sections.push(render(block)?),
+            }
+            after_import = block.block_type == BlockType::Import;
+        }
+    
+        Ok(format!("{}\n", sections.join("\n\n")))
+    }
+
+    /// Test round-trip of a Java method declaring checked exceptions
+    #[test]
+    fn test_java_throws_clause_round_trip() -> Result<()> {
+        let source = r#"package store;
+
+import java.io.IOException;
+import java.sql.SQLException;
+
+public class Loader {
+    private final String root;
+
+    public Loader(String root) {
+        this.root = root;
+    }
+
+    public String load(String path, int retries) throws IOException, SQLException {
+        if (retries < 0) {
+            throw new IllegalArgumentException("retries");
+        }
+        return read(root + path);
+    }
+}
+"#;
+    
+        assert_eq!(regenerate_java(source)?, source);
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "java", "Loader.java")?;
+        let load = parse_result.blocks.iter()
+            .find(|block| block.semantic_identity.canonical_name == "load")
+            .expect("method extracted");
+        assert_eq!(load.syntax_preservation.normalized_ast["throws"], serde_json::json!(["IOException", "SQLException"]));
+        // Unchecked exceptions thrown in the body are recorded but not re-emitted
+        assert_eq!(load.semantic_metadata.throws, vec!["IOException", "SQLException", "IllegalArgumentException"]);
+        Ok(())
+    }
+
+    /// Test that a Java generic method with a bounded type parameter round-trips,
+    /// both verbatim and rendered from its semantic generics
+    #[test]
+    fn test_java_bounded_generic_method_round_trip() -> Result<()> {
+        let source = r#"public class Sorter<E extends Number & Comparable<E>> {
+    public static <T extends Comparable<T>> T max(List<T> items) {
+        return items.get(0);
+    }
+}
+"#;
+    
+        assert_eq!(regenerate_java(source)?, source);
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "java", "Sorter.java")?;
+        let generics = |name: &str| parse_result.blocks.iter()
+            .find(|block| block.semantic_identity.canonical_name == name)
+            .and_then(|block| block.semantic_metadata.generics.clone())
+            .expect("generics extracted");
+        assert_eq!(generics("Sorter").generic_parameters[0].bounds, vec!["Number", "Comparable<E>"]);
+        assert_eq!(generics("Sorter").render("java"), "<E extends Number & Comparable<E>>");
+        let max = parse_result.blocks.iter()
+            .find(|block| block.semantic_identity.canonical_name == "max")
+            .expect("method extracted");
+        assert_eq!(generics("max").render("java"), "<T extends Comparable<T>>");
+    
+        // The template engine puts a method's type parameters before its return type
+        let method = Block {
+            return_type: Some("T".to_string()),
+            modifiers: Some(vec!["public".to_string(), "static".to_string()]),
+            semantic_metadata: Some(serde_json::to_value(&max.semantic_metadata)?),
+            ..Block::new(Uuid::new_v4(), "Method", "max", serde_json::json!({}))
+        };
+        let java = TemplateEngine::new().render_block(&method, "java")?;
+        let generics_at = java.find("<T extends Comparable<T>>").expect("generics rendered");
+        assert!(generics_at < java.find(" max(").expect("name rendered"), "{}", java);
+        Ok(())
+    }
+}
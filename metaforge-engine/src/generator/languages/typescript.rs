@@ -583,3 +583,77 @@ impl LanguageGenerator for JavaScriptGenerator {
         ts_gen.format(code, config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::core::SemanticBlock;
+    use crate::database::{Block, Container};
+    use crate::generator::HierarchicalGenerator;
+    use crate::generator::parameters::render_parameters;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test TypeScript optional and defaulted parameters and union return types
+    #[test]
+    fn test_typescript_signature_types_round_trip() -> Result<()> {
+        let source = "function find(id?: number, limit = 5): string | null {\n  return null;\n}\n\nfunction merge(a: Base & Extra, mode: 'fast' | 'safe' = 'safe'): Base & Extra {\n  return a;\n}\n\nfunction isText(value: unknown): value is string {\n  return typeof value === 'string';\n}\n\nfunction log(message: string) {\n  console.log(message);\n}\n";
+        let mut parser = UniversalParser::new()?;
+        let signatures = |blocks: &[SemanticBlock]| -> Result<Vec<(String, String, Option<String>)>> {
+            blocks.iter()
+                .map(|block| Ok((
+                    block.semantic_identity.canonical_name.clone(),
+                    render_parameters(&serde_json::to_value(&block.semantic_metadata.parameters)?, "typescript"),
+                    block.semantic_metadata.return_type.as_ref().map(|return_type| return_type.representation.clone()),
+                )))
+                .collect()
+        };
+    
+        let parse_result = parser.parse_file(source, "typescript", "find.ts")?;
+        let extracted = signatures(&parse_result.blocks)?;
+        let expected = |name: &str, parameters: &str, return_type: Option<&str>| (name.to_string(), parameters.to_string(), return_type.map(str::to_string));
+        assert_eq!(extracted, vec![
+            expected("find", "id?: number, limit = 5", Some("string | null")),
+            expected("merge", "a: Base & Extra, mode: 'fast' | 'safe' = 'safe'", Some("Base & Extra")),
+            expected("isText", "value: unknown", Some("value is string")),
+            expected("log", "message: string", None),
+        ]);
+        let find = &parse_result.blocks[0].semantic_metadata.parameters;
+        assert!(find[0].is_optional && find[0].default_value.is_none());
+        assert_eq!(find[1].default_expression.as_ref().map(|default| default.expression_type.as_str()), Some("number"));
+    
+        // Stored blocks regenerate the same signatures
+        let container = Container {
+            language: Some("typescript".to_string()),
+            original_path: Some("find.ts".to_string()),
+            ..Container::new("find.ts", "file")
+        };
+        // Rendered from the stored signature columns, not the original text
+        let blocks: Vec<_> = parse_result.blocks.iter().enumerate()
+            .map(|(position, block)| {
+                let mut stored = Block::new(container.id, "Function", &block.semantic_identity.canonical_name, serde_json::json!({}));
+                stored.position = position as i32;
+                stored.position_in_parent = position as i32;
+                stored.parameters = serde_json::to_value(&block.semantic_metadata.parameters).ok();
+                stored.return_type = block.semantic_metadata.return_type.as_ref().map(|return_type| return_type.representation.clone());
+                stored
+            })
+            .collect();
+        let generated = HierarchicalGenerator::from_blocks(&container, blocks).generate()?;
+        let openings: Vec<&str> = generated.lines().filter(|line| line.starts_with("function")).collect();
+        assert_eq!(openings, vec![
+            "function find(id?: number, limit = 5): string | null {",
+            "function merge(a: Base & Extra, mode: 'fast' | 'safe' = 'safe'): Base & Extra {",
+            "function isText(value: unknown): value is string {",
+            "function log(message: string) {",
+        ]);
+        let reparsed = parser.parse_file(&generated, "typescript", "find.ts")?;
+        assert_eq!(signatures(&reparsed.blocks)?, extracted);
+    
+        // Multi-line unions lose their leading `|`
+        let multiline = parser.parse_file("function pick(\n  mode:\n    | 'a'\n    | 'b',\n): A\n  | B {}\n", "typescript", "pick.ts")?;
+        assert_eq!(signatures(&multiline.blocks)?, vec![expected("pick", "mode: 'a' | 'b'", Some("A | B"))]);
+    
+        Ok(())
+    }
+}
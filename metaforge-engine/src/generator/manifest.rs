@@ -0,0 +1,117 @@
+//! Generation manifest
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use super::markers::SourceRange;
+use super::universal::GenerationConfig;
+
+/// Bumped whenever the manifest layout changes incompatibly
+pub const MANIFEST_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationManifest {
+    pub version: u32,
+    /// The generate run; the id of the migration it regenerated
+    pub pipeline_id: Uuid,
+    pub migration_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub build_config: GenerationConfig,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path relative to the output directory
+    pub path: String,
+    pub language: String,
+    pub blocks: Vec<ManifestBlock>,
+}
+
+/// Where a block's code sits in its generated file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestBlock {
+    pub block_id: Uuid,
+    #[serde(flatten)]
+    pub range: SourceRange,
+}
+
+impl GenerationManifest {
+    pub fn new(pipeline_id: Uuid, migration_id: Uuid, build_config: GenerationConfig) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            pipeline_id,
+            migration_id,
+            generated_at: Utc::now(),
+            build_config,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn add_file(&mut self, path: impl Into<String>, language: impl Into<String>, ranges: Vec<(Uuid, SourceRange)>) {
+        self.files.push(ManifestFile {
+            path: path.into(),
+            language: language.into(),
+            blocks: ranges.into_iter()
+                .map(|(block_id, range)| ManifestBlock { block_id, range })
+                .collect(),
+        });
+    }
+
+    /// Write the manifest into `output_dir`, returning its path
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        let path = output_dir.join(MANIFEST_FILE_NAME);
+        std::fs::create_dir_all(output_dir)?;
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Paths `previous` listed that this manifest doesn't, in `previous`'s order
+    pub fn files_dropped_since<'a>(&self, previous: &'a GenerationManifest) -> Vec<&'a str> {
+        previous.files.iter()
+            .map(|file| file.path.as_str())
+            .filter(|path| !self.files.iter().any(|file| file.path == *path))
+            .collect()
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let manifest: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if manifest.version > MANIFEST_VERSION {
+            anyhow::bail!(
+                "Manifest version {} is newer than supported version {}",
+                manifest.version,
+                MANIFEST_VERSION
+            );
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trips_and_lists_dropped_files() -> Result<()> {
+        let migration_id = Uuid::new_v4();
+        let mut previous = GenerationManifest::new(migration_id, migration_id, GenerationConfig::default());
+        previous.add_file("billing/pricing.py", "python", Vec::new());
+        previous.add_file("billing/legacy.py", "python", Vec::new());
+    
+        let output_dir = std::env::temp_dir().join(format!("metaforge-manifest-{}", Uuid::new_v4()));
+        let path = previous.write(&output_dir)?;
+        let loaded = GenerationManifest::load(&path)?;
+        assert_eq!((loaded.pipeline_id, loaded.files.len()), (migration_id, 2));
+        std::fs::remove_dir_all(&output_dir)?;
+    
+        let mut current = GenerationManifest::new(migration_id, migration_id, GenerationConfig::default());
+        current.add_file("billing/pricing.py", "python", Vec::new());
+        assert_eq!(current.files_dropped_since(&loaded), vec!["billing/legacy.py"]);
+        assert!(loaded.files_dropped_since(&current).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! Sync markers wrapping each rendered block in `@metaforge:block <uuid> start`/`end` comments
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use code_builders::comment;
+
+const MARKER_TAG: &str = "@metaforge:block";
+
+/// Region of source text, such as the part of a generated file enclosed by a
+/// block's markers.
+///
+/// Lines are 0-based, a marker region excludes the marker lines themselves,
+/// and `byte_end` is exclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRange {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+
+pub fn start_marker(block_id: Uuid, language: &str) -> String {
+    comment(language, &format!("{} {} start", MARKER_TAG, block_id))
+}
+
+pub fn end_marker(block_id: Uuid, language: &str) -> String {
+    comment(language, &format!("{} {} end", MARKER_TAG, block_id))
+}
+
+/// Read block markers back out of generated content.
+///
+/// Returns one entry per matched start/end pair, ordered by the position of
+/// the start marker. Nested blocks produce nested ranges; unmatched markers
+/// are ignored.
+pub fn parse_markers(content: &str) -> Vec<(Uuid, SourceRange)> {
+    let mut open: Vec<(Uuid, usize, usize)> = Vec::new();
+    let mut ranges = Vec::new();
+    let mut byte_offset = 0;
+
+    for (line_index, line) in content.split_inclusive('\n').enumerate() {
+        let line_start = byte_offset;
+        byte_offset += line.len();
+
+        let Some((block_id, is_start)) = parse_marker_line(line) else {
+            continue;
+        };
+
+        if is_start {
+            open.push((block_id, line_index + 1, byte_offset));
+        } else if let Some(pos) = open.iter().rposition(|(id, _, _)| *id == block_id) {
+            let (_, start_line, byte_start) = open.remove(pos);
+            ranges.push((block_id, SourceRange {
+                start_line,
+                end_line: line_index.saturating_sub(1).max(start_line),
+                byte_start,
+                byte_end: line_start,
+            }));
+        }
+    }
+
+    ranges.sort_by_key(|(_, range)| (range.byte_start, std::cmp::Reverse(range.byte_end)));
+    ranges
+}
+
+/// Remove every marker line from `content`.
+///
+/// Returns the content as it would have been generated without markers, and
+/// each block's range within that content, with the same conventions and
+/// ordering as `parse_markers`.
+pub fn strip_markers(content: &str) -> (String, Vec<(Uuid, SourceRange)>) {
+    let mut stripped = String::with_capacity(content.len());
+    let mut open: Vec<(Uuid, usize, usize)> = Vec::new();
+    let mut ranges = Vec::new();
+    let mut line_index = 0;
+
+    for line in content.split_inclusive('\n') {
+        match parse_marker_line(line) {
+            Some((block_id, true)) => open.push((block_id, line_index, stripped.len())),
+            Some((block_id, false)) => {
+                if let Some(pos) = open.iter().rposition(|(id, _, _)| *id == block_id) {
+                    let (_, start_line, byte_start) = open.remove(pos);
+                    ranges.push((block_id, SourceRange {
+                        start_line,
+                        end_line: line_index.saturating_sub(1).max(start_line),
+                        byte_start,
+                        byte_end: stripped.len(),
+                    }));
+                }
+            }
+            None => {
+                stripped.push_str(line);
+                line_index += 1;
+            }
+        }
+    }
+
+    // A trailing end marker leaves a newline the unmarked output wouldn't have
+    if !content.ends_with('\n') && stripped.ends_with('\n') {
+        stripped.pop();
+        for (_, range) in &mut ranges {
+            range.byte_end = range.byte_end.min(stripped.len());
+        }
+    }
+
+    ranges.sort_by_key(|(_, range)| (range.byte_start, std::cmp::Reverse(range.byte_end)));
+    (stripped, ranges)
+}
+
+fn parse_marker_line(line: &str) -> Option<(Uuid, bool)> {
+    let trimmed = line.trim();
+    let body = [("//", ""), ("#", ""), ("--", ""), ("/*", "*/"), ("<!--", "-->")].iter()
+        .find_map(|(open, close)| trimmed.strip_prefix(open)?.strip_suffix(close))?
+        .trim_start()
+        .strip_prefix(MARKER_TAG)?;
+
+    let mut parts = body.split_whitespace();
+    let block_id = Uuid::parse_str(parts.next()?).ok()?;
+    let is_start = match parts.next()? {
+        "start" => true,
+        "end" => false,
+        _ => return None,
+    };
+
+    parts.next().is_none().then_some((block_id, is_start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::markers;
+
+    /// Test that sync markers written by the generator can be read back
+    #[test]
+    fn test_sync_markers_round_trip() {
+        let outer = Uuid::new_v4();
+        let inner = Uuid::new_v4();
+    
+        let content = [
+            markers::start_marker(outer, "python"),
+            "class Greeter:".to_string(),
+            format!("    {}", markers::start_marker(inner, "python")),
+            "    def greet(self):".to_string(),
+            "        return 'hi'".to_string(),
+            format!("    {}", markers::end_marker(inner, "python")),
+            markers::end_marker(outer, "python"),
+            String::new(),
+        ].join("\n");
+    
+        let ranges = markers::parse_markers(&content);
+        assert_eq!(ranges.len(), 2);
+    
+        let (outer_id, outer_range) = &ranges[0];
+        assert_eq!(*outer_id, outer);
+        assert_eq!((outer_range.start_line, outer_range.end_line), (1, 5));
+    
+        let (inner_id, inner_range) = &ranges[1];
+        assert_eq!(*inner_id, inner);
+        assert_eq!((inner_range.start_line, inner_range.end_line), (3, 4));
+        assert_eq!(
+            &content[inner_range.byte_start..inner_range.byte_end],
+            "    def greet(self):\n        return 'hi'\n"
+        );
+    
+        // Rust uses `//` comments
+        assert!(markers::start_marker(outer, "rust").starts_with("// @metaforge:block"));
+    
+        // Languages without line comments get block comments, read back alike
+        let css = format!("{}\n.button {{ color: red; }}\n{}\n", markers::start_marker(outer, "css"), markers::end_marker(outer, "css"));
+        assert_eq!(css.lines().next(), Some(format!("/* @metaforge:block {} start */", outer).as_str()));
+        let html = format!("{}\n<p>hi</p>\n{}\n", markers::start_marker(inner, "html"), markers::end_marker(inner, "html"));
+        assert!(html.starts_with("<!-- @metaforge:block"));
+        assert_eq!(markers::parse_markers(&css)[0].0, outer);
+        let (stripped, ranges) = markers::strip_markers(&html);
+        assert_eq!((stripped.as_str(), ranges[0].0), ("<p>hi</p>\n", inner));
+    }
+
+    /// Stripping markers should give back unmarked output with block ranges into it
+    #[test]
+    fn test_strip_markers_maps_blocks_into_clean_output() {
+        let function = Uuid::new_v4();
+    
+        let content = [
+            "import os".to_string(),
+            String::new(),
+            markers::start_marker(function, "python"),
+            "def main():".to_string(),
+            "    return os.getcwd()".to_string(),
+            markers::end_marker(function, "python"),
+        ].join("\n");
+    
+        let (stripped, ranges) = markers::strip_markers(&content);
+        assert_eq!(stripped, "import os\n\ndef main():\n    return os.getcwd()");
+    
+        assert_eq!(ranges.len(), 1);
+        let (block_id, range) = &ranges[0];
+        assert_eq!(*block_id, function);
+        assert_eq!((range.start_line, range.end_line), (2, 3));
+        assert_eq!(&stripped[range.byte_start..range.byte_end], "def main():\n    return os.getcwd()");
+    }
+}
@@ -3,11 +3,34 @@ pub mod universal;
 pub mod validation;
 pub mod hierarchical;
 pub mod formatters;
+pub mod ordering;
+pub mod markers;
+pub mod manifest;
+pub mod source_map;
+pub mod idempotency;
+pub mod type_declarations;
+pub mod go;
+pub mod java;
+pub mod python_members;
+pub mod python_types;
+pub mod parameters;
+pub mod rust_attributes;
+pub mod rust_enums;
+pub mod rust_impls;
+pub mod statements;
+pub mod promise_style;
+pub mod output_naming;
+pub mod output_check;
+pub mod directory_diff;
+pub mod identifier_casing;
+pub mod package_files;
+pub mod tracer;
+pub mod summary;
 
 #[allow(unused_imports)]
 pub use universal::{UniversalGenerator, GenerationConfig};
 pub use hierarchical::HierarchicalGenerator;
 #[allow(unused_imports)]
-pub use formatters::{CodeFormatter, get_formatter, LanguageFormatters};
+pub use formatters::{CodeFormatter, CommandFormatter, get_formatter, get_formatter_with_config, FormatConfig, FormatSettings, FormatterCommand, LanguageFormatters};
 // pub use templates::{TemplateEngine, LanguageTemplate};
 // pub use validation::{ReconstructionValidator, ValidationResult};
\ No newline at end of file
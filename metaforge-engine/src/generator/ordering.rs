@@ -0,0 +1,68 @@
+//! Deterministic block ordering
+
+use std::cmp::Ordering;
+use crate::database::Block;
+
+/// Total order for blocks emitted at the same level
+pub fn compare_blocks(a: &Block, b: &Block) -> Ordering {
+    a.position.cmp(&b.position)
+        .then_with(|| compare_optional(a.hierarchical_index, b.hierarchical_index))
+        .then_with(|| compare_optional(source_start(a), source_start(b)))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Total order for the children of one parent block
+pub fn compare_children(a: &Block, b: &Block) -> Ordering {
+    a.position_in_parent.cmp(&b.position_in_parent)
+        .then_with(|| compare_blocks(a, b))
+}
+
+/// Sort blocks in place using `compare_blocks`
+pub fn sort_blocks(blocks: &mut [Block]) {
+    blocks.sort_by(compare_blocks);
+}
+
+/// Line and column where the block started in its original source, if recorded
+pub fn source_start(block: &Block) -> Option<(i64, i64)> {
+    let metadata = block.position_metadata.as_ref()?;
+    let line = metadata.get("start_line")?.as_i64()?;
+    let column = metadata.get("start_column").and_then(|column| column.as_i64()).unwrap_or(0);
+    Some((line, column))
+}
+
+/// `Some` values first, in ascending order, then `None`
+fn compare_optional<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// Test that blocks tied on position fall back to where they started in
+    /// the source, as stored in their position metadata
+    #[test]
+    fn test_block_ordering_breaks_ties_on_source_position() {
+        let container = Uuid::new_v4();
+        let block = |name: &str, start: Option<(i64, i64)>| {
+            let mut block = Block::new(container, "Function", name, serde_json::json!({}));
+            block.position_metadata = start.map(|(line, column)| serde_json::json!({"start_line": line, "start_column": column}));
+            block
+        };
+        let mut blocks = vec![
+            block("unplaced", None),
+            block("second_on_line", Some((4, 20))),
+            block("later_line", Some((9, 0))),
+            block("first_on_line", Some((4, 0))),
+        ];
+        sort_blocks(&mut blocks);
+        let names: Vec<&str> = blocks.iter().filter_map(|block| block.semantic_name.as_deref()).collect();
+        assert_eq!(names, vec!["first_on_line", "second_on_line", "later_line", "unplaced"]);
+    }
+}
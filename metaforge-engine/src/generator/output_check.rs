@@ -0,0 +1,113 @@
+//! Checking generated output against the files on disk
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileDrift {
+    /// The file exists with different content, first differing at this
+    /// 1-based line
+    Changed { line: usize },
+    /// Generation would create the file
+    Missing,
+}
+
+impl fmt::Display for FileDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileDrift::Changed { line } => write!(f, "differs from line {}", line),
+            FileDrift::Missing => f.write_str("missing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfDateFile {
+    pub path: PathBuf,
+    pub drift: FileDrift,
+}
+
+/// Files compared so far and those that don't match
+#[derive(Debug, Clone, Default)]
+pub struct OutputCheck {
+    pub checked: usize,
+    pub out_of_date: Vec<OutOfDateFile>,
+}
+
+impl OutputCheck {
+    /// Compare `expected` with the file at `path`, recording it when it
+    /// differs or doesn't exist
+    pub fn compare(&mut self, path: &Path, expected: &str) -> Result<()> {
+        self.checked += 1;
+        let drift = match std::fs::read(path) {
+            Ok(existing) if existing == expected.as_bytes() => return Ok(()),
+            Ok(existing) => FileDrift::Changed {
+                line: first_differing_line(&String::from_utf8_lossy(&existing), expected),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => FileDrift::Missing,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        self.out_of_date.push(OutOfDateFile { path: path.to_path_buf(), drift });
+        Ok(())
+    }
+
+    pub fn is_up_to_date(&self) -> bool {
+        self.out_of_date.is_empty()
+    }
+}
+
+/// 1-based number of the first line where the two texts differ; texts
+/// differing only in a trailing newline differ on their last line
+pub fn first_differing_line(existing: &str, expected: &str) -> usize {
+    let existing: Vec<&str> = existing.split('\n').collect();
+    let expected: Vec<&str> = expected.split('\n').collect();
+    let common = existing.iter().zip(&expected).take_while(|(a, b)| a == b).count();
+
+    let longer = if existing.len() > expected.len() { &existing } else { &expected };
+    if common == existing.len().min(expected.len()) && longer[common..] == [""] {
+        common
+    } else {
+        common + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// `generate --check` lists files that differ or are missing, without writing
+    #[test]
+    fn test_output_check_reports_out_of_date_files() -> Result<()> {
+        let output = std::env::temp_dir().join(format!("metaforge-check-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&output)?;
+        std::fs::write(output.join("same.py"), "x = 1\n")?;
+        std::fs::write(output.join("stale.py"), "x = 1\ny = 2\n")?;
+        std::fs::write(output.join("newline.py"), "x = 1")?;
+    
+        let mut check = OutputCheck::default();
+        check.compare(&output.join("same.py"), "x = 1\n")?;
+        assert!(check.is_up_to_date());
+        check.compare(&output.join("stale.py"), "x = 1\ny = 3\n")?;
+        check.compare(&output.join("newline.py"), "x = 1\n")?;
+        check.compare(&output.join("pkg/new.py"), "z = 0\n")?;
+    
+        assert_eq!(check.checked, 4);
+        let drifts: Vec<(String, String)> = check.out_of_date.iter()
+            .map(|file| (file.path.strip_prefix(&output).unwrap().display().to_string(), file.drift.to_string()))
+            .collect();
+        assert_eq!(drifts, vec![
+            ("stale.py".to_string(), "differs from line 2".to_string()),
+            ("newline.py".to_string(), "differs from line 1".to_string()),
+            ("pkg/new.py".to_string(), "missing".to_string()),
+        ]);
+        assert_eq!(check.out_of_date[2].drift, FileDrift::Missing);
+        assert!(!output.join("pkg").exists(), "checking must not write");
+        assert_eq!(std::fs::read_to_string(output.join("stale.py"))?, "x = 1\ny = 2\n");
+        assert_eq!(first_differing_line("a\nb", "a\nb\nc"), 3);
+    
+        std::fs::remove_dir_all(&output)?;
+        Ok(())
+    }
+}
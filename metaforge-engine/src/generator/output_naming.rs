@@ -0,0 +1,210 @@
+//! Output file naming
+
+use anyhow::{anyhow, Result};
+use ast_extractor::Language;
+use std::path::{Path, PathBuf};
+
+/// File extension for a target language, `txt` when it isn't known
+pub fn file_extension(language: &str) -> &'static str {
+    known_file_extension(language).unwrap_or("txt")
+}
+
+/// File extension for a target language or alias, case-insensitively.
+/// Every command that writes generated code goes through this, so a
+/// language gets the same extension whichever command produced the file.
+pub fn known_file_extension(language: &str) -> Option<&'static str> {
+    language.parse::<Language>().ok().map(Language::extension)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamingStrategy {
+    /// Keep the semantic name unchanged
+    AsIs,
+    /// `UserService` → `user_service`
+    SnakeCase,
+    /// `user_service` → `UserService`
+    PascalCase,
+    /// Whatever the target language expects: PascalCase for Java, Kotlin and
+    /// C# classes, snake_case for Rust, Python, Go, Ruby and C, unchanged otherwise
+    #[default]
+    LanguageDefault,
+}
+
+impl NamingStrategy {
+    /// Look up a strategy by name (`as-is`, `snake`, `pascal` or `default`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "as-is" => Some(Self::AsIs),
+            "snake" => Some(Self::SnakeCase),
+            "pascal" => Some(Self::PascalCase),
+            "default" => Some(Self::LanguageDefault),
+            _ => None,
+        }
+    }
+
+    /// The file stem for `name` in `language`
+    pub fn apply(self, name: &str, language: &str) -> String {
+        match self {
+            Self::AsIs => name.to_string(),
+            Self::SnakeCase => words(name).join("_").to_lowercase(),
+            Self::PascalCase => words(name).iter().map(|word| capitalize(word)).collect(),
+            Self::LanguageDefault => match language.parse::<Language>() {
+                Ok(Language::Java | Language::Kotlin | Language::CSharp) => Self::PascalCase.apply(name, language),
+                Ok(Language::Rust | Language::Python | Language::Go | Language::Ruby | Language::C) => {
+                    Self::SnakeCase.apply(name, language)
+                }
+                _ => Self::AsIs.apply(name, language),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollisionPolicy {
+    /// Refuse to replace an existing file
+    #[default]
+    Error,
+    /// Append `_2`, `_3`, … to the stem until the name is free
+    Suffix,
+}
+
+impl CollisionPolicy {
+    /// Look up a policy by name (`error` or `suffix`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(Self::Error),
+            "suffix" => Some(Self::Suffix),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputNaming {
+    pub strategy: NamingStrategy,
+    pub collisions: CollisionPolicy,
+}
+
+impl OutputNaming {
+    pub fn new(strategy: NamingStrategy, collisions: CollisionPolicy) -> Self {
+        Self { strategy, collisions }
+    }
+
+    /// File name for `name` in `language`, before any collision handling
+    pub fn file_name(&self, name: &str, language: &str) -> String {
+        format!("{}.{}", self.strategy.apply(name, language), file_extension(language))
+    }
+
+    /// File name for `name` that `is_taken` doesn't reject, following the
+    /// collision policy
+    pub fn unique_file_name(
+        &self,
+        name: &str,
+        language: &str,
+        is_taken: impl Fn(&str) -> bool,
+    ) -> Result<String> {
+        let file_name = self.file_name(name, language);
+        if !is_taken(&file_name) {
+            return Ok(file_name);
+        }
+        match self.collisions {
+            CollisionPolicy::Error => Err(anyhow!(
+                "{} already exists; choose another name or use --on-collision suffix",
+                file_name
+            )),
+            CollisionPolicy::Suffix => {
+                let stem = self.strategy.apply(name, language);
+                let extension = file_extension(language);
+                Ok((2..)
+                    .map(|index| format!("{}_{}.{}", stem, index, extension))
+                    .find(|candidate| !is_taken(candidate))
+                    .expect("unbounded suffixes"))
+            }
+        }
+    }
+
+    /// Path in `dir` to write `name` to, following the collision policy
+    pub fn output_path(&self, dir: &Path, name: &str, language: &str) -> Result<PathBuf> {
+        let file_name = self.unique_file_name(name, language, |candidate| dir.join(candidate).exists())?;
+        Ok(dir.join(file_name))
+    }
+}
+
+/// Split an identifier into words at separators and case changes, keeping
+/// acronyms together: `HTTPServer_config` → `HTTP`, `Server`, `config`
+pub(crate) fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name.split(|c: char| !c.is_alphanumeric()).filter(|part| !part.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut current = String::new();
+        for (index, &c) in chars.iter().enumerate() {
+            let boundary = index > 0 && c.is_uppercase() && {
+                let previous = chars[index - 1];
+                let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+                !previous.is_uppercase() || next_is_lower
+            };
+            if boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        words.push(current);
+    }
+    words
+}
+
+pub(crate) fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_output_naming_follows_language_conventions_and_resolves_collisions() -> Result<()> {
+        let naming = OutputNaming::default();
+        assert_eq!(naming.file_name("UserService", "rust"), "user_service.rs");
+        assert_eq!(naming.file_name("HTTPServerConfig", "python"), "http_server_config.py");
+        assert_eq!(naming.file_name("user_service", "csharp"), "UserService.cs");
+        assert_eq!(naming.file_name("TodoList", "typescript"), "TodoList.ts");
+        assert_eq!(NamingStrategy::AsIs.apply("UserService", "rust"), "UserService");
+        assert_eq!(NamingStrategy::PascalCase.apply("order-line item", "go"), "OrderLineItem");
+        assert_eq!(file_extension("cobol"), "txt");
+
+        let output = std::env::temp_dir().join(format!("metaforge-naming-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&output)?;
+        std::fs::write(output.join("user_service.rs"), "")?;
+
+        // `UserService` and `user_service` name the same file
+        assert!(naming.output_path(&output, "user_service", "rust").is_err());
+        let suffixed = OutputNaming::new(NamingStrategy::LanguageDefault, CollisionPolicy::Suffix);
+        assert_eq!(suffixed.output_path(&output, "UserService", "rust")?, output.join("user_service_2.rs"));
+        std::fs::write(output.join("user_service_2.rs"), "")?;
+        assert_eq!(suffixed.output_path(&output, "UserService", "rust")?, output.join("user_service_3.rs"));
+        assert_eq!(naming.output_path(&output, "Billing", "rust")?, output.join("billing.rs"));
+
+        std::fs::remove_dir_all(&output)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_every_command_shares_one_extension_per_language() {
+        // `compose` used to write JavaScript as `.txt`
+        for (language, extension) in [
+            ("python", "py"), ("javascript", "js"), ("typescript", "ts"), ("tsx", "tsx"),
+            ("rust", "rs"), ("go", "go"), ("java", "java"), ("csharp", "cs"),
+        ] {
+            assert_eq!(known_file_extension(language), Some(extension));
+            assert_eq!(file_extension(language), extension);
+        }
+        assert_eq!(known_file_extension("JavaScript"), Some("js"));
+        assert_eq!(known_file_extension("cobol"), None);
+        assert_eq!(file_extension("cobol"), "txt");
+    }
+}
@@ -0,0 +1,334 @@
+//! Package glue files
+
+use ast_extractor::Language;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::database::Block;
+
+/// Block types a module can export
+const EXPORTABLE_TYPES: [&str; 7] = ["Function", "Class", "Enum", "Interface", "Variable", "TypeDef", "Component"];
+
+/// Rust directories whose files are separate binaries rather than modules
+const RUST_BINARY_DIRS: [&str; 1] = ["bin"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageModule {
+    /// Path relative to the output directory, e.g. `app/models.py`
+    pub path: PathBuf,
+    pub language: Language,
+    /// Public top-level names, in source order
+    pub exports: Vec<String>,
+}
+
+impl PackageModule {
+    pub fn new(path: impl Into<PathBuf>, language: Language, exports: Vec<String>) -> Self {
+        Self { path: path.into(), language, exports }
+    }
+
+    /// The module at `path` exporting its public top-level blocks: names
+    /// without a leading underscore in Python, `pub` items in Rust and
+    /// `export`ed declarations in JavaScript and TypeScript. `None` for
+    /// languages without package glue.
+    pub fn from_blocks(path: &str, language: &str, blocks: &[Block]) -> Option<Self> {
+        let language = language.parse::<Language>().ok()?;
+        family(language)?;
+
+        let mut exports: Vec<String> = Vec::new();
+        for block in blocks {
+            if block.parent_block_id.is_some() || !EXPORTABLE_TYPES.contains(&block.block_type.as_str()) {
+                continue;
+            }
+            let Some(name) = &block.semantic_name else {
+                continue;
+            };
+            let public = match language {
+                Language::Python => !name.starts_with('_'),
+                _ => block.semantic_metadata.as_ref()
+                    .and_then(|metadata| metadata.get("visibility"))
+                    .and_then(|visibility| visibility.as_str())
+                    == Some("Public"),
+            };
+            if public && !exports.contains(name) {
+                exports.push(name.clone());
+            }
+        }
+        Some(Self::new(path, language, exports))
+    }
+
+    fn stem(&self) -> String {
+        self.path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageFile {
+    /// Path relative to the output directory, e.g. `app/__init__.py`
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Family {
+    Python,
+    Rust,
+    Script,
+}
+
+fn family(language: Language) -> Option<Family> {
+    match language {
+        Language::Python => Some(Family::Python),
+        Language::Rust => Some(Family::Rust),
+        Language::JavaScript | Language::TypeScript | Language::Tsx => Some(Family::Script),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Directory<'a> {
+    modules: Vec<&'a PackageModule>,
+    subdirectories: BTreeSet<PathBuf>,
+}
+
+/// Glue files the generated `modules` need and don't already include, in path order
+pub fn package_files(modules: &[PackageModule]) -> Vec<PackageFile> {
+    let existing: BTreeSet<&Path> = modules.iter().map(|module| module.path.as_path()).collect();
+    let mut by_family: BTreeMap<Family, Vec<&PackageModule>> = BTreeMap::new();
+    for module in modules {
+        if let Some(family) = family(module.language) {
+            by_family.entry(family).or_default().push(module);
+        }
+    }
+
+    let mut files = Vec::new();
+    for (family, modules) in by_family {
+        let directories = directories(&modules);
+        for (dir, directory) in &directories {
+            let file = match family {
+                Family::Python => python_init(dir, directory, &existing),
+                Family::Rust => rust_module_file(dir, directory, &directories, &existing),
+                Family::Script => script_index(dir, directory, &directories, &existing),
+            };
+            files.extend(file);
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Every directory holding a module or, further down, a module's directory
+fn directories<'a>(modules: &[&'a PackageModule]) -> BTreeMap<PathBuf, Directory<'a>> {
+    let mut directories: BTreeMap<PathBuf, Directory<'a>> = BTreeMap::new();
+    for module in modules {
+        let dir = module.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        directories.entry(dir.clone()).or_default().modules.push(module);
+        let mut child = dir;
+        while let Some(parent) = child.parent().map(Path::to_path_buf) {
+            directories.entry(parent.clone()).or_default().subdirectories.insert(child);
+            child = parent;
+        }
+    }
+    for directory in directories.values_mut() {
+        directory.modules.sort_by_key(|module| module.stem());
+    }
+    directories
+}
+
+/// Whether anything in `dir` or below it is exported
+fn exports_anything(dir: &Path, directories: &BTreeMap<PathBuf, Directory>) -> bool {
+    directories.get(dir).is_some_and(|directory| {
+        directory.modules.iter().any(|module| !module.exports.is_empty())
+            || directory.subdirectories.iter().any(|sub| exports_anything(sub, directories))
+    })
+}
+
+fn name_of(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// `__init__.py` importing each module's public names, for every directory
+/// below the output root
+fn python_init(dir: &Path, directory: &Directory, existing: &BTreeSet<&Path>) -> Option<PackageFile> {
+    let path = dir.join("__init__.py");
+    if dir.as_os_str().is_empty() || existing.contains(path.as_path()) {
+        return None;
+    }
+    let mut lines = Vec::new();
+    let mut all = Vec::new();
+    for module in &directory.modules {
+        let stem = module.stem();
+        if module.exports.is_empty() || stem.starts_with("__") {
+            continue;
+        }
+        lines.push(format!("from .{} import {}", stem, module.exports.join(", ")));
+        all.extend(module.exports.iter().map(|name| format!("\"{}\"", name)));
+    }
+    if !all.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("__all__ = [{}]", all.join(", ")));
+    }
+    Some(PackageFile { path, content: render_lines(&lines) })
+}
+
+/// `lib.rs` for a crate's `src` directory without one, `mod.rs` for the
+/// module directories inside it. Modules with public items are `pub mod`.
+fn rust_module_file(
+    dir: &Path,
+    directory: &Directory,
+    directories: &BTreeMap<PathBuf, Directory>,
+    existing: &BTreeSet<&Path>,
+) -> Option<PackageFile> {
+    let is_crate_root = |dir: &Path| {
+        name_of(dir) == "src" || ["lib.rs", "main.rs"].iter().any(|root| existing.contains(dir.join(root).as_path()))
+    };
+    let path = if is_crate_root(dir) {
+        if existing.contains(dir.join("main.rs").as_path()) {
+            return None;
+        }
+        dir.join("lib.rs")
+    } else {
+        // Only directories inside a crate's source tree are modules
+        let inside_crate = dir.ancestors().skip(1).any(is_crate_root);
+        if !inside_crate || dir.ancestors().any(|ancestor| RUST_BINARY_DIRS.contains(&name_of(ancestor).as_str())) {
+            return None;
+        }
+        if existing.contains(dir.with_extension("rs").as_path()) {
+            return None;
+        }
+        dir.join("mod.rs")
+    };
+    if existing.contains(path.as_path()) {
+        return None;
+    }
+
+    let mut declarations: Vec<(String, bool)> = directory.modules.iter()
+        .map(|module| (module.stem(), !module.exports.is_empty()))
+        .filter(|(stem, _)| !["lib", "main", "mod"].contains(&stem.as_str()))
+        .collect();
+    declarations.extend(directory.subdirectories.iter()
+        .filter(|sub| !RUST_BINARY_DIRS.contains(&name_of(sub).as_str()))
+        .filter(|sub| !existing.contains(sub.with_extension("rs").as_path()))
+        .map(|sub| (name_of(sub), exports_anything(sub, directories))));
+    declarations.sort();
+    let lines: Vec<String> = declarations.into_iter()
+        .map(|(name, public)| format!("{}mod {};", if public { "pub " } else { "" }, name))
+        .collect();
+    Some(PackageFile { path, content: render_lines(&lines) })
+}
+
+/// `index.ts` (or `index.js` when the directory holds no TypeScript)
+/// re-exporting every module and subdirectory that exports something
+fn script_index(
+    dir: &Path,
+    directory: &Directory,
+    directories: &BTreeMap<PathBuf, Directory>,
+    existing: &BTreeSet<&Path>,
+) -> Option<PackageFile> {
+    if dir.as_os_str().is_empty() {
+        return None;
+    }
+    let has_index = ["ts", "tsx", "js", "jsx", "mjs"].iter()
+        .any(|extension| existing.contains(dir.join(format!("index.{}", extension)).as_path()));
+    if has_index {
+        return None;
+    }
+    let typescript = modules_below(dir, directories).iter()
+        .any(|module| module.language != Language::JavaScript);
+
+    let mut targets: Vec<String> = directory.modules.iter()
+        .filter(|module| !module.exports.is_empty())
+        .map(|module| module.stem())
+        .collect();
+    targets.extend(directory.subdirectories.iter()
+        .filter(|sub| exports_anything(sub, directories))
+        .map(|sub| name_of(sub)));
+    targets.sort();
+    let lines: Vec<String> = targets.iter()
+        .map(|target| format!("export * from './{}';", target))
+        .collect();
+    Some(PackageFile {
+        path: dir.join(if typescript { "index.ts" } else { "index.js" }),
+        content: render_lines(&lines),
+    })
+}
+
+fn modules_below<'a>(dir: &Path, directories: &BTreeMap<PathBuf, Directory<'a>>) -> Vec<&'a PackageModule> {
+    directories.get(dir)
+        .map(|directory| {
+            let mut modules = directory.modules.clone();
+            for sub in &directory.subdirectories {
+                modules.extend(modules_below(sub, directories));
+            }
+            modules
+        })
+        .unwrap_or_default()
+}
+
+fn render_lines(lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::parser::universal::UniversalParser;
+
+    /// Package glue files are derived from the generated paths and re-export public names
+    #[test]
+    fn test_package_files_fill_in_package_structure() -> Result<()> {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let modules = vec![
+            PackageModule::new("app/models.py", Language::Python, names(&["User", "Order"])),
+            PackageModule::new("app/_internal.py", Language::Python, vec![]),
+            PackageModule::new("app/api/routes.py", Language::Python, names(&["router"])),
+            PackageModule::new("src/parser.rs", Language::Rust, names(&["parse"])),
+            PackageModule::new("src/util/strings.rs", Language::Rust, vec![]),
+            PackageModule::new("src/bin/cli.rs", Language::Rust, vec![]),
+            PackageModule::new("web/components/button.tsx", Language::Tsx, names(&["Button"])),
+            PackageModule::new("web/utils.js", Language::JavaScript, vec![]),
+            PackageModule::new("lib/index.js", Language::JavaScript, names(&["main"])),
+        ];
+        let files: Vec<(String, String)> = package_files(&modules).into_iter()
+            .map(|file| (file.path.to_string_lossy().into_owned(), file.content))
+            .collect();
+        assert_eq!(files, vec![
+            ("app/__init__.py".to_string(), "from .models import User, Order\n\n__all__ = [\"User\", \"Order\"]\n".to_string()),
+            ("app/api/__init__.py".to_string(), "from .routes import router\n\n__all__ = [\"router\"]\n".to_string()),
+            ("src/lib.rs".to_string(), "pub mod parser;\nmod util;\n".to_string()),
+            ("src/util/mod.rs".to_string(), "mod strings;\n".to_string()),
+            ("web/components/index.ts".to_string(), "export * from './button';\n".to_string()),
+            ("web/index.ts".to_string(), "export * from './components';\n".to_string()),
+        ]);
+
+        // Exports come from top-level blocks and their recorded visibility
+        let function = |name: &str, visibility: &str| Block {
+            semantic_metadata: Some(serde_json::json!({"visibility": visibility})),
+            ..Block::new(Uuid::new_v4(), "Function", name, serde_json::json!({}))
+        };
+        let public_fn = function("parse", "Public");
+        let mut private_fn = function("helper", "Private");
+        let module = PackageModule::from_blocks("src/parser.rs", "rust", &[public_fn.clone(), private_fn.clone()]).unwrap();
+        assert_eq!(module.exports, vec!["parse".to_string()]);
+        private_fn.semantic_name = Some("_helper".to_string());
+        let module = PackageModule::from_blocks("app/parser.py", "py", &[public_fn, private_fn]).unwrap();
+        assert_eq!(module.exports, vec!["parse".to_string()]);
+        assert!(PackageModule::from_blocks("Main.java", "java", &[]).is_none());
+
+        // JavaScript declarations are public only when exported
+        let source = "export function render() {}\nfunction helper() {}\nexport class View {}\nclass Cache {}\n";
+        let result = UniversalParser::new()?.parse_file(source, "javascript", "view.js")?;
+        let exported: Vec<&str> = result.blocks.iter()
+            .filter(|block| matches!(block.semantic_metadata.visibility, crate::core::Visibility::Public))
+            .map(|block| block.semantic_identity.canonical_name.as_str())
+            .collect();
+        assert_eq!(exported, vec!["render", "View"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+//! Parameter lists
+
+use code_builders::ExpressionRenderer;
+use crate::core::Parameter;
+
+/// Render one parameter as it appears between the parentheses
+pub fn render_parameter(param: &Parameter, language: &str) -> String {
+    let default = param.default_expression.as_ref()
+        .map(|expression| match language {
+            "python" => ExpressionRenderer::default().render(expression)
+                .unwrap_or_else(|_| expression.source_text.clone()),
+            _ => expression.source_text.clone(),
+        })
+        .or_else(|| param.default_value.clone());
+
+    match language {
+        "python" => match (&param.type_hint, default) {
+            (Some(type_hint), Some(default)) => format!("{}: {} = {}", param.name, type_hint, default),
+            (Some(type_hint), None) => format!("{}: {}", param.name, type_hint),
+            (None, Some(default)) => format!("{}={}", param.name, default),
+            (None, None) => param.name.clone(),
+        },
+        "javascript" | "typescript" | "tsx" => {
+            let mut rendered = param.name.clone();
+            // `x?: T` is optional without a default; `x = 1` already says so
+            if param.is_optional && default.is_none() {
+                rendered.push('?');
+            }
+            if let Some(type_hint) = &param.type_hint {
+                rendered.push_str(&format!(": {}", type_hint));
+            }
+            if let Some(default) = default {
+                rendered.push_str(&format!(" = {}", default));
+            }
+            rendered
+        }
+        _ => param.name.clone(),
+    }
+}
+
+/// Render a stored `parameters` array as a comma-separated list. Entries that
+/// don't deserialize as a `Parameter` fall back to their `name`.
+pub fn render_parameters(params: &serde_json::Value, language: &str) -> String {
+    let Some(params) = params.as_array() else {
+        return String::new();
+    };
+    params.iter()
+        .filter_map(|value| match serde_json::from_value::<Parameter>(value.clone()) {
+            Ok(param) => Some(render_parameter(&param, language)),
+            Err(_) => value.get("name").and_then(|name| name.as_str()).map(str::to_string),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::parser::universal::UniversalParser;
+
+    #[test]
+    fn test_parameter_defaults_round_trip_as_expressions() -> Result<()> {
+        let mut parser = UniversalParser::new()?;
+        let cases = [
+            ("python", "app.py", "def handler(retries=3, tags=[], started=time.now(), *args, name: str = 'guest', **options):\n    pass\n",
+                "retries=3, tags=[], started=time.now(), *args, name: str = 'guest', **options"),
+            ("javascript", "app.js", "function handler(retries = 3, tags = [], started = Date.now(), ...rest) {}\n",
+                "retries = 3, tags = [], started = Date.now(), ...rest"),
+            ("typescript", "app.ts", "function handler(retries: number = 3, label?: string, tags: string[] = []) {}\n",
+                "retries: number = 3, label?: string, tags: string[] = []"),
+        ];
+
+        for (language, path, source, expected) in cases {
+            let parse_result = parser.parse_file(source, language, path)?;
+            let function = parse_result.blocks.iter()
+                .find(|block| block.semantic_identity.canonical_name == "handler")
+                .expect("function extracted");
+            let parameters = &function.semantic_metadata.parameters;
+
+            // Defaults keep their structure, not just their text
+            let kinds: Vec<&str> = parameters.iter()
+                .filter_map(|param| param.default_expression.as_ref())
+                .map(|expression| expression.expression_type.as_str())
+                .collect();
+            match language {
+                "python" => assert_eq!(kinds, vec!["integer", "list", "call", "string"]),
+                "javascript" => assert_eq!(kinds, vec!["number", "array", "call_expression"]),
+                _ => assert_eq!(kinds, vec!["number", "array"]),
+            }
+            assert!(parameters.iter().all(|param| param.is_optional == (param.default_value.is_some() || param.name == "label")));
+
+            let stored = serde_json::to_value(parameters)?;
+            assert_eq!(render_parameters(&stored, language), expected, "{}", language);
+        }
+
+        // Rows written before defaults were stored still render their names
+        let legacy = serde_json::json!([{"name": "a"}, {"name": "b", "type_hint": null}]);
+        assert_eq!(render_parameters(&legacy, "python"), "a, b");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! Promise style of generated JavaScript and TypeScript
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::core::{BodyStatement, FunctionBody, StatementKind};
+use crate::database::Block;
+use super::statements::render_statements;
+
+/// `generation_hints` key selecting the promise style
+pub const PROMISE_STYLE_HINT: &str = "promise_style";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromiseStyle {
+    #[default]
+    AsyncAwait,
+    Then,
+}
+
+impl PromiseStyle {
+    /// The style `block`'s generation hints ask for; unknown values keep
+    /// the default
+    pub fn for_block(block: &Block) -> Self {
+        block.generation_hints.as_ref()
+            .and_then(|hints| hints.get(PROMISE_STYLE_HINT))
+            .and_then(|style| serde_json::from_value(style.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// `body` as a `.then()` chain, each line prefixed with `indent`, or `None`
+/// when it has no top-level `await` or can't be chained
+pub fn render_then_chain(body: &FunctionBody, language: &str, indent: &str) -> Option<String> {
+    let first_await = body.statements.iter().position(|statement| awaited(statement).is_some())?;
+    let before = &body.statements[..first_await];
+    // A value returned before the first `await` wouldn't be a promise
+    if before.iter().flat_map(BodyStatement::walk).any(|statement| statement.kind == StatementKind::Return) {
+        return None;
+    }
+    chain(&body.statements, language, indent)
+}
+
+fn chain(statements: &[BodyStatement], language: &str, indent: &str) -> Option<String> {
+    let Some(position) = statements.iter().position(|statement| awaited(statement).is_some()) else {
+        if statements.iter().any(|statement| contains_await(&statement.code)) {
+            return None;
+        }
+        return Some(render_statements(statements, language, indent));
+    };
+    let before = &statements[..position];
+    if before.iter().any(|statement| contains_await(&statement.code)) {
+        return None;
+    }
+    let (statement, rest) = (&statements[position], &statements[position + 1..]);
+    let promise = awaited(statement)?;
+    let promise = if needs_parentheses(promise) { format!("({})", promise) } else { promise.to_string() };
+
+    let mut lines = Vec::new();
+    if !before.is_empty() {
+        lines.push(render_statements(before, language, indent));
+    }
+    if statement.kind == StatementKind::Return {
+        lines.push(format!("{}return {};", indent, promise));
+        if !rest.is_empty() {
+            lines.push(render_statements(rest, language, indent));
+        }
+        return Some(lines.join("\n"));
+    }
+    let parameter = match statement.kind {
+        StatementKind::Expression => String::new(),
+        StatementKind::Assignment if is_declaration(&statement.code) => statement.target.clone()?,
+        _ => return None,
+    };
+    let callback = if rest.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n{}}}", chain(rest, language, &format!("{}    ", indent))?, indent)
+    };
+    lines.push(format!("{}return {}.then(({}) => {});", indent, promise, parameter, callback));
+    Some(lines.join("\n"))
+}
+
+/// The promise a top-level `await` statement waits for: `fetch(url)` in
+/// `const response = await fetch(url);`, `await fetch(url);` or
+/// `return await fetch(url);`. `None` when the statement has no `await` or
+/// awaits inside a larger expression.
+fn awaited(statement: &BodyStatement) -> Option<&str> {
+    if !matches!(statement.kind, StatementKind::Assignment | StatementKind::Expression | StatementKind::Return) {
+        return None;
+    }
+    let expression = statement.expression.as_ref()?.source_text.trim();
+    // Only the first of `const a = await f(), b = 1;` is parsed
+    if !statement.code.trim_end().trim_end_matches(';').trim_end().ends_with(expression) {
+        return None;
+    }
+    let promise = expression.strip_prefix("await")?;
+    if !promise.starts_with(char::is_whitespace) && !promise.starts_with('(') {
+        return None;
+    }
+    let promise = promise.trim();
+    (!contains_await(promise)).then_some(promise)
+}
+
+fn contains_await(code: &str) -> bool {
+    static AWAIT: OnceLock<Regex> = OnceLock::new();
+    AWAIT.get_or_init(|| Regex::new(r"\bawait\b").expect("valid regex")).is_match(code)
+}
+
+fn is_declaration(code: &str) -> bool {
+    ["const ", "let ", "var "].iter().any(|keyword| code.starts_with(keyword))
+}
+
+/// Whether `.then` would bind to less than all of `expression`, as in
+/// `a || b` or `new Client()`
+fn needs_parentheses(expression: &str) -> bool {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in expression.chars() {
+        if let Some(open) = quote {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if c == open => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            _ if c.is_alphanumeric() || matches!(c, '_' | '$' | '.' | '?' | '!') => {}
+            _ => return true,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::{grammar_for, UniversalParser};
+
+    #[test]
+    fn test_promise_style_hint_renders_valid_typescript() -> Result<()> {
+        let source = "async function greet(id: string): Promise<string> {\n    const user = await fetchUser(id);\n    log(user);\n    await audit(user.id);\n    return user.name;\n}\n";
+        let mut parser = UniversalParser::new()?;
+        let parse_result = parser.parse_file(source, "typescript", "greet.ts")?;
+        let function_body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast)
+            .expect("body attached");
+
+        let render = |style: &str| -> Result<String> {
+            let block = Block {
+                modifiers: Some(vec!["async".to_string()]),
+                body_ast: Some(function_body.to_value()),
+                source_language: Some("typescript".to_string()),
+                generation_hints: Some(serde_json::json!({"promise_style": style})),
+                ..Block::new(Uuid::new_v4(), "Function", "greet", serde_json::json!({}))
+            };
+            TemplateEngine::new().render_block(&block, "typescript")
+        };
+        let awaiting = render("async_await")?;
+        let chained = render("then")?;
+        assert!(awaiting.contains("async function greet") && awaiting.contains("const user = await fetchUser(id);"), "{}", awaiting);
+        assert!(chained.contains("return fetchUser(id).then((user) => {"), "{}", chained);
+        assert!(chained.contains("return audit(user.id).then(() => {"), "{}", chained);
+        assert!(!chained.contains("async") && !chained.contains("await"), "{}", chained);
+
+        let mut typescript = tree_sitter::Parser::new();
+        typescript.set_language(grammar_for("typescript").expect("bundled grammar"))?;
+        for rendered in [&awaiting, &chained] {
+            let tree = typescript.parse(rendered, None).expect("parsed");
+            assert!(!tree.root_node().has_error(), "{}", rendered);
+        }
+        Ok(())
+    }
+}
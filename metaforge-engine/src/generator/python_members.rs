@@ -0,0 +1,167 @@
+//! Python class members
+
+use serde::{Deserialize, Serialize};
+
+/// Key under `abstract_syntax` holding a serialized `PythonMember`
+pub const PYTHON_MEMBER_KEY: &str = "python_member";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PythonMember {
+    /// A `@property` getter or one of its `@name.setter`/`@name.deleter` methods
+    Property {
+        name: String,
+        accessor: PropertyAccessor,
+        /// Decorator expressions without the `@`, outermost first
+        decorators: Vec<String>,
+        /// Parameter list without the surrounding parentheses
+        parameters: String,
+        return_type: Option<String>,
+        is_async: bool,
+        /// Body lines with the body's own indentation removed
+        body: Vec<String>,
+    },
+    /// An assignment in a class body, e.g. `currency: str = "USD"`
+    ClassAttribute {
+        name: String,
+        annotation: Option<String>,
+        value: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyAccessor {
+    Getter,
+    Setter,
+    Deleter,
+}
+
+impl PropertyAccessor {
+    /// The accessor a decorator list declares, if any
+    pub fn from_decorators(decorators: &[String]) -> Option<Self> {
+        decorators.iter().find_map(|decorator| {
+            match decorator.as_str() {
+                "property" | "cached_property" | "functools.cached_property" => Some(Self::Getter),
+                other if other.ends_with(".setter") => Some(Self::Setter),
+                other if other.ends_with(".deleter") => Some(Self::Deleter),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl PythonMember {
+    /// Read the member stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(PYTHON_MEMBER_KEY)?.clone()).ok()
+    }
+
+    pub fn render(&self, indent: &str) -> String {
+        match self {
+            Self::Property { name, decorators, parameters, return_type, is_async, body, .. } => {
+                let mut lines: Vec<String> = decorators.iter()
+                    .map(|decorator| format!("{}@{}", indent, decorator))
+                    .collect();
+
+                let return_annotation = return_type.as_ref()
+                    .map(|return_type| format!(" -> {}", return_type))
+                    .unwrap_or_default();
+                lines.push(format!(
+                    "{}{}def {}({}){}:",
+                    indent,
+                    if *is_async { "async " } else { "" },
+                    name,
+                    parameters,
+                    return_annotation
+                ));
+
+                if body.is_empty() {
+                    lines.push(format!("{}    pass", indent));
+                }
+                lines.extend(body.iter().map(|line| {
+                    if line.is_empty() { String::new() } else { format!("{}    {}", indent, line) }
+                }));
+                lines.join("\n")
+            }
+            Self::ClassAttribute { name, annotation, value } => {
+                let mut line = format!("{}{}", indent, name);
+                if let Some(annotation) = annotation {
+                    line.push_str(&format!(": {}", annotation));
+                }
+                if let Some(value) = value {
+                    line.push_str(&format!(" = {}", value));
+                }
+                line
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::core::BlockType;
+    use crate::parser::universal::UniversalParser;
+
+    fn regenerate_python_class(source: &str) -> Result<(String, Vec<PythonMember>)> {
+        let parse_result = UniversalParser::new()?.parse_file(source, "python", "account.py")?;
+        let class = parse_result.blocks.iter()
+            .find(|block| block.block_type == BlockType::Class)
+            .expect("class extracted");
+    
+        let members: Vec<PythonMember> = parse_result.blocks.iter()
+            .filter(|block| block.structural_context.parent_block == Some(class.id))
+            .filter_map(|block| PythonMember::from_abstract_syntax(&block.syntax_preservation.normalized_ast))
+            .collect();
+        let rendered: Vec<String> = members.iter().map(|member| member.render("    ")).collect();
+    
+        Ok((format!("class {}:\n{}\n", class.semantic_identity.canonical_name, rendered.join("\n\n")), members))
+    }
+
+    /// Test round-trip of a property getter, its setter and a class attribute with a default
+    #[test]
+    fn test_python_properties_and_class_attributes_round_trip() -> Result<()> {
+        let source = r#"class Account:
+    currency: str = "USD"
+
+    @property
+    def balance(self) -> int:
+        return self._balance
+
+    @balance.setter
+    def balance(self, value: int) -> None:
+        if value < 0:
+            raise ValueError("negative balance")
+        self._balance = value
+"#;
+    
+        let (regenerated, members) = regenerate_python_class(source)?;
+        assert_eq!(regenerated, source);
+    
+        let accessors: Vec<Option<PropertyAccessor>> = members.iter()
+            .map(|member| match member {
+                PythonMember::Property { accessor, .. } => Some(*accessor),
+                PythonMember::ClassAttribute { .. } => None,
+            })
+            .collect();
+        assert_eq!(accessors, vec![None, Some(PropertyAccessor::Getter), Some(PropertyAccessor::Setter)]);
+        Ok(())
+    }
+
+    /// Test that multi-byte whitespace inside a property body is not split when stripping indentation
+    #[test]
+    fn test_property_body_with_multibyte_whitespace() -> Result<()> {
+        let source = "class Konto:\n    @property\n    def saldo(self) -> str:\n        \"\"\"Saldo\n       \u{00A0} in €\n        \"\"\"\n        return \"💶\"\n";
+    
+        let (_, members) = regenerate_python_class(source)?;
+        match &members[..] {
+            [PythonMember::Property { body, .. }] => {
+                assert_eq!(body, &vec!["\"\"\"Saldo", "\u{00A0} in €", "\"\"\"", "return \"💶\""]);
+            }
+            other => panic!("Expected one property, got {:?}", other),
+        }
+        Ok(())
+    }
+}
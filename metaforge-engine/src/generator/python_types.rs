@@ -0,0 +1,187 @@
+//! Python type declarations
+
+use serde::{Deserialize, Serialize};
+
+/// Key under `abstract_syntax` holding a serialized `PythonTypeDeclaration`
+pub const PYTHON_TYPE_KEY: &str = "python_type";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PythonTypeDeclaration {
+    /// `UserId = NewType('UserId', int)`
+    NewType {
+        name: String,
+        /// The callee as written, e.g. `NewType` or `typing.NewType`
+        function: String,
+        /// The type name argument, quotes included
+        name_argument: String,
+        base: String,
+    },
+    /// `type Vector = list[float]`, or the older `Vector: TypeAlias = list[float]`
+    Alias {
+        name: String,
+        /// Parameters of a generic `type` statement, e.g. `T` or `T: int`
+        type_parameters: Vec<String>,
+        value: String,
+        /// The `TypeAlias` annotation; `None` for a `type` statement
+        annotation: Option<String>,
+    },
+    /// A class deriving from `TypedDict`
+    TypedDict {
+        name: String,
+        /// Superclass arguments as written, `total=False` included
+        bases: Vec<String>,
+        docstring: Option<String>,
+        fields: Vec<TypedField>,
+    },
+    /// A class deriving from `NamedTuple`
+    NamedTuple {
+        name: String,
+        bases: Vec<String>,
+        docstring: Option<String>,
+        fields: Vec<TypedField>,
+    },
+}
+
+/// A `name: annotation` line of a `TypedDict` or `NamedTuple`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypedField {
+    pub name: String,
+    pub annotation: String,
+    pub default: Option<String>,
+    /// Whether the key must be present (`TypedDict`) or the field passed
+    /// (`NamedTuple`)
+    pub required: bool,
+}
+
+impl TypedField {
+    /// A `TypedDict` key: `Required[...]` and `NotRequired[...]` override the
+    /// class's `total`
+    pub fn typed_dict_key(name: String, annotation: String, total: bool) -> Self {
+        let qualifier = annotation.split('[').next().unwrap_or_default();
+        let required = match qualifier.rsplit('.').next().unwrap_or_default() {
+            "Required" => true,
+            "NotRequired" => false,
+            _ => total,
+        };
+        Self { name, annotation, default: None, required }
+    }
+
+    /// A `NamedTuple` field, required unless it has a default
+    pub fn named_tuple_field(name: String, annotation: String, default: Option<String>) -> Self {
+        let required = default.is_none();
+        Self { name, annotation, default, required }
+    }
+}
+
+impl PythonTypeDeclaration {
+    /// Read the declaration stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(PYTHON_TYPE_KEY)?.clone()).ok()
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::NewType { name, .. }
+            | Self::Alias { name, .. }
+            | Self::TypedDict { name, .. }
+            | Self::NamedTuple { name, .. } => name,
+        }
+    }
+
+    /// Render as Python, every line prefixed with `indent`
+    pub fn render(&self, indent: &str) -> String {
+        match self {
+            Self::NewType { name, function, name_argument, base } => {
+                format!("{}{} = {}({}, {})", indent, name, function, name_argument, base)
+            }
+            Self::Alias { name, type_parameters, value, annotation } => match annotation {
+                Some(annotation) => format!("{}{}: {} = {}", indent, name, annotation, value),
+                None if type_parameters.is_empty() => format!("{}type {} = {}", indent, name, value),
+                None => format!("{}type {}[{}] = {}", indent, name, type_parameters.join(", "), value),
+            },
+            Self::TypedDict { name, bases, docstring, fields }
+            | Self::NamedTuple { name, bases, docstring, fields } => {
+                let mut lines = vec![format!("{}class {}({}):", indent, name, bases.join(", "))];
+                let body_indent = format!("{}    ", indent);
+                if let Some(docstring) = docstring {
+                    lines.extend(docstring.lines().map(|line| {
+                        if line.is_empty() { String::new() } else { format!("{}{}", body_indent, line) }
+                    }));
+                }
+                for field in fields {
+                    let mut line = format!("{}{}: {}", body_indent, field.name, field.annotation);
+                    if let Some(default) = &field.default {
+                        line.push_str(&format!(" = {}", default));
+                    }
+                    lines.push(line);
+                }
+                if docstring.is_none() && fields.is_empty() {
+                    lines.push(format!("{}pass", body_indent));
+                }
+                lines.join("\n")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::core::BlockType;
+    use crate::database::{Block, Container};
+    use crate::generator::HierarchicalGenerator;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that NewTypes, type aliases and TypedDicts are extracted as type
+    /// declarations and regenerate as written
+    #[test]
+    fn test_python_type_declarations_round_trip() -> Result<()> {
+        let source = "from typing import NewType, NotRequired, TypedDict\n\nUserId = NewType('UserId', int)\n\ntype Vector = list[float]\n\ntype Pair[T] = tuple[T, T]\n\n\nclass Movie(TypedDict, total=False):\n    \"\"\"A movie, maybe\n    without a year.\"\"\"\n    title: Required[str]\n    year: int\n\n\nclass Point(NamedTuple):\n    x: int\n    y: int = 0\n";
+        let declarations = |source: &str| -> Result<Vec<PythonTypeDeclaration>> {
+            let mut blocks = UniversalParser::new()?.parse_file(source, "python", "types.py")?.blocks;
+            blocks.sort_by_key(|block| block.position.index);
+            assert!(blocks.iter().all(|block| block.block_type != BlockType::Class && block.block_type != BlockType::Variable));
+            Ok(blocks.iter()
+                .filter(|block| block.block_type == BlockType::TypeDef)
+                .filter_map(|block| PythonTypeDeclaration::from_abstract_syntax(&block.syntax_preservation.normalized_ast))
+                .collect())
+        };
+    
+        let extracted = declarations(source)?;
+        assert_eq!(extracted.iter().map(|declaration| declaration.name()).collect::<Vec<_>>(), vec!["UserId", "Vector", "Pair", "Movie", "Point"]);
+        assert_eq!(extracted[0], PythonTypeDeclaration::NewType {
+            name: "UserId".to_string(),
+            function: "NewType".to_string(),
+            name_argument: "'UserId'".to_string(),
+            base: "int".to_string(),
+        });
+        assert!(matches!(&extracted[2], PythonTypeDeclaration::Alias { type_parameters, annotation: None, .. } if type_parameters == &vec!["T".to_string()]));
+        let PythonTypeDeclaration::TypedDict { bases, fields, .. } = &extracted[3] else { panic!("Movie is a TypedDict: {:?}", extracted[3]) };
+        assert_eq!(bases, &vec!["TypedDict".to_string(), "total=False".to_string()]);
+        // `total=False` makes keys optional unless marked `Required`
+        assert_eq!(fields, &vec![
+            TypedField::typed_dict_key("title".to_string(), "Required[str]".to_string(), false),
+            TypedField::typed_dict_key("year".to_string(), "int".to_string(), false),
+        ]);
+        assert!(fields[0].required && !fields[1].required);
+    
+        let container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("types.py".to_string()),
+            ..Container::new("types", "file")
+        };
+        let parsed = UniversalParser::new()?.parse_file(source, "python", "types.py")?;
+        let stored = parsed.blocks.iter().map(|block| Block::from_semantic_block(block, container.id)).collect::<Result<Vec<_>>>()?;
+    
+        let generated = HierarchicalGenerator::from_blocks(&container, stored).generate()?;
+        for declaration in ["UserId = NewType('UserId', int)", "type Vector = list[float]", "type Pair[T] = tuple[T, T]"] {
+            assert!(generated.contains(declaration), "{} missing from\n{}", declaration, generated);
+        }
+        assert!(generated.contains("class Movie(TypedDict, total=False):\n    \"\"\"A movie, maybe\n    without a year.\"\"\"\n    title: Required[str]\n    year: int"), "{}", generated);
+        assert!(generated.contains("class Point(NamedTuple):\n    x: int\n    y: int = 0"), "{}", generated);
+        assert_eq!(declarations(&generated)?, extracted);
+        Ok(())
+    }
+}
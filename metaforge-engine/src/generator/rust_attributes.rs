@@ -0,0 +1,194 @@
+//! Rust outer attributes
+
+use serde::{Deserialize, Serialize};
+
+/// Key under `abstract_syntax` holding a list of serialized `RustAttribute`s
+pub const RUST_ATTRIBUTES_KEY: &str = "rust_attributes";
+
+/// Key under `abstract_syntax` holding the derived trait paths, in order
+pub const RUST_DERIVES_KEY: &str = "rust_derives";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RustAttribute {
+    /// Attribute path, e.g. `cfg`, `derive` or `tokio::test`
+    pub path: String,
+    /// Everything after the path as written, e.g. `(feature = "simd")` or ` = "text"`
+    pub input: Option<String>,
+}
+
+impl RustAttribute {
+    /// Parse the text of an outer attribute item, e.g. `#[cfg(test)]`
+    pub fn parse(text: &str) -> Option<Self> {
+        let inner = text.trim().strip_prefix("#[")?.strip_suffix(']')?.trim();
+        let path_end = inner.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+            .unwrap_or(inner.len());
+        let (path, input) = inner.split_at(path_end);
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            path: path.to_string(),
+            input: (!input.is_empty()).then(|| input.to_string()),
+        })
+    }
+
+    /// `#[derive(...)]` listing `traits`
+    pub fn derive(traits: &[String]) -> Self {
+        Self {
+            path: "derive".to_string(),
+            input: Some(format!("({})", traits.join(", "))),
+        }
+    }
+
+    /// Trait paths listed by a `derive(...)` attribute, as written
+    pub fn derives(&self) -> Vec<String> {
+        if self.path != "derive" {
+            return Vec::new();
+        }
+        self.input.as_deref()
+            .and_then(|input| input.trim().strip_prefix('(')?.strip_suffix(')'))
+            .map(|traits| traits.split(',')
+                .map(|name| name.split_whitespace().collect::<String>())
+                .filter(|name| !name.is_empty())
+                .collect())
+            .unwrap_or_default()
+    }
+
+    pub fn render(&self) -> String {
+        format!("#[{}{}]", self.path, self.input.as_deref().unwrap_or(""))
+    }
+
+    /// Read the attributes stored on a block's abstract syntax, outermost
+    /// first, with the derive attributes rebuilt from the stored derive list
+    /// when it has been changed: one `derive` where the first one was, or
+    /// ahead of the others when there was none
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Vec<Self> {
+        let attributes: Vec<Self> = abstract_syntax.get(RUST_ATTRIBUTES_KEY)
+            .and_then(|attributes| serde_json::from_value(attributes.clone()).ok())
+            .unwrap_or_default();
+        let Some(derives) = abstract_syntax.get(RUST_DERIVES_KEY)
+            .and_then(|derives| serde_json::from_value::<Vec<String>>(derives.clone()).ok())
+        else {
+            return attributes;
+        };
+        if derived_traits(&attributes) == derives {
+            return attributes;
+        }
+
+        let position = attributes.iter().position(|attribute| attribute.path == "derive").unwrap_or(0);
+        let mut rebuilt: Vec<Self> = attributes.into_iter().filter(|attribute| attribute.path != "derive").collect();
+        if !derives.is_empty() {
+            rebuilt.insert(position.min(rebuilt.len()), Self::derive(&derives));
+        }
+        rebuilt
+    }
+}
+
+/// Every trait derived by `attributes`, in order and without repeats
+pub fn derived_traits(attributes: &[RustAttribute]) -> Vec<String> {
+    let mut traits: Vec<String> = Vec::new();
+    for name in attributes.iter().flat_map(RustAttribute::derives) {
+        if !traits.contains(&name) {
+            traits.push(name);
+        }
+    }
+    traits
+}
+
+/// One attribute per line, each followed by a newline, ready to prefix an item
+pub fn render_attributes(attributes: &[RustAttribute], indent: &str) -> String {
+    attributes.iter()
+        .map(|attribute| format!("{}{}\n", indent, attribute.render()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::parser::universal::UniversalParser;
+
+    fn regenerate_rust_items(source: &str) -> Result<(String, Vec<Vec<RustAttribute>>)> {
+        let parse_result = UniversalParser::new()?.parse_file(source, "rust", "lib.rs")?;
+        let attributes: Vec<Vec<RustAttribute>> = parse_result.blocks.iter()
+            .map(|block| RustAttribute::from_abstract_syntax(&block.syntax_preservation.normalized_ast))
+            .collect();
+        let rendered: Vec<String> = parse_result.blocks.iter()
+            .zip(&attributes)
+            .map(|(block, attributes)| format!("{}{}", render_attributes(attributes, ""), block.syntax_preservation.original_text))
+            .collect();
+    
+        Ok((format!("{}\n", rendered.join("\n\n")), attributes))
+    }
+
+    /// Test round-trip of a function gated behind `#[cfg(test)]`
+    #[test]
+    fn test_rust_cfg_test_function_round_trip() -> Result<()> {
+        let source = r#"#[cfg(test)]
+fn fixture_path(name: &str) -> String {
+    format!("tests/fixtures/{}", name)
+}
+"#;
+    
+        let (regenerated, attributes) = regenerate_rust_items(source)?;
+        assert_eq!(regenerated, source);
+        assert_eq!(attributes[0][0], RustAttribute { path: "cfg".to_string(), input: Some("(test)".to_string()) });
+        Ok(())
+    }
+
+    /// Test round-trip of a feature-gated function alongside other attributes
+    #[test]
+    fn test_rust_feature_gated_function_round_trip() -> Result<()> {
+        let source = r#"#[cfg(feature = "simd")]
+#[inline]
+pub fn fast_sum(values: &[f32]) -> f32 {
+    values.iter().sum()
+}
+
+#[cfg(not(feature = "simd"))]
+pub fn fast_sum(values: &[f32]) -> f32 {
+    values.iter().fold(0.0, |total, value| total + value)
+}
+"#;
+    
+        let (regenerated, attributes) = regenerate_rust_items(source)?;
+        assert_eq!(regenerated, source);
+        let gates: Vec<&RustAttribute> = attributes.iter().map(|item| &item[0]).collect();
+        assert_eq!(gates, vec![
+            &RustAttribute { path: "cfg".to_string(), input: Some("(feature = \"simd\")".to_string()) },
+            &RustAttribute { path: "cfg".to_string(), input: Some("(not(feature = \"simd\"))".to_string()) },
+        ]);
+        assert_eq!(attributes[0][1], RustAttribute { path: "inline".to_string(), input: None });
+        Ok(())
+    }
+
+    /// Test round-trip of a struct's derive list next to a helper attribute
+    #[test]
+    fn test_rust_derive_and_serde_attributes_round_trip() -> Result<()> {
+        let source = r#"#[derive(Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+"#;
+    
+        let (regenerated, attributes) = regenerate_rust_items(source)?;
+        assert_eq!(regenerated, source);
+        assert_eq!(derived_traits(&attributes[0]), vec!["Debug", "Clone"]);
+        assert_eq!(attributes[0][1], RustAttribute { path: "serde".to_string(), input: Some("(rename_all = \"camelCase\")".to_string()) });
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "rust", "lib.rs")?;
+        let mut abstract_syntax = parse_result.blocks[0].syntax_preservation.normalized_ast.clone();
+        assert_eq!(abstract_syntax[RUST_DERIVES_KEY], serde_json::json!(["Debug", "Clone"]));
+    
+        // An edited derive list replaces the derive attribute in place
+        abstract_syntax[RUST_DERIVES_KEY] = serde_json::json!(["Debug", "Clone", "serde::Serialize"]);
+        assert_eq!(
+            render_attributes(&RustAttribute::from_abstract_syntax(&abstract_syntax), ""),
+            "#[derive(Debug, Clone, serde::Serialize)]\n#[serde(rename_all = \"camelCase\")]\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Rust enum variants
+
+use serde::{Deserialize, Serialize};
+use super::rust_attributes::RustAttribute;
+
+/// Key under `abstract_syntax` holding a serialized `RustEnum`
+pub const RUST_ENUM_KEY: &str = "rust_enum";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RustEnum {
+    pub variants: Vec<EnumVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: String,
+    /// Attributes such as `#[default]` or `#[serde(rename = "x")]`, outermost first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<RustAttribute>,
+    #[serde(default)]
+    pub fields: VariantFields,
+    /// Explicit discriminant expression, e.g. `5` or `1 << 3`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminant: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VariantFields {
+    /// `Variant`
+    #[default]
+    Unit,
+    /// `Variant(T, U)`
+    Tuple { types: Vec<String> },
+    /// `Variant { x: T }`
+    Struct { fields: Vec<VariantField> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantField {
+    pub name: String,
+    pub type_annotation: String,
+}
+
+impl RustEnum {
+    /// Read the variants stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(RUST_ENUM_KEY)?.clone()).ok()
+    }
+
+    /// One variant per line with a trailing comma, attributes on the lines
+    /// above, every line prefixed with `indent`
+    pub fn render_variants(&self, indent: &str) -> String {
+        self.variants.iter()
+            .flat_map(|variant| {
+                variant.attributes.iter()
+                    .map(|attribute| format!("{}{}", indent, attribute.render()))
+                    .chain(std::iter::once(format!("{}{},", indent, variant.render())))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl EnumVariant {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            attributes: Vec::new(),
+            fields: VariantFields::Unit,
+            discriminant: None,
+        }
+    }
+
+    /// The variant without attributes or trailing comma, e.g. `Move { x: i32 }`
+    pub fn render(&self) -> String {
+        let mut rendered = self.name.clone();
+        match &self.fields {
+            VariantFields::Unit => {}
+            VariantFields::Tuple { types } => rendered.push_str(&format!("({})", types.join(", "))),
+            VariantFields::Struct { fields } => {
+                let fields: Vec<String> = fields.iter()
+                    .map(|field| format!("{}: {}", field.name, field.type_annotation))
+                    .collect();
+                rendered.push_str(&format!(" {{ {} }}", fields.join(", ")));
+            }
+        }
+        if let Some(discriminant) = &self.discriminant {
+            rendered.push_str(&format!(" = {}", discriminant));
+        }
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::core::{BlockType, SemanticBlock};
+    use crate::database::Block;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that enum variants of every shape survive extraction and regeneration
+    #[test]
+    fn test_rust_enum_variants_round_trip() -> Result<()> {
+        let source = r#"enum Command {
+    #[default]
+    Quit,
+    Write(String, usize),
+    Move { x: i32, y: i32 },
+    Jump = 5,
+}
+"#;
+    
+        let extract = |source: &str| -> Result<(SemanticBlock, RustEnum)> {
+            let parse_result = UniversalParser::new()?.parse_file(source, "rust", "command.rs")?;
+            let block = parse_result.blocks.into_iter()
+                .find(|block| block.semantic_identity.canonical_name == "Command")
+                .expect("Command extracted");
+            let rust_enum = RustEnum::from_abstract_syntax(&block.syntax_preservation.normalized_ast)
+                .expect("variants stored");
+            Ok((block, rust_enum))
+        };
+    
+        let (block, rust_enum) = extract(source)?;
+        assert!(matches!(block.block_type, BlockType::Enum));
+        let mut quit = EnumVariant::new("Quit");
+        quit.attributes.push(RustAttribute { path: "default".to_string(), input: None });
+        let mut write = EnumVariant::new("Write");
+        write.fields = VariantFields::Tuple { types: vec!["String".to_string(), "usize".to_string()] };
+        let mut movement = EnumVariant::new("Move");
+        movement.fields = VariantFields::Struct {
+            fields: vec![
+                VariantField { name: "x".to_string(), type_annotation: "i32".to_string() },
+                VariantField { name: "y".to_string(), type_annotation: "i32".to_string() },
+            ],
+        };
+        let mut jump = EnumVariant::new("Jump");
+        jump.discriminant = Some("5".to_string());
+        assert_eq!(rust_enum.variants, vec![quit, write, movement, jump]);
+    
+        let stored = Block::new(Uuid::new_v4(), "Enum", "Command", serde_json::json!({ RUST_ENUM_KEY: rust_enum }));
+        let regenerated = TemplateEngine::new().render_block(&stored, "rust")?;
+        assert_eq!(format!("{}\n", regenerated.trim_end()), source);
+    
+        let (_, reparsed) = extract(&regenerated)?;
+        assert_eq!(reparsed, rust_enum);
+        Ok(())
+    }
+}
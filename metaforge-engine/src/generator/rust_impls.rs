@@ -0,0 +1,157 @@
+//! Rust impl blocks
+
+use serde::{Deserialize, Serialize};
+
+/// Key under `abstract_syntax` holding a serialized `RustImpl`
+pub const RUST_IMPL_KEY: &str = "rust_impl";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RustImpl {
+    /// Implemented trait as written, e.g. `fmt::Display` or `From<u8>`;
+    /// `None` for an inherent impl
+    #[serde(rename = "trait", default, skip_serializing_if = "Option::is_none")]
+    pub trait_name: Option<String>,
+    /// Type the impl is for, e.g. `Foo` or `Wrapper<T>`
+    pub for_type: String,
+    /// Type parameters as written, e.g. `<T: Clone>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generics: Option<String>,
+    /// Where clause predicates without the `where` keyword, e.g. `T: Clone, U: Default`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub where_clause: Option<String>,
+    /// Associated types and constants as written, e.g. `type Output = Self;`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub associated_items: Vec<String>,
+    #[serde(default)]
+    pub methods: Vec<ImplMethod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImplMethod {
+    pub name: String,
+    /// The method with its attributes and doc comments, dedented to column 0
+    pub source: String,
+}
+
+impl RustImpl {
+    /// Read the impl stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(RUST_IMPL_KEY)?.clone()).ok()
+    }
+
+    /// Block name for the impl, e.g. `impl Display for Foo` or `impl Foo`
+    pub fn name(&self) -> String {
+        format!("impl {}", self.target())
+    }
+
+    /// Everything before the opening brace, e.g.
+    /// `impl<T: Clone> From<T> for Wrapper<T>`
+    pub fn header(&self) -> String {
+        let generics = self.generics.as_deref().unwrap_or_default();
+        let mut header = format!("impl{} {}", generics, self.target());
+        if let Some(where_clause) = &self.where_clause {
+            header.push_str(&format!(" where {}", where_clause));
+        }
+        header
+    }
+
+    /// The whole impl block with associated items first and methods
+    /// separated by blank lines, every line prefixed with `indent`
+    pub fn render(&self, indent: &str) -> String {
+        let header = format!("{}{}", indent, self.header());
+        let member_indent = format!("{}    ", indent);
+        let associated_items = self.associated_items.iter()
+            .map(|item| indent_lines(item, &member_indent))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let members = std::iter::once(associated_items)
+            .filter(|items| !items.is_empty())
+            .chain(self.methods.iter().map(|method| indent_lines(&method.source, &member_indent)))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if members.is_empty() {
+            return format!("{} {{}}", header);
+        }
+        format!("{} {{\n{}\n{}}}", header, members, indent)
+    }
+
+    /// `Display for Foo`, or just `Foo` for an inherent impl
+    fn target(&self) -> String {
+        match &self.trait_name {
+            Some(trait_name) => format!("{} for {}", trait_name, self.for_type),
+            None => self.for_type.clone(),
+        }
+    }
+}
+
+fn indent_lines(source: &str, indent: &str) -> String {
+    source.lines()
+        .map(|line| if line.trim().is_empty() { String::new() } else { format!("{}{}", indent, line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::core::{BlockType, SemanticBlock};
+    use crate::database::Block;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that a trait impl keeps its trait, self type and both methods through regeneration
+    #[test]
+    fn test_rust_trait_impl_round_trip() -> Result<()> {
+        let source = r#"impl<T: Ord> Iterator for Countdown<T> where T: Copy {
+    type Item = T;
+
+    /// Yields the next value until the floor is reached
+    fn next(&mut self) -> Option<T> {
+        let value = self.values.pop()?;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.values.len(), Some(self.values.len()))
+    }
+}
+"#;
+    
+        let extract = |source: &str| -> Result<(SemanticBlock, RustImpl)> {
+            let parse_result = UniversalParser::new()?.parse_file(source, "rust", "countdown.rs")?;
+            let block = parse_result.blocks.into_iter()
+                .find(|block| matches!(block.block_type, BlockType::Class))
+                .expect("impl extracted");
+            let rust_impl = RustImpl::from_abstract_syntax(&block.syntax_preservation.normalized_ast)
+                .expect("impl stored");
+            Ok((block, rust_impl))
+        };
+    
+        let (block, rust_impl) = extract(source)?;
+        assert_eq!(block.semantic_identity.canonical_name, "impl Iterator for Countdown<T>");
+        assert_eq!(rust_impl.trait_name.as_deref(), Some("Iterator"));
+        assert_eq!(rust_impl.for_type, "Countdown<T>");
+        assert_eq!(rust_impl.generics.as_deref(), Some("<T: Ord>"));
+        assert_eq!(rust_impl.where_clause.as_deref(), Some("T: Copy"));
+        assert_eq!(rust_impl.associated_items, vec!["type Item = T;"]);
+        let names: Vec<&str> = rust_impl.methods.iter().map(|method| method.name.as_str()).collect();
+        assert_eq!(names, vec!["next", "size_hint"]);
+        assert!(rust_impl.methods[1].source.starts_with("#[inline]\nfn size_hint"));
+    
+        let stored = Block::new(Uuid::new_v4(), "Class", block.semantic_identity.canonical_name.clone(), serde_json::json!({ RUST_IMPL_KEY: rust_impl }));
+        let regenerated = TemplateEngine::new().render_block(&stored, "rust")?;
+        assert_eq!(format!("{}\n", regenerated.trim_end()), source);
+    
+        let (_, reparsed) = extract(&regenerated)?;
+        assert_eq!(reparsed, rust_impl);
+    
+        // An inherent impl has no trait
+        let (_, inherent) = extract("impl Countdown<u8> {\n    fn reset(&mut self) {}\n}\n")?;
+        assert_eq!(inherent.trait_name, None);
+        assert_eq!(inherent.name(), "impl Countdown<u8>");
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+//! Source maps of generated files
+
+use anyhow::Result;
+use code_builders::{LineMapping, OriginalRange, SourceMap};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::markers::SourceRange;
+use crate::database::Block;
+
+pub const SOURCE_MAP_EXTENSION: &str = "map.json";
+
+/// Where `block` sat in its original file, if extraction recorded it
+pub fn original_range(block: &Block) -> Option<OriginalRange> {
+    let position = block.position_metadata.as_ref()?;
+    let field = |name: &str| position.get(name)?.as_u64().map(|value| value as usize);
+    Some(OriginalRange {
+        start_line: field("start_line")?,
+        start_column: field("start_column").unwrap_or(0),
+        end_line: field("end_line")?,
+        end_column: field("end_column").unwrap_or(0),
+    })
+}
+
+/// Map the non-blank lines of the generated file `path` to blocks, given
+/// its `content` and the blocks' marker `ranges` within it, as
+/// `markers::parse_markers` or `markers::strip_markers` return them
+pub fn from_marker_ranges(
+    pipeline_id: Uuid,
+    path: &str,
+    content: &str,
+    ranges: &[(Uuid, SourceRange)],
+    blocks: &[Block],
+) -> SourceMap {
+    let originals: HashMap<Uuid, Option<OriginalRange>> = blocks.iter()
+        .map(|block| (block.id, original_range(block)))
+        .collect();
+
+    // Ranges come outermost first, so nested blocks claim their lines last
+    let mut lines = BTreeMap::new();
+    for (block_id, range) in ranges {
+        if range.byte_start == range.byte_end {
+            continue;
+        }
+        for line in range.start_line..=range.end_line {
+            lines.insert(line, *block_id);
+        }
+    }
+
+    let content: Vec<&str> = content.lines().collect();
+    let mappings = lines.into_iter()
+        .filter(|(line, _)| content.get(*line).is_some_and(|text| !text.trim().is_empty()))
+        .map(|(generated_line, block_id)| LineMapping {
+            generated_line,
+            block_id,
+            original: originals.get(&block_id).copied().flatten(),
+        })
+        .collect();
+    SourceMap { mappings, ..SourceMap::default() }
+        .with_file(path)
+        .with_pipeline_id(pipeline_id)
+}
+
+/// Write the map of the generated file `path` into `output_dir`, returning
+/// where it went
+pub fn write(output_dir: &Path, path: &str, source_map: &SourceMap) -> Result<PathBuf> {
+    let map_path = output_dir.join(format!("{}.{}", path, SOURCE_MAP_EXTENSION));
+    if let Some(parent) = map_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&map_path, serde_json::to_string_pretty(source_map)?)?;
+    Ok(map_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Container;
+    use crate::generator::{markers, HierarchicalGenerator};
+
+    #[test]
+    fn test_source_map_points_generated_lines_at_original_ranges() -> Result<()> {
+        let container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("billing/pricing.py".to_string()),
+            ..Container::new("pricing.py", "file")
+        };
+        let block = |name: &str, text: &str, position: i32, position_metadata: serde_json::Value| {
+            let mut block = Block::new(container.id, "Variable", name, serde_json::json!({"raw_text": text}));
+            block.position = position;
+            block.position_in_parent = position;
+            block.position_metadata = Some(position_metadata).filter(|metadata| !metadata.is_null());
+            block
+        };
+        let blocks = vec![
+            block("TAX_RATE", "TAX_RATE = 0.2", 0, serde_json::json!({"start_line": 4, "start_column": 0, "end_line": 4, "end_column": 14})),
+            // Stored before extraction recorded positions
+            block("CURRENCY", "CURRENCY = \"USD\"", 1, serde_json::Value::Null),
+        ];
+    
+        let generated = HierarchicalGenerator::from_blocks(&container, blocks.clone()).with_markers(true).generate()?;
+        let (content, ranges) = markers::strip_markers(&generated);
+        assert_eq!(content, "TAX_RATE = 0.2\n\nCURRENCY = \"USD\"");
+        let pipeline_id = Uuid::new_v4();
+        let map = from_marker_ranges(pipeline_id, "billing/pricing.py", &content, &ranges, &blocks);
+    
+        let lines: Vec<(usize, Uuid)> = map.mappings.iter().map(|mapping| (mapping.generated_line, mapping.block_id)).collect();
+        assert_eq!(lines, vec![(0, blocks[0].id), (2, blocks[1].id)]);
+        let original = map.lookup(0).and_then(|mapping| mapping.original).expect("position recorded");
+        assert_eq!((original.start_line, original.end_column), (4, 14));
+        assert!(map.lookup(2).unwrap().original.is_none());
+        assert!(map.lookup(1).is_none());
+        assert_eq!((map.file.as_deref(), map.pipeline_id), (Some("billing/pricing.py"), Some(pipeline_id)));
+    
+        // Written next to the generated file
+        let output_dir = std::env::temp_dir().join(format!("metaforge-sourcemap-{}", Uuid::new_v4()));
+        let path = write(&output_dir, "billing/pricing.py", &map)?;
+        assert_eq!(path, output_dir.join("billing/pricing.py.map.json"));
+        let written: SourceMap = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        assert_eq!(written, map);
+        std::fs::remove_dir_all(&output_dir)?;
+    
+        Ok(())
+    }
+}
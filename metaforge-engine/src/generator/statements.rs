@@ -0,0 +1,74 @@
+//! Function body statements
+
+use code_builders::ExpressionRenderer;
+use crate::core::{BodyStatement, FunctionBody, StatementKind, WithClause};
+use crate::parser::string_literals::map_lines_outside_strings;
+
+/// Render one statement, nested lines indented relative to its first line
+pub fn render_statement(statement: &BodyStatement, language: &str) -> String {
+    if let (Some(clause), false) = (&statement.with_clause, statement.body.is_empty()) {
+        return format!("{}\n{}", render_with_clause(clause), render_statements(&statement.body, language, "    "));
+    }
+    let expression = match (&statement.jsx, &statement.expression) {
+        // A multi-line element is wrapped in parentheses so `return` doesn't
+        // end the statement on its own line
+        (Some(jsx), _) => Some(match jsx.render("  ") {
+            rendered if rendered.contains('\n') => format!("(\n  {}\n)", rendered),
+            rendered => rendered,
+        }),
+        (None, Some(expression)) => Some(match language {
+            "python" => ExpressionRenderer::default().render(expression)
+                .unwrap_or_else(|_| expression.source_text.clone()),
+            _ => expression.source_text.clone(),
+        }),
+        (None, None) => None,
+    };
+    let terminator = if language == "python" { "" } else { ";" };
+
+    match (statement.kind, expression) {
+        // Only Rust blocks have a value; an arrow function's expression
+        // body needs the keyword once it sits inside braces
+        (StatementKind::Return, Some(expression)) if statement.implicit && language == "rust" => expression,
+        (StatementKind::Return, Some(expression)) => format!("return {}{}", expression, terminator),
+        (StatementKind::Return, None) => format!("return{}", terminator),
+        (StatementKind::Yield, Some(expression)) => format!("yield {}{}", expression, terminator),
+        _ => statement.code.clone(),
+    }
+}
+
+/// `with open(path) as fh, lock:` or its parenthesized form
+fn render_with_clause(clause: &WithClause) -> String {
+    let managers: Vec<String> = clause.managers.iter()
+        .map(|manager| {
+            let expression = ExpressionRenderer::default().render(&manager.expression)
+                .unwrap_or_else(|_| manager.expression.source_text.clone());
+            match &manager.target {
+                Some(target) => format!("{} as {}", expression, target),
+                None => expression,
+            }
+        })
+        .collect();
+    let managers = if clause.parenthesized {
+        format!("({})", managers.join(", "))
+    } else {
+        managers.join(", ")
+    };
+    format!("{}with {}:", if clause.is_async { "async " } else { "" }, managers)
+}
+
+/// Render every top-level statement of a body, each line prefixed with `indent`
+pub fn render_body(body: &FunctionBody, language: &str, indent: &str) -> String {
+    render_statements(&body.statements, language, indent)
+}
+
+pub(crate) fn render_statements(statements: &[BodyStatement], language: &str, indent: &str) -> String {
+    statements.iter()
+        .map(|statement| {
+            // Lines inside a multi-line string keep their exact content
+            map_lines_outside_strings(&render_statement(statement, language), language, |line| {
+                if line.trim().is_empty() { String::new() } else { format!("{}{}", indent, line) }
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,199 @@
+//! The summary a generate run prints, at a chosen level of detail
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Files listed under "Most errors" in a full summary
+const TOP_ERROR_FILES: usize = 5;
+
+/// How much detail `GenerationSummary::render` reports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryVerbosity {
+    /// One line, for logs and scripts
+    Terse,
+    /// Totals, validation errors and warnings, and the validation metrics
+    #[default]
+    Report,
+    /// The report plus per-file statistics and the files with most errors
+    Full,
+}
+
+impl SummaryVerbosity {
+    /// The verbosity for `--quiet` and a number of `-v` flags: quiet is
+    /// terse, none the report and `-v` or more the full breakdown
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Terse,
+            (false, 0) => Self::Report,
+            (false, _) => Self::Full,
+        }
+    }
+}
+
+/// What one generated file contributed to the run
+#[derive(Debug, Clone)]
+pub struct FileSummary {
+    pub path: String,
+    pub lines: usize,
+    pub blocks: usize,
+    /// Blocks rendered from their own data rather than as placeholders
+    pub covered: usize,
+    pub syntax_errors: usize,
+    /// `None` when the output was not validated
+    pub quality: Option<f64>,
+}
+
+/// Totals and validation results of a generate run
+#[derive(Debug, Clone)]
+pub struct GenerationSummary {
+    pub migration_id: Uuid,
+    pub output_dir: PathBuf,
+    pub files: Vec<FileSummary>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    /// `None` when no stored source was available to compare against
+    pub reconstruction_fidelity: Option<f64>,
+}
+
+impl GenerationSummary {
+    pub fn total_blocks(&self) -> usize {
+        self.files.iter().map(|file| file.blocks).sum()
+    }
+
+    pub fn syntax_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Share of blocks rendered from their own data, 1.0 for no blocks
+    pub fn semantic_coverage(&self) -> f64 {
+        let total = self.total_blocks();
+        if total == 0 {
+            return 1.0;
+        }
+        self.files.iter().map(|file| file.covered).sum::<usize>() as f64 / total as f64
+    }
+
+    /// The summary at the given level of detail, without a trailing newline
+    pub fn render(&self, verbosity: SummaryVerbosity) -> String {
+        if verbosity == SummaryVerbosity::Terse {
+            return format!(
+                "Migration {}: {} files, {} blocks, syntax {}, coverage {:.1}%, fidelity {}",
+                self.migration_id,
+                self.files.len(),
+                self.total_blocks(),
+                if self.syntax_valid() { "valid" } else { "invalid" },
+                self.semantic_coverage() * 100.0,
+                self.reconstruction_fidelity.map_or("n/a".to_string(), |fidelity| format!("{:.1}%", fidelity * 100.0)),
+            );
+        }
+
+        let mut lines = vec![
+            "📊 Summary:".to_string(),
+            format!("  Migration ID: {}", self.migration_id),
+            format!("  Files generated: {}", self.files.len()),
+            format!("  Total blocks: {}", self.total_blocks()),
+            format!("  Output directory: {}", self.output_dir.display()),
+        ];
+        if !self.errors.is_empty() {
+            lines.push(String::new());
+            lines.push("⚠️  Validation Errors:".to_string());
+            lines.extend(self.errors.iter().map(|error| format!("  - {}", error)));
+        }
+        if !self.warnings.is_empty() {
+            lines.push(String::new());
+            lines.push("⚠️  Validation Warnings:".to_string());
+            lines.extend(self.warnings.iter().map(|warning| format!("  - {}", warning)));
+        }
+        lines.push(String::new());
+        lines.push("📈 Validation Metrics:".to_string());
+        lines.push(format!("  Syntax valid: {}", self.syntax_valid()));
+        lines.push(format!("  Semantic coverage: {:.2}%", self.semantic_coverage() * 100.0));
+        lines.push(match self.reconstruction_fidelity {
+            Some(fidelity) => format!("  Reconstruction fidelity: {:.2}%", fidelity * 100.0),
+            None => "  Reconstruction fidelity: n/a (no stored source to compare)".to_string(),
+        });
+        if verbosity == SummaryVerbosity::Report {
+            return lines.join("\n");
+        }
+
+        if !self.files.is_empty() {
+            lines.push(String::new());
+            lines.push("📄 Per file:".to_string());
+        }
+        for file in &self.files {
+            let quality = file.quality.map_or("not validated".to_string(), |quality| format!("quality {:.2}", quality));
+            lines.push(format!(
+                "  {}: {} lines, {}/{} blocks, {} syntax errors, {}",
+                file.path, file.lines, file.covered, file.blocks, file.syntax_errors, quality
+            ));
+        }
+
+        let mut with_errors: Vec<&FileSummary> = self.files.iter().filter(|file| file.syntax_errors > 0).collect();
+        // Stable, so files with as many errors keep their generation order
+        with_errors.sort_by_key(|file| std::cmp::Reverse(file.syntax_errors));
+        if !with_errors.is_empty() {
+            lines.push(String::new());
+            lines.push("🔥 Most errors:".to_string());
+            for file in with_errors.into_iter().take(TOP_ERROR_FILES) {
+                lines.push(format!("  {}: {}", file.path, file.syntax_errors));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, blocks: usize, covered: usize, syntax_errors: usize) -> FileSummary {
+        FileSummary { path: path.to_string(), lines: 10, blocks, covered, syntax_errors, quality: Some(0.5) }
+    }
+
+    fn summary() -> GenerationSummary {
+        GenerationSummary {
+            migration_id: Uuid::nil(),
+            output_dir: PathBuf::from("generated"),
+            files: vec![file("app/main.py", 3, 3, 0), file("app/util.py", 1, 0, 1), file("lib.rs", 4, 4, 2)],
+            errors: vec![
+                "app/util.py: syntax error at 1:4".to_string(),
+                "lib.rs: syntax error at 2:0".to_string(),
+                "lib.rs: syntax error at 5:1".to_string(),
+            ],
+            warnings: vec!["app/util.py: 1 of 1 blocks rendered as placeholders".to_string()],
+            reconstruction_fidelity: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_verbosity_from_flags() {
+        assert_eq!(SummaryVerbosity::from_flags(true, 2), SummaryVerbosity::Terse);
+        assert_eq!(SummaryVerbosity::from_flags(false, 0), SummaryVerbosity::Report);
+        assert_eq!(SummaryVerbosity::from_flags(false, 1), SummaryVerbosity::Full);
+        assert_eq!(SummaryVerbosity::from_flags(false, 3), SummaryVerbosity::Full);
+    }
+
+    #[test]
+    fn test_summary_renders_each_verbosity() {
+        let summary = summary();
+
+        assert_eq!(
+            summary.render(SummaryVerbosity::Terse),
+            "Migration 00000000-0000-0000-0000-000000000000: 3 files, 8 blocks, syntax invalid, coverage 87.5%, fidelity n/a"
+        );
+
+        let report = summary.render(SummaryVerbosity::Report);
+        assert!(report.starts_with("📊 Summary:\n  Migration ID: 00000000-0000-0000-0000-000000000000\n  Files generated: 3\n  Total blocks: 8\n"));
+        assert!(report.contains("⚠️  Validation Errors:\n  - app/util.py: syntax error at 1:4\n"));
+        assert!(report.contains("⚠️  Validation Warnings:\n  - app/util.py: 1 of 1 blocks rendered as placeholders\n"));
+        assert!(report.ends_with("  Semantic coverage: 87.50%\n  Reconstruction fidelity: n/a (no stored source to compare)"));
+        assert!(!report.contains("Per file"));
+
+        let full = summary.render(SummaryVerbosity::Full);
+        assert!(full.starts_with(&report));
+        assert!(full.contains("📄 Per file:\n  app/main.py: 10 lines, 3/3 blocks, 0 syntax errors, quality 0.50\n"));
+        assert!(full.ends_with("🔥 Most errors:\n  lib.rs: 2\n  app/util.py: 1"));
+    }
+}
@@ -1,15 +1,37 @@
 use anyhow::{Result, anyhow};
+use ast_extractor::Language;
+use code_builders::{comment, DEFAULT_MAX_OUTPUT_BYTES};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
 // use crate::core::*;
+use crate::core::{EmptyFile, FilePreamble, FunctionBody, StatementKind};
 use crate::database::{Container, Block};
 use crate::generator::formatters::LanguageFormatters;
+use crate::generator::ordering;
+use crate::generator::source_map;
+use crate::generator::promise_style::{self, PromiseStyle};
+use crate::generator::statements;
+use crate::parser::string_literals::map_lines_outside_strings;
+use crate::generator::type_declarations::TypeDeclaration;
+use crate::generator::python_types::PythonTypeDeclaration;
+use crate::generator::rust_enums::RustEnum;
+use crate::generator::rust_impls::RustImpl;
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct TemplateEngine {
     templates: HashMap<String, LanguageTemplate>,
     formatters: LanguageFormatters,
+    /// Largest file `render_file` builds, in bytes
+    max_output_bytes: usize,
+}
+
+/// A placeholder in a template that its renderer never substitutes
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnfilledPlaceholder {
+    pub template: &'static str,
+    pub placeholder: String,
 }
 
 #[derive(Debug, Clone)]
@@ -239,7 +261,7 @@ impl TemplateEngine {
 
         // Java templates - Phase 1B New Language Support
         templates.insert("java".to_string(), LanguageTemplate {
-            function_template: "{{visibility}} {{modifiers}} {{return_type}} {{name}}{{generics}}({{params}}){{throws}} {\n{{body}}\n}".to_string(),
+            function_template: "{{visibility}} {{modifiers}} {{generics}} {{return_type}} {{name}}({{params}}){{throws}} {\n{{body}}\n}".to_string(),
             class_template: "{{visibility}} {{modifiers}} class {{name}}{{generics}}{{extends}}{{implements}} {\n{{body}}\n}".to_string(),
             variable_template: "{{visibility}} {{modifiers}} {{type}} {{name}} = {{value}};".to_string(),
             import_template: "import {{static_keyword}}{{path}};".to_string(),
@@ -247,7 +269,7 @@ impl TemplateEngine {
             file_header_template: "// Generated from semantic blocks\npackage {{package_name}};\n\n".to_string(),
             file_footer_template: "".to_string(),
             
-            method_template: "    {{visibility}} {{modifiers}} {{return_type}} {{name}}{{generics}}({{params}}){{throws}} {\n{{body}}\n    }".to_string(),
+            method_template: "    {{visibility}} {{modifiers}} {{generics}} {{return_type}} {{name}}({{params}}){{throws}} {\n{{body}}\n    }".to_string(),
             constructor_template: "    {{visibility}} {{name}}({{params}}){{throws}} {\n{{body}}\n    }".to_string(),
             interface_template: "{{visibility}} interface {{name}}{{generics}}{{extends}} {\n{{methods}}\n}".to_string(),
             enum_template: "{{visibility}} enum {{name}}{{implements}} {\n{{values}};\n{{body}}\n}".to_string(),
@@ -310,7 +332,7 @@ impl TemplateEngine {
             function_template: "{{template_decl}}{{return_type}} {{name}}({{params}}){{specifiers}} {\n{{body}}\n}".to_string(),
             class_template: "{{template_decl}}class {{name}}{{inheritance}} {\n{{access_specifiers}}\n{{body}}\n};".to_string(),
             variable_template: "{{modifiers}} {{type}} {{name}} = {{value}};".to_string(),
-            import_template: "#include {{angle_brackets}}{{path}}{{angle_brackets}}".to_string(),
+            import_template: "#include {{header}}".to_string(),
             comment_template: "// {{content}}".to_string(),
             file_header_template: "// Generated from semantic blocks\n#pragma once\n\n".to_string(),
             file_footer_template: "".to_string(),
@@ -399,7 +421,7 @@ impl TemplateEngine {
             switch_template: "switch ({{expression}}) {\n{{cases}}\n}".to_string(),
             loop_template: "while (true) {\n{{body}}\n}".to_string(),
             
-            generic_template: "{{template_syntax}}".to_string(),
+            generic_template: "// PHP doesn't have generics".to_string(),
             decorator_template: "#[{{name}}{{args}}]".to_string(),
             annotation_template: "#[{{name}}{{args}}]".to_string(),
             macro_template: "// PHP doesn't have macros - use functions or classes".to_string(),
@@ -410,15 +432,73 @@ impl TemplateEngine {
         Self { 
             templates,
             formatters: LanguageFormatters::new(),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+        }
+    }
+
+    /// Cap the size of rendered files, `DEFAULT_MAX_OUTPUT_BYTES` unless set
+    pub fn with_max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Languages with templates, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.templates.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+
+    /// Placeholders each language's templates contain but its renderers never fill.
+    ///
+    /// Every block template is rendered for a bare probe block through the same
+    /// `render_*` function that handles it in `render_block`, so the check
+    /// follows the renderers rather than a separate list. File headers and
+    /// footers may only use the placeholders `render_file` fills. Languages
+    /// without problems are omitted.
+    pub fn lint_templates(&self) -> BTreeMap<String, Vec<UnfilledPlaceholder>> {
+        let metadata = serde_json::Map::new();
+        let mut report = BTreeMap::new();
+        
+        for (language, template) in &self.templates {
+            let mut unfilled = BTreeSet::new();
+            
+            for (name, text) in [
+                ("file_header_template", &template.file_header_template),
+                ("file_footer_template", &template.file_footer_template),
+            ] {
+                for placeholder in placeholders(&fill_file_placeholders(text, "probe")) {
+                    unfilled.insert(UnfilledPlaceholder { template: name, placeholder });
+                }
+            }
+            
+            for (name, block_type) in BLOCK_TEMPLATES {
+                let probe = probe_block(block_type);
+                let rendered = match self.render_with(language, template, &probe, &metadata) {
+                    Ok(rendered) => rendered,
+                    Err(_) => continue,
+                };
+                for placeholder in placeholders(&rendered) {
+                    unfilled.insert(UnfilledPlaceholder { template: name, placeholder });
+                }
+            }
+            
+            if !unfilled.is_empty() {
+                report.insert(language.clone(), unfilled.into_iter().collect());
+            }
         }
+        
+        report
     }
 
+    /// Templates for `language` or any alias of it, e.g. `py` or `TypeScript`
     pub fn get_template(&self, language: &str) -> Result<&LanguageTemplate> {
-        self.templates.get(language)
+        self.templates.get(Language::canonical_name(language))
             .ok_or_else(|| anyhow!("No template found for language: {}", language))
     }
 
     pub fn render_block(&self, block: &Block, language: &str) -> Result<String> {
+        let language = Language::canonical_name(language);
         let template = self.get_template(language)?;
         let empty_map = serde_json::Map::new();
         let metadata = block.metadata.as_ref()
@@ -428,44 +508,201 @@ impl TemplateEngine {
         let block_type = block.block_type.as_str();
         let _semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
         
-        match block_type {
-            "Function" => self.render_function(template, block, metadata),
-            "Class" => self.render_class(template, block, metadata),
+        // Module-level statements go back exactly as written, placeholders
+        // such as `{{name}}` in their strings included
+        if block_type == "Statement" {
+            if let Some(text) = block.abstract_syntax.pointer("/implementation/original_text").and_then(|text| text.as_str()) {
+                return Ok(format!("{}\n", text));
+            }
+        }
+        
+        // TypeScript interfaces and type aliases carry a structured declaration
+        if matches!(language, "typescript" | "tsx") && matches!(block_type, "Interface" | "TypeDef") {
+            if let Some(declaration) = TypeDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+                return Ok(declaration.render_typescript(""));
+            }
+        }
+        
+        // Python `NewType`s, aliases, `TypedDict`s and `NamedTuple`s
+        if language == "python" && block_type == "TypeDef" {
+            if let Some(declaration) = PythonTypeDeclaration::from_abstract_syntax(&block.abstract_syntax) {
+                return Ok(declaration.render(""));
+            }
+        }
+        
+        // Rust impls carry their trait, self type and methods
+        if language == "rust" && block_type == "Class" {
+            if let Some(rust_impl) = RustImpl::from_abstract_syntax(&block.abstract_syntax) {
+                return Ok(rust_impl.render(""));
+            }
+        }
+        
+        self.render_with(language, template, block, metadata)
+    }
+
+    fn render_with(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+        let block_type = block.block_type.as_str();
+        let rendered = match block_type {
+            "Function" => self.render_function(language, template, block, metadata),
+            "Class" => self.render_class(language, template, block, metadata),
             "Variable" => self.render_variable(template, block, metadata),
             "Import" => self.render_import(template, block, metadata),
             "Comment" => self.render_comment(template, block, metadata),
             
             // Phase 1B: Enhanced block type support
-            "Method" => self.render_method(template, block, metadata),
-            "Constructor" => self.render_constructor(template, block, metadata),
-            "Interface" => self.render_interface(template, block, metadata),
-            "Enum" => self.render_enum(template, block, metadata),
-            "Struct" => self.render_struct(template, block, metadata),
-            "Trait" => self.render_trait(template, block, metadata),
-            "Module" => self.render_module(template, block, metadata),
-            "Namespace" => self.render_namespace(template, block, metadata),
+            "Method" => self.render_method(language, template, block, metadata),
+            "Constructor" => self.render_constructor(language, template, block, metadata),
+            "Interface" => self.render_interface(language, template, block, metadata),
+            "Enum" => self.render_enum(language, template, block, metadata),
+            "Struct" => self.render_struct(language, template, block, metadata),
+            "Trait" => self.render_trait(language, template, block, metadata),
+            "Module" => self.render_module(language, template, block, metadata),
+            "Namespace" => self.render_namespace(language, template, block, metadata),
             
             // Control flow blocks
-            "If" => self.render_if(template, block, metadata),
-            "For" => self.render_for(template, block, metadata),
-            "While" => self.render_while(template, block, metadata),
-            "TryCatch" => self.render_try_catch(template, block, metadata),
+            "If" => self.render_if(language, template, block, metadata),
+            "For" => self.render_for(language, template, block, metadata),
+            "While" => self.render_while(language, template, block, metadata),
+            "TryCatch" => self.render_try_catch(language, template, block, metadata),
             "Switch" => self.render_switch(template, block, metadata),
-            "Loop" => self.render_loop(template, block, metadata),
+            "Loop" => self.render_loop(language, template, block, metadata),
             
             // Advanced language features
             "Generic" => self.render_generic(template, block, metadata),
             "Decorator" => self.render_decorator(template, block, metadata),
             "Annotation" => self.render_annotation(template, block, metadata),
-            "Macro" => self.render_macro(template, block, metadata),
-            "Lambda" => self.render_lambda(template, block, metadata),
-            "Closure" => self.render_closure(template, block, metadata),
+            "Macro" => self.render_macro(language, template, block, metadata),
+            "Lambda" => self.render_lambda(language, template, block, metadata),
+            "Closure" => self.render_closure(language, template, block, metadata),
             
-            _ => Ok(format!("// Unknown block type: {}\n", block_type)),
+            _ => Ok(format!("{}\n", comment(language, &format!("Unknown block type: {}", block_type)))),
+        }?;
+        
+        self.fill_declaration_placeholders(rendered, language, block, metadata)
+    }
+
+    /// Fill the modifier, inheritance and naming placeholders that many
+    /// templates share but whose syntax differs by language
+    fn fill_declaration_placeholders(&self, mut rendered: String, language: &str, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+        let names: BTreeSet<String> = placeholders(&rendered).into_iter().collect();
+        
+        for name in names {
+            let token = format!("{{{{{}}}}}", name);
+            let value = match name.as_str() {
+                "name" => block.semantic_name.clone().unwrap_or_else(|| "unnamed".to_string()),
+                "visibility" => self.extract_visibility(block)?,
+                "generics" => self.extract_generics(block, language)?,
+                "where_clause" => self.extract_where_clause(block)?,
+                "async_keyword" => self.extract_async_keyword(block)?,
+                "export_keyword" => keyword_if_modified(block, "export"),
+                "abstract_keyword" => keyword_if_modified(block, "abstract"),
+                "static_keyword" => keyword_if_modified(block, "static"),
+                "mutability" => keyword_if_modified(block, "mut"),
+                "extends" => self.extract_extends(block)?,
+                "implements" => {
+                    let interfaces = string_list(block.abstract_syntax.pointer("/inheritance/implements"));
+                    if interfaces.is_empty() { String::new() } else { format!(" implements {}", interfaces.join(", ")) }
+                }
+                "throws" => {
+                    let exceptions = string_list(block.abstract_syntax.get("throws"));
+                    if exceptions.is_empty() { String::new() } else { format!(" throws {}", exceptions.join(", ")) }
+                }
+                "bases" => self.extract_base_types(block, metadata).join(", "),
+                "inheritance" => {
+                    let bases = self.extract_base_types(block, metadata);
+                    match (language, bases.first()) {
+                        (_, None) => String::new(),
+                        ("ruby", Some(base)) => format!(" < {}", base),
+                        ("cpp", _) => format!(" : {}", bases.iter().map(|b| format!("public {}", b)).collect::<Vec<_>>().join(", ")),
+                        _ => format!(" : {}", bases.join(", ")),
+                    }
+                }
+                "decorators" => {
+                    let indent = line_indent(&rendered, &token);
+                    self.extract_decorator_names(block, metadata).iter()
+                        .map(|decorator| format!("@{}\n{}", decorator, indent))
+                        .collect()
+                }
+                "docstring" => match block.abstract_syntax.get("docstring").and_then(|d| d.as_str()) {
+                    // One level deeper than the declaration line
+                    Some(doc) => format!("{}    \"\"\"{}\"\"\"\n", line_indent(&rendered, ""), doc),
+                    None => String::new(),
+                },
+                "template_decl" => {
+                    let params = block.language_features_typed().map(|f| f.generics).unwrap_or_default();
+                    if params.is_empty() {
+                        String::new()
+                    } else {
+                        format!("template<{}>\n", params.iter().map(|p| format!("typename {}", p)).collect::<Vec<_>>().join(", "))
+                    }
+                }
+                "specifiers" => block.modifiers.iter().flatten()
+                    .filter(|m| ["const", "override", "final", "noexcept"].contains(&m.as_str()))
+                    .map(|m| format!(" {}", m))
+                    .collect(),
+                "alias" => match block.abstract_syntax.get("alias").and_then(|a| a.as_str()) {
+                    None => String::new(),
+                    Some(alias) => match language {
+                        "go" => alias.to_string(),
+                        "csharp" => format!("{} = ", alias),
+                        _ => format!(" as {}", alias),
+                    },
+                },
+                "type_annotation" => self.extract_type_info(block)?,
+                // PHP property types precede the name and travel with the modifiers
+                "type_hint" if language == "php" => String::new(),
+                "type_hint" => self.extract_type_info(block)?,
+                "declaration_type" => block.modifiers.iter().flatten()
+                    .find(|m| ["const", "let", "var"].contains(&m.as_str()))
+                    .cloned()
+                    .unwrap_or_else(|| "const".to_string()),
+                "import_type" => block.abstract_syntax.get("import_type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("import")
+                    .to_string(),
+                "use_type" => "use".to_string(),
+                "imports" => {
+                    let imports = string_list(block.abstract_syntax.get("imports"));
+                    if !imports.is_empty() {
+                        format!("{{ {} }}", imports.join(", "))
+                    } else {
+                        let path = self.extract_import_path(block)?;
+                        let module = path.rsplit(['/', '.']).find(|segment| !segment.is_empty()).unwrap_or("module");
+                        format!("* as {}", block.semantic_name.as_deref().unwrap_or(module))
+                    }
+                }
+                "receiver" => block.abstract_syntax.get("receiver")
+                    .or_else(|| metadata.get("receiver"))
+                    .and_then(|r| r.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                "underlying_type" => {
+                    let underlying = block.abstract_syntax.get("underlying_type").and_then(|t| t.as_str());
+                    match (language, underlying) {
+                        ("go", underlying) => underlying.unwrap_or("int").to_string(),
+                        (_, Some(underlying)) => format!(" : {}", underlying),
+                        (_, None) => String::new(),
+                    }
+                }
+                "backing_type" => block.abstract_syntax.get("backing_type")
+                    .and_then(|t| t.as_str())
+                    .map(|t| format!(": {}", t))
+                    .unwrap_or_default(),
+                // Generated members keep the access they were extracted with
+                "access_specifiers" => format!("{}:", block.abstract_syntax.get("default_access")
+                    .and_then(|a| a.as_str())
+                    .unwrap_or("public")),
+                "enum_class" => "enum class".to_string(),
+                "enum_base" => "Enum".to_string(),
+                _ => continue,
+            };
+            rendered = rendered.replace(&token, &value);
         }
+        
+        Ok(rendered)
     }
 
-    fn render_function(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_function(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         // ✅ ENHANCED: First priority - use preserved implementation if available
         // ✅ ENHANCED: Check for preserved implementation data in abstract_syntax
         if let Some(implementation) = block.abstract_syntax.get("implementation") {
@@ -484,7 +721,7 @@ impl TemplateEngine {
                         rendered = rendered.replace("{{params}}", &params);
                         rendered = rendered.replace("{{return_type}}", &return_type);
                         rendered = rendered.replace("{{modifiers}}", &modifiers);
-                        rendered = rendered.replace("{{body}}", body_str); // Use preserved body directly
+                        rendered = rendered.replace("{{body}}", &reindent(body_str, "    ", language));
                         
                         return Ok(rendered);
                     }
@@ -507,7 +744,7 @@ impl TemplateEngine {
         let modifiers = self.extract_modifiers(block)?;
         
         // Extract body from semantic AST (no raw_text fallback)
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
 
         // Replace template variables
         rendered = rendered.replace("{{name}}", semantic_name);
@@ -519,7 +756,7 @@ impl TemplateEngine {
         Ok(rendered)
     }
 
-    fn render_class(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_class(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.class_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
@@ -540,17 +777,29 @@ impl TemplateEngine {
         rendered = rendered.replace("{{name}}", semantic_name);
         rendered = rendered.replace("{{bases}}", &bases);
         rendered = rendered.replace("{{fields}}", &fields);
+        rendered = rendered.replace("{{body}}", &fields);
         let extends_str = if bases.is_empty() { 
             String::new() 
         } else { 
             format!(" extends {}", bases) 
         };
         rendered = rendered.replace("{{extends}}", &extends_str);
-        rendered = rendered.replace("{{generics}}", &self.extract_generics(block)?);
-        rendered = rendered.replace("{{impl_blocks}}", &self.extract_impl_blocks(block)?);
-        rendered = rendered.replace("{{modifiers}}", &self.extract_modifiers(block)?);
-
-        Ok(rendered)
+        rendered = rendered.replace("{{generics}}", &self.extract_generics(block, language)?);
+        rendered = rendered.replace("{{where_clause}}", &self.extract_where_clause(block)?);
+        
+        // Templates with their own visibility slot shouldn't repeat it among the modifiers
+        if rendered.contains("{{visibility}}") {
+            rendered = rendered.replace("{{visibility}}", &self.extract_visibility(block)?);
+            rendered = rendered.replace("{{modifiers}}", &self.extract_non_visibility_modifiers(block));
+        } else {
+            rendered = rendered.replace("{{modifiers}}", &self.extract_modifiers(block)?);
+        }
+        rendered = rendered.replace("{{export_keyword}}", &keyword_if_modified(block, "export"));
+        rendered = rendered.replace("{{abstract_keyword}}", &keyword_if_modified(block, "abstract"));
+        
+        // {{inheritance}}, {{implements}}, {{decorators}} and {{docstring}} are
+        // language specific and filled by fill_declaration_placeholders
+        Ok(tidy_declaration_line(&rendered))
     }
 
     fn render_variable(&self, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
@@ -632,6 +881,9 @@ impl TemplateEngine {
 
         // Replace template variables
         rendered = rendered.replace("{{path}}", &path);
+        let system_header = block.abstract_syntax.get("system").and_then(|s| s.as_bool()).unwrap_or(false);
+        let header = if system_header { format!("<{}>", path) } else { format!("\"{}\"", path) };
+        rendered = rendered.replace("{{header}}", &header);
 
         Ok(rendered)
     }
@@ -648,25 +900,129 @@ impl TemplateEngine {
         Ok(rendered)
     }
 
-    pub fn render_file(&self, _container: &Container, blocks: &[Block], language: &str) -> Result<String> {
+    pub fn render_file(&self, container: &Container, blocks: &[Block], language: &str) -> Result<String> {
+        // No header or footer for a file that was empty to begin with
+        if let Some(empty_file) = EmptyFile::from_parsing_metadata(container.parsing_metadata.as_ref()) {
+            if blocks.is_empty() {
+                return Ok(empty_file.content);
+            }
+        }
+        let language = Language::canonical_name(language);
         let template = self.get_template(language)?;
-        let mut content = template.file_header_template.clone();
+        let module_name = container_module_name(container);
+        let header = fill_file_placeholders(&template.file_header_template, &module_name);
+        // The original file's shebang and pragmas take the place of the template's
+        let mut content = match FilePreamble::from_formatting_preferences(container.formatting_preferences.as_ref()) {
+            Some(preamble) => preamble.replace_header(&header, language),
+            None => header,
+        };
         
-        // Sort blocks by position
-        let mut sorted_blocks = blocks.to_vec();
-        sorted_blocks.sort_by_key(|b| b.position);
+        // Blocks whose parent is in the file render inside it; the rest are
+        // top level. Both sort into the deterministic generation order.
+        let ids: HashSet<Uuid> = blocks.iter().map(|block| block.id).collect();
+        let mut roots = Vec::new();
+        let mut children: HashMap<Uuid, Vec<Block>> = HashMap::new();
+        for block in blocks {
+            match block.parent_block_id.filter(|parent| ids.contains(parent)) {
+                Some(parent) => children.entry(parent).or_default().push(block.clone()),
+                None => roots.push(block.clone()),
+            }
+        }
+        ordering::sort_blocks(&mut roots);
+        for nested in children.values_mut() {
+            nested.sort_by(ordering::compare_children);
+        }
         
-        // Render each block
-        for block in sorted_blocks {
-            let rendered_block = self.render_block(&block, language)?;
+        // Render each block, refusing to grow the file past the cap
+        let path = container.original_path.as_deref().unwrap_or(&container.name);
+        for block in roots {
+            let rendered_block = self.render_nested(&block, &children, language)?;
+            self.check_output_size(path, content.len() + rendered_block.len() + 1)
+                .map_err(|e| anyhow!("{} (at block {})", e, block.id))?;
             content.push_str(&rendered_block);
             content.push('\n');
         }
         
-        content.push_str(&template.file_footer_template);
+        let footer = fill_file_placeholders(&template.file_footer_template, &module_name);
+        self.check_output_size(path, content.len() + footer.len())?;
+        content.push_str(&footer);
         
         // Phase 1B: Format the generated code
-        self.format_code(&content, language)
+        let formatted = self.format_code(&content, language)?;
+        self.check_output_size(path, formatted.len())?;
+        Ok(formatted)
+    }
+
+    /// Render `block` with the blocks nested in it one indent level deeper.
+    /// A nested definition that is a statement of the parent's body takes
+    /// that statement's place; one inside a compound statement is already
+    /// part of its text, as is any in a preserved body without statements;
+    /// any other goes at the end of the parent's body.
+    fn render_nested(&self, block: &Block, children: &HashMap<Uuid, Vec<Block>>, language: &str) -> Result<String> {
+        let Some(nested) = children.get(&block.id).filter(|nested| !nested.is_empty() && !renders_own_members(block, language)) else {
+            return self.render_block(block, language);
+        };
+        
+        let mut body = block.body_ast.as_ref().and_then(FunctionBody::from_value);
+        let preserved_body = block.abstract_syntax.pointer("/implementation/original_body").is_some();
+        let mut in_place = Vec::new();
+        let mut appended = Vec::new();
+        for child in nested {
+            let start_line = source_map::original_range(child).map(|range| range.start_line);
+            if body.is_none() && preserved_body {
+                continue;
+            }
+            if let (Some(body), Some(start_line)) = (body.as_mut(), start_line) {
+                let definition = body.statements.iter_mut()
+                    .find(|statement| statement.kind == StatementKind::Other && statement.line == start_line);
+                if let Some(statement) = definition {
+                    statement.code = nested_block_token(child.id);
+                    in_place.push(child);
+                    continue;
+                }
+                let covered = body.statements.iter().any(|statement| {
+                    (statement.line..statement.line + statement.code.lines().count()).contains(&start_line)
+                });
+                if covered {
+                    continue;
+                }
+            }
+            appended.push(child);
+        }
+        
+        let mut rendered = match body {
+            Some(body) if !in_place.is_empty() => {
+                // The preserved body text still holds the nested definitions
+                let mut block = block.clone();
+                block.body_ast = Some(body.to_value());
+                if let Some(implementation) = block.abstract_syntax.get_mut("implementation").and_then(Value::as_object_mut) {
+                    implementation.remove("original_body");
+                }
+                self.render_block(&block, language)?
+            }
+            _ => self.render_block(block, language)?,
+        };
+        for child in in_place {
+            let child_rendered = self.render_nested(&as_member_of(block, child, language), children, language)?;
+            rendered = replace_token_line(&rendered, &nested_block_token(child.id), &child_rendered, language);
+        }
+        if !appended.is_empty() {
+            let nested = appended.into_iter()
+                .map(|child| self.render_nested(&as_member_of(block, child, language), children, language))
+                .collect::<Result<Vec<_>>>()?;
+            rendered = append_nested(&rendered, &nested, language);
+        }
+        Ok(rendered)
+    }
+
+    fn check_output_size(&self, path: &str, bytes: usize) -> Result<()> {
+        if bytes > self.max_output_bytes {
+            return Err(anyhow!(
+                "Generated {} would be {} bytes, over the {}-byte output limit",
+                path, bytes, self.max_output_bytes
+            ));
+        }
+        Ok(())
     }
 
     /// Format generated code using appropriate language formatter
@@ -730,30 +1086,27 @@ impl TemplateEngine {
         Ok(String::new())
     }
     
-    fn extract_generics(&self, block: &Block) -> Result<String> {
+    fn extract_generics(&self, block: &Block, language: &str) -> Result<String> {
         // Extract generics from language_features or abstract_syntax
-        if let Some(features) = &block.language_features {
-            if let Some(generics) = features.get("generics") {
-                if let Some(generic_params) = generics.as_array() {
-                    let params: Vec<String> = generic_params.iter()
-                        .filter_map(|g| g.as_str().map(|s| s.to_string()))
-                        .collect();
-                    if !params.is_empty() {
-                        return Ok(format!("<{}>", params.join(", ")));
-                    }
-                }
+        if let Some(features) = block.language_features_typed() {
+            let params = features.generic_params();
+            if !params.is_empty() {
+                return Ok(format!("<{}>", params.join(", ")));
             }
         }
-        Ok(String::new())
+        // Languages without features keep bounds and defaults in the semantic
+        // generics, rendered in the target's syntax
+        Ok(block.generics_typed().map(|generics| generics.render(language)).unwrap_or_default())
     }
     
-    fn extract_impl_blocks(&self, _block: &Block) -> Result<String> {
-        // For now, return empty - impl blocks would be separate blocks in the hierarchical system
-        Ok(String::new())
-    }
-    
-    fn extract_function_body(&self, block: &Block) -> Result<String> {
+    fn extract_function_body(&self, block: &Block, language: &str) -> Result<String> {
         // Extract body from AST structure, not raw text
+        if let Some(body) = block.body_ast.as_ref().and_then(FunctionBody::from_value).filter(|body| !body.is_empty()) {
+            if let Some(chain) = then_chain_body(block, &body) {
+                return Ok(chain);
+            }
+            return Ok(statements::render_body(&body, language, "    "));
+        }
         if let Some(body_ast) = &block.body_ast {
             if let Some(statements) = body_ast.get("statements") {
                 if let Some(stmt_array) = statements.as_array() {
@@ -777,7 +1130,7 @@ impl TemplateEngine {
         }
         
         // Generate implementation from semantic structure
-        Ok("    // Implementation generated from semantic blocks".to_string())
+        Ok(format!("    {}", comment(language, "Implementation generated from semantic blocks")))
     }
     
     fn extract_variable_value(&self, block: &Block) -> Result<String> {
@@ -800,12 +1153,8 @@ impl TemplateEngine {
     
     fn extract_type_info(&self, block: &Block) -> Result<String> {
         // Extract from language_features type annotations
-        if let Some(features) = &block.language_features {
-            if let Some(type_info) = features.get("type_annotation") {
-                if let Some(type_str) = type_info.as_str() {
-                    return Ok(format!(": {}", type_str));
-                }
-            }
+        if let Some(type_str) = block.language_features_typed().and_then(|f| f.type_annotation) {
+            return Ok(format!(": {}", type_str));
         }
         
         // Extract from abstract_syntax
@@ -854,16 +1203,16 @@ impl TemplateEngine {
 
     // Phase 1B: Enhanced render methods for complete template coverage
     
-    fn render_method(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_method(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.method_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
         let params = self.extract_parameters(block, metadata)?;
         let return_type = self.extract_return_type(block, metadata)?;
         let modifiers = self.extract_modifiers(block)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         let visibility = self.extract_visibility(block)?;
-        let generics = self.extract_generics(block)?;
+        let generics = self.extract_generics(block, language)?;
         
         rendered = rendered.replace("{{name}}", semantic_name);
         rendered = rendered.replace("{{params}}", &params);
@@ -879,26 +1228,35 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_constructor(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_constructor(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.constructor_template.clone();
         
         let params = self.extract_parameters(block, metadata)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         let visibility = self.extract_visibility(block)?;
         
+        // Constructors are named after their class where the language requires it
+        let class_name = metadata.get("class_name")
+            .and_then(|n| n.as_str())
+            .or(block.semantic_name.as_deref())
+            .unwrap_or("unnamed");
+        
         rendered = rendered.replace("{{params}}", &params);
         rendered = rendered.replace("{{body}}", &body);
         rendered = rendered.replace("{{visibility}}", &visibility);
+        rendered = rendered.replace("{{name}}", class_name);
+        rendered = rendered.replace("{{initializer_list}}", &self.extract_feature_clause(block, "initializer_list", " : ")?);
+        rendered = rendered.replace("{{base_call}}", &self.extract_feature_clause(block, "base_call", ": ")?);
         
         Ok(rendered)
     }
     
-    fn render_interface(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_interface(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.interface_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
-        let methods = self.extract_interface_methods(block, metadata)?;
-        let generics = self.extract_generics(block)?;
+        let methods = self.extract_interface_methods(block, language)?;
+        let generics = self.extract_generics(block, language)?;
         let extends = self.extract_extends(block)?;
         let visibility = self.extract_visibility(block)?;
         
@@ -908,16 +1266,17 @@ impl TemplateEngine {
         rendered = rendered.replace("{{extends}}", &extends);
         rendered = rendered.replace("{{visibility}}", &visibility);
         rendered = rendered.replace("{{members}}", &methods);
+        rendered = rendered.replace("{{pure_virtual_methods}}", &methods);
         rendered = rendered.replace("{{where_clause}}", &self.extract_where_clause(block)?);
         
         Ok(rendered)
     }
     
-    fn render_enum(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_enum(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.enum_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
-        let values = self.extract_enum_values(block, metadata)?;
+        let values = self.extract_enum_values(block, language)?;
         let visibility = self.extract_visibility(block)?;
         let modifiers = self.extract_modifiers(block)?;
         
@@ -927,19 +1286,23 @@ impl TemplateEngine {
         rendered = rendered.replace("{{modifiers}}", &modifiers);
         rendered = rendered.replace("{{where_clause}}", &self.extract_where_clause(block)?);
         rendered = rendered.replace("{{variants}}", &values);
-        rendered = rendered.replace("{{generics}}", &self.extract_generics(block)?);
+        rendered = rendered.replace("{{cases}}", &values);
+        rendered = rendered.replace("{{constants}}", &values);
+        rendered = rendered.replace("{{generics}}", &self.extract_generics(block, language)?);
+        // Members beyond the values are separate child blocks
+        rendered = rendered.replace("{{body}}", "");
         
         Ok(rendered)
     }
     
-    fn render_struct(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_struct(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.struct_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
-        let fields = self.extract_struct_fields(block, metadata)?;
+        let fields = self.extract_struct_fields(block, language)?;
         let visibility = self.extract_visibility(block)?;
         let modifiers = self.extract_modifiers(block)?;
-        let generics = self.extract_generics(block)?;
+        let generics = self.extract_generics(block, language)?;
         
         rendered = rendered.replace("{{name}}", semantic_name);
         rendered = rendered.replace("{{fields}}", &fields);
@@ -948,17 +1311,30 @@ impl TemplateEngine {
         rendered = rendered.replace("{{generics}}", &generics);
         rendered = rendered.replace("{{where_clause}}", &self.extract_where_clause(block)?);
         rendered = rendered.replace("{{members}}", &fields);
+        rendered = rendered.replace("{{properties}}", &fields);
+        
+        // Struct-as-class templates take the field names as constructor parameters
+        let field_names = self.extract_struct_field_names(block);
+        rendered = rendered.replace("{{params}}", &field_names.join(", "));
+        rendered = rendered.replace("{{assignments}}", &field_names.iter()
+            .map(|name| format!("    this.{} = {};", name, name))
+            .collect::<Vec<_>>()
+            .join("\n"));
+        
+        // Constructors and methods are separate child blocks
+        rendered = rendered.replace("{{constructor}}", "");
+        rendered = rendered.replace("{{methods}}", "");
         
         Ok(rendered)
     }
     
-    fn render_trait(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_trait(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.trait_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
-        let methods = self.extract_interface_methods(block, metadata)?;
+        let methods = self.extract_interface_methods(block, language)?;
         let visibility = self.extract_visibility(block)?;
-        let generics = self.extract_generics(block)?;
+        let generics = self.extract_generics(block, language)?;
         
         rendered = rendered.replace("{{name}}", semantic_name);
         rendered = rendered.replace("{{methods}}", &methods);
@@ -967,15 +1343,17 @@ impl TemplateEngine {
         rendered = rendered.replace("{{where_clause}}", &self.extract_where_clause(block)?);
         rendered = rendered.replace("{{bounds}}", &self.extract_trait_bounds(block)?);
         rendered = rendered.replace("{{associated_types}}", &self.extract_associated_types(block)?);
+        rendered = rendered.replace("{{abstract_methods}}", &methods);
+        rendered = rendered.replace("{{constraints}}", &self.extract_concept_constraints(block));
         
         Ok(rendered)
     }
     
-    fn render_module(&self, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_module(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.module_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
-        let content = self.extract_module_content(block)?;
+        let content = self.extract_module_content(block, language)?;
         let visibility = self.extract_visibility(block)?;
         
         rendered = rendered.replace("{{name}}", semantic_name);
@@ -985,11 +1363,11 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_namespace(&self, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_namespace(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.namespace_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("unnamed");
-        let content = self.extract_module_content(block)?;
+        let content = self.extract_module_content(block, language)?;
         
         rendered = rendered.replace("{{name}}", semantic_name);
         rendered = rendered.replace("{{content}}", &content);
@@ -999,11 +1377,11 @@ impl TemplateEngine {
     
     // Control flow render methods
     
-    fn render_if(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_if(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.if_template.clone();
         
         let condition = self.extract_condition(block, metadata)?;
-        let then_body = self.extract_then_body(block)?;
+        let then_body = self.extract_then_body(block, language)?;
         let else_clause = self.extract_else_clause(block)?;
         
         rendered = rendered.replace("{{condition}}", &condition);
@@ -1013,13 +1391,13 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_for(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_for(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.for_template.clone();
         
         let initialization = self.extract_for_initialization(block, metadata)?;
         let condition = self.extract_condition(block, metadata)?;
         let increment = self.extract_for_increment(block, metadata)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         
         rendered = rendered.replace("{{initialization}}", &initialization);
         rendered = rendered.replace("{{condition}}", &condition);
@@ -1034,11 +1412,11 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_while(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_while(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.while_template.clone();
         
         let condition = self.extract_condition(block, metadata)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         
         rendered = rendered.replace("{{condition}}", &condition);
         rendered = rendered.replace("{{body}}", &body);
@@ -1046,11 +1424,11 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_try_catch(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_try_catch(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.try_catch_template.clone();
         
-        let try_body = self.extract_try_body(block)?;
-        let catch_body = self.extract_catch_body(block)?;
+        let try_body = self.extract_try_body(block, language)?;
+        let catch_body = self.extract_catch_body(block, language)?;
         let exception_var = self.extract_exception_var(block, metadata)?;
         let exception_types = self.extract_exception_types(block, metadata)?;
         
@@ -1064,9 +1442,13 @@ impl TemplateEngine {
         rendered = rendered.replace("{{error_type}}", &exception_types);
         rendered = rendered.replace("{{finally_clause}}", &self.extract_finally_clause(block)?);
         rendered = rendered.replace("{{ensure_clause}}", &self.extract_finally_clause(block)?);
-        rendered = rendered.replace("{{catch_blocks}}", &self.extract_catch_blocks(block)?);
+        rendered = rendered.replace("{{catch_blocks}}", &self.extract_catch_blocks(block, language)?);
         rendered = rendered.replace("{{expression}}", &self.extract_try_expression(block)?);
         rendered = rendered.replace("{{error_handling}}", &catch_body);
+        rendered = rendered.replace("{{ok_body}}", &try_body);
+        rendered = rendered.replace("{{err_body}}", &catch_body);
+        rendered = rendered.replace("{{ok_pattern}}", block.abstract_syntax.get("ok_pattern").and_then(|p| p.as_str()).unwrap_or("_"));
+        rendered = rendered.replace("{{err_pattern}}", &exception_var);
         
         Ok(rendered)
     }
@@ -1085,10 +1467,10 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_loop(&self, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_loop(&self, language: &str, template: &LanguageTemplate, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.loop_template.clone();
         
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         
         rendered = rendered.replace("{{body}}", &body);
         
@@ -1135,12 +1517,12 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_macro(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_macro(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.macro_template.clone();
         
         let semantic_name = block.semantic_name.as_deref().unwrap_or("macro");
         let params = self.extract_parameters(block, metadata)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         
         rendered = rendered.replace("{{name}}", semantic_name);
         rendered = rendered.replace("{{params}}", &params);
@@ -1151,30 +1533,33 @@ impl TemplateEngine {
         Ok(rendered)
     }
     
-    fn render_lambda(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_lambda(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.lambda_template.clone();
         
         let params = self.extract_parameters(block, metadata)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         let return_type = self.extract_return_type(block, metadata)?;
         
         rendered = rendered.replace("{{params}}", &params);
         rendered = rendered.replace("{{body}}", &body);
         rendered = rendered.replace("{{return_type}}", &return_type);
+        rendered = rendered.replace("{{capture}}", &self.extract_closure_capture(block)?);
         
         Ok(rendered)
     }
     
-    fn render_closure(&self, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn render_closure(&self, language: &str, template: &LanguageTemplate, block: &Block, metadata: &serde_json::Map<String, Value>) -> Result<String> {
         let mut rendered = template.closure_template.clone();
         
         let outer_params = self.extract_outer_params(block, metadata)?;
         let inner_params = self.extract_inner_params(block, metadata)?;
-        let body = self.extract_function_body(block)?;
+        let body = self.extract_function_body(block, language)?;
         let return_type = self.extract_return_type(block, metadata)?;
         
         rendered = rendered.replace("{{outer_params}}", &outer_params);
         rendered = rendered.replace("{{inner_params}}", &inner_params);
+        rendered = rendered.replace("{{params}}", &self.extract_parameters(block, metadata)?);
+        rendered = rendered.replace("{{name}}", block.semantic_name.as_deref().unwrap_or("outer"));
         rendered = rendered.replace("{{body}}", &body);
         rendered = rendered.replace("{{return_type}}", &return_type);
         rendered = rendered.replace("{{capture}}", &self.extract_closure_capture(block)?);
@@ -1190,7 +1575,7 @@ impl TemplateEngine {
     fn extract_visibility(&self, block: &Block) -> Result<String> {
         if let Some(modifiers) = &block.modifiers {
             for modifier in modifiers {
-                if ["public", "private", "protected", "internal", "pub", "priv"].contains(&modifier.as_str()) {
+                if VISIBILITY_MODIFIERS.contains(&modifier.as_str()) {
                     return Ok(format!("{} ", modifier));
                 }
             }
@@ -1216,26 +1601,26 @@ impl TemplateEngine {
     }
     
     fn extract_where_clause(&self, block: &Block) -> Result<String> {
-        if let Some(features) = &block.language_features {
-            if let Some(where_clause) = features.get("where_clause") {
-                if let Some(clause_str) = where_clause.as_str() {
-                    return Ok(format!(" where {}", clause_str));
-                }
-            }
+        if let Some(clause_str) = block.language_features_typed().and_then(|f| f.where_clause) {
+            return Ok(format!(" where {}", clause_str));
         }
         Ok(String::new())
     }
     
     fn extract_async_keyword(&self, block: &Block) -> Result<String> {
         if let Some(modifiers) = &block.modifiers {
-            if modifiers.contains(&"async".to_string()) {
+            // A body rewritten as a `.then()` chain has no `await` left
+            let then_chain = || block.body_ast.as_ref()
+                .and_then(FunctionBody::from_value)
+                .and_then(|body| then_chain_body(block, &body));
+            if modifiers.contains(&"async".to_string()) && then_chain().is_none() {
                 return Ok("async ".to_string());
             }
         }
         Ok(String::new())
     }
     
-    fn extract_interface_methods(&self, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn extract_interface_methods(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(body_ast) = &block.body_ast {
             if let Some(methods) = body_ast.get("methods") {
                 if let Some(method_array) = methods.as_array() {
@@ -1246,10 +1631,13 @@ impl TemplateEngine {
                 }
             }
         }
-        Ok("    // TODO: Define interface methods".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Define interface methods")))
     }
     
-    fn extract_enum_values(&self, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn extract_enum_values(&self, block: &Block, language: &str) -> Result<String> {
+        if let Some(rust_enum) = RustEnum::from_abstract_syntax(&block.abstract_syntax) {
+            return Ok(rust_enum.render_variants("    "));
+        }
         if let Some(body_ast) = &block.body_ast {
             if let Some(values) = body_ast.get("values") {
                 if let Some(value_array) = values.as_array() {
@@ -1260,10 +1648,10 @@ impl TemplateEngine {
                 }
             }
         }
-        Ok("    // TODO: Define enum values".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Define enum values")))
     }
     
-    fn extract_struct_fields(&self, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
+    fn extract_struct_fields(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(body_ast) = &block.body_ast {
             if let Some(fields) = body_ast.get("fields") {
                 if let Some(field_array) = fields.as_array() {
@@ -1282,7 +1670,7 @@ impl TemplateEngine {
                 }
             }
         }
-        Ok("    // TODO: Define struct fields".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Define struct fields")))
     }
     
     fn extract_extends(&self, block: &Block) -> Result<String> {
@@ -1296,6 +1684,64 @@ impl TemplateEngine {
         Ok(String::new())
     }
     
+    fn extract_non_visibility_modifiers(&self, block: &Block) -> String {
+        block.modifiers.iter().flatten()
+            .filter(|m| !VISIBILITY_MODIFIERS.contains(&m.as_str()))
+            .map(|m| format!("{} ", m))
+            .collect()
+    }
+    
+    /// Base classes from the metadata inheritance chain or `abstract_syntax.bases`
+    fn extract_base_types(&self, block: &Block, metadata: &serde_json::Map<String, Value>) -> Vec<String> {
+        let chain = string_list(metadata.get("inheritance_chain"));
+        if !chain.is_empty() {
+            return chain;
+        }
+        string_list(block.abstract_syntax.get("bases"))
+    }
+    
+    /// Decorator expressions without the `@`; metadata stores plain strings
+    /// or `{name, arguments}` objects
+    fn extract_decorator_names(&self, block: &Block, metadata: &serde_json::Map<String, Value>) -> Vec<String> {
+        let decorators = metadata.get("decorators").or(block.decorators.as_ref());
+        decorators.and_then(|d| d.as_array()).into_iter().flatten()
+            .filter_map(|decorator| match decorator {
+                Value::String(name) => Some(name.clone()),
+                Value::Object(obj) => {
+                    let name = obj.get("name")?.as_str()?;
+                    let arguments = string_list(obj.get("arguments"));
+                    Some(if arguments.is_empty() { name.to_string() } else { format!("{}({})", name, arguments.join(", ")) })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+    
+    /// A clause stored under `abstract_syntax[key]`, prefixed when present
+    fn extract_feature_clause(&self, block: &Block, key: &str, prefix: &str) -> Result<String> {
+        Ok(block.abstract_syntax.get(key)
+            .and_then(|c| c.as_str())
+            .map(|clause| format!("{}{}", prefix, clause))
+            .unwrap_or_default())
+    }
+    
+    fn extract_struct_field_names(&self, block: &Block) -> Vec<String> {
+        block.body_ast.as_ref()
+            .and_then(|body| body.get("fields"))
+            .and_then(|fields| fields.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|field| field.get("name").and_then(|n| n.as_str()).map(String::from))
+            .collect()
+    }
+    
+    fn extract_concept_constraints(&self, block: &Block) -> String {
+        block.abstract_syntax.get("constraints")
+            .and_then(|c| c.as_str())
+            .unwrap_or("true")
+            .to_string()
+    }
+    
     fn extract_trait_bounds(&self, _block: &Block) -> Result<String> {
         // Extract trait bounds for generic constraints
         Ok(String::new())
@@ -1306,13 +1752,13 @@ impl TemplateEngine {
         Ok(String::new())
     }
     
-    fn extract_module_content(&self, block: &Block) -> Result<String> {
+    fn extract_module_content(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(content) = block.abstract_syntax.get("content") {
             if let Some(content_str) = content.as_str() {
                 return Ok(content_str.to_string());
             }
         }
-        Ok("    // TODO: Define module content".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Define module content")))
     }
     
     // Control flow helper methods
@@ -1326,13 +1772,13 @@ impl TemplateEngine {
         Ok("true".to_string())
     }
     
-    fn extract_then_body(&self, block: &Block) -> Result<String> {
+    fn extract_then_body(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(then_body) = block.abstract_syntax.get("then_body") {
             if let Some(body_str) = then_body.as_str() {
                 return Ok(format!("    {}", body_str));
             }
         }
-        Ok("    // TODO: Implement then branch".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Implement then branch")))
     }
     
     fn extract_else_clause(&self, block: &Block) -> Result<String> {
@@ -1407,22 +1853,22 @@ impl TemplateEngine {
         Ok("item".to_string())
     }
     
-    fn extract_try_body(&self, block: &Block) -> Result<String> {
+    fn extract_try_body(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(try_body) = block.abstract_syntax.get("try_body") {
             if let Some(body_str) = try_body.as_str() {
                 return Ok(format!("    {}", body_str));
             }
         }
-        Ok("    // TODO: Implement try block".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Implement try block")))
     }
     
-    fn extract_catch_body(&self, block: &Block) -> Result<String> {
+    fn extract_catch_body(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(catch_body) = block.abstract_syntax.get("catch_body") {
             if let Some(body_str) = catch_body.as_str() {
                 return Ok(format!("    {}", body_str));
             }
         }
-        Ok("    // TODO: Handle exception".to_string())
+        Ok(format!("    {}", comment(language, "TODO: Handle exception")))
     }
     
     fn extract_exception_var(&self, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
@@ -1452,13 +1898,13 @@ impl TemplateEngine {
         Ok(String::new())
     }
     
-    fn extract_catch_blocks(&self, block: &Block) -> Result<String> {
+    fn extract_catch_blocks(&self, block: &Block, language: &str) -> Result<String> {
         if let Some(catch_blocks) = block.abstract_syntax.get("catch_blocks") {
             if let Some(blocks_str) = catch_blocks.as_str() {
                 return Ok(blocks_str.to_string());
             }
         }
-        Ok("catch (Exception e) {\n    // Handle exception\n}".to_string())
+        Ok(format!("catch (Exception e) {{\n    {}\n}}", comment(language, "Handle exception")))
     }
     
     fn extract_try_expression(&self, block: &Block) -> Result<String> {
@@ -1492,15 +1938,8 @@ impl TemplateEngine {
     }
     
     fn extract_generic_type_params(&self, block: &Block, _metadata: &serde_json::Map<String, Value>) -> Result<String> {
-        if let Some(features) = &block.language_features {
-            if let Some(generics) = features.get("generics") {
-                if let Some(generic_params) = generics.as_array() {
-                    let params: Vec<String> = generic_params.iter()
-                        .filter_map(|g| g.as_str().map(|s| s.to_string()))
-                        .collect();
-                    return Ok(params.join(", "));
-                }
-            }
+        if let Some(features) = block.language_features_typed() {
+            return Ok(features.generics.join(", "));
         }
         Ok("T".to_string())
     }
@@ -1568,3 +2007,371 @@ impl TemplateEngine {
         Ok("$var".to_string())
     }
 }
+
+const VISIBILITY_MODIFIERS: [&str; 6] = ["public", "private", "protected", "internal", "pub", "priv"];
+
+/// Block templates and the block type `render_block` routes to each
+const BLOCK_TEMPLATES: [(&str, &str); 25] = [
+    ("function_template", "Function"),
+    ("class_template", "Class"),
+    ("variable_template", "Variable"),
+    ("import_template", "Import"),
+    ("comment_template", "Comment"),
+    ("method_template", "Method"),
+    ("constructor_template", "Constructor"),
+    ("interface_template", "Interface"),
+    ("enum_template", "Enum"),
+    ("struct_template", "Struct"),
+    ("trait_template", "Trait"),
+    ("module_template", "Module"),
+    ("namespace_template", "Namespace"),
+    ("if_template", "If"),
+    ("for_template", "For"),
+    ("while_template", "While"),
+    ("try_catch_template", "TryCatch"),
+    ("switch_template", "Switch"),
+    ("loop_template", "Loop"),
+    ("generic_template", "Generic"),
+    ("decorator_template", "Decorator"),
+    ("annotation_template", "Annotation"),
+    ("macro_template", "Macro"),
+    ("lambda_template", "Lambda"),
+    ("closure_template", "Closure"),
+];
+
+/// `{{name}}` placeholders remaining in a template or rendered text
+fn placeholders(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let name_len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        if name_len > 0 && rest[name_len..].starts_with("}}") {
+            found.push(rest[..name_len].to_string());
+            rest = &rest[name_len + 2..];
+        }
+    }
+    found
+}
+
+/// A block with only its type and name set, for exercising renderers
+fn probe_block(block_type: &str) -> Block {
+    Block {
+        id: uuid::Uuid::nil(),
+        ..Block::new(uuid::Uuid::nil(), block_type, "probe", serde_json::json!({}))
+    }
+}
+
+/// `keyword ` when the block carries that modifier
+fn keyword_if_modified(block: &Block, keyword: &str) -> String {
+    match &block.modifiers {
+        Some(modifiers) if modifiers.iter().any(|m| m == keyword) => format!("{} ", keyword),
+        _ => String::new(),
+    }
+}
+
+/// The body of an async JavaScript or TypeScript function as a `.then()`
+/// chain, when its hints ask for that style and the body allows it
+fn then_chain_body(block: &Block, body: &FunctionBody) -> Option<String> {
+    let language = Language::canonical_name(block.source_language.as_deref()?);
+    let is_async = block.modifiers.as_ref().is_some_and(|modifiers| modifiers.iter().any(|m| m == "async"));
+    if !matches!(language, "javascript" | "typescript" | "tsx") || !is_async
+        || PromiseStyle::for_block(block) != PromiseStyle::Then {
+        return None;
+    }
+    promise_style::render_then_chain(body, language, "    ")
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value.and_then(|v| v.as_array()).into_iter().flatten()
+        .filter_map(|item| item.as_str().map(String::from))
+        .collect()
+}
+
+/// Leading whitespace of the line containing `token`
+fn line_indent(text: &str, token: &str) -> String {
+    let at = text.find(token).unwrap_or(0);
+    let line_start = text[..at].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    text[line_start..].chars().take_while(|c| *c == ' ' || *c == '\t').collect()
+}
+
+/// Fill the package or namespace placeholders in file headers and footers
+fn fill_file_placeholders(text: &str, module_name: &str) -> String {
+    text.replace("{{package_name}}", module_name)
+        .replace("{{namespace}}", module_name)
+}
+
+/// Package name for a container: declared in parsing metadata, else its directory
+fn container_module_name(container: &Container) -> String {
+    let declared = container.parsing_metadata.as_ref()
+        .and_then(|m| m.get("package").or_else(|| m.get("namespace")))
+        .and_then(|p| p.as_str());
+    if let Some(declared) = declared {
+        return declared.to_string();
+    }
+    container.original_path.as_deref()
+        .and_then(|path| std::path::Path::new(path).parent()?.file_name()?.to_str().map(String::from))
+        .unwrap_or_else(|| "main".to_string())
+}
+
+/// Line standing in for a nested definition until it is rendered in place
+fn nested_block_token(id: Uuid) -> String {
+    format!("@metaforge:nested {}", id)
+}
+
+/// Rust impls are written out from their own method list, which already
+/// holds the blocks nested in them
+fn renders_own_members(block: &Block, language: &str) -> bool {
+    language == "rust" && block.block_type == "Class" && RustImpl::from_abstract_syntax(&block.abstract_syntax).is_some()
+}
+
+/// `child` as its parent declares it: functions inside a class are methods,
+/// which only Python spells the same way
+fn as_member_of(parent: &Block, child: &Block, language: &str) -> Block {
+    let mut child = child.clone();
+    if parent.block_type == "Class" && child.block_type == "Function" && language != "python" {
+        child.block_type = "Method".to_string();
+    }
+    child
+}
+
+/// One level of indentation in `language`'s conventional style
+fn indent_unit(language: &str) -> &'static str {
+    match language {
+        "javascript" | "typescript" | "tsx" => "  ",
+        "go" => "\t",
+        _ => "    ",
+    }
+}
+
+/// `code` moved to `indent`, after removing the indentation its lines share.
+/// Lines inside multi-line strings keep their exact content.
+fn reindent(code: &str, indent: &str, language: &str) -> String {
+    let shared = code.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    map_lines_outside_strings(code, language, |line| {
+        if line.trim().is_empty() {
+            String::new()
+        } else {
+            format!("{}{}", indent, line.get(shared..).unwrap_or(line.trim_start()))
+        }
+    })
+}
+
+/// Replace the line holding `token` with `nested`, at that line's indentation
+fn replace_token_line(rendered: &str, token: &str, nested: &str, language: &str) -> String {
+    rendered.lines()
+        .map(|line| match line.find(token) {
+            Some(_) => {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                reindent(nested, indent, language)
+            }
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Add `nested` blocks at the end of `rendered`'s body: before its closing
+/// brace, or after its last line in Python
+fn append_nested(rendered: &str, nested: &[String], language: &str) -> String {
+    let mut lines: Vec<String> = rendered.trim_end().lines().map(String::from).collect();
+    let closing_at = match language {
+        "python" => None,
+        _ => lines.iter().rposition(|line| line.trim_start().starts_with('}')),
+    };
+    let closing = closing_at.map(|at| lines.split_off(at)).unwrap_or_default();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    
+    let indent = match closing.first() {
+        Some(line) => format!("{}{}", &line[..line.len() - line.trim_start().len()], indent_unit(language)),
+        None => indent_unit(language).to_string(),
+    };
+    let nested = nested.iter()
+        .map(|block| reindent(block, &indent, language))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    
+    // Members of a class follow its header or fields after a blank line
+    let body_has_lines = lines.len() > 1;
+    lines.extend(body_has_lines.then(String::new));
+    lines.push(nested);
+    lines.extend(closing);
+    lines.join("\n")
+}
+
+/// Collapse the gaps empty keyword placeholders leave in a declaration's first line
+fn tidy_declaration_line(rendered: &str) -> String {
+    let (first, rest) = rendered.split_once('\n').map_or((rendered, None), |(first, rest)| (first, Some(rest)));
+    let indent: String = first.chars().take_while(|c| c.is_whitespace()).collect();
+    let tidied = format!("{}{}", indent, first.split(' ').filter(|word| !word.is_empty()).collect::<Vec<_>>().join(" "));
+    match rest {
+        Some(rest) => format!("{}\n{}", tidied, rest),
+        None => tidied,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::BlockType;
+    use crate::generator::HierarchicalGenerator;
+    use crate::generator::output_naming::file_extension;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that render_file refuses to build a file past its output limit
+    #[test]
+    fn test_render_file_stops_at_max_output_bytes() -> Result<()> {
+        let container = Container {
+            language: Some("python".to_string()),
+            original_path: Some("synth/generated.py".to_string()),
+            ..Container::new("generated", "file")
+        };
+        let runaway = format!("print({})", "1 + ".repeat(500) + "1");
+        let block = Block::new(container.id, "Statement", "print(", serde_json::json!({"implementation": {"original_text": runaway}}));
+    
+        let rendered = TemplateEngine::new().render_file(&container, std::slice::from_ref(&block), "python")?;
+        assert!(rendered.contains(&runaway));
+    
+        let error = TemplateEngine::new().with_max_output_bytes(1024).render_file(&container, std::slice::from_ref(&block), "python").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("synth/generated.py") && message.contains("1024-byte output limit"), "{}", message);
+        assert!(message.contains(&block.id.to_string()), "{}", message);
+        Ok(())
+    }
+
+    /// Test that placeholder and fallback comments use the target language's
+    /// comment syntax
+    #[test]
+    fn test_generated_comments_are_valid_in_python_and_ruby() -> Result<()> {
+        let block = |block_type: &str, name: &str| Block::new(Uuid::new_v4(), block_type, name, serde_json::json!({}));
+        let engine = TemplateEngine::new();
+    
+        let python = [engine.render_block(&block("Module", "settings"), "python")?, engine.render_block(&block("Widget", "button"), "py")?].join("\n");
+        assert!(python.contains("# TODO: Define module content") && python.contains("# Unknown block type: Widget"), "{}", python);
+        assert!(!python.contains("//"), "{}", python);
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_python::language())?;
+        let tree = parser.parse(&python, None).expect("parsed");
+        assert!(!tree.root_node().has_error(), "{}", python);
+    
+        let ruby = [engine.render_block(&block("Module", "Settings"), "ruby")?, engine.render_block(&block("Widget", "button"), "rb")?].join("\n");
+        assert_eq!(ruby, "module Settings\n    # TODO: Define module content\nend\n# Unknown block type: Widget\n");
+    
+        // Languages without a hierarchical generator fall back to a comment naming the block
+        let container = Container {
+            language: Some("ruby".to_string()),
+            original_path: Some("greeter.rb".to_string()),
+            ..Container::new("greeter", "file")
+        };
+        let generated = HierarchicalGenerator::from_blocks(&container, vec![block("Function", "greet")]).generate()?;
+        assert_eq!(generated, "# Function: greet");
+        Ok(())
+    }
+
+    fn regenerate_nested(source: &str, language: &str, path: &str) -> Result<String> {
+        let blocks = UniversalParser::new()?.parse_file(source, language, path)?.blocks;
+        let container = Container {
+            language: Some(language.to_string()),
+            original_path: Some(path.to_string()),
+            ..Container::new("service", "file")
+        };
+        let stored = blocks.iter().map(|block| Block::from_semantic_block(block, container.id)).collect::<Result<Vec<_>>>()?;
+        TemplateEngine::new().render_file(&container, &stored, language)
+    }
+
+    /// Each class and function with its parent's name, in extraction order
+    fn block_hierarchy(source: &str, language: &str, path: &str) -> Result<Vec<(String, Option<String>)>> {
+        let blocks = UniversalParser::new()?.parse_file(source, language, path)?.blocks;
+        let name = |id: Uuid| blocks.iter().find(|block| block.id == id).map(|block| block.semantic_identity.canonical_name.clone());
+        Ok(blocks.iter()
+            .filter(|block| matches!(block.block_type, BlockType::Class | BlockType::Function))
+            .map(|block| (block.semantic_identity.canonical_name.clone(), block.structural_context.parent_block.and_then(name)))
+            .collect())
+    }
+
+    /// Test that methods regenerate inside their class and a nested helper inside its method
+    #[test]
+    fn test_nested_blocks_render_inside_their_parent() -> Result<()> {
+        let python = "class Service:\n    def start(self):\n        def helper(x):\n            return x * 2\n        return helper(1)\n\n    def stop(self):\n        return None\n";
+        let generated = regenerate_nested(python, "python", "service.py")?;
+        assert!(generated.contains("class Service():\n    def start(self):\n        def helper(x):\n            return x * 2\n        return helper(1)\n\n    def stop(self):\n        return None\n"), "{}", generated);
+        assert_eq!(generated.matches("def helper").count(), 1, "{}", generated);
+        assert_eq!(block_hierarchy(&generated, "python", "service.py")?, block_hierarchy(python, "python", "service.py")?);
+    
+        let javascript = "class Service {\n  start() {\n    function helper(x) {\n      return x * 2;\n    }\n    return helper(1);\n  }\n\n  stop() {\n    return null;\n  }\n}\n";
+        let generated = regenerate_nested(javascript, "javascript", "service.js")?;
+        assert_eq!(generated.matches("function helper").count(), 1, "{}", generated);
+        assert!(!generated.contains("function start"), "{}", generated);
+        assert_eq!(block_hierarchy(&generated, "javascript", "service.js")?, block_hierarchy(javascript, "javascript", "service.js")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_templates_have_no_unfilled_placeholders() -> Result<()> {
+        let engine = TemplateEngine::new();
+        let unfilled = engine.lint_templates();
+        assert!(unfilled.is_empty(), "placeholders no renderer fills: {:#?}", unfilled);
+
+        let method = Block {
+            return_type: Some("i32".to_string()),
+            modifiers: Some(vec!["public".to_string()]),
+            ..Block::new(Uuid::new_v4(), "Method", "load", serde_json::json!({"throws": ["IOException"]}))
+        };
+        let java = engine.render_block(&method, "java")?;
+        assert!(java.contains("load() throws IOException {"), "{}", java);
+        assert!(!java.contains("{{"), "{}", java);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_class_fills_every_language_template() -> Result<()> {
+        let engine = TemplateEngine::new();
+        let class = Block {
+            modifiers: Some(vec!["public".to_string()]),
+            metadata: Some(serde_json::json!({"inheritance_chain": ["Shape"], "fields": ["radius"]})),
+            language_features: Some(serde_json::json!({"generics": ["T"]})),
+            ..Block::new(Uuid::new_v4(), "Class", "Circle", serde_json::json!({"inheritance": {"implements": ["Drawable"]}}))
+        };
+
+        for language in engine.languages() {
+            let rendered = engine.render_block(&class, language)?;
+            assert!(!rendered.contains("{{"), "{} left placeholders:\n{}", language, rendered);
+            assert!(rendered.contains("Circle"), "{}:\n{}", language, rendered);
+            assert!(rendered.contains("radius"), "{} dropped the class body:\n{}", language, rendered);
+        }
+
+        assert!(engine.render_block(&class, "csharp")?.starts_with("public class Circle<T> : Shape {"));
+        assert!(engine.render_block(&class, "java")?.starts_with("public class Circle<T> extends Shape implements Drawable {"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_aliases_select_the_same_templates_and_formatters() -> Result<()> {
+        let engine = TemplateEngine::new();
+        assert_eq!(engine.get_template("py")?.function_template, engine.get_template("python")?.function_template);
+        assert_eq!(engine.get_template("TS")?.function_template, engine.get_template("typescript")?.function_template);
+        assert!(engine.get_template("cobol").is_err());
+
+        let formatters = LanguageFormatters::new();
+        assert_eq!(formatters.tool_args("ts"), formatters.tool_args("typescript"));
+        assert_eq!(formatters.tool_args("c++"), formatters.tool_args("cpp"));
+
+        let block = Block {
+            return_type: Some("i32".to_string()),
+            ..Block::new(Uuid::new_v4(), "Function", "total", serde_json::json!({}))
+        };
+        assert_eq!(engine.render_block(&block, "rs")?, engine.render_block(&block, "rust")?);
+
+        assert_eq!("golang".parse::<Language>().map(Language::extension), Ok("go"));
+        assert_eq!(file_extension("Kotlin"), "kt");
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! Pipeline tracing
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Instant;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Severity / verbosity of a trace event, from least to most verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TraceLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for TraceLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(level: &str) -> anyhow::Result<Self> {
+        match level.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" | "warning" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => bail!("Unknown trace level '{}': expected error, warn, info, debug or trace", level),
+        }
+    }
+}
+
+/// Stage of the last event of a run, after which progress streams end
+pub const FINISHED_STAGE: &str = "finished";
+
+/// A single event emitted while a pipeline runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub pipeline_id: Uuid,
+    pub level: TraceLevel,
+    pub stage: String,
+    pub message: String,
+    /// Milliseconds since the tracer was created
+    pub elapsed_ms: u64,
+    /// Set on stage-completion events
+    pub stage_duration_ms: Option<u64>,
+    pub block_id: Option<Uuid>,
+}
+
+impl TraceEvent {
+    /// An event outside any tracer, timed at zero
+    pub fn new(pipeline_id: Uuid, level: TraceLevel, stage: &str, message: impl Into<String>) -> Self {
+        Self {
+            pipeline_id,
+            level,
+            stage: stage.to_string(),
+            message: message.into(),
+            elapsed_ms: 0,
+            stage_duration_ms: None,
+            block_id: None,
+        }
+    }
+}
+
+/// Collects trace events and stage timings for one pipeline run
+pub struct GenerationTracer {
+    pipeline_id: Uuid,
+    level: TraceLevel,
+    stage_levels: HashMap<String, TraceLevel>,
+    started: Instant,
+    events: Vec<TraceEvent>,
+    stage_starts: HashMap<String, Instant>,
+    stage_timings: HashMap<String, u64>,
+    sender: Option<broadcast::Sender<TraceEvent>>,
+}
+
+impl GenerationTracer {
+    pub fn new(pipeline_id: Uuid, level: TraceLevel) -> Self {
+        Self {
+            pipeline_id,
+            level,
+            stage_levels: HashMap::new(),
+            started: Instant::now(),
+            events: Vec::new(),
+            stage_starts: HashMap::new(),
+            stage_timings: HashMap::new(),
+            sender: None,
+        }
+    }
+
+    /// Also publish every recorded event on `sender`.
+    ///
+    /// Publishing never blocks the pipeline: events are dropped when nobody is
+    /// subscribed, and receivers that fall behind the channel capacity observe
+    /// `RecvError::Lagged` and skip ahead.
+    pub fn with_broadcast(mut self, sender: broadcast::Sender<TraceEvent>) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Override the tracer's level for individual stages; stages not listed
+    /// keep the level passed to `new`
+    pub fn with_stage_levels(mut self, stage_levels: HashMap<String, TraceLevel>) -> Self {
+        self.stage_levels = stage_levels;
+        self
+    }
+
+    /// Most verbose level recorded for `stage`
+    pub fn level_for(&self, stage: &str) -> TraceLevel {
+        self.stage_levels.get(stage).copied().unwrap_or(self.level)
+    }
+
+    pub fn pipeline_id(&self) -> Uuid {
+        self.pipeline_id
+    }
+
+    /// Record an event if `level` is enabled for `stage`
+    pub fn trace(&mut self, level: TraceLevel, stage: &str, message: impl Into<String>) {
+        self.record(level, stage, message.into(), None, None);
+    }
+
+    /// Record an event tied to a specific block
+    pub fn trace_block(&mut self, level: TraceLevel, stage: &str, block_id: Uuid, message: impl Into<String>) {
+        self.record(level, stage, message.into(), None, Some(block_id));
+    }
+
+    /// Mark the start of a stage
+    pub fn begin_stage(&mut self, stage: &str) {
+        self.stage_starts.insert(stage.to_string(), Instant::now());
+        self.record(TraceLevel::Info, stage, format!("{} started", stage), None, None);
+    }
+
+    /// Mark the end of a stage, returning its duration in milliseconds
+    pub fn end_stage(&mut self, stage: &str) -> u64 {
+        let duration_ms = self.stage_starts.remove(stage)
+            .map(|start| start.elapsed().as_millis() as u64)
+            .unwrap_or(0);
+        self.stage_timings.insert(stage.to_string(), duration_ms);
+        self.record(TraceLevel::Info, stage, format!("{} completed", stage), Some(duration_ms), None);
+        duration_ms
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    pub fn stage_timings(&self) -> &HashMap<String, u64> {
+        &self.stage_timings
+    }
+
+    fn record(
+        &mut self,
+        level: TraceLevel,
+        stage: &str,
+        message: String,
+        stage_duration_ms: Option<u64>,
+        block_id: Option<Uuid>,
+    ) {
+        if level > self.level_for(stage) {
+            return;
+        }
+
+        let event = TraceEvent {
+            pipeline_id: self.pipeline_id,
+            level,
+            stage: stage.to_string(),
+            message,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            stage_duration_ms,
+            block_id,
+        };
+
+        if let Some(sender) = &self.sender {
+            // No subscribers is not an error for the pipeline
+            let _ = sender.send(event.clone());
+        }
+        self.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    /// Test that per-stage trace levels override the tracer's own level
+    #[test]
+    fn test_stage_trace_levels_filter_events() -> Result<()> {
+        let stage_levels = HashMap::from([("validation".to_string(), "debug".parse::<TraceLevel>()?)]);
+        let mut tracer = GenerationTracer::new(Uuid::new_v4(), TraceLevel::Warn).with_stage_levels(stage_levels);
+        tracer.trace(TraceLevel::Debug, "generation", "app.py: 3 of 3 blocks rendered");
+        tracer.trace(TraceLevel::Warn, "generation", "app.py: placeholder");
+        tracer.trace(TraceLevel::Debug, "validation", "app.py: quality 1.00");
+        tracer.trace(TraceLevel::Trace, "validation", "app.py: parsed");
+    
+        let messages: Vec<(&str, &str)> = tracer.events().iter()
+            .map(|event| (event.stage.as_str(), event.message.as_str()))
+            .collect();
+        assert_eq!(messages, vec![("generation", "app.py: placeholder"), ("validation", "app.py: quality 1.00")]);
+        assert_eq!(tracer.level_for("output"), TraceLevel::Warn);
+        assert!("verbose".parse::<TraceLevel>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! TypeScript interfaces and type aliases
+
+use serde::{Deserialize, Serialize};
+
+/// Key under `abstract_syntax` holding a serialized `TypeDeclaration`
+pub const TYPE_DECLARATION_KEY: &str = "type_declaration";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeDeclaration {
+    Interface {
+        name: String,
+        exported: bool,
+        /// e.g. `T`, `K extends keyof T = keyof T`
+        type_parameters: Vec<String>,
+        extends: Vec<String>,
+        members: Vec<InterfaceMember>,
+    },
+    TypeAlias {
+        name: String,
+        exported: bool,
+        type_parameters: Vec<String>,
+        /// One entry per union member; a single entry for non-union aliases
+        union_members: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InterfaceMember {
+    Property {
+        name: String,
+        type_annotation: Option<String>,
+        optional: bool,
+        readonly: bool,
+    },
+    /// Method, call, construct and index signatures, kept verbatim
+    Signature { text: String },
+}
+
+impl TypeDeclaration {
+    /// Read the declaration stored on a block's abstract syntax, if any
+    pub fn from_abstract_syntax(abstract_syntax: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(abstract_syntax.get(TYPE_DECLARATION_KEY)?.clone()).ok()
+    }
+
+    /// Render as TypeScript, every line prefixed with `indent`
+    pub fn render_typescript(&self, indent: &str) -> String {
+        match self {
+            TypeDeclaration::Interface { name, exported, type_parameters, extends, members } => {
+                let mut header = format!("{}{}interface {}{}", indent, export_keyword(*exported), name, generics(type_parameters));
+                if !extends.is_empty() {
+                    header.push_str(&format!(" extends {}", extends.join(", ")));
+                }
+
+                if members.is_empty() {
+                    return format!("{} {{}}", header);
+                }
+
+                let mut lines = vec![format!("{} {{", header)];
+                for member in members {
+                    lines.push(format!("{}  {};", indent, member.render()));
+                }
+                lines.push(format!("{}}}", indent));
+                lines.join("\n")
+            }
+            TypeDeclaration::TypeAlias { name, exported, type_parameters, union_members } => {
+                format!(
+                    "{}{}type {}{} = {};",
+                    indent,
+                    export_keyword(*exported),
+                    name,
+                    generics(type_parameters),
+                    union_members.join(" | ")
+                )
+            }
+        }
+    }
+}
+
+impl InterfaceMember {
+    fn render(&self) -> String {
+        match self {
+            InterfaceMember::Property { name, type_annotation, optional, readonly } => {
+                let mut rendered = String::new();
+                if *readonly {
+                    rendered.push_str("readonly ");
+                }
+                rendered.push_str(name);
+                if *optional {
+                    rendered.push('?');
+                }
+                if let Some(type_annotation) = type_annotation {
+                    rendered.push_str(": ");
+                    rendered.push_str(type_annotation);
+                }
+                rendered
+            }
+            InterfaceMember::Signature { text } => text.trim_end_matches([';', ',']).to_string(),
+        }
+    }
+}
+
+fn export_keyword(exported: bool) -> &'static str {
+    if exported { "export " } else { "" }
+}
+
+fn generics(type_parameters: &[String]) -> String {
+    if type_parameters.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", type_parameters.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use crate::parser::universal::UniversalParser;
+
+    /// Regenerate every TypeScript type declaration in `source` from the semantic model
+    fn regenerate_type_declarations(source: &str) -> Result<String> {
+        let parse_result = UniversalParser::new()?.parse_file(source, "typescript", "types.ts")?;
+    
+        let rendered: Vec<String> = parse_result.blocks.iter()
+            .filter_map(|block| TypeDeclaration::from_abstract_syntax(&block.syntax_preservation.normalized_ast))
+            .map(|declaration| declaration.render_typescript(""))
+            .collect();
+    
+        Ok(format!("{}\n", rendered.join("\n\n")))
+    }
+
+    /// Test round-trip of a generic interface with optional and readonly members
+    #[test]
+    fn test_typescript_generic_interface_round_trip() -> Result<()> {
+        let source = r#"export interface Repository<T extends Entity, K = string> extends Reader<T>, Writer<T> {
+  readonly name: string;
+  cache?: Map<K, T>;
+  find(id: K): Promise<T | undefined>;
+}
+"#;
+    
+        assert_eq!(regenerate_type_declarations(source)?, source);
+        Ok(())
+    }
+
+    /// Test round-trip of a discriminated union type alias
+    #[test]
+    fn test_typescript_discriminated_union_round_trip() -> Result<()> {
+        let source = r#"type Result<T, E = Error> = { kind: "ok"; value: T } | { kind: "err"; error: E } | Pending;
+"#;
+    
+        assert_eq!(regenerate_type_declarations(source)?, source);
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "typescript", "types.ts")?;
+        match TypeDeclaration::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast) {
+            Some(TypeDeclaration::TypeAlias { union_members, .. }) => assert_eq!(union_members.len(), 3),
+            other => panic!("Expected a type alias, got {:?}", other),
+        }
+        Ok(())
+    }
+}
@@ -1,19 +1,63 @@
 use anyhow::Result;
+use code_builders::StrictnessProfile;
+use serde::{Deserialize, Serialize};
+use crate::core::EmptyFile;
 use crate::database::{Database, Container, Block};
 use super::templates::TemplateEngine;
 use super::validation::{ReconstructionValidator, ValidationResult};
+use super::formatters::FormatConfig;
+use super::summary::SummaryVerbosity;
+use super::tracer::TraceLevel;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationConfig {
     pub output_dir: std::path::PathBuf,
     #[allow(dead_code)]
     pub format_code: bool,
     #[allow(dead_code)]
     pub group_imports: bool,
-    #[allow(dead_code)]
+    /// Drop repeated imports and merge those from the same module
+    #[serde(default)]
+    pub dedupe_imports: bool,
     pub add_markers: bool,
     #[allow(dead_code)]
     pub validate_output: bool,
+    /// Minimum generation quality (0.0-1.0) every file must reach
+    pub quality_threshold: f64,
+    /// Write a manifest mapping generated files back to their blocks
+    pub manifest: bool,
+    /// Write a `<file>.map.json` source map next to each generated file
+    #[serde(default)]
+    pub source_map: bool,
+    /// Settings passed to the external formatters when `format_code` is set
+    #[serde(default)]
+    pub format_config: FormatConfig,
+    /// Also write the `__init__.py`, `mod.rs` and `index.ts` files that
+    /// make the generated files a package
+    #[serde(default)]
+    pub package_files: bool,
+    /// Compare with the files already in `output_dir` instead of writing
+    #[serde(default)]
+    pub check: bool,
+    /// Fail when any file has blocks rendered as placeholders, instead of
+    /// warning
+    #[serde(default)]
+    pub fail_on_placeholders: bool,
+    /// Most verbose trace event reported for stages not in
+    /// `stage_trace_levels`; `None` reports no trace
+    #[serde(default)]
+    pub trace_level: Option<TraceLevel>,
+    /// Per-stage overrides of `trace_level`, keyed by stage name
+    #[serde(default)]
+    pub stage_trace_levels: HashMap<String, TraceLevel>,
+    /// File keeping rendered and formatted code between runs, so blocks
+    /// that are unchanged skip rendering and their files the formatter
+    #[serde(default)]
+    pub cache: Option<std::path::PathBuf>,
+    /// How much of the run the printed summary reports
+    #[serde(default)]
+    pub summary_verbosity: SummaryVerbosity,
 }
 
 impl Default for GenerationConfig {
@@ -22,12 +66,41 @@ impl Default for GenerationConfig {
             output_dir: std::path::PathBuf::from("generated"),
             format_code: true,
             group_imports: true,
+            dedupe_imports: false,
             add_markers: true,
             validate_output: true,
+            quality_threshold: 0.7,
+            manifest: false,
+            source_map: false,
+            format_config: FormatConfig::default(),
+            package_files: false,
+            check: false,
+            fail_on_placeholders: false,
+            trace_level: None,
+            stage_trace_levels: HashMap::new(),
+            cache: None,
+            summary_verbosity: SummaryVerbosity::default(),
         }
     }
 }
 
+impl GenerationConfig {
+    /// Take formatting, the quality threshold and placeholder handling from
+    /// `profile`, and validate the output if the profile requires it
+    pub fn with_profile(mut self, profile: StrictnessProfile) -> Self {
+        self.format_code = profile.format_on_build();
+        self.quality_threshold = profile.quality_threshold();
+        self.fail_on_placeholders = profile.strict_mode();
+        self.validate_output |= profile.requires_validation();
+        self
+    }
+
+    /// Whether generation reports a trace
+    pub fn is_traced(&self) -> bool {
+        self.trace_level.is_some() || !self.stage_trace_levels.is_empty()
+    }
+}
+
 #[allow(dead_code)]
 pub struct UniversalGenerator {
     template_engine: TemplateEngine,
@@ -62,7 +135,8 @@ impl UniversalGenerator {
             let blocks = db.get_blocks_by_container(container.id).await?;
             total_blocks += blocks.len();
             
-            if blocks.is_empty() {
+            // Files migrated empty have no blocks but are still written
+            if blocks.is_empty() && EmptyFile::from_parsing_metadata(container.parsing_metadata.as_ref()).is_none() {
                 continue;
             }
             
@@ -1,5 +1,7 @@
 use anyhow::Result;
 use serde::{Serialize, Deserialize};
+use tree_sitter::{Node, Parser};
+use crate::parser::universal::grammar_for;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -41,12 +43,18 @@ impl ReconstructionValidator {
             errors.push("Failed to reconstruct any content from original".to_string());
         }
         
-        // Language-specific validation
+        // Re-parse with the same grammar used for extraction
+        match self.syntax_errors(reconstructed_content, language)? {
+            Some(syntax_errors) => errors.extend(syntax_errors),
+            None => warnings.push(format!("No specific validation available for language: {}", language)),
+        }
+        
+        // Language-specific style checks
         match language {
-            "rust" => self.validate_rust_syntax(&reconstructed_content, &mut errors, &mut warnings),
-            "python" => self.validate_python_syntax(&reconstructed_content, &mut errors, &mut warnings),
-            "javascript" | "typescript" => self.validate_js_syntax(&reconstructed_content, &mut errors, &mut warnings),
-            _ => warnings.push(format!("No specific validation available for language: {}", language)),
+            "rust" => self.validate_rust_syntax(reconstructed_content, &mut warnings),
+            "python" => self.validate_python_syntax(reconstructed_content, &mut warnings),
+            "javascript" | "typescript" | "tsx" => self.validate_js_syntax(reconstructed_content, &mut warnings),
+            _ => {}
         }
         
         // Calculate metrics
@@ -68,32 +76,36 @@ impl ReconstructionValidator {
         })
     }
     
-    fn validate_rust_syntax(&self, content: &str, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
-        // Basic Rust syntax checks
-        if content.contains("fn ") && !content.contains("{") {
-            errors.push("Rust function missing opening brace".to_string());
-        }
+    /// Re-parse `content` and describe every syntax error with its location.
+    /// Returns `None` when there is no grammar for `language`.
+    pub fn syntax_errors(&self, content: &str, language: &str) -> Result<Option<Vec<String>>> {
+        let grammar = match grammar_for(language) {
+            Some(grammar) => grammar,
+            None => return Ok(None),
+        };
+        
+        let mut parser = Parser::new();
+        parser.set_language(grammar)?;
+        let tree = match parser.parse(content, None) {
+            Some(tree) => tree,
+            None => return Ok(Some(vec!["Generated code could not be parsed".to_string()])),
+        };
         
-        if content.contains("struct ") && !content.contains("{") {
-            errors.push("Rust struct missing opening brace".to_string());
+        let mut errors = Vec::new();
+        if tree.root_node().has_error() {
+            collect_syntax_errors(tree.root_node(), content, &mut errors);
         }
-        
+        Ok(Some(errors))
+    }
+    
+    fn validate_rust_syntax(&self, content: &str, warnings: &mut Vec<String>) {
         // Check for common Rust patterns
         if content.contains("let ") && !content.contains(";") {
             warnings.push("Rust variable declaration might be missing semicolon".to_string());
         }
     }
     
-    fn validate_python_syntax(&self, content: &str, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
-        // Basic Python syntax checks
-        if content.contains("def ") && !content.contains(":") {
-            errors.push("Python function missing colon".to_string());
-        }
-        
-        if content.contains("class ") && !content.contains(":") {
-            errors.push("Python class missing colon".to_string());
-        }
-        
+    fn validate_python_syntax(&self, content: &str, warnings: &mut Vec<String>) {
         // Check indentation
         let lines: Vec<&str> = content.lines().collect();
         for (i, line) in lines.iter().enumerate() {
@@ -105,16 +117,7 @@ impl ReconstructionValidator {
         }
     }
     
-    fn validate_js_syntax(&self, content: &str, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
-        // Basic JavaScript/TypeScript syntax checks
-        if content.contains("function ") && !content.contains("{") {
-            errors.push("JavaScript function missing opening brace".to_string());
-        }
-        
-        if content.contains("class ") && !content.contains("{") {
-            errors.push("JavaScript class missing opening brace".to_string());
-        }
-        
+    fn validate_js_syntax(&self, content: &str, warnings: &mut Vec<String>) {
         // Check for semicolons (optional but good practice)
         let lines: Vec<&str> = content.lines().collect();
         for (i, line) in lines.iter().enumerate() {
@@ -166,6 +169,11 @@ impl ReconstructionValidator {
         intersection as f64 / union as f64
     }
     
+    /// Word-level Jaccard similarity between original and regenerated source
+    pub fn reconstruction_fidelity(&self, original: &str, reconstructed: &str) -> f64 {
+        self.calculate_reconstruction_fidelity(original, reconstructed)
+    }
+    
     #[allow(dead_code)]
     pub fn validate_migration(&self, _migration_id: uuid::Uuid, _db: &crate::database::Database) -> Result<ValidationResult> {
         // This would validate an entire migration
@@ -184,3 +192,29 @@ impl ReconstructionValidator {
         })
     }
 }
+
+fn collect_syntax_errors(node: Node, content: &str, errors: &mut Vec<String>) {
+    if node.is_error() || node.is_missing() {
+        let start = node.start_position();
+        if node.is_missing() {
+            errors.push(format!("Syntax error at line {}, column {}: missing `{}`",
+                start.row + 1, start.column + 1, node.kind()));
+        } else {
+            let snippet: String = content.get(node.start_byte()..node.end_byte())
+                .unwrap_or("")
+                .chars()
+                .take(40)
+                .collect();
+            errors.push(format!("Syntax error at line {}, column {}: unexpected `{}`",
+                start.row + 1, start.column + 1, snippet.trim()));
+        }
+        return;
+    }
+    
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() || child.is_missing() {
+            collect_syntax_errors(child, content, errors);
+        }
+    }
+}
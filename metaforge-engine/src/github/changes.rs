@@ -0,0 +1,20 @@
+//! Files changed between two commits
+
+use std::path::PathBuf;
+
+/// Paths, relative to the repository root, that differ from a base commit.
+/// A rename counts as the old path deleted and the new one added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileChanges {
+    /// Full id of the base commit
+    pub base_commit: String,
+    pub added: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    pub deleted: Vec<PathBuf>,
+}
+
+impl FileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::{Result, Context};
-use git2::{Repository, Cred, RemoteCallbacks, FetchOptions};
+use git2::{Repository, Cred, Delta, DiffOptions, RemoteCallbacks, FetchOptions};
 use std::path::Path;
 use octocrab::Octocrab;
+use super::changes::FileChanges;
 
 pub struct GitHubClient {
     token: Option<String>,
@@ -65,6 +66,36 @@ impl GitHubClient {
         Ok(commit.id().to_string())
     }
     
+    /// Files of the working tree that differ from commit `since`, which may
+    /// be abbreviated or any other revision git understands
+    pub fn changed_files_since(&self, repo: &Repository, since: &str) -> Result<FileChanges> {
+        let base = repo.revparse_single(since)
+            .with_context(|| format!("Unknown commit: {}", since))?
+            .peel_to_commit()
+            .with_context(|| format!("Not a commit: {}", since))?;
+        
+        let mut options = DiffOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let diff = repo.diff_tree_to_workdir_with_index(Some(&base.tree()?), Some(&mut options))?;
+        
+        let mut changes = FileChanges { base_commit: base.id().to_string(), ..FileChanges::default() };
+        for delta in diff.deltas() {
+            let old_path = delta.old_file().path().map(Path::to_path_buf);
+            let new_path = delta.new_file().path().map(Path::to_path_buf);
+            match delta.status() {
+                Delta::Added | Delta::Untracked | Delta::Copied => changes.added.extend(new_path),
+                Delta::Modified | Delta::Typechange => changes.modified.extend(new_path),
+                Delta::Deleted => changes.deleted.extend(old_path),
+                Delta::Renamed => {
+                    changes.deleted.extend(old_path);
+                    changes.added.extend(new_path);
+                }
+                _ => {}
+            }
+        }
+        Ok(changes)
+    }
+    
     pub fn get_repo_name(&self, repo_url: &str) -> String {
         repo_url
             .trim_end_matches(".git")
@@ -1,3 +1,5 @@
 pub mod client;
+pub mod changes;
 
 pub use client::GitHubClient;
+pub use changes::FileChanges;
@@ -1,5 +1,6 @@
 pub mod versioning_schema;
 pub mod schema;
 pub mod server;
+pub mod subscriptions;
 pub mod types;
 
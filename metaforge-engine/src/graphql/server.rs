@@ -1,37 +1,80 @@
-use actix_web::{web, App, HttpServer, Result as ActixResult, HttpResponse, middleware::Logger};
+use actix_web::{web, App, Either, HttpServer, HttpRequest, Result as ActixResult, HttpResponse, middleware::Logger};
 use actix_cors::Cors;
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
 use sqlx::PgPool;
 use anyhow::Result;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use super::schema::{create_schema, GraphQLSchema};
+use super::subscriptions::GenerationProgressHub;
+
+/// Query budgets and request rate limits enforced by the server
+#[derive(Debug, Clone)]
+pub struct GraphQLServerConfig {
+    /// Maximum selection-set nesting depth of a query
+    pub max_depth: usize,
+    /// Maximum computed query complexity (one point per selected field)
+    pub max_complexity: usize,
+    /// Requests allowed per client IP per minute (0 disables rate limiting)
+    pub requests_per_minute: u32,
+    /// Reverse proxies whose `Forwarded`/`X-Forwarded-For` headers name the
+    /// client; requests from any other peer are keyed on the peer address
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for GraphQLServerConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 10,
+            max_complexity: 500,
+            requests_per_minute: 120,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
 
 pub struct GraphQLServer {
     schema: GraphQLSchema,
+    pool: PgPool,
+    requests_per_minute: u32,
+    trusted_proxies: Vec<IpAddr>,
     bind_address: String,
 }
 
 impl GraphQLServer {
-    pub fn new(_pool: PgPool, bind_address: String) -> Self {
-        let schema = create_schema();
+    /// Create a server that serves every request from the given pool,
+    /// with explicit query and rate limits
+    pub fn with_config(pool: PgPool, bind_address: String, config: GraphQLServerConfig) -> Self {
+        let schema = create_schema(GenerationProgressHub::new(), &config);
         
         Self {
             schema,
+            pool,
+            requests_per_minute: config.requests_per_minute,
+            trusted_proxies: config.trusted_proxies,
             bind_address,
         }
     }
     
-    pub async fn start(self, pool: PgPool) -> Result<()> {
+    pub async fn start(self) -> Result<()> {
         println!("🚀 GraphQL server starting at http://{}/graphql", self.bind_address);
         println!("📊 GraphQL playground available at http://{}/playground", self.bind_address);
+        println!("📡 GraphQL subscriptions available at ws://{}/graphql/ws", self.bind_address);
         
         let schema = self.schema;
+        let pool = self.pool;
+        // Shared across workers so the limit holds per process, not per worker
+        let rate_limiter = web::Data::new(RateLimiter::new(self.requests_per_minute, Duration::from_secs(60), self.trusted_proxies));
         
         HttpServer::new(move || {
             App::new()
                 .app_data(web::Data::new(schema.clone()))
                 .app_data(web::Data::new(pool.clone()))
+                .app_data(rate_limiter.clone())
                 .wrap(
                     Cors::default()
                         .allow_any_origin()
@@ -42,6 +85,7 @@ impl GraphQLServer {
                 .wrap(Logger::default())
                 .route("/graphql", web::post().to(graphql_handler))
                 .route("/graphql", web::get().to(graphql_playground))
+                .route("/graphql/ws", web::get().to(graphql_subscription))
                 .route("/playground", web::get().to(graphql_playground))
                 .route("/health", web::get().to(health_check))
         })
@@ -53,17 +97,104 @@ impl GraphQLServer {
     }
 }
 
+/// Fixed-window request counter keyed by client IP
+struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    trusted_proxies: Vec<IpAddr>,
+    clients: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    fn new(limit: u32, window: Duration, trusted_proxies: Vec<IpAddr>) -> Self {
+        Self {
+            limit,
+            window,
+            trusted_proxies,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// The address to count `req` against. Forwarding headers are client
+    /// controlled, so they are only believed when a trusted proxy sent them.
+    fn client_key(&self, req: &HttpRequest) -> String {
+        let Some(peer) = req.peer_addr().map(|addr| addr.ip()) else {
+            return "unknown".to_string();
+        };
+        if self.trusted_proxies.contains(&peer) {
+            if let Some(forwarded) = req.connection_info().realip_remote_addr() {
+                return forwarded.to_string();
+            }
+        }
+        peer.to_string()
+    }
+    
+    /// Count a request from `client`, returning false once it is over budget
+    fn check(&self, client: &str) -> bool {
+        if self.limit == 0 {
+            return true;
+        }
+        
+        let now = Instant::now();
+        let mut clients = self.clients.lock().unwrap();
+        
+        // Keep the map bounded when many clients come and go
+        if clients.len() > 10_000 {
+            clients.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+        }
+        
+        let entry = clients.entry(client.to_string()).or_insert((now, 0));
+        if now.duration_since(entry.0) >= self.window {
+            *entry = (now, 0);
+        }
+        entry.1 += 1;
+        entry.1 <= self.limit
+    }
+}
+
+fn rate_limited_response(limiter: &RateLimiter) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header(("Retry-After", limiter.window.as_secs().to_string()))
+        .json(serde_json::json!({
+            "errors": [{
+                "message": format!("Rate limit exceeded: at most {} requests per {}s", limiter.limit, limiter.window.as_secs()),
+                "extensions": { "code": "RATE_LIMITED" }
+            }]
+        }))
+}
+
 async fn graphql_handler(
     schema: web::Data<GraphQLSchema>,
     pool: web::Data<PgPool>,
+    limiter: web::Data<RateLimiter>,
+    http_req: HttpRequest,
     req: GraphQLRequest,
-) -> GraphQLResponse {
+) -> Either<GraphQLResponse, HttpResponse> {
+    if !limiter.check(&limiter.client_key(&http_req)) {
+        return Either::Right(rate_limited_response(&limiter));
+    }
+    
     let request = req.into_inner().data(pool.get_ref().clone());
-    schema.execute(request).await.into()
+    Either::Left(schema.execute(request).await.into())
+}
+
+async fn graphql_subscription(
+    schema: web::Data<GraphQLSchema>,
+    limiter: web::Data<RateLimiter>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> ActixResult<HttpResponse> {
+    if !limiter.check(&limiter.client_key(&req)) {
+        return Ok(rate_limited_response(&limiter));
+    }
+    
+    GraphQLSubscription::new(schema.get_ref().clone()).start(&req, payload)
 }
 
 async fn graphql_playground() -> ActixResult<HttpResponse> {
-    let source = playground_source(GraphQLPlaygroundConfig::new("/graphql"));
+    let source = playground_source(
+        GraphQLPlaygroundConfig::new("/graphql").subscription_endpoint("/graphql/ws")
+    );
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(source))
@@ -76,6 +207,7 @@ async fn health_check() -> ActixResult<HttpResponse> {
         "version": env!("CARGO_PKG_VERSION"),
         "endpoints": {
             "graphql": "/graphql",
+            "subscriptions": "/graphql/ws",
             "playground": "/playground"
         }
     })))
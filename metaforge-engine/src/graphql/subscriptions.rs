@@ -0,0 +1,122 @@
+use async_graphql::futures_util::stream::{self, Stream};
+use async_graphql::{Context, Result, Subscription, ID};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::generator::tracer::{TraceEvent, TraceLevel, FINISHED_STAGE};
+use super::types::GenerationProgressEvent;
+
+/// Events buffered per pipeline before slow subscribers start lagging
+pub const PROGRESS_CHANNEL_CAPACITY: usize = 256;
+
+/// Registry of live progress channels, keyed by pipeline id.
+///
+/// A generation run registers its id here and hands the returned sender to
+/// `GenerationTracer::with_broadcast`; subscriptions read from the same
+/// channel. Only runs create channels, so client-chosen ids can't grow the
+/// registry.
+#[derive(Clone, Default)]
+pub struct GenerationProgressHub {
+    channels: Arc<RwLock<HashMap<Uuid, broadcast::Sender<TraceEvent>>>>,
+}
+
+impl GenerationProgressHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get (or create) the progress channel for a pipeline run
+    pub fn register(&self, pipeline_id: Uuid) -> broadcast::Sender<TraceEvent> {
+        let mut channels = self.channels.write().unwrap();
+        channels.entry(pipeline_id)
+            .or_insert_with(|| broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0)
+            .clone()
+    }
+
+    /// End a run: subscribers receive a final `finished` event and their
+    /// streams close, even while the run's tracer still holds a sender
+    pub fn finish(&self, pipeline_id: Uuid) {
+        if let Some(sender) = self.channels.write().unwrap().remove(&pipeline_id) {
+            let _ = sender.send(TraceEvent::new(pipeline_id, TraceLevel::Info, FINISHED_STAGE, "Generation finished"));
+        }
+    }
+
+    /// Receiver for a run in progress; `None` once it has finished or for
+    /// ids no run has registered
+    fn subscribe(&self, pipeline_id: Uuid) -> Option<broadcast::Receiver<TraceEvent>> {
+        self.channels.read().unwrap().get(&pipeline_id).map(broadcast::Sender::subscribe)
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Stream trace events and stage timings from an in-flight generation run.
+    ///
+    /// Backpressure: the pipeline never waits for subscribers. Each run buffers
+    /// up to `PROGRESS_CHANNEL_CAPACITY` events; a subscriber that falls further
+    /// behind skips the oldest events and receives a single event with stage
+    /// `lagged` whose `droppedEvents` says how many were missed. The stream ends
+    /// after the run's `finished` event. Subscribing to an id with no run in
+    /// progress is an error.
+    async fn generation_progress(
+        &self,
+        ctx: &Context<'_>,
+        pipeline_id: ID,
+    ) -> Result<impl Stream<Item = GenerationProgressEvent>> {
+        let hub = ctx.data::<GenerationProgressHub>()?;
+        let uuid = Uuid::from_str(&pipeline_id)?;
+
+        let receiver = hub.subscribe(uuid).ok_or_else(|| {
+            async_graphql::Error::new(format!("No generation run in progress with pipeline id {}", uuid))
+        })?;
+
+        // The `finished` event is delivered, then the stream ends
+        Ok(stream::unfold(Some(receiver), move |receiver| async move {
+            let mut receiver = receiver?;
+            match receiver.recv().await {
+                Ok(event) if event.stage == FINISHED_STAGE => Some((GenerationProgressEvent::from(event), None)),
+                Ok(event) => Some((GenerationProgressEvent::from(event), Some(receiver))),
+                Err(RecvError::Lagged(skipped)) => Some((lagged_event(uuid, skipped), Some(receiver))),
+                Err(RecvError::Closed) => None,
+            }
+        }))
+    }
+}
+
+fn lagged_event(pipeline_id: Uuid, skipped: u64) -> GenerationProgressEvent {
+    GenerationProgressEvent {
+        pipeline_id: ID::from(pipeline_id.to_string()),
+        level: "Warn".to_string(),
+        stage: "lagged".to_string(),
+        message: format!("Subscriber fell behind; {} events dropped", skipped),
+        elapsed_ms: 0,
+        stage_duration_ms: None,
+        block_id: None,
+        dropped_events: skipped.min(i32::MAX as u64) as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribe_only_finds_runs_in_progress() {
+        let hub = GenerationProgressHub::new();
+        let pipeline_id = Uuid::new_v4();
+        assert!(hub.subscribe(pipeline_id).is_none());
+        assert!(hub.channels.read().unwrap().is_empty());
+
+        let _sender = hub.register(pipeline_id);
+        let mut receiver = hub.subscribe(pipeline_id).expect("run registered");
+
+        hub.finish(pipeline_id);
+        assert_eq!(receiver.try_recv().unwrap().stage, FINISHED_STAGE);
+        assert!(hub.subscribe(pipeline_id).is_none());
+    }
+}
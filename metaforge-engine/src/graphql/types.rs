@@ -1,4 +1,5 @@
 use async_graphql::*;
+use crate::generator::tracer::TraceEvent;
 
 /// GraphQL-compatible semantic block representation
 #[derive(SimpleObject, Clone, Debug)]
@@ -230,6 +231,21 @@ pub struct BlockSynthesisInput {
     pub target_language: Option<String>,
 }
 
+/// Abstract block specification, mirroring the CLI `synthesize` spec file
+#[derive(InputObject, Clone, Debug)]
+pub struct AbstractBlockSpecInput {
+    /// Type of block to create (Function, Class, Module, Interface, Struct, Enum, Constant, Variable)
+    pub block_type: String,
+    /// Semantic name for the block
+    pub semantic_name: String,
+    /// Description of the block's purpose
+    pub description: String,
+    /// Block properties
+    pub properties: Option<BlockPropertiesInput>,
+    /// Behavioral specifications
+    pub behaviors: Option<Vec<BehaviorInput>>,
+}
+
 /// Block properties input
 #[derive(InputObject, Clone, Debug)]
 pub struct BlockPropertiesInput {
@@ -392,6 +408,9 @@ pub struct GenerationInput {
     pub language: String,
     /// Generation options
     pub options: Option<GenerationOptionsInput>,
+    /// Id to publish progress under on the `generationProgress`
+    /// subscription, which accepts it while the run is in progress
+    pub pipeline_id: Option<ID>,
 }
 
 /// Code generation options
@@ -410,6 +429,8 @@ pub struct GenerationOptionsInput {
 /// Result of code generation
 #[derive(SimpleObject, Clone, Debug)]
 pub struct GenerationResult {
+    /// Id the run's progress was published under
+    pub pipeline_id: ID,
     /// Generated files
     pub files: Vec<GeneratedFile>,
     /// Generation statistics
@@ -430,3 +451,43 @@ pub struct GenerationStats {
     /// Generation time in milliseconds
     pub generation_time_ms: i32,
 }
+
+// ============================================================================
+// Generation Progress Types
+// ============================================================================
+
+/// Live progress event from an in-flight generation pipeline
+#[derive(SimpleObject, Clone, Debug)]
+pub struct GenerationProgressEvent {
+    /// Pipeline run this event belongs to
+    pub pipeline_id: ID,
+    /// Trace level (Error, Warn, Info, Debug, Trace)
+    pub level: String,
+    /// Pipeline stage that emitted the event
+    pub stage: String,
+    /// Human-readable event message
+    pub message: String,
+    /// Milliseconds since the pipeline started
+    pub elapsed_ms: i64,
+    /// Stage duration in milliseconds, set when a stage completes
+    pub stage_duration_ms: Option<i64>,
+    /// Block the event refers to, if any
+    pub block_id: Option<ID>,
+    /// Events skipped because this subscriber fell behind (0 for normal events)
+    pub dropped_events: i32,
+}
+
+impl From<TraceEvent> for GenerationProgressEvent {
+    fn from(event: TraceEvent) -> Self {
+        Self {
+            pipeline_id: ID::from(event.pipeline_id.to_string()),
+            level: format!("{:?}", event.level),
+            stage: event.stage,
+            message: event.message,
+            elapsed_ms: event.elapsed_ms as i64,
+            stage_duration_ms: event.stage_duration_ms.map(|ms| ms as i64),
+            block_id: event.block_id.map(|id| ID::from(id.to_string())),
+            dropped_events: 0,
+        }
+    }
+}
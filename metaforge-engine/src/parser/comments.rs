@@ -0,0 +1,80 @@
+//! Comment attachment during migration
+
+use ast_extractor::comments::{attach_comments, collect_comments};
+use ast_extractor::{SourceRange, ATTACHED_COMMENTS_KEY};
+use tree_sitter::Node;
+
+use crate::core::SemanticBlock;
+
+/// Attach the comments in `root` to the blocks they lead, trail or sit inside
+pub fn attach_block_comments(root: Node, source: &str, blocks: &mut [SemanticBlock]) {
+    let ranges: Vec<SourceRange> = blocks.iter()
+        .map(|block| SourceRange {
+            start_line: block.position.start_line,
+            start_column: block.position.start_column,
+            end_line: block.position.end_line,
+            end_column: block.position.end_column,
+            byte_start: 0,
+            byte_end: 0,
+        })
+        .collect();
+    let attached = attach_comments(&collect_comments(root, source), &ranges);
+
+    for (block, comments) in blocks.iter_mut().zip(attached) {
+        if comments.is_empty() {
+            continue;
+        }
+        if !block.syntax_preservation.normalized_ast.is_object() {
+            block.syntax_preservation.normalized_ast = serde_json::json!({});
+        }
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(ATTACHED_COMMENTS_KEY.to_string(), serde_json::to_value(&comments).unwrap_or_default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use ast_extractor::{AttachedComment, CommentAttachment};
+    use crate::parser::{ExtractionProfile, ParseResult};
+    use crate::parser::universal::UniversalParser;
+
+    /// Migration attaches each comment to the block it annotates
+    #[test]
+    fn test_comments_are_attached_to_the_blocks_they_annotate() -> Result<()> {
+        let attached = |result: &ParseResult, name: &str| -> Vec<(String, CommentAttachment)> {
+            let block = result.blocks.iter()
+                .find(|block| block.semantic_identity.canonical_name == name)
+                .unwrap_or_else(|| panic!("{} extracted", name));
+            AttachedComment::from_value(block.syntax_preservation.normalized_ast.get(ATTACHED_COMMENTS_KEY)).into_iter()
+                .map(|comment| (comment.text, comment.attachment))
+                .collect()
+        };
+
+        let javascript = "/* Adds two numbers */\nfunction add(a, b) {\n  // no overflow check\n  return a + b;\n} // end add\n";
+        let result = UniversalParser::new()?.parse_file(javascript, "javascript", "math.js")?;
+        assert_eq!(attached(&result, "add"), vec![
+            ("/* Adds two numbers */".to_string(), CommentAttachment::Leading),
+            ("// no overflow check".to_string(), CommentAttachment::Inline),
+            ("// end add".to_string(), CommentAttachment::Trailing),
+        ]);
+
+        let python = "class Cart:\n    # Sum of the line totals\n    def total(self):\n        return sum(self.lines)  # cached later\n";
+        let result = UniversalParser::new()?.parse_file(python, "python", "cart.py")?;
+        assert!(attached(&result, "Cart").is_empty());
+        assert_eq!(attached(&result, "total"), vec![
+            ("# Sum of the line totals".to_string(), CommentAttachment::Leading),
+            ("# cached later".to_string(), CommentAttachment::Trailing),
+        ]);
+
+        // Analysis-only runs skip the pass
+        let fast = UniversalParser::new()?
+            .with_profile(ExtractionProfile::fast())
+            .parse_file(javascript, "javascript", "math.js")?;
+        assert!(attached(&fast, "add").is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Detection of TODO/FIXME comments and stub bodies
+
+use ast_extractor::comments::collect_comments;
+use std::collections::BTreeMap;
+use tree_sitter::Node;
+
+use crate::core::{BodyStatement, DebtKind, DebtMarker, FunctionBody, SemanticBlock, StatementKind, DEBT_MARKERS_KEY};
+
+/// Record the debt markers found in `root` on the blocks that contain them
+pub fn attach_debt_markers(root: Node, source: &str, blocks: &mut [SemanticBlock]) {
+    let mut found: BTreeMap<usize, Vec<DebtMarker>> = BTreeMap::new();
+
+    for comment in collect_comments(root, source) {
+        for (offset, line) in comment.text.lines().enumerate() {
+            let Some(marker) = comment_marker(line, comment.range.start_line + offset) else {
+                continue;
+            };
+            if let Some(index) = innermost_block(blocks, marker.line) {
+                found.entry(index).or_default().push(marker);
+            }
+        }
+    }
+
+    for (index, block) in blocks.iter().enumerate() {
+        let body = FunctionBody::from_abstract_syntax(&block.syntax_preservation.normalized_ast);
+        if let Some(statement) = body.as_ref().and_then(stub_statement) {
+            found.entry(index).or_default()
+                .push(DebtMarker::new(DebtKind::Unimplemented, statement.code.trim(), statement.line));
+        }
+    }
+
+    for (index, mut markers) in found {
+        markers.sort_by_key(|marker| marker.line);
+        let block = &mut blocks[index];
+        if !block.syntax_preservation.normalized_ast.is_object() {
+            block.syntax_preservation.normalized_ast = serde_json::json!({});
+        }
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(DEBT_MARKERS_KEY.to_string(), serde_json::to_value(&markers).unwrap_or_default());
+        }
+    }
+}
+
+/// The `TODO` or `FIXME` on one comment line, from the marker word to the
+/// end of the line without a closing `*/`
+fn comment_marker(line: &str, row: usize) -> Option<DebtMarker> {
+    [(DebtKind::Todo, "TODO"), (DebtKind::Fixme, "FIXME")].into_iter()
+        .filter_map(|(kind, word)| Some((kind, find_word(line, word)?)))
+        .min_by_key(|(_, start)| *start)
+        .map(|(kind, start)| {
+            let text = line[start..].trim_end().trim_end_matches("*/").trim_end();
+            DebtMarker::new(kind, text, row)
+        })
+}
+
+/// Byte offset of `word` in `line` where it isn't part of a longer identifier
+fn find_word(line: &str, word: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word)
+        .map(|(start, _)| start)
+        .find(|&start| {
+            let before = line[..start].chars().next_back();
+            let after = line[start + word.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+}
+
+/// The block with the narrowest line range containing `line`
+fn innermost_block(blocks: &[SemanticBlock], line: usize) -> Option<usize> {
+    blocks.iter().enumerate()
+        .filter(|(_, block)| block.position.start_line <= line && line <= block.position.end_line)
+        .min_by_key(|(_, block)| (block.position.end_line - block.position.start_line, usize::MAX - block.position.start_line))
+        .map(|(index, _)| index)
+}
+
+/// The only statement of a body that does nothing but mark it unfinished
+fn stub_statement(body: &FunctionBody) -> Option<&BodyStatement> {
+    let mut statements = body.statements.iter().filter(|statement| !is_docstring(statement));
+    let statement = statements.next()?;
+    (statements.next().is_none() && is_stub(&statement.code)).then_some(statement)
+}
+
+fn is_docstring(statement: &BodyStatement) -> bool {
+    statement.kind == StatementKind::Expression && statement.code.trim_start().starts_with(['"', '\''])
+}
+
+fn is_stub(code: &str) -> bool {
+    let code = code.trim().trim_end_matches(';').trim_end();
+    let lowered = code.to_lowercase();
+    let says_not_implemented = lowered.contains("notimplemented") || lowered.contains("not implemented");
+    matches!(code, "pass" | "...")
+        || code.starts_with("todo!")
+        || code.starts_with("unimplemented!")
+        || (["raise", "throw", "panic!"].iter().any(|keyword| code.starts_with(keyword)) && says_not_implemented)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::analysis::debt_report::DebtReport;
+    use crate::database::{Block, Container};
+    use crate::parser::{ExtractionProfile, ParseResult};
+    use crate::parser::universal::UniversalParser;
+
+    /// TODO/FIXME comments and stub bodies are recorded on their blocks and listed with their location
+    #[test]
+    fn test_debt_markers_flag_todo_comments_and_stub_bodies() -> Result<()> {
+        let markers_of = |result: &ParseResult, name: &str| -> Vec<(DebtKind, String, usize)> {
+            let block = result.blocks.iter()
+                .find(|block| block.semantic_identity.canonical_name == name)
+                .unwrap_or_else(|| panic!("{} extracted", name));
+            DebtMarker::from_abstract_syntax(&block.syntax_preservation.normalized_ast).into_iter()
+                .map(|marker| (marker.kind, marker.text, marker.line))
+                .collect()
+        };
+
+        let python = "def load(path):\n    # TODO: retry on timeout\n    return open(path).read()\n\ndef save(path):\n    \"\"\"Persist the cache\"\"\"\n    pass\n\ndef parse(text):\n    raise NotImplementedError(\"parse\")\n\ndef done():\n    return TODOS\n";
+        let result = UniversalParser::new()?.parse_file(python, "python", "cache.py")?;
+        assert_eq!(markers_of(&result, "load"), vec![(DebtKind::Todo, "TODO: retry on timeout".to_string(), 1)]);
+        assert_eq!(markers_of(&result, "save"), vec![(DebtKind::Unimplemented, "pass".to_string(), 6)]);
+        assert_eq!(markers_of(&result, "parse"), vec![(DebtKind::Unimplemented, "raise NotImplementedError(\"parse\")".to_string(), 9)]);
+        assert!(markers_of(&result, "done").is_empty());
+
+        let rust = "fn checksum(data: &[u8]) -> u32 {\n    todo!()\n}\n\nfn total(values: &[u32]) -> u32 {\n    /* FIXME overflow */\n    values.iter().sum()\n}\n";
+        let result = UniversalParser::new()?.parse_file(rust, "rust", "lib.rs")?;
+        assert_eq!(markers_of(&result, "checksum"), vec![(DebtKind::Unimplemented, "todo!()".to_string(), 1)]);
+        assert_eq!(markers_of(&result, "total"), vec![(DebtKind::Fixme, "FIXME overflow".to_string(), 5)]);
+
+        let javascript = "function render(view) {\n  throw new Error(\"not implemented\");\n}\n";
+        let result = UniversalParser::new()?.parse_file(javascript, "javascript", "view.js")?;
+        assert_eq!(markers_of(&result, "render"), vec![(DebtKind::Unimplemented, "throw new Error(\"not implemented\");".to_string(), 1)]);
+
+        // Analysis-only runs skip the pass
+        let fast = UniversalParser::new()?
+            .with_profile(ExtractionProfile::fast())
+            .parse_file(python, "python", "cache.py")?;
+        assert!(markers_of(&fast, "save").is_empty());
+
+        // The report names the file and uses one-based lines
+        let container = Container {
+            original_path: Some("src/cache.py".to_string()),
+            ..Container::new("cache.py", "file")
+        };
+        let block = Block::new(container.id, "Function", "load", serde_json::json!({
+            "debt_markers": [{"kind": "todo", "text": "TODO: retry on timeout", "line": 1}]
+        }));
+        let report = DebtReport::from_blocks(&[container], &[block, Block::new(Uuid::new_v4(), "Function", "add", serde_json::json!({}))]);
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!((report.entries[0].file.as_str(), report.entries[0].line), ("src/cache.py", 2));
+        assert_eq!(report.render_table(), "src/cache.py:2  TODO  load  TODO: retry on timeout");
+
+        Ok(())
+    }
+}
@@ -10,6 +10,27 @@ pub struct ExtractionContext {
     symbol_table: HashMap<String, Uuid>,
     current_scope: ScopeInfo,
     position_counter: usize,
+    profile: ExtractionProfile,
+}
+
+/// Which optional analyses extractors run for each block.
+///
+/// Blocks, hierarchy and relationships (the call graph) are always extracted;
+/// the flags only gate the per-block analyses on top of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionProfile {
+    /// Parameters, return types, decorators and modifiers
+    pub semantic_metadata: bool,
+    /// Control-flow and complexity metrics
+    pub complexity: bool,
+    /// Assignments, detailed calls and other side-effect analysis
+    pub side_effects: bool,
+    /// Generic parameters and bounds
+    pub generics: bool,
+    /// TODO/FIXME comments and stub bodies, recorded under `debt_markers`
+    pub debt_markers: bool,
+    /// Comments attached to the blocks they annotate, recorded under `attached_comments`
+    pub comments: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -51,9 +72,19 @@ impl ExtractionContext {
             symbol_table: HashMap::new(),
             current_scope: ScopeInfo::Module("main".to_string()),
             position_counter: 0,
+            profile: ExtractionProfile::full(),
         }
     }
     
+    pub fn with_profile(mut self, profile: ExtractionProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+    
+    pub fn profile(&self) -> &ExtractionProfile {
+        &self.profile
+    }
+    
     pub fn enter_block(&mut self, mut block: SemanticBlock) -> Uuid {
         // Set parent from stack
         block.structural_context.parent_block = self.parent_stack.last().cloned();
@@ -179,20 +210,78 @@ impl ParseResult {
     }
 }
 
+impl ExtractionProfile {
+    /// Every analysis enabled
+    pub fn full() -> Self {
+        Self {
+            semantic_metadata: true,
+            complexity: true,
+            side_effects: true,
+            generics: true,
+            debt_markers: true,
+            comments: true,
+        }
+    }
+    
+    /// Structure and relationships only, for analysis-only runs such as call graphs
+    pub fn fast() -> Self {
+        Self {
+            semantic_metadata: false,
+            complexity: false,
+            side_effects: false,
+            generics: false,
+            debt_markers: false,
+            comments: false,
+        }
+    }
+    
+    /// Look up a profile by name (`fast` or `full`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(Self::full()),
+            "fast" => Some(Self::fast()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for ExtractionProfile {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
 impl Default for ExtractionContext {
     fn default() -> Self {
         Self::new()
     }
 }
 
-// Helper trait for language extractors
-pub trait LanguageExtractor {
+/// Turns a tree-sitter tree into semantic blocks.
+///
+/// Implement this and register it with `UniversalParser::register_extractor`
+/// to add a language from outside the crate.
+pub trait LanguageExtractor: Send + Sync {
     fn extract_with_context(
         &self,
         root: tree_sitter::Node,
         source: &str,
         file_path: &str,
     ) -> anyhow::Result<ParseResult>;
+    
+    /// Extract running only the analyses enabled in `profile`.
+    ///
+    /// Extractors without optional analyses can rely on the default, which
+    /// ignores the profile.
+    fn extract_with_profile(
+        &self,
+        root: tree_sitter::Node,
+        source: &str,
+        file_path: &str,
+        _profile: &ExtractionProfile,
+    ) -> anyhow::Result<ParseResult> {
+        self.extract_with_context(root, source, file_path)
+    }
 }
 
 impl std::fmt::Display for RelationshipType {
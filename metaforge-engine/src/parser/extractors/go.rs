@@ -0,0 +1,433 @@
+use anyhow::{Result, anyhow};
+use tree_sitter::Node;
+use crate::core::*;
+use crate::parser::extraction_context::{ExtractionContext, ParseResult, RelationshipType, LanguageExtractor};
+use crate::generator::go::{
+    GoDeclaration, GoField, GoImport, GoParameter, GoValueKeyword, GoValueSpec, GO_DECLARATION_KEY,
+};
+
+pub struct GoExtractor;
+
+impl LanguageExtractor for GoExtractor {
+    fn extract_with_context(&self, root: Node, source: &str, _file_path: &str) -> Result<ParseResult> {
+        let mut context = ExtractionContext::new();
+        self.visit_with_context(root, source, &mut context)?;
+        Ok(context.finish())
+    }
+}
+
+impl GoExtractor {
+    fn visit_with_context(&self, node: Node, source: &str, ctx: &mut ExtractionContext) -> Result<()> {
+        match node.kind() {
+            "package_clause" => {
+                if let Ok(block) = self.extract_package_block(node, source) {
+                    let block_id = ctx.enter_block(block);
+                    ctx.exit_block(block_id);
+                }
+            },
+            "import_declaration" => {
+                if let Ok(block) = self.extract_import_block(node, source) {
+                    ctx.enter_block(block);
+                }
+            },
+            "function_declaration" | "method_declaration" => {
+                if let Ok(block) = self.extract_function_block(node, source) {
+                    let block_id = ctx.enter_block(block);
+                    self.extract_function_calls(node, source, block_id, ctx)?;
+                    ctx.exit_block(block_id);
+                }
+            },
+            "type_declaration" => {
+                let mut cursor = node.walk();
+                for spec in node.named_children(&mut cursor) {
+                    if !matches!(spec.kind(), "type_spec" | "type_alias") {
+                        continue;
+                    }
+                    if let Ok(block) = self.extract_type_block(spec, source) {
+                        let block_id = ctx.enter_block(block);
+                        ctx.exit_block(block_id);
+                    }
+                }
+            },
+            "var_declaration" | "const_declaration" => {
+                if let Ok(block) = self.extract_value_block(node, source) {
+                    let block_id = ctx.enter_block(block);
+                    self.extract_function_calls(node, source, block_id, ctx)?;
+                    ctx.exit_block(block_id);
+                }
+            },
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    self.visit_with_context(child, source, ctx)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn extract_package_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let name = node.named_children(&mut node.walk())
+            .find(|child| child.kind() == "package_identifier")
+            .ok_or_else(|| anyhow!("Package name not found"))?
+            .utf8_text(source.as_bytes())?
+            .to_string();
+
+        self.declaration_block(node, source, BlockType::Module, name.clone(), GoDeclaration::Package { name })
+    }
+
+    fn extract_import_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let mut spec_nodes = Vec::new();
+        let mut grouped = false;
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "import_spec" => spec_nodes.push(child),
+                "import_spec_list" => {
+                    grouped = true;
+                    let mut list_cursor = child.walk();
+                    spec_nodes.extend(child.named_children(&mut list_cursor).filter(|c| c.kind() == "import_spec"));
+                }
+                _ => {}
+            }
+        }
+
+        let specs = spec_nodes.into_iter()
+            .map(|spec| {
+                let path = spec.child_by_field_name("path")
+                    .ok_or_else(|| anyhow!("Import path not found"))?
+                    .utf8_text(source.as_bytes())?
+                    .trim_matches(|c| c == '"' || c == '`')
+                    .to_string();
+                let alias = spec.child_by_field_name("name")
+                    .map(|name| name.utf8_text(source.as_bytes()).map(str::to_string))
+                    .transpose()?;
+                Ok(GoImport { alias, path })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let name = specs.iter().map(|spec| spec.path.as_str()).collect::<Vec<_>>().join(", ");
+        self.declaration_block(node, source, BlockType::Import, name, GoDeclaration::Import { specs, grouped })
+    }
+
+    fn extract_function_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let name = self.field_text(node, "name", source)?;
+        let parameters = self.parameter_list(node.child_by_field_name("parameters"), source)?;
+        let results = self.extract_results(node, source)?;
+        let body = node.child_by_field_name("body")
+            .map(|body| self.extract_body(body, source))
+            .transpose()?;
+
+        let declaration = match node.child_by_field_name("receiver") {
+            Some(receiver) => GoDeclaration::Method {
+                receiver: self.parameter_list(Some(receiver), source)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow!("Method {} has an empty receiver", name))?,
+                name: name.clone(),
+                parameters: parameters.clone(),
+                results: results.clone(),
+                body,
+            },
+            None => GoDeclaration::Function {
+                name: name.clone(),
+                type_parameters: self.parameter_list(node.child_by_field_name("type_parameters"), source)?,
+                parameters: parameters.clone(),
+                results: results.clone(),
+                body,
+            },
+        };
+
+        let mut block = self.declaration_block(node, source, BlockType::Function, name, declaration)?;
+
+        block.semantic_metadata.parameters = parameters.iter()
+            .flat_map(|param| param.names.iter().map(move |name| (name, &param.param_type)))
+            .enumerate()
+            .map(|(position, (name, param_type))| Parameter {
+                name: name.clone(),
+                type_hint: Some(param_type.clone()),
+                default_value: None,
+                is_optional: false,
+                position,
+                default_expression: None,
+            })
+            .collect();
+
+        if !results.is_empty() {
+            let types: Vec<String> = results.iter()
+                .flat_map(|result| {
+                    let count = result.names.len().max(1);
+                    std::iter::repeat(result.param_type.clone()).take(count)
+                })
+                .collect();
+            block.semantic_metadata.return_type = Some(TypeInfo {
+                representation: if types.len() == 1 { types[0].clone() } else { format!("({})", types.join(", ")) },
+                is_generic: false,
+                generic_args: Vec::new(),
+            });
+        }
+
+        if node.kind() == "method_declaration" {
+            block.structural_context.scope = ScopeInfo::Class(self.receiver_type_name(node, source)?);
+        }
+
+        Ok(block)
+    }
+
+    fn extract_type_block(&self, spec: Node, source: &str) -> Result<SemanticBlock> {
+        let name = self.field_text(spec, "name", source)?;
+        let type_parameters = self.parameter_list(spec.child_by_field_name("type_parameters"), source)?;
+        let type_node = spec.child_by_field_name("type")
+            .ok_or_else(|| anyhow!("Type {} has no definition", name))?;
+
+        let alias = spec.kind() == "type_alias";
+        let (block_type, declaration) = match type_node.kind() {
+            "struct_type" if !alias => (BlockType::Class, GoDeclaration::Struct {
+                name: name.clone(),
+                type_parameters,
+                fields: self.extract_struct_fields(type_node, source)?,
+            }),
+            "interface_type" if !alias => {
+                let mut cursor = type_node.walk();
+                let elements = type_node.named_children(&mut cursor)
+                    .map(|element| normalize_text(element, source))
+                    .collect::<Result<Vec<_>>>()?;
+                (BlockType::Interface, GoDeclaration::Interface {
+                    name: name.clone(),
+                    type_parameters,
+                    elements,
+                })
+            }
+            _ => (BlockType::TypeDef, GoDeclaration::Type {
+                name: name.clone(),
+                type_parameters,
+                underlying: type_node.utf8_text(source.as_bytes())?.to_string(),
+                alias,
+            }),
+        };
+
+        // Attach the position of the whole `type` declaration so gofmt-style
+        // grouping is not lost when only one spec is declared
+        let node = match spec.parent() {
+            Some(parent) if parent.named_child_count() == 1 => parent,
+            _ => spec,
+        };
+
+        self.declaration_block(node, source, block_type, name, declaration)
+    }
+
+    /// A whole `var` or `const` declaration, grouped specs included, as one block
+    fn extract_value_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let keyword = match node.kind() {
+            "const_declaration" => GoValueKeyword::Const,
+            _ => GoValueKeyword::Var,
+        };
+        let grouped = node.children(&mut node.walk()).any(|child| child.kind() == "(");
+
+        let mut specs = Vec::new();
+        let mut cursor = node.walk();
+        for spec in node.named_children(&mut cursor) {
+            if !matches!(spec.kind(), "var_spec" | "const_spec") {
+                continue;
+            }
+
+            // The name field also covers the commas between names
+            let mut name_cursor = spec.walk();
+            let names = spec.children_by_field_name("name", &mut name_cursor)
+                .filter(|name| name.kind() == "identifier")
+                .map(|name| name.utf8_text(source.as_bytes()).map(str::to_string))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let value_type = spec.child_by_field_name("type")
+                .map(|value_type| normalize_text(value_type, source))
+                .transpose()?;
+
+            // Values keep their source text; function literals span lines
+            let values = match spec.child_by_field_name("value") {
+                Some(list) => {
+                    let mut list_cursor = list.walk();
+                    let values = list.named_children(&mut list_cursor)
+                        .filter(|value| value.kind() != "comment")
+                        .map(|value| value.utf8_text(source.as_bytes()).map(str::to_string))
+                        .collect::<std::result::Result<Vec<_>, _>>()?;
+                    values
+                }
+                None => Vec::new(),
+            };
+
+            specs.push(GoValueSpec { names, value_type, values });
+        }
+
+        let name = specs.iter()
+            .flat_map(|spec| spec.names.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.declaration_block(node, source, BlockType::Variable, name, GoDeclaration::Value { keyword, specs, grouped })
+    }
+
+    fn extract_struct_fields(&self, struct_type: Node, source: &str) -> Result<Vec<GoField>> {
+        let mut fields = Vec::new();
+        let Some(list) = struct_type.named_children(&mut struct_type.walk())
+            .find(|child| child.kind() == "field_declaration_list") else {
+            return Ok(fields);
+        };
+
+        let mut cursor = list.walk();
+        for field in list.named_children(&mut cursor) {
+            if field.kind() != "field_declaration" {
+                continue;
+            }
+
+            let mut name_cursor = field.walk();
+            let names = field.children_by_field_name("name", &mut name_cursor)
+                .map(|name| name.utf8_text(source.as_bytes()).map(str::to_string))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let type_node = field.child_by_field_name("type")
+                .ok_or_else(|| anyhow!("Struct field has no type"))?;
+            let mut field_type = normalize_text(type_node, source)?;
+
+            // Embedded pointers (`*Base`) keep the star outside the type node
+            if names.is_empty() && field.children(&mut field.walk()).any(|c| c.kind() == "*") {
+                field_type = format!("*{}", field_type);
+            }
+
+            let tag = field.child_by_field_name("tag")
+                .map(|tag| tag.utf8_text(source.as_bytes()).map(str::to_string))
+                .transpose()?;
+
+            fields.push(GoField { names, field_type, tag });
+        }
+
+        Ok(fields)
+    }
+
+    /// Results are either a single bare type or a parenthesized parameter list
+    fn extract_results(&self, node: Node, source: &str) -> Result<Vec<GoParameter>> {
+        match node.child_by_field_name("result") {
+            None => Ok(Vec::new()),
+            Some(result) if result.kind() == "parameter_list" => self.parameter_list(Some(result), source),
+            Some(result) => Ok(vec![GoParameter {
+                names: Vec::new(),
+                param_type: normalize_text(result, source)?,
+            }]),
+        }
+    }
+
+    fn parameter_list(&self, list: Option<Node>, source: &str) -> Result<Vec<GoParameter>> {
+        let Some(list) = list else {
+            return Ok(Vec::new());
+        };
+
+        let mut parameters = Vec::new();
+        let mut cursor = list.walk();
+        for declaration in list.named_children(&mut cursor) {
+            let variadic = match declaration.kind() {
+                "parameter_declaration" => false,
+                "variadic_parameter_declaration" => true,
+                _ => continue,
+            };
+
+            let mut name_cursor = declaration.walk();
+            let names = declaration.children_by_field_name("name", &mut name_cursor)
+                .map(|name| name.utf8_text(source.as_bytes()).map(str::to_string))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let type_node = declaration.child_by_field_name("type")
+                .ok_or_else(|| anyhow!("Parameter has no type"))?;
+            let param_type = normalize_text(type_node, source)?;
+
+            parameters.push(GoParameter {
+                names,
+                param_type: if variadic { format!("...{}", param_type) } else { param_type },
+            });
+        }
+
+        Ok(parameters)
+    }
+
+    /// Statements between the braces of a function body, one level indented
+    fn extract_body(&self, body: Node, source: &str) -> Result<String> {
+        let text = body.utf8_text(source.as_bytes())?;
+        let inner = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')).unwrap_or(text);
+
+        if inner.contains('\n') {
+            Ok(inner.trim_matches('\n').trim_end().to_string())
+        } else if inner.trim().is_empty() {
+            Ok(String::new())
+        } else {
+            Ok(format!("\t{}", inner.trim()))
+        }
+    }
+
+    fn extract_function_calls(&self, node: Node, source: &str, caller_id: uuid::Uuid, ctx: &mut ExtractionContext) -> Result<()> {
+        if node.kind() == "call_expression" {
+            if let Some(function) = node.child_by_field_name("function") {
+                let name = match function.kind() {
+                    "selector_expression" => function.child_by_field_name("field"),
+                    _ => Some(function),
+                };
+                if let Some(name) = name {
+                    ctx.add_relationship(caller_id, name.utf8_text(source.as_bytes())?, RelationshipType::Calls);
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_function_calls(child, source, caller_id, ctx)?;
+        }
+        Ok(())
+    }
+
+    fn receiver_type_name(&self, node: Node, source: &str) -> Result<String> {
+        let receiver = self.parameter_list(node.child_by_field_name("receiver"), source)?;
+        Ok(receiver.first()
+            .map(|param| {
+                let base = param.param_type.trim_start_matches('*');
+                base.split('[').next().unwrap_or(base).to_string()
+            })
+            .unwrap_or_default())
+    }
+
+    fn declaration_block(
+        &self,
+        node: Node,
+        source: &str,
+        block_type: BlockType,
+        name: String,
+        declaration: GoDeclaration,
+    ) -> Result<SemanticBlock> {
+        let text = node.utf8_text(source.as_bytes())?;
+
+        let mut block = SemanticBlock::new(block_type, name, text.to_string(), "go".to_string());
+
+        let start = node.start_position();
+        let end = node.end_position();
+        block.position = BlockPosition {
+            start_line: start.row,
+            end_line: end.row,
+            start_column: start.column,
+            end_column: end.column,
+            index: 0,
+        };
+
+        block.syntax_preservation.normalized_ast = serde_json::json!({
+            GO_DECLARATION_KEY: serde_json::to_value(&declaration)?
+        });
+
+        Ok(block)
+    }
+
+    fn field_text(&self, node: Node, field: &str, source: &str) -> Result<String> {
+        let child = node.child_by_field_name(field)
+            .ok_or_else(|| anyhow!("{} has no {}", node.kind(), field))?;
+        Ok(child.utf8_text(source.as_bytes())?.to_string())
+    }
+}
+
+/// Source text with whitespace collapsed to single spaces
+fn normalize_text(node: Node, source: &str) -> Result<String> {
+    let text = node.utf8_text(source.as_bytes())?;
+    Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
@@ -0,0 +1,516 @@
+use anyhow::{Result, anyhow};
+use tree_sitter::Node;
+use crate::core::*;
+use crate::parser::extraction_context::{ExtractionContext, ParseResult, RelationshipType, LanguageExtractor};
+use crate::generator::java::{JavaDeclaration, JavaParameter, JAVA_DECLARATION_KEY};
+
+pub struct JavaExtractor;
+
+impl LanguageExtractor for JavaExtractor {
+    fn extract_with_context(&self, root: Node, source: &str, _file_path: &str) -> Result<ParseResult> {
+        let mut context = ExtractionContext::new();
+        self.visit_with_context(root, source, None, &mut context)?;
+        Ok(context.finish())
+    }
+}
+
+/// Keywords and annotations of a `modifiers` node, kept apart
+#[derive(Default)]
+struct Modifiers {
+    annotations: Vec<String>,
+    keywords: Vec<String>,
+}
+
+impl JavaExtractor {
+    /// `owner` is the enclosing class or interface of member declarations
+    fn visit_with_context(&self, node: Node, source: &str, owner: Option<&str>, ctx: &mut ExtractionContext) -> Result<()> {
+        match node.kind() {
+            "package_declaration" => {
+                if let Ok(block) = self.extract_package_block(node, source) {
+                    let block_id = ctx.enter_block(block);
+                    ctx.exit_block(block_id);
+                }
+            },
+            "import_declaration" => {
+                if let Ok(block) = self.extract_import_block(node, source) {
+                    ctx.enter_block(block);
+                }
+            },
+            "class_declaration" | "interface_declaration" => {
+                let block = self.extract_type_block(node, source)?;
+                let name = block.semantic_identity.canonical_name.clone();
+                let declaration = JavaDeclaration::from_abstract_syntax(&block.syntax_preservation.normalized_ast);
+                let block_id = ctx.enter_block(block);
+
+                match declaration {
+                    Some(JavaDeclaration::Class { superclass, interfaces, .. }) => {
+                        if let Some(base) = superclass {
+                            ctx.add_relationship(block_id, &base, RelationshipType::Inherits);
+                        }
+                        for interface in interfaces {
+                            ctx.add_relationship(block_id, &interface, RelationshipType::Implements);
+                        }
+                    }
+                    Some(JavaDeclaration::Interface { extends, .. }) => {
+                        for base in extends {
+                            ctx.add_relationship(block_id, &base, RelationshipType::Inherits);
+                        }
+                    }
+                    _ => {}
+                }
+
+                if let Some(body) = node.child_by_field_name("body") {
+                    let mut cursor = body.walk();
+                    for member in body.named_children(&mut cursor) {
+                        self.visit_with_context(member, source, Some(&name), ctx)?;
+                    }
+                }
+                ctx.exit_block(block_id);
+            },
+            "method_declaration" | "constructor_declaration" => {
+                let block = self.extract_method_block(node, source, owner)?;
+                let block_id = ctx.enter_block(block);
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.extract_method_calls(body, source, block_id, ctx)?;
+                }
+                ctx.exit_block(block_id);
+            },
+            "field_declaration" | "constant_declaration" => {
+                if let Ok(block) = self.extract_field_block(node, source) {
+                    ctx.enter_block(block);
+                }
+            },
+            // Enums, records and annotation types are not modelled yet; keep their source whole
+            "enum_declaration" | "record_declaration" | "annotation_type_declaration" => {
+                let name = self.field_text(node, "name", source)?;
+                let block_type = if node.kind() == "enum_declaration" { BlockType::Enum } else { BlockType::Class };
+                let mut block = self.positioned_block(node, source, block_type, name)?;
+                block.syntax_preservation.normalized_ast = serde_json::json!({
+                    "implementation": { "original_text": node.utf8_text(source.as_bytes())? }
+                });
+                let block_id = ctx.enter_block(block);
+                ctx.exit_block(block_id);
+            },
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    self.visit_with_context(child, source, owner, ctx)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn extract_package_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let name = node.named_children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "identifier" | "scoped_identifier"))
+            .ok_or_else(|| anyhow!("Package name not found"))?
+            .utf8_text(source.as_bytes())?
+            .to_string();
+
+        self.declaration_block(node, source, BlockType::Module, name.clone(), JavaDeclaration::Package { name })
+    }
+
+    fn extract_import_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let mut path = node.named_children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "identifier" | "scoped_identifier"))
+            .ok_or_else(|| anyhow!("Import path not found"))?
+            .utf8_text(source.as_bytes())?
+            .to_string();
+        if node.named_children(&mut node.walk()).any(|child| child.kind() == "asterisk") {
+            path.push_str(".*");
+        }
+        let is_static = node.children(&mut node.walk()).any(|child| child.kind() == "static");
+
+        self.declaration_block(node, source, BlockType::Import, path.clone(), JavaDeclaration::Import { path, is_static })
+    }
+
+    fn extract_type_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let name = self.field_text(node, "name", source)?;
+        let modifiers = self.modifiers(node, source)?;
+        let type_parameters = node.child_by_field_name("type_parameters")
+            .map(|params| normalize_text(params, source))
+            .transpose()?;
+
+        let (block_type, declaration) = if node.kind() == "interface_declaration" {
+            let extends = node.named_children(&mut node.walk())
+                .find(|child| child.kind() == "extends_interfaces")
+                .map(|clause| self.type_list(clause, source))
+                .transpose()?
+                .unwrap_or_default();
+            (BlockType::Interface, JavaDeclaration::Interface {
+                annotations: modifiers.annotations.clone(),
+                modifiers: modifiers.keywords.clone(),
+                name: name.clone(),
+                type_parameters,
+                extends,
+            })
+        } else {
+            let superclass = node.child_by_field_name("superclass")
+                .and_then(|clause| clause.named_child(0))
+                .map(|base| normalize_text(base, source))
+                .transpose()?;
+            let interfaces = node.child_by_field_name("interfaces")
+                .map(|clause| self.type_list(clause, source))
+                .transpose()?
+                .unwrap_or_default();
+            (BlockType::Class, JavaDeclaration::Class {
+                annotations: modifiers.annotations.clone(),
+                modifiers: modifiers.keywords.clone(),
+                name: name.clone(),
+                type_parameters,
+                superclass,
+                interfaces,
+            })
+        };
+
+        let mut block = self.declaration_block(node, source, block_type, name, declaration)?;
+        self.apply_modifiers(&mut block, &modifiers);
+        block.semantic_metadata.generics = self.generics(node, source)?;
+        Ok(block)
+    }
+
+    fn extract_method_block(&self, node: Node, source: &str, owner: Option<&str>) -> Result<SemanticBlock> {
+        let name = self.field_text(node, "name", source)?;
+        let modifiers = self.modifiers(node, source)?;
+        let parameters = self.parameters(node.child_by_field_name("parameters"), source)?;
+        let throws = match node.named_children(&mut node.walk()).find(|child| child.kind() == "throws") {
+            Some(clause) => self.type_list(clause, source)?,
+            None => Vec::new(),
+        };
+        let column = node.start_position().column;
+
+        let declaration = if node.kind() == "constructor_declaration" {
+            let body = node.child_by_field_name("body")
+                .ok_or_else(|| anyhow!("Constructor {} has no body", name))?;
+            JavaDeclaration::Constructor {
+                annotations: modifiers.annotations.clone(),
+                modifiers: modifiers.keywords.clone(),
+                name: name.clone(),
+                parameters: parameters.clone(),
+                throws: throws.clone(),
+                body: self.extract_body(body, source, column)?,
+            }
+        } else {
+            let mut return_type = normalize_text(node.child_by_field_name("type")
+                .ok_or_else(|| anyhow!("Method {} has no return type", name))?, source)?;
+            if let Some(dimensions) = node.child_by_field_name("dimensions") {
+                return_type.push_str(&normalize_text(dimensions, source)?);
+            }
+            JavaDeclaration::Method {
+                annotations: modifiers.annotations.clone(),
+                modifiers: modifiers.keywords.clone(),
+                type_parameters: node.child_by_field_name("type_parameters")
+                    .map(|params| normalize_text(params, source))
+                    .transpose()?,
+                return_type,
+                name: name.clone(),
+                parameters: parameters.clone(),
+                throws: throws.clone(),
+                body: node.child_by_field_name("body")
+                    .map(|body| self.extract_body(body, source, column))
+                    .transpose()?,
+            }
+        };
+
+        let return_type = match &declaration {
+            JavaDeclaration::Method { return_type, .. } if return_type != "void" => Some(return_type.clone()),
+            _ => None,
+        };
+        let mut block = self.declaration_block(node, source, BlockType::Function, name, declaration)?;
+        self.apply_modifiers(&mut block, &modifiers);
+        block.semantic_metadata.generics = self.generics(node, source)?;
+
+        block.semantic_metadata.parameters = parameters.iter()
+            .enumerate()
+            .map(|(position, param)| Parameter {
+                name: param.name.clone(),
+                type_hint: Some(param.param_type.clone()),
+                default_value: None,
+                is_optional: false,
+                position,
+                default_expression: None,
+            })
+            .collect();
+        block.semantic_metadata.return_type = return_type.map(|representation| TypeInfo {
+            representation,
+            is_generic: false,
+            generic_args: Vec::new(),
+        });
+
+        // Declared exceptions are re-emitted; unchecked ones thrown in the
+        // body are only recorded
+        let mut thrown = throws.clone();
+        if let Some(body) = node.child_by_field_name("body") {
+            for exception in self.thrown_exceptions(body, source)? {
+                if !thrown.contains(&exception) {
+                    thrown.push(exception);
+                }
+            }
+        }
+        block.semantic_metadata.throws = thrown;
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert("throws".to_string(), serde_json::json!(throws));
+        }
+
+        if let Some(owner) = owner {
+            block.structural_context.scope = ScopeInfo::Class(owner.to_string());
+        }
+        Ok(block)
+    }
+
+    fn extract_field_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let modifiers = self.modifiers(node, source)?;
+        let field_type = normalize_text(node.child_by_field_name("type")
+            .ok_or_else(|| anyhow!("Field has no type"))?, source)?;
+
+        let mut cursor = node.walk();
+        let declarator_nodes: Vec<Node> = node.children_by_field_name("declarator", &mut cursor).collect();
+        let name = declarator_nodes.iter()
+            .filter_map(|declarator| declarator.child_by_field_name("name"))
+            .map(|name| name.utf8_text(source.as_bytes()).map(str::to_string))
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .join(", ");
+        let declarators = declarator_nodes.iter()
+            .map(|declarator| declarator.utf8_text(source.as_bytes()).map(str::to_string))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut block = self.declaration_block(node, source, BlockType::Variable, name, JavaDeclaration::Field {
+            annotations: modifiers.annotations.clone(),
+            modifiers: modifiers.keywords.clone(),
+            field_type,
+            declarators,
+        })?;
+        self.apply_modifiers(&mut block, &modifiers);
+        Ok(block)
+    }
+
+    fn modifiers(&self, node: Node, source: &str) -> Result<Modifiers> {
+        let mut modifiers = Modifiers::default();
+        let Some(list) = node.children(&mut node.walk()).find(|child| child.kind() == "modifiers") else {
+            return Ok(modifiers);
+        };
+        let mut cursor = list.walk();
+        for modifier in list.children(&mut cursor) {
+            let text = normalize_text(modifier, source)?;
+            if modifier.kind().ends_with("annotation") {
+                modifiers.annotations.push(text);
+            } else {
+                modifiers.keywords.push(text);
+            }
+        }
+        Ok(modifiers)
+    }
+
+    fn apply_modifiers(&self, block: &mut SemanticBlock, modifiers: &Modifiers) {
+        block.semantic_metadata.visibility = if modifiers.keywords.iter().any(|m| m == "public") {
+            Visibility::Public
+        } else if modifiers.keywords.iter().any(|m| m == "protected") {
+            Visibility::Protected
+        } else if modifiers.keywords.iter().any(|m| m == "private") {
+            Visibility::Private
+        } else {
+            // Package-private
+            Visibility::Internal
+        };
+        for keyword in &modifiers.keywords {
+            match keyword.as_str() {
+                "static" => block.semantic_metadata.modifiers.push(Modifier::Static),
+                "final" => block.semantic_metadata.modifiers.push(Modifier::Final),
+                "abstract" => block.semantic_metadata.modifiers.push(Modifier::Abstract),
+                _ => {}
+            }
+        }
+        if modifiers.annotations.iter().any(|annotation| annotation == "@Override") {
+            block.semantic_metadata.modifiers.push(Modifier::Override);
+        }
+    }
+
+    fn parameters(&self, list: Option<Node>, source: &str) -> Result<Vec<JavaParameter>> {
+        let Some(list) = list else {
+            return Ok(Vec::new());
+        };
+
+        let mut parameters = Vec::new();
+        let mut cursor = list.walk();
+        for parameter in list.named_children(&mut cursor) {
+            let modifiers = match parameter.named_children(&mut parameter.walk()).find(|child| child.kind() == "modifiers") {
+                Some(list) => list.children(&mut list.walk())
+                    .map(|modifier| normalize_text(modifier, source))
+                    .collect::<Result<Vec<_>>>()?,
+                None => Vec::new(),
+            };
+            match parameter.kind() {
+                "formal_parameter" => {
+                    let mut name = self.field_text(parameter, "name", source)?;
+                    // `String args[]` keeps its dimensions on the name
+                    if let Some(dimensions) = parameter.child_by_field_name("dimensions") {
+                        name.push_str(&normalize_text(dimensions, source)?);
+                    }
+                    parameters.push(JavaParameter {
+                        modifiers,
+                        param_type: normalize_text(parameter.child_by_field_name("type")
+                            .ok_or_else(|| anyhow!("Parameter {} has no type", name))?, source)?,
+                        name,
+                    });
+                }
+                "spread_parameter" => {
+                    let mut cursor = parameter.walk();
+                    let children: Vec<Node> = parameter.named_children(&mut cursor)
+                        .filter(|child| child.kind() != "modifiers")
+                        .collect();
+                    let (Some(param_type), Some(declarator)) = (children.first(), children.last()) else {
+                        continue;
+                    };
+                    parameters.push(JavaParameter {
+                        modifiers,
+                        param_type: format!("{}...", normalize_text(*param_type, source)?),
+                        name: normalize_text(*declarator, source)?,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(parameters)
+    }
+
+    /// Types named in a `throws`, `implements` or `extends` clause
+    /// Type parameters with their `extends` bounds, one bound per type
+    /// joined by `&`
+    fn generics(&self, node: Node, source: &str) -> Result<Option<GenericInfo>> {
+        let Some(params) = node.child_by_field_name("type_parameters") else {
+            return Ok(None);
+        };
+
+        let mut parameters = Vec::new();
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor).filter(|param| param.kind() == "type_parameter") {
+            let name = param.named_children(&mut param.walk())
+                .find(|child| matches!(child.kind(), "type_identifier" | "identifier"))
+                .ok_or_else(|| anyhow!("Type parameter has no name"))?;
+            let bounds = match param.named_children(&mut param.walk()).find(|child| child.kind() == "type_bound") {
+                Some(bound) => self.type_list(bound, source)?,
+                None => Vec::new(),
+            };
+            parameters.push(GenericParameter::new(normalize_text(name, source)?).with_bounds(bounds));
+        }
+        Ok(Some(GenericInfo::new(parameters)))
+    }
+
+    fn type_list(&self, clause: Node, source: &str) -> Result<Vec<String>> {
+        let list = clause.named_children(&mut clause.walk())
+            .find(|child| child.kind() == "type_list")
+            .unwrap_or(clause);
+        let mut cursor = list.walk();
+        let types = list.named_children(&mut cursor)
+            .map(|child| normalize_text(child, source))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(types)
+    }
+
+    /// Exception types of `throw new X(...)` statements, outside nested
+    /// classes and lambdas
+    fn thrown_exceptions(&self, node: Node, source: &str) -> Result<Vec<String>> {
+        let mut found = Vec::new();
+        if node.kind() == "throw_statement" {
+            let created = node.named_child(0)
+                .filter(|expression| expression.kind() == "object_creation_expression")
+                .and_then(|expression| expression.child_by_field_name("type"));
+            if let Some(exception) = created {
+                found.push(normalize_text(exception, source)?);
+            }
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if matches!(child.kind(), "class_body" | "lambda_expression") {
+                continue;
+            }
+            for exception in self.thrown_exceptions(child, source)? {
+                if !found.contains(&exception) {
+                    found.push(exception);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Statements between the braces of a body, indented relative to the
+    /// declaration that owns it
+    fn extract_body(&self, body: Node, source: &str, column: usize) -> Result<String> {
+        let text = body.utf8_text(source.as_bytes())?;
+        let inner = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')).unwrap_or(text);
+
+        if inner.contains('\n') {
+            Ok(inner.trim_matches('\n').trim_end()
+                .lines()
+                .map(|line| {
+                    let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+                    &line[indent.min(column)..]
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        } else if inner.trim().is_empty() {
+            Ok(String::new())
+        } else {
+            Ok(format!("    {}", inner.trim()))
+        }
+    }
+
+    fn extract_method_calls(&self, node: Node, source: &str, caller_id: uuid::Uuid, ctx: &mut ExtractionContext) -> Result<()> {
+        if node.kind() == "method_invocation" {
+            if let Some(name) = node.child_by_field_name("name") {
+                ctx.add_relationship(caller_id, name.utf8_text(source.as_bytes())?, RelationshipType::Calls);
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.extract_method_calls(child, source, caller_id, ctx)?;
+        }
+        Ok(())
+    }
+
+    fn declaration_block(
+        &self,
+        node: Node,
+        source: &str,
+        block_type: BlockType,
+        name: String,
+        declaration: JavaDeclaration,
+    ) -> Result<SemanticBlock> {
+        let mut block = self.positioned_block(node, source, block_type, name)?;
+        block.syntax_preservation.normalized_ast = serde_json::json!({
+            JAVA_DECLARATION_KEY: serde_json::to_value(&declaration)?
+        });
+        Ok(block)
+    }
+
+    fn positioned_block(&self, node: Node, source: &str, block_type: BlockType, name: String) -> Result<SemanticBlock> {
+        let text = node.utf8_text(source.as_bytes())?;
+
+        let mut block = SemanticBlock::new(block_type, name, text.to_string(), "java".to_string());
+
+        let start = node.start_position();
+        let end = node.end_position();
+        block.position = BlockPosition {
+            start_line: start.row,
+            end_line: end.row,
+            start_column: start.column,
+            end_column: end.column,
+            index: 0,
+        };
+        Ok(block)
+    }
+
+    fn field_text(&self, node: Node, field: &str, source: &str) -> Result<String> {
+        let child = node.child_by_field_name(field)
+            .ok_or_else(|| anyhow!("{} has no {}", node.kind(), field))?;
+        Ok(child.utf8_text(source.as_bytes())?.to_string())
+    }
+}
+
+/// Source text with whitespace collapsed to single spaces
+fn normalize_text(node: Node, source: &str) -> Result<String> {
+    let text = node.utf8_text(source.as_bytes())?;
+    Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
@@ -1,7 +1,10 @@
 use anyhow::{Result, anyhow};
+use ast_extractor::ExpressionExtractor;
 use tree_sitter::Node;
 use crate::core::*;
 use crate::parser::extraction_context::{ExtractionContext, ParseResult, LanguageExtractor};
+use crate::parser::function_body::attach_function_body;
+use crate::generator::type_declarations::{TypeDeclaration, InterfaceMember, TYPE_DECLARATION_KEY};
 
 pub struct JavaScriptExtractor {
     pub is_typescript: bool,
@@ -45,6 +48,17 @@ impl JavaScriptExtractor {
                     ctx.exit_block(block_id);
                 }
             },
+            "interface_declaration" if self.is_typescript => {
+                if let Ok(block) = self.extract_interface_block(node, source) {
+                    let block_id = ctx.enter_block(block);
+                    ctx.exit_block(block_id);
+                }
+            },
+            "type_alias_declaration" if self.is_typescript => {
+                if let Ok(block) = self.extract_type_alias_block(node, source) {
+                    ctx.enter_block(block);
+                }
+            },
             "import_statement" => {
                 if let Ok(block) = self.extract_import_block(node, source) {
                     ctx.enter_block(block);
@@ -75,6 +89,11 @@ impl JavaScriptExtractor {
             text.to_string(),
             if self.is_typescript { "typescript" } else { "javascript" }.to_string(),
         );
+        block.semantic_metadata.visibility = export_visibility(node);
+        block.semantic_metadata.parameters = self.extract_parameters(node, source)?;
+        block.semantic_metadata.return_type = self.extract_return_type(node, source)?;
+        block.semantic_metadata.generics = self.extract_generics(node, source)?;
+        attach_function_body(node, source, if self.is_typescript { "typescript" } else { "javascript" }, &mut block)?;
         
         let start = node.start_position();
         let end = node.end_position();
@@ -99,6 +118,8 @@ impl JavaScriptExtractor {
             text.to_string(),
             if self.is_typescript { "typescript" } else { "javascript" }.to_string(),
         );
+        block.semantic_metadata.visibility = export_visibility(node);
+        block.semantic_metadata.generics = self.extract_generics(node, source)?;
         
         let start = node.start_position();
         let end = node.end_position();
@@ -123,6 +144,10 @@ impl JavaScriptExtractor {
             text.to_string(),
             if self.is_typescript { "typescript" } else { "javascript" }.to_string(),
         );
+        block.semantic_metadata.parameters = self.extract_parameters(node, source)?;
+        block.semantic_metadata.return_type = self.extract_return_type(node, source)?;
+        block.semantic_metadata.generics = self.extract_generics(node, source)?;
+        attach_function_body(node, source, if self.is_typescript { "typescript" } else { "javascript" }, &mut block)?;
         
         let start = node.start_position();
         let end = node.end_position();
@@ -161,6 +186,213 @@ impl JavaScriptExtractor {
         Ok(block)
     }
     
+    fn extract_interface_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let name = self.field_text(node, "name", source)?;
+        
+        let extends = node.children(&mut node.walk())
+            .filter(|child| matches!(child.kind(), "extends_type_clause" | "extends_clause"))
+            .flat_map(|clause| {
+                let mut cursor = clause.walk();
+                clause.named_children(&mut cursor).collect::<Vec<_>>()
+            })
+            .map(|ty| normalize_type_text(ty, source))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let mut members = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                match member.kind() {
+                    "property_signature" => members.push(self.extract_property_signature(member, source)?),
+                    "method_signature" | "call_signature" | "construct_signature" | "index_signature" => {
+                        members.push(InterfaceMember::Signature { text: normalize_type_text(member, source)? });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        
+        let declaration = TypeDeclaration::Interface {
+            name: name.clone(),
+            exported: is_exported(node),
+            type_parameters: self.extract_type_parameters(node, source)?,
+            extends,
+            members,
+        };
+        
+        self.type_declaration_block(node, source, BlockType::Interface, name, declaration)
+    }
+    
+    fn extract_type_alias_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let name = self.field_text(node, "name", source)?;
+        let value = node.child_by_field_name("value")
+            .ok_or_else(|| anyhow!("Type alias {} has no value", name))?;
+        
+        let mut union_members = Vec::new();
+        collect_union_members(value, source, &mut union_members)?;
+        
+        let declaration = TypeDeclaration::TypeAlias {
+            name: name.clone(),
+            exported: is_exported(node),
+            type_parameters: self.extract_type_parameters(node, source)?,
+            union_members,
+        };
+        
+        self.type_declaration_block(node, source, BlockType::TypeDef, name, declaration)
+    }
+    
+    fn type_declaration_block(
+        &self,
+        node: Node,
+        source: &str,
+        block_type: BlockType,
+        name: String,
+        declaration: TypeDeclaration,
+    ) -> Result<SemanticBlock> {
+        let text = node.utf8_text(source.as_bytes())?;
+        
+        let mut block = SemanticBlock::new(block_type, name, text.to_string(), "typescript".to_string());
+        block.semantic_metadata.visibility = export_visibility(node);
+        
+        let start = node.start_position();
+        let end = node.end_position();
+        block.position = BlockPosition {
+            start_line: start.row,
+            end_line: end.row,
+            start_column: start.column,
+            end_column: end.column,
+            index: 0,
+        };
+        
+        block.syntax_preservation.normalized_ast = serde_json::json!({
+            TYPE_DECLARATION_KEY: serde_json::to_value(&declaration)?
+        });
+        
+        Ok(block)
+    }
+    
+    fn extract_property_signature(&self, node: Node, source: &str) -> Result<InterfaceMember> {
+        let mut optional = false;
+        let mut readonly = false;
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "?" => optional = true,
+                "readonly" => readonly = true,
+                _ => {}
+            }
+        }
+        
+        // The `type` field is a type_annotation (`: T`); keep just the type
+        let type_annotation = node.child_by_field_name("type")
+            .and_then(|annotation| annotation.named_child(0))
+            .map(|ty| normalize_type_text(ty, source))
+            .transpose()?;
+        
+        Ok(InterfaceMember::Property {
+            name: self.field_text(node, "name", source)?,
+            type_annotation,
+            optional,
+            readonly,
+        })
+    }
+    
+    fn extract_type_parameters(&self, node: Node, source: &str) -> Result<Vec<String>> {
+        let Some(params) = node.child_by_field_name("type_parameters") else {
+            return Ok(Vec::new());
+        };
+        
+        let mut cursor = params.walk();
+        let type_parameters = params.named_children(&mut cursor)
+            .map(|param| normalize_type_text(param, source))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(type_parameters)
+    }
+    
+    /// Type parameters of a class, function or method with their `extends`
+    /// constraint and default
+    fn extract_generics(&self, node: Node, source: &str) -> Result<Option<GenericInfo>> {
+        let Some(params) = node.child_by_field_name("type_parameters") else {
+            return Ok(None);
+        };
+        
+        // `constraint` and `value` wrap the type in `extends`/`=`
+        let type_of = |wrapper: Option<Node>| wrapper
+            .and_then(|wrapper| wrapper.named_child(0))
+            .map(|ty| normalize_type_text(ty, source))
+            .transpose();
+        
+        let mut parameters = Vec::new();
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor).filter(|param| param.kind() == "type_parameter") {
+            let bounds = type_of(param.child_by_field_name("constraint"))?.into_iter().collect();
+            parameters.push(GenericParameter::new(self.field_text(param, "name", source)?)
+                .with_bounds(bounds)
+                .with_default(type_of(param.child_by_field_name("value"))?));
+        }
+        Ok(Some(GenericInfo::new(parameters)))
+    }
+    
+    /// Parameters in declaration order. Rest and destructuring parameters
+    /// keep their source form (`...rest`, `{ a, b }`); defaults keep their
+    /// expression structure.
+    fn extract_parameters(&self, node: Node, source: &str) -> Result<Vec<Parameter>> {
+        // `x => x * 2` has a lone parameter instead of a list
+        if let Some(param) = node.child_by_field_name("parameter") {
+            return Ok(vec![Parameter::new(param.utf8_text(source.as_bytes())?.to_string(), 0)]);
+        }
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return Ok(Vec::new());
+        };
+        
+        let expressions = ExpressionExtractor::new();
+        let mut parameters = Vec::new();
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            // TypeScript wraps every parameter to carry its annotation
+            let (pattern, default) = match param.kind() {
+                "assignment_pattern" => (param.child_by_field_name("left"), param.child_by_field_name("right")),
+                "required_parameter" | "optional_parameter" => {
+                    (param.child_by_field_name("pattern"), param.child_by_field_name("value"))
+                }
+                "comment" => continue,
+                _ => (Some(param), None),
+            };
+            let Some(pattern) = pattern else { continue };
+            
+            let mut parameter = Parameter::new(pattern.utf8_text(source.as_bytes())?.to_string(), parameters.len());
+            if let Some(ty) = param.child_by_field_name("type").and_then(|annotation| annotation.named_child(0)) {
+                parameter = parameter.with_type(type_text(ty, source)?);
+            }
+            if let Some(default) = default {
+                parameter = parameter.with_default(expressions.extract_expression(default, source)?);
+            }
+            parameter.is_optional |= param.kind() == "optional_parameter";
+            parameters.push(parameter);
+        }
+        Ok(parameters)
+    }
+    
+    /// Declared return type, e.g. `string | null` for `function f(): string | null`
+    fn extract_return_type(&self, node: Node, source: &str) -> Result<Option<TypeInfo>> {
+        // The annotation wraps the type after its `:`, including type
+        // predicates (`x is string`) and assertions (`asserts x`)
+        let Some(ty) = node.child_by_field_name("return_type").and_then(|annotation| annotation.named_child(0)) else {
+            return Ok(None);
+        };
+        Ok(Some(TypeInfo {
+            representation: type_text(ty, source)?,
+            is_generic: ty.kind() == "generic_type",
+            generic_args: Vec::new(),
+        }))
+    }
+    
+    fn field_text(&self, node: Node, field: &str, source: &str) -> Result<String> {
+        let child = node.child_by_field_name(field)
+            .ok_or_else(|| anyhow!("{} has no {}", node.kind(), field))?;
+        Ok(child.utf8_text(source.as_bytes())?.to_string())
+    }
+    
     fn extract_function_name(&self, node: Node, source: &str) -> Result<String> {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
@@ -172,9 +404,10 @@ impl JavaScriptExtractor {
     }
 
     fn extract_class_name(&self, node: Node, source: &str) -> Result<String> {
+        // TypeScript names classes with a `type_identifier`
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "identifier" {
+            if matches!(child.kind(), "identifier" | "type_identifier") {
                 return Ok(child.utf8_text(source.as_bytes())?.to_string());
             }
         }
@@ -203,4 +436,56 @@ impl JavaScriptExtractor {
         }
         Ok("unknown_import".to_string())
     }
-}
\ No newline at end of file
+}
+/// Whether the declaration sits under an `export` statement, looking through
+/// the declarator of `export const f = () => …`
+fn is_exported(node: Node) -> bool {
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        match parent.kind() {
+            "export_statement" => return true,
+            "variable_declarator" | "lexical_declaration" | "variable_declaration" => current = parent,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Module-level visibility: `Public` when exported, `Private` otherwise
+fn export_visibility(node: Node) -> Visibility {
+    if is_exported(node) { Visibility::Public } else { Visibility::Private }
+}
+
+/// Flatten nested `union_type` nodes (`A | B | C` parses as `(A | B) | C`)
+fn collect_union_members(node: Node, source: &str, members: &mut Vec<String>) -> Result<()> {
+    if node.kind() == "union_type" {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            collect_union_members(child, source, members)?;
+        }
+    } else {
+        members.push(normalize_type_text(node, source)?);
+    }
+    Ok(())
+}
+
+/// A type as written, with union (`A | B`) and intersection (`A & B`)
+/// members rejoined so a multi-line union loses its leading `|`
+fn type_text(node: Node, source: &str) -> Result<String> {
+    let separator = match node.kind() {
+        "union_type" => " | ",
+        "intersection_type" => " & ",
+        _ => return normalize_type_text(node, source),
+    };
+    let mut cursor = node.walk();
+    let members = node.named_children(&mut cursor)
+        .map(|member| type_text(member, source))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(members.join(separator))
+}
+
+/// Source text of a type with whitespace collapsed to single spaces
+fn normalize_type_text(node: Node, source: &str) -> Result<String> {
+    let text = node.utf8_text(source.as_bytes())?;
+    Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
@@ -1,7 +1,11 @@
 pub mod python;
 pub mod javascript;
 pub mod rust;
+pub mod go;
+pub mod java;
 
 pub use python::PythonExtractor;
 pub use javascript::JavaScriptExtractor;
 pub use rust::RustExtractor;
+pub use go::GoExtractor;
+pub use java::JavaExtractor;
@@ -1,13 +1,21 @@
 use anyhow::{Result, anyhow};
+use ast_extractor::ExpressionExtractor;
 use tree_sitter::Node;
 use crate::core::*;
-use crate::parser::extraction_context::{ExtractionContext, ParseResult, RelationshipType, LanguageExtractor};
+use crate::generator::python_members::{PropertyAccessor, PythonMember, PYTHON_MEMBER_KEY};
+use crate::generator::python_types::{PythonTypeDeclaration, TypedField, PYTHON_TYPE_KEY};
+use crate::parser::extraction_context::{ExtractionContext, ExtractionProfile, ParseResult, RelationshipType, LanguageExtractor};
+use crate::parser::function_body::attach_function_body;
 
 pub struct PythonExtractor;
 
 impl LanguageExtractor for PythonExtractor {
-    fn extract_with_context(&self, root: Node, source: &str, _file_path: &str) -> Result<ParseResult> {
-        let mut context = ExtractionContext::new();
+    fn extract_with_context(&self, root: Node, source: &str, file_path: &str) -> Result<ParseResult> {
+        self.extract_with_profile(root, source, file_path, &ExtractionProfile::full())
+    }
+    
+    fn extract_with_profile(&self, root: Node, source: &str, _file_path: &str, profile: &ExtractionProfile) -> Result<ParseResult> {
+        let mut context = ExtractionContext::new().with_profile(profile.clone());
         self.visit_with_context(root, source, &mut context)?;
         Ok(context.finish())
     }
@@ -22,25 +30,50 @@ impl PythonExtractor {
     
     fn visit_with_context(&self, node: Node, source: &str, ctx: &mut ExtractionContext) -> Result<()> {
         match node.kind() {
+            "module" => {
+                // Code between the declarations becomes blocks in its place
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    match self.extract_statement_block(child, source)? {
+                        Some(block) => {
+                            ctx.enter_block(block);
+                        }
+                        None => self.visit_with_context(child, source, ctx)?,
+                    }
+                }
+            },
             "function_definition" => {
-                let block = self.extract_function_block(node, source)?;
+                let mut block = self.extract_function_block(node, source, ctx.profile())?;
+                let property = self.extract_property_member(node, source)?;
+                let is_property = property.is_some();
+                if let (Some(member), Some(ast)) = (property, block.syntax_preservation.normalized_ast.as_object_mut()) {
+                    ast.insert(PYTHON_MEMBER_KEY.to_string(), serde_json::to_value(member)?);
+                }
                 let block_id = ctx.enter_block(block);
                 
                 // Extract function body and find calls
                 self.extract_function_calls(node, source, block_id, ctx)?;
                 
-                // Visit children for nested functions
-                let mut cursor = node.walk();
-                for child in node.children(&mut cursor) {
-                    if child.kind() == "block" {
-                        self.visit_with_context(child, source, ctx)?;
+                // Visit children for nested functions; property bodies are
+                // kept whole on the member instead
+                if !is_property {
+                    let mut cursor = node.walk();
+                    for child in node.children(&mut cursor) {
+                        if child.kind() == "block" {
+                            self.visit_with_context(child, source, ctx)?;
+                        }
                     }
                 }
                 
                 ctx.exit_block(block_id);
             },
             "class_definition" => {
-                let block = self.extract_class_block(node, source)?;
+                // `TypedDict` and `NamedTuple` keep their fields on the declaration
+                if let Some(declaration) = self.extract_typed_class(node, source)? {
+                    ctx.enter_block(self.type_declaration_block(node, source, declaration)?);
+                    return Ok(());
+                }
+                let block = self.extract_class_block(node, source, ctx.profile())?;
                 let block_id = ctx.enter_block(block);
                 
                 // Extract inheritance relationships
@@ -64,8 +97,18 @@ impl PythonExtractor {
                 let block = self.extract_import_block(node, source)?;
                 ctx.enter_block(block);
             },
+            "type_alias_statement" => {
+                if let Some(declaration) = self.extract_type_alias(node, source)? {
+                    ctx.enter_block(self.type_declaration_block(node, source, declaration)?);
+                }
+            },
             "assignment" => {
-                if let Some(block) = self.extract_variable_block(node, source)? {
+                if let Some(declaration) = self.extract_type_alias(node, source)? {
+                    ctx.enter_block(self.type_declaration_block(node, source, declaration)?);
+                } else if let Some(mut block) = self.extract_variable_block(node, source)? {
+                    if let (Some(member), Some(ast)) = (self.extract_class_attribute(node, source)?, block.syntax_preservation.normalized_ast.as_object_mut()) {
+                        ast.insert(PYTHON_MEMBER_KEY.to_string(), serde_json::to_value(member)?);
+                    }
                     ctx.enter_block(block);
                 }
             },
@@ -80,12 +123,9 @@ impl PythonExtractor {
         Ok(())
     }
 
-    fn extract_function_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+    fn extract_function_block(&self, node: Node, source: &str, profile: &ExtractionProfile) -> Result<SemanticBlock> {
         let name = self.extract_function_name(node, source)?;
         let text = node.utf8_text(source.as_bytes())?;
-        let parameters = self.extract_function_parameters(node, source)?;
-        let return_type = self.extract_return_type(node, source)?;
-        let decorators = self.extract_decorators(node, source)?;
         
         let mut block = SemanticBlock::new(
             BlockType::Function,
@@ -94,20 +134,29 @@ impl PythonExtractor {
             "python".to_string(),
         );
 
-        // Set parameters
-        block.semantic_metadata.parameters = parameters;
-        
-        // Set return type
-        if let Some(ret_type) = return_type {
-            block.semantic_metadata.return_type = Some(TypeInfo {
-                representation: ret_type,
-                is_generic: false,
-                generic_args: vec![],
-            });
+        if profile.semantic_metadata {
+            // Set parameters
+            block.semantic_metadata.parameters = self.extract_function_parameters(node, source)?;
+            
+            // Set return type
+            if let Some(ret_type) = self.extract_return_type(node, source)? {
+                block.semantic_metadata.return_type = Some(TypeInfo {
+                    representation: ret_type,
+                    is_generic: false,
+                    generic_args: vec![],
+                });
+            }
+            
+            // Set decorators
+            block.structural_context.decorators = self.extract_decorators(node, source)?;
+            
+            // Extract modifiers
+            if self.is_async_function(node, source)? {
+                block.semantic_metadata.modifiers.push(Modifier::Async);
+            }
+            
+            block.semantic_metadata.throws = self.extract_raised_exceptions(node, source)?;
         }
-        
-        // Set decorators
-        block.structural_context.decorators = decorators;
 
         // Set position
         let start = node.start_position();
@@ -120,24 +169,19 @@ impl PythonExtractor {
             index: 0, // Will be set by context
         };
         
-        // Extract modifiers
-        if self.is_async_function(node, source)? {
-            block.semantic_metadata.modifiers.push(Modifier::Async);
-        }
-        
         // ✅ ENHANCED: Preserve implementation details in normalized_ast
-        let implementation_details = self.extract_implementation_details(node, source)?;
+        let implementation_details = self.extract_implementation_details(node, source, profile)?;
         block.syntax_preservation.normalized_ast = serde_json::json!({
             "implementation": implementation_details
         });
+        attach_function_body(node, source, "python", &mut block)?;
         
         Ok(block)
     }
     
-    fn extract_class_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+    fn extract_class_block(&self, node: Node, source: &str, profile: &ExtractionProfile) -> Result<SemanticBlock> {
         let name = self.extract_class_name(node, source)?;
         let text = node.utf8_text(source.as_bytes())?;
-        let decorators = self.extract_decorators(node, source)?;
         
         let mut block = SemanticBlock::new(
             BlockType::Class,
@@ -147,7 +191,9 @@ impl PythonExtractor {
         );
 
         // Set decorators
-        block.structural_context.decorators = decorators;
+        if profile.semantic_metadata {
+            block.structural_context.decorators = self.extract_decorators(node, source)?;
+        }
 
         // Set position
         let start = node.start_position();
@@ -222,6 +268,227 @@ impl PythonExtractor {
         }
     }
     
+    /// A module-level statement that isn't a declaration: a call, a
+    /// compound statement such as an `if __name__ == "__main__":` guard, or
+    /// an assignment to something other than a plain name. Compound
+    /// statements are kept whole, definitions inside them included.
+    fn extract_statement_block(&self, node: Node, source: &str) -> Result<Option<SemanticBlock>> {
+        let is_statement = match node.kind() {
+            "expression_statement" => match node.named_child(0) {
+                // `name = value` is a variable; `app.debug = True` and
+                // `a, b = pair` run like any other statement
+                Some(assignment) if assignment.kind() == "assignment" => !matches!(
+                    assignment.child_by_field_name("left"),
+                    Some(left) if left.kind() == "identifier"
+                ),
+                Some(_) => true,
+                None => false,
+            },
+            "if_statement" | "for_statement" | "while_statement" | "try_statement" | "with_statement"
+            | "match_statement" | "assert_statement" | "raise_statement" | "delete_statement"
+            | "global_statement" | "pass_statement" | "print_statement" | "exec_statement" => true,
+            _ => false,
+        };
+        if !is_statement {
+            return Ok(None);
+        }
+        
+        let text = node.utf8_text(source.as_bytes())?;
+        let name = text.lines().next().unwrap_or_default().trim().to_string();
+        let mut block = SemanticBlock::new(
+            BlockType::Statement,
+            name,
+            text.to_string(),
+            "python".to_string(),
+        );
+        
+        let start = node.start_position();
+        let end = node.end_position();
+        block.position = BlockPosition {
+            start_line: start.row,
+            end_line: end.row,
+            start_column: start.column,
+            end_column: end.column,
+            index: 0, // Will be set by context
+        };
+        block.syntax_preservation.normalized_ast = serde_json::json!({
+            "implementation": {"original_text": text}
+        });
+        
+        Ok(Some(block))
+    }
+    
+    /// A `type` statement, a `TypeAlias`-annotated assignment or a
+    /// `NewType` call assigned to a name. Assignments in class bodies stay
+    /// class attributes.
+    fn extract_type_alias(&self, node: Node, source: &str) -> Result<Option<PythonTypeDeclaration>> {
+        if node.kind() == "type_alias_statement" {
+            let (Some(left), Some(value)) = (node.named_child(0), node.named_child(1)) else {
+                return Ok(None);
+            };
+            // `Vector[T]` is a generic type with the parameters in brackets
+            let left = left.named_child(0).unwrap_or(left);
+            let (name, type_parameters) = match (left.kind(), left.named_child(0), left.named_child(1)) {
+                ("generic_type", Some(name), Some(parameters)) => {
+                    let mut cursor = parameters.walk();
+                    let type_parameters = parameters.named_children(&mut cursor)
+                        .map(|parameter| parameter.utf8_text(source.as_bytes()).map(str::to_string))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    (name.utf8_text(source.as_bytes())?.to_string(), type_parameters)
+                }
+                _ => (left.utf8_text(source.as_bytes())?.to_string(), Vec::new()),
+            };
+            return Ok(Some(PythonTypeDeclaration::Alias {
+                name,
+                type_parameters,
+                value: value.utf8_text(source.as_bytes())?.to_string(),
+                annotation: None,
+            }));
+        }
+        
+        if self.enclosing_class(node).is_some() {
+            return Ok(None);
+        }
+        let (Some(left), Some(right)) = (node.child_by_field_name("left"), node.child_by_field_name("right")) else {
+            return Ok(None);
+        };
+        if left.kind() != "identifier" {
+            return Ok(None);
+        }
+        let name = left.utf8_text(source.as_bytes())?.to_string();
+        
+        if let Some(annotation) = self.field_text(node, "type", source)? {
+            if last_segment(&annotation) != "TypeAlias" {
+                return Ok(None);
+            }
+            return Ok(Some(PythonTypeDeclaration::Alias {
+                name,
+                type_parameters: Vec::new(),
+                value: right.utf8_text(source.as_bytes())?.to_string(),
+                annotation: Some(annotation),
+            }));
+        }
+        
+        let Some(function) = self.field_text(right, "function", source)?.filter(|function| last_segment(function) == "NewType") else {
+            return Ok(None);
+        };
+        let mut arguments = Vec::new();
+        if let Some(argument_list) = right.child_by_field_name("arguments") {
+            let mut cursor = argument_list.walk();
+            arguments.extend(argument_list.named_children(&mut cursor).filter(|argument| argument.kind() != "comment"));
+        }
+        let [name_argument, base] = arguments.as_slice() else {
+            return Ok(None);
+        };
+        Ok(Some(PythonTypeDeclaration::NewType {
+            name,
+            function,
+            name_argument: name_argument.utf8_text(source.as_bytes())?.to_string(),
+            base: base.utf8_text(source.as_bytes())?.to_string(),
+        }))
+    }
+    
+    /// A `TypedDict` or `NamedTuple` class whose body is only a docstring
+    /// and annotated fields. Classes with methods or decorators stay classes.
+    fn extract_typed_class(&self, node: Node, source: &str) -> Result<Option<PythonTypeDeclaration>> {
+        if node.parent().map(|parent| parent.kind()) == Some("decorated_definition") {
+            return Ok(None);
+        }
+        let (Some(superclasses), Some(body)) = (node.child_by_field_name("superclasses"), node.child_by_field_name("body")) else {
+            return Ok(None);
+        };
+        
+        let mut bases = Vec::new();
+        let mut kind = None;
+        let mut total = true;
+        let mut cursor = superclasses.walk();
+        for base in superclasses.named_children(&mut cursor) {
+            let text = base.utf8_text(source.as_bytes())?;
+            match base.kind() {
+                "comment" => continue,
+                "keyword_argument" => {
+                    if self.field_text(base, "name", source)?.as_deref() == Some("total") {
+                        total = self.field_text(base, "value", source)?.as_deref() != Some("False");
+                    }
+                }
+                _ => match last_segment(text) {
+                    "TypedDict" | "NamedTuple" => kind = Some(last_segment(text).to_string()),
+                    _ => {}
+                },
+            }
+            bases.push(text.to_string());
+        }
+        let Some(kind) = kind else {
+            return Ok(None);
+        };
+        
+        let mut docstring = None;
+        let mut fields = Vec::new();
+        let mut cursor = body.walk();
+        for (index, statement) in body.named_children(&mut cursor).filter(|child| child.kind() != "comment").enumerate() {
+            let inner = match statement.kind() {
+                "pass_statement" => continue,
+                "expression_statement" => statement.named_child(0),
+                _ => return Ok(None),
+            };
+            match inner {
+                Some(string) if string.kind() == "string" && index == 0 => {
+                    // Later docstring lines still carry the body's indentation
+                    let column = body.start_position().column;
+                    let lines: Vec<&str> = string.utf8_text(source.as_bytes())?
+                        .lines()
+                        .map(|line| strip_indentation(line, column).trim_end())
+                        .collect();
+                    docstring = Some(lines.join("\n"));
+                }
+                Some(ellipsis) if ellipsis.kind() == "ellipsis" => {}
+                Some(assignment) if assignment.kind() == "assignment" => {
+                    let (Some(name), Some(annotation)) = (self.field_text(assignment, "left", source)?, self.field_text(assignment, "type", source)?) else {
+                        return Ok(None);
+                    };
+                    let default = self.field_text(assignment, "right", source)?;
+                    fields.push(match kind.as_str() {
+                        "TypedDict" if default.is_none() => TypedField::typed_dict_key(name, annotation, total),
+                        "TypedDict" => return Ok(None),
+                        _ => TypedField::named_tuple_field(name, annotation, default),
+                    });
+                }
+                _ => return Ok(None),
+            }
+        }
+        
+        let name = self.extract_class_name(node, source)?;
+        Ok(Some(match kind.as_str() {
+            "TypedDict" => PythonTypeDeclaration::TypedDict { name, bases, docstring, fields },
+            _ => PythonTypeDeclaration::NamedTuple { name, bases, docstring, fields },
+        }))
+    }
+    
+    fn type_declaration_block(&self, node: Node, source: &str, declaration: PythonTypeDeclaration) -> Result<SemanticBlock> {
+        let text = node.utf8_text(source.as_bytes())?;
+        let mut block = SemanticBlock::new(
+            BlockType::TypeDef,
+            declaration.name().to_string(),
+            text.to_string(),
+            "python".to_string(),
+        );
+        
+        let start = node.start_position();
+        let end = node.end_position();
+        block.position = BlockPosition {
+            start_line: start.row,
+            end_line: end.row,
+            start_column: start.column,
+            end_column: end.column,
+            index: 0, // Will be set by context
+        };
+        block.syntax_preservation.normalized_ast = serde_json::json!({
+            PYTHON_TYPE_KEY: serde_json::to_value(&declaration)?
+        });
+        
+        Ok(block)
+    }
+    
     fn extract_function_calls(&self, node: Node, source: &str, caller_id: uuid::Uuid, ctx: &mut ExtractionContext) -> Result<()> {
         // Walk the function body looking for calls
         self.find_calls_recursive(node, source, caller_id, ctx)?;
@@ -300,29 +567,39 @@ impl PythonExtractor {
         Ok(None)
     }
 
+    /// Parameters in declaration order. Splats and the bare `*` / `/`
+    /// separators keep their markers in the name (`*args`, `**kwargs`, `*`)
+    /// so the signature can be rebuilt as written; defaults keep their
+    /// expression structure.
     fn extract_function_parameters(&self, node: Node, source: &str) -> Result<Vec<Parameter>> {
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return Ok(Vec::new());
+        };
+        let expressions = ExpressionExtractor::new();
         let mut parameters = Vec::new();
-        let mut cursor = node.walk();
-        
-        for child in node.children(&mut cursor) {
-            if child.kind() == "parameters" {
-                let mut param_cursor = child.walk();
-                for param_child in child.children(&mut param_cursor) {
-                    if param_child.kind() == "identifier" {
-                        let name = param_child.utf8_text(source.as_bytes())?.to_string();
-                        parameters.push(Parameter {
-                            name,
-                            type_hint: None,
-                            default_value: None,
-                            is_optional: false,
-                            position: parameters.len(),
-                        });
-                    }
+        let mut cursor = params.walk();
+
+        for param in params.named_children(&mut cursor) {
+            let (name_node, default_node) = match param.kind() {
+                "default_parameter" | "typed_default_parameter" => {
+                    (param.child_by_field_name("name"), param.child_by_field_name("value"))
                 }
-                break;
+                "typed_parameter" => (param.named_child(0), None),
+                "comment" => continue,
+                _ => (Some(param), None),
+            };
+            let Some(name_node) = name_node else { continue };
+
+            let mut parameter = Parameter::new(name_node.utf8_text(source.as_bytes())?.to_string(), parameters.len());
+            if let Some(type_hint) = self.field_text(param, "type", source)? {
+                parameter = parameter.with_type(type_hint);
+            }
+            if let Some(default_node) = default_node {
+                parameter = parameter.with_default(expressions.extract_expression(default_node, source)?);
             }
+            parameters.push(parameter);
         }
-        
+
         Ok(parameters)
     }
     
@@ -336,11 +613,13 @@ impl PythonExtractor {
         Ok(None)
     }
 
+    /// Decorators sit on the enclosing `decorated_definition`, not the definition itself
     fn extract_decorators(&self, node: Node, source: &str) -> Result<Vec<Decorator>> {
         let mut decorators = Vec::new();
-        let mut cursor = node.walk();
+        let decorated = node.parent().filter(|parent| parent.kind() == "decorated_definition").unwrap_or(node);
+        let mut cursor = decorated.walk();
         
-        for child in node.children(&mut cursor) {
+        for child in decorated.children(&mut cursor) {
             if child.kind() == "decorator" {
                 let text = child.utf8_text(source.as_bytes())?;
                 let name = text.trim_start_matches('@').to_string();
@@ -355,6 +634,77 @@ impl PythonExtractor {
         Ok(decorators)
     }
     
+    /// The class whose body directly contains `node`, looking through a
+    /// wrapping `decorated_definition` or `expression_statement`
+    fn enclosing_class<'t>(&self, node: Node<'t>) -> Option<Node<'t>> {
+        let mut current = node.parent()?;
+        if matches!(current.kind(), "decorated_definition" | "expression_statement") {
+            current = current.parent()?;
+        }
+        let class = current.parent()?;
+        (current.kind() == "block" && class.kind() == "class_definition").then_some(class)
+    }
+    
+    fn extract_property_member(&self, node: Node, source: &str) -> Result<Option<PythonMember>> {
+        if self.enclosing_class(node).is_none() {
+            return Ok(None);
+        }
+        let decorators: Vec<String> = self.extract_decorators(node, source)?
+            .into_iter()
+            .map(|decorator| decorator.name.trim().to_string())
+            .collect();
+        let Some(accessor) = PropertyAccessor::from_decorators(&decorators) else {
+            return Ok(None);
+        };
+        let parameters = self.field_text(node, "parameters", source)?.unwrap_or_default();
+        
+        // Later body lines still carry the body's indentation; strip it
+        let body = match node.child_by_field_name("body") {
+            Some(body) => {
+                let column = body.start_position().column;
+                body.utf8_text(source.as_bytes())?
+                    .lines()
+                    .map(|line| strip_indentation(line, column).trim_end().to_string())
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        
+        Ok(Some(PythonMember::Property {
+            name: self.extract_function_name(node, source)?,
+            accessor,
+            decorators,
+            parameters: parameters.trim_start_matches('(').trim_end_matches(')').to_string(),
+            return_type: self.field_text(node, "return_type", source)?,
+            is_async: self.is_async_function(node, source)?,
+            body,
+        }))
+    }
+    
+    fn extract_class_attribute(&self, node: Node, source: &str) -> Result<Option<PythonMember>> {
+        if self.enclosing_class(node).is_none() {
+            return Ok(None);
+        }
+        // Only simple names are attributes; `self.x = ...` never appears here
+        let name = match node.child_by_field_name("left") {
+            Some(left) if left.kind() == "identifier" => left.utf8_text(source.as_bytes())?.to_string(),
+            _ => return Ok(None),
+        };
+        
+        Ok(Some(PythonMember::ClassAttribute {
+            name,
+            annotation: self.field_text(node, "type", source)?,
+            value: self.field_text(node, "right", source)?,
+        }))
+    }
+    
+    fn field_text(&self, node: Node, field: &str, source: &str) -> Result<Option<String>> {
+        match node.child_by_field_name(field) {
+            Some(child) => Ok(Some(child.utf8_text(source.as_bytes())?.to_string())),
+            None => Ok(None),
+        }
+    }
+    
     fn extract_base_classes(&self, node: Node, source: &str) -> Result<Option<Vec<String>>> {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
@@ -380,20 +730,22 @@ impl PythonExtractor {
     }
     
     // ✅ NEW: Extract comprehensive implementation details
-    fn extract_implementation_details(&self, node: Node, source: &str) -> Result<serde_json::Value> {
-        let original_body = self.extract_function_body(node, source)?;
-        let variable_assignments = self.extract_variable_assignments(node, source)?;
-        let control_flow = self.extract_control_flow_info(node, source)?;
-        let return_statements = self.extract_return_statements(node, source)?;
-        let function_calls = self.extract_function_calls_detailed(node, source)?;
+    /// The body is always kept; the analyses are skipped when the profile disables them
+    fn extract_implementation_details(&self, node: Node, source: &str, profile: &ExtractionProfile) -> Result<serde_json::Value> {
+        let mut details = serde_json::json!({
+            "original_body": self.extract_function_body(node, source)?,
+            "return_statements": self.extract_return_statements(node, source)?,
+        });
         
-        Ok(serde_json::json!({
-            "original_body": original_body,
-            "variable_assignments": variable_assignments,
-            "control_flow": control_flow,
-            "return_statements": return_statements,
-            "function_calls": function_calls
-        }))
+        if profile.side_effects {
+            details["variable_assignments"] = self.extract_variable_assignments(node, source)?;
+            details["function_calls"] = self.extract_function_calls_detailed(node, source)?;
+        }
+        if profile.complexity {
+            details["control_flow"] = self.extract_control_flow_info(node, source)?;
+        }
+        
+        Ok(details)
     }
     
     fn extract_function_body(&self, node: Node, source: &str) -> Result<String> {
@@ -401,8 +753,9 @@ impl PythonExtractor {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             if child.kind() == "block" {
-                let body_text = child.utf8_text(source.as_bytes())?;
-                // Remove the leading indentation to get clean body
+                // The node's text starts at its first statement, so put back
+                // that line's indentation before removing the common one
+                let body_text = format!("{}{}", " ".repeat(child.start_position().column), child.utf8_text(source.as_bytes())?);
                 let lines: Vec<&str> = body_text.lines().collect();
                 if lines.is_empty() {
                     return Ok(String::new());
@@ -423,13 +776,7 @@ impl PythonExtractor {
                 
                 // Remove common indentation
                 let cleaned_lines: Vec<String> = lines.iter()
-                    .map(|line| {
-                        if line.len() >= min_indent {
-                            line[min_indent..].to_string()
-                        } else {
-                            line.to_string()
-                        }
-                    })
+                    .map(|line| strip_indentation(line, min_indent).to_string())
                     .collect();
                 
                 return Ok(cleaned_lines.join("\n"));
@@ -647,6 +994,57 @@ impl PythonExtractor {
         Ok(())
     }
     
+    /// Exceptions raised in the body, then those its docstring documents
+    fn extract_raised_exceptions(&self, node: Node, source: &str) -> Result<Vec<String>> {
+        let mut exceptions = Vec::new();
+        let Some(body) = node.child_by_field_name("body") else {
+            return Ok(exceptions);
+        };
+        self.find_raised_exceptions_recursive(body, source, &mut exceptions)?;
+        
+        let docstring = body.named_child(0)
+            .filter(|statement| statement.kind() == "expression_statement")
+            .and_then(|statement| statement.named_child(0))
+            .filter(|expression| expression.kind() == "string");
+        if let Some(docstring) = docstring {
+            for exception in documented_exceptions(docstring.utf8_text(source.as_bytes())?) {
+                if !exceptions.contains(&exception) {
+                    exceptions.push(exception);
+                }
+            }
+        }
+        Ok(exceptions)
+    }
+    
+    fn find_raised_exceptions_recursive(&self, node: Node, source: &str, exceptions: &mut Vec<String>) -> Result<()> {
+        // A bare `raise` re-raises whatever is being handled
+        if node.kind() == "raise_statement" {
+            if let Some(raised) = node.named_child(0) {
+                let raised = match raised.kind() {
+                    "call" => raised.child_by_field_name("function"),
+                    "identifier" | "attribute" => Some(raised),
+                    _ => None,
+                };
+                if let Some(raised) = raised {
+                    let exception = raised.utf8_text(source.as_bytes())?.to_string();
+                    if !exceptions.contains(&exception) {
+                        exceptions.push(exception);
+                    }
+                }
+            }
+        }
+        
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if matches!(child.kind(), "function_definition" | "class_definition" | "lambda") {
+                continue;
+            }
+            self.find_raised_exceptions_recursive(child, source, exceptions)?;
+        }
+        
+        Ok(())
+    }
+    
     fn extract_variable_implementation_details(&self, node: Node, source: &str, var_name: &str) -> Result<serde_json::Value> {
         let mut assignments = serde_json::Map::new();
         
@@ -661,4 +1059,138 @@ impl PythonExtractor {
             "line_number": node.start_position().row
         }))
     }
-}
\ No newline at end of file
+}
+
+/// Exception names listed in a docstring's Google (`Raises:`), NumPy
+/// (`Raises` over a dashed rule) or Sphinx (`:raises X:`) sections
+fn documented_exceptions(docstring: &str) -> Vec<String> {
+    let mut exceptions: Vec<String> = Vec::new();
+    let mut add = |names: &str| {
+        for name in names.split(',').map(str::trim) {
+            let is_name = !name.is_empty()
+                && name.split('.').all(|part| part.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                    && part.chars().all(|c| c.is_alphanumeric() || c == '_'));
+            if is_name && !exceptions.iter().any(|known| known == name) {
+                exceptions.push(name.to_string());
+            }
+        }
+    };
+    
+    let lines: Vec<&str> = docstring.lines().collect();
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index].trim();
+        if let Some(rest) = line.strip_prefix(":raises ").or_else(|| line.strip_prefix(":raise ")) {
+            if let Some((names, _)) = rest.split_once(':') {
+                add(names);
+            }
+        }
+        
+        let numpy = line == "Raises" && lines.get(index + 1)
+            .is_some_and(|rule| !rule.trim().is_empty() && rule.trim().chars().all(|c| c == '-'));
+        if line != "Raises:" && !numpy {
+            index += 1;
+            continue;
+        }
+        
+        // Entries share the first entry's indent; deeper lines describe them
+        let section_indent = indent(lines[index]);
+        index += if numpy { 2 } else { 1 };
+        let mut entry_indent = None;
+        while let Some(entry) = lines.get(index) {
+            if entry.trim().is_empty() || indent(entry) < section_indent || (!numpy && indent(entry) == section_indent) {
+                break;
+            }
+            let entry_indent = *entry_indent.get_or_insert(indent(entry));
+            if indent(entry) < entry_indent {
+                break;
+            }
+            if indent(entry) == entry_indent {
+                add(entry.trim().split(':').next().unwrap_or_default());
+            }
+            index += 1;
+        }
+    }
+    exceptions
+}
+
+/// The last part of a dotted name, `TypedDict` for `typing.TypedDict`
+fn last_segment(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// `line` without up to `width` bytes of leading whitespace.
+///
+/// Tree-sitter columns and `str::len` count bytes, so indentation that
+/// contains multi-byte whitespace (a no-break space, say) must not be cut
+/// mid-character; stripping stops at the last whole character instead.
+fn strip_indentation(line: &str, width: usize) -> &str {
+    let mut cut = 0;
+    for (index, c) in line.char_indices() {
+        if !c.is_whitespace() || index + c.len_utf8() > width {
+            break;
+        }
+        cut = index + c.len_utf8();
+    }
+    &line[cut..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that Python functions record raised and documented exceptions
+    #[test]
+    fn test_python_raised_exceptions_extracted() -> Result<()> {
+        let source = r#"def parse(text):
+    """Parse a config file.
+
+    Raises:
+        KeyError: If a required key is missing.
+            Also when a section is empty.
+        config.ParseError: On malformed input.
+    """
+    if not text:
+        raise ValueError("empty")
+    try:
+        return load(text)
+    except OSError:
+        raise
+    def fallback():
+        raise RuntimeError()
+
+
+def check(value):
+    """Check a value.
+
+    :raises TypeError: when value is not an int
+    """
+    raise errors.InvalidValue
+
+
+def convert(value):
+    """Convert a value.
+
+    Raises
+    ------
+    OverflowError
+        If the value is too large.
+    """
+    return int(value)
+"#;
+    
+        let parse_result = UniversalParser::new()?.parse_file(source, "python", "config.py")?;
+        let throws = |name: &str| parse_result.blocks.iter()
+            .find(|block| block.semantic_identity.canonical_name == name)
+            .map(|block| block.semantic_metadata.throws.clone())
+            .expect("function extracted");
+    
+        assert_eq!(throws("parse"), vec!["ValueError", "KeyError", "config.ParseError"]);
+        assert_eq!(throws("fallback"), vec!["RuntimeError"]);
+        assert_eq!(throws("check"), vec!["errors.InvalidValue", "TypeError"]);
+        assert_eq!(throws("convert"), vec!["OverflowError"]);
+        Ok(())
+    }
+}
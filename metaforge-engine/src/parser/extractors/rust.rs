@@ -1,14 +1,24 @@
 use anyhow::{Result, anyhow};
 use tree_sitter::Node;
 use std::collections::HashMap;
+use std::sync::OnceLock;
+use regex::Regex;
 use crate::core::*;
-use crate::parser::extraction_context::{ExtractionContext, ParseResult, LanguageExtractor};
+use crate::generator::rust_attributes::{derived_traits, RustAttribute, RUST_ATTRIBUTES_KEY, RUST_DERIVES_KEY};
+use crate::generator::rust_enums::{EnumVariant, RustEnum, VariantField, VariantFields, RUST_ENUM_KEY};
+use crate::generator::rust_impls::{ImplMethod, RustImpl, RUST_IMPL_KEY};
+use crate::parser::extraction_context::{ExtractionContext, ExtractionProfile, ParseResult, LanguageExtractor};
+use crate::parser::function_body::attach_function_body;
 
 pub struct RustExtractor;
 
 impl LanguageExtractor for RustExtractor {
-    fn extract_with_context(&self, root: Node, source: &str, _file_path: &str) -> Result<ParseResult> {
-        let mut context = ExtractionContext::new();
+    fn extract_with_context(&self, root: Node, source: &str, file_path: &str) -> Result<ParseResult> {
+        self.extract_with_profile(root, source, file_path, &ExtractionProfile::full())
+    }
+    
+    fn extract_with_profile(&self, root: Node, source: &str, _file_path: &str, profile: &ExtractionProfile) -> Result<ParseResult> {
+        let mut context = ExtractionContext::new().with_profile(profile.clone());
         self.visit_with_context(root, source, &mut context)?;
         Ok(context.finish())
     }
@@ -24,19 +34,26 @@ impl RustExtractor {
     fn visit_with_context(&self, node: Node, source: &str, ctx: &mut ExtractionContext) -> Result<()> {
         match node.kind() {
             "function_item" => {
-                if let Ok(block) = self.extract_function_block(node, source) {
+                if let Ok(block) = self.extract_function_block(node, source, ctx.profile()) {
                     let block_id = ctx.enter_block(block);
                     self.visit_children(node, source, ctx)?;
                     ctx.exit_block(block_id);
                 }
             },
-            "struct_item" | "enum_item" => {
+            "struct_item" => {
                 if let Ok(block) = self.extract_struct_block(node, source) {
                     let block_id = ctx.enter_block(block);
                     self.visit_children(node, source, ctx)?;
                     ctx.exit_block(block_id);
                 }
             },
+            "enum_item" => {
+                if let Ok(block) = self.extract_enum_block(node, source) {
+                    let block_id = ctx.enter_block(block);
+                    self.visit_children(node, source, ctx)?;
+                    ctx.exit_block(block_id);
+                }
+            },
             "impl_item" => {
                 if let Ok(block) = self.extract_impl_block(node, source) {
                     let block_id = ctx.enter_block(block);
@@ -64,7 +81,7 @@ impl RustExtractor {
         Ok(())
     }
     
-    fn extract_function_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+    fn extract_function_block(&self, node: Node, source: &str, profile: &ExtractionProfile) -> Result<SemanticBlock> {
         let name = self.extract_function_name(node, source)?;
         let text = node.utf8_text(source.as_bytes())?;
         
@@ -75,6 +92,21 @@ impl RustExtractor {
             "rust".to_string(),
         );
         
+        // Visibility is recorded separately; keep `async fn` and `const fn`
+        block.semantic_metadata.modifiers = RustVisitor::new(source).extract_modifiers(node)?.iter()
+            .filter_map(|modifier| match modifier.as_str() {
+                "async" => Some(Modifier::Async),
+                "const" => Some(Modifier::Const),
+                _ => None,
+            })
+            .collect();
+        if profile.side_effects {
+            block.semantic_metadata.side_effect_analysis = Some(RustVisitor::new(source).analyze_side_effects(node, text)?);
+        }
+        if profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(RustVisitor::new(source).calculate_complexity_metrics(node, text)?);
+        }
+        
         let start = node.start_position();
         let end = node.end_position();
         block.position = BlockPosition {
@@ -84,6 +116,9 @@ impl RustExtractor {
             end_column: end.column,
             index: 0,
         };
+        self.attach_attributes(node, source, &mut block)?;
+        self.attach_language_features(node, source, &mut block)?;
+        attach_function_body(node, source, "rust", &mut block)?;
         
         Ok(block)
     }
@@ -108,12 +143,15 @@ impl RustExtractor {
             end_column: end.column,
             index: 0,
         };
+        self.attach_attributes(node, source, &mut block)?;
+        self.attach_language_features(node, source, &mut block)?;
         
         Ok(block)
     }
     
     fn extract_impl_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
-        let name = self.extract_impl_name(node, source)?;
+        let rust_impl = extract_impl(node, source)?;
+        let name = rust_impl.name();
         let text = node.utf8_text(source.as_bytes())?;
         
         let mut block = SemanticBlock::new(
@@ -132,6 +170,28 @@ impl RustExtractor {
             end_column: end.column,
             index: 0,
         };
+        self.attach_attributes(node, source, &mut block)?;
+        self.attach_language_features(node, source, &mut block)?;
+        if !block.syntax_preservation.normalized_ast.is_object() {
+            block.syntax_preservation.normalized_ast = serde_json::json!({});
+        }
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(RUST_IMPL_KEY.to_string(), serde_json::to_value(&rust_impl)?);
+        }
+        
+        Ok(block)
+    }
+    
+    fn extract_enum_block(&self, node: Node, source: &str) -> Result<SemanticBlock> {
+        let mut block = self.extract_struct_block(node, source)?;
+        block.block_type = BlockType::Enum;
+        let variants = extract_enum_variants(node, source)?;
+        if !block.syntax_preservation.normalized_ast.is_object() {
+            block.syntax_preservation.normalized_ast = serde_json::json!({});
+        }
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(RUST_ENUM_KEY.to_string(), serde_json::to_value(&variants)?);
+        }
         
         Ok(block)
     }
@@ -156,38 +216,127 @@ impl RustExtractor {
             end_column: end.column,
             index: 0,
         };
+        self.attach_attributes(node, source, &mut block)?;
         
         Ok(block)
     }
     
-    fn extract_function_name(&self, node: Node, source: &str) -> Result<String> {
+    /// Record the outer attributes written before an item, e.g. `#[cfg(test)]`,
+    /// and the traits its `derive` attributes list
+    fn attach_attributes(&self, node: Node, source: &str, block: &mut SemanticBlock) -> Result<()> {
+        let mut attributes = Vec::new();
+        let mut decorators = Vec::new();
+        let mut sibling = node.prev_sibling();
+        while let Some(previous) = sibling {
+            match previous.kind() {
+                "attribute_item" => {
+                    if let Some(attribute) = RustAttribute::parse(previous.utf8_text(source.as_bytes())?) {
+                        decorators.push(Decorator {
+                            name: attribute.path.clone(),
+                            arguments: attribute.input.iter().cloned().collect(),
+                            line_number: previous.start_position().row,
+                        });
+                        attributes.push(attribute);
+                    }
+                }
+                // Doc comments may sit between attributes and the item
+                "line_comment" | "block_comment" => {}
+                _ => break,
+            }
+            sibling = previous.prev_sibling();
+        }
+        if attributes.is_empty() {
+            return Ok(());
+        }
+        
+        attributes.reverse();
+        decorators.reverse();
+        block.structural_context.decorators = decorators;
+        if !block.syntax_preservation.normalized_ast.is_object() {
+            block.syntax_preservation.normalized_ast = serde_json::json!({});
+        }
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(RUST_ATTRIBUTES_KEY.to_string(), serde_json::to_value(&attributes)?);
+            let derives = derived_traits(&attributes);
+            if !derives.is_empty() {
+                ast.insert(RUST_DERIVES_KEY.to_string(), serde_json::to_value(&derives)?);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Record generics, lifetimes, inline bounds and the where clause
+    fn attach_language_features(&self, node: Node, source: &str, block: &mut SemanticBlock) -> Result<()> {
+        let mut features = LanguageFeatures::default();
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "identifier" {
-                return Ok(child.utf8_text(source.as_bytes())?.to_string());
+            match child.kind() {
+                "type_parameters" => {
+                    let mut param_cursor = child.walk();
+                    for param in child.named_children(&mut param_cursor) {
+                        let text = param.utf8_text(source.as_bytes())?.trim();
+                        match param.kind() {
+                            "lifetime" => features.lifetimes.push(text.to_string()),
+                            "type_identifier" => features.generics.push(text.to_string()),
+                            "constrained_type_parameter" | "optional_type_parameter" | "type_parameter" => {
+                                let name = text.split([':', '=']).next().unwrap_or(text).trim();
+                                if name.starts_with('\'') {
+                                    features.lifetimes.push(name.to_string());
+                                } else {
+                                    features.generics.push(name.to_string());
+                                }
+                                if text.contains(':') {
+                                    features.trait_bounds.push(text.split('=').next().unwrap_or(text).trim().to_string());
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                "where_clause" => {
+                    let mut predicate_cursor = child.walk();
+                    let predicates = child.named_children(&mut predicate_cursor)
+                        .filter(|predicate| predicate.kind() == "where_predicate")
+                        .map(|predicate| predicate.utf8_text(source.as_bytes()).map(|t| t.trim().to_string()))
+                        .collect::<std::result::Result<Vec<_>, _>>()?;
+                    if !predicates.is_empty() {
+                        features.where_clause = Some(predicates.join(", "));
+                    }
+                }
+                _ => {}
             }
         }
-        Err(anyhow!("Function name not found"))
+        if features.is_empty() {
+            return Ok(());
+        }
+        
+        if !block.syntax_preservation.normalized_ast.is_object() {
+            block.syntax_preservation.normalized_ast = serde_json::json!({});
+        }
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(LANGUAGE_FEATURES_KEY.to_string(), features.to_value());
+        }
+        Ok(())
     }
     
-    fn extract_struct_name(&self, node: Node, source: &str) -> Result<String> {
+    fn extract_function_name(&self, node: Node, source: &str) -> Result<String> {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            if child.kind() == "type_identifier" {
+            if child.kind() == "identifier" {
                 return Ok(child.utf8_text(source.as_bytes())?.to_string());
             }
         }
-        Err(anyhow!("Struct name not found"))
+        Err(anyhow!("Function name not found"))
     }
     
-    fn extract_impl_name(&self, node: Node, source: &str) -> Result<String> {
+    fn extract_struct_name(&self, node: Node, source: &str) -> Result<String> {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             if child.kind() == "type_identifier" {
-                return Ok(format!("impl {}", child.utf8_text(source.as_bytes())?));
+                return Ok(child.utf8_text(source.as_bytes())?.to_string());
             }
         }
-        Ok("impl".to_string())
+        Err(anyhow!("Struct name not found"))
     }
     
     fn extract_use_name(&self, node: Node, source: &str) -> Result<String> {
@@ -207,11 +356,177 @@ impl RustExtractor {
     }
 }
 
+/// Variants of an `enum_item` with their fields, discriminants and attributes
+fn extract_enum_variants(node: Node, source: &str) -> Result<RustEnum> {
+    let mut rust_enum = RustEnum::default();
+    let body = match node.child_by_field_name("body") {
+        Some(body) => body,
+        None => return Ok(rust_enum),
+    };
+    let text = |node: Node| -> Result<String> { Ok(node.utf8_text(source.as_bytes())?.to_string()) };
+    
+    let mut attributes = Vec::new();
+    let mut cursor = body.walk();
+    for child in body.named_children(&mut cursor) {
+        match child.kind() {
+            "attribute_item" => attributes.extend(RustAttribute::parse(&text(child)?)),
+            "enum_variant" => {
+                let name = child.child_by_field_name("name")
+                    .ok_or_else(|| anyhow!("Enum variant has no name"))?;
+                let mut variant = EnumVariant::new(text(name)?);
+                variant.attributes = std::mem::take(&mut attributes);
+                variant.discriminant = child.child_by_field_name("value").map(text).transpose()?;
+                variant.fields = match child.child_by_field_name("body") {
+                    Some(fields) if fields.kind() == "ordered_field_declaration_list" => {
+                        let mut field_cursor = fields.walk();
+                        let types = fields.children_by_field_name("type", &mut field_cursor)
+                            .map(text)
+                            .collect::<Result<Vec<_>>>()?;
+                        VariantFields::Tuple { types }
+                    }
+                    Some(fields) => {
+                        let mut field_cursor = fields.walk();
+                        let fields = fields.named_children(&mut field_cursor)
+                            .filter(|field| field.kind() == "field_declaration")
+                            .filter_map(|field| Some((field.child_by_field_name("name")?, field.child_by_field_name("type")?)))
+                            .map(|(name, type_node)| Ok(VariantField { name: text(name)?, type_annotation: text(type_node)? }))
+                            .collect::<Result<Vec<_>>>()?;
+                        VariantFields::Struct { fields }
+                    }
+                    None => VariantFields::Unit,
+                };
+                rust_enum.variants.push(variant);
+            }
+            _ => {}
+        }
+    }
+    Ok(rust_enum)
+}
+
+/// Trait, self type and members of an `impl_item`
+fn extract_impl(node: Node, source: &str) -> Result<RustImpl> {
+    let text = |node: Node| -> Result<String> { Ok(node.utf8_text(source.as_bytes())?.trim().to_string()) };
+    let for_type = node.child_by_field_name("type")
+        .ok_or_else(|| anyhow!("Impl has no self type"))?;
+    let mut rust_impl = RustImpl {
+        trait_name: node.child_by_field_name("trait").map(text).transpose()?,
+        for_type: text(for_type)?,
+        generics: node.child_by_field_name("type_parameters").map(text).transpose()?,
+        ..RustImpl::default()
+    };
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "where_clause" {
+            let mut predicate_cursor = child.walk();
+            let predicates = child.named_children(&mut predicate_cursor)
+                .filter(|predicate| predicate.kind() == "where_predicate")
+                .map(text)
+                .collect::<Result<Vec<_>>>()?;
+            if !predicates.is_empty() {
+                rust_impl.where_clause = Some(predicates.join(", "));
+            }
+        }
+    }
+    
+    let body = match node.child_by_field_name("body") {
+        Some(body) => body,
+        None => return Ok(rust_impl),
+    };
+    // Attributes and doc comments travel with the member after them
+    let mut leading: Option<Node> = None;
+    let mut cursor = body.walk();
+    for child in body.named_children(&mut cursor) {
+        match child.kind() {
+            "attribute_item" | "line_comment" | "block_comment" => {
+                leading.get_or_insert(child);
+            }
+            "function_item" => {
+                let name = child.child_by_field_name("name")
+                    .ok_or_else(|| anyhow!("Impl method has no name"))?;
+                rust_impl.methods.push(ImplMethod {
+                    name: text(name)?,
+                    source: member_source(leading.take().unwrap_or(child), child, source),
+                });
+            }
+            "associated_type" | "type_item" | "const_item" | "macro_invocation" => {
+                rust_impl.associated_items.push(member_source(leading.take().unwrap_or(child), child, source));
+            }
+            _ => leading = None,
+        }
+    }
+    Ok(rust_impl)
+}
+
+/// Source from `first` through `last`, continuation lines dedented by the
+/// column `first` starts at
+/// Synchronous file, socket and stdin calls in a function's text, once
+/// each. A call followed by `.await`, such as `tokio::fs::read(path).await`,
+/// belongs to an async runtime and is skipped.
+fn blocking_io_calls(text: &str) -> Vec<IoOperation> {
+    static BLOCKING_CALL: OnceLock<Regex> = OnceLock::new();
+    let blocking_call = BLOCKING_CALL.get_or_init(|| {
+        Regex::new(r"(?:\b\w+::)*(?:fs::(\w+)|File::(open|create)|TcpStream::(connect)|io::(stdin))\s*\(").unwrap()
+    });
+
+    let mut operations: Vec<IoOperation> = Vec::new();
+    for captures in blocking_call.captures_iter(text) {
+        let whole = captures.get(0).unwrap();
+        let call = whole.as_str().trim_end_matches('(').trim_end().to_string();
+        if awaited(&text[whole.end()..]) || operations.iter().any(|operation| operation.call.as_deref() == Some(call.as_str())) {
+            continue;
+        }
+        let (operation_type, resource, latency) = match (1..=4).find_map(|group| Some((group, captures.get(group)?.as_str()))) {
+            Some((3, _)) => (IoType::Network, "tcp", 50.0),
+            Some((4, _)) => (IoType::Read, "stdin", 1.0),
+            Some((_, "create" | "write" | "copy" | "rename" | "create_dir" | "create_dir_all" | "set_permissions")) => (IoType::Write, "file", 1.0),
+            Some((_, name)) if name.starts_with("remove") => (IoType::Delete, "file", 1.0),
+            _ => (IoType::Read, "file", 1.0),
+        };
+        operations.push(IoOperation {
+            operation_type,
+            resource: resource.to_string(),
+            is_blocking: true,
+            estimated_latency: Some(latency),
+            call: Some(call),
+        });
+    }
+    operations
+}
+
+/// Whether the call whose arguments start `after_open_paren` is awaited
+fn awaited(after_open_paren: &str) -> bool {
+    let mut depth = 1;
+    for (index, c) in after_open_paren.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return after_open_paren[index + 1..].trim_start().starts_with(".await");
+        }
+    }
+    false
+}
+
+fn member_source(first: Node, last: Node, source: &str) -> String {
+    let column = first.start_position().column;
+    source[first.start_byte()..last.end_byte()].lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let indent = line.len() - line.trim_start().len();
+            if index == 0 { line } else { &line[indent.min(column)..] }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[allow(dead_code)]
 struct RustVisitor<'a> {
     source: &'a str,
     blocks: Vec<SemanticBlock>,
     current_module: Option<String>,
+    profile: ExtractionProfile,
 }
 
 #[allow(dead_code)]
@@ -221,9 +536,15 @@ impl<'a> RustVisitor<'a> {
             source,
             blocks: Vec::new(),
             current_module: None,
+            profile: ExtractionProfile::full(),
         }
     }
 
+    fn with_profile(mut self, profile: ExtractionProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
     fn string_to_modifier(&self, s: &str) -> Modifier {
         match s {
             "async" => Modifier::Async,
@@ -303,11 +624,21 @@ impl<'a> RustVisitor<'a> {
             .collect();
         
         // Enhanced semantic metadata (Phase 1.2)
-        block.semantic_metadata.parameter_details = Some(self.extract_parameter_details(node, &params)?);
-        block.semantic_metadata.side_effect_analysis = Some(self.analyze_side_effects(node, &original_text)?);
-        block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, &original_text)?);
-        block.semantic_metadata.generics = Some(self.extract_generics(node)?);
-        block.semantic_metadata.macros = Some(self.extract_macros(node)?);
+        if self.profile.semantic_metadata {
+            block.semantic_metadata.parameter_details = Some(self.extract_parameter_details(node, &params)?);
+        }
+        if self.profile.side_effects {
+            block.semantic_metadata.side_effect_analysis = Some(self.analyze_side_effects(node, original_text)?);
+        }
+        if self.profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, original_text)?);
+        }
+        if self.profile.generics {
+            block.semantic_metadata.generics = Some(self.extract_generics(node)?);
+        }
+        if self.profile.semantic_metadata {
+            block.semantic_metadata.macros = Some(self.extract_macros(node)?);
+        }
 
         // Set position
         block.position = BlockPosition {
@@ -365,8 +696,12 @@ impl<'a> RustVisitor<'a> {
             .collect();
         
         // Enhanced semantic metadata (Phase 1.2)
-        block.semantic_metadata.generics = Some(self.extract_generics(node)?);
-        block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, &original_text)?);
+        if self.profile.generics {
+            block.semantic_metadata.generics = Some(self.extract_generics(node)?);
+        }
+        if self.profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, original_text)?);
+        }
 
         // Set position
         block.position = BlockPosition {
@@ -386,7 +721,7 @@ impl<'a> RustVisitor<'a> {
         let original_text = node.utf8_text(self.source.as_bytes())?;
         
         let mut block = SemanticBlock::new(
-            BlockType::Class,
+            BlockType::Enum,
             name.clone(),
             original_text.to_string(),
             "rust".to_string(),
@@ -399,6 +734,9 @@ impl<'a> RustVisitor<'a> {
         // Set syntax preservation
         block.syntax_preservation.original_text = original_text.to_string();
         block.syntax_preservation.normalized_ast = self.node_to_json(node)?;
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(RUST_ENUM_KEY.to_string(), serde_json::to_value(extract_enum_variants(node, self.source)?)?);
+        }
         block.syntax_preservation.reconstruction_hints = ReconstructionHints {
             prefer_original: true,
             template: Some(original_text.to_string()),
@@ -424,8 +762,12 @@ impl<'a> RustVisitor<'a> {
             .collect();
         
         // Enhanced semantic metadata (Phase 1.2)
-        block.semantic_metadata.generics = Some(self.extract_generics(node)?);
-        block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, &original_text)?);
+        if self.profile.generics {
+            block.semantic_metadata.generics = Some(self.extract_generics(node)?);
+        }
+        if self.profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, original_text)?);
+        }
 
         // Set position
         block.position = BlockPosition {
@@ -441,7 +783,8 @@ impl<'a> RustVisitor<'a> {
     }
 
     fn visit_impl(&mut self, node: Node) -> Result<()> {
-        let name = self.extract_impl_name(node)?;
+        let rust_impl = extract_impl(node, self.source)?;
+        let name = rust_impl.name();
         let original_text = node.utf8_text(self.source.as_bytes())?;
         
         let mut block = SemanticBlock::new(
@@ -458,6 +801,9 @@ impl<'a> RustVisitor<'a> {
         // Set syntax preservation
         block.syntax_preservation.original_text = original_text.to_string();
         block.syntax_preservation.normalized_ast = self.node_to_json(node)?;
+        if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+            ast.insert(RUST_IMPL_KEY.to_string(), serde_json::to_value(&rust_impl)?);
+        }
         block.syntax_preservation.reconstruction_hints = ReconstructionHints {
             prefer_original: true,
             template: Some(original_text.to_string()),
@@ -483,8 +829,12 @@ impl<'a> RustVisitor<'a> {
             .collect();
         
         // Enhanced semantic metadata (Phase 1.2)
-        block.semantic_metadata.generics = Some(self.extract_generics(node)?);
-        block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, &original_text)?);
+        if self.profile.generics {
+            block.semantic_metadata.generics = Some(self.extract_generics(node)?);
+        }
+        if self.profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, original_text)?);
+        }
 
         // Set position
         block.position = BlockPosition {
@@ -542,8 +892,12 @@ impl<'a> RustVisitor<'a> {
             .collect();
         
         // Enhanced semantic metadata (Phase 1.2)
-        block.semantic_metadata.generics = Some(self.extract_generics(node)?);
-        block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, &original_text)?);
+        if self.profile.generics {
+            block.semantic_metadata.generics = Some(self.extract_generics(node)?);
+        }
+        if self.profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, original_text)?);
+        }
 
         // Set position
         block.position = BlockPosition {
@@ -601,8 +955,12 @@ impl<'a> RustVisitor<'a> {
             .collect();
         
         // Enhanced semantic metadata (Phase 1.2)
-        block.semantic_metadata.macros = Some(self.extract_macro_info(node)?);
-        block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, &original_text)?);
+        if self.profile.semantic_metadata {
+            block.semantic_metadata.macros = Some(self.extract_macro_info(node)?);
+        }
+        if self.profile.complexity {
+            block.semantic_metadata.complexity_metrics = Some(self.calculate_complexity_metrics(node, original_text)?);
+        }
 
         // Set position
         block.position = BlockPosition {
@@ -744,16 +1102,6 @@ impl<'a> RustVisitor<'a> {
         Err(anyhow!("Could not find enum name"))
     }
 
-    fn extract_impl_name(&self, node: Node) -> Result<String> {
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            if child.kind() == "type_identifier" {
-                return Ok(child.utf8_text(self.source.as_bytes())?.to_string());
-            }
-        }
-        Err(anyhow!("Could not find impl name"))
-    }
-
     fn extract_trait_name(&self, node: Node) -> Result<String> {
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
@@ -811,6 +1159,7 @@ impl<'a> RustVisitor<'a> {
                             default_value: None, // Rust doesn't have default parameters
                             is_optional: false,
                             position: params.len(),
+                            default_expression: None,
                         });
                     }
                 }
@@ -856,8 +1205,16 @@ impl<'a> RustVisitor<'a> {
     fn extract_modifiers(&self, node: Node) -> Result<Vec<String>> {
         let mut modifiers = Vec::new();
         let mut cursor = node.walk();
+        // `async`, `unsafe` and `const` on a function sit in a
+        // `function_modifiers` node rather than directly on the item
+        let mut children: Vec<Node> = node.children(&mut cursor).collect();
+        if let Some(index) = children.iter().position(|child| child.kind() == "function_modifiers") {
+            let function_modifiers = children.remove(index);
+            let mut modifier_cursor = function_modifiers.walk();
+            children.extend(function_modifiers.children(&mut modifier_cursor));
+        }
         
-        for child in node.children(&mut cursor) {
+        for child in children {
             match child.kind() {
                 "visibility_modifier" => {
                     let text = child.utf8_text(self.source.as_bytes())?;
@@ -885,12 +1242,7 @@ impl<'a> RustVisitor<'a> {
     }
 
     fn hash_signature(&self, text: &str) -> String {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        
-        let mut hasher = DefaultHasher::new();
-        text.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
+        crate::core::normalize::source_hash(text, "rust")
     }
 
     fn node_to_json(&self, node: Node) -> Result<serde_json::Value> {
@@ -1024,13 +1376,33 @@ impl<'a> RustVisitor<'a> {
             });
         }
         
+        let mutability = self.analyze_mutability(node, original_text);
+        if mutability.mutates_globals {
+            side_effects.push(SideEffect {
+                effect_type: SideEffectType::GlobalStateMutation,
+                description: "Writes to a static or global variable".to_string(),
+                severity: EffectSeverity::Critical,
+                line_number: None,
+                confidence: 0.7,
+            });
+        }
+        if mutability.mutates_externals {
+            side_effects.push(SideEffect {
+                effect_type: SideEffectType::GlobalStateMutation,
+                description: "Mutates shared state through a global lock, cell or atomic".to_string(),
+                severity: EffectSeverity::High,
+                line_number: None,
+                confidence: 0.7,
+            });
+        }
+        
         // Extract dependencies from use statements
         self.extract_dependencies_from_text(original_text, &mut dependencies);
         
         // Determine purity level
         let purity_level = if side_effects.is_empty() {
             PurityLevel::Pure
-        } else if side_effects.iter().any(|se| matches!(se.effect_type, SideEffectType::FileIO | SideEffectType::NetworkIO)) {
+        } else if side_effects.iter().any(|se| matches!(se.effect_type, SideEffectType::FileIO | SideEffectType::NetworkIO | SideEffectType::GlobalStateMutation)) {
             PurityLevel::Impure
         } else {
             PurityLevel::MostlyPure
@@ -1040,7 +1412,7 @@ impl<'a> RustVisitor<'a> {
             purity_level,
             side_effects,
             dependencies,
-            mutability: self.analyze_mutability(node, original_text),
+            mutability,
             resource_usage: self.analyze_resource_usage(node, original_text),
         })
     }
@@ -1078,28 +1450,29 @@ impl<'a> RustVisitor<'a> {
         let is_mutable = original_text.contains("mut ") || original_text.contains("&mut");
         let mutates_self = original_text.contains("&mut self");
         let mutates_parameters = original_text.contains("mut ") && !original_text.contains("mut self");
-        let mutates_globals = original_text.contains("static mut");
+        // Statics are SCREAMING_CASE by convention: assigning to one mutates a
+        // global, and locking or storing through one mutates shared state
+        static GLOBAL_WRITE: OnceLock<Regex> = OnceLock::new();
+        static SHARED_WRITE: OnceLock<Regex> = OnceLock::new();
+        let global_write = GLOBAL_WRITE.get_or_init(|| {
+            Regex::new(r"\b[A-Z][A-Z0-9_]+\s*(?:[-+*/%|&^]|<<|>>)?=[^=]").unwrap()
+        });
+        let shared_write = SHARED_WRITE.get_or_init(|| {
+            Regex::new(r"\b[A-Z][A-Z0-9_]+\s*\.\s*(?:lock|write|borrow_mut|get_mut|store|swap|fetch_[a-z_]+|compare_exchange\w*)\s*\(").unwrap()
+        });
+        let mutates_globals = original_text.contains("static mut") || global_write.is_match(original_text);
         
         MutabilityInfo {
             is_mutable,
             mutates_self,
             mutates_parameters,
             mutates_globals,
-            mutates_externals: false,
+            mutates_externals: shared_write.is_match(original_text),
         }
     }
     
     fn analyze_resource_usage(&self, _node: Node, original_text: &str) -> ResourceUsage {
-        let mut io_operations = Vec::new();
-        
-        if original_text.contains("std::fs::") {
-            io_operations.push(IoOperation {
-                operation_type: IoType::Read,
-                resource: "file".to_string(),
-                is_blocking: true,
-                estimated_latency: Some(1.0),
-            });
-        }
+        let mut io_operations = blocking_io_calls(original_text);
         
         if original_text.contains("reqwest::") {
             io_operations.push(IoOperation {
@@ -1107,6 +1480,7 @@ impl<'a> RustVisitor<'a> {
                 resource: "http".to_string(),
                 is_blocking: false, // async by default
                 estimated_latency: Some(100.0),
+                call: None,
             });
         }
         
@@ -1416,3 +1790,32 @@ impl<'a> RustVisitor<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::universal::UniversalParser;
+
+    #[test]
+    fn test_rust_shared_state_mutation_detection() -> Result<()> {
+        let source = "fn record(key: u32) {\n    CACHE.lock().unwrap().insert(key, 1);\n}\n\nfn bump() {\n    unsafe { COUNTER += 1; }\n}\n\nfn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n";
+    
+        let result = UniversalParser::new()?.parse_file(source, "rust", "lib.rs")?;
+        let mutability = |name: &str| result.blocks.iter()
+            .find(|b| b.semantic_identity.canonical_name == name)
+            .and_then(|b| b.semantic_metadata.side_effect_analysis.clone())
+            .map(|analysis| analysis.mutability)
+            .expect("side effect analysis");
+    
+        assert!(mutability("record").mutates_externals);
+        assert!(mutability("bump").mutates_globals);
+        let pure = mutability("add");
+        assert!(!pure.mutates_globals && !pure.mutates_externals);
+    
+        let fast = UniversalParser::new()?
+            .with_profile(ExtractionProfile::fast())
+            .parse_file(source, "rust", "lib.rs")?;
+        assert!(fast.blocks.iter().all(|b| b.semantic_metadata.side_effect_analysis.is_none()));
+        Ok(())
+    }
+}
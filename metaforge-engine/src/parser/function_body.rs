@@ -0,0 +1,295 @@
+//! Statement-level extraction of function bodies
+
+use anyhow::Result;
+use ast_extractor::{ExpressionAST, ExpressionExtractor};
+use tree_sitter::Node;
+
+use crate::core::{BodyStatement, ContextManager, FunctionBody, SemanticBlock, StatementKind, WithClause, FUNCTION_BODY_KEY};
+use super::jsx::extract_jsx;
+use super::string_literals::verbatim_rows;
+
+/// Extract the body of `function` and attach it to the block's `normalized_ast`.
+/// Functions without a body (trait methods, overload signatures) are left alone.
+pub fn attach_function_body(function: Node, source: &str, language: &str, block: &mut SemanticBlock) -> Result<()> {
+    let body = extract_function_body(function, source, language)?;
+    if body.is_empty() {
+        return Ok(());
+    }
+    if !block.syntax_preservation.normalized_ast.is_object() {
+        block.syntax_preservation.normalized_ast = serde_json::json!({});
+    }
+    if let Some(ast) = block.syntax_preservation.normalized_ast.as_object_mut() {
+        ast.insert(FUNCTION_BODY_KEY.to_string(), body.to_value());
+    }
+    Ok(())
+}
+
+/// Classify the statements of a function node's `body`
+pub fn extract_function_body(function: Node, source: &str, language: &str) -> Result<FunctionBody> {
+    let Some(body) = function.child_by_field_name("body") else {
+        return Ok(FunctionBody::default());
+    };
+    let walker = BodyWalker::new(source, language);
+    let statements = if walker.is_block(body) {
+        walker.block(body, true)?
+    } else {
+        // An arrow function's expression body is its return value
+        vec![walker.with_value(walker.statement_for(body, StatementKind::Return)?, body)?.implicit()]
+    };
+    Ok(FunctionBody { statements })
+}
+
+struct BodyWalker<'a> {
+    source: &'a str,
+    language: &'a str,
+    expressions: ExpressionExtractor,
+}
+
+impl<'a> BodyWalker<'a> {
+    fn new(source: &'a str, language: &'a str) -> Self {
+        Self { source, language, expressions: ExpressionExtractor::new() }
+    }
+
+    fn is_rust(&self) -> bool {
+        self.language == "rust"
+    }
+
+    fn is_block(&self, node: Node) -> bool {
+        matches!(node.kind(), "block" | "statement_block")
+    }
+
+    /// Statements of a block. `tail` marks a Rust block whose value is the
+    /// function's return value.
+    fn block(&self, block: Node, tail: bool) -> Result<Vec<BodyStatement>> {
+        let mut cursor = block.walk();
+        let children: Vec<Node> = block.named_children(&mut cursor)
+            .filter(|child| !child.kind().ends_with("comment"))
+            .collect();
+        let mut statements = Vec::new();
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            statements.push(self.statement(*child, tail && is_last)?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&self, node: Node, tail: bool) -> Result<BodyStatement> {
+        let kind = node.kind();
+        match kind {
+            "expression_statement" => {
+                let Some(inner) = node.named_child(0) else {
+                    return self.statement_for(node, StatementKind::Other);
+                };
+                // Only a Rust statement without its semicolon yields a value
+                let terminated = node.utf8_text(self.source.as_bytes())?.trim_end().ends_with(';');
+                let tail = tail && self.is_rust() && !terminated;
+                let statement = self.expression_statement(inner, tail)?;
+                Ok(BodyStatement { code: self.code(node)?, line: node.start_position().row, ..statement })
+            }
+            "return_statement" => {
+                let statement = self.statement_for(node, StatementKind::Return)?;
+                Ok(match node.named_child(0) {
+                    Some(value) => self.with_value(statement, value)?,
+                    None => statement,
+                })
+            }
+            "let_declaration" => self.assignment(node, "pattern", "value"),
+            "lexical_declaration" | "variable_declaration" => {
+                let mut cursor = node.walk();
+                let declarator = node.named_children(&mut cursor)
+                    .find(|child| child.kind() == "variable_declarator");
+                match declarator {
+                    Some(declarator) => {
+                        let statement = self.assignment(declarator, "name", "value")?;
+                        Ok(BodyStatement { code: self.code(node)?, line: node.start_position().row, ..statement })
+                    }
+                    None => self.statement_for(node, StatementKind::Other),
+                }
+            }
+            _ if self.is_rust() => self.expression_statement(node, tail),
+            _ if is_control_flow(kind) => self.control_flow(node, tail),
+            _ => self.statement_for(node, StatementKind::Other),
+        }
+    }
+
+    /// An expression in statement position
+    fn expression_statement(&self, node: Node, tail: bool) -> Result<BodyStatement> {
+        match node.kind() {
+            "assignment" | "augmented_assignment" | "assignment_expression"
+            | "augmented_assignment_expression" | "compound_assignment_expr" => self.assignment(node, "left", "right"),
+            "return_expression" => {
+                let statement = self.statement_for(node, StatementKind::Return)?;
+                Ok(match node.named_child(0) {
+                    Some(value) => self.with_value(statement, value)?,
+                    None => statement,
+                })
+            }
+            "yield" | "yield_expression" => {
+                let statement = self.statement_for(node, StatementKind::Yield)?;
+                Ok(match node.named_child(0) {
+                    Some(value) => self.with_value(statement, value)?,
+                    None => statement,
+                })
+            }
+            kind if is_control_flow(kind) => self.control_flow(node, tail),
+            kind if kind.ends_with("_item") || kind == "macro_definition" => {
+                self.statement_for(node, StatementKind::Other)
+            }
+            _ if tail => Ok(self.with_value(self.statement_for(node, StatementKind::Return)?, node)?.implicit()),
+            _ => self.with_value(self.statement_for(node, StatementKind::Expression)?, node),
+        }
+    }
+
+    fn assignment(&self, node: Node, target_field: &str, value_field: &str) -> Result<BodyStatement> {
+        let mut statement = self.statement_for(node, StatementKind::Assignment)?;
+        if let Some(target) = node.child_by_field_name(target_field) {
+            statement = statement.with_target(target.utf8_text(self.source.as_bytes())?);
+        }
+        if let Some(value) = node.child_by_field_name(value_field) {
+            statement = self.with_value(statement, value)?;
+        }
+        Ok(statement)
+    }
+
+    /// A branch or loop: its condition (or scrutinee) as the expression and
+    /// the statements of every nested block as `body`. A Rust `if`/`match`
+    /// in tail position passes the tail on to its branches; loops don't.
+    fn control_flow(&self, node: Node, tail: bool) -> Result<BodyStatement> {
+        let mut statement = self.statement_for(node, StatementKind::ControlFlow)?;
+        let condition = match node.kind() {
+            // The raised or thrown value
+            "raise_statement" | "throw_statement" => node.named_child(0),
+            _ => ["condition", "value", "right", "subject"].iter()
+                .find_map(|field| node.child_by_field_name(field)),
+        };
+        if let Some(condition) = condition {
+            statement = statement.with_expression(self.expression(condition)?);
+        }
+        if node.kind() == "with_statement" && self.language == "python" {
+            statement.with_clause = self.with_clause(node)?;
+        }
+        let branches_return = tail && matches!(node.kind(), "if_expression" | "match_expression");
+        statement.body = self.nested(node, branches_return)?;
+        Ok(statement)
+    }
+
+    /// The context managers of a Python `with_statement` and their `as` targets
+    fn with_clause(&self, node: Node) -> Result<Option<WithClause>> {
+        let mut cursor = node.walk();
+        let Some(clause) = node.named_children(&mut cursor).find(|child| child.kind() == "with_clause") else {
+            return Ok(None);
+        };
+        let mut managers = Vec::new();
+        let mut cursor = clause.walk();
+        for item in clause.named_children(&mut cursor).filter(|child| child.kind() == "with_item") {
+            let Some(value) = item.child_by_field_name("value") else {
+                continue;
+            };
+            // `open(path) as fh` is an `as_pattern` around the manager
+            let (manager, target) = match (value.kind(), value.named_child(0), value.child_by_field_name("alias")) {
+                ("as_pattern", Some(manager), Some(alias)) => (manager, Some(self.code(alias)?)),
+                _ => (value, None),
+            };
+            managers.push(ContextManager { expression: self.expression(manager)?, target });
+        }
+        Ok(Some(WithClause {
+            managers,
+            is_async: node.child(0).is_some_and(|child| child.kind() == "async"),
+            parenthesized: clause.child(0).is_some_and(|child| child.kind() == "("),
+        }))
+    }
+
+    fn nested(&self, node: Node, tail: bool) -> Result<Vec<BodyStatement>> {
+        let mut statements = Vec::new();
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let kind = child.kind();
+            if self.is_block(child) {
+                statements.extend(self.block(child, tail)?);
+            } else if kind == "match_arm" {
+                match child.child_by_field_name("value") {
+                    Some(value) if self.is_block(value) => statements.extend(self.block(value, tail)?),
+                    Some(value) => statements.push(self.expression_statement(value, tail)?),
+                    None => {}
+                }
+            } else if is_control_flow(kind) && self.is_rust() {
+                // `else if` nests the next `if` inside the else clause
+                statements.push(self.control_flow(child, tail)?);
+            } else if is_clause(kind) {
+                statements.extend(self.nested(child, tail)?);
+            } else if kind.ends_with("_statement") || kind.ends_with("_declaration") {
+                // Braceless JavaScript branch, e.g. `if (done) return;`
+                statements.push(self.statement(child, false)?);
+            }
+        }
+        Ok(statements)
+    }
+
+    /// Attach the value a statement returns, yields or assigns, with its JSX tree if it has one
+    fn with_value(&self, mut statement: BodyStatement, value: Node) -> Result<BodyStatement> {
+        statement.jsx = extract_jsx(value, self.source)?;
+        Ok(statement.with_expression(self.expression(value)?))
+    }
+
+    fn statement_for(&self, node: Node, kind: StatementKind) -> Result<BodyStatement> {
+        Ok(BodyStatement::new(kind, self.code(node)?, node.start_position().row))
+    }
+
+    /// The node's expression tree, its source text dedented like `code`
+    fn expression(&self, node: Node) -> Result<ExpressionAST> {
+        let mut expression = self.expressions.extract_expression(node, self.source)?;
+        expression.source_text = self.dedent(&expression.source_text, node);
+        Ok(expression)
+    }
+
+    fn code(&self, node: Node) -> Result<String> {
+        Ok(self.dedent(node.utf8_text(self.source.as_bytes())?, node))
+    }
+
+    fn dedent(&self, text: &str, node: Node) -> String {
+        dedent(self.source, text, node)
+    }
+}
+
+/// Strip the indentation of the line `node` starts on from the text's
+/// continuation lines, so nested lines keep only their relative indent.
+/// Lines inside a multi-line string literal are its content and are kept
+/// as written.
+pub(crate) fn dedent(source: &str, text: &str, node: Node) -> String {
+    let line_start = node.start_byte() - node.start_position().column;
+    let start_line = &source[line_start..];
+    let base = start_line.len() - start_line.trim_start_matches([' ', '\t']).len();
+    let verbatim = verbatim_rows(node);
+    let mut lines = text.lines();
+    let mut dedented = lines.next().unwrap_or_default().to_string();
+    for (row, line) in (node.start_position().row + 1..).zip(lines) {
+        dedented.push('\n');
+        if verbatim.binary_search(&row).is_ok() {
+            dedented.push_str(line);
+            continue;
+        }
+        let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+        dedented.push_str(&line[indent.min(base)..]);
+    }
+    dedented
+}
+
+fn is_control_flow(kind: &str) -> bool {
+    matches!(
+        kind,
+        "if_statement" | "for_statement" | "for_in_statement" | "while_statement" | "do_statement"
+            | "switch_statement" | "try_statement" | "with_statement" | "match_statement"
+            | "raise_statement" | "throw_statement" | "break_statement" | "continue_statement"
+            | "if_expression" | "match_expression" | "for_expression" | "while_expression"
+            | "loop_expression" | "break_expression" | "continue_expression"
+    )
+}
+
+/// Nodes that only group the statements of a control-flow statement
+fn is_clause(kind: &str) -> bool {
+    matches!(
+        kind,
+        "else_clause" | "elif_clause" | "except_clause" | "finally_clause" | "catch_clause"
+            | "case_clause" | "match_block" | "switch_body" | "switch_case" | "switch_default"
+    )
+}
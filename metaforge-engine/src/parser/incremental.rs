@@ -0,0 +1,199 @@
+//! Incremental re-parsing for watch and editor workflows
+
+use anyhow::{bail, Result};
+use tree_sitter::{InputEdit, Point, Tree};
+
+use crate::core::SemanticBlock;
+use super::extraction_context::ParseResult;
+
+/// One replacement of a byte range of a file's source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceEdit {
+    pub start_byte: usize,
+    /// End of the replaced range in the old source
+    pub old_end_byte: usize,
+    pub replacement: String,
+    start_position: Point,
+    old_end_position: Point,
+}
+
+impl SourceEdit {
+    /// Replace `old_source[start_byte..old_end_byte]` with `replacement`
+    pub fn new(old_source: &str, start_byte: usize, old_end_byte: usize, replacement: impl Into<String>) -> Result<Self> {
+        if start_byte > old_end_byte || old_end_byte > old_source.len() {
+            bail!("Edit range {}..{} is outside the {}-byte source", start_byte, old_end_byte, old_source.len());
+        }
+        if !old_source.is_char_boundary(start_byte) || !old_source.is_char_boundary(old_end_byte) {
+            bail!("Edit range {}..{} splits a character", start_byte, old_end_byte);
+        }
+        Ok(Self {
+            start_byte,
+            old_end_byte,
+            replacement: replacement.into(),
+            start_position: point_at(old_source, start_byte),
+            old_end_position: point_at(old_source, old_end_byte),
+        })
+    }
+
+    /// The single edit turning `old_source` into `new_source`: whatever lies
+    /// between their longest common prefix and suffix
+    pub fn between(old_source: &str, new_source: &str) -> Result<Self> {
+        let mut prefix = old_source.bytes().zip(new_source.bytes())
+            .take_while(|(old, new)| old == new)
+            .count();
+        while !old_source.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let mut suffix = old_source[prefix..].bytes().rev().zip(new_source[prefix..].bytes().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        while !old_source.is_char_boundary(old_source.len() - suffix) {
+            suffix -= 1;
+        }
+
+        let edit = Self::new(old_source, prefix, old_source.len() - suffix, &new_source[prefix..new_source.len() - suffix])?;
+        debug_assert_eq!(edit.apply(old_source), new_source);
+        Ok(edit)
+    }
+
+    /// End of the replacement in the new source
+    pub fn new_end_byte(&self) -> usize {
+        self.start_byte + self.replacement.len()
+    }
+
+    /// `old_source` with the edit applied
+    pub fn apply(&self, old_source: &str) -> String {
+        let mut source = String::with_capacity(old_source.len() + self.replacement.len());
+        source.push_str(&old_source[..self.start_byte]);
+        source.push_str(&self.replacement);
+        source.push_str(&old_source[self.old_end_byte..]);
+        source
+    }
+
+    /// The edit as tree-sitter describes it
+    pub fn input_edit(&self) -> InputEdit {
+        InputEdit {
+            start_byte: self.start_byte,
+            old_end_byte: self.old_end_byte,
+            new_end_byte: self.new_end_byte(),
+            start_position: self.start_position,
+            old_end_position: self.old_end_position,
+            new_end_position: advance(self.start_position, &self.replacement),
+        }
+    }
+
+    /// 0-based rows of the new source the replacement spans
+    fn new_rows(&self) -> (usize, usize) {
+        (self.start_position.row, advance(self.start_position, &self.replacement).row)
+    }
+}
+
+/// The result of an incremental (or fallback full) reparse
+#[derive(Debug)]
+pub struct IncrementalParse {
+    pub result: ParseResult,
+    /// Indexes into `result.blocks` of the blocks whose subtrees changed
+    pub changed: Vec<usize>,
+    /// The new tree, to pass as the old tree for the next edit
+    pub tree: Tree,
+    /// Whether a previous tree was reused
+    pub incremental: bool,
+}
+
+impl IncrementalParse {
+    pub fn changed_blocks(&self) -> impl Iterator<Item = &SemanticBlock> {
+        self.changed.iter().map(|&index| &self.result.blocks[index])
+    }
+}
+
+/// Indexes of the blocks overlapping the edit or a range whose syntax
+/// changed between `old_tree` (already edited) and `new_tree`
+pub(crate) fn changed_blocks(blocks: &[SemanticBlock], edit: &SourceEdit, old_tree: &Tree, new_tree: &Tree) -> Vec<usize> {
+    // A change inside a token, like a renamed identifier, leaves the tree's
+    // shape alone, so the edit itself always counts
+    let mut rows = vec![edit.new_rows()];
+    rows.extend(old_tree.changed_ranges(new_tree).map(|range| (range.start_point.row, range.end_point.row)));
+
+    blocks.iter()
+        .enumerate()
+        .filter(|(_, block)| rows.iter().any(|&(start, end)| block.position.start_line <= end && block.position.end_line >= start))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Row and byte column of `byte` in `source`
+fn point_at(source: &str, byte: usize) -> Point {
+    advance(Point::new(0, 0), &source[..byte])
+}
+
+/// The point reached after `text` starting from `start`
+fn advance(start: Point, text: &str) -> Point {
+    match text.rfind('\n') {
+        Some(last_newline) => Point::new(
+            start.row + text.matches('\n').count(),
+            text.len() - last_newline - 1,
+        ),
+        None => Point::new(start.row, start.column + text.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that an edit reparses incrementally and reports only the blocks it touched
+    #[test]
+    fn test_incremental_reparse_reports_changed_blocks() -> Result<()> {
+        let source = "def load(path):\n    return open(path).read()\n\n\ndef save(path, data):\n    open(path, 'w').write(data)\n";
+        let mut parser = UniversalParser::new()?.with_tree_retention();
+        let initial = parser.parse_file(source, "python", "io.py")?;
+        assert!(parser.retained_tree("io.py").is_some());
+    
+        let start = source.rfind("data)").unwrap();
+        let edit = SourceEdit::new(source, start, start + 4, "payload")?;
+        let edited = edit.apply(source);
+        assert!(edited.ends_with("write(payload)\n"));
+    
+        let reparsed = parser.reparse_edited(&edit, &edited, "python", "io.py")?;
+        assert!(reparsed.incremental);
+        assert_eq!(reparsed.result.blocks.len(), initial.blocks.len());
+        let changed: Vec<&str> = reparsed.changed_blocks()
+            .map(|block| block.semantic_identity.canonical_name.as_str())
+            .collect();
+        assert_eq!(changed, vec!["save"]);
+        // Same blocks as a full parse of the edited source
+        let full = UniversalParser::new()?.parse_file(&edited, "python", "io.py")?;
+        let names = |result: &ParseResult| result.blocks.iter()
+            .map(|block| (block.semantic_identity.canonical_name.clone(), block.syntax_preservation.original_text.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(names(&reparsed.result), names(&full));
+    
+        // Without a previous tree everything is parsed, and changed
+        let fresh = UniversalParser::new()?.parse_incremental(None, &edit, &edited, "python", "io.py")?;
+        assert!(!fresh.incremental);
+        assert_eq!(fresh.changed.len(), fresh.result.blocks.len());
+    
+        assert!(SourceEdit::new(source, 10, source.len() + 1, "").is_err());
+        Ok(())
+    }
+
+    /// Test that the edit between two versions of a file spans only what differs
+    #[test]
+    fn test_source_edit_between_versions() -> Result<()> {
+        let old = "def greet():\n    return \"héllo\"\n";
+        let new = "def greet():\n    return \"hèllo\"\n";
+        let edit = SourceEdit::between(old, new)?;
+        assert_eq!(edit.start_byte, old.find('é').unwrap());
+        assert_eq!(edit.old_end_byte, edit.start_byte + 'é'.len_utf8());
+        assert_eq!(edit.replacement, "è");
+        assert_eq!(edit.apply(old), new);
+    
+        // A whole file counts as inserted into an empty one
+        let inserted = SourceEdit::between("", new)?;
+        assert_eq!((inserted.start_byte, inserted.old_end_byte), (0, 0));
+        assert_eq!(inserted.replacement, new);
+        assert_eq!(SourceEdit::between(old, old)?.replacement, "");
+        Ok(())
+    }
+}
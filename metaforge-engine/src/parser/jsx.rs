@@ -0,0 +1,113 @@
+//! JSX element extraction
+
+use anyhow::Result;
+use tree_sitter::Node;
+
+use crate::core::{JsxAttribute, JsxChild, JsxElement, JsxNode, JsxSpacing};
+use super::function_body::dedent;
+
+/// The element tree of `node` when it is a JSX element, looking through parentheses
+pub fn extract_jsx(node: Node, source: &str) -> Result<Option<JsxElement>> {
+    let mut node = node;
+    while node.kind() == "parenthesized_expression" {
+        match node.named_child(0) {
+            Some(inner) => node = inner,
+            None => return Ok(None),
+        }
+    }
+    match node.kind() {
+        "jsx_element" | "jsx_self_closing_element" => Ok(Some(extract_element(node, source)?)),
+        _ => Ok(None),
+    }
+}
+
+fn extract_element(node: Node, source: &str) -> Result<JsxElement> {
+    let self_closing = node.kind() == "jsx_self_closing_element";
+    let opening = if self_closing { Some(node) } else { node.child_by_field_name("open_tag") };
+    let mut element = JsxElement {
+        tag: None,
+        attributes: Vec::new(),
+        children: Vec::new(),
+        self_closing,
+        multiline: false,
+    };
+
+    if let Some(opening) = opening {
+        if let Some(name) = opening.child_by_field_name("name") {
+            element.tag = Some(name.utf8_text(source.as_bytes())?.to_string());
+        }
+        let mut cursor = opening.walk();
+        for attribute in opening.children_by_field_name("attribute", &mut cursor) {
+            element.attributes.push(extract_attribute(attribute, source)?);
+        }
+    }
+    if self_closing {
+        return Ok(element);
+    }
+
+    // Whitespace between children decides their spacing, not their content
+    let mut previous_end = opening.map(|opening| opening.end_byte()).unwrap_or(node.start_byte());
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let (start, end, jsx_node) = match child.kind() {
+            "jsx_text" | "html_character_reference" => {
+                let raw = child.utf8_text(source.as_bytes())?;
+                let text = raw.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                let start = child.start_byte() + (raw.len() - raw.trim_start().len());
+                (start, start + text.len(), JsxNode::Text(text.to_string()))
+            }
+            "jsx_expression" => (child.start_byte(), child.end_byte(), JsxNode::Expression(braced(child, source)?)),
+            "jsx_element" | "jsx_self_closing_element" => {
+                (child.start_byte(), child.end_byte(), JsxNode::Element(Box::new(extract_element(child, source)?)))
+            }
+            _ => continue,
+        };
+        element.children.push(JsxChild { spacing: spacing(&source[previous_end..start]), node: jsx_node });
+        previous_end = end;
+    }
+    if let Some(closing) = node.child_by_field_name("close_tag") {
+        element.multiline = source[previous_end..closing.start_byte()].contains('\n');
+    }
+    Ok(element)
+}
+
+fn extract_attribute(node: Node, source: &str) -> Result<JsxAttribute> {
+    if node.kind() == "jsx_expression" {
+        return Ok(JsxAttribute::Expression { expression: braced(node, source)? });
+    }
+    let name = node.named_child(0)
+        .map(|name| name.utf8_text(source.as_bytes()))
+        .transpose()?
+        .unwrap_or_default()
+        .to_string();
+    let value = match node.named_child(1) {
+        Some(value) => Some(match value.kind() {
+            "jsx_expression" => JsxNode::Expression(braced(value, source)?),
+            "jsx_element" | "jsx_self_closing_element" => JsxNode::Element(Box::new(extract_element(value, source)?)),
+            _ => JsxNode::Text(value.utf8_text(source.as_bytes())?.to_string()),
+        }),
+        None => None,
+    };
+    Ok(JsxAttribute::Named { name, value })
+}
+
+/// A `{…}` node's text without the braces
+fn braced(node: Node, source: &str) -> Result<String> {
+    let text = node.utf8_text(source.as_bytes())?;
+    let inner = text.strip_prefix('{').unwrap_or(text);
+    let inner = inner.strip_suffix('}').unwrap_or(inner);
+    Ok(dedent(source, inner, node))
+}
+
+fn spacing(gap: &str) -> JsxSpacing {
+    if gap.contains('\n') {
+        JsxSpacing::Newline
+    } else if gap.is_empty() {
+        JsxSpacing::None
+    } else {
+        JsxSpacing::Space
+    }
+}
@@ -1,9 +1,19 @@
 pub mod universal;
 pub mod extractors;
 pub mod extraction_context;
+pub mod comments;
+pub mod debt_markers;
+pub mod function_body;
+pub mod jsx;
+pub mod incremental;
+pub mod string_literals;
 
 // Re-export core types for external use
 #[allow(unused_imports)]
-pub use extraction_context::{ExtractionContext, ParseResult, BlockRelationship, RelationshipType, LanguageExtractor};
+pub use extraction_context::{ExtractionContext, ExtractionProfile, ParseResult, BlockRelationship, RelationshipType, LanguageExtractor};
+#[allow(unused_imports)]
+pub use incremental::{IncrementalParse, SourceEdit};
+#[allow(unused_imports)]
+pub use universal::extract_block_from_snippet;
 
 // pub use universal::UniversalParser;
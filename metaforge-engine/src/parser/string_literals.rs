@@ -0,0 +1,116 @@
+//! Multi-line string literals as no-format spans
+
+use std::collections::BTreeSet;
+use tree_sitter::{Node, Parser};
+
+use super::universal::grammar_for;
+
+/// Whether a node of this kind is a string literal in one of the bundled grammars
+pub fn is_string_literal(kind: &str) -> bool {
+    matches!(
+        kind,
+        "string" | "template_string" | "string_literal" | "raw_string_literal"
+            | "interpreted_string_literal" | "text_block"
+    )
+}
+
+/// Rows of the source, in order, that start inside a string literal under
+/// `node`: every row of a multi-line literal after its first
+pub fn verbatim_rows(node: Node) -> Vec<usize> {
+    let mut rows = BTreeSet::new();
+    collect_verbatim_rows(node, &mut rows);
+    rows.into_iter().collect()
+}
+
+fn collect_verbatim_rows(node: Node, rows: &mut BTreeSet<usize>) {
+    if is_string_literal(node.kind()) {
+        rows.extend(node.start_position().row + 1..=node.end_position().row);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_verbatim_rows(child, rows);
+    }
+}
+
+/// 0-based lines of `code` that start inside a string literal. Empty for
+/// languages without a bundled grammar.
+pub fn verbatim_lines(code: &str, language: &str) -> Vec<usize> {
+    if !code.contains('\n') {
+        return Vec::new();
+    }
+    let Some(grammar) = grammar_for(ast_extractor::Language::canonical_name(language)) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(grammar).is_err() {
+        return Vec::new();
+    }
+    match parser.parse(code, None) {
+        Some(tree) => verbatim_rows(tree.root_node()),
+        None => Vec::new(),
+    }
+}
+
+/// Each line of `code` passed through `format`, except those starting
+/// inside a string literal, which are kept verbatim
+pub fn map_lines_outside_strings(code: &str, language: &str, format: impl Fn(&str) -> String) -> String {
+    let verbatim = verbatim_lines(code, language);
+    code.lines()
+        .enumerate()
+        .map(|(index, line)| if verbatim.binary_search(&index).is_ok() { line.to_string() } else { format(line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use uuid::Uuid;
+    use crate::core::FunctionBody;
+    use crate::database::Block;
+    use crate::generator::formatters::LanguageFormatters;
+    use crate::generator::templates::TemplateEngine;
+    use crate::parser::universal::UniversalParser;
+
+    /// Test that multi-line string literals keep their exact content through
+    /// extraction, regeneration and the builtin formatters
+    #[test]
+    fn test_multi_line_strings_round_trip_verbatim() -> Result<()> {
+        let body = [
+            "    query = \"\"\"",
+            "SELECT name,",
+            "       total",
+            "  FROM orders",
+            "    ",
+            " WHERE total > 100",
+            "\"\"\"",
+            "    if db:",
+            "        db.execute(\"\"\"",
+            "            DELETE FROM cache",
+            "        \"\"\")",
+            "    return db.fetch(query)",
+        ].join("\n");
+        let source = format!("def report(db):\n{}\n", body);
+        let mut parser = UniversalParser::new()?;
+        let parse_result = parser.parse_file(&source, "python", "report.py")?;
+        let function_body = FunctionBody::from_abstract_syntax(&parse_result.blocks[0].syntax_preservation.normalized_ast)
+            .expect("body attached");
+        // Only the statement's own indentation is stripped, not the string's
+        assert_eq!(function_body.statements[0].code, body.lines().take(7).collect::<Vec<_>>().join("\n").trim_start());
+    
+        let block = Block {
+            body_ast: Some(function_body.to_value()),
+            source_language: Some("python".to_string()),
+            ..Block::new(Uuid::new_v4(), "Function", "report", serde_json::json!({}))
+        };
+        let rendered = TemplateEngine::new().render_block(&block, "python")?;
+        assert!(rendered.contains(&body), "{}", rendered);
+    
+        // Java has no external formatter, so the line-trimming fallback always runs
+        let java = "class Report {\n    String query = \"\"\"\n        SELECT *\n          FROM orders\n        \"\"\";\n    }";
+        let formatted = LanguageFormatters::new().format_code(java, "java")?;
+        assert_eq!(formatted, "class Report {\nString query = \"\"\"\n        SELECT *\n          FROM orders\n        \"\"\";\n}");
+        Ok(())
+    }
+}
@@ -3,8 +3,13 @@ use anyhow::{Result, anyhow};
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use super::extractors::{PythonExtractor, JavaScriptExtractor, RustExtractor};
-use super::extraction_context::{ParseResult, LanguageExtractor};
+use ast_extractor::Language;
+use crate::core::SemanticBlock;
+use super::extractors::{PythonExtractor, JavaScriptExtractor, RustExtractor, GoExtractor, JavaExtractor};
+use super::extraction_context::{ParseResult, LanguageExtractor, ExtractionProfile};
+use super::comments::attach_block_comments;
+use super::debt_markers::attach_debt_markers;
+use super::incremental::{changed_blocks, IncrementalParse, SourceEdit};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UniversalBlock {
@@ -22,6 +27,7 @@ pub struct UniversalBlock {
 pub enum BlockType {
     Function,
     Class,
+    Enum,
     Interface,
     Variable,
     Import,
@@ -35,6 +41,7 @@ pub enum BlockType {
     Query,
     Config,
     Module,
+    Statement,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,11 +61,42 @@ pub struct SimplifiedNode {
     pub modifiers: Vec<String>,
 }
 
+/// Languages with a bundled tree-sitter grammar
+pub const SUPPORTED_LANGUAGES: &[&str] = &["python", "javascript", "typescript", "tsx", "rust", "go", "java"];
+
+/// Tree-sitter grammar used to parse (and re-parse) a language
+pub fn grammar_for(language: &str) -> Option<tree_sitter::Language> {
+    match language {
+        "python" => Some(tree_sitter_python::language()),
+        "javascript" => Some(tree_sitter_javascript::language()),
+        "typescript" => Some(tree_sitter_typescript::language_typescript()),
+        "tsx" => Some(tree_sitter_typescript::language_tsx()),
+        "rust" => Some(tree_sitter_rust::language()),
+        "go" => Some(tree_sitter_go::language()),
+        "java" => Some(tree_sitter_java::language()),
+        _ => None,
+    }
+}
+
+/// File path recorded on blocks extracted from a snippet
+pub const SNIPPET_PATH: &str = "<snippet>";
+
+/// Blocks of a standalone snippet, such as a function pasted into an
+/// editor: parsed and extracted like a file, but without a repository,
+/// container or database. `language` may be an alias such as `py`.
+pub fn extract_block_from_snippet(code: &str, language: &str) -> Result<Vec<SemanticBlock>> {
+    let language = Language::canonical_name(language);
+    let mut blocks = UniversalParser::new()?.parse_file(code, language, SNIPPET_PATH)?.blocks;
+    blocks.sort_by_key(|block| block.position.index);
+    Ok(blocks)
+}
+
 pub struct UniversalParser {
     parsers: HashMap<String, Parser>,
-    python_extractor: PythonExtractor,
-    javascript_extractor: JavaScriptExtractor,
-    rust_extractor: RustExtractor,
+    extractors: HashMap<String, Box<dyn LanguageExtractor>>,
+    profile: ExtractionProfile,
+    /// Each file's latest tree, by path, when retention is enabled
+    retained_trees: Option<HashMap<String, Tree>>,
 }
 
 #[allow(dead_code)]
@@ -67,57 +105,157 @@ impl UniversalParser {
         let mut parsers = HashMap::new();
         
         // Initialize core language parsers for MVP
-        let languages = vec![
-            ("python", tree_sitter_python::language()),
-            ("javascript", tree_sitter_javascript::language()),
-            ("typescript", tree_sitter_typescript::language_typescript()),
-            ("tsx", tree_sitter_typescript::language_tsx()),
-            ("rust", tree_sitter_rust::language()),
-        ];
-        
-        for (name, language) in languages {
+        for &name in SUPPORTED_LANGUAGES {
+            let language = grammar_for(name)
+                .ok_or_else(|| anyhow!("No grammar for language: {}", name))?;
             let mut parser = Parser::new();
             parser.set_language(language)?;
             parsers.insert(name.to_string(), parser);
         }
         
+        let mut extractors: HashMap<String, Box<dyn LanguageExtractor>> = HashMap::new();
+        extractors.insert("python".to_string(), Box::new(PythonExtractor));
+        extractors.insert("javascript".to_string(), Box::new(JavaScriptExtractor { is_typescript: false }));
+        extractors.insert("typescript".to_string(), Box::new(JavaScriptExtractor { is_typescript: true }));
+        extractors.insert("tsx".to_string(), Box::new(JavaScriptExtractor { is_typescript: true }));
+        extractors.insert("rust".to_string(), Box::new(RustExtractor));
+        extractors.insert("go".to_string(), Box::new(GoExtractor));
+        extractors.insert("java".to_string(), Box::new(JavaExtractor));
+        
         Ok(Self { 
             parsers,
-            python_extractor: PythonExtractor,
-            javascript_extractor: JavaScriptExtractor { is_typescript: false },
-            rust_extractor: RustExtractor,
+            extractors,
+            profile: ExtractionProfile::full(),
+            retained_trees: None,
         })
     }
     
+    /// Only run the analyses enabled in `profile` when extracting
+    pub fn with_profile(mut self, profile: ExtractionProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+    
+    /// Keep each file's latest tree so `reparse_edited` can reparse it
+    /// incrementally
+    pub fn with_tree_retention(mut self) -> Self {
+        self.retained_trees = Some(HashMap::new());
+        self
+    }
+    
+    /// The tree retained from the last parse of `file_path`
+    pub fn retained_tree(&self, file_path: &str) -> Option<&Tree> {
+        self.retained_trees.as_ref()?.get(file_path)
+    }
+    
+    /// Register the tree-sitter grammar for a language the crate does not bundle.
+    ///
+    /// Together with `register_extractor` this lets downstream crates add
+    /// languages without modifying the parser.
+    pub fn register_grammar(&mut self, language: &str, grammar: tree_sitter::Language) -> Result<()> {
+        let mut parser = Parser::new();
+        parser.set_language(grammar)?;
+        self.parsers.insert(language.to_string(), parser);
+        Ok(())
+    }
+    
+    /// Register (or replace) the extractor used for `language`.
+    ///
+    /// The language must have a grammar, either bundled or added with
+    /// `register_grammar`, before files in it can be parsed.
+    pub fn register_extractor(&mut self, language: &str, extractor: Box<dyn LanguageExtractor>) {
+        self.extractors.insert(language.to_string(), extractor);
+    }
+    
+    /// Languages that have both a grammar and an extractor
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self.extractors.keys()
+            .filter(|language| self.parsers.contains_key(*language))
+            .cloned()
+            .collect();
+        languages.sort();
+        languages
+    }
+    
     pub fn parse_file(&mut self, content: &str, language: &str, file_path: &str) -> Result<ParseResult> {
+        let tree = self.parse_tree(content, language, None)?;
+        let extraction_result = self.extract_from_tree(&tree, content, language, file_path)?;
+        self.retain_tree(file_path, tree);
+        Ok(extraction_result)
+    }
+    
+    /// Reparse `new_source`, the result of applying `edit` to the source
+    /// `old_tree` was parsed from, reusing the unchanged parts of that tree.
+    /// Without `old_tree` the file is parsed in full and every block counts
+    /// as changed. See `parser::incremental` for which extractors support it.
+    pub fn parse_incremental(
+        &mut self,
+        old_tree: Option<&Tree>,
+        edit: &SourceEdit,
+        new_source: &str,
+        language: &str,
+        file_path: &str,
+    ) -> Result<IncrementalParse> {
+        let Some(old_tree) = old_tree else {
+            let tree = self.parse_tree(new_source, language, None)?;
+            let result = self.extract_from_tree(&tree, new_source, language, file_path)?;
+            self.retain_tree(file_path, tree.clone());
+            return Ok(IncrementalParse {
+                changed: (0..result.blocks.len()).collect(),
+                result,
+                tree,
+                incremental: false,
+            });
+        };
+
+        let mut edited_tree = old_tree.clone();
+        edited_tree.edit(&edit.input_edit());
+        let tree = self.parse_tree(new_source, language, Some(&edited_tree))?;
+        let result = self.extract_from_tree(&tree, new_source, language, file_path)?;
+        let changed = changed_blocks(&result.blocks, edit, &edited_tree, &tree);
+        self.retain_tree(file_path, tree.clone());
+        Ok(IncrementalParse { result, changed, tree, incremental: true })
+    }
+    
+    /// `parse_incremental` against the tree retained for `file_path`
+    pub fn reparse_edited(&mut self, edit: &SourceEdit, new_source: &str, language: &str, file_path: &str) -> Result<IncrementalParse> {
+        let old_tree = self.retained_tree(file_path).cloned();
+        self.parse_incremental(old_tree.as_ref(), edit, new_source, language, file_path)
+    }
+    
+    fn parse_tree(&mut self, content: &str, language: &str, old_tree: Option<&Tree>) -> Result<Tree> {
         let parser = self.parsers.get_mut(language)
             .ok_or_else(|| anyhow!("Unsupported language: {}", language))?;
         
-        let tree = parser.parse(content, None)
-            .ok_or_else(|| anyhow!("Failed to parse file"))?;
-        
+        parser.parse(content, old_tree)
+            .ok_or_else(|| anyhow!("Failed to parse file"))
+    }
+    
+    fn extract_from_tree(&self, tree: &Tree, content: &str, language: &str, file_path: &str) -> Result<ParseResult> {
         // Single extraction path - no duplication
-        let mut extraction_result = match language {
-            "python" => self.python_extractor.extract_with_context(tree.root_node(), content, file_path)?,
-            "javascript" => self.javascript_extractor.extract_with_context(tree.root_node(), content, file_path)?,
-            "typescript" => {
-                let ts_extractor = JavaScriptExtractor { is_typescript: true };
-                ts_extractor.extract_with_context(tree.root_node(), content, file_path)?
-            },
-            "tsx" => {
-                let tsx_extractor = JavaScriptExtractor { is_typescript: true };
-                tsx_extractor.extract_with_context(tree.root_node(), content, file_path)?
-            },
-            "rust" => self.rust_extractor.extract_with_context(tree.root_node(), content, file_path)?,
-            _ => return Err(anyhow!("No extractor for language: {}", language))
-        };
+        let extractor = self.extractors.get(language)
+            .ok_or_else(|| anyhow!("No extractor for language: {}", language))?;
+        let mut extraction_result = extractor.extract_with_profile(tree.root_node(), content, file_path, &self.profile)?;
         
         // Second pass: resolve relationships
         extraction_result.resolve_relationships();
         
+        if self.profile.debt_markers {
+            attach_debt_markers(tree.root_node(), content, &mut extraction_result.blocks);
+        }
+        if self.profile.comments {
+            attach_block_comments(tree.root_node(), content, &mut extraction_result.blocks);
+        }
+        
         Ok(extraction_result)
     }
     
+    fn retain_tree(&mut self, file_path: &str, tree: Tree) {
+        if let Some(trees) = &mut self.retained_trees {
+            trees.insert(file_path.to_string(), tree);
+        }
+    }
+    
     // Legacy method for backward compatibility - converts to old format
     pub fn parse_file_legacy(&mut self, content: &str, language: &str, file_path: &str) -> Result<Vec<UniversalBlock>> {
         let parse_result = self.parse_file(content, language, file_path)?;
@@ -226,7 +364,7 @@ impl UniversalParser {
             // Rust
             ("rust", "function_item") => Some(BlockType::Function),
             ("rust", "struct_item") => Some(BlockType::Class),
-            ("rust", "enum_item") => Some(BlockType::Class),
+            ("rust", "enum_item") => Some(BlockType::Enum),
             ("rust", "impl_item") => Some(BlockType::Class),
             ("rust", "trait_item") => Some(BlockType::Interface),
             ("rust", "macro_definition") => Some(BlockType::Function),
@@ -364,6 +502,7 @@ impl UniversalParser {
         match block_type {
             BlockType::Function => crate::core::BlockType::Function,
             BlockType::Class => crate::core::BlockType::Class,
+            BlockType::Enum => crate::core::BlockType::Enum,
             BlockType::Interface => crate::core::BlockType::Interface,
             BlockType::Variable => crate::core::BlockType::Variable,
             BlockType::Import => crate::core::BlockType::Import,
@@ -377,6 +516,7 @@ impl UniversalParser {
             BlockType::Query => crate::core::BlockType::Query,
             BlockType::Config => crate::core::BlockType::Config,
             BlockType::Module => crate::core::BlockType::Module,
+            BlockType::Statement => crate::core::BlockType::Statement,
         }
     }
     
@@ -384,6 +524,7 @@ impl UniversalParser {
         match block_type {
             crate::core::BlockType::Function => BlockType::Function,
             crate::core::BlockType::Class => BlockType::Class,
+            crate::core::BlockType::Enum => BlockType::Enum,
             crate::core::BlockType::Interface => BlockType::Interface,
             crate::core::BlockType::Variable => BlockType::Variable,
             crate::core::BlockType::Import => BlockType::Import,
@@ -397,6 +538,136 @@ impl UniversalParser {
             crate::core::BlockType::Query => BlockType::Query,
             crate::core::BlockType::Config => BlockType::Config,
             crate::core::BlockType::Module => BlockType::Module,
+            crate::core::BlockType::Statement => BlockType::Statement,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::BlockType;
+    use crate::parser::ExtractionContext;
+
+    /// Test that standalone function and class snippets are extracted without a
+    /// file, container or database
+    #[test]
+    fn test_extract_blocks_from_snippets() -> Result<()> {
+        let snippets = [
+            ("python", "def add(a, b):\n    return a + b\n", "class Counter:\n    def inc(self):\n        self.n += 1\n"),
+            ("js", "function add(a, b) {\n  return a + b;\n}\n", "class Counter {\n  inc() { this.n += 1; }\n}\n"),
+            ("typescript", "function add(a: number, b: number): number {\n  return a + b;\n}\n", "class Counter {\n  inc(): void { this.n += 1; }\n}\n"),
+            ("rust", "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n", "struct Counter {\n    n: u32,\n}\n"),
+            ("go", "func add(a, b int) int {\n\treturn a + b\n}\n", "type Counter struct {\n\tn int\n}\n"),
+            ("java", "int add(int a, int b) {\n    return a + b;\n}\n", "class Counter {\n    void inc() { n++; }\n}\n"),
+        ];
+        let outline = |blocks: &[SemanticBlock]| -> Vec<(BlockType, String)> {
+            blocks.iter().map(|block| (block.block_type.clone(), block.semantic_identity.canonical_name.clone())).collect()
+        };
+    
+        for (language, function, class) in snippets {
+            let blocks = extract_block_from_snippet(function, language)?;
+            assert_eq!(outline(&blocks), vec![(BlockType::Function, "add".to_string())], "{}", language);
+        
+            let blocks = extract_block_from_snippet(class, language)?;
+            assert_eq!(outline(&blocks)[0], (BlockType::Class, "Counter".to_string()), "{}", language);
+        }
+    
+        assert!(extract_block_from_snippet("x = 1", "cobol").is_err());
+        Ok(())
+    }
+
+    /// Test that accented identifiers and emoji strings extract without panics or corrupted text
+    #[test]
+    fn test_non_ascii_identifiers_and_strings_extract_cleanly() -> Result<()> {
+        let sources = [
+            ("python", "def größe(wert: int) -> str:\n    return \"📏 \" + str(wert)\n\nclass Café:\n    def préparer(self):\n        return \"☕ prêt\"\n", vec!["größe", "Café", "préparer"]),
+            ("rust", "fn grüße(name: &str) -> String {\n    format!(\"👋 {}\", name)\n}\n\nstruct Zählung {\n    wert: u32,\n}\n", vec!["grüße", "Zählung"]),
+            ("javascript", "function saludar(año) {\n  return `🎉 ${año}`;\n}\n\nclass Canción {\n  tocar() { return \"🎵\"; }\n}\n", vec!["saludar", "Canción", "tocar"]),
+            ("typescript", "function saludar(año: number): string {\n  return `🎉 ${año}`;\n}\n", vec!["saludar"]),
+            ("go", "package main\n\nfunc Größe(wert int) string {\n\treturn \"📏\"\n}\n", vec!["Größe"]),
+        ];
+    
+        for (language, source, expected_names) in sources {
+            let parse_result = UniversalParser::new()?.parse_file(source, language, "i18n")?;
+            let names: Vec<&str> = parse_result.blocks.iter()
+                .map(|block| block.semantic_identity.canonical_name.as_str())
+                .collect();
+            for name in expected_names {
+                assert!(names.contains(&name), "{}: {} missing from {:?}", language, name, names);
+            }
+            for block in &parse_result.blocks {
+                assert!(source.contains(&block.syntax_preservation.original_text), "{}: corrupted text for {}", language, block.semantic_identity.canonical_name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Extractor defined outside the crate: the whole file becomes one module block
+    struct WholeFileExtractor;
+
+    impl LanguageExtractor for WholeFileExtractor {
+        fn extract_with_context(&self, _root: tree_sitter::Node, source: &str, file_path: &str) -> Result<ParseResult> {
+            let mut context = ExtractionContext::new();
+            context.enter_block(SemanticBlock::new(
+                BlockType::Module,
+                file_path.to_string(),
+                source.to_string(),
+                "python-dsl".to_string(),
+            ));
+            Ok(context.finish())
+        }
+    }
+
+    /// Test that extractors and grammars can be registered at runtime
+    #[test]
+    fn test_register_extractor() -> Result<()> {
+        let mut parser = UniversalParser::new()?;
+        let source = "def rule():\n    pass\n";
+    
+        assert!(parser.parse_file(source, "python-dsl", "rules.dsl").is_err());
+    
+        parser.register_grammar("python-dsl", tree_sitter_python::language())?;
+        parser.register_extractor("python-dsl", Box::new(WholeFileExtractor));
+        assert!(parser.languages().contains(&"python-dsl".to_string()));
+    
+        let result = parser.parse_file(source, "python-dsl", "rules.dsl")?;
+        assert_eq!(result.blocks.len(), 1);
+        assert!(matches!(result.blocks[0].block_type, BlockType::Module));
+    
+        // Built-in languages can be overridden the same way
+        parser.register_extractor("python", Box::new(WholeFileExtractor));
+        assert_eq!(parser.parse_file(source, "python", "rules.py")?.blocks.len(), 1);
+        Ok(())
+    }
+
+    /// Test that the fast profile skips per-block analyses but keeps the call graph
+    #[test]
+    fn test_fast_extraction_profile() -> Result<()> {
+        let source = "def helper(x: int) -> int:\n    return x\n\ndef main(value):\n    if value:\n        total = helper(value)\n    return total\n";
+    
+        let full = UniversalParser::new()?.parse_file(source, "python", "app.py")?;
+        let fast = UniversalParser::new()?
+            .with_profile(ExtractionProfile::fast())
+            .parse_file(source, "python", "app.py")?;
+    
+        assert_eq!(full.blocks.len(), fast.blocks.len());
+        assert_eq!(full.relationships.len(), fast.relationships.len());
+    
+        let helper = |result: &ParseResult| result.blocks.iter()
+            .find(|b| b.semantic_identity.canonical_name == "helper")
+            .cloned()
+            .expect("helper block");
+        assert_eq!(helper(&full).semantic_metadata.parameters.len(), 1);
+        assert!(helper(&fast).semantic_metadata.parameters.is_empty());
+    
+        let main_block = fast.blocks.iter()
+            .find(|b| b.semantic_identity.canonical_name == "main")
+            .expect("main block");
+        let implementation = &main_block.syntax_preservation.normalized_ast["implementation"];
+        assert!(implementation.get("original_body").is_some());
+        assert!(implementation.get("control_flow").is_none());
+        assert!(implementation.get("variable_assignments").is_none());
+        Ok(())
+    }
+}
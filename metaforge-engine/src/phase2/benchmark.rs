@@ -0,0 +1,294 @@
+// Benchmark harness: per-stage timings over a fixed corpus, gated against a committed baseline
+// Following ARCHITECT principle: Performance Later - Optimize only after profiling
+
+use anyhow::{Context, Result};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::core::SemanticBlock;
+use crate::database::Block;
+use crate::generator::templates::TemplateEngine;
+use crate::parser::universal::UniversalParser;
+use crate::scanner::FileScanner;
+use super::metrics::MetricsCollector;
+
+/// Pipeline stages in the order they run
+pub const STAGES: [&str; 3] = ["extraction", "mapping", "generation"];
+
+/// Slowdowns smaller than this are timer noise, whatever the ratio
+pub const NOISE_FLOOR_MS: f64 = 1.0;
+
+/// One source file of the benchmark corpus
+#[derive(Debug, Clone)]
+pub struct BenchFixture {
+    pub path: String,
+    pub language: String,
+    pub source: String,
+}
+
+/// The corpus bundled with the binary, so every run measures the same input
+pub fn default_corpus() -> Vec<BenchFixture> {
+    [
+        ("fixtures/bench/inventory.py", "python", include_str!("../../fixtures/bench/inventory.py")),
+        ("fixtures/bench/ledger.rs", "rust", include_str!("../../fixtures/bench/ledger.rs")),
+        ("fixtures/bench/router.ts", "typescript", include_str!("../../fixtures/bench/router.ts")),
+        ("fixtures/bench/cache.go", "go", include_str!("../../fixtures/bench/cache.go")),
+    ]
+    .into_iter()
+    .map(|(path, language, source)| BenchFixture {
+        path: path.to_string(),
+        language: language.to_string(),
+        source: source.to_string(),
+    })
+    .collect()
+}
+
+/// Every file under `dir` in a language the parser supports, in path order
+pub fn load_corpus(dir: &Path) -> Result<Vec<BenchFixture>> {
+    let supported = UniversalParser::new()?.languages();
+    let mut fixtures: Vec<BenchFixture> = FileScanner::new().scan_directory(dir)?
+        .into_iter()
+        .filter(|file| supported.contains(&file.language))
+        .map(|file| BenchFixture {
+            path: file.path.display().to_string(),
+            language: file.language,
+            source: file.content,
+        })
+        .collect();
+    fixtures.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(fixtures)
+}
+
+/// Median timing of one stage across iterations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageResult {
+    pub median_ms: f64,
+    /// Files (extraction) or blocks (mapping, generation) handled per iteration
+    pub items: usize,
+    pub throughput_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub stages: BTreeMap<String, StageResult>,
+    pub files: usize,
+    pub blocks: usize,
+    pub iterations: usize,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A stage of the current run measured against the baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageComparison {
+    pub stage: String,
+    pub baseline_ms: f64,
+    pub current_ms: f64,
+    pub ratio: f64,
+    pub regressed: bool,
+}
+
+impl BenchmarkReport {
+    /// Load a baseline written by `save`
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid baseline {}", path.display()))
+    }
+
+    /// Write as pretty JSON so baseline updates review well in a diff
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut contents = serde_json::to_string_pretty(self)?;
+        contents.push('\n');
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    /// Compare each stage present in both reports. A stage regresses when it
+    /// is more than `threshold` times slower and the slowdown exceeds
+    /// `NOISE_FLOOR_MS`.
+    pub fn compare(&self, baseline: &BenchmarkReport, threshold: f64) -> Vec<StageComparison> {
+        self.stages.iter()
+            .filter_map(|(stage, current)| {
+                let base = baseline.stages.get(stage)?;
+                let ratio = if base.median_ms > 0.0 { current.median_ms / base.median_ms } else { 1.0 };
+                Some(StageComparison {
+                    stage: stage.clone(),
+                    baseline_ms: base.median_ms,
+                    current_ms: current.median_ms,
+                    ratio,
+                    regressed: ratio > threshold && current.median_ms - base.median_ms > NOISE_FLOOR_MS,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Runs extraction, mapping and generation over a corpus and records the
+/// timings in a `MetricsCollector`
+pub struct BenchmarkHarness {
+    iterations: usize,
+    template_engine: TemplateEngine,
+}
+
+impl BenchmarkHarness {
+    pub fn new() -> Self {
+        Self {
+            iterations: 5,
+            template_engine: TemplateEngine::new(),
+        }
+    }
+
+    /// Number of timed runs; each stage reports its median
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    pub fn run(&self, corpus: &[BenchFixture], metrics: &mut MetricsCollector) -> Result<BenchmarkReport> {
+        let mut parser = UniversalParser::new()?;
+        let mut samples: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+        let mut blocks_per_run = 0;
+
+        for _ in 0..self.iterations {
+            metrics.start_timer("parsing_time");
+            let start = Instant::now();
+            let mut extracted = Vec::new();
+            for fixture in corpus {
+                let result = parser.parse_file(&fixture.source, &fixture.language, &fixture.path)
+                    .with_context(|| format!("Failed to extract {}", fixture.path))?;
+                extracted.push((fixture, result.blocks));
+            }
+            samples.entry("extraction").or_default().push(elapsed_ms(start));
+            metrics.stop_timer("parsing_time");
+
+            metrics.start_timer("mapping_time");
+            let start = Instant::now();
+            let container_id = Uuid::new_v4();
+            let mapped = extracted.iter()
+                .flat_map(|(fixture, blocks)| blocks.iter().map(|block| Ok((fixture.language.as_str(), map_block(block, container_id)?))))
+                .collect::<Result<Vec<(&str, Block)>>>()?;
+            samples.entry("mapping").or_default().push(elapsed_ms(start));
+            metrics.stop_timer("mapping_time");
+
+            metrics.start_timer("generation_time");
+            let start = Instant::now();
+            let rendered = mapped.iter()
+                .filter(|(language, block)| self.template_engine.render_block(block, language).is_ok())
+                .count();
+            samples.entry("generation").or_default().push(elapsed_ms(start));
+            metrics.stop_timer("generation_time");
+
+            blocks_per_run = mapped.len();
+            metrics.record_count("blocks_rendered", rendered);
+        }
+
+        let stages = samples.into_iter()
+            .map(|(stage, timings)| {
+                let median_ms = median(timings);
+                let items = if stage == "extraction" { corpus.len() } else { blocks_per_run };
+                let throughput_per_sec = if median_ms > 0.0 { items as f64 / (median_ms / 1000.0) } else { 0.0 };
+                (stage.to_string(), StageResult { median_ms, items, throughput_per_sec })
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        metrics.record_count("files", corpus.len());
+        metrics.record_count("blocks", blocks_per_run);
+        if let Some(generation) = stages.get("generation") {
+            metrics.record_metric("throughput_blocks_per_sec", generation.throughput_per_sec);
+        }
+
+        Ok(BenchmarkReport {
+            stages,
+            files: corpus.len(),
+            blocks: blocks_per_run,
+            iterations: self.iterations,
+            recorded_at: Utc::now(),
+        })
+    }
+}
+
+impl Default for BenchmarkHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The row `insert_semantic_block` would store, built in memory
+fn map_block(block: &SemanticBlock, container_id: Uuid) -> Result<Block> {
+    Ok(Block {
+        source_language: Some(block.source_language.clone()),
+        ..Block::from_semantic_block(block, container_id)?
+    })
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        (values[mid - 1] + values[mid]) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase2::metrics::BaselineMetrics;
+
+    #[test]
+    fn test_benchmark_harness_times_each_stage_and_gates_regressions() -> Result<()> {
+        let mut metrics = MetricsCollector::new();
+        let report = BenchmarkHarness::new()
+            .with_iterations(2)
+            .run(&default_corpus(), &mut metrics)?;
+
+        assert_eq!(report.files, 4);
+        assert!(report.blocks > 0);
+        for stage in STAGES {
+            let result = &report.stages[stage];
+            assert!(result.median_ms >= 0.0 && result.items > 0, "{}: {:?}", stage, result);
+        }
+        let summary = metrics.get_metrics_summary();
+        assert!(summary.timings.contains_key("parsing_time"));
+        assert_eq!(summary.counts.get("blocks"), Some(&report.blocks));
+
+        // The baseline survives a save/load round trip
+        let path = std::env::temp_dir().join(format!("metaforge-bench-{}.json", Uuid::new_v4()));
+        report.save(&path)?;
+        let mut baseline = BenchmarkReport::load(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(baseline.blocks, report.blocks);
+        assert!(report.compare(&baseline, 2.0).iter().all(|c| !c.regressed));
+
+        // 3x slower regresses; a 3x change below the noise floor does not
+        baseline.stages.get_mut("extraction").unwrap().median_ms = 10.0;
+        baseline.stages.get_mut("mapping").unwrap().median_ms = 0.1;
+        let mut current = baseline.clone();
+        current.stages.get_mut("extraction").unwrap().median_ms = 30.0;
+        current.stages.get_mut("mapping").unwrap().median_ms = 0.3;
+        let regressed: Vec<String> = current.compare(&baseline, 2.0).into_iter()
+            .filter(|c| c.regressed)
+            .map(|c| c.stage)
+            .collect();
+        assert_eq!(regressed, vec!["extraction"]);
+
+        // The collector exposes the baseline derived from a run
+        metrics.set_baseline(BaselineMetrics::from_benchmark(&current));
+        assert_eq!(metrics.baseline().map(|b| b.parsing_time_ms), Some(30.0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+// Health checks: is this environment ready for a migration run?
+// Gathers the formatter, grammar and database checks scattered across the
+// pipeline into one pre-flight report
+
+use ast_extractor::Language;
+use serde::{Serialize, Deserialize};
+
+use crate::database::{Database, DatabaseConfig};
+use crate::generator::formatters::{get_formatter_with_config, FormatConfig};
+use crate::parser::universal::{grammar_for, SUPPORTED_LANGUAGES};
+use super::validation::ValidationEngine;
+
+/// Outcome of one readiness check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub passed: bool,
+    /// Whether a failure should stop a run; formatters are not, since the
+    /// builtin formatter stands in for a missing one
+    pub critical: bool,
+    pub detail: String,
+}
+
+impl HealthCheck {
+    pub fn new(name: impl Into<String>, passed: bool, critical: bool, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), passed, critical, detail: detail.into() }
+    }
+
+    pub fn status(&self) -> &'static str {
+        match (self.passed, self.critical) {
+            (true, _) => "ok",
+            (false, true) => "missing",
+            (false, false) => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn extend(&mut self, checks: impl IntoIterator<Item = HealthCheck>) {
+        self.checks.extend(checks);
+    }
+
+    /// Critical checks that failed
+    pub fn blocking(&self) -> Vec<&HealthCheck> {
+        self.checks.iter().filter(|check| check.critical && !check.passed).collect()
+    }
+
+    /// True when every critical check passed
+    pub fn is_ready(&self) -> bool {
+        self.blocking().is_empty()
+    }
+
+    /// One row per check, columns aligned
+    pub fn render_table(&self) -> String {
+        let mut rows = vec![["Check", "Status", "Detail"].map(String::from).to_vec()];
+        rows.extend(self.checks.iter().map(|check| {
+            vec![check.name.clone(), check.status().to_string(), check.detail.clone()]
+        }));
+
+        let widths: Vec<usize> = (0..3)
+            .map(|column| rows.iter().map(|cells| cells[column].len()).max().unwrap_or(0))
+            .collect();
+        rows.iter()
+            .map(|cells| {
+                cells.iter().zip(&widths)
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Whether each supported language's tree-sitter grammar loads
+pub fn check_grammars() -> Vec<HealthCheck> {
+    SUPPORTED_LANGUAGES.iter()
+        .map(|language| {
+            let loaded = grammar_for(language)
+                .map(|grammar| tree_sitter::Parser::new().set_language(grammar))
+                .transpose();
+            let name = format!("grammar {}", language);
+            match loaded {
+                Ok(Some(())) => HealthCheck::new(name, true, true, ""),
+                Ok(None) => HealthCheck::new(name, false, true, "no grammar compiled in"),
+                Err(e) => HealthCheck::new(name, false, true, e.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Whether the external formatter of each supported language is installed,
+/// once per tool
+pub fn check_formatters(config: &FormatConfig) -> Vec<HealthCheck> {
+    let mut tools: Vec<(&str, Vec<&str>, bool)> = Vec::new();
+    for &language in SUPPORTED_LANGUAGES {
+        let Some(tool) = language.parse::<Language>().ok().and_then(Language::default_formatter) else {
+            continue;
+        };
+        match tools.iter_mut().find(|(known, _, _)| *known == tool) {
+            Some((_, languages, _)) => languages.push(language),
+            None => tools.push((tool, vec![language], get_formatter_with_config(language, config).is_available())),
+        }
+    }
+    tools.into_iter()
+        .map(|(tool, languages, available)| {
+            let mut detail = languages.join(", ");
+            if !available {
+                detail.push_str("; builtin formatting used instead");
+            }
+            HealthCheck::new(format!("formatter {}", tool), available, false, detail)
+        })
+        .collect()
+}
+
+/// Whether the database is reachable and its schema has the columns the
+/// hierarchical pipeline needs
+pub async fn check_database(database_url: &str, config: &DatabaseConfig) -> Vec<HealthCheck> {
+    let db = match Database::with_config(database_url, config).await {
+        Ok(db) => db,
+        Err(e) => {
+            return vec![
+                HealthCheck::new("database", false, true, e.to_string()),
+                HealthCheck::new("schema", false, true, "not checked: database unreachable"),
+            ];
+        }
+    };
+    let schema = match ValidationEngine::new(db).verify_schema_alignment().await {
+        Ok(true) => HealthCheck::new("schema", true, true, "migrated"),
+        Ok(false) => HealthCheck::new("schema", false, true, "columns missing; run `metaforge-engine init`"),
+        Err(e) => HealthCheck::new("schema", false, true, e.to_string()),
+    };
+    vec![HealthCheck::new("database", true, true, "reachable"), schema]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_health_report_flags_missing_critical_dependencies() -> Result<()> {
+        let mut report = HealthReport::new();
+        report.extend(check_grammars());
+        assert!(report.is_ready(), "{}", report.render_table());
+        assert_eq!(report.checks.len(), crate::parser::universal::SUPPORTED_LANGUAGES.len());
+    
+        // A missing formatter only warns; builtin formatting takes over
+        let formatters = check_formatters(&FormatConfig::default());
+        let tools: Vec<&str> = formatters.iter().map(|check| check.name.as_str()).collect();
+        assert_eq!(tools, vec!["formatter black", "formatter prettier", "formatter rustfmt", "formatter gofmt"]);
+        assert!(formatters.iter().all(|check| !check.critical));
+        report.extend(formatters);
+        assert!(report.is_ready());
+    
+        // An unreachable database blocks the run, and its schema goes unchecked
+        report.extend(check_database("not a database url", &DatabaseConfig::default()).await);
+        assert!(!report.is_ready());
+        let blocking: Vec<&str> = report.blocking().iter().map(|check| check.name.as_str()).collect();
+        assert_eq!(blocking, vec!["database", "schema"]);
+    
+        let table = report.render_table();
+        assert!(table.starts_with("Check"), "{}", table);
+        assert!(table.lines().any(|line| line.starts_with("grammar python") && line.contains(" ok")), "{}", table);
+        assert!(table.lines().any(|line| line.starts_with("schema") && line.contains("missing")), "{}", table);
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 use crate::database::{Database, Container, Block};
 use crate::generator::templates::TemplateEngine;
+use crate::generator::ordering;
 use std::collections::{HashMap, HashSet};
 use petgraph::{Graph, Directed, graph::NodeIndex};
 use petgraph::algo::toposort;
@@ -81,7 +82,7 @@ impl HierarchicalGenerator {
         let blocks_query = r#"
             SELECT * FROM blocks 
             WHERE container_id = $1 
-            ORDER BY position, hierarchical_index, parent_block_id NULLS FIRST
+            ORDER BY position, hierarchical_index, parent_block_id NULLS FIRST, id
         "#;
         let blocks: Vec<Block> = sqlx::query_as(blocks_query)
             .bind(container_id)
@@ -119,10 +120,12 @@ impl HierarchicalGenerator {
         let mut graph = Graph::<Uuid, (), Directed>::new();
         let mut node_map: HashMap<Uuid, NodeIndex> = HashMap::new();
 
-        // Add all blocks as nodes
-        for block_id in hierarchy.blocks.keys() {
-            let node_idx = graph.add_node(*block_id);
-            node_map.insert(*block_id, node_idx);
+        // Add all blocks as nodes, in generation order so the toposort is stable
+        let mut ordered_blocks: Vec<&Block> = hierarchy.blocks.values().collect();
+        ordered_blocks.sort_by(|a, b| ordering::compare_blocks(a, b));
+        for block in ordered_blocks {
+            let node_idx = graph.add_node(block.id);
+            node_map.insert(block.id, node_idx);
         }
 
         // Add edges for dependencies
@@ -230,8 +233,8 @@ impl HierarchicalGenerator {
                 .filter_map(|child_id| hierarchy.blocks.get(child_id))
                 .collect();
             
-            // Sort children by position_in_parent
-            sorted_children.sort_by_key(|child| child.position_in_parent);
+            // Sort children into the deterministic generation order
+            sorted_children.sort_by(|a, b| ordering::compare_children(a, b));
             
             // Recursively generate children
             for child in sorted_children {
@@ -6,6 +6,7 @@ use serde::{Serialize, Deserialize};
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use super::benchmark::{default_corpus, BenchmarkHarness, BenchmarkReport};
 
 #[derive(Debug, Clone)]
 pub struct MetricsCollector {
@@ -27,18 +28,16 @@ impl MetricsCollector {
     pub async fn establish_baselines(&mut self) -> Result<bool> {
         println!("Establishing baseline performance metrics...");
         
+        let report = BenchmarkHarness::new().run(&default_corpus(), self)?;
         let baseline = BaselineMetrics {
-            parsing_time_ms: self.measure_parsing_performance().await?,
-            generation_time_ms: self.measure_generation_performance().await?,
             memory_usage_mb: self.measure_memory_usage().await?,
             accuracy_percentage: self.measure_baseline_accuracy().await?,
-            throughput_blocks_per_sec: self.measure_throughput().await?,
-            established_at: Utc::now(),
+            ..BaselineMetrics::from_benchmark(&report)
         };
         
         println!("Baseline metrics established:");
-        println!("  Parsing time: {}ms", baseline.parsing_time_ms);
-        println!("  Generation time: {}ms", baseline.generation_time_ms);
+        println!("  Parsing time: {:.2}ms", baseline.parsing_time_ms);
+        println!("  Generation time: {:.2}ms", baseline.generation_time_ms);
         println!("  Memory usage: {}MB", baseline.memory_usage_mb);
         println!("  Accuracy: {:.1}%", baseline.accuracy_percentage);
         println!("  Throughput: {:.1} blocks/sec", baseline.throughput_blocks_per_sec);
@@ -50,9 +49,11 @@ impl MetricsCollector {
     /// Verify performance requirements (DoD requirement)
     pub async fn verify_performance_requirements(&self) -> Result<bool> {
         if let Some(baseline) = &self.baseline_metrics {
-            // Measure current performance
-            let current_parsing = self.measure_parsing_performance().await?;
-            let current_generation = self.measure_generation_performance().await?;
+            // Measure current performance on the same corpus
+            let report = BenchmarkHarness::new().run(&default_corpus(), &mut MetricsCollector::new())?;
+            let current = BaselineMetrics::from_benchmark(&report);
+            let current_parsing = current.parsing_time_ms;
+            let current_generation = current.generation_time_ms;
             let current_memory = self.measure_memory_usage().await?;
             
             // Check if within 2x of baseline (DoD requirement)
@@ -77,6 +78,16 @@ impl MetricsCollector {
         }
     }
 
+    /// The baseline from `establish_baselines` or `set_baseline`, if any
+    pub fn baseline(&self) -> Option<&BaselineMetrics> {
+        self.baseline_metrics.as_ref()
+    }
+
+    /// Use a previously recorded baseline instead of measuring one
+    pub fn set_baseline(&mut self, baseline: BaselineMetrics) {
+        self.baseline_metrics = Some(baseline);
+    }
+
     /// Start timing a performance metric
     pub fn start_timer(&mut self, metric_name: &str) {
         self.performance_timers.insert(metric_name.to_string(), Instant::now());
@@ -128,31 +139,6 @@ impl MetricsCollector {
         summary
     }
 
-    /// Measure parsing performance
-    async fn measure_parsing_performance(&self) -> Result<f64> {
-        // Simplified parsing performance measurement
-        // In full implementation, this would parse sample files and measure time
-        let start = Instant::now();
-        
-        // Simulate parsing work
-        std::thread::sleep(Duration::from_millis(10));
-        
-        let elapsed = start.elapsed();
-        Ok(elapsed.as_millis() as f64)
-    }
-
-    /// Measure generation performance
-    async fn measure_generation_performance(&self) -> Result<f64> {
-        // Simplified generation performance measurement
-        let start = Instant::now();
-        
-        // Simulate generation work
-        std::thread::sleep(Duration::from_millis(15));
-        
-        let elapsed = start.elapsed();
-        Ok(elapsed.as_millis() as f64)
-    }
-
     /// Measure memory usage
     async fn measure_memory_usage(&self) -> Result<f64> {
         // Simplified memory measurement
@@ -185,13 +171,6 @@ impl MetricsCollector {
         Ok(98.5) // Baseline accuracy percentage
     }
 
-    /// Measure throughput
-    async fn measure_throughput(&self) -> Result<f64> {
-        // Simplified throughput measurement
-        // In full implementation, this would process sample blocks and measure rate
-        Ok(150.0) // Blocks per second
-    }
-
     /// Generate performance report
     pub fn generate_performance_report(&self) -> PerformanceReport {
         let mut report = PerformanceReport::new();
@@ -250,6 +229,22 @@ pub struct BaselineMetrics {
     pub established_at: DateTime<Utc>,
 }
 
+impl BaselineMetrics {
+    /// Timings and throughput from a benchmark run; memory and accuracy are
+    /// not measured by the harness and are left at zero
+    pub fn from_benchmark(report: &BenchmarkReport) -> Self {
+        let stage_ms = |stage: &str| report.stages.get(stage).map_or(0.0, |result| result.median_ms);
+        Self {
+            parsing_time_ms: stage_ms("extraction"),
+            generation_time_ms: stage_ms("generation"),
+            memory_usage_mb: 0.0,
+            accuracy_percentage: 0.0,
+            throughput_blocks_per_sec: report.stages.get("generation").map_or(0.0, |result| result.throughput_per_sec),
+            established_at: report.recorded_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MetricValue {
     Duration(Duration),
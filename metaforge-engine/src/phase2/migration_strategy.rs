@@ -9,6 +9,7 @@ use crate::database::{Database, Container, Block};
 use crate::phase2::validation::ValidationEngine;
 use crate::phase2::backup_system::BackupManager;
 use crate::generator::templates::TemplateEngine;
+use crate::generator::output_naming::known_file_extension;
 use crate::parser::universal::UniversalParser;
 use std::collections::HashMap;
 
@@ -508,13 +509,8 @@ impl MigrationManager {
     // Additional helper methods
     
     fn get_file_extension(&self, language: &str) -> Result<&str> {
-        match language.to_lowercase().as_str() {
-            "python" => Ok("py"),
-            "rust" => Ok("rs"),
-            "javascript" => Ok("js"),
-            "typescript" => Ok("ts"),
-            _ => Err(anyhow::anyhow!("Unsupported language: {}", language)),
-        }
+        known_file_extension(language)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported language: {}", language))
     }
 
     fn calculate_semantic_accuracy(&self, original: &str, regenerated: &str, _language: &str) -> f64 {
@@ -6,6 +6,8 @@ pub mod hierarchical_generation;
 pub mod validation;
 pub mod backup_system;
 pub mod metrics;
+pub mod benchmark;
+pub mod health;
 
 use anyhow::Result;
 use uuid::Uuid;
@@ -6,6 +6,7 @@ use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 use crate::database::{Database, Container, Block};
 use crate::generator::templates::TemplateEngine;
+use crate::generator::output_naming::known_file_extension;
 use crate::parser::universal::UniversalParser;
 use std::collections::HashMap;
 
@@ -261,13 +262,8 @@ impl ValidationEngine {
     }
 
     fn get_file_extension(&self, language: &str) -> Result<&str> {
-        match language {
-            "python" => Ok("py"),
-            "rust" => Ok("rs"),
-            "javascript" => Ok("js"),
-            "typescript" => Ok("ts"),
-            _ => Err(anyhow::anyhow!("Unknown language: {}", language)),
-        }
+        known_file_extension(language)
+            .ok_or_else(|| anyhow::anyhow!("Unknown language: {}", language))
     }
 
     async fn test_template_rendering(&self, language: &str, block_type: &str) -> Result<bool> {
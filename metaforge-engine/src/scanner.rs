@@ -44,7 +44,8 @@ impl FileScanner {
         Ok(files)
     }
     
-    fn should_ignore(&self, path: &Path) -> bool {
+    /// Whether `path` lies in a directory the scanner skips, e.g. `.git`
+    pub fn should_ignore(&self, path: &Path) -> bool {
         path.components().any(|component| {
             if let Some(name) = component.as_os_str().to_str() {
                 self.ignore_patterns.iter().any(|pattern| name == pattern)
@@ -54,18 +55,22 @@ impl FileScanner {
         })
     }
     
-    fn process_file(&self, path: &Path) -> Result<Option<SourceFile>> {
+    /// Language of a source file the scanner picks up, from its extension
+    /// or, for extensionless files, its name
+    pub fn language_for(path: &Path) -> Option<&'static str> {
         let extension = path.extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("");
         
-        let language = match extension {
+        match extension {
             "py" => Some("python"),
             "js" | "mjs" => Some("javascript"),
             "ts" | "mts" => Some("typescript"),
             "jsx" => Some("javascript"),
             "tsx" => Some("tsx"),
             "rs" => Some("rust"),
+            "go" => Some("go"),
+            "java" => Some("java"),
             _ => {
                 // Handle special files without extensions
                 let filename = path.file_name()
@@ -85,9 +90,11 @@ impl FileScanner {
                     _ => None,
                 }
             }
-        };
-        
-        if let Some(language) = language {
+        }
+    }
+    
+    fn process_file(&self, path: &Path) -> Result<Option<SourceFile>> {
+        if let Some(language) = Self::language_for(path) {
             // Try to read as UTF-8, skip if it contains invalid UTF-8
             let content = match fs::read_to_string(path) {
                 Ok(content) => content,
@@ -102,25 +109,13 @@ impl FileScanner {
                 .unwrap()
                 .to_path_buf();
             
-            // Special handling for empty files - still include them
-            let filename = path.file_name()
-                .and_then(|name| name.to_str())
-                .unwrap_or("");
-                
-            let final_content = if content.trim().is_empty() {
-                match filename {
-                    "__init__.py" => "# Empty __init__.py file\n".to_string(),
-                    _ => content,
-                }
-            } else {
-                content
-            };
-            
+            // Empty files keep their exact content; migration decides
+            // whether to skip them or preserve them (`core::EmptyFile`)
             Ok(Some(SourceFile {
                 path: relative_path,
-                content: final_content.clone(),
+                hash: self.calculate_hash(&content),
+                content,
                 language: language.to_string(),
-                hash: self.calculate_hash(&final_content),
             }))
         } else {
             Ok(None)
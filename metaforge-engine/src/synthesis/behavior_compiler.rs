@@ -3,6 +3,7 @@ use uuid::Uuid;
 use anyhow::Result;
 use std::collections::HashMap;
 use crate::database::{Database, Block};
+use crate::ai_operations::spec_validation::{is_identifier, SpecIssue, SpecValidation};
 
 /// Behavioral Specification Compiler - compiles high-level behaviors to executable code
 pub struct BehaviorCompiler {
@@ -27,6 +28,64 @@ pub struct BehaviorSpecification {
     pub examples: Vec<BehaviorExample>,
 }
 
+impl BehaviorSpecification {
+    /// Field-level problems with the specification, without a compiler or
+    /// database. A behavior without postconditions is undefined, so that is
+    /// an error; missing preconditions and examples are warnings.
+    pub fn validate(&self) -> SpecValidation {
+        let mut validation = SpecValidation::default();
+
+        if self.name.trim().is_empty() {
+            validation.error("name", "must not be empty");
+        }
+        if self.description.trim().is_empty() && self.intent.trim().is_empty() {
+            validation.warning("intent", "neither an intent nor a description is given");
+        }
+        if self.preconditions.is_empty() {
+            validation.warning("preconditions", "no preconditions specified");
+        }
+        if self.postconditions.is_empty() {
+            validation.error("postconditions", "no postconditions specified - behavior is undefined");
+        }
+        for (list, conditions) in [("preconditions", &self.preconditions), ("postconditions", &self.postconditions)] {
+            for (index, condition) in conditions.iter().enumerate() {
+                if condition.description.trim().is_empty() && condition.formal_expression.is_none() {
+                    validation.error(format!("{}[{}].description", list, index), "must not be empty without a formal_expression");
+                }
+            }
+        }
+        for (index, invariant) in self.invariants.iter().enumerate() {
+            if invariant.formal_expression.trim().is_empty() {
+                validation.error(format!("invariants[{}].formal_expression", index), "must not be empty");
+            }
+        }
+        if let Some(performance) = &self.performance_requirements {
+            if performance.max_execution_time_ms == Some(0) {
+                validation.error("performance_requirements.max_execution_time_ms", "0 can never be met");
+            }
+            if performance.max_memory_usage_mb == Some(0) {
+                validation.error("performance_requirements.max_memory_usage_mb", "0 can never be met");
+            }
+        }
+        if let Some(encryption) = self.security_requirements.as_ref().and_then(|security| security.encryption_requirements.as_ref()) {
+            if encryption.algorithm.trim().is_empty() {
+                validation.error("security_requirements.encryption_requirements.algorithm", "must not be empty");
+            }
+        }
+        if self.examples.is_empty() {
+            validation.warning("examples", "none given; no example tests will be generated");
+        }
+        for (index, example) in self.examples.iter().enumerate() {
+            if example.name.trim().is_empty() {
+                validation.error(format!("examples[{}].name", index), "must not be empty");
+            } else if !is_identifier(&example.name) {
+                validation.error(format!("examples[{}].name", index), format!("`{}` can't name a test function", example.name));
+            }
+        }
+        validation
+    }
+}
+
 /// Condition that must be true at specific points
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Condition {
@@ -589,17 +648,11 @@ impl BehaviorCompiler {
 
     /// Validate a behavioral specification
     pub async fn validate_specification(&self, spec: &BehaviorSpecification) -> Result<ValidationResult> {
-        let mut issues = Vec::new();
-        let mut warnings = Vec::new();
-
         // Check for completeness
-        if spec.preconditions.is_empty() {
-            warnings.push("No preconditions specified".to_string());
-        }
-        
-        if spec.postconditions.is_empty() {
-            issues.push("No postconditions specified - behavior is undefined".to_string());
-        }
+        let validation = spec.validate();
+        let describe = |issue: &SpecIssue| format!("{}: {}", issue.field, issue.message);
+        let mut issues: Vec<String> = validation.errors().map(describe).collect();
+        let warnings: Vec<String> = validation.warnings().map(describe).collect();
 
         // Check for consistency
         if let Err(e) = self.check_consistency(spec).await {
@@ -0,0 +1,615 @@
+//! Expression-level diff
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::core::normalize::POSITION_KEYS;
+use crate::database::Block;
+use crate::generator::markers::SourceRange;
+
+/// Keys naming what kind of node an object is, in order of preference
+const KIND_KEYS: &[&str] = &["expression_type", "node_type", "type", "kind"];
+/// Keys holding a node's source text
+const TEXT_KEYS: &[&str] = &["source_text", "code", "text", "raw_text"];
+/// Keys that describe layout or location rather than structure
+const SKIPPED_KEYS: &[&str] = &["start", "end", "range", "byte_start", "byte_end", "formatting", "complexity_score"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditKind {
+    Inserted,
+    Deleted,
+    Modified,
+}
+
+/// One node-level change. Inserted and deleted subtrees are reported once,
+/// at their root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeEdit {
+    pub kind: EditKind,
+    pub node_kind: String,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+    pub old_range: Option<SourceRange>,
+    pub new_range: Option<SourceRange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpressionDiff {
+    pub edits: Vec<NodeEdit>,
+    /// Tree edit distance between the two bodies
+    pub distance: usize,
+    /// The bodies were too large for a full tree diff, so only their
+    /// top-level statements were compared
+    pub truncated: bool,
+}
+
+/// Computes expression-level diffs, bounding the work spent on huge bodies
+#[derive(Debug, Clone)]
+pub struct ExpressionDiffer {
+    max_node_pairs: usize,
+}
+
+impl Default for ExpressionDiffer {
+    fn default() -> Self {
+        Self { max_node_pairs: 1_000_000 }
+    }
+}
+
+impl ExpressionDiffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Above `old nodes × new nodes` pairs, fall back to comparing top-level
+    /// statements; the full algorithm needs a table of that size
+    pub fn with_max_node_pairs(mut self, max_node_pairs: usize) -> Self {
+        self.max_node_pairs = max_node_pairs;
+        self
+    }
+
+    /// Diff the bodies of two versions of a block
+    pub fn diff_blocks(&self, old: &Block, new: &Block) -> ExpressionDiff {
+        self.diff_values(block_tree(old), block_tree(new))
+    }
+
+    pub fn diff_values(&self, old: &Value, new: &Value) -> ExpressionDiff {
+        let old_tree = TreeNode::from_value("body", old);
+        let new_tree = TreeNode::from_value("body", new);
+        let old_flat = FlatTree::new(&old_tree);
+        let new_flat = FlatTree::new(&new_tree);
+
+        if old_flat.len().saturating_mul(new_flat.len()) > self.max_node_pairs {
+            return diff_top_level(&old_tree, &new_tree);
+        }
+
+        ZhangShasha::new(&old_flat, &new_flat).edit_script()
+    }
+}
+
+impl ExpressionDiff {
+    pub fn count(&self, kind: EditKind) -> usize {
+        self.edits.iter().filter(|edit| edit.kind == kind).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} inserted, {} deleted, {} modified{}",
+            self.count(EditKind::Inserted),
+            self.count(EditKind::Deleted),
+            self.count(EditKind::Modified),
+            if self.truncated { " (top-level statements only)" } else { "" }
+        )
+    }
+
+    /// The new source with each line marked `+` (inserted), `~` (modified) or
+    /// blank, and deleted code shown as `-` lines where it used to start.
+    /// Ranges are read as 0-based lines, as tree-sitter reports them.
+    pub fn render_annotated(&self, new_source: &str) -> String {
+        let covers = |kind: EditKind, line: usize| {
+            self.edits.iter().any(|edit| {
+                edit.kind == kind
+                    && edit.new_range.as_ref().is_some_and(|r| r.start_line <= line && line <= r.end_line)
+            })
+        };
+        let deleted_at = |line: Option<usize>| {
+            self.edits.iter().filter(move |edit| {
+                edit.kind == EditKind::Deleted && edit.old_range.as_ref().map(|r| r.start_line) == line
+            })
+        };
+
+        let mut output = Vec::new();
+        let line_count = new_source.lines().count();
+        for (index, line) in new_source.lines().enumerate() {
+            for edit in deleted_at(Some(index)) {
+                push_deleted(&mut output, edit);
+            }
+            let marker = if covers(EditKind::Inserted, index) {
+                '+'
+            } else if covers(EditKind::Modified, index) {
+                '~'
+            } else {
+                ' '
+            };
+            output.push(format!("{} {}", marker, line));
+        }
+
+        // Deletions past the end of the new source or without a location
+        for edit in self.edits.iter().filter(|edit| edit.kind == EditKind::Deleted) {
+            match &edit.old_range {
+                Some(range) if range.start_line < line_count => {}
+                _ => push_deleted(&mut output, edit),
+            }
+        }
+
+        output.join("\n")
+    }
+}
+
+fn push_deleted(output: &mut Vec<String>, edit: &NodeEdit) {
+    let text = edit.old_text.as_deref().unwrap_or(&edit.node_kind);
+    output.extend(text.lines().map(|line| format!("- {}", line)));
+}
+
+/// The source text recorded for a block, if the extractor kept it
+pub fn block_source(block: &Block) -> Option<&str> {
+    ["raw_text", "text"].iter()
+        .find_map(|key| block.abstract_syntax.get(*key).and_then(|v| v.as_str()))
+}
+
+/// The richest tree a block carries
+fn block_tree(block: &Block) -> &Value {
+    match &block.body_ast {
+        Some(body) if !body.is_null() => body,
+        _ => &block.abstract_syntax,
+    }
+}
+
+/// A JSON node reduced to what the diff compares
+#[derive(Debug)]
+struct TreeNode {
+    kind: String,
+    /// Kind plus scalar fields; leaves also include their text
+    signature: String,
+    text: Option<String>,
+    range: Option<SourceRange>,
+    children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn from_value(label: &str, value: &Value) -> Self {
+        match value {
+            Value::Object(map) => {
+                let kind = KIND_KEYS.iter()
+                    .find_map(|key| map.get(*key).and_then(|v| v.as_str()))
+                    .unwrap_or(label)
+                    .to_string();
+                let text = TEXT_KEYS.iter()
+                    .find_map(|key| map.get(*key).and_then(|v| v.as_str()))
+                    .map(String::from);
+
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+
+                let mut scalars = Vec::new();
+                let mut children = Vec::new();
+                for key in keys {
+                    let key_str = key.as_str();
+                    if KIND_KEYS.contains(&key_str) || TEXT_KEYS.contains(&key_str)
+                        || SKIPPED_KEYS.contains(&key_str) || POSITION_KEYS.contains(&key_str)
+                    {
+                        continue;
+                    }
+                    match &map[key] {
+                        Value::Array(items) => {
+                            children.extend(items.iter().map(|item| Self::from_value(key, item)));
+                        }
+                        Value::Object(_) => children.push(Self::from_value(key, &map[key])),
+                        Value::Null => {}
+                        scalar => scalars.push(format!("{}={}", key, scalar)),
+                    }
+                }
+
+                let mut signature = kind.clone();
+                for scalar in &scalars {
+                    signature.push('|');
+                    signature.push_str(scalar);
+                }
+                if children.is_empty() {
+                    if let Some(text) = &text {
+                        signature.push('|');
+                        signature.push_str(&crate::core::normalize::normalize_source(text));
+                    }
+                }
+
+                Self { kind, signature, text, range: parse_range(map), children }
+            }
+            Value::Array(items) => Self {
+                kind: label.to_string(),
+                signature: label.to_string(),
+                text: None,
+                range: None,
+                children: items.iter().map(|item| Self::from_value(label, item)).collect(),
+            },
+            scalar => {
+                let text = match scalar {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                Self {
+                    kind: label.to_string(),
+                    signature: format!("{}|{}", label, text),
+                    text: Some(text),
+                    range: None,
+                    children: Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// Signature of the whole subtree, for the top-level fallback
+    fn deep_signature(&self) -> String {
+        let children: Vec<String> = self.children.iter().map(Self::deep_signature).collect();
+        format!("{}({})", self.signature, children.join(","))
+    }
+}
+
+/// Read `start_line`/`end_line` or tree-sitter style `start.row`/`end.row`,
+/// plus byte offsets when recorded
+fn parse_range(map: &serde_json::Map<String, Value>) -> Option<SourceRange> {
+    let number = |key: &str| map.get(key).and_then(|v| v.as_u64()).map(|n| n as usize);
+    let row = |key: &str| map.get(key).and_then(|v| v.get("row")).and_then(|v| v.as_u64()).map(|n| n as usize);
+
+    let start_line = number("start_line").or_else(|| row("start"))?;
+    let end_line = number("end_line").or_else(|| row("end")).unwrap_or(start_line);
+    Some(SourceRange {
+        start_line,
+        end_line,
+        byte_start: number("start_byte").or_else(|| number("byte_start")).unwrap_or(0),
+        byte_end: number("end_byte").or_else(|| number("byte_end")).unwrap_or(0),
+    })
+}
+
+/// A tree in post-order, as Zhang-Shasha indexes it
+struct FlatTree<'a> {
+    nodes: Vec<&'a TreeNode>,
+    parents: Vec<Option<usize>>,
+    /// Post-order index of each node's leftmost leaf
+    leftmost: Vec<usize>,
+    keyroots: Vec<usize>,
+}
+
+impl<'a> FlatTree<'a> {
+    fn new(root: &'a TreeNode) -> Self {
+        let mut tree = Self { nodes: Vec::new(), parents: Vec::new(), leftmost: Vec::new(), keyroots: Vec::new() };
+        tree.visit(root);
+
+        // A keyroot is the highest node with a given leftmost leaf
+        let mut seen = HashSet::new();
+        for index in (0..tree.len()).rev() {
+            if seen.insert(tree.leftmost[index]) {
+                tree.keyroots.push(index);
+            }
+        }
+        tree.keyroots.reverse();
+        tree
+    }
+
+    fn visit(&mut self, node: &'a TreeNode) -> usize {
+        let child_indices: Vec<usize> = node.children.iter().map(|child| self.visit(child)).collect();
+        let index = self.nodes.len();
+        let leftmost = child_indices.first().map(|&first| self.leftmost[first]).unwrap_or(index);
+        for child in child_indices {
+            self.parents[child] = Some(index);
+        }
+        self.nodes.push(node);
+        self.parents.push(None);
+        self.leftmost.push(leftmost);
+        index
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+struct ZhangShasha<'a, 'b> {
+    old: &'b FlatTree<'a>,
+    new: &'b FlatTree<'a>,
+    tree_dist: Vec<Vec<usize>>,
+}
+
+impl<'a, 'b> ZhangShasha<'a, 'b> {
+    fn new(old: &'b FlatTree<'a>, new: &'b FlatTree<'a>) -> Self {
+        let mut algorithm = Self { old, new, tree_dist: vec![vec![0; new.len()]; old.len()] };
+        for &i in &old.keyroots {
+            for &j in &new.keyroots {
+                algorithm.forest_dist(i, j);
+            }
+        }
+        algorithm
+    }
+
+    fn rename_cost(&self, i: usize, j: usize) -> usize {
+        usize::from(self.old.nodes[i].signature != self.new.nodes[j].signature)
+    }
+
+    /// Forest distances between the subtrees rooted at `i` and `j`, recording
+    /// tree distances for every pair of whole subtrees along the way
+    fn forest_dist(&mut self, i: usize, j: usize) -> Vec<Vec<usize>> {
+        let (li, lj) = (self.old.leftmost[i], self.new.leftmost[j]);
+        let (rows, cols) = (i - li + 2, j - lj + 2);
+        let mut fd = vec![vec![0; cols]; rows];
+        for (x, row) in fd.iter_mut().enumerate() {
+            row[0] = x;
+        }
+        for (y, cell) in fd[0].iter_mut().enumerate() {
+            *cell = y;
+        }
+
+        for x in 1..rows {
+            for y in 1..cols {
+                let (ni, nj) = (li + x - 1, lj + y - 1);
+                let delete = fd[x - 1][y] + 1;
+                let insert = fd[x][y - 1] + 1;
+                if self.old.leftmost[ni] == li && self.new.leftmost[nj] == lj {
+                    fd[x][y] = delete.min(insert).min(fd[x - 1][y - 1] + self.rename_cost(ni, nj));
+                    self.tree_dist[ni][nj] = fd[x][y];
+                } else {
+                    let (px, py) = (self.old.leftmost[ni] - li, self.new.leftmost[nj] - lj);
+                    fd[x][y] = delete.min(insert).min(fd[px][py] + self.tree_dist[ni][nj]);
+                }
+            }
+        }
+        fd
+    }
+
+    fn edit_script(mut self) -> ExpressionDiff {
+        let (old_root, new_root) = (self.old.len() - 1, self.new.len() - 1);
+        let distance = self.tree_dist[old_root][new_root];
+
+        let mut deleted = Vec::new();
+        let mut inserted = Vec::new();
+        let mut matched = Vec::new();
+        let mut pending = vec![(old_root, new_root)];
+
+        while let Some((i, j)) = pending.pop() {
+            let fd = self.forest_dist(i, j);
+            let (li, lj) = (self.old.leftmost[i], self.new.leftmost[j]);
+            let (mut x, mut y) = (i - li + 1, j - lj + 1);
+
+            while x > 0 || y > 0 {
+                if x > 0 && y > 0 {
+                    let (ni, nj) = (li + x - 1, lj + y - 1);
+                    if self.old.leftmost[ni] == li && self.new.leftmost[nj] == lj {
+                        if fd[x][y] == fd[x - 1][y - 1] + self.rename_cost(ni, nj) {
+                            matched.push((ni, nj));
+                            x -= 1;
+                            y -= 1;
+                            continue;
+                        }
+                    } else {
+                        let (px, py) = (self.old.leftmost[ni] - li, self.new.leftmost[nj] - lj);
+                        if fd[x][y] == fd[px][py] + self.tree_dist[ni][nj] {
+                            pending.push((ni, nj));
+                            x = px;
+                            y = py;
+                            continue;
+                        }
+                    }
+                }
+                if x > 0 && fd[x][y] == fd[x - 1][y] + 1 {
+                    deleted.push(li + x - 1);
+                    x -= 1;
+                } else {
+                    inserted.push(lj + y - 1);
+                    y -= 1;
+                }
+            }
+        }
+
+        let mut edits = Vec::new();
+        let deleted_set: HashSet<usize> = deleted.iter().copied().collect();
+        let inserted_set: HashSet<usize> = inserted.iter().copied().collect();
+
+        // Report each removed or added subtree once, at its root
+        for &index in &deleted {
+            if self.old.parents[index].is_some_and(|parent| deleted_set.contains(&parent)) {
+                continue;
+            }
+            let node = self.old.nodes[index];
+            edits.push(NodeEdit {
+                kind: EditKind::Deleted,
+                node_kind: node.kind.clone(),
+                old_text: node.text.clone(),
+                new_text: None,
+                old_range: node.range.clone(),
+                new_range: None,
+            });
+        }
+        for &index in &inserted {
+            if self.new.parents[index].is_some_and(|parent| inserted_set.contains(&parent)) {
+                continue;
+            }
+            let node = self.new.nodes[index];
+            edits.push(NodeEdit {
+                kind: EditKind::Inserted,
+                node_kind: node.kind.clone(),
+                old_text: None,
+                new_text: node.text.clone(),
+                old_range: None,
+                new_range: node.range.clone(),
+            });
+        }
+        for &(i, j) in &matched {
+            if self.rename_cost(i, j) == 0 {
+                continue;
+            }
+            let (old_node, new_node) = (self.old.nodes[i], self.new.nodes[j]);
+            edits.push(NodeEdit {
+                kind: EditKind::Modified,
+                node_kind: new_node.kind.clone(),
+                old_text: old_node.text.clone(),
+                new_text: new_node.text.clone(),
+                old_range: old_node.range.clone(),
+                new_range: new_node.range.clone(),
+            });
+        }
+
+        sort_edits(&mut edits);
+        ExpressionDiff { edits, distance, truncated: false }
+    }
+}
+
+/// Longest-common-subsequence diff of the roots' children, used when the full
+/// tree diff would be too expensive. Adjacent deletions and insertions pair
+/// up as modifications.
+fn diff_top_level(old: &TreeNode, new: &TreeNode) -> ExpressionDiff {
+    let old_sigs: Vec<String> = old.children.iter().map(TreeNode::deep_signature).collect();
+    let new_sigs: Vec<String> = new.children.iter().map(TreeNode::deep_signature).collect();
+    let (n, m) = (old_sigs.len(), new_sigs.len());
+
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for x in (0..n).rev() {
+        for y in (0..m).rev() {
+            lcs[x][y] = if old_sigs[x] == new_sigs[y] {
+                lcs[x + 1][y + 1] + 1
+            } else {
+                lcs[x + 1][y].max(lcs[x][y + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    let mut removed: Vec<&TreeNode> = Vec::new();
+    let mut added: Vec<&TreeNode> = Vec::new();
+    let flush = |edits: &mut Vec<NodeEdit>, removed: &mut Vec<&TreeNode>, added: &mut Vec<&TreeNode>| {
+        let paired = removed.len().min(added.len());
+        for (old_node, new_node) in removed.iter().zip(added.iter()) {
+            edits.push(NodeEdit {
+                kind: EditKind::Modified,
+                node_kind: new_node.kind.clone(),
+                old_text: old_node.text.clone(),
+                new_text: new_node.text.clone(),
+                old_range: old_node.range.clone(),
+                new_range: new_node.range.clone(),
+            });
+        }
+        for old_node in &removed[paired..] {
+            edits.push(NodeEdit {
+                kind: EditKind::Deleted,
+                node_kind: old_node.kind.clone(),
+                old_text: old_node.text.clone(),
+                new_text: None,
+                old_range: old_node.range.clone(),
+                new_range: None,
+            });
+        }
+        for new_node in &added[paired..] {
+            edits.push(NodeEdit {
+                kind: EditKind::Inserted,
+                node_kind: new_node.kind.clone(),
+                old_text: None,
+                new_text: new_node.text.clone(),
+                old_range: None,
+                new_range: new_node.range.clone(),
+            });
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    while x < n || y < m {
+        if x < n && y < m && old_sigs[x] == new_sigs[y] {
+            flush(&mut edits, &mut removed, &mut added);
+            x += 1;
+            y += 1;
+        } else if y >= m || (x < n && lcs[x + 1][y] >= lcs[x][y + 1]) {
+            removed.push(&old.children[x]);
+            x += 1;
+        } else {
+            added.push(&new.children[y]);
+            y += 1;
+        }
+    }
+    flush(&mut edits, &mut removed, &mut added);
+
+    // Each modification is at least one edit, as are whole-subtree insertions and deletions
+    let distance = edits.len();
+    sort_edits(&mut edits);
+    ExpressionDiff { edits, distance, truncated: true }
+}
+
+/// Order edits by where they appear, new positions first
+fn sort_edits(edits: &mut [NodeEdit]) {
+    edits.sort_by_key(|edit| {
+        edit.new_range.as_ref()
+            .or(edit.old_range.as_ref())
+            .map(|range| (range.start_line, range.byte_start))
+            .unwrap_or((usize::MAX, usize::MAX))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum_body(mutable: bool, operands: &[&str], with_log: bool) -> serde_json::Value {
+        let operands: Vec<serde_json::Value> = operands.iter()
+            .map(|name| serde_json::json!({"expression_type": "Variable", "text": name, "start_line": 0}))
+            .collect();
+        let mut statements = vec![serde_json::json!({
+            "type": "let_declaration",
+            "text": format!("let {}total = sum({});", if mutable { "mut " } else { "" }, operands.len()),
+            "start_line": 0,
+            "mutable": mutable,
+            "value": {"expression_type": "FunctionCall", "text": "sum(..)", "start_line": 0, "operands": operands},
+        })];
+        if with_log {
+            statements.push(serde_json::json!({
+                "type": "expression_statement",
+                "text": "log(total);",
+                "start_line": 1,
+                "value": {"expression_type": "FunctionCall", "text": "log(total)", "operands": [{"expression_type": "Variable", "text": "total"}]},
+            }));
+        }
+        statements.push(serde_json::json!({
+            "type": "return",
+            "text": "total",
+            "start_line": statements.len(),
+            "value": {"expression_type": "Variable", "text": "total"},
+        }));
+        serde_json::json!({"type": "block", "statements": statements})
+    }
+
+    #[test]
+    fn test_expression_diff_reports_node_edits() {
+        let old = sum_body(false, &["a", "b"], true);
+        let new = sum_body(true, &["a", "b", "c"], false);
+
+        let diff = ExpressionDiffer::new().diff_values(&old, &new);
+        assert!(!diff.truncated);
+        assert_eq!(diff.distance, 5);
+        let edits: Vec<(EditKind, &str, Option<&str>)> = diff.edits.iter()
+            .map(|edit| (edit.kind, edit.node_kind.as_str(), edit.new_text.as_deref().or(edit.old_text.as_deref())))
+            .collect();
+        assert_eq!(edits, vec![
+            (EditKind::Inserted, "Variable", Some("c")),
+            (EditKind::Modified, "let_declaration", Some("let mut total = sum(3);")),
+            (EditKind::Deleted, "expression_statement", Some("log(total);")),
+        ]);
+        assert_eq!(diff.edits[2].old_range.as_ref().map(|range| range.start_line), Some(1));
+
+        let annotated = diff.render_annotated("let mut total = sum(a, b, c);\ntotal");
+        assert_eq!(annotated, "+ let mut total = sum(a, b, c);\n- log(total);\n  total");
+
+        // Too large for a full tree diff: top-level statements only
+        let capped = ExpressionDiffer::new().with_max_node_pairs(10).diff_values(&old, &new);
+        assert!(capped.truncated);
+        assert_eq!(capped.count(EditKind::Modified), 1);
+        assert_eq!(capped.count(EditKind::Deleted), 1);
+        assert_eq!(capped.count(EditKind::Inserted), 0);
+    }
+}
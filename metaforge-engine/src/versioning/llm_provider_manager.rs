@@ -4,6 +4,7 @@ use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use async_trait::async_trait;
 use chrono::Utc;
+use std::time::{Duration, Instant};
 
 use crate::database::{Database, schema::{Block, PromptTemplate, LLMInteraction}};
 
@@ -38,6 +39,223 @@ pub struct ProviderCapabilities {
     pub reliability_score: f32,
 }
 
+/// Provider failures that decide whether a fallback is worth trying.
+///
+/// Providers return `anyhow::Error`; wrapping the cause in one of these lets
+/// the manager tell a rate limit or outage apart from a request that no
+/// provider would accept.
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    #[error("rate limited by {provider}")]
+    RateLimited { provider: String, retry_after_ms: Option<u64> },
+    #[error("{provider} returned server error {status}")]
+    ServerError { provider: String, status: u16 },
+    #[error("{provider} timed out after {timeout_ms}ms")]
+    Timeout { provider: String, timeout_ms: u64 },
+    #[error("{provider} rejected the request: {message}")]
+    BadRequest { provider: String, message: String },
+}
+
+impl ProviderError {
+    /// Classify an HTTP status returned by `provider`, if it is an error
+    pub fn from_status(provider: &str, status: u16, message: impl Into<String>) -> Option<Self> {
+        let provider = provider.to_string();
+        match status {
+            429 => Some(Self::RateLimited { provider, retry_after_ms: None }),
+            500..=599 => Some(Self::ServerError { provider, status }),
+            400..=499 => Some(Self::BadRequest { provider, message: message.into() }),
+            _ => None,
+        }
+    }
+
+    pub fn is_transient(&self) -> bool {
+        !matches!(self, Self::BadRequest { .. })
+    }
+}
+
+/// Whether retrying or falling back could succeed where this error didn't
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    if let Some(provider_error) = error.downcast_ref::<ProviderError>() {
+        return provider_error.is_transient();
+    }
+    if let Some(http_error) = error.downcast_ref::<reqwest::Error>() {
+        if http_error.is_timeout() || http_error.is_connect() {
+            return true;
+        }
+        return http_error.status()
+            .is_some_and(|status| status.as_u16() == 429 || status.is_server_error());
+    }
+    false
+}
+
+/// How often to retry one provider before moving down the chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub backoff_multiplier: f64,
+    /// Attempts running longer than this count as transient failures
+    pub timeout_ms: Option<u64>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 8_000,
+            backoff_multiplier: 2.0,
+            timeout_ms: Some(60_000),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let delay = self.initial_backoff_ms as f64 * self.backoff_multiplier.powi(retry as i32);
+        Duration::from_millis(delay.min(self.max_backoff_ms as f64) as u64)
+    }
+}
+
+/// Ordered provider chains per operation, plus per-provider retry policies.
+///
+/// Operations are prompt template categories such as `code_generation`, or
+/// the names callers pass to `LLMProviderManager::execute_request`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FallbackConfig {
+    #[serde(default)]
+    pub chains: HashMap<String, Vec<String>>,
+    /// Used for operations without their own chain
+    #[serde(default)]
+    pub default_chain: Vec<String>,
+    #[serde(default)]
+    pub retry_policies: HashMap<String, RetryPolicy>,
+    #[serde(default)]
+    pub default_retry: RetryPolicy,
+}
+
+impl FallbackConfig {
+    pub fn with_chain(mut self, operation: impl Into<String>, chain: Vec<String>) -> Self {
+        self.chains.insert(operation.into(), chain);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, provider: impl Into<String>, policy: RetryPolicy) -> Self {
+        self.retry_policies.insert(provider.into(), policy);
+        self
+    }
+
+    /// The configured chain for an operation, empty if none applies
+    pub fn chain_for(&self, operation: &str) -> &[String] {
+        self.chains.get(operation).unwrap_or(&self.default_chain)
+    }
+
+    pub fn retry_policy(&self, provider: &str) -> &RetryPolicy {
+        self.retry_policies.get(provider).unwrap_or(&self.default_retry)
+    }
+}
+
+/// A failed attempt made before a result was served
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedAttempt {
+    pub provider: String,
+    pub attempt: u32,
+    pub error: String,
+    pub transient: bool,
+}
+
+/// A result together with the provider that actually produced it
+#[derive(Debug, Clone)]
+pub struct ServedResult {
+    pub provider: String,
+    pub result: LLMResult,
+    /// Wall time of the successful attempt
+    pub latency_ms: u32,
+    pub failed_attempts: Vec<FailedAttempt>,
+}
+
+/// Try each provider in `chain` until one succeeds.
+///
+/// Transient failures are retried with backoff according to the provider's
+/// policy, then the next provider is tried. A non-transient failure stops the
+/// chain, since another provider would reject the same request. Providers in
+/// the chain that aren't registered are skipped.
+pub async fn execute_chain<F>(
+    providers: &HashMap<String, Box<dyn LLMProvider>>,
+    config: &FallbackConfig,
+    chain: &[String],
+    build_request: F,
+) -> Result<ServedResult>
+where
+    F: Fn(&str) -> Result<LLMRequest>,
+{
+    let mut failed_attempts = Vec::new();
+
+    for provider_name in chain {
+        let Some(provider) = providers.get(provider_name) else {
+            continue;
+        };
+        let policy = config.retry_policy(provider_name);
+        let request = build_request(provider_name)?;
+
+        for attempt in 0..=policy.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(policy.backoff(attempt - 1)).await;
+            }
+
+            let start_time = std::time::Instant::now();
+            let outcome = match policy.timeout_ms {
+                Some(timeout_ms) => tokio::time::timeout(
+                    Duration::from_millis(timeout_ms),
+                    provider.execute(request.clone()),
+                )
+                .await
+                .unwrap_or_else(|_| Err(ProviderError::Timeout {
+                    provider: provider_name.clone(),
+                    timeout_ms,
+                }.into())),
+                None => provider.execute(request.clone()).await,
+            };
+
+            match outcome {
+                Ok(result) => {
+                    return Ok(ServedResult {
+                        provider: provider_name.clone(),
+                        result,
+                        latency_ms: start_time.elapsed().as_millis() as u32,
+                        failed_attempts,
+                    });
+                }
+                Err(error) => {
+                    let transient = is_transient_error(&error);
+                    failed_attempts.push(FailedAttempt {
+                        provider: provider_name.clone(),
+                        attempt,
+                        error: error.to_string(),
+                        transient,
+                    });
+                    if !transient {
+                        return Err(error.context(format!("{} failed with a non-retryable error", provider_name)));
+                    }
+                }
+            }
+        }
+
+        tracing::warn!("Provider {} exhausted its retries, falling back", provider_name);
+    }
+
+    if failed_attempts.is_empty() {
+        anyhow::bail!("None of the providers in the chain are available: {:?}", chain);
+    }
+    let tried: Vec<String> = failed_attempts.iter()
+        .map(|attempt| format!("{}#{}: {}", attempt.provider, attempt.attempt, attempt.error))
+        .collect();
+    anyhow::bail!("All providers in the chain failed: {}", tried.join("; "))
+}
+
 #[async_trait]
 pub trait LLMProvider: Send + Sync {
     fn name(&self) -> &str;
@@ -46,7 +264,71 @@ pub trait LLMProvider: Send + Sync {
     fn supports_model(&self, model: &str) -> bool;
 }
 
+/// Model used when a request names none the provider serves
+const OPENAI_DEFAULT_MODEL: &str = "gpt-4-turbo";
+const ANTHROPIC_DEFAULT_MODEL: &str = "claude-3-sonnet-20240229";
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+/// The messages API requires a limit; used when the request sets none
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// POST `body` and decode the JSON reply. Error statuses become
+/// `ProviderError`s so the fallback chain can tell a rate limit or outage
+/// from a request no provider would accept.
+async fn post_json(provider: &str, request: reqwest::RequestBuilder, body: &serde_json::Value) -> Result<serde_json::Value> {
+    let response = request.json(body).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let message = response.text().await.unwrap_or_default();
+        return Err(match ProviderError::from_status(provider, status.as_u16(), message) {
+            Some(error) => error.into(),
+            None => anyhow::anyhow!("{} returned unexpected status {}", provider, status),
+        });
+    }
+    Ok(response.json().await?)
+}
+
+/// Request body for OpenAI-compatible chat completion endpoints
+fn chat_completion_body(model: &str, request: &LLMRequest) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": request.prompt }],
+    });
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = serde_json::json!(temperature);
+    }
+    if let Some(max_tokens) = request.max_tokens {
+        body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+    body
+}
+
+/// Read a chat completion reply into an `LLMResult`
+fn chat_completion_result(response: &serde_json::Value, started: Instant, cost_per_1k_tokens: f32) -> Result<LLMResult> {
+    let content = response.pointer("/choices/0/message/content")
+        .and_then(|content| content.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Chat completion has no message content"))?;
+    let tokens_used = response.pointer("/usage/total_tokens")
+        .and_then(|tokens| tokens.as_u64())
+        .map(|tokens| tokens as u32);
+    Ok(llm_result(content, tokens_used, started, cost_per_1k_tokens))
+}
+
+/// Providers don't report a confidence; prompts that need one ask the model
+/// to state it in the reply
+fn llm_result(content: &str, tokens_used: Option<u32>, started: Instant, cost_per_1k_tokens: f32) -> LLMResult {
+    LLMResult {
+        content: content.to_string(),
+        confidence_score: None,
+        tokens_used,
+        latency_ms: Some(started.elapsed().as_millis() as u32),
+        cost_cents: tokens_used.map(|tokens| tokens as f32 / 1000.0 * cost_per_1k_tokens * 100.0),
+        reasoning: None,
+        metadata: HashMap::new(),
+    }
+}
+
 pub struct OpenAIProvider {
+    client: reqwest::Client,
     api_key: String,
     base_url: String,
 }
@@ -54,6 +336,7 @@ pub struct OpenAIProvider {
 impl OpenAIProvider {
     pub fn new(api_key: String) -> Self {
         Self {
+            client: reqwest::Client::new(),
             api_key,
             base_url: "https://api.openai.com/v1".to_string(),
         }
@@ -79,17 +362,16 @@ impl LLMProvider for OpenAIProvider {
     }
     
     async fn execute(&self, request: LLMRequest) -> Result<LLMResult> {
-        // TODO: Implement actual OpenAI API call
-        // For now, return a mock response
-        Ok(LLMResult {
-            content: format!("OpenAI response to: {}", request.prompt.chars().take(50).collect::<String>()),
-            confidence_score: Some(0.85),
-            tokens_used: Some(150),
-            latency_ms: Some(1800),
-            cost_cents: Some(0.15),
-            reasoning: Some("Generated using GPT-4 with high confidence".to_string()),
-            metadata: HashMap::new(),
-        })
+        let model = request.model.clone()
+            .filter(|model| self.supports_model(model))
+            .unwrap_or_else(|| OPENAI_DEFAULT_MODEL.to_string());
+        let started = Instant::now();
+        let response = post_json(
+            self.name(),
+            self.client.post(format!("{}/chat/completions", self.base_url)).bearer_auth(&self.api_key),
+            &chat_completion_body(&model, &request),
+        ).await?;
+        chat_completion_result(&response, started, self.capabilities().cost_per_1k_tokens)
     }
     
     fn supports_model(&self, model: &str) -> bool {
@@ -98,12 +380,16 @@ impl LLMProvider for OpenAIProvider {
 }
 
 pub struct AnthropicProvider {
+    client: reqwest::Client,
     api_key: String,
 }
 
 impl AnthropicProvider {
     pub fn new(api_key: String) -> Self {
-        Self { api_key }
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+        }
     }
 }
 
@@ -126,31 +412,58 @@ impl LLMProvider for AnthropicProvider {
     }
     
     async fn execute(&self, request: LLMRequest) -> Result<LLMResult> {
-        // TODO: Implement actual Anthropic API call
-        Ok(LLMResult {
-            content: format!("Anthropic response to: {}", request.prompt.chars().take(50).collect::<String>()),
-            confidence_score: Some(0.88),
-            tokens_used: Some(180),
-            latency_ms: Some(2200),
-            cost_cents: Some(0.14),
-            reasoning: Some("Generated using Claude 3 with detailed analysis".to_string()),
-            metadata: HashMap::new(),
-        })
+        let model = request.model.clone()
+            .filter(|model| self.supports_model(model))
+            .unwrap_or_else(|| ANTHROPIC_DEFAULT_MODEL.to_string());
+        let mut body = serde_json::json!({
+            "model": model,
+            "max_tokens": request.max_tokens.unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS),
+            "messages": [{ "role": "user", "content": request.prompt }],
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = serde_json::json!(temperature);
+        }
+        
+        let started = Instant::now();
+        let response = post_json(
+            self.name(),
+            self.client.post("https://api.anthropic.com/v1/messages")
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", ANTHROPIC_API_VERSION),
+            &body,
+        ).await?;
+        
+        let content = response.pointer("/content/0/text")
+            .and_then(|content| content.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Message has no text content"))?;
+        let usage = |field: &str| response.pointer(&format!("/usage/{}", field)).and_then(|tokens| tokens.as_u64());
+        let tokens_used = match (usage("input_tokens"), usage("output_tokens")) {
+            (None, None) => None,
+            (input, output) => Some((input.unwrap_or(0) + output.unwrap_or(0)) as u32),
+        };
+        Ok(llm_result(content, tokens_used, started, self.capabilities().cost_per_1k_tokens))
     }
     
     fn supports_model(&self, model: &str) -> bool {
-        matches!(model, "claude-3-opus" | "claude-3-sonnet" | "claude-3-haiku")
+        model.starts_with("claude-")
     }
 }
 
+/// A self-hosted model behind an OpenAI-compatible API; `endpoint` is the
+/// API root, e.g. `http://localhost:11434/v1`
 pub struct LocalLLMProvider {
+    client: reqwest::Client,
     endpoint: String,
     model_name: String,
 }
 
 impl LocalLLMProvider {
     pub fn new(endpoint: String, model_name: String) -> Self {
-        Self { endpoint, model_name }
+        Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            model_name,
+        }
     }
 }
 
@@ -173,16 +486,13 @@ impl LLMProvider for LocalLLMProvider {
     }
     
     async fn execute(&self, request: LLMRequest) -> Result<LLMResult> {
-        // TODO: Implement local LLM API call
-        Ok(LLMResult {
-            content: format!("Local LLM response to: {}", request.prompt.chars().take(50).collect::<String>()),
-            confidence_score: Some(0.75),
-            tokens_used: Some(200),
-            latency_ms: Some(4500),
-            cost_cents: Some(0.0),
-            reasoning: Some("Generated using local model with privacy preservation".to_string()),
-            metadata: HashMap::new(),
-        })
+        let started = Instant::now();
+        let response = post_json(
+            self.name(),
+            self.client.post(format!("{}/chat/completions", self.endpoint.trim_end_matches('/'))),
+            &chat_completion_body(&self.model_name, &request),
+        ).await?;
+        chat_completion_result(&response, started, self.capabilities().cost_per_1k_tokens)
     }
     
     fn supports_model(&self, model: &str) -> bool {
@@ -194,13 +504,14 @@ pub struct LLMProviderManager {
     providers: HashMap<String, Box<dyn LLMProvider>>,
     db: Database,
     default_provider: String,
+    fallback: FallbackConfig,
 }
 
 impl Clone for LLMProviderManager {
     fn clone(&self) -> Self {
         // For now, create a new instance with the same database
         // In a real implementation, you'd need to clone the providers properly
-        Self::new(self.db.clone())
+        Self::new(self.db.clone()).with_fallback_config(self.fallback.clone())
     }
 }
 
@@ -222,13 +533,33 @@ impl LLMProviderManager {
             providers.insert("local".to_string(), Box::new(LocalLLMProvider::new(endpoint, model)));
         }
         
+        // Fallback chains and retry policies, as JSON
+        let fallback = std::env::var("LLM_FALLBACK_CONFIG").ok()
+            .and_then(|path| {
+                let loaded = std::fs::read_to_string(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| Ok(serde_json::from_str::<FallbackConfig>(&json)?));
+                loaded.map_err(|e| tracing::warn!("Ignoring fallback config {}: {}", path, e)).ok()
+            })
+            .unwrap_or_default();
+        
         Self {
             providers,
             db,
             default_provider: "openai".to_string(), // Default fallback
+            fallback,
         }
     }
     
+    pub fn with_fallback_config(mut self, fallback: FallbackConfig) -> Self {
+        self.fallback = fallback;
+        self
+    }
+    
+    pub fn fallback_config(&self) -> &FallbackConfig {
+        &self.fallback
+    }
+    
     pub fn add_provider(&mut self, name: String, provider: Box<dyn LLMProvider>) {
         self.providers.insert(name, provider);
     }
@@ -248,40 +579,58 @@ impl LLMProviderManager {
         // Load prompt template
         let template = self.db.get_prompt_template(prompt_template_id).await?;
         
-        // Select best provider based on task
-        let selected_provider = if let Some(p) = provider {
-            p
-        } else {
-            self.select_best_provider(&template, block)?
+        // An explicit provider is used alone; otherwise walk the operation's chain
+        let chain = match provider {
+            Some(p) => vec![p],
+            None => {
+                let category = template.category.as_deref().unwrap_or("general");
+                self.resolve_chain(category, Some(self.select_best_provider(&template, block)?))
+            }
         };
         
-        let provider = self.providers.get(&selected_provider)
-            .ok_or_else(|| anyhow::anyhow!("Provider {} not available", selected_provider))?;
+        // Each provider gets its own variant of the prompt
+        let served = execute_chain(&self.providers, &self.fallback, &chain, |provider_name| {
+            Ok(LLMRequest {
+                prompt: self.fill_template(&template, provider_name, block, &context)?,
+                model: None, // Use provider default
+                temperature: Some(0.7),
+                max_tokens: None,
+                context: context.clone(),
+            })
+        }).await?;
         
-        // Get provider-specific prompt
-        let prompt = self.fill_template(&template, &selected_provider, block, &context)?;
+        self.track_interaction(Some(prompt_template_id), Some(block), &served).await?;
         
-        // Execute with tracking
-        let start_time = std::time::Instant::now();
-        let result = provider.execute(LLMRequest {
-            prompt,
-            model: None, // Use provider default
-            temperature: Some(0.7),
-            max_tokens: None,
-            context: context.clone(),
-        }).await?;
+        Ok(served.result)
+    }
+    
+    /// Execute a ready-made request through the fallback chain for `operation`
+    pub async fn execute_request(&self, operation: &str, request: LLMRequest) -> Result<ServedResult> {
+        let chain = self.resolve_chain(operation, None);
+        let served = execute_chain(&self.providers, &self.fallback, &chain, |_| Ok(request.clone())).await?;
         
-        let latency_ms = start_time.elapsed().as_millis() as u32;
+        self.track_interaction(None, None, &served).await?;
         
-        // Track interaction
-        self.track_interaction(
-            prompt_template_id,
-            &selected_provider,
-            &result,
-            latency_ms,
-        ).await?;
+        Ok(served)
+    }
+    
+    /// The configured chain for an operation, or the preferred provider
+    /// followed by the remaining ones from most to least reliable
+    fn resolve_chain(&self, operation: &str, preferred: Option<String>) -> Vec<String> {
+        let configured = self.fallback.chain_for(operation);
+        if !configured.is_empty() {
+            return configured.to_vec();
+        }
+        
+        let mut remaining: Vec<(&String, f32)> = self.providers.iter()
+            .filter(|(name, _)| preferred.as_ref() != Some(*name))
+            .map(|(name, provider)| (name, provider.capabilities().reliability_score))
+            .collect();
+        remaining.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
         
-        Ok(result)
+        preferred.into_iter()
+            .chain(remaining.into_iter().map(|(name, _)| name.clone()))
+            .collect()
     }
     
     fn select_best_provider(&self, template: &PromptTemplate, block: &Block) -> Result<String> {
@@ -350,21 +699,25 @@ impl LLMProviderManager {
         Ok(prompt)
     }
     
+    /// Log a served request. Prompts run against a block record its id in
+    /// the request payload, which cost reports attribute the spend by.
     async fn track_interaction(
         &self,
-        prompt_template_id: Uuid,
-        provider: &str,
-        result: &LLMResult,
-        latency_ms: u32,
+        prompt_template_id: Option<Uuid>,
+        block: Option<&Block>,
+        served: &ServedResult,
     ) -> Result<()> {
+        let result = &served.result;
         let interaction = LLMInteraction {
             id: Uuid::new_v4(),
             block_version_id: None, // Would be set if this is for versioning
-            prompt_template_id: Some(prompt_template_id),
-            provider: provider.to_string(),
+            prompt_template_id,
+            provider: served.provider.clone(),
             model: "default".to_string(), // Would be actual model used
             request_payload: serde_json::json!({
-                "provider": provider,
+                "provider": served.provider,
+                "block_id": block.map(|block| block.id),
+                "failed_attempts": served.failed_attempts,
                 "timestamp": Utc::now()
             }),
             response_payload: serde_json::json!({
@@ -372,7 +725,7 @@ impl LLMProviderManager {
                 "confidence": result.confidence_score
             }),
             tokens_used: result.tokens_used.map(|t| t as i32),
-            latency_ms: Some(latency_ms as i32),
+            latency_ms: Some(served.latency_ms as i32),
             cost_cents: result.cost_cents,
             confidence_score: result.confidence_score,
             human_rating: None,
@@ -413,3 +766,111 @@ pub struct ProviderStats {
     pub success_rate: f64,
     pub avg_confidence: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails with the scripted errors in order, then succeeds
+    struct ScriptedProvider {
+        name: String,
+        failures: std::sync::Mutex<Vec<ProviderError>>,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl ScriptedProvider {
+        fn new(name: &str, failures: Vec<ProviderError>) -> Box<Self> {
+            Box::new(Self {
+                name: name.to_string(),
+                failures: std::sync::Mutex::new(failures),
+                calls: std::sync::atomic::AtomicU32::new(0),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                supports_code_generation: true,
+                supports_analysis: true,
+                supports_refactoring: true,
+                max_context_length: 1000,
+                cost_per_1k_tokens: 0.0,
+                avg_latency_ms: 0,
+                reliability_score: 1.0,
+            }
+        }
+
+        async fn execute(&self, request: LLMRequest) -> Result<LLMResult> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let mut failures = self.failures.lock().unwrap();
+            if !failures.is_empty() {
+                return Err(failures.remove(0).into());
+            }
+            Ok(LLMResult {
+                content: format!("{}: {}", self.name, request.prompt),
+                confidence_score: None,
+                tokens_used: None,
+                latency_ms: None,
+                cost_cents: None,
+                reasoning: None,
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn supports_model(&self, _model: &str) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_fallback_chain() -> Result<()> {
+        let rate_limited = |provider: &str| ProviderError::from_status(provider, 429, "slow down").unwrap();
+        let no_wait = RetryPolicy { max_retries: 1, initial_backoff_ms: 0, timeout_ms: None, ..RetryPolicy::default() };
+        let config = FallbackConfig::default()
+            .with_chain("code_generation", vec!["primary".to_string(), "missing".to_string(), "secondary".to_string()])
+            .with_retry_policy("primary", no_wait.clone())
+            .with_retry_policy("secondary", no_wait);
+        let chain = config.chain_for("code_generation").to_vec();
+        let request = |provider: &str| Ok(LLMRequest {
+            prompt: format!("prompt for {}", provider),
+            model: None,
+            temperature: None,
+            max_tokens: None,
+            context: HashMap::new(),
+        });
+
+        // Primary stays rate limited through its retry, so secondary serves
+        let mut providers: HashMap<String, Box<dyn LLMProvider>> = HashMap::new();
+        providers.insert("primary".to_string(), ScriptedProvider::new("primary", vec![rate_limited("primary"), rate_limited("primary")]));
+        providers.insert("secondary".to_string(), ScriptedProvider::new("secondary", vec![]));
+        let served = execute_chain(&providers, &config, &chain, request).await?;
+        assert_eq!(served.provider, "secondary");
+        assert_eq!(served.result.content, "secondary: prompt for secondary");
+        assert_eq!(served.failed_attempts.len(), 2);
+        assert!(served.failed_attempts.iter().all(|attempt| attempt.provider == "primary" && attempt.transient));
+
+        // A server error followed by success is absorbed by the retry
+        providers.insert("primary".to_string(), ScriptedProvider::new("primary", vec![ProviderError::from_status("primary", 503, "").unwrap()]));
+        let served = execute_chain(&providers, &config, &chain, request).await?;
+        assert_eq!(served.provider, "primary");
+        assert_eq!(served.failed_attempts.len(), 1);
+
+        // A bad request is not retried and does not fall back
+        providers.insert("primary".to_string(), ScriptedProvider::new("primary", vec![ProviderError::from_status("primary", 400, "bad prompt").unwrap()]));
+        let error = execute_chain(&providers, &config, &chain, request).await.unwrap_err();
+        assert!(error.to_string().contains("non-retryable"));
+
+        // Operations without a chain of their own use the default
+        assert!(config.chain_for("documentation").is_empty());
+        let config = FallbackConfig { default_chain: vec!["secondary".to_string()], ..FallbackConfig::default() };
+        assert_eq!(config.chain_for("documentation"), ["secondary".to_string()]);
+
+        Ok(())
+    }
+}
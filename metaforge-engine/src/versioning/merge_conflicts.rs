@@ -0,0 +1,324 @@
+//! Statement-level view of semantic merge conflicts
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::semantic_vcs::{BlockState, SemanticConflict};
+
+/// `BlockState::properties` key holding the block's rendered code
+pub const RENDERED_CODE_PROPERTY: &str = "rendered_code";
+
+/// Statements both sides changed differently
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictRegion {
+    /// 1-based line of the base version where the region starts
+    pub base_line: usize,
+    pub base: Vec<String>,
+    pub ours: Vec<String>,
+    pub theirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeChunk {
+    /// Statements that merged without conflict
+    Resolved(Vec<String>),
+    Conflict(ConflictRegion),
+}
+
+/// A three-way merge of one block's statements
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct StatementMerge {
+    pub chunks: Vec<MergeChunk>,
+}
+
+impl StatementMerge {
+    pub fn merge(base: &str, ours: &str, theirs: &str) -> Self {
+        let base: Vec<&str> = base.lines().collect();
+        let ours: Vec<&str> = ours.lines().collect();
+        let theirs: Vec<&str> = theirs.lines().collect();
+        let to_ours = matching_lines(&base, &ours);
+        let to_theirs = matching_lines(&base, &theirs);
+
+        let mut merge = Self::default();
+        let (mut i, mut j, mut k) = (0, 0, 0);
+        for b in 0..=base.len() {
+            // Base statements both sides kept anchor the regions in between
+            let anchor = if b == base.len() {
+                (ours.len(), theirs.len())
+            } else {
+                match (to_ours[b], to_theirs[b]) {
+                    (Some(o), Some(t)) => (o, t),
+                    _ => continue,
+                }
+            };
+            let (o, t) = anchor;
+            let (base_region, ours_region, theirs_region) = (&base[i..b], &ours[j..o], &theirs[k..t]);
+            if ours_region == base_region || ours_region == theirs_region {
+                merge.push_resolved(theirs_region);
+            } else if theirs_region == base_region {
+                merge.push_resolved(ours_region);
+            } else {
+                merge.chunks.push(MergeChunk::Conflict(ConflictRegion {
+                    base_line: i + 1,
+                    base: owned(base_region),
+                    ours: owned(ours_region),
+                    theirs: owned(theirs_region),
+                }));
+            }
+            if b < base.len() {
+                merge.push_resolved(&base[b..=b]);
+            }
+            (i, j, k) = (b + 1, o + 1, t + 1);
+        }
+        merge
+    }
+
+    fn push_resolved(&mut self, lines: &[&str]) {
+        if lines.is_empty() {
+            return;
+        }
+        match self.chunks.last_mut() {
+            Some(MergeChunk::Resolved(resolved)) => resolved.extend(owned(lines)),
+            _ => self.chunks.push(MergeChunk::Resolved(owned(lines))),
+        }
+    }
+
+    pub fn conflicts(&self) -> impl Iterator<Item = &ConflictRegion> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            MergeChunk::Conflict(region) => Some(region),
+            MergeChunk::Resolved(_) => None,
+        })
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.conflicts().next().is_none()
+    }
+
+    /// The merged code, when no region conflicts
+    pub fn merged(&self) -> Option<String> {
+        self.is_clean().then(|| self.with_markers("ours", "theirs"))
+    }
+
+    /// The merged code with git's diff3-style markers around each conflict
+    pub fn with_markers(&self, ours_label: &str, theirs_label: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Resolved(resolved) => lines.extend(resolved.iter().cloned()),
+                MergeChunk::Conflict(region) => {
+                    lines.push(format!("<<<<<<< {}", ours_label));
+                    lines.extend(region.ours.iter().cloned());
+                    lines.push("||||||| base".to_string());
+                    lines.extend(region.base.iter().cloned());
+                    lines.push("=======".to_string());
+                    lines.extend(region.theirs.iter().cloned());
+                    lines.push(format!(">>>>>>> {}", theirs_label));
+                }
+            }
+        }
+        join_lines(&lines)
+    }
+}
+
+/// The rendered code of a conflicting block on each side of the merge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictCode {
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+    /// The statements that clash
+    pub regions: Vec<ConflictRegion>,
+    /// The merged code, when the sides changed different statements
+    pub suggested_resolution: Option<String>,
+}
+
+impl ConflictCode {
+    pub fn new(base: &str, ours: &str, theirs: &str) -> Self {
+        let merge = StatementMerge::merge(base, ours, theirs);
+        Self {
+            base: base.to_string(),
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+            regions: merge.conflicts().cloned().collect(),
+            suggested_resolution: merge.merged(),
+        }
+    }
+
+    /// From the states' rendered code; `None` unless both sides carry it.
+    /// A block without a base version was added on both sides.
+    pub fn from_states(base: &BlockState, ours: &BlockState, theirs: &BlockState) -> Option<Self> {
+        let ours = rendered_code(ours)?;
+        let theirs = rendered_code(theirs)?;
+        Some(Self::new(rendered_code(base).unwrap_or(""), ours, theirs))
+    }
+
+    /// The merged code with conflict markers
+    pub fn with_markers(&self) -> String {
+        StatementMerge::merge(&self.base, &self.ours, &self.theirs).with_markers("ours", "theirs")
+    }
+}
+
+fn rendered_code(state: &BlockState) -> Option<&str> {
+    state.properties.get(RENDERED_CODE_PROPERTY)?.as_str()
+}
+
+/// Write `<block id>.base`, `.ours`, `.theirs` and `.merged` (with conflict
+/// markers) into `dir` for each conflict with rendered code, returning the
+/// paths written
+pub fn write_conflict_files(dir: &Path, conflicts: &[SemanticConflict]) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut written = Vec::new();
+    for conflict in conflicts {
+        let Some(code) = &conflict.code else {
+            continue;
+        };
+        let merged = code.with_markers();
+        for (side, content) in [("base", &code.base), ("ours", &code.ours), ("theirs", &code.theirs), ("merged", &merged)] {
+            let path = dir.join(format!("{}.{}", conflict.block_id, side));
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// For each line of `base`, the line of `other` it is matched with in a
+/// longest common subsequence
+fn matching_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let (n, m) = (base.len(), other.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if base[i] == other[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+fn owned(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+fn join_lines(lines: &[String]) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("{}\n", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+    use crate::versioning::semantic_vcs::{ComplexitySnapshot, ConflictType};
+
+    fn block_state(block_id: Uuid, rendered_code: &str) -> BlockState {
+        BlockState {
+            block_id,
+            semantic_signature: String::new(),
+            behavior_hash: String::new(),
+            interface_hash: String::new(),
+            implementation_hash: String::new(),
+            dependencies: Default::default(),
+            properties: HashMap::from([(RENDERED_CODE_PROPERTY.to_string(), serde_json::json!(rendered_code))]),
+            complexity_metrics: ComplexitySnapshot {
+                cyclomatic_complexity: 1,
+                cognitive_complexity: 0,
+                lines_of_code: rendered_code.lines().count() as u32,
+                maintainability_index: 100.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_semantic_merge_conflicts_show_clashing_statements() -> Result<()> {
+        let base = "def total(items):\n    result = 0\n    for item in items:\n        result += item\n    return result\n";
+        let ours = "def total(items):\n    result = 0\n    for item in items:\n        result += item.price\n    return result\n";
+        let theirs = "def total(items, start=0):\n    result = start\n    for item in items:\n        result += item\n    return result\n";
+    
+        // Different statements changed: merges cleanly and is offered as the resolution
+        let code = ConflictCode::new(base, ours, theirs);
+        assert!(code.regions.is_empty());
+        assert_eq!(
+            code.suggested_resolution.as_deref(),
+            Some("def total(items, start=0):\n    result = start\n    for item in items:\n        result += item.price\n    return result\n")
+        );
+    
+        // The same statement changed differently: only that statement conflicts
+        let theirs = "def total(items):\n    result = 0\n    for item in items:\n        result += item.cost\n    return result\n";
+        let merge = StatementMerge::merge(base, ours, theirs);
+        assert_eq!(merge.conflicts().cloned().collect::<Vec<_>>(), vec![ConflictRegion {
+            base_line: 4,
+            base: vec!["        result += item".to_string()],
+            ours: vec!["        result += item.price".to_string()],
+            theirs: vec!["        result += item.cost".to_string()],
+        }]);
+        assert_eq!(merge.merged(), None);
+        assert_eq!(merge.with_markers("ours", "theirs"), [
+            "def total(items):",
+            "    result = 0",
+            "    for item in items:",
+            "<<<<<<< ours",
+            "        result += item.price",
+            "||||||| base",
+            "        result += item",
+            "=======",
+            "        result += item.cost",
+            ">>>>>>> theirs",
+            "    return result",
+            "",
+        ].join("\n"));
+    
+        // Both sides making the same change, or adding the same block, is no conflict
+        assert!(StatementMerge::merge(base, ours, ours).is_clean());
+        assert_eq!(ConflictCode::new("", "x = 1\n", "x = 1\n").suggested_resolution.as_deref(), Some("x = 1\n"));
+    
+        // Conflict files, like git's base/ours/theirs/merged
+        let block_id = Uuid::new_v4();
+        let conflict = SemanticConflict {
+            conflict_id: Uuid::new_v4(),
+            conflict_type: ConflictType::ConcurrentModification,
+            block_id,
+            base_state: block_state(block_id, base),
+            our_state: block_state(block_id, ours),
+            their_state: block_state(block_id, theirs),
+            resolution_options: vec![],
+            auto_resolvable: false,
+            code: None,
+        };
+        let code = ConflictCode::from_states(&conflict.base_state, &conflict.our_state, &conflict.their_state).expect("rendered code");
+        assert_eq!(code.regions.len(), 1);
+        let conflict = SemanticConflict { code: Some(code), ..conflict };
+    
+        let dir = std::env::temp_dir().join(format!("metaforge-conflicts-{}", Uuid::new_v4()));
+        let written = write_conflict_files(&dir, &[conflict])?;
+        assert_eq!(written.len(), 4);
+        assert_eq!(std::fs::read_to_string(dir.join(format!("{}.theirs", block_id)))?, theirs);
+        assert!(std::fs::read_to_string(dir.join(format!("{}.merged", block_id)))?.contains("<<<<<<< ours\n        result += item.price\n"));
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
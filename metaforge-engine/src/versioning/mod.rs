@@ -5,6 +5,8 @@ pub mod semantic_version_control;
 pub mod llm_provider_manager;
 pub mod semantic_merge_handler;
 pub mod llm_context_manager;
+pub mod expression_diff;
+pub mod merge_conflicts;
 
 // Explicit re-exports to avoid ambiguity
 pub use semantic_version_control::SemanticVersionControl; 
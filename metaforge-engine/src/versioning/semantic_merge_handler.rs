@@ -240,27 +240,22 @@ impl SemanticMergeHandler {
             ]),
         };
         
-        // Use the first available provider for now
-        let providers = self.llm_manager.list_providers();
-        if let Some(provider_name) = providers.first() {
-            if let Some(provider) = self.llm_manager.get_provider(provider_name) {
-                let result = provider.execute(llm_request).await?;
-                
-                // Parse LLM response to determine resolution strategy
-                let resolution = self.parse_llm_resolution(&result.content)?;
-                
-                conflict.resolution = Some(resolution);
-                conflict.reasoning = Some(result.content);
-                conflict.confidence = result.confidence_score;
-                
-                // Consider it resolved if confidence is high enough
-                Ok(result.confidence_score.unwrap_or(0.0) > 0.8)
-            } else {
-                Ok(false)
-            }
-        } else {
-            Ok(false)
+        // Walk the fallback chain so a rate-limited provider doesn't fail the merge
+        if self.llm_manager.list_providers().is_empty() {
+            return Ok(false);
         }
+        let result = self.llm_manager.execute_request("conflict_resolution", llm_request).await?.result;
+        
+        // Parse LLM response to determine resolution strategy
+        let resolution = self.parse_llm_resolution(&result.content)?;
+        // Consider it resolved if the confidence the model stated is high enough
+        let resolved = !resolution.requires_human_review;
+        
+        conflict.resolution = Some(resolution);
+        conflict.reasoning = Some(result.content);
+        conflict.confidence = result.confidence_score;
+        
+        Ok(resolved)
     }
     
     fn create_conflict_resolution_prompt(
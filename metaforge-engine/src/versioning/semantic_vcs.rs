@@ -4,6 +4,7 @@ use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use crate::database::Database;
+use super::merge_conflicts::ConflictCode;
 
 /// Semantic Version Control System - tracks changes at semantic level
 pub struct SemanticVCS {
@@ -309,6 +310,10 @@ pub struct SemanticConflict {
     pub their_state: BlockState,
     pub resolution_options: Vec<ResolutionOption>,
     pub auto_resolvable: bool,
+    /// Each side's rendered code and the statements that clash, when the
+    /// states carry rendered code
+    #[serde(default)]
+    pub code: Option<ConflictCode>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -418,8 +423,11 @@ impl SemanticVCS {
         let our_state = self.get_commit_state(our_commit).await?;
         let their_state = self.get_commit_state(their_commit).await?;
 
-        // Identify conflicts
-        let conflicts = self.identify_conflicts(&base_state, &our_state, &their_state).await?;
+        // Identify conflicts, down to the statements that clash
+        let mut conflicts = self.identify_conflicts(&base_state, &our_state, &their_state).await?;
+        for conflict in &mut conflicts {
+            conflict.code = ConflictCode::from_states(&conflict.base_state, &conflict.our_state, &conflict.their_state);
+        }
         
         // Auto-resolve compatible changes
         let auto_resolved = self.auto_resolve_changes(&base_state, &our_state, &their_state).await?;
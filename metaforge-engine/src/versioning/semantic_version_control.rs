@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use blake3::Hasher;
 use chrono::Utc;
 
+use crate::core::normalize_block;
 use crate::database::{Database, schema::{Block, BlockVersion, LLMInteraction}};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,39 +155,10 @@ impl SemanticVersionControl {
         Ok(diff)
     }
     
-    /// Calculate semantic hash based on structure and behavior
+    /// Calculate semantic hash based on structure and behavior; formatting
+    /// never changes it
     fn calculate_semantic_hash(&self, block: &Block) -> Result<String> {
-        let mut hasher = Hasher::new();
-        
-        // Hash semantic components
-        hasher.update(block.block_type.as_bytes());
-        
-        if let Some(name) = &block.semantic_name {
-            hasher.update(name.as_bytes());
-        }
-        
-        // Hash abstract syntax (semantic structure)
-        let ast_str = serde_json::to_string(&block.abstract_syntax)?;
-        hasher.update(ast_str.as_bytes());
-        
-        // Hash parameters and return type (interface)
-        if let Some(params) = &block.parameters {
-            let params_str = serde_json::to_string(params)?;
-            hasher.update(params_str.as_bytes());
-        }
-        
-        if let Some(return_type) = &block.return_type {
-            hasher.update(return_type.as_bytes());
-        }
-        
-        // Hash modifiers (affects behavior)
-        if let Some(modifiers) = &block.modifiers {
-            for modifier in modifiers {
-                hasher.update(modifier.as_bytes());
-            }
-        }
-        
-        Ok(hasher.finalize().to_hex().to_string())
+        Ok(normalize_block(block).semantic_hash())
     }
     
     /// Calculate syntax hash based on actual code
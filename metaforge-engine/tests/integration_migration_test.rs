@@ -1,4 +1,5 @@
-use metaforge_engine::database::Database;
+use metaforge_engine::database::{Database, Container, schema::{BlockRelationship, is_transient_error}};
+use metaforge_engine::parser::universal::UniversalParser;
 use anyhow::Result;
 use uuid::Uuid;
 
@@ -198,3 +199,117 @@ async fn test_migration_constraint_validation() -> Result<()> {
     
     Ok(())
 }
+
+#[tokio::test]
+async fn test_retry_fails_fast_on_constraint_violation() -> Result<()> {
+    let db = Database::setup(&test_database_url()).await?;
+    
+    let migration_id = Uuid::new_v4();
+    let db_ref = &db;
+    let insert = move || async move {
+        sqlx::query(
+            "INSERT INTO migrations (id, repository_name, repo_name, repo_url, commit_hash, status) 
+             VALUES ($1, 'retry-test', 'retry-test', 'https://github.com/test/retry.git', 'abc123', 'pending')"
+        )
+        .bind(migration_id)
+        .execute(db_ref.pool())
+        .await?;
+        Ok::<(), anyhow::Error>(())
+    };
+    
+    db.with_retry(insert).await?;
+    
+    // A duplicate primary key is permanent: it must surface on the first attempt
+    let mut attempts = 0;
+    let result = db.with_retry(|| {
+        attempts += 1;
+        insert()
+    }).await;
+    
+    assert!(result.is_err(), "Duplicate insert should fail");
+    assert!(!is_transient_error(result.as_ref().unwrap_err()));
+    assert_eq!(attempts, 1, "Permanent errors must not be retried");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_insert_file_rolls_back_on_mid_file_failure() -> Result<()> {
+    let db = Database::setup(&test_database_url()).await?;
+    let migration_id = db.create_migration("https://github.com/test/atomic.git", "atomic", "def456").await?;
+    
+    let source = "def first():\n    return 1\n\ndef second():\n    return 2\n";
+    let parse_result = UniversalParser::new()?.parse_file(source, "python", "atomic.py")?;
+    assert!(!parse_result.blocks.is_empty());
+    
+    let container = Container {
+        id: Uuid::new_v4(),
+        name: "atomic".to_string(),
+        container_type: "code".to_string(),
+        language: Some("python".to_string()),
+        original_path: Some("atomic.py".to_string()),
+        original_hash: Some("hash".to_string()),
+        source_code: Some(source.to_string()),
+        version: 1,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        semantic_summary: None,
+        parsing_metadata: None,
+        formatting_preferences: None,
+        reconstruction_hints: None,
+    };
+    
+    // Blocks insert fine, then the relationship violates its foreign key
+    let dangling = BlockRelationship {
+        source_block_id: parse_result.blocks[0].id,
+        target_block_id: Uuid::new_v4(),
+        relationship_type: "calls".to_string(),
+        metadata: None,
+    };
+    
+    let result = db.insert_file(&container, migration_id, &parse_result.blocks, &[dangling]).await;
+    assert!(result.is_err(), "Dangling relationship should fail the file");
+    
+    let containers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM containers WHERE id = $1")
+        .bind(container.id)
+        .fetch_one(db.pool())
+        .await?;
+    let blocks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blocks WHERE container_id = $1")
+        .bind(container.id)
+        .fetch_one(db.pool())
+        .await?;
+    
+    assert_eq!(containers, 0, "Container must be rolled back");
+    assert_eq!(blocks, 0, "No partial blocks may remain");
+    
+    // The same file commits cleanly once the bad relationship is gone
+    db.insert_file(&container, migration_id, &parse_result.blocks, &[]).await?;
+    let blocks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blocks WHERE container_id = $1")
+        .bind(container.id)
+        .fetch_one(db.pool())
+        .await?;
+    assert_eq!(blocks as usize, parse_result.blocks.len());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resume_skips_completed_and_other_commit_migrations() -> Result<()> {
+    let db = Database::setup(&test_database_url()).await?;
+    
+    let repo_url = format!("https://github.com/test/resume-{}.git", Uuid::new_v4());
+    let unfinished = db.create_migration(&repo_url, "resume", "abc123").await?;
+    assert_eq!(db.find_resumable_migration(&repo_url, "abc123").await?, Some(unfinished));
+    
+    // A migration of another commit never resumes
+    assert_eq!(db.find_resumable_migration(&repo_url, "def456").await?, None);
+    
+    // Nor does one that already completed
+    sqlx::query("UPDATE migrations SET status = 'completed' WHERE id = $1")
+        .bind(unfinished)
+        .execute(db.pool())
+        .await?;
+    assert_eq!(db.find_resumable_migration(&repo_url, "abc123").await?, None);
+    
+    Ok(())
+}
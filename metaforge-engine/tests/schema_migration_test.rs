@@ -205,3 +205,85 @@ def calculate_fibonacci(n):
     
     Ok(())
 }
+
+#[tokio::test]
+async fn test_prune_detaches_branches_and_interactions() -> Result<()> {
+    let db = Database::setup(&test_database_url()).await?;
+    db.create_versioning_schema().await?;
+    // Databases whose versioning tables came from `create_versioning_schema`
+    // have a branch's base migration
+    sqlx::query("ALTER TABLE semantic_branches ADD COLUMN IF NOT EXISTS base_migration_id UUID REFERENCES migrations(id)")
+        .execute(db.pool())
+        .await?;
+    
+    let migration_id = db.create_migration("https://github.com/test/prune.git", "prune", "abc123def").await?;
+    let container_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO containers (id, name, container_type, migration_id) VALUES ($1, 'lib.py', 'file', $2)")
+        .bind(container_id)
+        .bind(migration_id)
+        .execute(db.pool())
+        .await?;
+    let block_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO blocks (id, container_id, block_type, position) VALUES ($1, $2, 'Function', 0)")
+        .bind(block_id)
+        .bind(container_id)
+        .execute(db.pool())
+        .await?;
+    let version_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO block_versions (id, block_id, version_number, semantic_hash, syntax_hash)
+         VALUES ($1, $2, 1, 'semantic', 'syntax')"
+    )
+    .bind(version_id)
+    .bind(block_id)
+    .execute(db.pool())
+    .await?;
+    let interaction_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO llm_interactions (id, block_version_id, provider, model, request_payload, response_payload)
+         VALUES ($1, $2, 'openai', 'gpt-4', '{}', '{}')"
+    )
+    .bind(interaction_id)
+    .bind(version_id)
+    .execute(db.pool())
+    .await?;
+    let branch_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO semantic_branches (id, name, repository_id, base_migration_id) VALUES ($1, 'feature', $2, $3)")
+        .bind(branch_id)
+        .bind(Uuid::new_v4())
+        .bind(migration_id)
+        .execute(db.pool())
+        .await?;
+    
+    // A dry run reports the rows it would detach and leaves them pointing at the migration
+    let report = db.prune_migrations(&[migration_id], true).await?;
+    assert_eq!((report.migrations, report.blocks), (1, 1));
+    assert_eq!((report.detached_interactions, report.detached_branches), (1, 1));
+    let base: Option<Uuid> = sqlx::query_scalar("SELECT base_migration_id FROM semantic_branches WHERE id = $1")
+        .bind(branch_id)
+        .fetch_one(db.pool())
+        .await?;
+    assert_eq!(base, Some(migration_id));
+    
+    let report = db.prune_migrations(&[migration_id], false).await?;
+    assert_eq!((report.detached_interactions, report.detached_branches), (1, 1));
+    
+    // The interaction and the branch are kept, detached from what was deleted
+    let version: Option<Uuid> = sqlx::query_scalar("SELECT block_version_id FROM llm_interactions WHERE id = $1")
+        .bind(interaction_id)
+        .fetch_one(db.pool())
+        .await?;
+    assert_eq!(version, None);
+    let base: Option<Uuid> = sqlx::query_scalar("SELECT base_migration_id FROM semantic_branches WHERE id = $1")
+        .bind(branch_id)
+        .fetch_one(db.pool())
+        .await?;
+    assert_eq!(base, None);
+    let blocks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blocks WHERE id = $1")
+        .bind(block_id)
+        .fetch_one(db.pool())
+        .await?;
+    assert_eq!(blocks, 0);
+    
+    Ok(())
+}
@@ -189,6 +189,7 @@ mod intent_processor_tests {
                 },
                 behaviors: vec![],
                 invariants: vec![],
+                generation_hints: std::collections::HashMap::new(),
             }
         );
 